@@ -0,0 +1,109 @@
+//! Transfer of bytes between the host and the memory of a guest.
+//!
+//! The guest memory is made of words, so a slice starts at a word-aligned address and its last
+//! word is completed with the bytes already in memory. The whole slice must lie in memory that
+//! the guest may access in the same way: written by the host for the guest to read, or written by
+//! the guest for the host to read.
+
+use anyhow::{Result, anyhow};
+
+use crate::{ByteAddr, EmuContext, Platform, VMState, WORD_SIZE, Word, WordAddr};
+
+/// The word addresses of `len` bytes from `addr`, checked against the platform with `allowed`.
+fn guest_words(
+    platform: &Platform,
+    addr: ByteAddr,
+    len: usize,
+    allowed: impl Fn(&Platform, u32) -> bool,
+) -> Result<impl Iterator<Item = WordAddr>> {
+    if !addr.is_aligned() {
+        return Err(anyhow!("guest slice at {addr:?} is not word-aligned"));
+    }
+    let num_words = len.div_ceil(WORD_SIZE);
+    let end = u32::try_from(num_words * WORD_SIZE)
+        .ok()
+        .and_then(|size| addr.0.checked_add(size))
+        .ok_or_else(|| anyhow!("guest slice of {len} bytes at {addr:?} overflows"))?;
+    let start = addr.waddr();
+    let words = (0..num_words).map(move |i| start + i);
+    if let Some(word) = words
+        .clone()
+        .find(|word| !allowed(platform, ByteAddr::from(*word).0))
+    {
+        return Err(anyhow!(
+            "guest slice {addr:?}..{:?} is not accessible at {word:?}",
+            ByteAddr(end)
+        ));
+    }
+    Ok(words)
+}
+
+/// Write `bytes` to the memory of the guest from `addr`, which must be word-aligned, without side
+/// effects. The range must be writable by the guest.
+pub fn write_slice_to_guest(vm: &mut VMState, addr: ByteAddr, bytes: &[u8]) -> Result<()> {
+    let words = guest_words(vm.platform(), addr, bytes.len(), Platform::can_write)?;
+    for (word, chunk) in words.zip(bytes.chunks(WORD_SIZE)) {
+        let mut value = vm.peek_memory(word).to_le_bytes();
+        value[..chunk.len()].copy_from_slice(chunk);
+        vm.init_memory(word, Word::from_le_bytes(value));
+    }
+    Ok(())
+}
+
+/// Read `len` bytes from the memory of the guest from `addr`, which must be word-aligned. The
+/// range must be readable by the guest.
+pub fn read_slice_from_guest(vm: &VMState, addr: ByteAddr, len: usize) -> Result<Vec<u8>> {
+    let words = guest_words(vm.platform(), addr, len, Platform::can_read)?;
+    let mut bytes = words
+        .flat_map(|word| vm.peek_memory(word).to_le_bytes())
+        .collect::<Vec<_>>();
+    bytes.truncate(len);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CENO_PLATFORM, Program};
+
+    fn vm() -> VMState {
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            vec![],
+            Default::default(),
+        );
+        VMState::new(CENO_PLATFORM, program)
+    }
+
+    #[test]
+    fn test_slice_round_trip() -> Result<()> {
+        let mut vm = vm();
+        let addr = ByteAddr(CENO_PLATFORM.ram.start);
+        vm.init_memory(addr.waddr() + 1_usize, 0xaabb_ccdd);
+
+        write_slice_to_guest(&mut vm, addr, b"hello")?;
+        assert_eq!(read_slice_from_guest(&vm, addr, 5)?, b"hello");
+        // The bytes past the slice in its last word are kept.
+        assert_eq!(vm.peek_memory(addr.waddr() + 1_usize), 0xaabb_cc6f);
+        assert_eq!(read_slice_from_guest(&vm, addr, 0)?, b"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_slice_checks() {
+        let mut vm = vm();
+        let ram_end = ByteAddr(CENO_PLATFORM.ram.end);
+        // Not aligned.
+        assert!(
+            write_slice_to_guest(&mut vm, ByteAddr(CENO_PLATFORM.ram.start + 1), b"x").is_err()
+        );
+        // Past the end of RAM, or of the address space.
+        assert!(write_slice_to_guest(&mut vm, ByteAddr(ram_end.0 - 4), b"hello").is_err());
+        assert!(read_slice_from_guest(&vm, ByteAddr(u32::MAX - 3), 8).is_err());
+        // The ROM is readable but not writable.
+        let rom = ByteAddr(CENO_PLATFORM.rom.start);
+        assert!(write_slice_to_guest(&mut vm, rom, b"x").is_err());
+        assert!(read_slice_from_guest(&vm, rom, 4).is_ok());
+    }
+}
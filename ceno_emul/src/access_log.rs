@@ -0,0 +1,91 @@
+//! The register and memory accesses of a trace as columns, one row per access, in the order of
+//! their cycles, e.g. to feed the memory consistency circuits or a columnar file for analysis.
+
+use std::io;
+
+use crate::{
+    InsnKind::{self, *},
+    WORD_SIZE,
+    addr::{Addr, Cycle, Word},
+    rv32im::InsnCategory,
+    tracer::{MemOp, StepRecord},
+};
+
+/// One row per access. Accesses are to whole words: `value` is the word read, or the word
+/// after a write, and `width` is the number of its bytes that the instruction uses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryAccessColumns {
+    /// The byte address of the word, a register as in `Platform::register_vma`.
+    pub addr: Vec<Addr>,
+    pub cycle: Vec<Cycle>,
+    /// The cycle of the previous access to the word, or 0 for the first one.
+    pub prev_cycle: Vec<Cycle>,
+    pub value: Vec<Word>,
+    pub is_write: Vec<bool>,
+    pub width: Vec<u8>,
+}
+
+impl MemoryAccessColumns {
+    /// The accesses of `steps`.
+    pub fn from_steps(steps: &[StepRecord]) -> Self {
+        let mut columns = Self::default();
+        for step in steps {
+            for read in [step.rs1(), step.rs2()].into_iter().flatten() {
+                columns.push(&read, read.value, false, WORD_SIZE as u8);
+            }
+            if let Some(write) = step.rd() {
+                columns.push(&write, write.value.after, true, WORD_SIZE as u8);
+            }
+            if let Some(op) = step.memory_op() {
+                let codes = step.insn().codes();
+                let is_store = matches!(codes.category, InsnCategory::Store);
+                columns.push(&op, op.value.after, is_store, access_width(codes.kind));
+            }
+        }
+        columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.addr.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addr.is_empty()
+    }
+
+    /// Write the columns as CSV with a header row.
+    pub fn write_csv(&self, out: &mut impl io::Write) -> io::Result<()> {
+        writeln!(out, "addr,cycle,prev_cycle,value,is_write,width")?;
+        for i in 0..self.len() {
+            writeln!(
+                out,
+                "{:#010x},{},{},{:#010x},{},{}",
+                self.addr[i],
+                self.cycle[i],
+                self.prev_cycle[i],
+                self.value[i],
+                self.is_write[i] as u8,
+                self.width[i]
+            )?;
+        }
+        Ok(())
+    }
+
+    fn push<T>(&mut self, op: &MemOp<T>, value: Word, is_write: bool, width: u8) {
+        self.addr.push(op.addr.into());
+        self.cycle.push(op.cycle);
+        self.prev_cycle.push(op.previous_cycle);
+        self.value.push(value);
+        self.is_write.push(is_write);
+        self.width.push(width);
+    }
+}
+
+/// The bytes of the word that a load or a store uses.
+fn access_width(kind: InsnKind) -> u8 {
+    match kind {
+        LB | LBU | SB => 1,
+        LH | LHU | SH => 2,
+        _ => WORD_SIZE as u8,
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::rv32im::{DecodedInstruction, InsnCategory, InsnFormat, InsnKind};
+
+/// Decode an instruction word and format it in assembly syntax, e.g. `bltu x2, x3, -8`.
+pub fn fmt_insn(word: u32) -> String {
+    if word & 0x03 != 0x03 {
+        // Not a 32-bit instruction.
+        return format!("invalid 0x{word:08x}");
+    }
+    DecodedInstruction::new(word).to_string()
+}
+
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use InsnKind::*;
+
+        let codes = self.codes();
+        let name = format!("{:?}", codes.kind).to_lowercase();
+        let (rd, rs1, rs2) = (self.rd(), self.rs1(), self.rs2());
+        let imm = self.immediate() as i32;
+
+        match (codes.category, codes.format, codes.kind) {
+            (InsnCategory::Invalid, ..) => write!(f, "invalid 0x{:08x}", self.encoded()),
+            (.., EANY) => match imm {
+                0 => write!(f, "ecall"),
+                1 => write!(f, "ebreak"),
+                _ => write!(f, "{name} {imm}"),
+            },
+            (.., CLZ | CPOP) => write!(f, "{name} x{rd}, x{rs1}"),
+            (.., FENCE) => match (imm as u32 >> 8) & 0xf {
+                0x8 => write!(f, "fence.tso"),
+                _ if imm & 0xff == 0xff => write!(f, "fence"),
+                _ => write!(
+                    f,
+                    "fence {}, {}",
+                    fmt_fence_set(imm as u32 >> 4),
+                    fmt_fence_set(imm as u32)
+                ),
+            },
+            (.., FENCE_I) => write!(f, "fence.i"),
+            (.., SLLI | SRLI | SRAI) => write!(f, "{name} x{rd}, x{rs1}, {}", imm & 0x1f),
+            (InsnCategory::Load, ..) | (.., JALR) => write!(f, "{name} x{rd}, {imm}(x{rs1})"),
+            (_, InsnFormat::R, _) => write!(f, "{name} x{rd}, x{rs1}, x{rs2}"),
+            (_, InsnFormat::I, _) => write!(f, "{name} x{rd}, x{rs1}, {imm}"),
+            (_, InsnFormat::S, _) => write!(f, "{name} x{rs2}, {imm}(x{rs1})"),
+            (_, InsnFormat::B, _) => write!(f, "{name} x{rs1}, x{rs2}, {imm}"),
+            (_, InsnFormat::U, _) => write!(f, "{name} x{rd}, 0x{:x}", (imm as u32) >> 12),
+            (_, InsnFormat::J, _) => write!(f, "{name} x{rd}, {imm}"),
+        }
+    }
+}
+
+/// The set of accesses ordered by a fence, e.g. `rw`, from the lowest 4 bits of `bits`.
+fn fmt_fence_set(bits: u32) -> String {
+    "iorw"
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| bits & (8 >> i) != 0)
+        .map(|(_, c)| c)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fmt_insn;
+    use crate::{InsnKind::*, encode_rv32};
+
+    #[test]
+    fn test_fmt_insn() {
+        let cases = [
+            (encode_rv32(ADD, 2, 3, 1, 0), "add x1, x2, x3"),
+            (encode_rv32(ADDI, 1, 0, 1, -1_i32 as u32), "addi x1, x1, -1"),
+            (encode_rv32(SRAI, 5, 0, 4, 3), "srai x4, x5, 3"),
+            (encode_rv32(LW, 2, 0, 1, 8), "lw x1, 8(x2)"),
+            (encode_rv32(SB, 1, 2, 0, -4_i32 as u32), "sb x2, -4(x1)"),
+            (encode_rv32(BLTU, 2, 3, 0, -8_i32 as u32), "bltu x2, x3, -8"),
+            (encode_rv32(JAL, 0, 0, 1, 16), "jal x1, 16"),
+            (encode_rv32(JALR, 1, 0, 0, 0), "jalr x0, 0(x1)"),
+            (encode_rv32(LUI, 0, 0, 7, 0x12345 << 12), "lui x7, 0x12345"),
+            (encode_rv32(EANY, 0, 0, 0, 0), "ecall"),
+            (encode_rv32(ANDN, 2, 3, 1, 0), "andn x1, x2, x3"),
+            (encode_rv32(CLZ, 2, 0, 1, 0), "clz x1, x2"),
+            (encode_rv32(CPOP, 2, 0, 1, 0), "cpop x1, x2"),
+            (0x6071_1093, "invalid 0x60711093"),
+            (0x0ff0_000f, "fence"),
+            (0x0210_000f, "fence r, w"),
+            (0x8330_000f, "fence.tso"),
+            (0x0000_100f, "fence.i"),
+            (0, "invalid 0x00000000"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(fmt_insn(word), expected);
+        }
+    }
+}
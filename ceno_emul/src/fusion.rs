@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::{
+    addr::{Cycle, WordAddr},
+    rv32im::{DecodedInstruction, InsnKind},
+    tracer::StepRecord,
+};
+
+/// Pairs of consecutive instructions that compilers emit together for one operation, and that
+/// a circuit can prove as a single step. Every kind has a fused circuit in the zkVM.
+///
+/// A comparison followed by a branch on its result is not fused: when the branch is not
+/// taken, the fused step does not tell the branch offset, which its circuit must fetch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FusedKind {
+    /// `lui rd, hi; addi rd, rd, lo`, as in `li rd, imm`.
+    LuiAddi,
+    /// `auipc rd, hi; jalr rd, lo(rd)` with an even `lo`, as in `call offset`. The offset is
+    /// then the target minus the auipc result, since the jalr does not round the target.
+    AuipcJalr,
+}
+
+impl FusedKind {
+    pub const ALL: [FusedKind; 2] = [FusedKind::LuiAddi, FusedKind::AuipcJalr];
+
+    /// The pattern formed by `first` followed by `second`, if any. The second instruction
+    /// must consume the rd of the first one, which must not be x0.
+    pub fn detect(first: &DecodedInstruction, second: &DecodedInstruction) -> Option<Self> {
+        use InsnKind::*;
+
+        let rd = first.rd_internal();
+        if rd == DecodedInstruction::RD_NULL || second.rs1() != rd {
+            return None;
+        }
+        match (first.codes().kind, second.codes().kind) {
+            (LUI, ADDI) if second.rd() == rd => Some(FusedKind::LuiAddi),
+            (AUIPC, JALR) if second.rd() == rd && second.immediate() & 1 == 0 => {
+                Some(FusedKind::AuipcJalr)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A trace where the pairs of steps that form a [`FusedKind`] are merged into one step.
+#[derive(Clone, Debug, Default)]
+pub struct FusedTrace {
+    /// The steps, with the pattern of the pair that a step stands for if it was fused.
+    pub steps: Vec<(Option<FusedKind>, StepRecord)>,
+    /// All the addresses that were accessed and the cycle when they were last accessed.
+    pub final_accesses: HashMap<WordAddr, Cycle>,
+}
+
+impl FusedTrace {
+    /// The number of steps that stand for a pair of instructions.
+    pub fn num_fused(&self) -> usize {
+        self.steps.iter().filter(|(kind, _)| kind.is_some()).count()
+    }
+}
+
+/// Fuse the pairs of consecutive steps that form one of the `kinds`.
+///
+/// A fused step keeps the cycle of its first instruction and spans the cycles of both, so the
+/// other steps keep their cycles. It keeps the operands of the first instruction, and its rd
+/// write takes the value left by the second one. The other accesses of the second instruction
+/// are dropped, so the accesses that followed them now follow the latest access kept.
+pub fn fuse_steps(steps: Vec<StepRecord>, kinds: &[FusedKind]) -> FusedTrace {
+    let mut latest = HashMap::new();
+    let mut fused = Vec::with_capacity(steps.len());
+    let mut steps = steps.into_iter().peekable();
+
+    while let Some(step) = steps.next() {
+        let kind = steps
+            .peek()
+            .filter(|next| next.pc().before == step.pc().after)
+            .and_then(|next| FusedKind::detect(&step.insn(), &next.insn()))
+            .filter(|kind| kinds.contains(kind));
+        let mut step = match kind {
+            Some(_) => {
+                let next = steps.next().unwrap();
+                step.fuse(&next)
+            }
+            None => step,
+        };
+        step.relink(&mut latest);
+        fused.push((kind, step));
+    }
+
+    FusedTrace {
+        steps: fused,
+        final_accesses: latest,
+    }
+}
@@ -7,14 +7,44 @@ pub use platform::{CENO_PLATFORM, Platform};
 mod tracer;
 pub use tracer::{Change, MemOp, ReadOp, StepRecord, Tracer, WriteOp};
 
+mod random;
+pub use random::random_word;
+
 mod vm_state;
-pub use vm_state::VMState;
+pub use vm_state::{StopReason, VMState, Watchpoint};
+
+mod abi;
+pub use abi::{read_slice_from_guest, write_slice_to_guest};
+
+mod shard;
+pub use shard::{MergedTrace, ShardTrace, merge_shards, replay_shards, run_shard, shard_snapshots};
+
+mod access_log;
+pub use access_log::MemoryAccessColumns;
+
+mod fusion;
+pub use fusion::{FusedKind, FusedTrace, fuse_steps};
 
 mod rv32im;
-pub use rv32im::{DecodedInstruction, EmuContext, InsnCategory, InsnCodes, InsnFormat, InsnKind};
+pub use rv32im::{
+    DecodeCache, DecodedInstruction, EmuContext, InsnCategory, InsnCodes, InsnFormat, InsnKind,
+    decode,
+};
+
+mod rv32c;
+pub use rv32c::{expand_compressed, insn_size};
+
+mod disassemble;
+pub use disassemble::fmt_insn;
 
 mod elf;
 pub use elf::Program;
 
 mod rv32im_encode;
-pub use rv32im_encode::encode_rv32;
+pub use rv32im_encode::{encode_rv32, encode_rvc_program};
+
+mod profile;
+pub use profile::{
+    Histogram, SoftFloatReport, Symbols, UNKNOWN_REGION, is_soft_float_routine, profile,
+    profile_with_symbols, soft_float_report,
+};
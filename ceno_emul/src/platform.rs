@@ -15,6 +15,10 @@ pub struct Platform {
     pub stack_top: Addr,
     /// If true, ecall instructions are no-op instead of trap. Testing only.
     pub unsafe_ecall_nop: bool,
+    /// If true, the compressed instructions of the C extension are executed as their 32-bit
+    /// equivalents, and instructions are 2-byte aligned. Emulation only: the circuits expect
+    /// 4-byte instructions.
+    pub rvc: bool,
 }
 
 pub const CENO_PLATFORM: Platform = Platform {
@@ -23,6 +27,7 @@ pub const CENO_PLATFORM: Platform = Platform {
     public_io: 0x3000_1000..0x3000_2000,
     stack_top: 0xC0000000,
     unsafe_ecall_nop: false,
+    rvc: false,
 };
 
 impl Platform {
@@ -93,6 +98,12 @@ impl Platform {
         0
     }
 
+    /// The code of ecall RANDOM, which replaces arg0 with the word at index arg0 of the random
+    /// stream of the seed of the VM. See [`crate::random_word`].
+    pub const fn ecall_random() -> u32 {
+        1
+    }
+
     /// The code of success.
     pub const fn code_success() -> u32 {
         0
@@ -0,0 +1,221 @@
+//! Where the cycles of an execution go, so that guest authors can see which instructions and
+//! functions dominate the proving cost before proving.
+
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::{Result, anyhow};
+use elf::{ElfBytes, abi::STT_FUNC, endian::LittleEndian};
+
+use crate::{InsnKind, StepRecord};
+
+/// The region of the steps outside of any known function.
+pub const UNKNOWN_REGION: &str = "<unknown>";
+
+/// The function symbols of a program, to name the code regions of a profile.
+#[derive(Clone, Debug, Default)]
+pub struct Symbols {
+    /// start address -> (end address, name)
+    functions: BTreeMap<u32, (u32, String)>,
+}
+
+impl Symbols {
+    /// Read the function symbols of an ELF file. A stripped file has none.
+    pub fn from_elf(input: &[u8]) -> Result<Symbols> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+        let mut functions = BTreeMap::new();
+        if let Some((symbols, names)) = elf
+            .symbol_table()
+            .map_err(|err| anyhow!("Elf symbol table error: {err}"))?
+        {
+            for symbol in symbols
+                .iter()
+                .filter(|s| s.st_symtype() == STT_FUNC && s.st_size > 0)
+            {
+                let name = names
+                    .get(symbol.st_name as usize)
+                    .map_err(|err| anyhow!("Elf symbol name error: {err}"))?;
+                let start = symbol.st_value as u32;
+                functions.insert(start, (start + symbol.st_size as u32, name.to_string()));
+            }
+        }
+        Ok(Symbols { functions })
+    }
+
+    /// Add the function `name` at the addresses `start..end`, e.g. from a symbol map other than
+    /// the ELF file.
+    pub fn insert(&mut self, start: u32, end: u32, name: impl Into<String>) {
+        self.functions.insert(start, (end, name.into()));
+    }
+
+    /// The function containing `pc`, if any.
+    pub fn lookup(&self, pc: u32) -> Option<&str> {
+        self.function(pc).map(|(_, name)| name)
+    }
+
+    /// The start address and name of the function containing `pc`, if any.
+    fn function(&self, pc: u32) -> Option<(u32, &str)> {
+        self.functions
+            .range(..=pc)
+            .next_back()
+            .filter(|(_, (end, _))| pc < *end)
+            .map(|(start, (_, name))| (*start, name.as_str()))
+    }
+}
+
+/// The number of steps of a trace, by instruction kind and by code region. Every step costs
+/// [`crate::Tracer::SUBCYCLES_PER_INSN`] cycles.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    pub total: u64,
+    pub by_kind: BTreeMap<InsnKind, u64>,
+    /// By function name, or [`UNKNOWN_REGION`].
+    pub by_region: BTreeMap<String, u64>,
+}
+
+/// Profile a trace without symbols: all the steps are in [`UNKNOWN_REGION`].
+pub fn profile(trace: &[StepRecord]) -> Histogram {
+    profile_with_symbols(trace, &Symbols::default())
+}
+
+pub fn profile_with_symbols(trace: &[StepRecord], symbols: &Symbols) -> Histogram {
+    let mut histogram = Histogram::default();
+    for step in trace {
+        histogram.total += 1;
+        *histogram
+            .by_kind
+            .entry(step.insn().codes().kind)
+            .or_default() += 1;
+        let region = symbols.lookup(step.pc().before.0).unwrap_or(UNKNOWN_REGION);
+        // Avoid allocating the name again for every step of a known region.
+        match histogram.by_region.get_mut(region) {
+            Some(count) => *count += 1,
+            None => {
+                histogram.by_region.insert(region.to_string(), 1);
+            }
+        }
+    }
+    histogram
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |count: u64| 100.0 * count as f64 / self.total.max(1) as f64;
+        writeln!(f, "{} steps", self.total)?;
+
+        writeln!(f, "by instruction:")?;
+        let mut by_kind = self.by_kind.iter().collect::<Vec<_>>();
+        by_kind.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (kind, count) in by_kind {
+            writeln!(
+                f,
+                "  {:<8} {count:>12} {:>6.2}%",
+                format!("{kind:?}"),
+                percent(*count)
+            )?;
+        }
+
+        writeln!(f, "by region:")?;
+        let mut by_region = self.by_region.iter().collect::<Vec<_>>();
+        by_region.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (region, count) in by_region {
+            writeln!(f, "  {count:>12} {:>6.2}%  {region}", percent(*count))?;
+        }
+        Ok(())
+    }
+}
+
+/// The operations of the soft-float routines of compiler-rt and libgcc, which the compiler calls
+/// for the floating-point arithmetic of a target without the F and D extensions. They are named
+/// `__<op><modes>[<arity>]`, e.g. `__mulsf3`, `__fixdfsi` or `__extendsfdf2`.
+const SOFT_FLOAT_OPS: &[&str] = &[
+    "add", "sub", "mul", "div", "neg", "powi", "cmp", "eq", "ne", "lt", "le", "gt", "ge", "unord",
+    "fix", "fixuns", "float", "floatun", "extend", "trunc",
+];
+
+/// Whether `name` is a soft-float routine: an operation on single, double or quad precision
+/// floats (`sf`, `df`, `tf`), possibly from or to integers (`si`, `di`, `ti`).
+pub fn is_soft_float_routine(name: &str) -> bool {
+    let Some(name) = name.strip_prefix("__") else {
+        return false;
+    };
+    SOFT_FLOAT_OPS.iter().any(|op| {
+        let Some(modes) = name.strip_prefix(op) else {
+            return false;
+        };
+        let modes = modes
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .as_bytes();
+        !modes.is_empty()
+            && modes.len() % 2 == 0
+            && modes
+                .chunks(2)
+                .all(|mode| matches!(mode, b"sf" | b"df" | b"tf" | b"si" | b"di" | b"ti"))
+            && modes.chunks(2).any(|mode| mode[1] == b'f')
+    })
+}
+
+/// The steps of a trace spent in soft-float routines, so that guest authors can see whether
+/// rewriting their floating-point arithmetic in fixed point would pay off.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SoftFloatReport {
+    pub total: u64,
+    /// The steps in each soft-float routine.
+    pub steps_by_routine: BTreeMap<String, u64>,
+    /// The calls of each soft-float routine, counted at its first instruction.
+    pub calls_by_routine: BTreeMap<String, u64>,
+}
+
+impl SoftFloatReport {
+    /// The steps in all soft-float routines.
+    pub fn soft_float_steps(&self) -> u64 {
+        self.steps_by_routine.values().sum()
+    }
+
+    /// The fraction of the steps in soft-float routines, from 0 to 1.
+    pub fn share(&self) -> f64 {
+        self.soft_float_steps() as f64 / self.total.max(1) as f64
+    }
+}
+
+/// Find the steps in soft-float routines. This needs the symbols of the program: the routines of
+/// a stripped program are not detected.
+pub fn soft_float_report(trace: &[StepRecord], symbols: &Symbols) -> SoftFloatReport {
+    let mut report = SoftFloatReport {
+        total: trace.len() as u64,
+        ..Default::default()
+    };
+    for step in trace {
+        let pc = step.pc().before.0;
+        let Some((start, name)) = symbols.function(pc) else {
+            continue;
+        };
+        if !is_soft_float_routine(name) {
+            continue;
+        }
+        *report.steps_by_routine.entry(name.to_string()).or_default() += 1;
+        if pc == start {
+            *report.calls_by_routine.entry(name.to_string()).or_default() += 1;
+        }
+    }
+    report
+}
+
+impl fmt::Display for SoftFloatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} steps in soft-float routines ({:.2}%)",
+            self.soft_float_steps(),
+            self.total,
+            100.0 * self.share()
+        )?;
+        let mut by_routine = self.steps_by_routine.iter().collect::<Vec<_>>();
+        by_routine.sort_by_key(|(_, steps)| std::cmp::Reverse(**steps));
+        for (routine, steps) in by_routine {
+            let calls = self.calls_by_routine.get(routine).copied().unwrap_or(0);
+            writeln!(f, "  {steps:>12} steps {calls:>10} calls  {routine}")?;
+        }
+        Ok(())
+    }
+}
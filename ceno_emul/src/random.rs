@@ -0,0 +1,42 @@
+//! The pseudo-random words of the RANDOM ecall.
+//!
+//! Word `index` of the stream of `seed` is the low 32 bits of `(seed_hi + 1) * (index + seed_lo)^7`
+//! in the Goldilocks field, where `seed_lo` and `seed_hi` are the low and high halves of the
+//! seed. This takes a few multiplications to check in a circuit over the same field. `x^7` is a
+//! permutation of the field, so distinct indices give distinct field elements, but the stream is
+//! not a cryptographic generator: it only makes the words of a guest a function of the seed.
+
+use crate::addr::Word;
+
+/// The Goldilocks modulus, `2^64 - 2^32 + 1`.
+const MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+fn mul_mod(a: u64, b: u64) -> u64 {
+    (a as u128 * b as u128 % MODULUS as u128) as u64
+}
+
+/// The word at `index` in the stream of `seed`.
+pub fn random_word(seed: u64, index: Word) -> Word {
+    let t = index as u64 + (seed & 0xffff_ffff);
+    let t2 = mul_mod(t, t);
+    let t3 = mul_mod(t2, t);
+    let t4 = mul_mod(t2, t2);
+    mul_mod((seed >> 32) + 1, mul_mod(t4, t3)) as Word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::random_word;
+
+    #[test]
+    fn test_random_word() {
+        // (0 + 1) * (2 + 3)^7
+        assert_eq!(random_word(3, 2), 78125);
+        // (1 + 1) * 1^7
+        assert_eq!(random_word(1 << 32, 1), 2);
+        // (2^32 - 1 + 1)^7 = 2^32 modulo p, as 2^96 = -1
+        assert_eq!(random_word(u32::MAX as u64, 1), 0);
+        assert_ne!(random_word(7, 0), random_word(7, 1));
+        assert_ne!(random_word(7, 0), random_word(8 << 32 | 7, 0));
+    }
+}
@@ -0,0 +1,259 @@
+//! The compressed instructions of the C extension, executed as their 32-bit equivalents.
+//!
+//! See the chapter "C" Standard Extension for Compressed Instructions of the RISC-V unprivileged
+//! specification for the encodings.
+
+/// The size in bytes of the instruction whose lowest 16 bits are `low`.
+pub const fn insn_size(low: u16) -> usize {
+    if low & 0x3 == 0x3 { 4 } else { 2 }
+}
+
+/// The 32-bit instruction equivalent to the compressed instruction `insn`, or `None` if `insn`
+/// is not a valid RV32C instruction.
+pub fn expand_compressed(insn: u16) -> Option<u32> {
+    let insn = insn as u32;
+    let bits = |hi: u32, lo: u32| (insn >> lo) & ((1 << (hi - lo + 1)) - 1);
+    let bit = |i: u32| (insn >> i) & 1;
+    // The registers x8 to x15 of the 3-bit register fields.
+    let reg_c = |lo: u32| bits(lo + 2, lo) + 8;
+    let rd = bits(11, 7);
+    let rs2 = bits(6, 2);
+    // The 6-bit signed immediate of C.ADDI, C.LI, C.ANDI.
+    let imm6 = sign_extend(bit(12) << 5 | bits(6, 2), 6);
+    // The offset of C.J and C.JAL.
+    let imm_j = sign_extend(
+        bit(12) << 11
+            | bit(11) << 4
+            | bits(10, 9) << 8
+            | bit(8) << 10
+            | bit(7) << 6
+            | bit(6) << 7
+            | bits(5, 3) << 1
+            | bit(2) << 5,
+        12,
+    );
+    // The offset of C.BEQZ and C.BNEZ.
+    let imm_b = sign_extend(
+        bit(12) << 8 | bits(11, 10) << 3 | bits(6, 5) << 6 | bits(4, 3) << 1 | bit(2) << 5,
+        9,
+    );
+    // The offset of C.LW and C.SW.
+    let uimm_w = bits(12, 10) << 3 | bit(6) << 2 | bit(5) << 6;
+
+    let expanded = match (insn & 0x3, bits(15, 13)) {
+        // C.ADDI4SPN
+        (0b00, 0b000) => {
+            let nzuimm = bits(12, 11) << 4 | bits(10, 7) << 6 | bit(6) << 2 | bit(5) << 3;
+            if nzuimm == 0 {
+                return None;
+            }
+            i_type(OP_IMM, 0b000, reg_c(2), 2, nzuimm)
+        }
+        // C.LW
+        (0b00, 0b010) => i_type(LOAD, 0b010, reg_c(2), reg_c(7), uimm_w),
+        // C.SW
+        (0b00, 0b110) => s_type(0b010, reg_c(7), reg_c(2), uimm_w),
+        // C.ADDI and C.NOP
+        (0b01, 0b000) => i_type(OP_IMM, 0b000, rd, rd, imm6),
+        // C.JAL
+        (0b01, 0b001) => j_type(1, imm_j),
+        // C.LI
+        (0b01, 0b010) => i_type(OP_IMM, 0b000, rd, 0, imm6),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == 2 => {
+            let nzimm = sign_extend(
+                bit(12) << 9 | bit(6) << 4 | bit(5) << 6 | bits(4, 3) << 7 | bit(2) << 5,
+                10,
+            );
+            if nzimm == 0 {
+                return None;
+            }
+            i_type(OP_IMM, 0b000, 2, 2, nzimm)
+        }
+        // C.LUI
+        (0b01, 0b011) => {
+            if imm6 == 0 {
+                return None;
+            }
+            imm6 << 12 | rd << 7 | LUI
+        }
+        (0b01, 0b100) => {
+            let rd = reg_c(7);
+            match bits(11, 10) {
+                // C.SRLI and C.SRAI, whose shift amount is at most 31 in RV32.
+                0b00 | 0b01 if bit(12) == 0 => {
+                    let func7 = if bits(11, 10) == 0b01 { 0x20 } else { 0 };
+                    i_type(OP_IMM, 0b101, rd, rd, func7 << 5 | rs2)
+                }
+                // C.ANDI
+                0b10 => i_type(OP_IMM, 0b111, rd, rd, imm6),
+                // C.SUB, C.XOR, C.OR, C.AND
+                0b11 if bit(12) == 0 => {
+                    let (func7, func3) = match bits(6, 5) {
+                        0b00 => (0x20, 0b000),
+                        0b01 => (0, 0b100),
+                        0b10 => (0, 0b110),
+                        _ => (0, 0b111),
+                    };
+                    r_type(func7, func3, rd, rd, reg_c(2))
+                }
+                _ => return None,
+            }
+        }
+        // C.J
+        (0b01, 0b101) => j_type(0, imm_j),
+        // C.BEQZ
+        (0b01, 0b110) => b_type(0b000, reg_c(7), 0, imm_b),
+        // C.BNEZ
+        (0b01, 0b111) => b_type(0b001, reg_c(7), 0, imm_b),
+        // C.SLLI
+        (0b10, 0b000) if bit(12) == 0 => i_type(OP_IMM, 0b001, rd, rd, rs2),
+        // C.LWSP
+        (0b10, 0b010) if rd != 0 => {
+            let uimm = bit(12) << 5 | bits(6, 4) << 2 | bits(3, 2) << 6;
+            i_type(LOAD, 0b010, rd, 2, uimm)
+        }
+        (0b10, 0b100) => match (bit(12), rd, rs2) {
+            (0, 0, 0) => return None,
+            // C.JR
+            (0, 1.., 0) => i_type(JALR, 0b000, 0, rd, 0),
+            // C.MV
+            (0, _, 1..) => r_type(0, 0b000, rd, 0, rs2),
+            // C.EBREAK
+            (1, 0, 0) => EBREAK,
+            // C.JALR
+            (1, _, 0) => i_type(JALR, 0b000, 1, rd, 0),
+            // C.ADD
+            _ => r_type(0, 0b000, rd, rd, rs2),
+        },
+        // C.SWSP
+        (0b10, 0b110) => {
+            let uimm = bits(12, 9) << 2 | bits(8, 7) << 6;
+            s_type(0b010, 2, rs2, uimm)
+        }
+        _ => return None,
+    };
+    Some(expanded)
+}
+
+const LOAD: u32 = 0x03;
+const OP_IMM: u32 = 0x13;
+const STORE: u32 = 0x23;
+const OP: u32 = 0x33;
+const LUI: u32 = 0x37;
+const BRANCH: u32 = 0x63;
+const JALR: u32 = 0x67;
+const JAL: u32 = 0x6f;
+const EBREAK: u32 = 0x0010_0073;
+
+/// Sign-extend the lowest `width` bits of `value`.
+fn sign_extend(value: u32, width: u32) -> u32 {
+    let shift = 32 - width;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+fn r_type(func7: u32, func3: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    func7 << 25 | rs2 << 20 | rs1 << 15 | func3 << 12 | rd << 7 | OP
+}
+
+fn i_type(opcode: u32, func3: u32, rd: u32, rs1: u32, imm: u32) -> u32 {
+    (imm & 0xfff) << 20 | rs1 << 15 | func3 << 12 | rd << 7 | opcode
+}
+
+fn s_type(func3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (imm >> 5 & 0x7f) << 25 | rs2 << 20 | rs1 << 15 | func3 << 12 | (imm & 0x1f) << 7 | STORE
+}
+
+fn b_type(func3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (imm >> 12 & 1) << 31
+        | (imm >> 5 & 0x3f) << 25
+        | rs2 << 20
+        | rs1 << 15
+        | func3 << 12
+        | (imm >> 1 & 0xf) << 8
+        | (imm >> 11 & 1) << 7
+        | BRANCH
+}
+
+fn j_type(rd: u32, imm: u32) -> u32 {
+    (imm >> 20 & 1) << 31
+        | (imm >> 1 & 0x3ff) << 21
+        | (imm >> 11 & 1) << 20
+        | (imm >> 12 & 0xff) << 12
+        | rd << 7
+        | JAL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_compressed, insn_size};
+
+    #[test]
+    fn test_expand_compressed() {
+        // The 32-bit instructions are the ones of an assembler, except that C.MV is an ADD.
+        for (compressed, expected) in [
+            (0x0048, 0x00410513), // c.addi4spn a0, sp, 4
+            (0x1fe4, 0x3fc10493), // c.addi4spn s1, sp, 1020
+            (0x41c8, 0x0045a503), // c.lw a0, 4(a1)
+            (0x5fe0, 0x07c7a403), // c.lw s0, 124(a5)
+            (0xc1c8, 0x00a5a223), // c.sw a0, 4(a1)
+            (0xdc7c, 0x06f42e23), // c.sw a5, 124(s0)
+            (0x0001, 0x00000013), // c.nop
+            (0x0505, 0x00150513), // c.addi a0, 1
+            (0x1281, 0xfe028293), // c.addi t0, -32
+            (0x2021, 0x008000ef), // c.jal 8
+            (0x3001, 0x801ff0ef), // c.jal -2048
+            (0x2ffd, 0x7fe000ef), // c.jal 2046
+            (0x4505, 0x00100513), // c.li a0, 1
+            (0x50fd, 0xfff00093), // c.li ra, -1
+            (0x6141, 0x01010113), // c.addi16sp sp, 16
+            (0x7101, 0xe0010113), // c.addi16sp sp, -512
+            (0x617d, 0x1f010113), // c.addi16sp sp, 496
+            (0x6505, 0x00001537), // c.lui a0, 1
+            (0x7301, 0xfffe0337), // c.lui t1, 0xfffe0
+            (0x637d, 0x0001f337), // c.lui t1, 31
+            (0x8105, 0x00155513), // c.srli a0, 1
+            (0x80fd, 0x01f4d493), // c.srli s1, 31
+            (0x8505, 0x40155513), // c.srai a0, 1
+            (0x87c5, 0x4117d793), // c.srai a5, 17
+            (0x8905, 0x00157513), // c.andi a0, 1
+            (0x9801, 0xfe047413), // c.andi s0, -32
+            (0x8d0d, 0x40b50533), // c.sub a0, a1
+            (0x8d2d, 0x00b54533), // c.xor a0, a1
+            (0x8e55, 0x00d66633), // c.or a2, a3
+            (0x8c7d, 0x00f47433), // c.and s0, a5
+            (0xa021, 0x0080006f), // c.j 8
+            (0xb001, 0x801ff06f), // c.j -2048
+            (0xbffd, 0xfffff06f), // c.j -2
+            (0xc501, 0x00050463), // c.beqz a0, 8
+            (0xd081, 0xf00480e3), // c.beqz s1, -256
+            (0xeffd, 0x0e079f63), // c.bnez a5, 254
+            (0xfd7d, 0xfe051fe3), // c.bnez a0, -2
+            (0x0506, 0x00151513), // c.slli a0, 1
+            (0x0ffe, 0x01ff9f93), // c.slli t6, 31
+            (0x4512, 0x00412503), // c.lwsp a0, 4(sp)
+            (0x50fe, 0x0fc12083), // c.lwsp ra, 252(sp)
+            (0x8082, 0x00008067), // c.jr ra
+            (0x8282, 0x00028067), // c.jr t0
+            (0x852e, 0x00b00533), // c.mv a0, a1
+            (0x8f86, 0x00100fb3), // c.mv t6, ra
+            (0x9002, 0x00100073), // c.ebreak
+            (0x9502, 0x000500e7), // c.jalr a0
+            (0x952e, 0x00b50533), // c.add a0, a1
+            (0x9fee, 0x01bf8fb3), // c.add t6, s11
+            (0xc22a, 0x00a12223), // c.swsp a0, 4(sp)
+            (0xdf86, 0x0e112e23), // c.swsp ra, 252(sp)
+        ] {
+            assert_eq!(insn_size(compressed), 2);
+            assert_eq!(
+                expand_compressed(compressed),
+                Some(expected),
+                "0x{compressed:04x}"
+            );
+        }
+        // Reserved encodings.
+        for reserved in [0x0000, 0x6101, 0x6001, 0x4002, 0x8002, 0x1006] {
+            assert_eq!(expand_compressed(reserved), None, "0x{reserved:04x}");
+        }
+    }
+}
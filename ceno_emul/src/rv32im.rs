@@ -20,7 +20,10 @@ use num_derive::ToPrimitive;
 use std::sync::OnceLock;
 use strum_macros::EnumIter;
 
-use super::addr::{ByteAddr, RegIdx, WORD_SIZE, Word, WordAddr};
+use super::{
+    addr::{ByteAddr, RegIdx, WORD_SIZE, Word, WordAddr},
+    rv32c::{expand_compressed, insn_size},
+};
 
 pub trait EmuContext {
     // Handle environment call
@@ -65,6 +68,14 @@ pub trait EmuContext {
         self.load_memory(pc)
     }
 
+    // Callback when the instruction at pc is fetched, as its 32-bit equivalent if compressed.
+    fn on_insn_fetched(&mut self, _pc: ByteAddr, _insn: Word) {}
+
+    // Whether the compressed instructions of the C extension are supported.
+    fn allows_compressed(&self) -> bool {
+        false
+    }
+
     // Check access for instruction load
     fn check_insn_load(&self, _addr: ByteAddr) -> bool {
         true
@@ -185,6 +196,16 @@ pub enum InsnKind {
     SW,
     /// ECALL and EBREAK etc.
     EANY,
+    // A subset of the bit-manipulation extension (Zbb).
+    ANDN,
+    ORN,
+    XNOR,
+    CLZ,
+    CPOP,
+    /// Memory ordering, a no-op with a single hart and no instruction cache.
+    FENCE,
+    #[allow(non_camel_case_types)]
+    FENCE_I,
 }
 use InsnKind::*;
 
@@ -229,6 +250,11 @@ impl DecodedInstruction {
         self.opcode
     }
 
+    /// Get the rd field, regardless of the instruction format.
+    pub fn rd(&self) -> u32 {
+        self.rd
+    }
+
     /// The internal register destination. It is either the regular rd, or an internal RD_NULL if
     /// the instruction does not write to a register or writes to x0.
     pub fn rd_internal(&self) -> u32 {
@@ -326,7 +352,7 @@ const fn insn(
     }
 }
 
-type InstructionTable = [InsnCodes; 47];
+type InstructionTable = [InsnCodes; 54];
 type FastInstructionTable = [u8; 1 << 10];
 
 const RV32IM_ISA: InstructionTable = [
@@ -377,6 +403,14 @@ const RV32IM_ISA: InstructionTable = [
     insn(S, SH, Store, 0x23, 0x1, -1),
     insn(S, SW, Store, 0x23, 0x2, -1),
     insn(I, EANY, System, 0x73, 0x0, 0x00),
+    insn(R, ANDN, Compute, 0x33, 0x7, 0x20),
+    insn(R, ORN, Compute, 0x33, 0x6, 0x20),
+    insn(R, XNOR, Compute, 0x33, 0x4, 0x20),
+    // CLZ and CPOP only differ by the rs2 field, see `FastDecodeTable::lookup`.
+    insn(I, CLZ, Compute, 0x13, 0x1, 0x30),
+    insn(I, CPOP, Compute, 0x13, 0x1, 0x30),
+    insn(I, FENCE, Compute, 0x0f, 0x0, -1),
+    insn(I, FENCE_I, Compute, 0x0f, 0x1, -1),
 ];
 
 #[cfg(test)]
@@ -449,12 +483,62 @@ impl FastDecodeTable {
 
     fn lookup(&self, decoded: &DecodedInstruction) -> InsnCodes {
         let isa_idx = self.table[Self::map10(decoded.opcode, decoded.func3, decoded.func7)];
-        RV32IM_ISA[isa_idx as usize]
+        match RV32IM_ISA[isa_idx as usize].kind {
+            // The unary bit-manipulation instructions are told apart by rs2, and their func7 is
+            // not one of the values of the table.
+            CLZ | CPOP => match (decoded.func7, decoded.rs2) {
+                (0x30, 0) => CLZ.codes(),
+                (0x30, 2) => CPOP.codes(),
+                _ => INVALID.codes(),
+            },
+            _ => RV32IM_ISA[isa_idx as usize],
+        }
     }
 }
 
+/// Split an instruction word into its fields. The instruction kind is then available from
+/// [`DecodedInstruction::codes`], and the assembly syntax from its `Display` implementation.
+pub fn decode(word: u32) -> DecodedInstruction {
+    DecodedInstruction::new(word)
+}
+
 static FAST_DECODE_TABLE: OnceLock<FastDecodeTable> = OnceLock::new();
 
+/// The instructions of a program, decoded once and indexed by pc, so that
+/// [`Emulator::step_cached`] skips decoding. An entry is only used while the
+/// word at its pc is the one it was decoded from.
+#[derive(Clone, Debug, Default)]
+pub struct DecodeCache {
+    base_address: u32,
+    entries: Vec<(Word, DecodedInstruction, InsnCodes)>,
+}
+
+impl DecodeCache {
+    pub fn new(base_address: u32, instructions: &[Word]) -> Self {
+        let table = FastDecodeTable::get();
+        let entries = instructions
+            .iter()
+            .map(|&word| {
+                let decoded = decode(word);
+                let insn = table.lookup(&decoded);
+                (word, decoded, insn)
+            })
+            .collect();
+        Self {
+            base_address,
+            entries,
+        }
+    }
+
+    fn get(&self, pc: ByteAddr, word: Word) -> Option<(&DecodedInstruction, InsnCodes)> {
+        let idx = pc.0.wrapping_sub(self.base_address) as usize / WORD_SIZE;
+        match self.entries.get(idx) {
+            Some((cached, decoded, insn)) if *cached == word => Some((decoded, *insn)),
+            _ => None,
+        }
+    }
+}
+
 impl Emulator {
     pub fn new() -> Self {
         Self {
@@ -462,7 +546,13 @@ impl Emulator {
         }
     }
 
-    pub fn step<C: EmuContext>(&self, ctx: &mut C) -> Result<()> {
+    /// Execute the instruction at pc. Take the decoded instruction from `cache` when it holds
+    /// the word fetched at pc.
+    pub fn step_cached<C: EmuContext>(
+        &self,
+        ctx: &mut C,
+        cache: Option<&DecodeCache>,
+    ) -> Result<()> {
         let pc = ctx.get_pc();
 
         if !ctx.check_insn_load(pc) {
@@ -470,52 +560,117 @@ impl Emulator {
             return Err(anyhow!("Fatal: could not fetch instruction at pc={:?}", pc));
         }
 
-        let word = ctx.fetch(pc.waddr())?;
-        if word & 0x03 != 0x03 {
-            // Opcode must end in 0b11 in RV32IM.
-            ctx.trap(TrapCause::IllegalInstruction(word))?;
-            return Err(anyhow!(
-                "Fatal: illegal instruction at pc={:?}: 0x{:08x}",
-                pc,
-                word
-            ));
-        }
-
-        let decoded = DecodedInstruction::new(word);
-        let insn = self.table.lookup(&decoded);
-        ctx.on_insn_decoded(&decoded);
-        tracing::trace!("pc: {:x}, kind: {:?}", pc.0, insn.kind);
+        let Some((word, size)) = self.fetch_insn(ctx, pc)? else {
+            return Err(anyhow!("Fatal: could not fetch instruction at pc={:?}", pc));
+        };
+        ctx.on_insn_fetched(pc, word);
+
+        let fresh;
+        let (decoded, insn) = match cache.and_then(|cache| cache.get(pc, word)) {
+            Some((decoded, insn)) => (decoded, insn),
+            None => {
+                fresh = decode(word);
+                let insn = self.table.lookup(&fresh);
+                (&fresh, insn)
+            }
+        };
+        ctx.on_insn_decoded(decoded);
+        tracing::trace!("pc: {:x}, insn: {}", pc.0, decoded);
 
         if match insn.category {
-            InsnCategory::Compute => self.step_compute(ctx, insn.kind, &decoded)?,
-            InsnCategory::Branch => self.step_branch(ctx, insn.kind, &decoded)?,
-            InsnCategory::Load => self.step_load(ctx, insn.kind, &decoded)?,
-            InsnCategory::Store => self.step_store(ctx, insn.kind, &decoded)?,
-            InsnCategory::System => self.step_system(ctx, insn.kind, &decoded)?,
+            InsnCategory::Compute => self.step_compute(ctx, insn.kind, decoded, size)?,
+            InsnCategory::Branch => self.step_branch(ctx, insn.kind, decoded, size)?,
+            InsnCategory::Load => self.step_load(ctx, insn.kind, decoded, size)?,
+            InsnCategory::Store => self.step_store(ctx, insn.kind, decoded, size)?,
+            InsnCategory::System => self.step_system(ctx, insn.kind, decoded)?,
             InsnCategory::Invalid => ctx.trap(TrapCause::IllegalInstruction(word))?,
         } {
-            ctx.on_normal_end(&decoded);
+            ctx.on_normal_end(decoded);
         };
 
         Ok(())
     }
 
+    /// Fetch the instruction at `pc` and its size in bytes. A compressed instruction is expanded
+    /// to its 32-bit equivalent. With compressed instructions, a 32-bit instruction may span two
+    /// words.
+    fn fetch_insn<C: EmuContext>(
+        &self,
+        ctx: &mut C,
+        pc: ByteAddr,
+    ) -> Result<Option<(Word, usize)>> {
+        let illegal = |ctx: &mut C, word: Word| -> Result<Option<(Word, usize)>> {
+            ctx.trap(TrapCause::IllegalInstruction(word))?;
+            Err(anyhow!(
+                "Fatal: illegal instruction at pc={:?}: 0x{:08x}",
+                pc,
+                word
+            ))
+        };
+
+        let word = ctx.fetch(pc.waddr())?;
+        if !ctx.allows_compressed() {
+            if word & 0x03 != 0x03 {
+                // Opcode must end in 0b11 in RV32IM.
+                return illegal(ctx, word);
+            }
+            return Ok(Some((word, WORD_SIZE)));
+        }
+
+        if pc.0 & 1 != 0 {
+            ctx.trap(TrapCause::InstructionAddressMisaligned)?;
+            return Ok(None);
+        }
+        let word = if pc.is_aligned() { word } else { word >> 16 };
+        if insn_size(word as u16) == 2 {
+            return match expand_compressed(word as u16) {
+                Some(insn) => Ok(Some((insn, 2))),
+                None => illegal(ctx, word & 0xffff),
+            };
+        }
+        if pc.is_aligned() {
+            return Ok(Some((word, WORD_SIZE)));
+        }
+        // The upper half of the instruction is in the next word.
+        if !ctx.check_insn_load(pc + 2usize) {
+            ctx.trap(TrapCause::InstructionAccessFault)?;
+            return Ok(None);
+        }
+        let next = ctx.fetch(pc.waddr() + 1usize)?;
+        Ok(Some((word | next << 16, WORD_SIZE)))
+    }
+
+    /// Whether `pc` is a valid target of a jump or branch.
+    fn is_insn_aligned<C: EmuContext>(ctx: &C, pc: ByteAddr) -> bool {
+        if ctx.allows_compressed() {
+            pc.0 & 1 == 0
+        } else {
+            pc.is_aligned()
+        }
+    }
+
     fn step_compute<M: EmuContext>(
         &self,
         ctx: &mut M,
         kind: InsnKind,
         decoded: &DecodedInstruction,
+        size: usize,
     ) -> Result<bool> {
         use InsnKind::*;
 
         let pc = ctx.get_pc();
-        let mut new_pc = pc + WORD_SIZE;
+        let mut new_pc = pc + size;
+        if matches!(kind, FENCE | FENCE_I) {
+            // No register is read nor written.
+            ctx.set_pc(new_pc);
+            return Ok(true);
+        }
         let imm_i = decoded.imm_i();
         let out = match kind {
             // Instructions that do not read rs1 nor rs2.
             JAL => {
                 new_pc = pc.wrapping_add(decoded.imm_j());
-                (pc + WORD_SIZE).0
+                (pc + size).0
             }
             LUI => decoded.imm_u(),
             AUIPC => (pc.wrapping_add(decoded.imm_u())).0,
@@ -546,9 +701,11 @@ impl Emulator {
                             0
                         }
                     }
+                    CLZ => rs1.leading_zeros(),
+                    CPOP => rs1.count_ones(),
                     JALR => {
                         new_pc = ByteAddr(rs1.wrapping_add(imm_i) & 0xfffffffe);
-                        (pc + WORD_SIZE).0
+                        (pc + size).0
                     }
 
                     _ => {
@@ -561,6 +718,9 @@ impl Emulator {
                             XOR => rs1 ^ rs2,
                             OR => rs1 | rs2,
                             AND => rs1 & rs2,
+                            ANDN => rs1 & !rs2,
+                            ORN => rs1 | !rs2,
+                            XNOR => !(rs1 ^ rs2),
                             SLL => rs1 << (rs2 & 0x1f),
                             SRL => rs1 >> (rs2 & 0x1f),
                             SRA => ((rs1 as i32) >> (rs2 & 0x1f)) as u32,
@@ -620,7 +780,7 @@ impl Emulator {
                 }
             }
         };
-        if !new_pc.is_aligned() {
+        if !Self::is_insn_aligned(ctx, new_pc) {
             return ctx.trap(TrapCause::InstructionAddressMisaligned);
         }
         ctx.store_register(decoded.rd_internal() as usize, out)?;
@@ -633,6 +793,7 @@ impl Emulator {
         ctx: &mut M,
         kind: InsnKind,
         decoded: &DecodedInstruction,
+        size: usize,
     ) -> Result<bool> {
         use InsnKind::*;
 
@@ -653,10 +814,10 @@ impl Emulator {
         let new_pc = if taken {
             pc.wrapping_add(decoded.imm_b())
         } else {
-            pc + WORD_SIZE
+            pc + size
         };
 
-        if !new_pc.is_aligned() {
+        if !Self::is_insn_aligned(ctx, new_pc) {
             return ctx.trap(TrapCause::InstructionAddressMisaligned);
         }
         ctx.set_pc(new_pc);
@@ -668,6 +829,7 @@ impl Emulator {
         ctx: &mut M,
         kind: InsnKind,
         decoded: &DecodedInstruction,
+        size: usize,
     ) -> Result<bool> {
         let rs1 = ctx.load_register(decoded.rs1 as usize)?;
         // LOAD instructions do not read rs2.
@@ -711,7 +873,7 @@ impl Emulator {
             _ => unreachable!(),
         };
         ctx.store_register(decoded.rd_internal() as usize, out)?;
-        ctx.set_pc(ctx.get_pc() + WORD_SIZE);
+        ctx.set_pc(ctx.get_pc() + size);
         Ok(true)
     }
 
@@ -720,6 +882,7 @@ impl Emulator {
         ctx: &mut M,
         kind: InsnKind,
         decoded: &DecodedInstruction,
+        size: usize,
     ) -> Result<bool> {
         let rs1 = ctx.load_register(decoded.rs1 as usize)?;
         let rs2 = ctx.load_register(decoded.rs2 as usize)?;
@@ -753,7 +916,7 @@ impl Emulator {
             _ => unreachable!(),
         }
         ctx.store_memory(addr.waddr(), data)?;
-        ctx.set_pc(ctx.get_pc() + WORD_SIZE);
+        ctx.set_pc(ctx.get_pc() + size);
         Ok(true)
     }
 
@@ -1,4 +1,4 @@
-use crate::{InsnKind, rv32im::InsnFormat};
+use crate::{InsnKind, rv32c::insn_size, rv32im::InsnFormat};
 
 const MASK_4_BITS: u32 = 0xF;
 const MASK_5_BITS: u32 = 0x1F;
@@ -51,7 +51,12 @@ const fn encode_i(kind: InsnKind, rs1: u32, rd: u32, imm: u32) -> u32 {
     let opcode = kind.codes().opcode;
     // SRLI/SRAI use a specialization of the I-type format with the shift type in imm[10].
     let is_arithmetic_right_shift = (matches!(kind, InsnKind::SRAI) as u32) << 10;
-    let imm = imm & MASK_12_BITS | is_arithmetic_right_shift;
+    let imm = match kind {
+        // The unary bit-manipulation instructions have a fixed immediate.
+        InsnKind::CLZ => 0x600,
+        InsnKind::CPOP => 0x602,
+        _ => imm & MASK_12_BITS | is_arithmetic_right_shift,
+    };
     imm << 20 | rs1 << 15 | func3 << 12 | rd << 7 | opcode
 }
 
@@ -114,3 +119,21 @@ const fn encode_j(kind: InsnKind, rd: u32, imm: u32) -> u32 {
 const fn encode_u(kind: InsnKind, rd: u32, imm: u32) -> u32 {
     (imm >> 12) << 12 | (rd & MASK_5_BITS) << 7 | kind.codes().opcode
 }
+
+/// Lay out a program of 32-bit and compressed instructions as it is in memory, in words. A
+/// compressed instruction is given by its 16 bits. The last word is padded with a C.NOP if needed.
+pub fn encode_rvc_program(insns: &[u32]) -> Vec<u32> {
+    const C_NOP: u16 = 0x0001;
+    let halves = insns
+        .iter()
+        .flat_map(|&insn| match insn_size(insn as u16) {
+            2 => vec![insn as u16],
+            _ => vec![insn as u16, (insn >> 16) as u16],
+        })
+        .chain([C_NOP])
+        .collect::<Vec<_>>();
+    halves
+        .chunks_exact(2)
+        .map(|pair| pair[0] as u32 | (pair[1] as u32) << 16)
+        .collect()
+}
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, thread};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    MemoryAccessColumns,
+    addr::{Cycle, Word, WordAddr},
+    tracer::{StepRecord, Tracer},
+    vm_state::VMState,
+};
+
+/// The steps of one shard, a segment of the execution that starts where the previous shard
+/// stopped.
+#[derive(Clone, Debug, Default)]
+pub struct ShardTrace {
+    pub steps: Vec<StepRecord>,
+}
+
+/// The traces of all shards as one execution. The shards take consecutive ranges of cycles, in
+/// the order they were run.
+#[derive(Clone, Debug, Default)]
+pub struct MergedTrace {
+    pub steps: Vec<StepRecord>,
+    /// All the addresses that were accessed and the cycle when they were last accessed.
+    pub final_accesses: HashMap<WordAddr, Cycle>,
+}
+
+impl MergedTrace {
+    /// The register and memory accesses of the merged steps, as columns.
+    pub fn export_mc(&self) -> MemoryAccessColumns {
+        MemoryAccessColumns::from_steps(&self.steps)
+    }
+}
+
+/// Run `vm` for at most `max_steps` steps, or until it halts.
+///
+/// `vm` is left where the next shard starts: its pc, registers, memory and the cycle of its
+/// tracer carry over, so that the shards of one VM chain into a single execution.
+pub fn run_shard(vm: &mut VMState, max_steps: usize) -> Result<ShardTrace> {
+    let steps = vm
+        .iter_until_halt()
+        .take(max_steps)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ShardTrace { steps })
+}
+
+/// Run `vm` until it halts, and take a [`VMState::snapshot`] at the start of each shard of at
+/// most `max_steps` steps, for [`replay_shards`].
+pub fn shard_snapshots(vm: &mut VMState, max_steps: usize) -> Result<Vec<Vec<u8>>> {
+    let mut snapshots = vec![];
+    while !vm.halted() {
+        snapshots.push(vm.snapshot()?);
+        for step in vm.iter_until_halt().take(max_steps) {
+            step?;
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Run the shards that start at `snapshots`, from [`shard_snapshots`] with the same
+/// `max_steps`, in parallel on copies of `vm`, which must have the same program and platform.
+///
+/// A restored VM does not know the accesses before its snapshot, so the operations of each
+/// shard are linked to the last accesses of the previous shards, as if the shards had been
+/// run in sequence by [`run_shard`]. The result is ready for [`merge_shards`].
+pub fn replay_shards(
+    vm: &VMState,
+    snapshots: &[Vec<u8>],
+    max_steps: usize,
+) -> Result<Vec<ShardTrace>> {
+    let num_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = snapshots.len().div_ceil(num_threads).max(1);
+    let mut shards = thread::scope(|scope| {
+        let handles = snapshots
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|snapshot| {
+                            let mut vm = vm.clone();
+                            vm.restore(snapshot)?;
+                            run_shard(&mut vm, max_steps)
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("shard replay panicked"))
+            .collect::<Result<Vec<_>>>()
+    })?
+    .concat();
+
+    let mut latest = HashMap::new();
+    for step in shards.iter_mut().flat_map(|shard| &mut shard.steps) {
+        step.relink(&mut latest);
+    }
+    Ok(shards)
+}
+
+/// Merge the traces of shards run by [`run_shard`] or [`replay_shards`], in this order.
+///
+/// Every shard must continue the previous ones: its first step follows the last step of the
+/// previous shard in cycle and pc, and its first access to an address follows the last access
+/// to it, with the value left there.
+pub fn merge_shards(shards: Vec<ShardTrace>) -> Result<MergedTrace> {
+    let mut latest: HashMap<WordAddr, (Cycle, Word)> = HashMap::new();
+    let mut steps: Vec<StepRecord> = vec![];
+    for (i, shard) in shards.into_iter().enumerate() {
+        let not_continued = |reason: String| anyhow!("shard {} does not continue: {}", i, reason);
+        for step in shard.steps {
+            if let Some(last) = steps.last() {
+                if step.cycle() != last.cycle() + Tracer::SUBCYCLES_PER_INSN
+                    || step.pc().before != last.pc().after
+                {
+                    return Err(not_continued(format!(
+                        "step at cycle {} and pc {:?} after cycle {} and pc {:?}",
+                        step.cycle(),
+                        step.pc().before,
+                        last.cycle(),
+                        last.pc().after
+                    )));
+                }
+            }
+
+            let reads = [step.rs1(), step.rs2()]
+                .into_iter()
+                .flatten()
+                .map(|op| (op.addr, op.previous_cycle, op.value, op.cycle, op.value));
+            let writes = [step.rd(), step.memory_op()]
+                .into_iter()
+                .flatten()
+                .map(|op| {
+                    let value = op.value;
+                    (
+                        op.addr,
+                        op.previous_cycle,
+                        value.before,
+                        op.cycle,
+                        value.after,
+                    )
+                });
+            for (addr, previous_cycle, before, cycle, after) in reads.chain(writes) {
+                if let Some(&(latest_cycle, latest_value)) = latest.get(&addr) {
+                    if (previous_cycle, before) != (latest_cycle, latest_value) {
+                        return Err(not_continued(format!(
+                            "access to {:?} at cycle {} follows cycle {} with {:#x}, not cycle \
+                             {} with {:#x}",
+                            addr, cycle, previous_cycle, before, latest_cycle, latest_value
+                        )));
+                    }
+                } else if previous_cycle != 0 {
+                    return Err(not_continued(format!(
+                        "access to {:?} at cycle {} follows cycle {} of another shard",
+                        addr, cycle, previous_cycle
+                    )));
+                }
+                latest.insert(addr, (cycle, after));
+            }
+            steps.push(step);
+        }
+    }
+
+    let final_accesses = latest
+        .into_iter()
+        .map(|(addr, (cycle, _))| (addr, cycle))
+        .collect();
+    Ok(MergedTrace {
+        steps,
+        final_accesses,
+    })
+}
@@ -41,6 +41,9 @@ pub struct MemOp<T> {
     pub value: T,
     /// The cycle when this memory address was last accessed before this operation.
     pub previous_cycle: Cycle,
+    /// The cycle of this operation: the instruction cycle plus the operand's subcycle,
+    /// e.g. [`Tracer::SUBCYCLE_RS1`].
+    pub cycle: Cycle,
 }
 
 impl<T> MemOp<T> {
@@ -48,6 +51,12 @@ impl<T> MemOp<T> {
     pub fn register_index(&self) -> RegIdx {
         Platform::register_index(self.addr.into())
     }
+
+    fn relink(&mut self, latest: &mut HashMap<WordAddr, Cycle>) {
+        if let Some(previous_cycle) = latest.insert(self.addr, self.cycle) {
+            self.previous_cycle = previous_cycle;
+        }
+    }
 }
 
 pub type ReadOp = MemOp<Word>;
@@ -140,6 +149,7 @@ impl StepRecord {
                     after: mem_op.value,
                 },
                 previous_cycle: mem_op.previous_cycle,
+                cycle: mem_op.cycle,
             }),
             prev_cycle,
         )
@@ -188,6 +198,12 @@ impl StepRecord {
         )
     }
 
+    /// Create a test record for an instruction that accesses no register nor memory, e.g. FENCE.
+    pub fn new_nop_instruction(cycle: Cycle, pc: ByteAddr, insn_code: u32) -> StepRecord {
+        let pc = Change::new(pc, pc + PC_STEP_SIZE);
+        StepRecord::new_insn(cycle, pc, insn_code, None, None, None, None, 0)
+    }
+
     /// Create a test record for an ECALL instruction that can do anything.
     pub fn new_ecall_any(cycle: Cycle, pc: ByteAddr) -> StepRecord {
         let value = 1234;
@@ -205,6 +221,7 @@ impl StepRecord {
                     after: value,
                 },
                 previous_cycle: 0,
+                cycle: cycle + Tracer::SUBCYCLE_MEM,
             }),
             0,
         )
@@ -230,16 +247,19 @@ impl StepRecord {
                 addr: Platform::register_vma(insn.rs1() as RegIdx).into(),
                 value: rs1,
                 previous_cycle,
+                cycle: cycle + Tracer::SUBCYCLE_RS1,
             }),
             rs2: rs2_read.map(|rs2| ReadOp {
                 addr: Platform::register_vma(insn.rs2() as RegIdx).into(),
                 value: rs2,
                 previous_cycle,
+                cycle: cycle + Tracer::SUBCYCLE_RS2,
             }),
             rd: rd.map(|rd| WriteOp {
                 addr: Platform::register_vma(insn.rd_internal() as RegIdx).into(),
                 value: rd,
                 previous_cycle,
+                cycle: cycle + Tracer::SUBCYCLE_RD,
             }),
             memory_op,
         }
@@ -282,9 +302,32 @@ impl StepRecord {
     pub fn is_busy_loop(&self) -> bool {
         self.pc.before == self.pc.after
     }
+
+    /// Merge this step with the `next` one into a single step that spans both of their cycles.
+    /// It keeps the operands of this step, and its rd write takes the value left by `next`,
+    /// which writes the same register if it writes any.
+    pub(crate) fn fuse(mut self, next: &StepRecord) -> StepRecord {
+        self.pc.after = next.pc.after;
+        if let (Some(rd), Some(next_rd)) = (&mut self.rd, &next.rd) {
+            debug_assert_eq!(rd.addr, next_rd.addr);
+            rd.value.after = next_rd.value.after;
+        }
+        self
+    }
+
+    /// Point every operation to the access given by `latest` for its address, if any, and
+    /// record the operation there as the latest access.
+    pub(crate) fn relink(&mut self, latest: &mut HashMap<WordAddr, Cycle>) {
+        for op in [&mut self.rs1, &mut self.rs2].into_iter().flatten() {
+            op.relink(latest);
+        }
+        for op in [&mut self.rd, &mut self.memory_op].into_iter().flatten() {
+            op.relink(latest);
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Tracer {
     record: StepRecord,
 
@@ -304,6 +347,14 @@ impl Tracer {
     pub const SUBCYCLE_MEM: Cycle = 3;
     pub const SUBCYCLES_PER_INSN: Cycle = 4;
 
+    /// The width of the timestamps compared in the circuits, which bounds the length of a trace.
+    pub const TIMESTAMP_BITS: u32 = 32;
+    /// The last cycle with a timestamp of [`Self::TIMESTAMP_BITS`] bits.
+    pub const MAX_CYCLE: Cycle = (1 << Self::TIMESTAMP_BITS) - 1;
+    /// The most instructions in a trace, whose last one moves to a cycle at most
+    /// [`Self::MAX_CYCLE`].
+    pub const MAX_STEPS: usize = (Self::MAX_CYCLE / Self::SUBCYCLES_PER_INSN) as usize - 1;
+
     pub fn new() -> Tracer {
         Tracer {
             record: StepRecord {
@@ -314,6 +365,17 @@ impl Tracer {
         }
     }
 
+    /// A tracer that continues at `cycle`, with no record of earlier accesses.
+    pub(crate) fn at_cycle(cycle: Cycle) -> Tracer {
+        Tracer {
+            record: StepRecord {
+                cycle,
+                ..StepRecord::default()
+            },
+            latest_accesses: HashMap::new(),
+        }
+    }
+
     /// Return the completed step and advance to the next cycle.
     pub fn advance(&mut self) -> StepRecord {
         let next_cycle = self.record.cycle + Self::SUBCYCLES_PER_INSN;
@@ -327,9 +389,10 @@ impl Tracer {
         self.record.pc.after = pc;
     }
 
-    pub fn fetch(&mut self, pc: WordAddr, value: Word) {
-        self.record.pc.before = pc.baddr();
-        self.record.insn_code = value;
+    /// Record the instruction at `pc`, as its 32-bit equivalent if compressed.
+    pub fn fetch(&mut self, pc: ByteAddr, insn: Word) {
+        self.record.pc.before = pc;
+        self.record.insn_code = insn;
     }
 
     pub fn load_register(&mut self, idx: RegIdx, value: Word) {
@@ -337,18 +400,10 @@ impl Tracer {
 
         match (&self.record.rs1, &self.record.rs2) {
             (None, None) => {
-                self.record.rs1 = Some(ReadOp {
-                    addr,
-                    value,
-                    previous_cycle: self.track_access(addr, Self::SUBCYCLE_RS1),
-                });
+                self.record.rs1 = Some(self.track_op(addr, value, Self::SUBCYCLE_RS1));
             }
             (Some(_), None) => {
-                self.record.rs2 = Some(ReadOp {
-                    addr,
-                    value,
-                    previous_cycle: self.track_access(addr, Self::SUBCYCLE_RS2),
-                });
+                self.record.rs2 = Some(self.track_op(addr, value, Self::SUBCYCLE_RS2));
             }
             _ => unimplemented!("Only two register reads are supported"),
         }
@@ -360,11 +415,7 @@ impl Tracer {
         }
 
         let addr = Platform::register_vma(idx).into();
-        self.record.rd = Some(WriteOp {
-            addr,
-            value,
-            previous_cycle: self.track_access(addr, Self::SUBCYCLE_RD),
-        });
+        self.record.rd = Some(self.track_op(addr, value, Self::SUBCYCLE_RD));
     }
 
     pub fn load_memory(&mut self, addr: WordAddr, value: Word) {
@@ -376,11 +427,18 @@ impl Tracer {
             unimplemented!("Only one memory access is supported");
         }
 
-        self.record.memory_op = Some(WriteOp {
+        self.record.memory_op = Some(self.track_op(addr, value, Self::SUBCYCLE_MEM));
+    }
+
+    /// Record an access at `subcycle` of the current instruction, with its own timestamp and
+    /// the timestamp of the previous access to the same address.
+    fn track_op<T>(&mut self, addr: WordAddr, value: T, subcycle: Cycle) -> MemOp<T> {
+        MemOp {
             addr,
             value,
-            previous_cycle: self.track_access(addr, Self::SUBCYCLE_MEM),
-        });
+            previous_cycle: self.track_access(addr, subcycle),
+            cycle: self.record.cycle + subcycle,
+        }
     }
 
     /// - Return the cycle when an address was last accessed.
@@ -1,19 +1,58 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use super::rv32im::EmuContext;
 use crate::{
     PC_STEP_SIZE, Program,
-    addr::{ByteAddr, RegIdx, Word, WordAddr},
+    addr::{ByteAddr, Cycle, RegIdx, WORD_SIZE, Word, WordAddr},
     platform::Platform,
-    rv32im::{DecodedInstruction, Emulator, TrapCause},
+    random::random_word,
+    rv32im::{DecodeCache, DecodedInstruction, Emulator, TrapCause},
     tracer::{Change, StepRecord, Tracer},
 };
 use anyhow::{Result, anyhow};
-use std::{iter::from_fn, ops::Deref, sync::Arc};
+use std::{
+    iter::from_fn,
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+/// A memory range that stops execution when it is accessed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub addrs: Range<WordAddr>,
+    pub on_read: bool,
+    pub on_write: bool,
+}
+
+/// Why [`VMState::iter_until_stop`] stopped before the program halted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The pc reached a breakpoint. The instruction there is not executed yet.
+    Breakpoint(ByteAddr),
+    /// The instruction at `pc` accessed a watched word. Reads have `before == after`.
+    Watchpoint {
+        pc: ByteAddr,
+        addr: WordAddr,
+        value: Change<Word>,
+    },
+}
+
+/// The state saved by [`VMState::snapshot`]. The memory is the pages that differ from the
+/// program image, each as the byte address of the page and its words.
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct Snapshot {
+    pc: Word,
+    cycle: Cycle,
+    halted: bool,
+    registers: Vec<Word>,
+    pages: Vec<(u32, Vec<Word>)>,
+}
 
 /// An implementation of the machine state and of the side-effects of operations.
+#[derive(Clone)]
 pub struct VMState {
     program: Arc<Program>,
+    decode_cache: Arc<DecodeCache>,
     platform: Platform,
     pc: Word,
     /// Map a word-address (addr/4) to a word.
@@ -22,6 +61,15 @@ pub struct VMState {
     // Termination.
     halted: bool,
     tracer: Tracer,
+    /// The seed of the words of the RANDOM ecall.
+    random_seed: u64,
+
+    // Debugging.
+    breakpoints: BTreeSet<ByteAddr>,
+    watchpoints: Vec<Watchpoint>,
+    stops: Vec<StopReason>,
+    /// Breakpoint we stopped at, which must not fire again when resuming.
+    resume_pc: Option<ByteAddr>,
 }
 
 impl VMState {
@@ -29,18 +77,31 @@ impl VMState {
     /// 32 architectural registers + 1 register RD_NULL for dark writes to x0.
     pub const REG_COUNT: usize = 32 + 1;
 
+    /// The size in bytes of the memory pages saved by [`Self::snapshot`].
+    pub const PAGE_SIZE: u32 = 4096;
+
     pub fn new(platform: Platform, program: Program) -> Self {
         let pc = program.entry;
+        let decode_cache = Arc::new(DecodeCache::new(
+            program.base_address,
+            &program.instructions,
+        ));
         let program = Arc::new(program);
 
         let mut vm = Self {
             pc,
             platform,
             program: program.clone(),
+            decode_cache,
             memory: HashMap::new(),
             registers: [0; VMState::REG_COUNT],
             halted: false,
             tracer: Tracer::new(),
+            random_seed: 0,
+            breakpoints: BTreeSet::new(),
+            watchpoints: vec![],
+            stops: vec![],
+            resume_pc: None,
         };
 
         // init memory from program.image
@@ -72,6 +133,16 @@ impl VMState {
         self.program.deref()
     }
 
+    /// The seed of the words returned by the RANDOM ecall. The host commits to it in the public
+    /// values, so that the verifier can check the words.
+    pub fn random_seed(&self) -> u64 {
+        self.random_seed
+    }
+
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_seed = seed;
+    }
+
     /// Set a word in memory without side effects.
     pub fn init_memory(&mut self, addr: WordAddr, value: Word) {
         self.memory.insert(addr, value);
@@ -79,17 +150,29 @@ impl VMState {
 
     pub fn iter_until_halt(&mut self) -> impl Iterator<Item = Result<StepRecord>> + '_ {
         let emu = Emulator::new();
+        let cache = self.decode_cache.clone();
         from_fn(move || {
             if self.halted() {
                 None
             } else {
-                Some(self.step(&emu))
+                let step = self.step(&emu, &cache);
+                // nobody stops here, so do not accumulate watchpoint hits
+                self.stops.clear();
+                Some(step)
             }
         })
     }
 
-    fn step(&mut self, emu: &Emulator) -> Result<StepRecord> {
-        emu.step(self)?;
+    fn step(&mut self, emu: &Emulator, cache: &DecodeCache) -> Result<StepRecord> {
+        // the state after this step must have a timestamp that the circuits can compare
+        let cycle = self.tracer.cycle();
+        if cycle + Tracer::SUBCYCLES_PER_INSN > Tracer::MAX_CYCLE {
+            return Err(anyhow!(
+                "Timestamp overflow at cycle {cycle}: traces are limited to {} steps",
+                Tracer::MAX_STEPS
+            ));
+        }
+        emu.step_cached(self, Some(cache))?;
         let step = self.tracer.advance();
         if step.is_busy_loop() && !self.halted() {
             Err(anyhow!("Stuck in loop {}", "{}"))
@@ -98,6 +181,176 @@ impl VMState {
         }
     }
 
+    /// Execute up to `max_steps` instructions without recording them, e.g. to
+    /// estimate the length of the trace quickly. Returns the number of executed
+    /// instructions, and the VM has halted if it is less than `max_steps`.
+    ///
+    /// Breakpoints and watchpoints are ignored, and the tracer does not see these
+    /// steps, so the execution must not be traced further.
+    pub fn run_untraced(&mut self, max_steps: usize) -> Result<usize> {
+        let emu = Emulator::new();
+        let cache = self.decode_cache.clone();
+        let mut ctx = Untraced { vm: self };
+        for i in 0..max_steps {
+            if ctx.vm.halted() {
+                return Ok(i);
+            }
+            let pc = ctx.vm.pc;
+            emu.step_cached(&mut ctx, Some(&cache))?;
+            if ctx.vm.pc == pc && !ctx.vm.halted() {
+                return Err(anyhow!("Stuck in loop {}", "{}"));
+            }
+        }
+        Ok(max_steps)
+    }
+
+    pub fn add_breakpoint(&mut self, pc: ByteAddr) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: ByteAddr) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    /// Stop after any instruction that reads and/or writes a word in `addrs`.
+    /// Returns an id for [`Self::remove_watchpoint`].
+    pub fn add_watchpoint(
+        &mut self,
+        addrs: Range<WordAddr>,
+        on_read: bool,
+        on_write: bool,
+    ) -> usize {
+        self.watchpoints.push(Watchpoint {
+            addrs,
+            on_read,
+            on_write,
+        });
+        self.watchpoints.len() - 1
+    }
+
+    pub fn remove_watchpoint(&mut self, id: usize) {
+        // keep the ids of the other watchpoints stable
+        self.watchpoints[id] = Watchpoint {
+            addrs: WordAddr::from(0)..WordAddr::from(0),
+            on_read: false,
+            on_write: false,
+        };
+    }
+
+    /// Like [`Self::iter_until_halt`], but also ends after a step that triggered a watchpoint,
+    /// or before executing an instruction at a breakpoint. The reasons are then available from
+    /// [`Self::take_stop_reasons`], and calling this again resumes execution.
+    pub fn iter_until_stop(&mut self) -> impl Iterator<Item = Result<StepRecord>> + '_ {
+        let emu = Emulator::new();
+        let cache = self.decode_cache.clone();
+        from_fn(move || {
+            if self.halted() || !self.stops.is_empty() {
+                return None;
+            }
+            let pc = ByteAddr(self.pc);
+            if self.resume_pc.take() != Some(pc) && self.breakpoints.contains(&pc) {
+                self.resume_pc = Some(pc);
+                self.stops.push(StopReason::Breakpoint(pc));
+                return None;
+            }
+            Some(self.step(&emu, &cache))
+        })
+    }
+
+    /// The reasons of the last stop, clearing them so that execution can resume.
+    pub fn take_stop_reasons(&mut self) -> Vec<StopReason> {
+        std::mem::take(&mut self.stops)
+    }
+
+    fn check_watchpoints(&mut self, addr: WordAddr, value: Change<Word>, is_write: bool) {
+        let hit = self
+            .watchpoints
+            .iter()
+            .any(|w| w.addrs.contains(&addr) && if is_write { w.on_write } else { w.on_read });
+        if hit {
+            self.stops.push(StopReason::Watchpoint {
+                pc: ByteAddr(self.pc),
+                addr,
+                value,
+            });
+        }
+    }
+
+    /// Save the registers, the pc, the cycle, and the memory pages written since the program
+    /// was loaded, to be continued with [`Self::restore`], e.g. on another machine.
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        let image_value = |addr: WordAddr| {
+            let addr: u32 = addr.into();
+            self.program.image.get(&addr).copied().unwrap_or(0)
+        };
+        let dirty_pages = self
+            .memory
+            .iter()
+            .filter(|&(&addr, &value)| value != image_value(addr))
+            .map(|(&addr, _)| u32::from(addr) / Self::PAGE_SIZE * Self::PAGE_SIZE)
+            .collect::<BTreeSet<_>>();
+        let pages = dirty_pages
+            .into_iter()
+            .map(|page| {
+                let words = (page..)
+                    .step_by(WORD_SIZE)
+                    .take(Self::PAGE_SIZE as usize / WORD_SIZE)
+                    .map(|addr| self.peek_memory(addr.into()))
+                    .collect();
+                (page, words)
+            })
+            .collect();
+        let snapshot = Snapshot {
+            pc: self.pc,
+            cycle: self.tracer.cycle(),
+            halted: self.halted,
+            registers: self.registers.to_vec(),
+            pages,
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&snapshot)
+            .map_err(|e| anyhow!("cannot serialize the snapshot: {e}"))?;
+        Ok(bytes.into_vec())
+    }
+
+    /// Continue from a [`Self::snapshot`]. This VM must have been created from the same program
+    /// and platform. Its memory is reset to the program image with the saved pages on top, and
+    /// the tracer continues at the saved cycle with no record of earlier accesses.
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<()> {
+        // The archive is read in place, which requires it to be aligned.
+        let mut aligned = rkyv::util::AlignedVec::<16>::new();
+        aligned.extend_from_slice(bytes);
+        let snapshot = rkyv::from_bytes::<Snapshot, rkyv::rancor::Error>(&aligned)
+            .map_err(|e| anyhow!("invalid snapshot: {e}"))?;
+        let registers = snapshot
+            .registers
+            .try_into()
+            .map_err(|_| anyhow!("the snapshot does not have {} registers", Self::REG_COUNT))?;
+
+        let mut memory = HashMap::new();
+        for (&addr, &value) in &self.program.image {
+            memory.insert(WordAddr::from(addr), value);
+        }
+        for (page, words) in snapshot.pages {
+            if page % Self::PAGE_SIZE != 0 || words.len() != Self::PAGE_SIZE as usize / WORD_SIZE {
+                return Err(anyhow!(
+                    "invalid snapshot page {page:#x} of {} words",
+                    words.len()
+                ));
+            }
+            for (addr, value) in (page..).step_by(WORD_SIZE).zip(words) {
+                memory.insert(WordAddr::from(addr), value);
+            }
+        }
+        self.memory = memory;
+        self.registers = registers;
+        self.pc = snapshot.pc;
+        self.halted = snapshot.halted;
+        self.tracer = Tracer::at_cycle(snapshot.cycle);
+        self.stops.clear();
+        self.resume_pc = None;
+        Ok(())
+    }
+
     pub fn init_register_unsafe(&mut self, idx: RegIdx, value: Word) {
         self.registers[idx] = value;
     }
@@ -109,7 +362,8 @@ impl VMState {
 }
 
 impl EmuContext for VMState {
-    // Expect an ecall to terminate the program: function HALT with argument exit_code.
+    // Expect an ecall to terminate the program: function HALT with argument exit_code, or
+    // function RANDOM with argument the index of the word to return.
     fn ecall(&mut self) -> Result<bool> {
         let function = self.load_register(Platform::reg_ecall())?;
         let arg0 = self.load_register(Platform::reg_arg0())?;
@@ -118,6 +372,10 @@ impl EmuContext for VMState {
 
             self.halt();
             Ok(true)
+        } else if function == Platform::ecall_random() {
+            self.store_register(Platform::reg_arg0(), random_word(self.random_seed, arg0))?;
+            self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+            Ok(true)
         } else if self.platform.unsafe_ecall_nop {
             // Treat unknown ecalls as all powerful instructions:
             // Read two registers, write one register, write one memory word, and branch.
@@ -168,6 +426,7 @@ impl EmuContext for VMState {
     fn load_memory(&mut self, addr: WordAddr) -> Result<Word> {
         let value = self.peek_memory(addr);
         self.tracer.load_memory(addr, value);
+        self.check_watchpoints(addr, Change::new(value, value), false);
         Ok(value)
     }
 
@@ -175,6 +434,7 @@ impl EmuContext for VMState {
     fn store_memory(&mut self, addr: WordAddr, after: Word) -> Result<()> {
         let before = self.peek_memory(addr);
         self.tracer.store_memory(addr, Change { after, before });
+        self.check_watchpoints(addr, Change { before, after }, true);
         self.memory.insert(addr, after);
         Ok(())
     }
@@ -190,9 +450,15 @@ impl EmuContext for VMState {
     }
 
     fn fetch(&mut self, pc: WordAddr) -> Result<Word> {
-        let value = self.peek_memory(pc);
-        self.tracer.fetch(pc, value);
-        Ok(value)
+        Ok(self.peek_memory(pc))
+    }
+
+    fn on_insn_fetched(&mut self, pc: ByteAddr, insn: Word) {
+        self.tracer.fetch(pc, insn);
+    }
+
+    fn allows_compressed(&self) -> bool {
+        self.platform.rvc
     }
 
     fn check_data_load(&self, addr: ByteAddr) -> bool {
@@ -207,3 +473,145 @@ impl EmuContext for VMState {
         self.platform.can_execute(addr.0)
     }
 }
+
+/// Execution on a [`VMState`] without the tracer, for [`VMState::run_untraced`].
+struct Untraced<'a> {
+    vm: &'a mut VMState,
+}
+
+impl EmuContext for Untraced<'_> {
+    fn ecall(&mut self) -> Result<bool> {
+        let function = self.peek_register(Platform::reg_ecall());
+        if function == Platform::ecall_halt() {
+            self.vm.halt();
+            Ok(true)
+        } else if function == Platform::ecall_random() {
+            let index = self.peek_register(Platform::reg_arg0());
+            self.store_register(
+                Platform::reg_arg0(),
+                random_word(self.vm.random_seed, index),
+            )?;
+            self.set_pc(ByteAddr(self.vm.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else if self.vm.platform.unsafe_ecall_nop {
+            // The accesses of an unknown ecall do not change the state.
+            self.set_pc(ByteAddr(self.vm.pc) + PC_STEP_SIZE);
+            Ok(true)
+        } else {
+            self.trap(TrapCause::EcallError)
+        }
+    }
+
+    fn trap(&self, cause: TrapCause) -> Result<bool> {
+        self.vm.trap(cause)
+    }
+
+    fn get_pc(&self) -> ByteAddr {
+        ByteAddr(self.vm.pc)
+    }
+
+    fn set_pc(&mut self, after: ByteAddr) {
+        self.vm.pc = after.0;
+    }
+
+    fn load_register(&mut self, idx: RegIdx) -> Result<Word> {
+        Ok(self.peek_register(idx))
+    }
+
+    fn store_register(&mut self, idx: RegIdx, after: Word) -> Result<()> {
+        if idx != 0 {
+            self.vm.registers[idx] = after;
+        }
+        Ok(())
+    }
+
+    fn load_memory(&mut self, addr: WordAddr) -> Result<Word> {
+        Ok(self.peek_memory(addr))
+    }
+
+    fn store_memory(&mut self, addr: WordAddr, after: Word) -> Result<()> {
+        self.vm.memory.insert(addr, after);
+        Ok(())
+    }
+
+    fn peek_register(&self, idx: RegIdx) -> Word {
+        self.vm.registers[idx]
+    }
+
+    fn peek_memory(&self, addr: WordAddr) -> Word {
+        self.vm.peek_memory(addr)
+    }
+
+    fn check_data_load(&self, addr: ByteAddr) -> bool {
+        self.vm.check_data_load(addr)
+    }
+
+    fn check_data_store(&self, addr: ByteAddr) -> bool {
+        self.vm.check_data_store(addr)
+    }
+
+    fn check_insn_load(&self, addr: ByteAddr) -> bool {
+        self.vm.check_insn_load(addr)
+    }
+
+    fn allows_compressed(&self) -> bool {
+        self.vm.allows_compressed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CENO_PLATFORM;
+
+    #[test]
+    fn test_timestamp_overflow() {
+        // addi x0, x0, 0
+        let nops = vec![0x0000_0013; 4];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let image = (pc_base..).step_by(WORD_SIZE).zip(nops.clone()).collect();
+        let program = Program::new(pc_base, pc_base, nops, image);
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.tracer = Tracer::at_cycle(Tracer::MAX_CYCLE - 2 * Tracer::SUBCYCLES_PER_INSN);
+
+        let steps = vm.iter_until_halt().take(3).collect::<Vec<_>>();
+        assert!(steps[0].is_ok() && steps[1].is_ok());
+        assert_eq!(vm.tracer().cycle(), Tracer::MAX_CYCLE);
+        assert!(steps[2].is_err());
+    }
+
+    #[test]
+    fn test_restore_rejects_bad_pages() {
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, vec![], Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let words_per_page = VMState::PAGE_SIZE as usize / WORD_SIZE;
+        let snapshot = |pages| {
+            let snapshot = Snapshot {
+                pc: pc_base,
+                cycle: Tracer::SUBCYCLES_PER_INSN,
+                halted: false,
+                registers: vec![0; VMState::REG_COUNT],
+                pages,
+            };
+            rkyv::to_bytes::<rkyv::rancor::Error>(&snapshot)
+                .unwrap()
+                .into_vec()
+        };
+        let ram = CENO_PLATFORM.ram.start;
+
+        assert!(
+            vm.restore(&snapshot(vec![(ram, vec![1; words_per_page])]))
+                .is_ok()
+        );
+        assert_eq!(vm.peek_memory(ram.into()), 1);
+        assert!(
+            vm.restore(&snapshot(vec![(ram + 4, vec![1; words_per_page])]))
+                .is_err()
+        );
+        assert!(
+            vm.restore(&snapshot(vec![(ram, vec![1; words_per_page + 1])]))
+                .is_err()
+        );
+    }
+}
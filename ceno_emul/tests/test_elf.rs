@@ -1,5 +1,8 @@
 use anyhow::Result;
-use ceno_emul::{ByteAddr, CENO_PLATFORM, EmuContext, InsnKind, Platform, StepRecord, VMState};
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM, EmuContext, InsnKind, Platform, StepRecord, Symbols, UNKNOWN_REGION,
+    VMState, profile_with_symbols, read_slice_from_guest,
+};
 
 #[test]
 fn test_ceno_rt_mini() -> Result<()> {
@@ -9,6 +12,25 @@ fn test_ceno_rt_mini() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ceno_rt_mini_profile() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_mini;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let steps = run(&mut state)?;
+
+    let symbols = Symbols::from_elf(program_elf)?;
+    let histogram = profile_with_symbols(&steps, &symbols);
+    assert_eq!(histogram.total, steps.len() as u64);
+    assert_eq!(histogram.by_region.values().sum::<u64>(), histogram.total);
+    assert!(
+        histogram
+            .by_region
+            .keys()
+            .any(|region| region != UNKNOWN_REGION)
+    );
+    Ok(())
+}
+
 #[test]
 fn test_ceno_rt_panic() -> Result<()> {
     let program_elf = ceno_examples::ceno_rt_panic;
@@ -98,13 +120,5 @@ fn read_all_messages(state: &VMState) -> Vec<Vec<u8>> {
 fn read_message(state: &VMState, word_offset: u32) -> Vec<u8> {
     let out_addr = ByteAddr(INFO_OUT_ADDR).waddr() + word_offset;
     let byte_len = state.peek_memory(out_addr);
-    let word_len_up = byte_len.div_ceil(4);
-
-    let mut info_out = Vec::with_capacity(WORD_SIZE * word_len_up as usize);
-    for i in 1..1 + word_len_up {
-        let value = state.peek_memory(out_addr + i);
-        info_out.extend_from_slice(&value.to_le_bytes());
-    }
-    info_out.truncate(byte_len as usize);
-    info_out
+    read_slice_from_guest(state, (out_addr + 1_usize).into(), byte_len as usize).unwrap()
 }
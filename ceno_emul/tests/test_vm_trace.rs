@@ -3,8 +3,10 @@ use anyhow::Result;
 use std::collections::{BTreeMap, HashMap};
 
 use ceno_emul::{
-    CENO_PLATFORM, Cycle, EmuContext, InsnKind, Platform, Program, StepRecord, Tracer, VMState,
-    WORD_SIZE, WordAddr,
+    ByteAddr, CENO_PLATFORM, Change, Cycle, DecodedInstruction, EmuContext, FusedKind, InsnKind,
+    Platform, Program, StepRecord, StopReason, Symbols, Tracer, UNKNOWN_REGION, VMState, WORD_SIZE,
+    WordAddr, encode_rv32, encode_rvc_program, fuse_steps, is_soft_float_routine, merge_shards,
+    profile, random_word, replay_shards, run_shard, shard_snapshots, soft_float_report,
 };
 
 #[test]
@@ -44,6 +46,27 @@ fn test_vm_trace() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_run_untraced() -> Result<()> {
+    let program = program_from(&PROGRAM_FIBONACCI_20);
+    let mut traced = VMState::new(CENO_PLATFORM, program.clone());
+    let steps = run(&mut traced)?;
+
+    let mut untraced = VMState::new(CENO_PLATFORM, program);
+    assert_eq!(untraced.run_untraced(10)?, 10);
+    assert!(!untraced.halted());
+    assert_eq!(untraced.run_untraced(usize::MAX)?, steps.len() - 10);
+    assert!(untraced.halted());
+
+    let (x1, x2, x3) = expected_fibonacci_20();
+    assert_eq!(untraced.peek_register(1), x1);
+    assert_eq!(untraced.peek_register(2), x2);
+    assert_eq!(untraced.peek_register(3), x3);
+    // Nothing was recorded.
+    assert_eq!(untraced.tracer().final_accesses().len(), 0);
+    Ok(())
+}
+
 #[test]
 fn test_empty_program() -> Result<()> {
     let empty_program = Program::new(
@@ -58,6 +81,623 @@ fn test_empty_program() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_breakpoint() -> Result<()> {
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        PROGRAM_FIBONACCI_20.to_vec(),
+        PROGRAM_FIBONACCI_20
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
+                    insn,
+                )
+            })
+            .collect(),
+    );
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    // The BNE at the end of the loop.
+    let bne_pc = ByteAddr(CENO_PLATFORM.pc_base() + 5 * WORD_SIZE as u32);
+    ctx.add_breakpoint(bne_pc);
+
+    let mut stops = 0;
+    let mut steps = vec![];
+    loop {
+        steps.extend(ctx.iter_until_stop().collect::<Result<Vec<_>>>()?);
+        if ctx.halted() {
+            break;
+        }
+        assert_eq!(ctx.take_stop_reasons(), vec![StopReason::Breakpoint(
+            bne_pc
+        )]);
+        assert_eq!(steps.len(), 2 + 4 * stops + 3);
+        stops += 1;
+    }
+    assert_eq!(stops, 10);
+
+    let ops: Vec<InsnKind> = steps.iter().map(|step| step.insn().codes().kind).collect();
+    assert_eq!(ops, expected_ops_fibonacci_20());
+    Ok(())
+}
+
+#[test]
+fn test_watchpoint() -> Result<()> {
+    let ram = |offset: u32| WordAddr::from(CENO_PLATFORM.ram.start + offset);
+    let blocks = [3, 5, 7];
+    let mut vm = VMState::new(CENO_PLATFORM, program_from(&program_double_blocks(3)));
+    for (k, &block) in blocks.iter().enumerate() {
+        vm.init_memory(ram(4 + 4 * k as u32), block);
+    }
+    let expected = run(&mut vm.clone())?;
+
+    // Watch the writes to the second output.
+    let output = ram(64 + 4);
+    let mut watched = vm.clone();
+    watched.add_watchpoint(output..output + 1u32, false, true);
+    let mut steps = watched.iter_until_stop().collect::<Result<Vec<_>>>()?;
+    assert!(!watched.halted());
+    let last = steps.last().unwrap();
+    let sw_pc = ByteAddr(CENO_PLATFORM.pc_base() + 7 * WORD_SIZE as u32);
+    assert_eq!(last.pc().before, sw_pc);
+    assert_eq!(watched.take_stop_reasons(), vec![StopReason::Watchpoint {
+        pc: sw_pc,
+        addr: output,
+        value: Change::new(0, 2 * blocks[1]),
+    }]);
+
+    // Resuming runs to the end.
+    steps.extend(watched.iter_until_stop().collect::<Result<Vec<_>>>()?);
+    assert!(watched.halted());
+    assert_eq!(steps, expected);
+
+    // Without stopping, the hits are not kept.
+    vm.add_watchpoint(output..output + 1u32, true, true);
+    run(&mut vm)?;
+    assert_eq!(vm.take_stop_reasons(), vec![]);
+    Ok(())
+}
+
+#[test]
+fn test_merge_shards() -> Result<()> {
+    let ram = |offset: u32| WordAddr::from(CENO_PLATFORM.ram.start + offset);
+    let blocks = [3, 5, 7];
+    let mut vm = VMState::new(CENO_PLATFORM, program_from(&program_double_blocks(3)));
+    for (k, &block) in blocks.iter().enumerate() {
+        vm.init_memory(ram(4 + 4 * k as u32), block);
+    }
+    let expected = run(&mut vm.clone())?;
+
+    // Each shard continues from the pc, registers and memory left by the previous one.
+    let mut shards = vec![];
+    while !vm.halted() {
+        shards.push(run_shard(&mut vm, 10)?);
+    }
+    assert_eq!(shards.len(), expected.len().div_ceil(10));
+    let merged = merge_shards(shards)?;
+    assert_eq!(merged.steps, expected);
+    for k in 0..blocks.len() as u32 {
+        assert_eq!(vm.peek_memory(ram(64 + 4 * k)), 2 * blocks[k as usize]);
+    }
+
+    let mut latest = HashMap::new();
+    for step in &merged.steps {
+        for op in [step.rs1(), step.rs2()].into_iter().flatten() {
+            latest.insert(op.addr, op.cycle);
+        }
+        for (addr, cycle) in [step.rd(), step.memory_op()]
+            .into_iter()
+            .flatten()
+            .map(|op| (op.addr, op.cycle))
+        {
+            latest.insert(addr, cycle);
+        }
+    }
+    assert_eq!(merged.final_accesses, latest);
+
+    let columns = merged.export_mc();
+    assert_eq!(columns.cycle.len(), columns.len());
+    assert_eq!(columns.prev_cycle.len(), columns.len());
+    assert!(columns.cycle.is_sorted());
+    assert!(
+        columns
+            .prev_cycle
+            .iter()
+            .zip(&columns.cycle)
+            .all(|(prev, cycle)| prev < cycle)
+    );
+    let mut csv = Vec::new();
+    columns.write_csv(&mut csv)?;
+    let csv = String::from_utf8(csv)?;
+    assert_eq!(csv.lines().count(), columns.len() + 1);
+    assert!(csv.starts_with("addr,cycle,prev_cycle,value,is_write,width\n"));
+    Ok(())
+}
+
+#[test]
+fn test_replay_shards() -> Result<()> {
+    let ram = |offset: u32| WordAddr::from(CENO_PLATFORM.ram.start + offset);
+    let mut vm = VMState::new(CENO_PLATFORM, program_from(&program_double_blocks(3)));
+    for k in 0..3 {
+        vm.init_memory(ram(4 + 4 * k), 3 + 2 * k);
+    }
+    let expected = merge_shards(vec![run_shard(&mut vm.clone(), usize::MAX)?])?;
+
+    // The shards replayed in parallel from their snapshots link up into the single run.
+    let snapshots = shard_snapshots(&mut vm, 10)?;
+    assert_eq!(snapshots.len(), expected.steps.len().div_ceil(10));
+    let merged = merge_shards(replay_shards(&vm, &snapshots, 10)?)?;
+    assert_eq!(merged.steps, expected.steps);
+    assert_eq!(merged.final_accesses, expected.final_accesses);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot() -> Result<()> {
+    let program = program_from(&program_double_block());
+    let ram = |offset: u32| WordAddr::from(CENO_PLATFORM.ram.start + offset);
+    let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+    vm.init_memory(ram(0), 1);
+    vm.init_memory(ram(8), 21);
+
+    let steps = vm.iter_until_halt().take(5).collect::<Result<Vec<_>>>()?;
+    let snapshot = vm.snapshot()?;
+    let rest = run(&mut vm)?;
+
+    // A fresh VM of the same program continues from the snapshot, at the same cycle.
+    let mut restored = VMState::new(CENO_PLATFORM, program);
+    restored.restore(&snapshot)?;
+    assert_eq!(
+        restored.tracer().cycle(),
+        steps[4].cycle() + Tracer::SUBCYCLES_PER_INSN
+    );
+    let restored_rest = run(&mut restored)?;
+    assert_eq!(
+        rest.iter()
+            .map(|step| (step.cycle(), step.pc()))
+            .collect::<Vec<_>>(),
+        restored_rest
+            .iter()
+            .map(|step| (step.cycle(), step.pc()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(restored.peek_memory(ram(68)), 42);
+    assert_eq!(restored.peek_memory(ram(0)), 2);
+    for idx in 0..VMState::REG_COUNT {
+        assert_eq!(restored.peek_register(idx), vm.peek_register(idx));
+    }
+
+    // Only the written page is saved.
+    assert!(snapshot.len() < VMState::PAGE_SIZE as usize * 2);
+    assert!(restored.restore(&snapshot[..snapshot.len() / 2]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_merge_unchained_shards() -> Result<()> {
+    let vm = VMState::new(CENO_PLATFORM, program_from(&program_double_blocks(3)));
+
+    // The second shard starts over from the initial state instead of the end of the first one.
+    let shards = vec![
+        run_shard(&mut vm.clone(), 10)?,
+        run_shard(&mut vm.clone(), 10)?,
+    ];
+    let res = merge_shards(shards);
+    assert!(matches!(res, Err(e) if e.to_string().contains("shard 1 does not continue")));
+    Ok(())
+}
+
+#[test]
+fn test_fuse_auipc_jalr() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&[
+        encode_rv32(AUIPC, 0, 0, 1, 0), // x1 = pc
+        encode_rv32(JALR, 1, 0, 1, 12), // call pc + 12
+        encode_rv32(EANY, 0, 0, 0, 0),  // skipped
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(ADDI, 0, 0, Platform::reg_arg0() as u32, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+    assert_eq!(steps.len(), 5);
+
+    let fused = fuse_steps(steps.clone(), &FusedKind::ALL);
+    assert_eq!(fused.num_fused(), 1);
+    let (kind, call) = &fused.steps[0];
+    assert_eq!(*kind, Some(FusedKind::AuipcJalr));
+    let pc = steps[0].pc().before;
+    assert_eq!(call.pc().after, pc + 3 * WORD_SIZE);
+    assert_eq!(call.rd().unwrap().value.after, (pc + 2 * WORD_SIZE).0);
+    assert_eq!(fused.steps[1].1, steps[2]);
+
+    // A jalr that rounds its target down is not fused.
+    let auipc = DecodedInstruction::new(encode_rv32(AUIPC, 0, 0, 1, 0));
+    let odd_jalr = DecodedInstruction::new(encode_rv32(JALR, 1, 0, 1, 13));
+    assert_eq!(FusedKind::detect(&auipc, &odd_jalr), None);
+    Ok(())
+}
+
+#[test]
+fn test_fuse_steps() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&[
+        encode_rv32(LUI, 0, 0, 1, 0x12345000),     // x1 = 0x12345000
+        encode_rv32(ADDI, 1, 0, 1, -16i32 as u32), // x1 -= 16
+        encode_rv32(ADDI, 0, 0, 2, 3),             // x2 = 3
+        encode_rv32(ADDI, 2, 0, 2, -1i32 as u32),  // loop: x2 -= 1
+        encode_rv32(SLTI, 2, 0, 3, 1),             // x3 = x2 < 1
+        encode_rv32(BEQ, 3, 0, 0, -8i32 as u32),   // if x3 == 0 { goto loop }
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(ADDI, 0, 0, Platform::reg_arg0() as u32, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+    assert_eq!(steps.len(), 15);
+
+    let fused = fuse_steps(steps.clone(), &FusedKind::ALL);
+    assert_eq!(fused.num_fused(), 1);
+    assert_eq!(fused.steps.len(), 14);
+    let kinds = fused
+        .steps
+        .iter()
+        .map(|(kind, _)| *kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds[0], Some(FusedKind::LuiAddi));
+    assert_eq!(kinds[1], None);
+    // A comparison and a branch on it have no fused circuit.
+    assert_eq!(kinds[3], None);
+
+    // The constant is written at once, over two instructions.
+    let (_, li) = &fused.steps[0];
+    assert_eq!(li.pc().after, li.pc().before + 2 * WORD_SIZE);
+    assert_eq!(li.rd().unwrap().value.after, 0x12345000 - 16);
+    assert_eq!(
+        steps[2].cycle(),
+        li.cycle() + 2 * Tracer::SUBCYCLES_PER_INSN
+    );
+
+    // Every access follows the latest access that was kept.
+    let mut latest = HashMap::new();
+    for (_, step) in &fused.steps {
+        let reads = [step.rs1(), step.rs2()]
+            .into_iter()
+            .flatten()
+            .map(|op| (op.addr, op.previous_cycle, op.cycle));
+        let writes = [step.rd(), step.memory_op()]
+            .into_iter()
+            .flatten()
+            .map(|op| (op.addr, op.previous_cycle, op.cycle));
+        for (addr, previous_cycle, cycle) in reads.chain(writes) {
+            if let Some(&latest_cycle) = latest.get(&addr) {
+                assert_eq!(previous_cycle, latest_cycle);
+            }
+            latest.insert(addr, cycle);
+        }
+    }
+    assert_eq!(fused.final_accesses, latest);
+
+    // Nothing is fused without patterns.
+    assert_eq!(fuse_steps(steps, &[]).num_fused(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_profile() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&PROGRAM_FIBONACCI_20);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+
+    let histogram = profile(&steps);
+    assert_eq!(histogram.total, steps.len() as u64);
+    assert_eq!(
+        histogram.by_kind,
+        BTreeMap::from([(ADDI, 12), (ADD, 20), (BNE, 10), (EANY, 1)])
+    );
+    assert_eq!(
+        histogram.by_region,
+        BTreeMap::from([(UNKNOWN_REGION.to_string(), steps.len() as u64)])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_soft_float_report() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&[
+        encode_rv32(JAL, 0, 0, 1, 16), // call __mulsf3
+        encode_rv32(JAL, 0, 0, 1, 12), // call __mulsf3
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        // __mulsf3
+        encode_rv32(ADDI, 2, 0, 2, 1),
+        encode_rv32(JALR, 1, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+
+    let base = CENO_PLATFORM.pc_base();
+    let mut symbols = Symbols::default();
+    symbols.insert(base, base + 16, "main");
+    symbols.insert(base + 16, base + 24, "__mulsf3");
+    let report = soft_float_report(&steps, &symbols);
+    assert_eq!(report.total, 8);
+    assert_eq!(report.soft_float_steps(), 4);
+    assert_eq!(report.share(), 0.5);
+    assert_eq!(
+        report.calls_by_routine,
+        BTreeMap::from([("__mulsf3".to_string(), 2)])
+    );
+
+    // Without symbols, nothing is detected.
+    assert_eq!(
+        soft_float_report(&steps, &Symbols::default()).soft_float_steps(),
+        0
+    );
+
+    for name in [
+        "__addsf3",
+        "__divdf3",
+        "__ledf2",
+        "__unordsf2",
+        "__fixunssfsi",
+        "__floatsidf",
+        "__extendsfdf2",
+        "__powisf2",
+    ] {
+        assert!(is_soft_float_routine(name), "{name}");
+    }
+    for name in ["__muldi3", "__divsi3", "__lshrdi3", "main", "addsf3"] {
+        assert!(!is_soft_float_routine(name), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_fence() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&[
+        encode_rv32(ADDI, 0, 0, 1, 7),
+        0x0ff0_000f, // fence
+        0x0000_100f, // fence.i
+        encode_rv32(ADDI, 1, 0, 1, 1),
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+    assert_eq!(ctx.peek_register(1), 8);
+
+    // A fence only moves to the next instruction.
+    for (step, kind) in steps[1..3].iter().zip([FENCE, FENCE_I]) {
+        assert_eq!(step.insn().codes().kind, kind);
+        assert_eq!(step.pc().after, step.pc().before + WORD_SIZE);
+        assert!(step.rs1().is_none() && step.rs2().is_none() && step.rd().is_none());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ecall_random() -> Result<()> {
+    use InsnKind::*;
+    let (reg_ecall, reg_arg0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let program = program_from(&[
+        encode_rv32(ADDI, 0, 0, reg_ecall, Platform::ecall_random()),
+        encode_rv32(ADDI, 0, 0, reg_arg0, 5),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, reg_arg0, 0, 1, 0),
+        encode_rv32(ADDI, 0, 0, reg_ecall, Platform::ecall_halt()),
+        encode_rv32(ADDI, 0, 0, reg_arg0, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let seed = 0x1234_5678_9abc_def0;
+    let mut ctx = VMState::new(CENO_PLATFORM, program.clone());
+    ctx.set_random_seed(seed);
+    let steps = run(&mut ctx)?;
+    assert_eq!(ctx.peek_register(1), random_word(seed, 5));
+
+    // The ecall reads the function and the index, and writes the word.
+    let ecall = &steps[2];
+    assert_eq!(ecall.rs1().unwrap().value, Platform::ecall_random());
+    assert_eq!(ecall.rs2().unwrap().value, 5);
+    assert_eq!(ecall.rd().unwrap().value.after, random_word(seed, 5));
+    assert_eq!(ecall.pc().after, ecall.pc().before + WORD_SIZE);
+
+    let mut untraced = VMState::new(CENO_PLATFORM, program);
+    untraced.set_random_seed(seed);
+    untraced.run_untraced(usize::MAX)?;
+    assert_eq!(untraced.peek_register(1), random_word(seed, 5));
+    Ok(())
+}
+
+#[test]
+fn test_bitmanip() -> Result<()> {
+    use InsnKind::*;
+    // The encodings of an assembler.
+    assert_eq!(encode_rv32(ANDN, 2, 3, 1, 0), 0x403170b3);
+    assert_eq!(encode_rv32(ORN, 2, 3, 1, 0), 0x403160b3);
+    assert_eq!(encode_rv32(XNOR, 2, 3, 1, 0), 0x403140b3);
+    assert_eq!(encode_rv32(CLZ, 2, 0, 1, 0), 0x60011093);
+    assert_eq!(encode_rv32(CPOP, 2, 0, 1, 0), 0x60211093);
+
+    let program = program_from(&[
+        encode_rv32(LUI, 0, 0, 1, 0x00f0_0000), // x1 = 0x00f0_0000
+        encode_rv32(ADDI, 0, 0, 2, 0x0ff),      // x2 = 0xff
+        encode_rv32(ANDN, 1, 2, 3, 0),          // x3 = x1 & !x2
+        encode_rv32(ORN, 0, 2, 4, 0),           // x4 = !x2
+        encode_rv32(XNOR, 1, 2, 9, 0),          // x9 = !(x1 ^ x2)
+        encode_rv32(CLZ, 1, 0, 6, 0),           // x6 = clz(x1)
+        encode_rv32(CPOP, 1, 0, 7, 0),          // x7 = cpop(x1)
+        encode_rv32(CLZ, 0, 0, 8, 0),           // x8 = clz(0)
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let steps = run(&mut ctx)?;
+    assert_eq!(ctx.peek_register(3), 0x00f0_0000);
+    assert_eq!(ctx.peek_register(4), !0xff);
+    assert_eq!(ctx.peek_register(9), !0x00f0_00ff);
+    assert_eq!(ctx.peek_register(6), 8);
+    assert_eq!(ctx.peek_register(7), 4);
+    assert_eq!(ctx.peek_register(8), 32);
+    assert_eq!(steps[5].insn().codes().kind, CLZ);
+    assert_eq!(steps[6].insn().codes().kind, CPOP);
+    Ok(())
+}
+
+#[test]
+fn test_compressed() -> Result<()> {
+    use InsnKind::*;
+    let program = program_from(&encode_rvc_program(&[
+        0x4501,     // c.li a0, 0
+        0x458d,     // c.li a1, 3
+        0x0001,     // c.nop
+        0x06450513, // loop: addi a0, a0, 100 (spans two words)
+        0x15fd,     // c.addi a1, -1
+        0xfded,     // c.bnez a1, loop
+        0x4281,     // c.li t0, 0
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]));
+
+    // Compressed instructions are illegal unless enabled.
+    let mut ctx = VMState::new(CENO_PLATFORM, program.clone());
+    assert!(matches!(run(&mut ctx), Err(e) if e.to_string().contains("IllegalInstruction")));
+
+    let platform = Platform {
+        rvc: true,
+        ..CENO_PLATFORM
+    };
+    let mut ctx = VMState::new(platform, program);
+    let steps = run(&mut ctx)?;
+    assert_eq!(ctx.peek_register(10), 300);
+    assert_eq!(ctx.peek_register(11), 0);
+
+    // The trace has the 32-bit equivalents, at 2-byte steps.
+    let ops: Vec<InsnKind> = steps.iter().map(|step| step.insn().codes().kind).collect();
+    let body = [ADDI, ADDI, BNE];
+    let expected = [ADDI, ADDI, ADDI]
+        .into_iter()
+        .chain(body.repeat(3))
+        .chain([ADDI, EANY])
+        .collect::<Vec<_>>();
+    assert_eq!(ops, expected);
+    let offsets: Vec<u32> = steps
+        .iter()
+        .map(|step| step.pc().before.0 - CENO_PLATFORM.pc_base())
+        .collect();
+    assert_eq!(&offsets[..7], &[0, 2, 4, 6, 10, 12, 6]);
+    assert_eq!(&offsets[offsets.len() - 2..], &[14, 16]);
+    assert_eq!(steps[3].insn_code(), 0x06450513);
+    assert_eq!(steps[1].insn_code(), encode_rv32(ADDI, 0, 0, 11, 3));
+    Ok(())
+}
+
+fn program_from(insns: &[u32]) -> Program {
+    Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        insns.to_vec(),
+        insns
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
+                    insn,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Read a cursor `k` at the start of RAM, double the block `k` into the output `k`, and
+/// advance the cursor.
+fn program_double_block() -> Vec<u32> {
+    use InsnKind::*;
+    vec![
+        encode_rv32(LUI, 0, 0, 1, CENO_PLATFORM.ram.start), // x1 = RAM
+        encode_rv32(LW, 1, 0, 2, 0),                        // x2 = cursor
+        encode_rv32(SLLI, 2, 0, 3, 2),                      // x3 = 4 * x2
+        encode_rv32(ADD, 3, 1, 3, 0),                       // x3 += x1
+        encode_rv32(LW, 3, 0, 4, 4),                        // x4 = block
+        encode_rv32(ADD, 4, 4, 4, 0),                       // x4 += x4
+        encode_rv32(SW, 3, 4, 0, 64),                       // output = x4
+        encode_rv32(ADDI, 2, 0, 2, 1),                      // x2 += 1
+        encode_rv32(SW, 1, 2, 0, 0),                        // cursor = x2
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(ADDI, 0, 0, Platform::reg_arg0() as u32, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]
+}
+
+/// Double the `count` blocks after a cursor at the start of RAM into the outputs, advancing
+/// the cursor after each block.
+fn program_double_blocks(count: u32) -> Vec<u32> {
+    use InsnKind::*;
+    vec![
+        encode_rv32(LUI, 0, 0, 1, CENO_PLATFORM.ram.start), // x1 = RAM
+        encode_rv32(ADDI, 0, 0, 5, count),                  // x5 = count
+        encode_rv32(LW, 1, 0, 2, 0),                        // loop: x2 = cursor
+        encode_rv32(SLLI, 2, 0, 3, 2),                      // x3 = 4 * x2
+        encode_rv32(ADD, 3, 1, 3, 0),                       // x3 += x1
+        encode_rv32(LW, 3, 0, 4, 4),                        // x4 = block
+        encode_rv32(ADD, 4, 4, 4, 0),                       // x4 += x4
+        encode_rv32(SW, 3, 4, 0, 64),                       // output = x4
+        encode_rv32(ADDI, 2, 0, 2, 1),                      // x2 += 1
+        encode_rv32(SW, 1, 2, 0, 0),                        // cursor = x2
+        encode_rv32(BNE, 2, 5, 0, -32i32 as u32),           // if x2 != x5 { goto loop }
+        encode_rv32(
+            ADDI,
+            0,
+            0,
+            Platform::reg_ecall() as u32,
+            Platform::ecall_halt(),
+        ),
+        encode_rv32(ADDI, 0, 0, Platform::reg_arg0() as u32, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]
+}
+
 fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
     state.iter_until_halt().collect()
 }
@@ -0,0 +1,86 @@
+use std::{fs, process::ExitCode};
+
+use ceno_host::DefaultPcs;
+use ceno_zkvm::scheme::PublicValues;
+use clap::{Parser, ValueEnum};
+use goldilocks::GoldilocksExt2;
+use mpcs::{Basefold, BasefoldBasecodeParams};
+
+/// Verify a proof of a halting execution.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// The path to the verifying key.
+    vk: String,
+
+    /// The path to the proof.
+    proof: String,
+
+    /// The polynomial commitment scheme of the proof.
+    #[arg(long, value_enum, default_value_t = PcsKind::BasefoldRs)]
+    pcs: PcsKind,
+
+    #[arg(long, default_value_t = 0)]
+    exit_code: u32,
+
+    #[arg(long)]
+    init_pc: u32,
+
+    #[arg(long, default_value_t = 0)]
+    init_cycle: u32,
+
+    #[arg(long)]
+    end_pc: u32,
+
+    #[arg(long)]
+    end_cycle: u32,
+
+    /// Public IO words, comma-separated.
+    #[arg(long, value_delimiter = ',')]
+    public_io: Vec<u32>,
+
+    /// Final register values from x0, comma-separated, padded like the register table.
+    #[arg(long, value_delimiter = ',')]
+    final_regs: Vec<u32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum PcsKind {
+    /// Basefold with Reed-Solomon codes.
+    BasefoldRs,
+    /// Basefold with random foldable codes.
+    BasefoldBasecode,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let vk = fs::read(&args.vk).expect("read verifying key");
+    let proof = fs::read(&args.proof).expect("read proof");
+    let public_values = PublicValues::new(
+        args.exit_code,
+        args.init_pc,
+        args.init_cycle,
+        args.end_pc,
+        args.end_cycle,
+        args.public_io,
+        args.final_regs,
+    );
+
+    let result = match args.pcs {
+        PcsKind::BasefoldRs => ceno_host::verify::<DefaultPcs>(&vk, &proof, &public_values),
+        PcsKind::BasefoldBasecode => ceno_host::verify::<
+            Basefold<GoldilocksExt2, BasefoldBasecodeParams>,
+        >(&vk, &proof, &public_values),
+    };
+    match result {
+        Ok(()) => {
+            println!("proof verified");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
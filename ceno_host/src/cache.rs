@@ -0,0 +1,157 @@
+//! An on-disk cache of proofs, to skip proving again an execution that was proven before, e.g.
+//! while iterating on a guest program.
+//!
+//! An entry is keyed by the digests of the program, of its input and of the verifying key. A
+//! new verifying key, for example after a change of the proving parameters, misses the entries
+//! proven with the old one.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use tiny_keccak::{Hasher, Keccak};
+
+/// Changes when the format of the entries changes, so that older entries are not read.
+const CACHE_VERSION: u32 = 1;
+
+type Digest = [u8; 32];
+
+fn digest(bytes: &[u8]) -> Digest {
+    let mut keccak = Keccak::v256();
+    keccak.update(bytes);
+    let mut output = [0; 32];
+    keccak.finalize(&mut output);
+    output
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub program: Digest,
+    pub input: Digest,
+    pub vk: Digest,
+}
+
+impl CacheKey {
+    /// The key of the proof of `program_elf` run on `input`, e.g. [`crate::CenoStdin::to_bytes`],
+    /// with the verifying key encoded by [`crate::encode_vk`].
+    pub fn new(program_elf: &[u8], input: &[u8], vk_bytes: &[u8]) -> Self {
+        CacheKey {
+            program: digest(program_elf),
+            input: digest(input),
+            vk: digest(vk_bytes),
+        }
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keccak = Keccak::v256();
+        keccak.update(&CACHE_VERSION.to_le_bytes());
+        keccak.update(&self.program);
+        keccak.update(&self.input);
+        keccak.update(&self.vk);
+        let mut output = [0u8; 32];
+        keccak.finalize(&mut output);
+        output.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// Proofs encoded by [`crate::encode_proof`], one file per entry in a directory.
+#[derive(Clone, Debug)]
+pub struct ProofCache {
+    dir: PathBuf,
+}
+
+impl ProofCache {
+    /// Open the cache in `dir`, created if it does not exist.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(ProofCache {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{key}.proof"))
+    }
+
+    pub fn get(&self, key: &CacheKey) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)) {
+            Ok(proof) => Ok(Some(proof)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Store `proof`. The entry is written to a temporary file first, so that a reader never
+    /// sees a partial proof.
+    pub fn insert(&self, key: &CacheKey, proof: &[u8]) -> io::Result<()> {
+        let path = self.path(key);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, proof)?;
+        fs::rename(&tmp, &path)
+    }
+
+    pub fn remove(&self, key: &CacheKey) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove all the entries.
+    pub fn clear(&self) -> io::Result<()> {
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "proof") {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The cached proof of `key`, or else the proof made by `prove`, which is then cached.
+    pub fn get_or_prove(
+        &self,
+        key: &CacheKey,
+        prove: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<Vec<u8>> {
+        if let Some(proof) = self.get(key)? {
+            return Ok(proof);
+        }
+        let proof = prove()?;
+        self.insert(key, &proof)?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheKey, ProofCache};
+
+    #[test]
+    fn test_proof_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ProofCache::new(dir.path()).unwrap();
+        let key = CacheKey::new(b"elf", b"input", b"vk");
+
+        let mut proven = 0;
+        let mut prove = || {
+            proven += 1;
+            anyhow::Ok(b"proof".to_vec())
+        };
+        assert_eq!(cache.get_or_prove(&key, &mut prove).unwrap(), b"proof");
+        assert_eq!(cache.get_or_prove(&key, &mut prove).unwrap(), b"proof");
+        assert_eq!(proven, 1);
+
+        // Another verifying key misses the entry.
+        let new_vk = CacheKey::new(b"elf", b"input", b"new vk");
+        assert_ne!(new_vk.to_string(), key.to_string());
+        assert_eq!(cache.get(&new_vk).unwrap(), None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(&key).unwrap(), None);
+        cache.remove(&key).unwrap();
+    }
+}
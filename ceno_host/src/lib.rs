@@ -0,0 +1,214 @@
+//! Entry points for applications that produce or consume Ceno proofs.
+//!
+//! Verifying keys and proofs are exchanged as bytes produced by [`encode_vk`] and
+//! [`encode_proof`], so integrators do not need to depend on the mpcs and zkvm types directly.
+
+use std::{fmt, io};
+
+use ceno_emul::{
+    Histogram, MergedTrace, StepRecord, Symbols, VMState, merge_shards, profile_with_symbols,
+    replay_shards, shard_snapshots,
+};
+use ceno_zkvm::{
+    error::ZKVMError,
+    scheme::{PublicValues, ZKVMProof, constants::TRANSCRIPT_LABEL, verifier::ZKVMVerifier},
+    structs::ZKVMVerifyingKey,
+};
+use goldilocks::GoldilocksExt2;
+use mpcs::{BasefoldDefault, PolynomialCommitmentScheme};
+use transcript::Transcript;
+
+mod cache;
+pub use cache::{CacheKey, ProofCache};
+
+mod stdin;
+pub use stdin::{CenoStdin, Encode, INPUT_ADDR};
+
+mod stdout;
+pub use stdout::{GuestStdout, INFO_OUT_ADDR, collect_stdout};
+
+type E = GoldilocksExt2;
+
+/// The commitment scheme of proofs unless the prover chose another one.
+pub type DefaultPcs = BasefoldDefault<E>;
+
+#[derive(Debug)]
+pub enum VerifyError {
+    InvalidVerifyingKey(serde_json::Error),
+    InvalidProof(serde_json::Error),
+    /// The proof was generated for other public values than the expected ones.
+    PublicValuesMismatch,
+    /// The proof does not verify, for this reason.
+    Rejected(ZKVMError),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidVerifyingKey(e) => write!(f, "invalid verifying key: {e}"),
+            Self::InvalidProof(e) => write!(f, "invalid proof: {e}"),
+            Self::PublicValuesMismatch => write!(f, "public values do not match the proof"),
+            Self::Rejected(e) => write!(f, "proof rejected: {e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+pub fn encode_vk<PCS: PolynomialCommitmentScheme<E>>(vk: &ZKVMVerifyingKey<E, PCS>) -> Vec<u8> {
+    serde_json::to_vec(vk).expect("verifying key serialization cannot fail")
+}
+
+pub fn encode_proof<PCS: PolynomialCommitmentScheme<E>>(proof: &ZKVMProof<E, PCS>) -> Vec<u8> {
+    serde_json::to_vec(proof).expect("proof serialization cannot fail")
+}
+
+/// Verify a proof of a halting execution against the expected public values. The proof and
+/// the verifying key must have been produced with the commitment scheme `PCS`, e.g.
+/// [`DefaultPcs`].
+pub fn verify<PCS: PolynomialCommitmentScheme<E>>(
+    vk_bytes: &[u8],
+    proof_bytes: &[u8],
+    public_values: &PublicValues<u32>,
+) -> Result<(), VerifyError> {
+    let vk: ZKVMVerifyingKey<E, PCS> =
+        serde_json::from_slice(vk_bytes).map_err(VerifyError::InvalidVerifyingKey)?;
+    let proof: ZKVMProof<E, PCS> =
+        serde_json::from_slice(proof_bytes).map_err(VerifyError::InvalidProof)?;
+
+    if proof.raw_pi != public_values.to_vec::<E>() {
+        return Err(VerifyError::PublicValuesMismatch);
+    }
+
+    let verifier = ZKVMVerifier::new(vk);
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
+    match verifier.verify_proof(proof, transcript) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(VerifyError::Rejected(ZKVMError::VerifyError(
+            "verifier returned false".into(),
+        ))),
+        Err(e) => Err(VerifyError::Rejected(e)),
+    }
+}
+
+/// Emulate `vm` until it halts, in shards of at most `shard_steps` steps, and merge them into
+/// one trace to prove. A first run takes a snapshot at the start of each shard, from which the
+/// shards are then traced in parallel.
+pub fn emulate_shards(vm: &mut VMState, shard_steps: usize) -> anyhow::Result<MergedTrace> {
+    let snapshots = shard_snapshots(vm, shard_steps)?;
+    merge_shards(replay_shards(vm, &snapshots, shard_steps)?)
+}
+
+/// Profile the steps of an execution of `elf` by instruction kind and by function, to see
+/// where the proving cost concentrates.
+pub fn profile_elf(elf: &[u8], steps: &[StepRecord]) -> anyhow::Result<Histogram> {
+    Ok(profile_with_symbols(steps, &Symbols::from_elf(elf)?))
+}
+
+/// Write the profile of [`profile_elf`] to `out` as a table.
+pub fn dump_profile(
+    elf: &[u8],
+    steps: &[StepRecord],
+    out: &mut impl io::Write,
+) -> anyhow::Result<()> {
+    write!(out, "{}", profile_elf(elf, steps)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use ceno_emul::{CENO_PLATFORM, InsnKind, Program, StepRecord, VMState, encode_rv32};
+    use ceno_zkvm::{
+        instructions::riscv::ecall::HaltInstruction,
+        scheme::{
+            PublicValues, ZKVMProof,
+            constants::{MAX_NUM_VARIABLES, TRANSCRIPT_LABEL},
+            prover::ZKVMProver,
+        },
+        structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+        tables::{ProgramTableCircuit, U16TableCircuit},
+    };
+    use ff::Field;
+    use mpcs::PolynomialCommitmentScheme;
+    use transcript::Transcript;
+
+    use super::{DefaultPcs, E, VerifyError, encode_proof, encode_vk, verify};
+
+    /// The verifying key and a proof of a program that halts right away, with its public
+    /// values.
+    fn halt_proof() -> (Vec<u8>, ZKVMProof<E, DefaultPcs>, PublicValues<u32>) {
+        let pc = CENO_PLATFORM.pc_base();
+        let halt = encode_rv32(InsnKind::EANY, 0, 0, 0, 0);
+        let program = Program::new(pc, pc, vec![halt], BTreeMap::from([(pc, halt)]));
+
+        let param = DefaultPcs::setup(1 << MAX_NUM_VARIABLES).unwrap();
+        let (pp, vp) = DefaultPcs::trim(param, 1 << MAX_NUM_VARIABLES).unwrap();
+        let mut cs = ZKVMConstraintSystem::default();
+        let halt_config = cs.register_opcode_circuit::<HaltInstruction<E>>();
+        let u16_config = cs.register_table_circuit::<U16TableCircuit<E>>();
+        let prog_config = cs.register_table_circuit::<ProgramTableCircuit<E>>();
+        let mut fixed_traces = ZKVMFixedTraces::default();
+        fixed_traces.register_opcode_circuit::<HaltInstruction<E>>(&cs);
+        fixed_traces.register_table_circuit::<U16TableCircuit<E>>(&cs, &u16_config, &());
+        fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(&cs, &prog_config, &program);
+        let pk = cs
+            .clone()
+            .key_gen::<DefaultPcs>(pp, vp, fixed_traces)
+            .unwrap();
+        let vk_bytes = encode_vk(&pk.get_vk());
+
+        let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+        let records = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .unwrap();
+        let mut witnesses = ZKVMWitnesses::default();
+        witnesses
+            .assign_opcode_circuit::<HaltInstruction<E>>(&cs, &halt_config, records)
+            .unwrap();
+        witnesses.finalize_lk_multiplicities();
+        witnesses
+            .assign_table_circuit::<U16TableCircuit<E>>(&cs, &u16_config, &())
+            .unwrap();
+        witnesses
+            .assign_table_circuit::<ProgramTableCircuit<E>>(&cs, &prog_config, &program)
+            .unwrap();
+
+        let pv = PublicValues::new(0, 0, 0, 0, 0, vec![0], vec![]);
+        let proof = ZKVMProver::new(pk)
+            .create_proof(witnesses, pv.clone(), Transcript::new(TRANSCRIPT_LABEL))
+            .unwrap();
+        (vk_bytes, proof, pv)
+    }
+
+    #[test]
+    fn test_verify() {
+        let (vk_bytes, proof, pv) = halt_proof();
+        verify::<DefaultPcs>(&vk_bytes, &encode_proof(&proof), &pv).unwrap();
+
+        let other_pv = PublicValues::new(1, 0, 0, 0, 0, vec![0], vec![]);
+        assert!(matches!(
+            verify::<DefaultPcs>(&vk_bytes, &encode_proof(&proof), &other_pv),
+            Err(VerifyError::PublicValuesMismatch)
+        ));
+
+        // The evaluations of the public values are not the ones of the public values.
+        let mut tampered = proof;
+        tampered.pi_evals[0] += E::ONE;
+        assert!(matches!(
+            verify::<DefaultPcs>(&vk_bytes, &encode_proof(&tampered), &pv),
+            Err(VerifyError::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_malformed_input() {
+        let pv = PublicValues::new(0, 0, 0, 0, 0, vec![], vec![]);
+        assert!(matches!(
+            verify::<DefaultPcs>(b"", b"", &pv),
+            Err(VerifyError::InvalidVerifyingKey(_))
+        ));
+    }
+}
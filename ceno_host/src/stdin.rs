@@ -0,0 +1,209 @@
+//! The input of a guest program, read by the guest with `ceno_rt::stdin`.
+//!
+//! The input is written to memory at [`INPUT_ADDR`] as the byte length of the stream followed
+//! by frames. A frame is its byte length as 8 bytes followed by its bytes, padded to 8 bytes, so
+//! that the bytes of every frame are 8-byte aligned in the guest memory.
+
+use anyhow::Result;
+use ceno_emul::{ByteAddr, VMState, Word, WordAddr, write_slice_to_guest};
+
+/// Where the guest finds its input. This must match `ceno_rt::INPUT_ADDR`.
+pub const INPUT_ADDR: u32 = 0xD000_0000;
+
+const ALIGN: usize = 8;
+
+/// A value that can be written to the input of a guest, as decoded by the `Decode` trait of
+/// `ceno_rt`.
+pub trait Encode {
+    fn encode(&self, stdin: &mut CenoStdin);
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CenoStdin {
+    /// The frames, without the header of the stream.
+    bytes: Vec<u8>,
+}
+
+impl CenoStdin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `bytes` as one frame, read by the guest with `read_slice`.
+    pub fn write_slice(&mut self, bytes: &[u8]) -> &mut Self {
+        self.bytes
+            .extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        self.bytes.extend_from_slice(bytes);
+        self.bytes
+            .resize(self.bytes.len().next_multiple_of(ALIGN), 0);
+        self
+    }
+
+    /// Write `value`, read by the guest with `read::<T>`.
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.encode(self);
+        self
+    }
+
+    /// The stream in bytes, as laid out from [`INPUT_ADDR`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut stream = Vec::with_capacity(ALIGN + self.bytes.len());
+        stream.extend_from_slice(&(self.bytes.len() as u64).to_le_bytes());
+        stream.extend_from_slice(&self.bytes);
+        stream
+    }
+
+    /// The memory initialization of the input, e.g. for `VMState::init_memory`.
+    pub fn to_words(&self) -> Vec<(WordAddr, Word)> {
+        let start = ByteAddr(INPUT_ADDR).waddr();
+        self.to_bytes()
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(i, word)| (start + i, Word::from_le_bytes(word.try_into().unwrap())))
+            .collect()
+    }
+
+    /// Write the input to the memory of `vm`, before it runs the guest.
+    pub fn write_to_guest(&self, vm: &mut VMState) -> Result<()> {
+        write_slice_to_guest(vm, ByteAddr(INPUT_ADDR), &self.to_bytes())
+    }
+}
+
+macro_rules! impl_encode_int {
+    ($($t:ty),*) => {
+        $(impl Encode for $t {
+            fn encode(&self, stdin: &mut CenoStdin) {
+                stdin.write_slice(&self.to_le_bytes());
+            }
+        })*
+    };
+}
+
+impl_encode_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Encode for bool {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        (*self as u8).encode(stdin);
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        stdin.write_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        self.as_str().encode(stdin);
+    }
+}
+
+/// The length as a `u32`, then the items.
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        (self.len() as u32).encode(stdin);
+        self.iter().for_each(|item| item.encode(stdin));
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        self.as_slice().encode(stdin);
+    }
+}
+
+/// Whether there is a value as a `bool`, then the value.
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        self.is_some().encode(stdin);
+        if let Some(value) = self {
+            value.encode(stdin);
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, stdin: &mut CenoStdin) {
+        (**self).encode(stdin);
+    }
+}
+
+macro_rules! impl_encode_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, stdin: &mut CenoStdin) {
+                let ($($name,)+) = self;
+                $($name.encode(stdin);)+
+            }
+        }
+    };
+}
+
+impl_encode_tuple!(A);
+impl_encode_tuple!(A, B);
+impl_encode_tuple!(A, B, C);
+impl_encode_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::{CenoStdin, INPUT_ADDR};
+    use ceno_emul::{ByteAddr, CENO_PLATFORM, Platform, StepRecord, VMState, write_slice_to_guest};
+
+    #[test]
+    fn test_stdin_layout() {
+        let mut stdin = CenoStdin::new();
+        stdin.write_slice(b"abc").write(&(7u32, Some("xy")));
+        let bytes = stdin.to_bytes();
+
+        // Every frame starts and ends 8-byte aligned.
+        assert_eq!(bytes.len() % 8, 0);
+        let frames = [&b"abc"[..], &7u32.to_le_bytes(), &[1], b"xy"];
+        assert_eq!(
+            u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize,
+            bytes.len() - 8
+        );
+        let mut offset = 8;
+        for frame in frames {
+            let len = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize;
+            assert_eq!(&bytes[offset + 8..offset + 8 + len], frame);
+            offset += 8 + len.next_multiple_of(8);
+        }
+        assert_eq!(offset, bytes.len());
+
+        let words = stdin.to_words();
+        assert_eq!(words.len() * 4, bytes.len());
+        assert_eq!(words[0].0, ByteAddr(INPUT_ADDR).waddr());
+        assert_eq!(words[2].1, 3);
+    }
+
+    /// Run the guest that checks the values it reads, and return its exit code.
+    fn run_stdin_guest(input: &[u8]) -> u32 {
+        let mut vm = VMState::new_from_elf(CENO_PLATFORM, ceno_examples::ceno_rt_stdin).unwrap();
+        write_slice_to_guest(&mut vm, ByteAddr(INPUT_ADDR), input).unwrap();
+        let steps = vm
+            .iter_until_halt()
+            .collect::<anyhow::Result<Vec<StepRecord>>>()
+            .unwrap();
+        let last = steps.last().unwrap();
+        assert_eq!(last.rs1().unwrap().value, Platform::ecall_halt());
+        last.rs2().unwrap().value
+    }
+
+    #[test]
+    fn test_stdin_guest() {
+        let mut stdin = CenoStdin::new();
+        stdin
+            .write_slice(b"raw")
+            .write(&(vec![1u32, 2, 3], "ceno".to_string()))
+            .write(&Some(u64::MAX))
+            .write(&None::<i8>);
+        assert_eq!(run_stdin_guest(&stdin.to_bytes()), 0);
+
+        // A frame longer than the stream halts the guest with an error.
+        let mut bytes = stdin.to_bytes();
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(run_stdin_guest(&bytes), 1);
+    }
+}
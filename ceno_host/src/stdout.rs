@@ -0,0 +1,110 @@
+//! The output of a guest program, written by the guest with `ceno_rt::print!`.
+//!
+//! The guest writes frames to memory from [`INFO_OUT_ADDR`], one per string it formats: the byte
+//! length of the frame as a word, then its bytes, padded to a word. The output ends at the first
+//! empty frame, i.e. at memory that the guest did not write.
+
+use std::io::{self, Read};
+
+use anyhow::{Result, anyhow};
+use ceno_emul::{ByteAddr, VMState, WORD_SIZE, WordAddr, read_slice_from_guest};
+
+/// Where the guest writes its output. This must match `ceno_rt::INFO_OUT_ADDR`.
+pub const INFO_OUT_ADDR: u32 = 0xC000_0000;
+
+/// The output of the guest of a VM, read as the stream of the bytes of its frames, e.g. to pipe
+/// it with `io::copy`.
+pub struct GuestStdout<'a> {
+    vm: &'a VMState,
+    /// The length word of the next frame.
+    next: WordAddr,
+    frame: Vec<u8>,
+    /// The bytes of `frame` read so far.
+    read: usize,
+}
+
+impl<'a> GuestStdout<'a> {
+    pub fn new(vm: &'a VMState) -> Self {
+        GuestStdout {
+            vm,
+            next: ByteAddr(INFO_OUT_ADDR).waddr(),
+            frame: vec![],
+            read: 0,
+        }
+    }
+
+    /// The next frame, or `None` after the last one.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        let len = self.vm.peek_memory(self.next) as usize;
+        if len == 0 {
+            return Ok(None);
+        }
+        let bytes = read_slice_from_guest(self.vm, (self.next + 1_usize).into(), len)?;
+        self.next += 1 + len.div_ceil(WORD_SIZE);
+        Ok(Some(bytes))
+    }
+}
+
+impl Read for GuestStdout<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.read == self.frame.len() {
+            match self.next_frame().map_err(io::Error::other)? {
+                Some(frame) => {
+                    self.frame = frame;
+                    self.read = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.frame.len() - self.read);
+        buf[..len].copy_from_slice(&self.frame[self.read..self.read + len]);
+        self.read += len;
+        Ok(len)
+    }
+}
+
+/// The output of the guest of `vm` decoded as UTF-8, e.g. to assert on what it printed.
+pub fn collect_stdout(vm: &VMState) -> Result<String> {
+    let mut bytes = vec![];
+    GuestStdout::new(vm).read_to_end(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|e| anyhow!("the guest output is not UTF-8: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use ceno_emul::{CENO_PLATFORM, StepRecord, VMState};
+
+    use super::{GuestStdout, collect_stdout};
+
+    #[test]
+    fn test_guest_stdout() {
+        let mut vm = VMState::new_from_elf(CENO_PLATFORM, ceno_examples::ceno_rt_io).unwrap();
+        vm.iter_until_halt()
+            .collect::<anyhow::Result<Vec<StepRecord>>>()
+            .unwrap();
+
+        let mut stdout = GuestStdout::new(&vm);
+        assert_eq!(
+            stdout.next_frame().unwrap().unwrap(),
+            "📜📜📜 Hello, World!\n".as_bytes()
+        );
+        // The rest of the stream, in small reads.
+        let mut rest = vec![];
+        let mut buf = [0; 3];
+        loop {
+            let len = stdout.read(&mut buf).unwrap();
+            if len == 0 {
+                break;
+            }
+            rest.extend_from_slice(&buf[..len]);
+        }
+        assert_eq!(rest, "🌏🌍🌎\n".as_bytes());
+
+        assert_eq!(
+            collect_stdout(&vm).unwrap(),
+            "📜📜📜 Hello, World!\n🌏🌍🌎\n"
+        );
+    }
+}
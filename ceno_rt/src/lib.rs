@@ -3,11 +3,15 @@
 
 use core::arch::{asm, global_asm};
 
+extern crate alloc;
+
 mod allocator;
 
 mod io;
 pub use io::info_out;
 
+pub mod stdin;
+
 mod params;
 pub use params::*;
 
@@ -1,3 +1,6 @@
 pub const WORD_SIZE: usize = 4;
 
 pub const INFO_OUT_ADDR: u32 = 0xC000_0000;
+
+/// Where the host writes the input of the program, see [`crate::stdin`].
+pub const INPUT_ADDR: u32 = 0xD000_0000;
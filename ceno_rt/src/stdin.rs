@@ -0,0 +1,146 @@
+//! The input of the program, as written by `ceno_host::CenoStdin`.
+//!
+//! At [`INPUT_ADDR`] there is the byte length of the stream followed by frames. A frame is its
+//! byte length as 8 bytes followed by its bytes, padded to 8 bytes. A frame that would go past
+//! the end of the stream halts the program.
+
+use crate::INPUT_ADDR;
+use alloc::{string::String, vec::Vec};
+use core::{cell::Cell, slice};
+
+const ALIGN: usize = 8;
+
+static STDIN: Stdin = Stdin::new(INPUT_ADDR);
+
+pub fn stdin() -> &'static Stdin {
+    &STDIN
+}
+
+/// Read the next value of the input.
+pub fn read<T: Decode>() -> T {
+    stdin().read()
+}
+
+/// Read the next frame of the input.
+pub fn read_slice() -> &'static [u8] {
+    stdin().read_slice()
+}
+
+pub struct Stdin {
+    start: usize,
+    /// The offset of the next frame from `start`.
+    cursor: Cell<usize>,
+}
+
+// Safety: Only single-threaded programs are supported.
+unsafe impl Sync for Stdin {}
+
+impl Stdin {
+    const fn new(addr: u32) -> Self {
+        assert!(addr as usize % ALIGN == 0);
+        Stdin {
+            start: addr as usize,
+            cursor: Cell::new(ALIGN),
+        }
+    }
+
+    fn read_u64_at(&self, offset: usize) -> u64 {
+        // Safety: the input region is in RAM and `offset` is 8-byte aligned.
+        unsafe { *((self.start + offset) as *const u64) }
+    }
+
+    /// The byte length of the stream, after its header.
+    fn len(&self) -> usize {
+        self.read_u64_at(0) as usize
+    }
+
+    pub fn read_slice(&self) -> &'static [u8] {
+        let offset = self.cursor.get();
+        let end = ALIGN + self.len();
+        if offset + ALIGN > end {
+            panic!("read past the end of the input");
+        }
+        let len = self.read_u64_at(offset) as usize;
+        let next = len
+            .checked_next_multiple_of(ALIGN)
+            .and_then(|padded| (offset + ALIGN).checked_add(padded))
+            .filter(|next| *next <= end);
+        let Some(next) = next else {
+            panic!("input frame out of bounds");
+        };
+        self.cursor.set(next);
+        // Safety: the frame is within the input stream.
+        unsafe { slice::from_raw_parts((self.start + offset + ALIGN) as *const u8, len) }
+    }
+
+    pub fn read<T: Decode>(&self) -> T {
+        T::decode(self)
+    }
+}
+
+/// A value that can be read from the input, as encoded by the `Encode` trait of `ceno_host`.
+pub trait Decode: Sized {
+    fn decode(stdin: &Stdin) -> Self;
+}
+
+macro_rules! impl_decode_int {
+    ($($t:ty),*) => {
+        $(impl Decode for $t {
+            fn decode(stdin: &Stdin) -> Self {
+                match stdin.read_slice().try_into() {
+                    Ok(bytes) => <$t>::from_le_bytes(bytes),
+                    Err(_) => panic!("invalid integer in the input"),
+                }
+            }
+        })*
+    };
+}
+
+impl_decode_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Decode for bool {
+    fn decode(stdin: &Stdin) -> Self {
+        match u8::decode(stdin) {
+            0 => false,
+            1 => true,
+            _ => panic!("invalid bool in the input"),
+        }
+    }
+}
+
+impl Decode for String {
+    fn decode(stdin: &Stdin) -> Self {
+        match core::str::from_utf8(stdin.read_slice()) {
+            Ok(s) => String::from(s),
+            Err(_) => panic!("invalid string in the input"),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(stdin: &Stdin) -> Self {
+        let len = u32::decode(stdin);
+        (0..len).map(|_| T::decode(stdin)).collect()
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(stdin: &Stdin) -> Self {
+        bool::decode(stdin).then(|| T::decode(stdin))
+    }
+}
+
+macro_rules! impl_decode_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Decode),+> Decode for ($($name,)+) {
+            fn decode(stdin: &Stdin) -> Self {
+                ($($name::decode(stdin),)+)
+            }
+        }
+    };
+}
+
+impl_decode_tuple!(A);
+impl_decode_tuple!(A, B);
+impl_decode_tuple!(A, B, C);
+impl_decode_tuple!(A, B, C, D);
@@ -0,0 +1,276 @@
+//! The whole pipeline on a tiny Fibonacci program, through the public APIs only: execute it
+//! in the emulator, assign the witnesses of the opcode and table circuits, prove it with
+//! Basefold as the PCS, and verify the proof. Every step is checked, so that a change that
+//! breaks one layer for the next fails here, and not only in the full e2e runs.
+//!
+//! `cargo test --example fibonacci_pipeline` runs it on a small input.
+
+use ceno_emul::{
+    CENO_PLATFORM, EmuContext,
+    InsnKind::{ADD, ADDI, BNE, EANY, LUI, LW},
+    PC_WORD_SIZE, Platform, Program, StepRecord, Tracer, VMState, WordAddr, encode_rv32,
+};
+use ceno_zkvm::{
+    declare_program,
+    instructions::{
+        Instruction,
+        riscv::{
+            AddInstruction, MemPadder, MmuConfig, Rv32imConfig, arith_imm::AddiInstruction,
+            branch::BneInstruction, constants::EXIT_PC,
+        },
+    },
+    scheme::{
+        PublicValues, constants::MAX_NUM_VARIABLES, mock_prover::MockProver, prover::ZKVMProver,
+        verifier::ZKVMVerifier,
+    },
+    state::GlobalState,
+    structs::{ProgramParams, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+    tables::{MemFinalRecord, ProgramTableCircuit},
+};
+use clap::Parser;
+use goldilocks::GoldilocksExt2;
+use itertools::Itertools;
+use mpcs::{Basefold, BasefoldRSParams, PolynomialCommitmentScheme};
+use transcript::Transcript;
+
+type E = GoldilocksExt2;
+type Pcs = Basefold<E, BasefoldRSParams>;
+
+const PROGRAM_SIZE: usize = 16;
+#[allow(clippy::unusual_byte_groupings)]
+const ECALL_HALT: u32 = 0b_000000000000_00000_000_00000_1110011;
+/// Compute the `n`-th Fibonacci number in x1, with `n` read from the public IO.
+#[allow(clippy::unusual_byte_groupings)]
+const PROGRAM_CODE: [u32; PROGRAM_SIZE] = {
+    let mut program: [u32; PROGRAM_SIZE] = [ECALL_HALT; PROGRAM_SIZE];
+    declare_program!(
+        program,
+        encode_rv32(LUI, 0, 0, 10, CENO_PLATFORM.public_io.start), // lui x10, public_io
+        encode_rv32(LW, 10, 0, 3, 0),                              // lw x3, 0(x10)
+        encode_rv32(ADDI, 0, 0, 1, 0),                             // addi x1, x0, 0
+        encode_rv32(ADDI, 0, 0, 2, 1),                             // addi x2, x0, 1
+        // Main loop.
+        encode_rv32(ADD, 1, 2, 4, 0),              // add x4, x1, x2
+        encode_rv32(ADDI, 2, 0, 1, 0),             // addi x1, x2, 0
+        encode_rv32(ADDI, 4, 0, 2, 0),             // addi x2, x4, 0
+        encode_rv32(ADDI, 3, 0, 3, -1_i32 as u32), // addi x3, x3, -1
+        encode_rv32(BNE, 3, 0, 0, -16_i32 as u32), // bne x3, x0, -16
+        // End.
+        ECALL_HALT, // ecall halt
+    );
+    program
+};
+
+/// Prove and verify the computation of a Fibonacci number
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// The index of the Fibonacci number, at least 1
+    #[arg(short, long, default_value_t = 100)]
+    n: u32,
+}
+
+fn fibonacci(n: u32) -> u32 {
+    (0..n)
+        .fold((0u32, 1u32), |(a, b), _| (b, a.wrapping_add(b)))
+        .0
+}
+
+fn run(n: u32) {
+    assert!(n > 0, "the loop runs at least once");
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        PROGRAM_CODE.to_vec(),
+        PROGRAM_CODE
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
+                    insn,
+                )
+            })
+            .collect(),
+    );
+
+    // Key generation.
+    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
+    let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+    let mut zkvm_cs = ZKVMConstraintSystem::new_with_platform(ProgramParams {
+        program_size: PROGRAM_SIZE,
+        ..Default::default()
+    });
+    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs);
+    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs);
+    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>();
+    zkvm_cs.register_global_state::<GlobalState>();
+
+    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
+        &zkvm_cs,
+        &prog_config,
+        &program,
+    );
+    let reg_init = mmu_config.initial_registers();
+    // RAM is not used in this program, but it must have a particular size at the moment.
+    let mem_init = MemPadder::init_mem(CENO_PLATFORM.ram.clone(), mmu_config.static_mem_len(), &[]);
+    let public_io_init = MemPadder::init_mem(
+        CENO_PLATFORM.public_io.clone(),
+        mmu_config.public_io_len(),
+        &[n],
+    );
+    let io_addrs = public_io_init.iter().map(|v| v.addr).collect_vec();
+    config.generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces);
+    mmu_config.generate_fixed_traces(
+        &zkvm_cs,
+        &mut zkvm_fixed_traces,
+        &reg_init,
+        &mem_init,
+        &io_addrs,
+    );
+
+    let pk = zkvm_cs
+        .clone()
+        .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces.clone())
+        .expect("keygen failed");
+    let vk = pk.get_vk();
+
+    // Execution.
+    let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+    for record in &public_io_init {
+        vm.init_memory(record.addr.into(), record.value);
+    }
+    let all_records = vm
+        .iter_until_halt()
+        .collect::<Result<Vec<StepRecord>, _>>()
+        .expect("vm exec failed");
+    assert_eq!(
+        vm.peek_register(1),
+        fibonacci(n),
+        "wrong result of the emulator"
+    );
+
+    let halt_record = all_records
+        .iter()
+        .rev()
+        .find(|record| {
+            record.insn().codes().kind == EANY
+                && record.rs1().unwrap().value == Platform::ecall_halt()
+        })
+        .expect("halt record not found");
+    let exit_code = halt_record.rs2().unwrap().value;
+    let final_access = vm.tracer().final_accesses();
+    let end_cycle: u32 = vm.tracer().cycle().try_into().unwrap();
+
+    let reg_final = reg_init
+        .iter()
+        .map(|rec| {
+            let index = rec.addr as usize;
+            if index < VMState::REG_COUNT {
+                let vma: WordAddr = Platform::register_vma(index).into();
+                MemFinalRecord {
+                    addr: rec.addr,
+                    value: vm.peek_register(index),
+                    cycle: *final_access.get(&vma).unwrap_or(&0),
+                }
+            } else {
+                // The table is padded beyond the number of registers.
+                MemFinalRecord {
+                    addr: rec.addr,
+                    value: 0,
+                    cycle: 0,
+                }
+            }
+        })
+        .collect_vec();
+    let mem_final = mem_init
+        .iter()
+        .map(|rec| {
+            let vma: WordAddr = rec.addr.into();
+            MemFinalRecord {
+                addr: rec.addr,
+                value: vm.peek_memory(vma),
+                cycle: *final_access.get(&vma).unwrap_or(&0),
+            }
+        })
+        .collect_vec();
+    let public_io_final = public_io_init
+        .iter()
+        .map(|rec| *final_access.get(&rec.addr.into()).unwrap_or(&0))
+        .collect_vec();
+
+    let pi = PublicValues::new(
+        exit_code,
+        vm.program().entry,
+        Tracer::SUBCYCLES_PER_INSN as u32,
+        EXIT_PC as u32,
+        end_cycle,
+        public_io_init.iter().map(|v| v.value).collect(),
+        reg_final.iter().map(|rec| rec.value).collect(),
+    );
+
+    // Witness generation.
+    let num_steps = all_records.len();
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    config
+        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records, vm.random_seed())
+        .unwrap();
+    zkvm_witness.finalize_lk_multiplicities();
+    config
+        .assign_table_circuit(&zkvm_cs, &mut zkvm_witness)
+        .unwrap();
+    mmu_config
+        .assign_table_circuit(
+            &zkvm_cs,
+            &mut zkvm_witness,
+            &reg_final,
+            &mem_final,
+            &public_io_final,
+        )
+        .unwrap();
+    zkvm_witness
+        .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+        .unwrap();
+
+    // One addition and one branch per iteration, and three moves besides the two initial ones.
+    let num_instances = |name: String| {
+        zkvm_witness
+            .get_opcode_witness(&name)
+            .map_or(0, |witness| witness.num_instances())
+    };
+    let n = n as usize;
+    assert_eq!(num_instances(AddInstruction::<E>::name()), n);
+    assert_eq!(num_instances(AddiInstruction::<E>::name()), 3 * n + 2);
+    assert_eq!(num_instances(BneInstruction::<E>::name()), n);
+
+    MockProver::assert_satisfied_full(zkvm_cs, zkvm_fixed_traces, &zkvm_witness, &pi);
+
+    // Proving and verification.
+    let prover = ZKVMProver::new(pk);
+    let verifier = ZKVMVerifier::new(vk);
+    let zkvm_proof = prover
+        .create_proof(zkvm_witness, pi, Transcript::new(b"fibonacci"))
+        .expect("create_proof failed");
+    let metadata = verifier
+        .verify_metadata(&zkvm_proof)
+        .expect("invalid proof metadata")
+        .expect("proof metadata not found");
+    assert_eq!(metadata.num_steps, num_steps);
+    assert!(
+        verifier
+            .verify_proof(zkvm_proof, Transcript::new(b"fibonacci"))
+            .expect("verify proof return with error"),
+    );
+}
+
+fn main() {
+    let args = Args::parse();
+    run(args.n);
+    println!("proved and verified fibonacci({})", args.n);
+}
+
+#[test]
+fn fibonacci_pipeline() {
+    run(10);
+}
@@ -16,7 +16,11 @@ use ceno_emul::{
     PC_WORD_SIZE, Platform, Program, StepRecord, Tracer, VMState, Word, WordAddr, encode_rv32,
 };
 use ceno_zkvm::{
-    scheme::{PublicValues, constants::MAX_NUM_VARIABLES, verifier::ZKVMVerifier},
+    scheme::{
+        PublicValues,
+        constants::{MAX_NUM_VARIABLES, TRANSCRIPT_LABEL},
+        verifier::ZKVMVerifier,
+    },
     stats::{StaticReport, TraceReport},
     structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
 };
@@ -206,21 +210,6 @@ fn main() {
 
         let end_cycle: u32 = vm.tracer().cycle().try_into().unwrap();
         let exit_code = halt_record.rs2().unwrap().value;
-        let pi = PublicValues::new(
-            exit_code,
-            vm.program().entry,
-            Tracer::SUBCYCLES_PER_INSN as u32,
-            EXIT_PC as u32,
-            end_cycle,
-            public_io_init.iter().map(|v| v.value).collect(),
-        );
-
-        let mut zkvm_witness = ZKVMWitnesses::default();
-        // assign opcode circuits
-        config
-            .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records)
-            .unwrap();
-        zkvm_witness.finalize_lk_multiplicities();
 
         // Find the final register values and cycles.
         let reg_final = reg_init
@@ -245,6 +234,23 @@ fn main() {
             })
             .collect_vec();
 
+        let pi = PublicValues::new(
+            exit_code,
+            vm.program().entry,
+            Tracer::SUBCYCLES_PER_INSN as u32,
+            EXIT_PC as u32,
+            end_cycle,
+            public_io_init.iter().map(|v| v.value).collect(),
+            reg_final.iter().map(|rec| rec.value).collect(),
+        );
+
+        let mut zkvm_witness = ZKVMWitnesses::default();
+        // assign opcode circuits
+        config
+            .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records, vm.random_seed())
+            .unwrap();
+        zkvm_witness.finalize_lk_multiplicities();
+
         // Find the final memory values and cycles.
         let mem_final = mem_init
             .iter()
@@ -302,7 +308,7 @@ fn main() {
 
         let timer = Instant::now();
 
-        let transcript = Transcript::new(b"riscv");
+        let transcript = Transcript::new(TRANSCRIPT_LABEL);
 
         let mut zkvm_proof = prover
             .create_proof(zkvm_witness, pi, transcript)
@@ -314,14 +320,14 @@ fn main() {
             timer.elapsed().as_secs()
         );
 
-        let transcript = Transcript::new(b"riscv");
+        let transcript = Transcript::new(TRANSCRIPT_LABEL);
         assert!(
             verifier
                 .verify_proof(zkvm_proof.clone(), transcript)
                 .expect("verify proof return with error"),
         );
 
-        let transcript = Transcript::new(b"riscv");
+        let transcript = Transcript::new(TRANSCRIPT_LABEL);
         // change public input maliciously should cause verifier to reject proof
         zkvm_proof.raw_pi[0] = vec![<GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::ONE];
         zkvm_proof.raw_pi[1] = vec![<GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::ONE];
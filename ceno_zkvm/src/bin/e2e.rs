@@ -1,11 +1,16 @@
 use ceno_emul::{
-    ByteAddr, CENO_PLATFORM, EmuContext, InsnKind::EANY, Platform, StepRecord, Tracer, VMState,
-    WORD_SIZE, WordAddr,
+    ByteAddr, CENO_PLATFORM, EmuContext, FusedKind, InsnKind::EANY, Platform, StepRecord, Tracer,
+    VMState, WORD_SIZE, WordAddr, fuse_steps,
 };
 use ceno_zkvm::{
+    error::ZKVMError,
     instructions::riscv::{DummyExtraConfig, MemPadder, MmuConfig, Rv32imConfig},
     scheme::{
-        PublicValues, constants::MAX_NUM_VARIABLES, mock_prover::MockProver, prover::ZKVMProver,
+        PublicValues,
+        commit_stream::commit_while_assigning,
+        constants::{MAX_NUM_VARIABLES, PACKING_MIN_NUM_VARS, TRANSCRIPT_LABEL},
+        mock_prover::MockProver,
+        prover::ZKVMProver,
         verifier::ZKVMVerifier,
     },
     state::GlobalState,
@@ -16,7 +21,7 @@ use clap::{Parser, ValueEnum};
 use ff_ext::ff::Field;
 use goldilocks::GoldilocksExt2;
 use itertools::{Itertools, MinMaxResult, chain, enumerate};
-use mpcs::{Basefold, BasefoldRSParams, PolynomialCommitmentScheme};
+use mpcs::{Basefold, BasefoldBasecodeParams, BasefoldRSParams, PolynomialCommitmentScheme};
 use std::{
     collections::{HashMap, HashSet},
     fs, panic,
@@ -41,6 +46,22 @@ struct Args {
     /// The preset configuration to use.
     #[arg(short, long, value_enum, default_value_t = Preset::Ceno)]
     platform: Preset,
+
+    /// The polynomial commitment scheme to prove with.
+    #[arg(long, value_enum, default_value_t = PcsKind::BasefoldRs)]
+    pcs: PcsKind,
+
+    /// The seed of the words returned by the RANDOM ecall.
+    #[arg(long, default_value_t = 0)]
+    random_seed: u64,
+
+    /// Prove the pairs of instructions that have a fused circuit as one step each.
+    #[arg(long)]
+    fuse_steps: bool,
+
+    /// Pack the opcode circuits with witnesses of fewer variables into shared commitments.
+    #[arg(long, default_value_t = PACKING_MIN_NUM_VARS)]
+    packing_min_num_vars: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -49,14 +70,19 @@ enum Preset {
     Sp1,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PcsKind {
+    /// Basefold with Reed-Solomon codes.
+    BasefoldRs,
+    /// Basefold with random foldable codes.
+    BasefoldBasecode,
+}
+
+type E = GoldilocksExt2;
+
 fn main() {
     let args = Args::parse();
 
-    type E = GoldilocksExt2;
-    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
-    const PROGRAM_SIZE: usize = 1 << 14;
-    type ExampleProgramTableCircuit<E> = ProgramTableCircuit<E>;
-
     // set up logger
     let (flame_layer, _guard) = FlameLayer::with_file("./tracing.folded").unwrap();
     let subscriber = Registry::default()
@@ -74,6 +100,20 @@ fn main() {
         .with(flame_layer.with_threads_collapsed(true));
     tracing::subscriber::set_global_default(subscriber).unwrap();
 
+    match args.pcs {
+        PcsKind::BasefoldRs => run::<Basefold<E, BasefoldRSParams>>(args),
+        PcsKind::BasefoldBasecode => run::<Basefold<E, BasefoldBasecodeParams>>(args),
+    }
+}
+
+fn run<Pcs: PolynomialCommitmentScheme<E>>(args: Args)
+where
+    Pcs::ProverParam: Sync,
+    Pcs::CommitmentWithData: Send + Sync,
+{
+    const PROGRAM_SIZE: usize = 1 << 14;
+    type ExampleProgramTableCircuit<E> = ProgramTableCircuit<E>;
+
     let platform = match args.platform {
         Preset::Ceno => CENO_PLATFORM,
         Preset::Sp1 => Platform {
@@ -93,10 +133,11 @@ fn main() {
     tracing::info!("Loading ELF file: {}", args.elf);
     let elf_bytes = fs::read(&args.elf).expect("read elf file");
     let mut vm = VMState::new_from_elf(platform.clone(), &elf_bytes).unwrap();
+    vm.set_random_seed(args.random_seed);
 
     // keygen
-    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
-    let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("PCS setup");
+    let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("PCS trim");
     let program_params = ProgramParams {
         platform: platform.clone(),
         program_size: PROGRAM_SIZE,
@@ -159,7 +200,7 @@ fn main() {
 
     // proving
     let e2e_start = Instant::now();
-    let prover = ZKVMProver::new(pk);
+    let prover = ZKVMProver::new(pk).with_packing(args.packing_min_num_vars);
     let verifier = ZKVMVerifier::new(vk);
 
     let all_records = vm
@@ -184,28 +225,24 @@ fn main() {
         .and_then(|halt_record| halt_record.rs2())
         .map(|rs2| rs2.value);
 
-    let final_access = vm.tracer().final_accesses();
+    // Fusing drops the accesses of the second instruction of a pair, so the last accesses
+    // come from the fused trace.
+    let (all_records, final_access) = if args.fuse_steps {
+        let fused = fuse_steps(all_records, &FusedKind::ALL);
+        tracing::info!("Fused {} pairs of steps", fused.num_fused());
+        (fused.steps, fused.final_accesses)
+    } else {
+        let final_access = vm.tracer().final_accesses().clone();
+        (
+            all_records
+                .into_iter()
+                .map(|record| (None, record))
+                .collect(),
+            final_access,
+        )
+    };
     let end_cycle: u32 = vm.tracer().cycle().try_into().unwrap();
 
-    let pi = PublicValues::new(
-        exit_code.unwrap_or(0),
-        vm.program().entry,
-        Tracer::SUBCYCLES_PER_INSN as u32,
-        vm.get_pc().into(),
-        end_cycle,
-        io_init.iter().map(|rec| rec.value).collect_vec(),
-    );
-
-    let mut zkvm_witness = ZKVMWitnesses::default();
-    // assign opcode circuits
-    let dummy_records = config
-        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records)
-        .unwrap();
-    dummy_config
-        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, dummy_records)
-        .unwrap();
-    zkvm_witness.finalize_lk_multiplicities();
-
     // Find the final register values and cycles.
     let reg_final = reg_init
         .iter()
@@ -229,6 +266,17 @@ fn main() {
         })
         .collect_vec();
 
+    let pi = PublicValues::new(
+        exit_code.unwrap_or(0),
+        vm.program().entry,
+        Tracer::SUBCYCLES_PER_INSN as u32,
+        vm.get_pc().into(),
+        end_cycle,
+        io_init.iter().map(|rec| rec.value).collect_vec(),
+        reg_final.iter().map(|rec| rec.value).collect_vec(),
+    )
+    .with_random_seed(vm.random_seed());
+
     // Find the final memory values and cycles.
     let mem_final = mem_init
         .iter()
@@ -249,33 +297,64 @@ fn main() {
         .map(|rec| *final_access.get(&rec.addr.into()).unwrap_or(&0))
         .collect_vec();
 
-    // assign table circuits
-    config
-        .assign_table_circuit(&zkvm_cs, &mut zkvm_witness)
-        .unwrap();
-    mmu_config
-        .assign_table_circuit(
+    // Assign all the circuits, and hand over the witnesses to `send` once the lookups that they
+    // feed are counted.
+    let random_seed = vm.random_seed();
+    let assign = |send: &mut dyn FnMut(&mut ZKVMWitnesses<E>) -> Result<(), ZKVMError>|
+     -> Result<ZKVMWitnesses<E>, ZKVMError> {
+        let mut zkvm_witness = ZKVMWitnesses::default();
+        // assign opcode circuits
+        let dummy_records = config.assign_fused_opcode_circuit(
+            &zkvm_cs,
+            &mut zkvm_witness,
+            all_records,
+            random_seed,
+        )?;
+        dummy_config.assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, dummy_records)?;
+        send(&mut zkvm_witness)?;
+        zkvm_witness.finalize_lk_multiplicities();
+
+        // assign table circuits
+        config.assign_table_circuit(&zkvm_cs, &mut zkvm_witness)?;
+        mmu_config.assign_table_circuit(
             &zkvm_cs,
             &mut zkvm_witness,
             &reg_final,
             &mem_final,
             &io_final,
-        )
-        .unwrap();
-    // assign program circuit
-    zkvm_witness
-        .assign_table_circuit::<ExampleProgramTableCircuit<E>>(&zkvm_cs, &prog_config, vm.program())
-        .unwrap();
-
-    if std::env::var("MOCK_PROVING").is_ok() {
-        MockProver::assert_satisfied_full(zkvm_cs, zkvm_fixed_traces, &zkvm_witness, &pi);
-        tracing::info!("Mock proving passed");
-    }
+        )?;
+        // assign program circuit
+        zkvm_witness.assign_table_circuit::<ExampleProgramTableCircuit<E>>(
+            &zkvm_cs,
+            &prog_config,
+            vm.program(),
+        )?;
+        send(&mut zkvm_witness)?;
+        Ok(zkvm_witness)
+    };
+
     let timer = Instant::now();
+    let committed = if std::env::var("MOCK_PROVING").is_ok() {
+        // The mock prover needs all the witnesses at once.
+        let zkvm_witness = assign(&mut |_| Ok(())).unwrap();
+        MockProver::assert_satisfied_full(zkvm_cs.clone(), zkvm_fixed_traces, &zkvm_witness, &pi);
+        tracing::info!("Mock proving passed");
+        prover
+            .commit_witnesses(zkvm_witness)
+            .expect("commit_witnesses failed")
+    } else {
+        // Commit to the witnesses of the opcode circuits while the tables are assigned.
+        let (_, committed) =
+            commit_while_assigning::<E, Pcs, _>(&pp, 1, prover.packing_min_num_vars, |sink| {
+                assign(&mut |witnesses| sink.send(witnesses))
+            })
+            .expect("commit_while_assigning failed");
+        committed
+    };
 
-    let transcript = Transcript::new(b"riscv");
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
     let mut zkvm_proof = prover
-        .create_proof(zkvm_witness, pi, transcript)
+        .create_proof_from_committed(committed, &[], pi, transcript)
         .expect("create_proof failed");
 
     println!(
@@ -284,7 +363,7 @@ fn main() {
         e2e_start.elapsed(),
     );
 
-    let transcript = Transcript::new(b"riscv");
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
     assert!(
         verifier
             .verify_proof_halt(zkvm_proof.clone(), transcript, exit_code.is_some())
@@ -296,7 +375,7 @@ fn main() {
         None => tracing::error!("Unfinished execution. max_steps={:?}.", args.max_steps),
     }
 
-    let transcript = Transcript::new(b"riscv");
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
     // change public input maliciously should cause verifier to reject proof
     zkvm_proof.raw_pi[0] = vec![<GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::ONE];
     zkvm_proof.raw_pi[1] = vec![<GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::ONE];
@@ -307,7 +386,9 @@ fn main() {
         // by default it will print msg to stdout/stderr
         // we override it to avoid print msg since we will capture the msg by our own
     }));
-    let result = panic::catch_unwind(|| verifier.verify_proof(zkvm_proof, transcript));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        verifier.verify_proof(zkvm_proof, transcript)
+    }));
     panic::set_hook(default_hook);
     match result {
         Ok(res) => {
@@ -10,6 +10,7 @@ use crate::{
 pub mod general;
 pub mod global_state;
 pub mod memory;
+pub mod range_inference;
 pub mod register;
 pub mod utils;
 
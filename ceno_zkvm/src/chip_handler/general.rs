@@ -5,11 +5,11 @@ use crate::{
     error::ZKVMError,
     expression::{Expression, Fixed, Instance, ToExpr, WitIn},
     instructions::riscv::constants::{
-        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, INIT_CYCLE_IDX, INIT_PC_IDX, PUBLIC_IO_IDX,
-        UINT_LIMBS,
+        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, FINAL_REGS_IDX, INIT_CYCLE_IDX, INIT_PC_IDX,
+        PUBLIC_IO_IDX, RANDOM_SEED_IDX, UINT_LIMBS,
     },
     structs::{ProgramParams, RAMType, ROMType},
-    tables::InsnRecord,
+    tables::{CustomTable, InsnRecord, PackedRangeTable, PackedU5Table, PackedU8Table},
 };
 
 impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
@@ -64,6 +64,26 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.cs.query_instance(|| "public_io", PUBLIC_IO_IDX)
     }
 
+    /// The limbs of the final register values, one register per row.
+    pub fn query_final_regs(&mut self) -> Result<[Instance; UINT_LIMBS], ZKVMError> {
+        Ok([
+            self.cs
+                .query_instance(|| "final_regs_low", FINAL_REGS_IDX)?,
+            self.cs
+                .query_instance(|| "final_regs_high", FINAL_REGS_IDX + 1)?,
+        ])
+    }
+
+    /// The low and high halves of the seed of the RANDOM ecall.
+    pub fn query_random_seed(&mut self) -> Result<[Instance; 2], ZKVMError> {
+        Ok([
+            self.cs
+                .query_instance(|| "random_seed_low", RANDOM_SEED_IDX)?,
+            self.cs
+                .query_instance(|| "random_seed_high", RANDOM_SEED_IDX + 1)?,
+        ])
+    }
+
     pub fn lk_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -77,6 +97,26 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.cs.lk_record(name_fn, rom_type, items)
     }
 
+    /// Look up `record` in the custom table `T`.
+    pub fn lk_custom_record<T, NR, N>(
+        &mut self,
+        name_fn: N,
+        record: Vec<Expression<E>>,
+    ) -> Result<(), ZKVMError>
+    where
+        T: CustomTable,
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        assert_eq!(record.len(), T::NUM_COLUMNS, "wrong record width");
+        self.register_custom_table::<T>();
+        self.cs.lk_record(name_fn, T::rom_type(), record)
+    }
+
+    pub fn register_custom_table<T: CustomTable>(&mut self) {
+        self.cs.register_custom_table::<T>()
+    }
+
     pub fn lk_table_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -238,36 +278,49 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.namespace(|| "require_one", |cb| cb.cs.require_zero(name_fn, 1 - expr))
     }
 
+    /// Require `a == b` whenever the boolean `cond` is one.
     pub fn condition_require_equal<NR, N>(
         &mut self,
         name_fn: N,
         cond: Expression<E>,
-        target: Expression<E>,
-        true_expr: Expression<E>,
-        false_expr: Expression<E>,
+        a: Expression<E>,
+        b: Expression<E>,
     ) -> Result<(), ZKVMError>
     where
         NR: Into<String>,
         N: FnOnce() -> NR,
     {
-        // cond * (true_expr) + (1 - cond) * false_expr
-        // => false_expr + cond * true_expr - cond * false_expr
         self.namespace(
             || "cond_require_equal",
-            |cb| {
-                let cond_target = false_expr.clone() + cond.clone() * true_expr - cond * false_expr;
-                cb.cs.require_zero(name_fn, target - cond_target)
-            },
+            |cb| cb.cs.require_zero(name_fn, cond * (a - b)),
+        )
+    }
+
+    /// Require `expr` to be zero whenever the boolean `cond` is one.
+    pub fn require_zero_if<NR, N>(
+        &mut self,
+        name_fn: N,
+        cond: Expression<E>,
+        expr: Expression<E>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespace(
+            || "require_zero_if",
+            |cb| cb.cs.require_zero(name_fn, cond * expr),
         )
     }
 
+    /// `when_true` if the boolean `cond` is one, else `when_false`, using a single product.
     pub fn select(
         &mut self,
         cond: &Expression<E>,
         when_true: &Expression<E>,
         when_false: &Expression<E>,
     ) -> Expression<E> {
-        cond * when_true + (1 - cond) * when_false
+        when_false + cond * (when_true - when_false)
     }
 
     pub(crate) fn assert_ux<NR, N, const C: usize>(
@@ -288,6 +341,35 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         }
     }
 
+    /// Range-check `exprs` to `C` bits, packed into as few lookups as the packed range table of
+    /// that width allows. The values must be counted with `LkMultiplicity::assert_ux_packed`,
+    /// in the same order.
+    ///
+    /// The limbs of `UInt` are 16 bits and keep one `U16` lookup each: a packed table of two
+    /// 16-bit values would have 2^32 rows.
+    pub(crate) fn assert_ux_packed<NR, N, const C: usize>(
+        &mut self,
+        name_fn: N,
+        exprs: Vec<Expression<E>>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let (rom_type, pack) = match C {
+            8 => (PackedU8Table::ROM_TYPE, PackedU8Table::PACK),
+            5 => (PackedU5Table::ROM_TYPE, PackedU5Table::PACK),
+            c => panic!("Unsupported packed bit range {c}"),
+        };
+        self.namespace(name_fn, |cb| {
+            exprs.chunks(pack).enumerate().try_for_each(|(i, chunk)| {
+                let mut record = chunk.to_vec();
+                record.resize(pack, Expression::ZERO);
+                cb.lk_record(|| format!("packed_{i}"), rom_type, record)
+            })
+        })
+    }
+
     fn assert_u5<NR, N>(&mut self, name_fn: N, expr: Expression<E>) -> Result<(), ZKVMError>
     where
         NR: Into<String>,
@@ -318,14 +400,25 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
     }
 
     /// create namespace to prefix all constraints define under the scope
+    #[track_caller]
     pub fn namespace<NR: Into<String>, N: FnOnce() -> NR, T>(
         &mut self,
         name_fn: N,
         cb: impl FnOnce(&mut CircuitBuilder<E>) -> Result<T, ZKVMError>,
     ) -> Result<T, ZKVMError> {
-        self.cs.namespace(name_fn, |cs| {
-            let mut inner_circuit_builder =
-                CircuitBuilder::new_with_params(cs, self.params.clone());
+        self.scope(name_fn(), cb)
+    }
+
+    /// Run `cb` in the child scope `name`, see [`ConstraintSystem::scope`].
+    #[track_caller]
+    pub fn scope<T>(
+        &mut self,
+        name: impl Into<String>,
+        cb: impl FnOnce(&mut CircuitBuilder<E>) -> Result<T, ZKVMError>,
+    ) -> Result<T, ZKVMError> {
+        let params = self.params.clone();
+        self.cs.scope(name, |cs| {
+            let mut inner_circuit_builder = CircuitBuilder::new_with_params(cs, params);
             cb(&mut inner_circuit_builder)
         })
     }
@@ -414,6 +507,25 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.logic_u8(ROMType::Pow, 2.into(), b, c)
     }
 
+    /// Assert that the byte `a` has `clz` leading zeros, and that `is_zero` is whether `a == 0`.
+    pub fn lookup_clz_byte(
+        &mut self,
+        a: Expression<E>,
+        clz: Expression<E>,
+        is_zero: Expression<E>,
+    ) -> Result<(), ZKVMError> {
+        self.logic_u8(ROMType::Clz, a, clz, is_zero)
+    }
+
+    /// Assert that the byte `a` has `count` ones.
+    pub fn lookup_popcount_byte(
+        &mut self,
+        a: Expression<E>,
+        count: Expression<E>,
+    ) -> Result<(), ZKVMError> {
+        self.logic_u8(ROMType::Popcount, a, count, 0.into())
+    }
+
     pub(crate) fn is_equal(
         &mut self,
         lhs: Expression<E>,
@@ -422,7 +534,7 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         let is_eq = self.create_witin(|| "is_eq");
         let diff_inverse = self.create_witin(|| "diff_inverse");
 
-        self.require_zero(|| "is equal", is_eq.expr() * &lhs - is_eq.expr() * &rhs)?;
+        self.condition_require_equal(|| "is equal", is_eq.expr(), lhs.clone(), rhs.clone())?;
         self.require_zero(
             || "is equal",
             1 - is_eq.expr() - diff_inverse.expr() * lhs + diff_inverse.expr() * rhs,
@@ -4,7 +4,7 @@ use crate::{
     error::ZKVMError,
     expression::Expression,
     gadgets::AssertLTConfig,
-    instructions::riscv::constants::UINT_LIMBS,
+    instructions::riscv::constants::TS_LIMBS,
     structs::RAMType,
 };
 use ff_ext::ExtensionField;
@@ -44,7 +44,7 @@ impl<'a, E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR> MemoryChipOpera
                 || "prev_ts < ts",
                 prev_ts,
                 ts.clone(),
-                UINT_LIMBS,
+                TS_LIMBS,
             )?;
 
             let next_ts = ts + 1;
@@ -85,7 +85,7 @@ impl<'a, E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR> MemoryChipOpera
                 || "prev_ts < ts",
                 prev_ts,
                 ts.clone(),
-                UINT_LIMBS,
+                TS_LIMBS,
             )?;
 
             let next_ts = ts + 1;
@@ -0,0 +1,409 @@
+//! Inference of the range checks of a circuit by interval analysis of its zero constraints.
+//!
+//! The witnesses with a declared width, see [`CircuitBuilder::declare_bits`], and the range
+//! checked witnesses are bounded. A linear constraint with a single unbounded witness, of
+//! coefficient ±1, then bounds that witness without a check. A linear constraint whose unbounded
+//! witnesses have coefficients of the same sign, each a power of two times the previous one,
+//! decomposes the rest of the constraint into limbs, which need checks to the widths between
+//! their coefficients.
+
+use std::collections::BTreeMap;
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::{Itertools, chain};
+
+use crate::{
+    ROMType,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    expression::{Expression, Interval, ToExpr, WitIn},
+    structs::WitnessId,
+    witness::{LkMultiplicity, RowMajorMatrix},
+};
+
+/// A range check that the analysis finds missing, needless, or of the wrong width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeCheckSuggestion {
+    pub witin: WitnessId,
+    pub name: String,
+    /// The width to check the witness to, or `None` if the other witnesses bound it already.
+    pub bits: Option<usize>,
+    /// The width the witness is checked to now, if it is.
+    pub checked_bits: Option<usize>,
+}
+
+/// A term of a zero constraint: a constant coefficient times a product of witnesses.
+struct Term<E: ExtensionField> {
+    coeff: i128,
+    vars: Vec<WitnessId>,
+    expr: Expression<E>,
+}
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    /// Suggest the range checks that the circuit misses, those it does not need, and those of
+    /// the wrong width. A check is needless given the checks that are kept, so that all the
+    /// needless checks can be removed together.
+    pub fn suggest_range_checks(&self) -> Vec<RangeCheckSuggestion> {
+        let constraints = chain!(
+            &self.assert_zero_expressions,
+            &self.assert_zero_sumcheck_expressions
+        )
+        .filter_map(terms)
+        .collect_vec();
+        let bounds_of = |checked: &BTreeMap<WitnessId, usize>| {
+            chain!(&self.witin_bits, checked)
+                .map(|(witin, bits)| (*witin, Interval::bits(*bits)))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let suggest = |witin: WitnessId, bits, checked_bits| RangeCheckSuggestion {
+            witin,
+            name: self.witin_namespace_map[witin as usize].clone(),
+            bits,
+            checked_bits,
+        };
+
+        let mut checked = checked_bits(self);
+        let mut suggestions = propagate(&constraints, &mut bounds_of(&checked))
+            .into_iter()
+            .filter_map(|(witin, bits)| Some(suggest(witin, Some(bits?), None)))
+            .collect_vec();
+        for (witin, bits) in checked.clone() {
+            if self.witin_bits.contains_key(&witin) {
+                continue;
+            }
+            checked.remove(&witin);
+            let mut bounds = bounds_of(&checked);
+            match propagate(&constraints, &mut bounds).get(&witin) {
+                Some(None) if bounds[&witin].lo >= 0 && bounds[&witin].num_bits() <= bits => {
+                    suggestions.push(suggest(witin, None, Some(bits)));
+                    continue;
+                }
+                Some(Some(needed)) if *needed != bits => {
+                    suggestions.push(suggest(witin, Some(*needed), Some(bits)))
+                }
+                _ => {}
+            }
+            checked.insert(witin, bits);
+        }
+        suggestions.sort_by_key(|suggestion| suggestion.witin);
+        suggestions
+    }
+}
+
+impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
+    /// Declare the width of `witin`, which the range inference takes as given, e.g. for the
+    /// inputs of a gadget that its caller checks.
+    pub fn declare_bits(&mut self, witin: &WitIn, bits: usize) {
+        self.cs.witin_bits.insert(witin.id, bits);
+    }
+
+    /// Add the missing range checks found by [`ConstraintSystem::suggest_range_checks`] whose
+    /// width has a range table, and return the suggestions left. The witness assignment counts
+    /// the added lookups, see [`count_inferred_range_checks`].
+    pub fn insert_range_checks(&mut self) -> Result<Vec<RangeCheckSuggestion>, ZKVMError> {
+        let mut left = vec![];
+        for suggestion in self.cs.suggest_range_checks() {
+            let witin = WitIn {
+                id: suggestion.witin,
+            };
+            let name = || format!("inferred_{}", witin.id);
+            let (bits, rom_type) = match (suggestion.bits, suggestion.checked_bits) {
+                (Some(0), None) => {
+                    self.require_zero(name, witin.expr())?;
+                    continue;
+                }
+                (Some(1), None) => {
+                    self.assert_bit(name, witin.expr())?;
+                    continue;
+                }
+                (Some(5), None) => (5, ROMType::U5),
+                (Some(8), None) => (8, ROMType::U8),
+                (Some(14), None) => (14, ROMType::U14),
+                (Some(16), None) => (16, ROMType::U16),
+                _ => {
+                    left.push(suggestion);
+                    continue;
+                }
+            };
+            self.lk_record(name, rom_type, vec![witin.expr()])?;
+            self.cs.inferred_range_checks.push((witin.id, bits));
+        }
+        Ok(left)
+    }
+}
+
+/// Count the lookups of the range checks added by [`CircuitBuilder::insert_range_checks`] in the
+/// instances of `witness`.
+pub(crate) fn count_inferred_range_checks<E: ExtensionField>(
+    cs: &ConstraintSystem<E>,
+    witness: &RowMajorMatrix<E::BaseField>,
+    lk_multiplicity: &mut LkMultiplicity,
+) {
+    if cs.inferred_range_checks.is_empty() {
+        return;
+    }
+    for row in witness.iter_rows().take(witness.num_instances()) {
+        for &(witin, bits) in &cs.inferred_range_checks {
+            let v = unsafe { row[witin as usize].assume_init() }.to_canonical_u64();
+            match bits {
+                5 => lk_multiplicity.assert_ux::<5>(v),
+                8 => lk_multiplicity.assert_ux::<8>(v),
+                14 => lk_multiplicity.assert_ux::<14>(v),
+                16 => lk_multiplicity.assert_ux::<16>(v),
+                _ => unreachable!("no range table of {bits} bits"),
+            }
+        }
+    }
+}
+
+/// The terms of a zero constraint, or `None` if it has a fixed column, an instance or a
+/// challenge.
+fn terms<E: ExtensionField>(expr: &Expression<E>) -> Option<Vec<Term<E>>> {
+    let no_bounds = BTreeMap::new();
+    expr.monomial_terms()
+        .into_iter()
+        .map(|(coeff, vars)| {
+            let witins = vars
+                .iter()
+                .map(|var| match var {
+                    Expression::WitIn(witin) => Some(*witin),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Term {
+                coeff: coeff.interval(&no_bounds)?.lo,
+                vars: witins,
+                expr: coeff * vars.into_iter().product::<Expression<E>>(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|terms| terms.into_iter().filter(|term| term.coeff != 0).collect())
+}
+
+/// The widths of the witnesses that the lookups and the bit constraints check.
+fn checked_bits<E: ExtensionField>(cs: &ConstraintSystem<E>) -> BTreeMap<WitnessId, usize> {
+    let mut checked = BTreeMap::new();
+    let mut check = |item: &Expression<E>, bits: usize| {
+        if let Expression::WitIn(witin) = item {
+            let checked_bits = checked.entry(*witin).or_insert(bits);
+            *checked_bits = bits.min(*checked_bits);
+        }
+    };
+    for (rom_type, items) in &cs.lk_expressions_items_map {
+        let widths: &[usize] = match rom_type {
+            ROMType::U5 => &[5],
+            ROMType::U8 => &[8],
+            ROMType::U14 => &[14],
+            ROMType::U16 => &[16],
+            ROMType::PackedU5 => &[5, 5, 5],
+            ROMType::PackedU8 => &[8, 8],
+            ROMType::And | ROMType::Or | ROMType::Xor => &[8, 8, 8],
+            ROMType::Ltu => &[8, 8, 1],
+            _ => &[],
+        };
+        items
+            .iter()
+            .zip(widths)
+            .for_each(|(item, bits)| check(item, *bits));
+    }
+    // `assert_bit`, i.e. `x * (1 - x) = 0`
+    for expr in &cs.assert_zero_sumcheck_expressions {
+        if let Some([x, xx]) = terms(expr).as_deref() {
+            if x.vars.len() == 1 && xx.vars == [x.vars[0]; 2] && x.coeff == -xx.coeff {
+                check(&Expression::WitIn(x.vars[0]), 1);
+            }
+        }
+    }
+    checked
+}
+
+/// Bound the witnesses of `constraints` from `bounds`, until no constraint bounds another one,
+/// and return the witnesses it bounded, with the width of the check each one needs, if any.
+fn propagate<E: ExtensionField>(
+    constraints: &[Vec<Term<E>>],
+    bounds: &mut BTreeMap<WitnessId, Interval>,
+) -> BTreeMap<WitnessId, Option<usize>> {
+    let mut found = BTreeMap::new();
+    let mut pending = constraints.iter().collect_vec();
+    loop {
+        let num_pending = pending.len();
+        pending.retain(|terms| !resolve(terms, bounds, &mut found));
+        if pending.len() == num_pending {
+            return found;
+        }
+    }
+}
+
+/// Bound the unbounded witnesses of a constraint if it determines them, and return whether it
+/// has none left.
+fn resolve<E: ExtensionField>(
+    terms: &[Term<E>],
+    bounds: &mut BTreeMap<WitnessId, Interval>,
+    found: &mut BTreeMap<WitnessId, Option<usize>>,
+) -> bool {
+    let (free, bounded): (Vec<_>, Vec<_>) = terms
+        .iter()
+        .partition(|term| term.vars.iter().any(|var| !bounds.contains_key(var)));
+    if free.is_empty() {
+        return true;
+    }
+    if free.iter().any(|term| term.vars.len() != 1)
+        || free
+            .iter()
+            .any(|term| term.coeff.signum() != free[0].coeff.signum())
+    {
+        return false;
+    }
+    let rest = bounded
+        .iter()
+        .map(|term| term.expr.clone())
+        .sum::<Expression<E>>();
+    let Some(rest) = rest.interval(bounds) else {
+        return false;
+    };
+    // the free terms sum to `target`, with positive coefficients
+    let target = if free[0].coeff > 0 { -rest } else { rest };
+    let mut limbs = free
+        .iter()
+        .map(|term| (term.coeff.abs(), term.vars[0]))
+        .collect_vec();
+    if let [(1, witin)] = limbs[..] {
+        bounds.insert(witin, target);
+        found.insert(witin, None);
+        return true;
+    }
+    if target.lo < 0 {
+        return false;
+    }
+
+    limbs.sort();
+    let mut widths = vec![];
+    for ((coeff, _), (next_coeff, _)) in limbs.iter().tuple_windows() {
+        if next_coeff % coeff != 0 || (next_coeff / coeff).count_ones() != 1 {
+            return false;
+        }
+        widths.push((next_coeff / coeff).trailing_zeros() as usize);
+    }
+    let (last_coeff, _) = limbs.last().unwrap();
+    widths.push(Interval::point(target.hi / last_coeff).num_bits());
+    for ((_, witin), bits) in limbs.into_iter().zip(widths) {
+        bounds.insert(witin, Interval::bits(bits));
+        found.insert(witin, Some(bits));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use super::RangeCheckSuggestion;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::ToExpr,
+    };
+
+    #[test]
+    fn test_suggest_range_checks() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "range_inference");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let [a, b, sum, x, lo, hi] =
+            ["a", "b", "sum", "x", "lo", "hi"].map(|name| cb.create_witin(|| name));
+        cb.declare_bits(&a, 8);
+        cb.declare_bits(&b, 8);
+        cb.declare_bits(&x, 16);
+        // `sum` is bounded by `a` and `b`, so its check is needless.
+        cb.require_equal(|| "sum", sum.expr(), a.expr() + b.expr())
+            .unwrap();
+        cb.assert_ux::<_, _, 16>(|| "sum", sum.expr()).unwrap();
+        // `x` is decomposed into two unchecked bytes.
+        cb.require_equal(|| "x", x.expr(), lo.expr() + (hi.expr() << 8))
+            .unwrap();
+
+        let names = cb.cs.witin_namespace_map.clone();
+        let suggestion = |witin: u16, bits, checked_bits| RangeCheckSuggestion {
+            witin,
+            name: names[witin as usize].clone(),
+            bits,
+            checked_bits,
+        };
+        let needless = suggestion(sum.id, None, Some(16));
+        assert_eq!(cb.cs.suggest_range_checks(), vec![
+            needless.clone(),
+            suggestion(lo.id, Some(8), None),
+            suggestion(hi.id, Some(8), None),
+        ]);
+
+        assert_eq!(cb.insert_range_checks().unwrap(), vec![needless.clone()]);
+        assert_eq!(cb.cs.inferred_range_checks, vec![(lo.id, 8), (hi.id, 8)]);
+        assert_eq!(cb.cs.suggest_range_checks(), vec![needless]);
+    }
+
+    /// The opcode circuits with their inferred range checks accept a real trace, with the
+    /// lookups of the checks counted by the assignment.
+    #[cfg(feature = "auto_range_checks")]
+    #[test]
+    fn test_run_trace_with_inferred_range_checks() {
+        use ceno_emul::{
+            CENO_PLATFORM, InsnKind, PC_WORD_SIZE, Program, StepRecord, Tracer, VMState,
+            encode_rv32,
+        };
+        use itertools::enumerate;
+
+        use crate::{
+            instructions::riscv::{DummyExtraConfig, Rv32imConfig},
+            scheme::{
+                PublicValues,
+                mock_prover::{MOCK_PC_START, MockProver},
+            },
+            structs::ZKVMConstraintSystem,
+        };
+
+        type E = GoldilocksExt2;
+        let programs = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 1, 5),
+            encode_rv32(InsnKind::ADDI, 0, 0, 2, 3),
+            encode_rv32(InsnKind::ADD, 1, 2, 3, 0),
+            encode_rv32(InsnKind::SUB, 2, 1, 4, 0),
+            encode_rv32(InsnKind::MUL, 1, 4, 5, 0),
+            encode_rv32(InsnKind::SLTU, 4, 1, 6, 0),
+            encode_rv32(InsnKind::XOR, 1, 4, 7, 0),
+            encode_rv32(InsnKind::SRLI, 4, 0, 8, 3),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let program = Program::new(
+            MOCK_PC_START.0,
+            MOCK_PC_START.0,
+            programs.clone(),
+            enumerate(&programs)
+                .map(|(i, insn)| (MOCK_PC_START.0 + (i * PC_WORD_SIZE) as u32, *insn))
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let trace = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .unwrap();
+        let end_cycle = vm.tracer().cycle() as u32;
+
+        let mut cs = ZKVMConstraintSystem::default();
+        let config = Rv32imConfig::<E>::construct_circuits(&mut cs);
+        let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut cs);
+        assert!(
+            cs.get_css()
+                .values()
+                .any(|cs| !cs.inferred_range_checks.is_empty())
+        );
+        let pi = PublicValues::new(
+            0,
+            MOCK_PC_START.0,
+            Tracer::SUBCYCLES_PER_INSN as u32,
+            0,
+            end_cycle,
+            vec![0],
+            vec![0],
+        );
+        assert!(MockProver::run_trace(&cs, &config, &dummy_config, &programs, &pi, &trace).is_ok());
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     error::ZKVMError,
     expression::{Expression, ToExpr},
     gadgets::AssertLTConfig,
-    instructions::riscv::constants::UINT_LIMBS,
+    instructions::riscv::constants::TS_LIMBS,
     structs::RAMType,
 };
 
@@ -48,7 +48,7 @@ impl<'a, E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR> RegisterChipOpe
                 || "prev_ts < ts",
                 prev_ts,
                 ts.clone(),
-                UINT_LIMBS,
+                TS_LIMBS,
             )?;
 
             let next_ts = ts + 1;
@@ -92,7 +92,7 @@ impl<'a, E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR> RegisterChipOpe
                 || "prev_ts < ts",
                 prev_ts,
                 ts.clone(),
-                UINT_LIMBS,
+                TS_LIMBS,
             )?;
 
             let next_ts = ts + 1;
@@ -1,6 +1,7 @@
 use ceno_emul::Addr;
 use itertools::{Itertools, chain};
-use std::{collections::HashMap, iter::once, marker::PhantomData};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{collections::BTreeMap, iter::once, marker::PhantomData, panic::Location};
 
 use ff_ext::ExtensionField;
 use mpcs::PolynomialCommitmentScheme;
@@ -10,12 +11,14 @@ use crate::{
     chip_handler::utils::rlc_chip_record,
     error::ZKVMError,
     expression::{Expression, Fixed, Instance, WitIn},
-    structs::{ProgramParams, ProvingKey, RAMType, VerifyingKey, WitnessId},
+    scheme::constants::MAX_CONSTRAINT_DEGREE,
+    structs::{CustomTableId, ProgramParams, ProvingKey, RAMType, VerifyingKey, WitnessId},
+    tables::{CustomTable, CustomTableSpec},
     witness::RowMajorMatrix,
 };
 
 /// namespace used for annotation, preserve meta info during circuit construction
-#[derive(Clone, Debug, Default, serde::Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct NameSpace {
     namespace: Vec<String>,
 }
@@ -64,9 +67,26 @@ impl NameSpace {
     pub fn get_namespaces(&self) -> &[String] {
         &self.namespace
     }
+
+    /// The path of the current scope, e.g. `root/beq/lt`.
+    pub fn path(&self) -> String {
+        self.namespace.join("/")
+    }
+}
+
+/// Whether `path` lies in the subtree of `scope`, i.e. the segments of `scope` appear in `path`
+/// as consecutive segments. Unlike a substring match, `lt` is not in the scope `l`.
+pub fn in_scope(path: &str, scope: &str) -> bool {
+    let path = path.split('/').collect_vec();
+    let scope = scope.split('/').collect_vec();
+    path.windows(scope.len()).any(|window| window == scope)
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct LogupTableExpression<E: ExtensionField> {
     pub multiplicity: Expression<E>,
     pub values: Expression<E>,
@@ -75,25 +95,29 @@ pub struct LogupTableExpression<E: ExtensionField> {
 
 // TODO encapsulate few information of table spec to SetTableAddrType value
 // once confirm syntax is friendly and parsed by recursive verifier
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SetTableAddrType {
     FixedAddr,
     DynamicAddr(DynamicAddr),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DynamicAddr {
     pub addr_witin_id: usize,
     pub offset: Addr,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SetTableSpec {
     pub addr_type: SetTableAddrType,
     pub len: usize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct SetTableExpression<E: ExtensionField> {
     pub expr: Expression<E>,
 
@@ -102,7 +126,11 @@ pub struct SetTableExpression<E: ExtensionField> {
     pub table_spec: SetTableSpec,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ConstraintSystem<E: ExtensionField> {
     pub(crate) ns: NameSpace,
 
@@ -113,7 +141,7 @@ pub struct ConstraintSystem<E: ExtensionField> {
     pub num_fixed: usize,
     pub fixed_namespace_map: Vec<String>,
 
-    pub instance_name_map: HashMap<Instance, String>,
+    pub instance_name_map: BTreeMap<Instance, String>,
 
     pub r_expressions: Vec<Expression<E>>,
     pub r_expressions_namespace_map: Vec<String>,
@@ -149,13 +177,26 @@ pub struct ConstraintSystem<E: ExtensionField> {
 
     /// max zero sumcheck degree
     pub max_non_lc_degree: usize,
+    /// zero constraints above this degree are rejected by `require_zero`
+    pub max_constraint_degree: usize,
 
     // alpha, beta challenge for chip record
     pub chip_record_alpha: Expression<E>,
     pub chip_record_beta: Expression<E>,
 
-    pub debug_map: HashMap<usize, Vec<Expression<E>>>,
+    pub debug_map: BTreeMap<usize, Vec<Expression<E>>>,
     pub lk_expressions_items_map: Vec<(ROMType, Vec<Expression<E>>)>,
+    /// the declared bit widths of the witnesses, see `CircuitBuilder::declare_bits`
+    pub witin_bits: BTreeMap<WitnessId, usize>,
+    /// the range checks added by `CircuitBuilder::insert_range_checks`, as witness and bits,
+    /// which the witness assignment counts on top of the instructions' own lookups
+    pub inferred_range_checks: Vec<(WitnessId, usize)>,
+    /// the custom tables looked up or defined by this circuit, for the mock prover
+    #[serde(skip)]
+    pub custom_tables: BTreeMap<CustomTableId, CustomTableSpec>,
+    /// where each scope was first opened, by path
+    #[serde(skip)]
+    pub scope_sites: BTreeMap<String, &'static Location<'static>>,
 
     pub(crate) phantom: PhantomData<E>,
 }
@@ -169,7 +210,7 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             num_fixed: 0,
             fixed_namespace_map: vec![],
             ns: NameSpace::new(root_name_fn),
-            instance_name_map: HashMap::new(),
+            instance_name_map: BTreeMap::new(),
             r_expressions: vec![],
             r_expressions_namespace_map: vec![],
             r_ram_types: vec![],
@@ -189,11 +230,16 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             assert_zero_sumcheck_expressions: vec![],
             assert_zero_sumcheck_expressions_namespace_map: vec![],
             max_non_lc_degree: 0,
+            max_constraint_degree: MAX_CONSTRAINT_DEGREE,
             chip_record_alpha: Expression::Challenge(0, 1, E::ONE, E::ZERO),
             chip_record_beta: Expression::Challenge(1, 1, E::ONE, E::ZERO),
 
-            debug_map: HashMap::new(),
+            debug_map: BTreeMap::new(),
             lk_expressions_items_map: vec![],
+            witin_bits: BTreeMap::new(),
+            inferred_range_checks: vec![],
+            custom_tables: BTreeMap::new(),
+            scope_sites: BTreeMap::new(),
 
             phantom: std::marker::PhantomData,
         }
@@ -280,9 +326,11 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         record: Vec<Expression<E>>,
     ) -> Result<(), ZKVMError> {
         let rlc_record = self.rlc_chip_record(
-            std::iter::once(Expression::Constant(E::BaseField::from(rom_type as u64)))
-                .chain(record.clone())
-                .collect(),
+            std::iter::once(Expression::Constant(E::BaseField::from(
+                rom_type.id() as u64
+            )))
+            .chain(record.clone())
+            .collect(),
         );
         assert_eq!(
             rlc_record.degree(),
@@ -299,6 +347,19 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         Ok(())
     }
 
+    /// Register the custom table `T` under its id, which no other table may take.
+    pub fn register_custom_table<T: CustomTable>(&mut self) {
+        let spec = self.custom_tables.entry(T::ID).or_insert_with(T::spec);
+        assert_eq!(
+            spec.name,
+            T::NAME,
+            "custom tables {} and {} share the id {}",
+            spec.name,
+            T::NAME,
+            T::ID
+        );
+    }
+
     pub fn lk_table_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -312,7 +373,7 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         N: FnOnce() -> NR,
     {
         let rlc_record = self.rlc_chip_record(
-            vec![(rom_type as usize).into()]
+            vec![rom_type.id().into()]
                 .into_iter()
                 .chain(record.clone())
                 .collect_vec(),
@@ -442,13 +503,17 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         name_fn: N,
         assert_zero_expr: Expression<E>,
     ) -> Result<(), ZKVMError> {
-        assert!(
-            assert_zero_expr.degree() > 0,
-            "constant expression assert to zero ?"
-        );
-        if assert_zero_expr.degree() == 1 {
+        let degree = assert_zero_expr.degree();
+        assert!(degree > 0, "constant expression assert to zero ?");
+        let path = self.ns.compute_path(name_fn().into());
+        if degree > self.max_constraint_degree {
+            return Err(ZKVMError::DegreeTooHigh(format!(
+                "{path}: constraint degree {degree} exceeds max {}",
+                self.max_constraint_degree
+            )));
+        }
+        if degree == 1 {
             self.assert_zero_expressions.push(assert_zero_expr);
-            let path = self.ns.compute_path(name_fn().into());
             self.assert_zero_expressions_namespace_map.push(path);
         } else {
             let assert_zero_expr = if assert_zero_expr.is_monomial_form() {
@@ -460,23 +525,117 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             };
             self.max_non_lc_degree = self.max_non_lc_degree.max(assert_zero_expr.degree());
             self.assert_zero_sumcheck_expressions.push(assert_zero_expr);
-            let path = self.ns.compute_path(name_fn().into());
             self.assert_zero_sumcheck_expressions_namespace_map
                 .push(path);
         }
         Ok(())
     }
 
+    #[track_caller]
     pub fn namespace<NR: Into<String>, N: FnOnce() -> NR, T>(
         &mut self,
         name_fn: N,
         cb: impl FnOnce(&mut ConstraintSystem<E>) -> T,
     ) -> T {
-        self.ns.push_namespace(name_fn().into());
+        self.scope(name_fn(), cb)
+    }
+
+    /// Run `cb` in the child scope `name` of the current one. The names of the witnesses and
+    /// constraints created inside are prefixed by its path, and the caller is recorded as the
+    /// creation site of the scope.
+    #[track_caller]
+    pub fn scope<T>(
+        &mut self,
+        name: impl Into<String>,
+        cb: impl FnOnce(&mut ConstraintSystem<E>) -> T,
+    ) -> T {
+        self.ns.push_namespace(name.into());
+        self.scope_sites
+            .entry(self.ns.path())
+            .or_insert_with(Location::caller);
         let t = cb(self);
         self.ns.pop_namespace();
         t
     }
+
+    /// A stable, line-based listing of the witnesses and constraints, with the
+    /// expressions in the text format of [`Expression::to_text`], for snapshot
+    /// tests and diffs. Every line starts with its kind, then the name.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![];
+        for (id, name) in self.witin_namespace_map.iter().enumerate() {
+            lines.push(format!("witin {id} {name:?}"));
+        }
+        for (id, name) in self.fixed_namespace_map.iter().enumerate() {
+            lines.push(format!("fixed {id} {name:?}"));
+        }
+        for (Instance(id), name) in &self.instance_name_map {
+            lines.push(format!("instance {id} {name:?}"));
+        }
+        let mut push_exprs = |kind: &str, exprs: &[Expression<E>], names: &[String]| {
+            for (expr, name) in exprs.iter().zip_eq(names) {
+                lines.push(format!("{kind} {name:?} {}", expr.to_text()));
+            }
+        };
+        push_exprs(
+            "read",
+            &self.r_expressions,
+            &self.r_expressions_namespace_map,
+        );
+        push_exprs(
+            "write",
+            &self.w_expressions,
+            &self.w_expressions_namespace_map,
+        );
+        push_exprs(
+            "lookup",
+            &self.lk_expressions,
+            &self.lk_expressions_namespace_map,
+        );
+        push_exprs(
+            "assert_zero",
+            &self.assert_zero_expressions,
+            &self.assert_zero_expressions_namespace_map,
+        );
+        push_exprs(
+            "assert_zero_sumcheck",
+            &self.assert_zero_sumcheck_expressions,
+            &self.assert_zero_sumcheck_expressions_namespace_map,
+        );
+        for (kind, tables, names) in [
+            (
+                "read_table",
+                &self.r_table_expressions,
+                &self.r_table_expressions_namespace_map,
+            ),
+            (
+                "write_table",
+                &self.w_table_expressions,
+                &self.w_table_expressions_namespace_map,
+            ),
+        ] {
+            for (table, name) in tables.iter().zip_eq(names) {
+                lines.push(format!(
+                    "{kind} {name:?} {} {}",
+                    table.table_spec.len,
+                    table.expr.to_text()
+                ));
+            }
+        }
+        for (table, name) in self
+            .lk_table_expressions
+            .iter()
+            .zip_eq(&self.lk_table_expressions_namespace_map)
+        {
+            lines.push(format!(
+                "lookup_table {name:?} {} {} {}",
+                table.table_len,
+                table.multiplicity.to_text(),
+                table.values.to_text()
+            ));
+        }
+        lines.join("\n")
+    }
 }
 
 #[cfg(test)]
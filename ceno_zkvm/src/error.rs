@@ -11,9 +11,11 @@ pub enum ZKVMError {
     UtilError(UtilError),
     WitnessNotFound(String),
     InvalidWitness(String),
+    DegreeTooHigh(String),
     VKNotFound(String),
     FixedTraceNotFound(String),
     VerifyError(String),
+    ParseError(String),
     PCSError(Error),
 }
 
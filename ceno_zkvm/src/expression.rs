@@ -1,4 +1,8 @@
+mod interval;
 mod monomial;
+mod text;
+
+pub use interval::Interval;
 
 use std::{
     cmp::max,
@@ -14,6 +18,7 @@ use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 
 use multilinear_extensions::virtual_poly_v2::ArcMultilinearExtension;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
     circuit_builder::CircuitBuilder,
@@ -21,7 +26,11 @@ use crate::{
     structs::{ChallengeId, RAMType, WitnessId},
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub enum Expression<E: ExtensionField> {
     /// WitIn(Id)
     WitIn(WitnessId),
@@ -726,10 +735,10 @@ pub struct WitIn {
     pub id: WitnessId,
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Fixed(pub usize);
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Instance(pub usize);
 
 impl WitIn {
@@ -1005,11 +1014,37 @@ pub mod fmt {
 mod tests {
     use goldilocks::GoldilocksExt2;
 
-    use crate::circuit_builder::{CircuitBuilder, ConstraintSystem};
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        error::ZKVMError,
+    };
 
     use super::{Expression, ToExpr, fmt};
     use ff::Field;
 
+    #[test]
+    fn test_degree_bound() {
+        type E = GoldilocksExt2;
+        let mut cs = ConstraintSystem::new(|| "test_root");
+        let mut cb = CircuitBuilder::<E>::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let cubic: Expression<E> = x.expr() * x.expr() * x.expr();
+        assert_eq!(cubic.degree(), 3);
+
+        let err = cb
+            .namespace(|| "cube", |cb| cb.require_zero(|| "x^3", cubic.clone()))
+            .unwrap_err();
+        match err {
+            ZKVMError::DegreeTooHigh(msg) => assert!(msg.contains("cube"), "{msg}"),
+            e => panic!("unexpected error {e:?}"),
+        }
+        assert!(cb.cs.assert_zero_sumcheck_expressions.is_empty());
+
+        cb.cs.max_constraint_degree = 3;
+        cb.require_zero(|| "x^3", cubic).unwrap();
+        assert_eq!(cb.cs.max_non_lc_degree, 3);
+    }
+
     #[test]
     fn test_expression_arithmetics() {
         type E = GoldilocksExt2;
@@ -0,0 +1,116 @@
+//! Interval analysis of expressions: the integers an expression takes, given the integers its
+//! witnesses take, as long as no intermediate value wraps around the modulus.
+
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Mul, Neg},
+};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use super::Expression;
+use crate::structs::WitnessId;
+
+/// The integers from `lo` to `hi`, inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: i128,
+    pub hi: i128,
+}
+
+impl Interval {
+    pub fn point(v: i128) -> Self {
+        Interval { lo: v, hi: v }
+    }
+
+    /// The unsigned integers of `bits` bits.
+    pub fn bits(bits: usize) -> Self {
+        Interval {
+            lo: 0,
+            hi: (1 << bits) - 1,
+        }
+    }
+
+    /// The number of bits of the largest magnitude in the interval.
+    pub fn num_bits(&self) -> usize {
+        (128 - self.magnitude().leading_zeros()) as usize
+    }
+
+    fn magnitude(&self) -> u128 {
+        self.lo.unsigned_abs().max(self.hi.unsigned_abs())
+    }
+
+    /// Whether the field elements of the interval are distinct, i.e. it spans less than half of
+    /// the field on either side of zero.
+    fn fits<F: SmallField>(&self) -> bool {
+        self.magnitude() <= (F::MODULUS_U64 / 2) as u128
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+    fn add(self, rhs: Interval) -> Interval {
+        Interval {
+            lo: self.lo + rhs.lo,
+            hi: self.hi + rhs.hi,
+        }
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+    fn mul(self, rhs: Interval) -> Interval {
+        let products = [
+            self.lo * rhs.lo,
+            self.lo * rhs.hi,
+            self.hi * rhs.lo,
+            self.hi * rhs.hi,
+        ];
+        Interval {
+            lo: *products.iter().min().unwrap(),
+            hi: *products.iter().max().unwrap(),
+        }
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+    fn neg(self) -> Interval {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+/// The integer of a constant, taking the upper half of the field as the negative integers.
+fn signed<F: SmallField>(c: F) -> i128 {
+    let v = c.to_canonical_u64();
+    if v > F::MODULUS_U64 / 2 {
+        v as i128 - F::MODULUS_U64 as i128
+    } else {
+        v as i128
+    }
+}
+
+impl<E: ExtensionField> Expression<E> {
+    /// The interval of the expression given the intervals of the witnesses in `bounds`, or `None`
+    /// if it depends on an unbounded witness, a fixed column, an instance or a challenge, or if
+    /// it could wrap around the modulus.
+    pub fn interval(&self, bounds: &BTreeMap<WitnessId, Interval>) -> Option<Interval> {
+        let interval = match self {
+            Expression::WitIn(id) => *bounds.get(id)?,
+            Expression::Constant(c) => Interval::point(signed(*c)),
+            Expression::Sum(a, b) => a.interval(bounds)? + b.interval(bounds)?,
+            Expression::Product(a, b) => a.interval(bounds)? * b.interval(bounds)?,
+            Expression::ScaledSum(x, a, b) => {
+                x.interval(bounds)? * a.interval(bounds)? + b.interval(bounds)?
+            }
+            Expression::Fixed(_) | Expression::Instance(_) | Expression::Challenge(..) => {
+                return None;
+            }
+        };
+        interval.fits::<E::BaseField>().then_some(interval)
+    }
+}
@@ -1,15 +1,23 @@
 use ff_ext::ExtensionField;
-use itertools::{Itertools, chain, iproduct};
+use itertools::{chain, iproduct};
 
 use super::Expression;
 use Expression::*;
-use std::iter::Sum;
+use std::{collections::BTreeMap, iter::Sum};
 
 impl<E: ExtensionField> Expression<E> {
     pub(super) fn to_monomial_form_inner(&self) -> Self {
         Self::combine(self.distribute()).into_iter().sum()
     }
 
+    /// The terms of the monomial form, as their coefficients and their sorted variables.
+    pub(crate) fn monomial_terms(&self) -> Vec<(Expression<E>, Vec<Expression<E>>)> {
+        Self::combine(self.distribute())
+            .into_iter()
+            .map(|Term { coeff, vars }| (coeff, vars))
+            .collect()
+    }
+
     fn distribute(&self) -> Vec<Term<E>> {
         match self {
             Constant(_) => {
@@ -46,18 +54,23 @@ impl<E: ExtensionField> Expression<E> {
         }
     }
 
-    fn combine(mut terms: Vec<Term<E>>) -> Vec<Term<E>> {
-        for Term { vars, .. } in &mut terms {
+    /// Merges the terms with the same variables. The sorted variables are the key of a term, and
+    /// the terms come out in the order of their keys, so that equal expressions up to the order
+    /// of their factors and summands get the same monomial form.
+    fn combine(terms: Vec<Term<E>>) -> Vec<Term<E>> {
+        let mut combined = BTreeMap::<Vec<Expression<E>>, Vec<Expression<E>>>::new();
+        for Term { coeff, mut vars } in terms {
             vars.sort();
+            combined.entry(vars).or_default().push(coeff);
         }
-        terms
-            .into_iter()
-            .map(|Term { coeff, vars }| (vars, coeff))
-            .into_group_map()
+        combined
             .into_iter()
-            .map(|(vars, coeffs)| Term {
-                coeff: coeffs.into_iter().sum(),
-                vars,
+            .map(|(vars, mut coeffs)| {
+                coeffs.sort();
+                Term {
+                    coeff: coeffs.into_iter().sum(),
+                    vars,
+                }
             })
             .collect()
     }
@@ -127,6 +140,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_monomial_form_is_canonical() {
+        use Expression::*;
+
+        let a = || Fixed(FixedS(0));
+        let x = || WitIn(0);
+        let y = || WitIn(1);
+        let n = || Constant(104.into());
+        let r = || Challenge(0, 1, E::from(1), E::from(0));
+
+        let pairs: [(Expression<E>, Expression<E>); 3] = [
+            (x() * y() + a(), a() + y() * x()),
+            ((x() + r()) * (y() + n()), (n() + y()) * (r() + x())),
+            (n() * x() + r() * x() + y(), y() + x() * r() + x() * n()),
+        ];
+        for (lhs, rhs) in pairs {
+            assert_eq!(lhs.to_monomial_form(), rhs.to_monomial_form());
+        }
+    }
+
     /// Create an evaluator of expressions. Fixed, witness, and challenge values are pseudo-random.
     fn make_eval() -> impl Fn(&Expression<E>) -> E {
         // Create a deterministic RNG from a seed.
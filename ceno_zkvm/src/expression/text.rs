@@ -0,0 +1,263 @@
+//! A stable text format for expressions, which keeps their exact structure,
+//! as opposed to the [`Display`](std::fmt::Display) format meant for debugging.
+//!
+//! Every node is an s-expression:
+//! - `(wit 3)`, `(fixed 1)`, `(instance 0)`
+//! - `(const 5)`, with the canonical value of the base field element
+//! - `(sum a b)`, `(product a b)`, `(scaled x a b)` for `a * x + b`
+//! - `(challenge 0 2 [1 0] [0 0])` for `Challenge(id, power, scalar, offset)`,
+//!   with the base field limbs of the extension field elements.
+
+use std::fmt::Write;
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use super::{Expression, Fixed, Instance};
+use crate::error::ZKVMError;
+
+impl<E: ExtensionField> Expression<E> {
+    /// Write in the stable text format, which [`Self::parse`] reads back exactly.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        write_expr(self, &mut text);
+        text
+    }
+
+    /// Read an expression in the stable text format of [`Self::to_text`].
+    pub fn parse(text: &str) -> Result<Self, ZKVMError> {
+        let mut parser = Parser {
+            tokens: tokenize(text),
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(parser.error(format!("unexpected trailing {token:?}"))),
+        }
+    }
+}
+
+fn write_expr<E: ExtensionField>(expr: &Expression<E>, text: &mut String) {
+    match expr {
+        Expression::WitIn(id) => write!(text, "(wit {id})").unwrap(),
+        Expression::Fixed(Fixed(id)) => write!(text, "(fixed {id})").unwrap(),
+        Expression::Instance(Instance(id)) => write!(text, "(instance {id})").unwrap(),
+        Expression::Constant(c) => write!(text, "(const {})", c.to_canonical_u64()).unwrap(),
+        Expression::Sum(a, b) | Expression::Product(a, b) => {
+            text.push_str(match expr {
+                Expression::Sum(..) => "(sum ",
+                _ => "(product ",
+            });
+            write_expr(a, text);
+            text.push(' ');
+            write_expr(b, text);
+            text.push(')');
+        }
+        Expression::ScaledSum(x, a, b) => {
+            text.push_str("(scaled ");
+            write_expr(x, text);
+            text.push(' ');
+            write_expr(a, text);
+            text.push(' ');
+            write_expr(b, text);
+            text.push(')');
+        }
+        Expression::Challenge(id, pow, scalar, offset) => {
+            write!(text, "(challenge {id} {pow} ").unwrap();
+            write_ext(scalar, text);
+            text.push(' ');
+            write_ext(offset, text);
+            text.push(')');
+        }
+    }
+}
+
+fn write_ext<E: ExtensionField>(value: &E, text: &mut String) {
+    text.push('[');
+    for (i, limb) in value.as_bases().iter().enumerate() {
+        if i > 0 {
+            text.push(' ');
+        }
+        write!(text, "{}", limb.to_canonical_u64()).unwrap();
+    }
+    text.push(']');
+}
+
+/// Split into parentheses, brackets and the atoms between them.
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let is_delimiter = matches!(c, '(' | ')' | '[' | ']');
+        if is_delimiter || c.is_whitespace() {
+            if let Some(start) = start.take() {
+                tokens.push(&text[start..i]);
+            }
+            if is_delimiter {
+                tokens.push(&text[i..i + 1]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(start) = start {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: String) -> ZKVMError {
+        ZKVMError::ParseError(format!("at token {}: {msg}", self.pos))
+    }
+
+    fn next(&mut self) -> Result<&'a str, ZKVMError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| self.error("unexpected end of input".to_string()))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ZKVMError> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(self.error(format!("expected {expected:?}, found {token:?}"))),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, ZKVMError> {
+        let token = self.next()?;
+        token
+            .parse()
+            .map_err(|_| self.error(format!("expected a number, found {token:?}")))
+    }
+
+    fn base<F: SmallField>(&mut self) -> Result<F, ZKVMError> {
+        let value: u64 = self.number()?;
+        if value >= F::MODULUS_U64 {
+            return Err(self.error(format!("{value} is not a canonical field element")));
+        }
+        Ok(F::from(value))
+    }
+
+    fn ext<E: ExtensionField>(&mut self) -> Result<E, ZKVMError> {
+        self.expect("[")?;
+        let limbs = (0..E::DEGREE)
+            .map(|_| self.base())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.expect("]")?;
+        Ok(E::from_bases(&limbs))
+    }
+
+    fn expr<E: ExtensionField>(&mut self) -> Result<Expression<E>, ZKVMError> {
+        self.expect("(")?;
+        let expr = match self.next()? {
+            "wit" => Expression::WitIn(self.number()?),
+            "fixed" => Expression::Fixed(Fixed(self.number()?)),
+            "instance" => Expression::Instance(Instance(self.number()?)),
+            "const" => Expression::Constant(self.base()?),
+            "sum" => Expression::Sum(Box::new(self.expr()?), Box::new(self.expr()?)),
+            "product" => Expression::Product(Box::new(self.expr()?), Box::new(self.expr()?)),
+            "scaled" => Expression::ScaledSum(
+                Box::new(self.expr()?),
+                Box::new(self.expr()?),
+                Box::new(self.expr()?),
+            ),
+            "challenge" => {
+                Expression::Challenge(self.number()?, self.number()?, self.ext()?, self.ext()?)
+            }
+            tag => return Err(self.error(format!("unknown expression {tag:?}"))),
+        };
+        self.expect(")")?;
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::{Expression, Fixed, Instance, ToExpr},
+    };
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut cs = ConstraintSystem::new(|| "test_root");
+        let mut cb = CircuitBuilder::<E>::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let y = cb.create_witin(|| "y");
+
+        let expr: Expression<E> =
+            (Expression::Challenge(1, 2, 3.into(), -E::from(4)) + 5) * x.expr() * y.expr()
+                - 6 * Expression::Fixed(Fixed(2))
+                + Expression::Instance(Instance(0));
+        let text = expr.to_text();
+        assert_eq!(Expression::<E>::parse(&text).unwrap(), expr);
+
+        assert_eq!(
+            (3 * x.expr() + 1).to_text(),
+            "(scaled (wit 0) (const 3) (const 1))"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for text in [
+            "",
+            "(wit 0",
+            "(wit 0) (wit 1)",
+            "(witness 0)",
+            "(const 18446744073709551615)",
+            "(challenge 0 1 [1] [0 0])",
+        ] {
+            assert!(Expression::<E>::parse(text).is_err(), "{text}");
+        }
+        assert_eq!(
+            Expression::<E>::parse(" ( sum(wit 0)\n(const 1) ) ").unwrap(),
+            Expression::Sum(
+                Box::new(Expression::WitIn(0)),
+                Box::new(Expression::Constant(1.into()))
+            )
+        );
+    }
+
+    #[test]
+    fn test_constraint_system_text() {
+        let mut cs = ConstraintSystem::new(|| "test_root");
+        let mut cb = CircuitBuilder::<E>::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let pc = cb.query_init_pc().unwrap();
+        cb.require_zero(|| "x_is_pc", x.expr() - pc.expr()).unwrap();
+
+        let text = cs.to_text();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "witin 0 \"test_root/x\"");
+        assert!(lines[1].starts_with("instance ") && lines[1].ends_with(" \"init_pc\""));
+        let expr = lines[2]
+            .strip_prefix("assert_zero \"test_root/x_is_pc\" ")
+            .unwrap();
+        assert_eq!(
+            Expression::<E>::parse(expr).unwrap(),
+            cs.assert_zero_expressions[0]
+        );
+
+        // The JSON format keeps everything, in a deterministic order.
+        let json = serde_json::to_string(&cs).unwrap();
+        let decoded: ConstraintSystem<E> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.to_text(), text);
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+}
@@ -1,6 +1,6 @@
 use std::mem::MaybeUninit;
 
-use ff_ext::ExtensionField;
+use ff_ext::{ExtensionField, PowConst};
 use goldilocks::SmallField;
 
 use crate::{
@@ -49,7 +49,7 @@ impl IsZeroConfig {
                 let is_zero = cb.create_witin(|| "is_zero");
 
                 // x!=0 => is_zero=0
-                cb.require_zero(|| "is_zero_0", is_zero.expr() * x.clone())?;
+                cb.require_zero_if(|| "is_zero_0", is_zero.expr(), x.clone())?;
 
                 (Some(is_zero), is_zero.expr())
             };
@@ -70,7 +70,7 @@ impl IsZeroConfig {
         let (is_zero, inverse) = if x.is_zero_vartime() {
             (F::ONE, F::ZERO)
         } else {
-            (F::ZERO, x.invert().expect("not zero"))
+            (F::ZERO, x.inverse().expect("not zero"))
         };
 
         if let Some(wit) = self.is_zero {
@@ -0,0 +1,378 @@
+use std::{array, mem::MaybeUninit};
+
+use ff_ext::ExtensionField;
+use itertools::{Itertools, izip};
+use mpcs::util::hash::Digest;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    witness::LkMultiplicity,
+};
+
+/// Number of base field elements in a Merkle node, see `mpcs::util::hash::Digest`.
+pub const DIGEST_WIDTH: usize = 4;
+
+/// In-circuit counterpart of the hashers used by `mpcs::util::merkle_tree::MerkleTree`.
+///
+/// A config is constructed once per hash invocation, and its `assign_*` functions must
+/// return the same digest as the native `mpcs::util::hash` function it mirrors.
+pub trait MerkleHasherConfig<E: ExtensionField>: Sized {
+    /// Hash a pair of sibling leaves, mirroring `hash_two_leaves_{base,ext}`.
+    /// Extension field leaves are passed as their base field limbs.
+    fn construct_leaf_hash(
+        cb: &mut CircuitBuilder<E>,
+        left: &[Expression<E>],
+        right: &[Expression<E>],
+    ) -> Result<Self, ZKVMError>;
+
+    /// Compress two child nodes into their parent, mirroring `hash_two_digests`.
+    fn construct_node_hash(
+        cb: &mut CircuitBuilder<E>,
+        left: &[Expression<E>; DIGEST_WIDTH],
+        right: &[Expression<E>; DIGEST_WIDTH],
+    ) -> Result<Self, ZKVMError>;
+
+    /// The output digest of this hash invocation.
+    fn digest(&self) -> [Expression<E>; DIGEST_WIDTH];
+
+    fn assign_leaf_hash(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        left: &[E::BaseField],
+        right: &[E::BaseField],
+    ) -> Result<Digest<E::BaseField>, ZKVMError>;
+
+    fn assign_node_hash(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        left: &Digest<E::BaseField>,
+        right: &Digest<E::BaseField>,
+    ) -> Result<Digest<E::BaseField>, ZKVMError>;
+}
+
+#[derive(Debug)]
+struct MerkleLevelConfig<H> {
+    /// bit of the leaf index selecting on which side the current node sits
+    index_bit: WitIn,
+    sibling: [WitIn; DIGEST_WIDTH],
+    left: [WitIn; DIGEST_WIDTH],
+    right: [WitIn; DIGEST_WIDTH],
+    hash: H,
+}
+
+/// Recompute a Merkle root from a leaf pair and a `MerklePathWithoutLeafOrRoot`.
+///
+/// The node ordering follows `authenticate_merkle_path_root`: the lowest bit of the
+/// leaf index is ignored, and at each level `index_bit = 0` means the current node is
+/// the left child.
+#[derive(Debug)]
+pub struct MerklePathConfig<H> {
+    left_leaf: Vec<WitIn>,
+    right_leaf: Vec<WitIn>,
+    leaf_hash: H,
+    levels: Vec<MerkleLevelConfig<H>>,
+    root: [WitIn; DIGEST_WIDTH],
+}
+
+impl<H> MerklePathConfig<H> {
+    pub fn root_expr<E: ExtensionField>(&self) -> [Expression<E>; DIGEST_WIDTH] {
+        self.root.map(|wit| wit.expr())
+    }
+
+    pub fn root(&self) -> &[WitIn; DIGEST_WIDTH] {
+        &self.root
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// `leaf_width` is the number of base field limbs per leaf, i.e. 1 for base field
+    /// trees and `E::DEGREE` for extension field trees.
+    /// `depth` is the length of the authentication path, i.e. `MerkleTree::height() - 1`.
+    pub fn construct_circuit<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+        leaf_width: usize,
+        depth: usize,
+    ) -> Result<Self, ZKVMError>
+    where
+        H: MerkleHasherConfig<E>,
+    {
+        cb.namespace(name_fn, |cb| {
+            let left_leaf = (0..leaf_width)
+                .map(|i| cb.create_witin(|| format!("left_leaf_{i}")))
+                .collect_vec();
+            let right_leaf = (0..leaf_width)
+                .map(|i| cb.create_witin(|| format!("right_leaf_{i}")))
+                .collect_vec();
+
+            let leaf_hash = cb.namespace(
+                || "leaf_hash",
+                |cb| {
+                    H::construct_leaf_hash(
+                        cb,
+                        &left_leaf.iter().map(|wit| wit.expr()).collect_vec(),
+                        &right_leaf.iter().map(|wit| wit.expr()).collect_vec(),
+                    )
+                },
+            )?;
+
+            let mut node = leaf_hash.digest();
+            let levels = (0..depth)
+                .map(|level| {
+                    cb.namespace(
+                        || format!("level_{level}"),
+                        |cb| {
+                            let index_bit = cb.create_witin(|| "index_bit");
+                            cb.assert_bit(|| "index_bit is boolean", index_bit.expr())?;
+                            let sibling: [WitIn; DIGEST_WIDTH] =
+                                array::from_fn(|i| cb.create_witin(|| format!("sibling_{i}")));
+
+                            // index_bit = 0 => hash(node, sibling)
+                            // index_bit = 1 => hash(sibling, node)
+                            let mut left = Vec::with_capacity(DIGEST_WIDTH);
+                            let mut right = Vec::with_capacity(DIGEST_WIDTH);
+                            for (i, (node, sibling)) in izip!(&node, &sibling).enumerate() {
+                                let when_left = cb.select(&index_bit.expr(), &sibling.expr(), node);
+                                let when_right =
+                                    cb.select(&index_bit.expr(), node, &sibling.expr());
+                                left.push(cb.flatten_expr(|| format!("left_{i}"), when_left)?);
+                                right.push(cb.flatten_expr(|| format!("right_{i}"), when_right)?);
+                            }
+                            let left: [WitIn; DIGEST_WIDTH] = left.try_into().unwrap();
+                            let right: [WitIn; DIGEST_WIDTH] = right.try_into().unwrap();
+
+                            let hash = H::construct_node_hash(
+                                cb,
+                                &left.map(|wit| wit.expr()),
+                                &right.map(|wit| wit.expr()),
+                            )?;
+                            node = hash.digest();
+
+                            Ok(MerkleLevelConfig {
+                                index_bit,
+                                sibling,
+                                left,
+                                right,
+                                hash,
+                            })
+                        },
+                    )
+                })
+                .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+            let root = array::from_fn(|i| cb.create_witin(|| format!("root_{i}")));
+            for (i, (root, node)) in izip!(&root, node).enumerate() {
+                cb.require_equal(|| format!("root_{i}"), root.expr(), node)?;
+            }
+
+            Ok(MerklePathConfig {
+                left_leaf,
+                right_leaf,
+                leaf_hash,
+                levels,
+                root,
+            })
+        })
+    }
+
+    /// Assign the leaf pair at `index` and its authentication path, as returned by
+    /// `MerkleTree::merkle_path_without_leaf_sibling_or_root`. Returns the recomputed root.
+    pub fn assign_instance<E: ExtensionField>(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        left_leaf: &[E::BaseField],
+        right_leaf: &[E::BaseField],
+        index: usize,
+        path: &[Digest<E::BaseField>],
+    ) -> Result<Digest<E::BaseField>, ZKVMError>
+    where
+        H: MerkleHasherConfig<E>,
+    {
+        assert_eq!(left_leaf.len(), self.left_leaf.len());
+        assert_eq!(right_leaf.len(), self.right_leaf.len());
+        assert_eq!(path.len(), self.levels.len());
+
+        for (wit, value) in izip!(&self.left_leaf, left_leaf) {
+            set_val!(instance, wit, *value);
+        }
+        for (wit, value) in izip!(&self.right_leaf, right_leaf) {
+            set_val!(instance, wit, *value);
+        }
+        let mut node =
+            self.leaf_hash
+                .assign_leaf_hash(instance, lk_multiplicity, left_leaf, right_leaf)?;
+
+        // The lowest bit in the index is ignored. It can point to either leaves
+        let mut index = index >> 1;
+        for (level, sibling) in izip!(&self.levels, path) {
+            let is_right = index & 1 == 1;
+            set_val!(instance, level.index_bit, is_right as u64);
+            for (wit, value) in izip!(&level.sibling, &sibling.0) {
+                set_val!(instance, wit, *value);
+            }
+            let (left, right) = if is_right {
+                (sibling, &node)
+            } else {
+                (&node, sibling)
+            };
+            for (wit, value) in izip!(&level.left, &left.0) {
+                set_val!(instance, wit, *value);
+            }
+            for (wit, value) in izip!(&level.right, &right.0) {
+                set_val!(instance, wit, *value);
+            }
+            node = level
+                .hash
+                .assign_node_hash(instance, lk_multiplicity, left, right)?;
+            index >>= 1;
+        }
+
+        for (wit, value) in izip!(&self.root, &node.0) {
+            set_val!(instance, wit, *value);
+        }
+
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+
+    use ff::Field;
+    use ff_ext::ExtensionField;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use itertools::Itertools;
+    use mpcs::util::{
+        hash::{Digest, hash_two_digests, hash_two_leaves_base},
+        merkle_tree::MerkleTree,
+    };
+    use multilinear_extensions::mle::FieldType;
+
+    use super::{DIGEST_WIDTH, MerkleHasherConfig, MerklePathConfig};
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        error::ZKVMError,
+        expression::{Expression, ToExpr, WitIn},
+        scheme::mock_prover::MockProver,
+        set_val,
+        witness::{LkMultiplicity, RowMajorMatrix},
+    };
+
+    /// Unconstrained digest cells filled with the native mpcs hashes, enough to
+    /// check the path selection and node ordering against a real `MerkleTree`.
+    struct OracleHasher {
+        digest: [WitIn; DIGEST_WIDTH],
+    }
+
+    impl OracleHasher {
+        fn construct<E: ExtensionField>(cb: &mut CircuitBuilder<E>) -> Self {
+            OracleHasher {
+                digest: std::array::from_fn(|i| cb.create_witin(|| format!("digest_{i}"))),
+            }
+        }
+
+        fn assign<F: Field>(&self, instance: &mut [MaybeUninit<F>], digest: &[F]) {
+            for (wit, value) in self.digest.iter().zip_eq(digest) {
+                set_val!(instance, wit, *value);
+            }
+        }
+    }
+
+    impl<E: ExtensionField> MerkleHasherConfig<E> for OracleHasher {
+        fn construct_leaf_hash(
+            cb: &mut CircuitBuilder<E>,
+            _left: &[Expression<E>],
+            _right: &[Expression<E>],
+        ) -> Result<Self, ZKVMError> {
+            Ok(Self::construct(cb))
+        }
+
+        fn construct_node_hash(
+            cb: &mut CircuitBuilder<E>,
+            _left: &[Expression<E>; DIGEST_WIDTH],
+            _right: &[Expression<E>; DIGEST_WIDTH],
+        ) -> Result<Self, ZKVMError> {
+            Ok(Self::construct(cb))
+        }
+
+        fn digest(&self) -> [Expression<E>; DIGEST_WIDTH] {
+            self.digest.map(|wit| wit.expr())
+        }
+
+        fn assign_leaf_hash(
+            &self,
+            instance: &mut [MaybeUninit<E::BaseField>],
+            _lk_multiplicity: &mut LkMultiplicity,
+            left: &[E::BaseField],
+            right: &[E::BaseField],
+        ) -> Result<Digest<E::BaseField>, ZKVMError> {
+            let digest = hash_two_leaves_base::<E>(&left[0], &right[0]);
+            self.assign(instance, &digest.0);
+            Ok(digest)
+        }
+
+        fn assign_node_hash(
+            &self,
+            instance: &mut [MaybeUninit<E::BaseField>],
+            _lk_multiplicity: &mut LkMultiplicity,
+            left: &Digest<E::BaseField>,
+            right: &Digest<E::BaseField>,
+        ) -> Result<Digest<E::BaseField>, ZKVMError> {
+            let digest = hash_two_digests(left, right);
+            self.assign(instance, &digest.0);
+            Ok(digest)
+        }
+    }
+
+    #[test]
+    fn test_merkle_path_matches_mpcs_tree() {
+        let num_vars = 4;
+        let leaves = (0..1u64 << num_vars).map(Goldilocks::from).collect_vec();
+        let tree = MerkleTree::<GoldilocksExt2>::from_leaves(FieldType::Base(leaves.clone()));
+
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "merkle_path");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = MerklePathConfig::<OracleHasher>::construct_circuit(
+            &mut cb,
+            || "path",
+            1,
+            tree.height() - 1,
+        )
+        .unwrap();
+
+        let indices = [0, 5, 10, (1 << num_vars) - 1];
+        let mut lkm = LkMultiplicity::default();
+        let mut raw_witin =
+            RowMajorMatrix::<Goldilocks>::new(indices.len(), cb.cs.num_witin as usize);
+        for (instance, index) in raw_witin.iter_mut().zip(indices) {
+            let path = tree
+                .merkle_path_without_leaf_sibling_or_root(index)
+                .iter()
+                .cloned()
+                .collect_vec();
+            let pair = index & !1;
+            let root = config
+                .assign_instance::<GoldilocksExt2>(
+                    instance,
+                    &mut lkm,
+                    &[leaves[pair]],
+                    &[leaves[pair + 1]],
+                    index,
+                    &path,
+                )
+                .unwrap();
+            assert_eq!(root, tree.root());
+        }
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[], None, Some(lkm));
+    }
+}
@@ -1,11 +1,17 @@
 mod div;
 mod is_lt;
 mod is_zero;
+mod merkle_path;
+mod poseidon_hasher;
 mod signed_ext;
+mod typed_witin;
 
 pub use div::DivConfig;
 pub use is_lt::{
     AssertLTConfig, AssertSignedLtConfig, InnerLtConfig, IsLtConfig, SignedLtConfig, cal_lt_diff,
 };
 pub use is_zero::{IsEqualConfig, IsZeroConfig};
+pub use merkle_path::{DIGEST_WIDTH, MerkleHasherConfig, MerklePathConfig};
+pub use poseidon_hasher::{PoseidonHasherConfig, PoseidonPermutationConfig};
 pub use signed_ext::SignedExtendConfig;
+pub use typed_witin::{BoolWit, U8Wit, U16Wit, UxWit};
@@ -0,0 +1,468 @@
+use std::{array, mem::MaybeUninit};
+
+use ff::Field;
+use ff_ext::ExtensionField;
+use itertools::{Itertools, izip};
+use mpcs::util::hash::Digest;
+use poseidon::{
+    ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_ROUNDS, SPONGE_RATE, SPONGE_WIDTH,
+    poseidon::Poseidon,
+};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{DIGEST_WIDTH, MerkleHasherConfig},
+    set_val,
+    witness::LkMultiplicity,
+};
+
+fn constant<F: Poseidon>(value: u64) -> F {
+    F::from_canonical_u64(value)
+}
+
+/// Whether the S-box applies to every lane of `round`, or only to the first one.
+fn is_full_round(round: usize) -> bool {
+    round < HALF_N_FULL_ROUNDS || round >= HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS
+}
+
+/// The entry of the MDS matrix multiplying the lane `col` into the lane `row`, see
+/// `Poseidon::mds_row_shf`.
+fn mds_entry<F: Poseidon>(row: usize, col: usize) -> F {
+    let circ = F::MDS_MATRIX_CIRC[(col + SPONGE_WIDTH - row) % SPONGE_WIDTH];
+    let diag = if row == col {
+        F::MDS_MATRIX_DIAG[row]
+    } else {
+        0
+    };
+    constant(circ + diag)
+}
+
+/// The S-box `x^7` of a lane, as `x^6 * x`, through witnesses of degree 2.
+#[derive(Debug)]
+struct SboxConfig {
+    x2: WitIn,
+    x4: WitIn,
+    x6: WitIn,
+}
+
+impl SboxConfig {
+    fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        x: &Expression<E>,
+    ) -> Result<Self, ZKVMError> {
+        let x2 = cb.create_witin(|| "x2");
+        let x4 = cb.create_witin(|| "x4");
+        let x6 = cb.create_witin(|| "x6");
+        cb.require_equal(|| "x2", x2.expr(), x.clone() * x.clone())?;
+        cb.require_equal(|| "x4", x4.expr(), x2.expr() * x2.expr())?;
+        cb.require_equal(|| "x6", x6.expr(), x2.expr() * x4.expr())?;
+        Ok(SboxConfig { x2, x4, x6 })
+    }
+
+    fn expr<E: ExtensionField>(&self, x: &Expression<E>) -> Expression<E> {
+        self.x6.expr() * x.clone()
+    }
+
+    fn assign_instance<F: Field>(&self, instance: &mut [MaybeUninit<F>], x: F) -> F {
+        let x2 = x.square();
+        let x4 = x2.square();
+        let x6 = x2 * x4;
+        set_val!(instance, self.x2, x2);
+        set_val!(instance, self.x4, x4);
+        set_val!(instance, self.x6, x6);
+        x6 * x
+    }
+}
+
+#[derive(Debug)]
+struct PoseidonRoundConfig {
+    /// One per lane in the full rounds, only the first lane in the partial rounds.
+    sboxes: Vec<SboxConfig>,
+    /// The state after the MDS layer of the round.
+    state: [WitIn; SPONGE_WIDTH],
+}
+
+/// The Poseidon permutation of `poseidon::poseidon::Poseidon::poseidon`, round by round:
+/// the partial rounds are proven in their plain form, not the fast one of the native code,
+/// which computes the same permutation.
+#[derive(Debug)]
+pub struct PoseidonPermutationConfig {
+    rounds: Vec<PoseidonRoundConfig>,
+}
+
+impl PoseidonPermutationConfig {
+    pub fn construct_circuit<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+        input: [Expression<E>; SPONGE_WIDTH],
+    ) -> Result<Self, ZKVMError> {
+        cb.namespace(name_fn, |cb| {
+            let mut state = input;
+            let rounds = (0..N_ROUNDS)
+                .map(|round| {
+                    cb.namespace(
+                        || format!("round_{round}"),
+                        |cb| {
+                            let num_sboxes = if is_full_round(round) {
+                                SPONGE_WIDTH
+                            } else {
+                                1
+                            };
+                            let x = izip!(&state, &ALL_ROUND_CONSTANTS[round * SPONGE_WIDTH..])
+                                .map(|(lane, c)| lane.clone() + Expression::Constant(constant(*c)))
+                                .collect_vec();
+                            let sboxes = x[..num_sboxes]
+                                .iter()
+                                .enumerate()
+                                .map(|(lane, x)| {
+                                    cb.namespace(
+                                        || format!("sbox_{lane}"),
+                                        |cb| SboxConfig::construct_circuit(cb, x),
+                                    )
+                                })
+                                .collect::<Result<Vec<_>, ZKVMError>>()?;
+                            let y = izip!(0.., &x)
+                                .map(|(lane, x)| match sboxes.get(lane) {
+                                    Some(sbox) => sbox.expr(x),
+                                    None => x.clone(),
+                                })
+                                .collect_vec();
+
+                            let next: [WitIn; SPONGE_WIDTH] =
+                                array::from_fn(|i| cb.create_witin(|| format!("state_{i}")));
+                            for (row, next) in next.iter().enumerate() {
+                                let mds = y
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(col, y)| {
+                                        Expression::Constant(mds_entry(row, col)) * y.clone()
+                                    })
+                                    .reduce(|acc, term| acc + term)
+                                    .unwrap();
+                                cb.require_equal(|| format!("mds_{row}"), next.expr(), mds)?;
+                            }
+                            state = next.map(|wit| wit.expr());
+
+                            Ok(PoseidonRoundConfig {
+                                sboxes,
+                                state: next,
+                            })
+                        },
+                    )
+                })
+                .collect::<Result<Vec<_>, ZKVMError>>()?;
+            Ok(PoseidonPermutationConfig { rounds })
+        })
+    }
+
+    /// The state after the permutation.
+    pub fn output<E: ExtensionField>(&self) -> [Expression<E>; SPONGE_WIDTH] {
+        self.rounds.last().unwrap().state.map(|wit| wit.expr())
+    }
+
+    fn digest(&self) -> [WitIn; DIGEST_WIDTH] {
+        array::from_fn(|i| self.rounds.last().unwrap().state[i])
+    }
+
+    /// Assign the permutation of `input`, and return it.
+    pub fn assign_instance<F: Poseidon>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        input: [F; SPONGE_WIDTH],
+    ) -> [F; SPONGE_WIDTH] {
+        let mut state = input;
+        for (round, config) in self.rounds.iter().enumerate() {
+            let y = izip!(0.., state, &ALL_ROUND_CONSTANTS[round * SPONGE_WIDTH..])
+                .map(|(lane, value, c)| {
+                    let x = value + constant::<F>(*c);
+                    match config.sboxes.get(lane) {
+                        Some(sbox) => sbox.assign_instance(instance, x),
+                        None => x,
+                    }
+                })
+                .collect_vec();
+            state = array::from_fn(|row| {
+                y.iter()
+                    .enumerate()
+                    .map(|(col, y)| mds_entry::<F>(row, col) * y)
+                    .sum()
+            });
+            for (wit, value) in izip!(&config.state, state) {
+                set_val!(instance, wit, value);
+            }
+        }
+        state
+    }
+}
+
+/// The Poseidon hasher of `mpcs::util::hash`, with the sponge of
+/// `poseidon::poseidon_hash::PoseidonHash`: the inputs of at most `DIGEST_WIDTH` elements are
+/// their own digest, the longer ones are absorbed `SPONGE_RATE` elements per permutation, and
+/// two digests are compressed by a single permutation.
+#[derive(Debug)]
+pub struct PoseidonHasherConfig {
+    permutations: Vec<PoseidonPermutationConfig>,
+    /// The first lanes of the last permutation, or the inputs padded with zeros when they are
+    /// their own digest.
+    digest: [WitIn; DIGEST_WIDTH],
+}
+
+impl PoseidonHasherConfig {
+    fn construct_sponge<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        inputs: &[Expression<E>],
+    ) -> Result<Self, ZKVMError> {
+        if inputs.len() <= DIGEST_WIDTH {
+            let digest = array::from_fn(|i| cb.create_witin(|| format!("digest_{i}")));
+            for (i, digest) in digest.iter().enumerate() {
+                let input = inputs.get(i).cloned().unwrap_or(Expression::ZERO);
+                cb.require_equal(|| format!("digest_{i}"), digest.expr(), input)?;
+            }
+            return Ok(PoseidonHasherConfig {
+                permutations: vec![],
+                digest,
+            });
+        }
+
+        let mut state: [Expression<E>; SPONGE_WIDTH] = array::from_fn(|_| Expression::ZERO);
+        let permutations = inputs
+            .chunks(SPONGE_RATE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                state[..chunk.len()].clone_from_slice(chunk);
+                let permutation = PoseidonPermutationConfig::construct_circuit(
+                    cb,
+                    || format!("permutation_{i}"),
+                    state.clone(),
+                )?;
+                state = permutation.output();
+                Ok(permutation)
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        let digest = permutations.last().unwrap().digest();
+        Ok(PoseidonHasherConfig {
+            permutations,
+            digest,
+        })
+    }
+
+    fn assign_sponge<F: Poseidon>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        inputs: &[F],
+    ) -> Digest<F> {
+        let mut state = [F::ZERO; SPONGE_WIDTH];
+        if self.permutations.is_empty() {
+            state[..inputs.len()].copy_from_slice(inputs);
+            for (wit, value) in izip!(&self.digest, state) {
+                set_val!(instance, wit, value);
+            }
+        } else {
+            assert_eq!(inputs.chunks(SPONGE_RATE).len(), self.permutations.len());
+            for (permutation, chunk) in izip!(&self.permutations, inputs.chunks(SPONGE_RATE)) {
+                state[..chunk.len()].copy_from_slice(chunk);
+                state = permutation.assign_instance(instance, state);
+            }
+        }
+        Digest(state[..DIGEST_WIDTH].try_into().unwrap())
+    }
+}
+
+impl<E: ExtensionField> MerkleHasherConfig<E> for PoseidonHasherConfig {
+    fn construct_leaf_hash(
+        cb: &mut CircuitBuilder<E>,
+        left: &[Expression<E>],
+        right: &[Expression<E>],
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_sponge(cb, &[left, right].concat())
+    }
+
+    fn construct_node_hash(
+        cb: &mut CircuitBuilder<E>,
+        left: &[Expression<E>; DIGEST_WIDTH],
+        right: &[Expression<E>; DIGEST_WIDTH],
+    ) -> Result<Self, ZKVMError> {
+        let mut input: [Expression<E>; SPONGE_WIDTH] = array::from_fn(|_| Expression::ZERO);
+        input[..DIGEST_WIDTH].clone_from_slice(left);
+        input[DIGEST_WIDTH..2 * DIGEST_WIDTH].clone_from_slice(right);
+        let permutation =
+            PoseidonPermutationConfig::construct_circuit(cb, || "permutation", input)?;
+        Ok(PoseidonHasherConfig {
+            digest: permutation.digest(),
+            permutations: vec![permutation],
+        })
+    }
+
+    fn digest(&self) -> [Expression<E>; DIGEST_WIDTH] {
+        self.digest.map(|wit| wit.expr())
+    }
+
+    fn assign_leaf_hash(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        _lk_multiplicity: &mut LkMultiplicity,
+        left: &[E::BaseField],
+        right: &[E::BaseField],
+    ) -> Result<Digest<E::BaseField>, ZKVMError> {
+        Ok(self.assign_sponge(instance, &[left, right].concat()))
+    }
+
+    fn assign_node_hash(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        _lk_multiplicity: &mut LkMultiplicity,
+        left: &Digest<E::BaseField>,
+        right: &Digest<E::BaseField>,
+    ) -> Result<Digest<E::BaseField>, ZKVMError> {
+        let mut input = [E::BaseField::ZERO; SPONGE_WIDTH];
+        input[..DIGEST_WIDTH].copy_from_slice(&left.0);
+        input[DIGEST_WIDTH..2 * DIGEST_WIDTH].copy_from_slice(&right.0);
+        let output = self.permutations[0].assign_instance(instance, input);
+        Ok(Digest(output[..DIGEST_WIDTH].try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use itertools::Itertools;
+    use mpcs::util::{hash::Digest, merkle_tree::MerkleTree};
+    use multilinear_extensions::mle::FieldType;
+    use poseidon::poseidon_hash::PoseidonHash;
+
+    use super::PoseidonHasherConfig;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::ToExpr,
+        gadgets::{MerkleHasherConfig, MerklePathConfig},
+        scheme::mock_prover::MockProver,
+        set_val,
+        witness::{LkMultiplicity, RowMajorMatrix},
+    };
+
+    type E = GoldilocksExt2;
+
+    fn digest(seed: u64) -> Digest<Goldilocks> {
+        Digest(std::array::from_fn(|i| {
+            Goldilocks::from(seed * 4 + i as u64)
+        }))
+    }
+
+    #[test]
+    fn test_poseidon_node_hash() {
+        let mut cs = ConstraintSystem::<E>::new(|| "poseidon_node_hash");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let left = std::array::from_fn(|i| cb.create_witin(|| format!("left_{i}")));
+        let right = std::array::from_fn(|i| cb.create_witin(|| format!("right_{i}")));
+        let config = <PoseidonHasherConfig as MerkleHasherConfig<E>>::construct_node_hash(
+            &mut cb,
+            &left.map(|wit| wit.expr()),
+            &right.map(|wit| wit.expr()),
+        )
+        .unwrap();
+
+        let pairs = [(digest(0), digest(1)), (digest(7), digest(3))];
+        let mut lkm = LkMultiplicity::default();
+        let mut raw_witin =
+            RowMajorMatrix::<Goldilocks>::new(pairs.len(), cb.cs.num_witin as usize);
+        for (instance, (l, r)) in raw_witin.iter_mut().zip(&pairs) {
+            for (wit, value) in left.iter().zip(&l.0).chain(right.iter().zip(&r.0)) {
+                set_val!(instance, wit, *value);
+            }
+            let digest = <PoseidonHasherConfig as MerkleHasherConfig<E>>::assign_node_hash(
+                &config, instance, &mut lkm, l, r,
+            )
+            .unwrap();
+            assert_eq!(digest, PoseidonHash::two_to_one(l, r));
+        }
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[], None, Some(lkm));
+    }
+
+    #[test]
+    fn test_poseidon_leaf_hash() {
+        // its own digest, one permutation, and two absorbed chunks
+        for leaf_width in [2, 3, 5] {
+            let mut cs = ConstraintSystem::<E>::new(|| "poseidon_leaf_hash");
+            let mut cb = CircuitBuilder::new(&mut cs);
+            let inputs = (0..2 * leaf_width)
+                .map(|i| cb.create_witin(|| format!("input_{i}")))
+                .collect_vec();
+            let exprs = inputs.iter().map(|wit| wit.expr()).collect_vec();
+            let config = <PoseidonHasherConfig as MerkleHasherConfig<E>>::construct_leaf_hash(
+                &mut cb,
+                &exprs[..leaf_width],
+                &exprs[leaf_width..],
+            )
+            .unwrap();
+
+            let values = (0..2 * leaf_width as u64)
+                .map(|i| Goldilocks::from(i * 31 + 5))
+                .collect_vec();
+            let mut lkm = LkMultiplicity::default();
+            let mut raw_witin = RowMajorMatrix::<Goldilocks>::new(1, cb.cs.num_witin as usize);
+            let instance: &mut [MaybeUninit<Goldilocks>] = raw_witin.iter_mut().next().unwrap();
+            for (wit, value) in inputs.iter().zip(&values) {
+                set_val!(instance, wit, *value);
+            }
+            let digest = <PoseidonHasherConfig as MerkleHasherConfig<E>>::assign_leaf_hash(
+                &config,
+                instance,
+                &mut lkm,
+                &values[..leaf_width],
+                &values[leaf_width..],
+            )
+            .unwrap();
+            assert_eq!(digest, PoseidonHash::hash_or_noop(&values));
+
+            MockProver::assert_satisfied_raw(&cb, raw_witin, &[], None, Some(lkm));
+        }
+    }
+
+    #[test]
+    fn test_poseidon_merkle_path() {
+        let num_vars = 3;
+        let leaves = (0..1u64 << num_vars).map(Goldilocks::from).collect_vec();
+        let tree = MerkleTree::<E>::from_leaves(FieldType::Base(leaves.clone()));
+
+        let mut cs = ConstraintSystem::<E>::new(|| "poseidon_merkle_path");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = MerklePathConfig::<PoseidonHasherConfig>::construct_circuit(
+            &mut cb,
+            || "path",
+            1,
+            tree.height() - 1,
+        )
+        .unwrap();
+
+        let indices = [0, 5, (1 << num_vars) - 1];
+        let mut lkm = LkMultiplicity::default();
+        let mut raw_witin =
+            RowMajorMatrix::<Goldilocks>::new(indices.len(), cb.cs.num_witin as usize);
+        for (instance, index) in raw_witin.iter_mut().zip(indices) {
+            let path = tree
+                .merkle_path_without_leaf_sibling_or_root(index)
+                .iter()
+                .cloned()
+                .collect_vec();
+            let pair = index & !1;
+            let root = config
+                .assign_instance::<E>(
+                    instance,
+                    &mut lkm,
+                    &[leaves[pair]],
+                    &[leaves[pair + 1]],
+                    index,
+                    &path,
+                )
+                .unwrap();
+            assert_eq!(root, tree.root());
+        }
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[], None, Some(lkm));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
     circuit_builder::CircuitBuilder,
     error::ZKVMError,
-    expression::{Expression, ToExpr, WitIn},
+    expression::{Expression, ToExpr},
+    gadgets::BoolWit,
     instructions::riscv::constants::UInt,
-    set_val,
     witness::LkMultiplicity,
 };
 use ff_ext::ExtensionField;
@@ -12,7 +12,7 @@ use std::{marker::PhantomData, mem::MaybeUninit};
 #[derive(Debug)]
 pub struct SignedExtendConfig<E> {
     /// most significant bit
-    msb: WitIn,
+    msb: BoolWit,
     /// number of bits contained in the value
     n_bits: usize,
 
@@ -45,9 +45,7 @@ impl<E: ExtensionField> SignedExtendConfig<E> {
     ) -> Result<Self, ZKVMError> {
         assert!(n_bits == 8 || n_bits == 16);
 
-        let msb = cb.create_witin(|| "msb");
-        // require msb is boolean
-        cb.assert_bit(|| "msb is boolean", msb.expr())?;
+        let msb = BoolWit::construct_circuit(cb, || "msb")?;
 
         // assert 2*val - msb*2^N_BITS is within range [0, 2^N_BITS)
         // - if val < 2^(N_BITS-1), then 2*val < 2^N_BITS, msb can only be zero.
@@ -97,7 +95,7 @@ impl<E: ExtensionField> SignedExtendConfig<E> {
         };
 
         assert_ux(lk_multiplicity, 2 * val - (msb << self.n_bits));
-        set_val!(instance, self.msb, E::BaseField::from(msb));
+        self.msb.assign_instance(instance, msb == 1);
 
         Ok(())
     }
@@ -0,0 +1,121 @@
+use std::mem::MaybeUninit;
+
+use ff_ext::ExtensionField;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    witness::LkMultiplicity,
+};
+
+/// A witness constrained to be 0 or 1 on creation.
+#[derive(Clone, Copy, Debug)]
+pub struct BoolWit(WitIn);
+
+impl BoolWit {
+    pub fn construct_circuit<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR + Clone>(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+    ) -> Result<Self, ZKVMError> {
+        let wit = cb.create_witin(name_fn.clone());
+        cb.assert_bit(name_fn, wit.expr())?;
+        Ok(Self(wit))
+    }
+
+    pub fn witin(&self) -> WitIn {
+        self.0
+    }
+
+    pub fn assign_instance<F: Copy + From<u64>>(&self, instance: &mut [MaybeUninit<F>], bit: bool) {
+        set_val!(instance, self.0, bit as u64);
+    }
+}
+
+/// A witness range-checked to `C` bits on creation. Assigning it records the matching lookup,
+/// so the constraint and the multiplicity cannot get out of sync.
+#[derive(Clone, Copy, Debug)]
+pub struct UxWit<const C: usize>(WitIn);
+
+pub type U8Wit = UxWit<8>;
+pub type U16Wit = UxWit<16>;
+
+impl<const C: usize> UxWit<C> {
+    pub fn construct_circuit<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR + Clone>(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+    ) -> Result<Self, ZKVMError> {
+        let wit = cb.create_witin(name_fn.clone());
+        cb.assert_ux::<_, _, C>(name_fn, wit.expr())?;
+        Ok(Self(wit))
+    }
+
+    pub fn witin(&self) -> WitIn {
+        self.0
+    }
+
+    pub fn assign_instance<F: Copy + From<u64>>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        lk_multiplicity: &mut LkMultiplicity,
+        value: u64,
+    ) {
+        debug_assert!(value < (1 << C), "{value} does not fit in {C} bits");
+        lk_multiplicity.assert_ux::<C>(value);
+        set_val!(instance, self.0, value);
+    }
+}
+
+impl<E: ExtensionField> ToExpr<E> for BoolWit {
+    type Output = Expression<E>;
+    fn expr(&self) -> Expression<E> {
+        self.0.expr()
+    }
+}
+
+impl<E: ExtensionField, const C: usize> ToExpr<E> for UxWit<C> {
+    type Output = Expression<E>;
+    fn expr(&self) -> Expression<E> {
+        self.0.expr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+
+    use super::{BoolWit, U8Wit, U16Wit};
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        scheme::mock_prover::MockProver,
+        structs::ROMType,
+        witness::{LkMultiplicity, RowMajorMatrix},
+    };
+
+    #[test]
+    fn test_typed_witin() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "typed_witin");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let bit = BoolWit::construct_circuit(&mut cb, || "bit").unwrap();
+        let byte = U8Wit::construct_circuit(&mut cb, || "byte").unwrap();
+        let limb = U16Wit::construct_circuit(&mut cb, || "limb").unwrap();
+        assert_eq!(cb.cs.assert_zero_sumcheck_expressions.len(), 1);
+        assert_eq!(cb.cs.lk_expressions.len(), 2);
+
+        let values = [(true, 0xab, 0xbeef), (false, 0xff, 0)];
+        let mut lkm = LkMultiplicity::default();
+        let mut raw_witin =
+            RowMajorMatrix::<Goldilocks>::new(values.len(), cb.cs.num_witin as usize);
+        for (instance, (b, u8_value, u16_value)) in raw_witin.iter_mut().zip(values) {
+            bit.assign_instance(instance, b);
+            byte.assign_instance(instance, &mut lkm, u8_value);
+            limb.assign_instance(instance, &mut lkm, u16_value);
+        }
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[], None, Some(lkm.clone()));
+        let counts = lkm.into_finalize_result();
+        assert_eq!(counts[ROMType::U8.id()][&0xab], 1);
+        assert_eq!(counts[ROMType::U16.id()][&0xbeef], 1);
+    }
+}
@@ -8,7 +8,7 @@ use rayon::{
 };
 
 use crate::{
-    circuit_builder::CircuitBuilder,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
     error::ZKVMError,
     scheme::constants::MIN_PAR_SIZE,
     witness::{LkMultiplicity, RowMajorMatrix},
@@ -17,6 +17,9 @@ use ff::Field;
 
 pub mod riscv;
 
+mod spec;
+pub use spec::{ChipSpec, ColumnSpec, LookupSpec, RecordSpec};
+
 pub enum InstancePaddingStrategy {
     Zero,
     RepeatLast,
@@ -34,6 +37,15 @@ pub trait Instruction<E: ExtensionField> {
         circuit_builder: &mut CircuitBuilder<E>,
     ) -> Result<Self::InstructionConfig, ZKVMError>;
 
+    /// Describe the columns, lookups and records of the circuit that
+    /// [`Self::construct_circuit`] builds.
+    fn describe() -> ChipSpec {
+        let mut cs = ConstraintSystem::new(|| Self::name());
+        let mut circuit_builder = CircuitBuilder::new(&mut cs);
+        Self::construct_circuit(&mut circuit_builder).expect("construct circuit");
+        ChipSpec::from_cs(Self::name(), &cs)
+    }
+
     // assign single instance giving step from trace
     fn assign_instance(
         config: &Self::InstructionConfig,
@@ -8,12 +8,15 @@ pub use rv32im::{
 
 pub mod arith;
 pub mod arith_imm;
+pub mod bitmanip;
 pub mod branch;
 pub mod config;
 pub mod constants;
 pub mod divu;
 pub mod dummy;
 pub mod ecall;
+pub mod fence;
+pub mod fused;
 pub mod jump;
 pub mod logic;
 pub mod logic_imm;
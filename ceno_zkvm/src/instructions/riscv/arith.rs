@@ -468,4 +468,56 @@ mod test {
 
         MockProver::assert_satisfied_raw(&cb, raw_witin, &[insn_code], None, Some(lkm));
     }
+
+    #[test]
+    fn test_opcode_mul_sign_boundaries() {
+        let boundaries = [
+            0,
+            1,
+            2,
+            i32::MAX as u32,
+            i32::MIN as u32,
+            u32::MAX,
+            u32::MAX - 1,
+        ];
+        for rs1 in boundaries {
+            for rs2 in boundaries {
+                verify_mul(rs1, rs2);
+            }
+        }
+    }
+
+    fn verify_mul(rs1: u32, rs2: u32) {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(|| "mul", |cb| Ok(MulInstruction::construct_circuit(cb)))
+            .unwrap()
+            .unwrap();
+
+        let rd = rs1.wrapping_mul(rs2);
+        let insn_code = encode_rv32(InsnKind::MUL, 2, 3, 4, 0);
+        let (raw_witin, lkm) =
+            MulInstruction::assign_instances(&config, cb.cs.num_witin as usize, vec![
+                StepRecord::new_r_instruction(
+                    3,
+                    MOCK_PC_START,
+                    insn_code,
+                    rs1,
+                    rs2,
+                    Change::new(0, rd),
+                    0,
+                ),
+            ])
+            .unwrap();
+
+        let expected_rd_written =
+            UInt::from_const_unchecked(Value::new_unchecked(rd).as_u16_limbs().to_vec());
+        config
+            .rd_written
+            .require_equal(|| "assert_rd_written", &mut cb, &expected_rd_written)
+            .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[insn_code], None, Some(lkm));
+    }
 }
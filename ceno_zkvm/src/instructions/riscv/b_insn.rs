@@ -70,7 +70,7 @@ impl<E: ExtensionField> BInstructionConfig<E> {
 
         // Branch program counter
         let pc_offset =
-            branch_taken_bit.clone() * imm.expr() - branch_taken_bit * PC_STEP_SIZE + PC_STEP_SIZE;
+            circuit_builder.select(&branch_taken_bit, &imm.expr(), &PC_STEP_SIZE.into());
         let next_pc = vm_state.next_pc.unwrap();
         circuit_builder.require_equal(
             || "pc_branch",
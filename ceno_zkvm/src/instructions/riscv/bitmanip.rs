@@ -0,0 +1,36 @@
+//! A subset of the bit-manipulation extension (Zbb).
+
+mod count_circuit;
+mod logic_not_circuit;
+pub use count_circuit::{ClzInstruction, CpopInstruction};
+use logic_not_circuit::{LogicNotInstruction, LogicNotOp};
+
+#[cfg(test)]
+mod test;
+
+use crate::tables::{AndTable, OrTable, XorTable};
+use ceno_emul::InsnKind;
+
+pub struct AndnOp;
+impl LogicNotOp for AndnOp {
+    const INST_KIND: InsnKind = InsnKind::ANDN;
+    type OpsTable = AndTable;
+    const NOT_RS2: bool = true;
+}
+pub type AndnInstruction<E> = LogicNotInstruction<E, AndnOp>;
+
+pub struct OrnOp;
+impl LogicNotOp for OrnOp {
+    const INST_KIND: InsnKind = InsnKind::ORN;
+    type OpsTable = OrTable;
+    const NOT_RS2: bool = true;
+}
+pub type OrnInstruction<E> = LogicNotInstruction<E, OrnOp>;
+
+pub struct XnorOp;
+impl LogicNotOp for XnorOp {
+    const INST_KIND: InsnKind = InsnKind::XNOR;
+    type OpsTable = XorTable;
+    const NOT_RS2: bool = false;
+}
+pub type XnorInstruction<E> = LogicNotInstruction<E, XnorOp>;
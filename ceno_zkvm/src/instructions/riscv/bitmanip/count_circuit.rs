@@ -0,0 +1,200 @@
+//! The circuit implementation of the bit counting instructions CLZ and CPOP.
+//!
+//! Both are I-type instructions whose immediate is fixed, and whose result is at most 32, so it
+//! fits in the low limb of `rd`. The bits of `rs1` are counted per byte with a lookup.
+
+use core::mem::MaybeUninit;
+use ff_ext::ExtensionField;
+use std::marker::PhantomData;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    instructions::{
+        Instruction,
+        riscv::{constants::UInt8, i_insn::IInstructionConfig},
+    },
+    set_val,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{InsnKind, StepRecord};
+
+/// The immediate of CLZ, which tells it apart from CPOP in the program table.
+const CLZ_IMM: u32 = 0x600;
+const CPOP_IMM: u32 = 0x602;
+
+/// The operand of the instruction, and the instruction lookup with the fixed immediate.
+#[derive(Debug)]
+struct CountConfig<E: ExtensionField> {
+    i_insn: IInstructionConfig<E>,
+    rs1_read: UInt8<E>,
+}
+
+impl<E: ExtensionField> CountConfig<E> {
+    fn construct_circuit(
+        cb: &mut CircuitBuilder<E>,
+        insn_kind: InsnKind,
+        imm: u32,
+        rs1_read: UInt8<E>,
+        count: Expression<E>,
+    ) -> Result<Self, ZKVMError> {
+        let i_insn = IInstructionConfig::<E>::construct_circuit(
+            cb,
+            insn_kind,
+            &imm.into(),
+            rs1_read.register_expr(),
+            [count, 0.into()],
+            false,
+        )?;
+        Ok(CountConfig { i_insn, rs1_read })
+    }
+
+    fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<[u8; 4], ZKVMError> {
+        self.i_insn
+            .assign_instance(instance, lk_multiplicity, step)?;
+        let rs1_read = step.rs1().unwrap().value;
+        self.rs1_read.assign_u64(instance, rs1_read as u64);
+        Ok(rs1_read.to_le_bytes())
+    }
+}
+
+fn limbs<E: ExtensionField>(x: &UInt8<E>) -> Vec<Expression<E>> {
+    x.wits_in().unwrap().iter().map(|w| w.expr()).collect()
+}
+
+/// Count the leading zeros of `rs1`.
+pub struct ClzInstruction<E>(PhantomData<E>);
+
+#[derive(Debug)]
+pub struct ClzConfig<E: ExtensionField> {
+    count: CountConfig<E>,
+    /// The leading zeros of each byte, from the low byte.
+    clz: [WitIn; 4],
+    /// Whether each byte is zero.
+    is_zero: [WitIn; 4],
+    /// The leading zeros of the low 2, 3, and 4 bytes, as numbers of that many bytes.
+    partial: [WitIn; 3],
+}
+
+impl<E: ExtensionField> Instruction<E> for ClzInstruction<E> {
+    type InstructionConfig = ClzConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", InsnKind::CLZ)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let rs1_read = UInt8::new_unchecked(|| "rs1_read", cb)?;
+        let clz = [0, 1, 2, 3].map(|i| cb.create_witin(|| format!("clz_{i}")));
+        let is_zero = [0, 1, 2, 3].map(|i| cb.create_witin(|| format!("is_zero_{i}")));
+        let partial = [1, 2, 3].map(|i| cb.create_witin(|| format!("partial_{i}")));
+
+        for (i, byte) in limbs(&rs1_read).into_iter().enumerate() {
+            cb.lookup_clz_byte(byte, clz[i].expr(), is_zero[i].expr())?;
+        }
+        // The leading zeros of the bytes up to `i` are those of the byte `i`, plus those of the
+        // lower bytes if the byte `i` is zero.
+        let mut lower = clz[0].expr();
+        for i in 1..4 {
+            cb.require_equal(
+                || format!("partial_{i}"),
+                partial[i - 1].expr(),
+                clz[i].expr() + is_zero[i].expr() * lower,
+            )?;
+            lower = partial[i - 1].expr();
+        }
+
+        let count = CountConfig::construct_circuit(cb, InsnKind::CLZ, CLZ_IMM, rs1_read, lower)?;
+        Ok(ClzConfig {
+            count,
+            clz,
+            is_zero,
+            partial,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let bytes = config
+            .count
+            .assign_instance(instance, lk_multiplicity, step)?;
+
+        let mut lower = 0;
+        for (i, byte) in bytes.into_iter().enumerate() {
+            lk_multiplicity.lookup_clz_byte(byte as u64);
+            let (clz, is_zero) = (byte.leading_zeros() as u64, (byte == 0) as u64);
+            set_val!(instance, config.clz[i], clz);
+            set_val!(instance, config.is_zero[i], is_zero);
+            lower = clz + is_zero * lower;
+            if i > 0 {
+                set_val!(instance, config.partial[i - 1], lower);
+            }
+        }
+        debug_assert_eq!(lower, step.rd().unwrap().value.after as u64);
+
+        Ok(())
+    }
+}
+
+/// Count the ones of `rs1`.
+pub struct CpopInstruction<E>(PhantomData<E>);
+
+#[derive(Debug)]
+pub struct CpopConfig<E: ExtensionField> {
+    count: CountConfig<E>,
+    /// The ones of each byte.
+    ones: [WitIn; 4],
+}
+
+impl<E: ExtensionField> Instruction<E> for CpopInstruction<E> {
+    type InstructionConfig = CpopConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", InsnKind::CPOP)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let rs1_read = UInt8::new_unchecked(|| "rs1_read", cb)?;
+        let ones = [0, 1, 2, 3].map(|i| cb.create_witin(|| format!("ones_{i}")));
+
+        for (byte, ones) in limbs(&rs1_read).into_iter().zip(&ones) {
+            cb.lookup_popcount_byte(byte, ones.expr())?;
+        }
+        let total = ones
+            .iter()
+            .map(|ones| ones.expr())
+            .reduce(|a, b| a + b)
+            .unwrap();
+
+        let count = CountConfig::construct_circuit(cb, InsnKind::CPOP, CPOP_IMM, rs1_read, total)?;
+        Ok(CpopConfig { count, ones })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let bytes = config
+            .count
+            .assign_instance(instance, lk_multiplicity, step)?;
+
+        for (byte, ones) in bytes.into_iter().zip(&config.ones) {
+            lk_multiplicity.lookup_popcount_byte(byte as u64);
+            set_val!(instance, ones, byte.count_ones() as u64);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! The circuit implementation of the logic instructions with a negated operand or result.
+
+use core::mem::MaybeUninit;
+use ff_ext::ExtensionField;
+use itertools::izip;
+use std::marker::PhantomData;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr},
+    instructions::{
+        Instruction,
+        riscv::{constants::UInt8, r_insn::RInstructionConfig},
+    },
+    tables::OpsTable,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{InsnKind, StepRecord};
+
+/// This trait defines a logic instruction whose second operand or result is negated, e.g.
+/// `ANDN = rs1 & !rs2` and `XNOR = !(rs1 ^ rs2)`.
+pub trait LogicNotOp {
+    const INST_KIND: InsnKind;
+    type OpsTable: OpsTable;
+    /// Whether `rs2` is negated, or else the result.
+    const NOT_RS2: bool;
+}
+
+/// The Instruction circuit for a given LogicNotOp.
+pub struct LogicNotInstruction<E, I>(PhantomData<(E, I)>);
+
+impl<E: ExtensionField, I: LogicNotOp> Instruction<E> for LogicNotInstruction<E, I> {
+    type InstructionConfig = LogicNotConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", I::INST_KIND)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let rs1_read = UInt8::new_unchecked(|| "rs1_read", cb)?;
+        let rs2_read = UInt8::new_unchecked(|| "rs2_read", cb)?;
+        let rd_written = UInt8::new_unchecked(|| "rd_written", cb)?;
+
+        let r_insn = RInstructionConfig::<E>::construct_circuit(
+            cb,
+            I::INST_KIND,
+            rs1_read.register_expr(),
+            rs2_read.register_expr(),
+            rd_written.register_expr(),
+        )?;
+
+        // The negation of a byte `x` is `255 - x`. The lookups also check that it is a byte.
+        let not = |x: Expression<E>| Expression::from(0xffu32) - x;
+        let limbs = |x: &UInt8<E>| {
+            x.wits_in()
+                .unwrap()
+                .iter()
+                .map(|w| w.expr())
+                .collect::<Vec<_>>()
+        };
+        for (a, b, c) in izip!(limbs(&rs1_read), limbs(&rs2_read), limbs(&rd_written)) {
+            let (b, c) = if I::NOT_RS2 { (not(b), c) } else { (b, not(c)) };
+            cb.logic_u8(I::OpsTable::ROM_TYPE, a, b, c)?;
+        }
+
+        Ok(LogicNotConfig {
+            r_insn,
+            rs1_read,
+            rs2_read,
+            rd_written,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<<E as ExtensionField>::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let rs1 = step.rs1().unwrap().value;
+        let rs2 = step.rs2().unwrap().value;
+        let rs2 = if I::NOT_RS2 { !rs2 } else { rs2 };
+        UInt8::<E>::logic_assign::<I::OpsTable>(lk_multiplicity, rs1 as u64, rs2 as u64);
+
+        config
+            .r_insn
+            .assign_instance(instance, lk_multiplicity, step)?;
+        config
+            .rs1_read
+            .assign_u64(instance, step.rs1().unwrap().value as u64);
+        config
+            .rs2_read
+            .assign_u64(instance, step.rs2().unwrap().value as u64);
+        config
+            .rd_written
+            .assign_u64(instance, step.rd().unwrap().value.after as u64);
+
+        Ok(())
+    }
+}
+
+/// This config implements R-Instructions that represent registers values as 4 * u8.
+#[derive(Debug)]
+pub struct LogicNotConfig<E: ExtensionField> {
+    r_insn: RInstructionConfig<E>,
+
+    rs1_read: UInt8<E>,
+    rs2_read: UInt8<E>,
+    pub(crate) rd_written: UInt8<E>,
+}
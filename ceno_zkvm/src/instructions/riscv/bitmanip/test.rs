@@ -0,0 +1,125 @@
+use ceno_emul::{Change, PC_STEP_SIZE, StepRecord, Word, encode_rv32};
+use goldilocks::GoldilocksExt2;
+
+use crate::{
+    chip_handler::test::DebugIndex,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    instructions::{Instruction, riscv::constants::UInt8},
+    scheme::mock_prover::{MOCK_PC_START, MockProver},
+    utils::split_to_u8,
+};
+
+use super::*;
+
+const A: Word = 0xbead1010;
+const B: Word = 0xef552020;
+
+fn verify_logic_not<I: LogicNotOp>(rs1: Word, rs2: Word, rd: Word) {
+    let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+    let mut cb = CircuitBuilder::new(&mut cs);
+    let config = cb
+        .namespace(
+            || format!("{:?}", I::INST_KIND),
+            |cb| {
+                Ok(LogicNotInstruction::<GoldilocksExt2, I>::construct_circuit(
+                    cb,
+                ))
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    let insn_code = encode_rv32(I::INST_KIND, 2, 3, 4, 0);
+    let (raw_witin, lkm) = LogicNotInstruction::<GoldilocksExt2, I>::assign_instances(
+        &config,
+        cb.cs.num_witin as usize,
+        vec![StepRecord::new_r_instruction(
+            3,
+            MOCK_PC_START,
+            insn_code,
+            rs1,
+            rs2,
+            Change::new(0, rd),
+            0,
+        )],
+    )
+    .unwrap();
+
+    let expected_rd_written = UInt8::from_const_unchecked(split_to_u8::<u64>(rd));
+    config
+        .rd_written
+        .require_equal(|| "assert_rd_written", &mut cb, &expected_rd_written)
+        .unwrap();
+
+    MockProver::assert_satisfied_raw(&cb, raw_witin, &[insn_code], None, Some(lkm));
+}
+
+#[test]
+fn test_opcode_andn() {
+    verify_logic_not::<AndnOp>(A, B, A & !B);
+    verify_logic_not::<AndnOp>(u32::MAX, 0, u32::MAX);
+}
+
+#[test]
+fn test_opcode_orn() {
+    verify_logic_not::<OrnOp>(A, B, A | !B);
+    verify_logic_not::<OrnOp>(0, u32::MAX, 0);
+}
+
+#[test]
+fn test_opcode_xnor() {
+    verify_logic_not::<XnorOp>(A, B, !(A ^ B));
+    verify_logic_not::<XnorOp>(A, A, u32::MAX);
+}
+
+fn verify_count<I: Instruction<GoldilocksExt2>>(kind: InsnKind, rs1: Word, rd: Word) {
+    let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+    let mut cb = CircuitBuilder::new(&mut cs);
+    let config = cb
+        .namespace(|| format!("{kind:?}"), |cb| Ok(I::construct_circuit(cb)))
+        .unwrap()
+        .unwrap();
+
+    let insn_code = encode_rv32(kind, 2, 0, 4, 0);
+    let (raw_witin, lkm) = I::assign_instances(&config, cb.cs.num_witin as usize, vec![
+        StepRecord::new_i_instruction(
+            3,
+            Change::new(MOCK_PC_START, MOCK_PC_START + PC_STEP_SIZE),
+            insn_code,
+            rs1,
+            Change::new(0, rd),
+            0,
+        ),
+    ])
+    .unwrap();
+
+    let rd_written_expr = cb.get_debug_expr(DebugIndex::RdWrite as usize)[0].clone();
+    cb.require_equal(|| "assert_rd_written", rd_written_expr, rd.into())
+        .unwrap();
+
+    MockProver::assert_satisfied_raw(&cb, raw_witin, &[insn_code], None, Some(lkm));
+}
+
+#[test]
+fn test_opcode_clz() {
+    for rs1 in [
+        0,
+        1,
+        0x80,
+        0x100,
+        0x0001_0000,
+        0x00ff_0000,
+        0x8000_0000,
+        A,
+        u32::MAX,
+    ] {
+        verify_count::<ClzInstruction<_>>(InsnKind::CLZ, rs1, rs1.leading_zeros());
+    }
+}
+
+#[test]
+fn test_opcode_cpop() {
+    for rs1 in [0, 1, 0x8000_0001, A, B, u32::MAX] {
+        verify_count::<CpopInstruction<_>>(InsnKind::CPOP, rs1, rs1.count_ones());
+    }
+}
@@ -1,6 +1,7 @@
 use std::mem::MaybeUninit;
 
 use crate::{expression::WitIn, set_val, utils::i64_to_base, witness::LkMultiplicity};
+use ff_ext::PowConst;
 use goldilocks::SmallField;
 use itertools::Itertools;
 
@@ -108,7 +109,7 @@ impl UIntLtuInput<'_> {
         set_val!(instance, config.rhs_ne_byte, rhs_ne_byte);
         set_val!(instance, config.byte_diff_inv, {
             if flag {
-                (lhs_ne_byte - rhs_ne_byte).invert().unwrap()
+                (lhs_ne_byte - rhs_ne_byte).inverse().unwrap()
             } else {
                 F::ONE
             }
@@ -3,6 +3,7 @@ pub use ceno_emul::PC_STEP_SIZE;
 
 pub const ECALL_HALT_OPCODE: [usize; 2] = [0x00_00, 0x00_00];
 pub const EXIT_PC: usize = 0;
+pub const ECALL_RANDOM_OPCODE: [usize; 2] = [0x00_01, 0x00_00];
 pub const EXIT_CODE_IDX: usize = 0;
 
 pub const INIT_PC_IDX: usize = 2;
@@ -10,10 +11,19 @@ pub const INIT_CYCLE_IDX: usize = 3;
 pub const END_PC_IDX: usize = 4;
 pub const END_CYCLE_IDX: usize = 5;
 pub const PUBLIC_IO_IDX: usize = 6;
+pub const FINAL_REGS_IDX: usize = 7;
+pub const RANDOM_SEED_IDX: usize = 9;
 
 pub const LIMB_BITS: usize = 16;
 pub const LIMB_MASK: u32 = 0xFFFF;
 
+/// The width of the timestamps, which the emulator keeps traces within.
+pub const TS_BITS: usize = ceno_emul::Tracer::TIMESTAMP_BITS as usize;
+/// The limbs of the difference of two timestamps, in `prev_ts < ts` checks.
+pub const TS_LIMBS: usize = TS_BITS.div_ceil(LIMB_BITS);
+// a timestamp plus a difference must not wrap around the Goldilocks modulus
+const _: () = assert!(TS_LIMBS * LIMB_BITS < 63);
+
 #[cfg(feature = "riv32")]
 pub const BIT_WIDTH: usize = 32usize;
 #[cfg(feature = "riv64")]
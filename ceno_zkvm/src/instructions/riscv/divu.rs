@@ -76,13 +76,10 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ArithInstruction<E
         // div by zero check
         let is_zero =
             IsZeroConfig::construct_circuit(cb, || "divisor_zero_check", divisor.value())?;
-        let outcome_value = outcome.value();
-        cb.condition_require_equal(
+        cb.require_zero_if(
             || "outcome_is_zero",
             is_zero.expr(),
-            outcome_value.clone(),
-            ((1u64 << UInt::<E>::TOTAL_BITS) - 1).into(),
-            outcome_value,
+            outcome.value() - ((1u64 << UInt::<E>::TOTAL_BITS) - 1),
         )?;
 
         // remainder should be less than divisor if divisor != 0.
@@ -1,7 +1,9 @@
 mod halt;
+mod random;
 
 use ceno_emul::InsnKind;
 pub use halt::HaltInstruction;
+pub use random::{RandomConfig, RandomInstruction};
 
 use super::{RIVInstruction, dummy::DummyInstruction};
 
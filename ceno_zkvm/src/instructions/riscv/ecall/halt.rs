@@ -11,6 +11,7 @@ use crate::{
             ecall_insn::EcallInstructionConfig,
         },
     },
+    scheme::constants::HALT_CIRCUIT_NAME,
     set_val,
     witness::LkMultiplicity,
 };
@@ -24,13 +25,18 @@ pub struct HaltConfig {
     lt_x10_cfg: AssertLTConfig,
 }
 
+/// The HALT ecall: read the exit code from arg0 into the public values, and move to `EXIT_PC`.
+///
+/// `EXIT_PC` is not in the program table, so no instruction can fetch from it, and the state
+/// written here can only be consumed by the final state of the public values. The verifier
+/// requires one instance of this circuit for a halting trace, and checks the final pc.
 pub struct HaltInstruction<E>(PhantomData<E>);
 
 impl<E: ExtensionField> Instruction<E> for HaltInstruction<E> {
     type InstructionConfig = HaltConfig;
 
     fn name() -> String {
-        "ECALL_HALT".into()
+        HALT_CIRCUIT_NAME.into()
     }
 
     fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
@@ -91,7 +97,7 @@ impl<E: ExtensionField> Instruction<E> for HaltInstruction<E> {
             instance,
             lk_multiplicity,
             step.rs2().unwrap().previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_RS2,
+            step.rs2().unwrap().cycle,
         )?;
 
         config
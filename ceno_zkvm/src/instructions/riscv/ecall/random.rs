@@ -0,0 +1,213 @@
+use crate::{
+    chip_handler::RegisterChipOperations,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{AssertLTConfig, IsEqualConfig},
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_RANDOM_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::LkMultiplicity,
+};
+use ceno_emul::{Platform, StepRecord, Tracer};
+use ff::Field;
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use std::{marker::PhantomData, mem::MaybeUninit, sync::Arc};
+
+/// The columns of the RANDOM ecall, which do not depend on the seed.
+struct RandomColumns<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    prev_x10_ts: WitIn,
+    lt_x10_read_cfg: AssertLTConfig,
+    lt_x10_write_cfg: AssertLTConfig,
+    index: UInt<E>,
+    /// `index^2` to `index^7`
+    index_powers: [WitIn; 6],
+    /// The low half of the value, returned to the guest.
+    word: UInt<E>,
+    /// The high half of the value.
+    high: UInt<E>,
+    high_is_max: IsEqualConfig,
+}
+
+pub struct RandomConfig<E: ExtensionField> {
+    columns: Arc<RandomColumns<E>>,
+    seed: u64,
+}
+
+impl<E: ExtensionField> RandomConfig<E> {
+    /// The same circuit, to assign the steps of a VM with `seed`.
+    pub fn with_seed(&self, seed: u64) -> Self {
+        RandomConfig {
+            columns: self.columns.clone(),
+            seed,
+        }
+    }
+}
+
+/// The RANDOM ecall: replace arg0 with the word at index arg0 of the random stream of the seed in
+/// the public values, as in [`ceno_emul::random_word`].
+///
+/// The word is the low half of `(seed_hi + 1) * (index + seed_lo)^7`. Expanding the power, it is
+/// linear in the powers of the index, with coefficients from the seed, so that the seed only
+/// enters constraints of degree 1.
+pub struct RandomInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for RandomInstruction<E> {
+    type InstructionConfig = RandomConfig<E>;
+
+    fn name() -> String {
+        "ECALL_RANDOM".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let [seed_lo, seed_hi] = cb.query_random_seed()?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let index = UInt::new_unchecked(|| "index", cb)?;
+        let word = UInt::new(|| "word", cb)?;
+        let high = UInt::new(|| "high", cb)?;
+
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [ECALL_RANDOM_OPCODE[0].into(), ECALL_RANDOM_OPCODE[1].into()],
+            None,
+            None,
+        )?;
+
+        // read the index from arg0, and write the word back
+        let (_, lt_x10_read_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            index.register_expr(),
+        )?;
+        let (_, lt_x10_write_cfg) = cb.register_write(
+            || "write x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RD,
+            index.register_expr(),
+            word.register_expr(),
+        )?;
+
+        let x = index.value();
+        let index_powers = [2, 3, 4, 5, 6, 7].map(|i| cb.create_witin(|| format!("index^{i}")));
+        let mut prev = x.clone();
+        for (power, i) in index_powers.iter().zip(2..) {
+            cb.require_equal(|| format!("index^{i}"), power.expr(), prev * x.clone())?;
+            prev = power.expr();
+        }
+
+        // (seed_hi + 1) * sum_i binomial(7, i) * seed_lo^(7 - i) * index^i
+        let powers = [Expression::from(1), x]
+            .into_iter()
+            .chain(index_powers.iter().map(|power| power.expr()));
+        let seed_lo: Expression<E> = seed_lo.expr();
+        let value = powers
+            .enumerate()
+            .map(|(i, power)| {
+                let seed_lo_power = (i..7).fold(Expression::from(1), |acc, _| acc * &seed_lo);
+                BINOMIAL_7[i] * seed_lo_power * power
+            })
+            .sum::<Expression<E>>();
+        let value = (seed_hi.expr() + 1) * value;
+        cb.require_equal(|| "word", word.value() + high.value() * (1u64 << 32), value)?;
+
+        // the halves are canonical: above p - 1 = (2^32 - 1) * 2^32 only if the high half is
+        // 2^32 - 1 and the low half is not zero
+        let high_is_max =
+            IsEqualConfig::construct_circuit(cb, || "high_is_max", high.value(), u32::MAX.into())?;
+        cb.require_zero_if(|| "canonical", high_is_max.expr(), word.value())?;
+
+        Ok(RandomConfig {
+            columns: Arc::new(RandomColumns {
+                ecall_cfg,
+                prev_x10_ts,
+                lt_x10_read_cfg,
+                lt_x10_write_cfg,
+                index,
+                index_powers,
+                word,
+                high,
+                high_is_max,
+            }),
+            seed: 0,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let columns = &config.columns;
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_RANDOM_OPCODE[0] + (ECALL_RANDOM_OPCODE[1] << 16)) as u32
+        );
+
+        // the access of X10 register is read in rs2() and written in rd()
+        let read = step.rs2().unwrap();
+        let write = step.rd().unwrap();
+        set_val!(instance, columns.prev_x10_ts, read.previous_cycle);
+        columns.lt_x10_read_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            read.previous_cycle,
+            read.cycle,
+        )?;
+        columns.lt_x10_write_cfg.assign_instance(
+            instance,
+            lk_multiplicity,
+            write.previous_cycle,
+            write.cycle,
+        )?;
+
+        columns
+            .index
+            .assign_value(instance, Value::new_unchecked(read.value));
+        let x = E::BaseField::from(read.value as u64);
+        let mut power = x;
+        for wit in &columns.index_powers {
+            power *= x;
+            set_val!(instance, wit, power);
+        }
+
+        let t = x + E::BaseField::from(config.seed & 0xffff_ffff);
+        let value =
+            (E::BaseField::from(config.seed >> 32) + E::BaseField::ONE) * t.pow_vartime([7]);
+        let value = value.to_canonical_u64();
+        // with another seed than the one of the trace, the word differs from the one written,
+        // and the register accesses do not match
+        let (word, high) = (value as u32, (value >> 32) as u32);
+        columns
+            .word
+            .assign_value(instance, Value::new(word, lk_multiplicity));
+        columns
+            .high
+            .assign_value(instance, Value::new(high, lk_multiplicity));
+        columns.high_is_max.assign_instance(
+            instance,
+            E::BaseField::from(high as u64),
+            E::BaseField::from(u32::MAX as u64),
+        )?;
+
+        columns
+            .ecall_cfg
+            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+
+        Ok(())
+    }
+}
+
+/// `binomial(7, i)`
+const BINOMIAL_7: [u64; 8] = [1, 7, 21, 35, 35, 21, 7, 1];
@@ -87,7 +87,7 @@ impl EcallInstructionConfig {
             instance,
             lk_multiplicity,
             step.rs1().unwrap().previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_RS1,
+            step.rs1().unwrap().cycle,
         )?;
 
         Ok(())
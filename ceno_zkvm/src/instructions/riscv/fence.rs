@@ -0,0 +1,141 @@
+//! FENCE and FENCE.I, which are no-ops: the circuit only fetches the instruction and moves to the
+//! next pc.
+
+use std::marker::PhantomData;
+
+use ceno_emul::{InsnKind, StepRecord};
+use ff_ext::ExtensionField;
+
+use super::{RIVInstruction, insn_base::StateInOut};
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{ToExpr, WitIn},
+    instructions::Instruction,
+    set_val,
+    tables::InsnRecord,
+    utils::i64_to_base,
+    witness::LkMultiplicity,
+};
+use core::mem::MaybeUninit;
+
+pub struct FenceOp;
+impl RIVInstruction for FenceOp {
+    const INST_KIND: InsnKind = InsnKind::FENCE;
+}
+pub type FenceInstruction<E> = FenceInstructionBase<E, FenceOp>;
+
+pub struct FenceIOp;
+impl RIVInstruction for FenceIOp {
+    const INST_KIND: InsnKind = InsnKind::FENCE_I;
+}
+pub type FenceIInstruction<E> = FenceInstructionBase<E, FenceIOp>;
+
+pub struct FenceInstructionBase<E, I>(PhantomData<(E, I)>);
+
+#[derive(Debug)]
+pub struct FenceConfig<E: ExtensionField> {
+    vm_state: StateInOut<E>,
+    // The fields of the instruction, which have no effect but must match the program.
+    rd: WitIn,
+    rs1: WitIn,
+    imm: WitIn,
+}
+
+impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for FenceInstructionBase<E, I> {
+    type InstructionConfig = FenceConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", I::INST_KIND)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<FenceConfig<E>, ZKVMError> {
+        let vm_state = StateInOut::construct_circuit(cb, false)?;
+        let rd = cb.create_witin(|| "rd");
+        let rs1 = cb.create_witin(|| "rs1");
+        let imm = cb.create_witin(|| "imm");
+
+        cb.lk_fetch(&InsnRecord::new(
+            vm_state.pc.expr(),
+            I::INST_KIND.into(),
+            Some(rd.expr()),
+            rs1.expr(),
+            0.into(),
+            imm.expr(),
+        ))?;
+
+        Ok(FenceConfig {
+            vm_state,
+            rd,
+            rs1,
+            imm,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        config.vm_state.assign_instance(instance, step)?;
+
+        let insn = step.insn();
+        set_val!(instance, config.rd, insn.rd_internal() as u64);
+        set_val!(instance, config.rs1, insn.rs1_or_zero() as u64);
+        set_val!(
+            instance,
+            config.imm,
+            i64_to_base::<E::BaseField>(InsnRecord::imm_internal(&insn))
+        );
+        lk_multiplicity.fetch(step.pc().before.0);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::StepRecord;
+    use goldilocks::GoldilocksExt2;
+
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        scheme::mock_prover::{MOCK_PC_START, MockProver},
+    };
+
+    #[test]
+    fn test_opcode_fence() {
+        // fence, fence r, w, and fence.tso
+        for insn_code in [0x0ff0_000f, 0x0210_000f, 0x8330_000f] {
+            verify::<FenceOp>(insn_code);
+        }
+    }
+
+    #[test]
+    fn test_opcode_fence_i() {
+        verify::<FenceIOp>(0x0000_100f);
+    }
+
+    fn verify<I: RIVInstruction>(insn_code: u32) {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || format!("{:?}", I::INST_KIND),
+                |cb| Ok(FenceInstructionBase::<GoldilocksExt2, I>::construct_circuit(cb)),
+            )
+            .unwrap()
+            .unwrap();
+
+        let (raw_witin, lkm) = FenceInstructionBase::<GoldilocksExt2, I>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![StepRecord::new_nop_instruction(3, MOCK_PC_START, insn_code)],
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[insn_code], None, Some(lkm));
+    }
+}
@@ -0,0 +1,398 @@
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{FusedKind, InsnKind, PC_STEP_SIZE, StepRecord};
+use ff::Field;
+use ff_ext::ExtensionField;
+
+use crate::{
+    Value,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    instructions::Instruction,
+    set_val,
+    tables::InsnRecord,
+    utils::i64_to_base,
+    witness::LkMultiplicity,
+};
+
+use super::{
+    constants::UInt,
+    insn_base::{MemAddr, StateInOut, WriteRD},
+};
+
+pub struct LuiAddiConfig<E: ExtensionField> {
+    vm_state: StateInOut<E>,
+    rd: WriteRD<E>,
+
+    hi: UInt<E>,
+    lo: UInt<E>,
+    rd_written: UInt<E>,
+}
+
+/// The fused pair `lui rd, hi; addi rd, rd, lo` of [`FusedKind::LuiAddi`], in one row.
+///
+/// Both instructions are fetched from the program table, and rd is written once with
+/// `hi + lo`, the value that the addi leaves.
+pub struct LuiAddiInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for LuiAddiInstruction<E> {
+    type InstructionConfig = LuiAddiConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", FusedKind::LuiAddi)
+    }
+
+    fn construct_circuit(
+        circuit_builder: &mut CircuitBuilder<E>,
+    ) -> Result<Self::InstructionConfig, ZKVMError> {
+        let vm_state = StateInOut::construct_circuit_fused(circuit_builder, false, 2)?;
+
+        let hi = UInt::new(|| "hi", circuit_builder)?;
+        let lo = UInt::new(|| "lo", circuit_builder)?;
+        let rd_written = hi.add(|| "hi + lo", circuit_builder, &lo, true)?;
+
+        let rd =
+            WriteRD::construct_circuit(circuit_builder, rd_written.register_expr(), vm_state.ts)?;
+
+        // Fetch both instructions.
+        circuit_builder.lk_fetch(&InsnRecord::new(
+            vm_state.pc.expr(),
+            InsnKind::LUI.into(),
+            Some(rd.id.expr()),
+            0.into(),
+            0.into(),
+            hi.value(),
+        ))?;
+        circuit_builder.lk_fetch(&InsnRecord::new(
+            vm_state.pc.expr() + PC_STEP_SIZE,
+            InsnKind::ADDI.into(),
+            Some(rd.id.expr()),
+            rd.id.expr(),
+            0.into(),
+            lo.value(),
+        ))?;
+
+        Ok(LuiAddiConfig {
+            vm_state,
+            rd,
+            hi,
+            lo,
+            rd_written,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<<E as ExtensionField>::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        config.vm_state.assign_instance(instance, step)?;
+        config.rd.assign_instance(instance, lk_multiplicity, step)?;
+
+        // The step keeps the lui, and the addi immediate is what it added.
+        let hi = InsnRecord::imm_internal(&step.insn()) as u32;
+        let lo = step.rd().unwrap().value.after.wrapping_sub(hi);
+        let hi = Value::new(hi, lk_multiplicity);
+        let lo = Value::new(lo, lk_multiplicity);
+        let result = hi.add(&lo, lk_multiplicity, true);
+
+        config.hi.assign_value(instance, hi);
+        config.lo.assign_value(instance, lo);
+        config.rd_written.assign_add_outcome(instance, &result);
+
+        lk_multiplicity.fetch(step.pc().before.0);
+        lk_multiplicity.fetch(step.pc().before.0 + PC_STEP_SIZE as u32);
+
+        Ok(())
+    }
+}
+
+pub struct AuipcJalrConfig<E: ExtensionField> {
+    vm_state: StateInOut<E>,
+    rd: WriteRD<E>,
+
+    hi: WitIn,
+    auipc_overflow: WitIn,
+    auipc_value: UInt<E>,
+    lo: WitIn,
+    next_pc_addr: MemAddr<E>,
+    overflow: Option<(WitIn, WitIn)>,
+    rd_written: UInt<E>,
+}
+
+/// The fused pair `auipc rd, hi; jalr rd, lo(rd)` of [`FusedKind::AuipcJalr`], in one row.
+///
+/// The auipc result only lives in the row; rd is written once with the return address
+/// of the jalr, `pc + 8`.
+pub struct AuipcJalrInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for AuipcJalrInstruction<E> {
+    type InstructionConfig = AuipcJalrConfig<E>;
+
+    fn name() -> String {
+        format!("{:?}", FusedKind::AuipcJalr)
+    }
+
+    fn construct_circuit(
+        circuit_builder: &mut CircuitBuilder<E>,
+    ) -> Result<Self::InstructionConfig, ZKVMError> {
+        let vm_state = StateInOut::construct_circuit_fused(circuit_builder, true, 2)?;
+
+        // auipc: hi + pc = auipc_value + auipc_overflow * 2^32, as in the AUIPC circuit.
+        let hi = circuit_builder.create_witin(|| "hi");
+        let auipc_value = UInt::new(|| "auipc_value", circuit_builder)?;
+        let auipc_overflow = circuit_builder.create_witin(|| "auipc_overflow");
+        circuit_builder.assert_bit(|| "is_bit", auipc_overflow.expr())?;
+        circuit_builder.require_equal(
+            || "hi+pc = auipc_value+2^32*overflow",
+            hi.expr() + vm_state.pc.expr(),
+            auipc_value.value() + auipc_overflow.expr() * (1u64 << 32),
+        )?;
+
+        // jalr: next_pc is auipc_value + lo rounded down, as in the JALR circuit.
+        let lo = circuit_builder.create_witin(|| "lo"); // signed 12-bit value
+        let next_pc_addr = MemAddr::<E>::construct_unaligned(circuit_builder)?;
+
+        let (overflow_expr, overflow) = if cfg!(feature = "forbid_overflow") {
+            (Expression::ZERO, None)
+        } else {
+            let overflow = circuit_builder.create_witin(|| "overflow");
+            let tmp = circuit_builder.create_witin(|| "overflow1");
+            circuit_builder.require_zero(|| "overflow_0_or_pm1", overflow.expr() * tmp.expr())?;
+            circuit_builder.require_equal(
+                || "overflow_tmp",
+                tmp.expr(),
+                (1 - overflow.expr()) * (1 + overflow.expr()),
+            )?;
+            (overflow.expr(), Some((overflow, tmp)))
+        };
+
+        circuit_builder.require_equal(
+            || "auipc_value+lo = next_pc_unrounded + overflow*2^32",
+            auipc_value.value() + lo.expr(),
+            next_pc_addr.expr_unaligned() + overflow_expr * (1u64 << 32),
+        )?;
+
+        circuit_builder.require_equal(
+            || "next_pc_addr = next_pc",
+            next_pc_addr.expr_align2(),
+            vm_state.next_pc.unwrap().expr(),
+        )?;
+
+        // write the return address of the jalr to rd
+        let rd_written = UInt::new(|| "rd_written", circuit_builder)?;
+        circuit_builder.require_equal(
+            || "rd_written = pc+8",
+            rd_written.value(),
+            vm_state.pc.expr() + 2 * PC_STEP_SIZE,
+        )?;
+
+        let rd =
+            WriteRD::construct_circuit(circuit_builder, rd_written.register_expr(), vm_state.ts)?;
+
+        // Fetch both instructions.
+        circuit_builder.lk_fetch(&InsnRecord::new(
+            vm_state.pc.expr(),
+            InsnKind::AUIPC.into(),
+            Some(rd.id.expr()),
+            0.into(),
+            0.into(),
+            hi.expr(),
+        ))?;
+        circuit_builder.lk_fetch(&InsnRecord::new(
+            vm_state.pc.expr() + PC_STEP_SIZE,
+            InsnKind::JALR.into(),
+            Some(rd.id.expr()),
+            rd.id.expr(),
+            0.into(),
+            lo.expr(),
+        ))?;
+
+        Ok(AuipcJalrConfig {
+            vm_state,
+            rd,
+            hi,
+            auipc_overflow,
+            auipc_value,
+            lo,
+            next_pc_addr,
+            overflow,
+            rd_written,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        instance: &mut [MaybeUninit<<E as ExtensionField>::BaseField>],
+        lk_multiplicity: &mut LkMultiplicity,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        config.vm_state.assign_instance(instance, step)?;
+        config.rd.assign_instance(instance, lk_multiplicity, step)?;
+
+        // The step keeps the auipc. The jalr immediate is even, so it is the distance
+        // from the auipc result to the target.
+        let pc = step.pc().before.0;
+        let hi = InsnRecord::imm_internal(&step.insn());
+        let (auipc_value, auipc_overflow) = pc.overflowing_add(hi as u32);
+        let lo = step.pc().after.0.wrapping_sub(auipc_value) as i32;
+        let (sum, overflowing) = auipc_value.overflowing_add_signed(lo);
+
+        set_val!(instance, config.hi, i64_to_base::<E::BaseField>(hi));
+        set_val!(instance, config.auipc_overflow, auipc_overflow as u64);
+        config
+            .auipc_value
+            .assign_value(instance, Value::new(auipc_value, lk_multiplicity));
+        set_val!(instance, config.lo, i64_to_base::<E::BaseField>(lo as i64));
+
+        config
+            .next_pc_addr
+            .assign_instance(instance, lk_multiplicity, sum)?;
+
+        if let Some((overflow_cfg, tmp_cfg)) = &config.overflow {
+            let (overflow, tmp) = match (overflowing, lo < 0) {
+                (false, _) => (E::BaseField::ZERO, E::BaseField::ONE),
+                (true, false) => (E::BaseField::ONE, E::BaseField::ZERO),
+                (true, true) => (-E::BaseField::ONE, E::BaseField::ZERO),
+            };
+            set_val!(instance, overflow_cfg, overflow);
+            set_val!(instance, tmp_cfg, tmp);
+        } else {
+            assert!(!overflowing, "overflow not allowed in JALR");
+        }
+
+        config.rd_written.assign_value(
+            instance,
+            Value::new(step.rd().unwrap().value.after, lk_multiplicity),
+        );
+
+        lk_multiplicity.fetch(pc);
+        lk_multiplicity.fetch(pc + PC_STEP_SIZE as u32);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{
+        Change, FusedKind, InsnKind, PC_STEP_SIZE, StepRecord, Tracer, encode_rv32, fuse_steps,
+    };
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::{
+            Instruction,
+            riscv::test_utils::{imm_i, imm_u},
+        },
+        scheme::mock_prover::{MOCK_PC_START, MockProver},
+    };
+
+    use super::{AuipcJalrInstruction, LuiAddiInstruction};
+
+    #[test]
+    fn test_fused_lui_addi() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "lui_addi",
+                |cb| {
+                    let config = LuiAddiInstruction::<GoldilocksExt2>::construct_circuit(cb);
+                    Ok(config)
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        // li x4, 0x90004ff0
+        let hi = imm_u(0x90005);
+        let result = hi.wrapping_sub(16);
+        let lui_code = encode_rv32(InsnKind::LUI, 0, 0, 4, hi);
+        let addi_code = encode_rv32(InsnKind::ADDI, 4, 0, 4, imm_i(-16));
+        let fused = fuse_steps(
+            vec![
+                StepRecord::new_u_instruction(4, MOCK_PC_START, lui_code, Change::new(0, hi), 0),
+                StepRecord::new_i_instruction(
+                    4 + Tracer::SUBCYCLES_PER_INSN,
+                    Change::new(
+                        MOCK_PC_START + PC_STEP_SIZE,
+                        MOCK_PC_START + 2 * PC_STEP_SIZE,
+                    ),
+                    addi_code,
+                    hi,
+                    Change::new(hi, result),
+                    4 + Tracer::SUBCYCLE_RD,
+                ),
+            ],
+            &[FusedKind::LuiAddi],
+        );
+        assert_eq!(fused.num_fused(), 1);
+
+        let (raw_witin, lkm) = LuiAddiInstruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            fused.steps.into_iter().map(|(_, step)| step).collect(),
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[lui_code, addi_code], None, Some(lkm));
+    }
+
+    #[test]
+    fn test_fused_auipc_jalr() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "auipc_jalr",
+                |cb| {
+                    let config = AuipcJalrInstruction::<GoldilocksExt2>::construct_circuit(cb);
+                    Ok(config)
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        // call pc + 0x1000 - 16
+        let hi = imm_u(1);
+        let auipc_value = MOCK_PC_START.0 + hi;
+        let target = MOCK_PC_START + (hi as usize - 16);
+        let return_addr = MOCK_PC_START + 2 * PC_STEP_SIZE;
+        let auipc_code = encode_rv32(InsnKind::AUIPC, 0, 0, 4, hi);
+        let jalr_code = encode_rv32(InsnKind::JALR, 4, 0, 4, imm_i(-16));
+        let fused = fuse_steps(
+            vec![
+                StepRecord::new_u_instruction(
+                    4,
+                    MOCK_PC_START,
+                    auipc_code,
+                    Change::new(0, auipc_value),
+                    0,
+                ),
+                StepRecord::new_i_instruction(
+                    4 + Tracer::SUBCYCLES_PER_INSN,
+                    Change::new(MOCK_PC_START + PC_STEP_SIZE, target),
+                    jalr_code,
+                    auipc_value,
+                    Change::new(auipc_value, return_addr.0),
+                    4 + Tracer::SUBCYCLE_RD,
+                ),
+            ],
+            &[FusedKind::AuipcJalr],
+        );
+        assert_eq!(fused.num_fused(), 1);
+
+        let (raw_witin, lkm) = AuipcJalrInstruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            fused.steps.into_iter().map(|(_, step)| step).collect(),
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &[auipc_code, jalr_code], None, Some(lkm));
+    }
+}
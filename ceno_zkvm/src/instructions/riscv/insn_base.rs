@@ -36,16 +36,26 @@ impl<E: ExtensionField> StateInOut<E> {
     pub fn construct_circuit(
         circuit_builder: &mut CircuitBuilder<E>,
         branching: bool,
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_circuit_fused(circuit_builder, branching, 1)
+    }
+
+    /// The state of a step that stands for `num_insns` consecutive instructions, as in
+    /// [`ceno_emul::fuse_steps`]. It spans their cycles and, unless branching, their pcs.
+    pub fn construct_circuit_fused(
+        circuit_builder: &mut CircuitBuilder<E>,
+        branching: bool,
+        num_insns: usize,
     ) -> Result<Self, ZKVMError> {
         let pc = circuit_builder.create_witin(|| "pc");
         let (next_pc_opt, next_pc_expr) = if branching {
             let next_pc = circuit_builder.create_witin(|| "next_pc");
             (Some(next_pc), next_pc.expr())
         } else {
-            (None, pc.expr() + PC_STEP_SIZE)
+            (None, pc.expr() + PC_STEP_SIZE * num_insns)
         };
         let ts = circuit_builder.create_witin(|| "ts");
-        let next_ts = ts.expr() + Tracer::SUBCYCLES_PER_INSN;
+        let next_ts = ts.expr() + Tracer::SUBCYCLES_PER_INSN * num_insns as u64;
         circuit_builder.state_in(pc.expr(), ts.expr())?;
         circuit_builder.state_out(next_pc_expr, next_ts)?;
 
@@ -116,12 +126,8 @@ impl<E: ExtensionField> ReadRS1<E> {
         set_val!(instance, self.prev_ts, op.previous_cycle);
 
         // Register read
-        self.lt_cfg.assign_instance(
-            instance,
-            lk_multiplicity,
-            op.previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_RS1,
-        )?;
+        self.lt_cfg
+            .assign_instance(instance, lk_multiplicity, op.previous_cycle, op.cycle)?;
 
         Ok(())
     }
@@ -170,12 +176,8 @@ impl<E: ExtensionField> ReadRS2<E> {
         set_val!(instance, self.prev_ts, op.previous_cycle);
 
         // Register read
-        self.lt_cfg.assign_instance(
-            instance,
-            lk_multiplicity,
-            op.previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_RS2,
-        )?;
+        self.lt_cfg
+            .assign_instance(instance, lk_multiplicity, op.previous_cycle, op.cycle)?;
 
         Ok(())
     }
@@ -232,12 +234,8 @@ impl<E: ExtensionField> WriteRD<E> {
         );
 
         // Register write
-        self.lt_cfg.assign_instance(
-            instance,
-            lk_multiplicity,
-            op.previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_RD,
-        )?;
+        self.lt_cfg
+            .assign_instance(instance, lk_multiplicity, op.previous_cycle, op.cycle)?;
 
         Ok(())
     }
@@ -291,7 +289,7 @@ impl<E: ExtensionField> ReadMEM<E> {
             instance,
             lk_multiplicity,
             step.memory_op().unwrap().previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_MEM,
+            step.memory_op().unwrap().cycle,
         )?;
 
         Ok(())
@@ -342,7 +340,7 @@ impl WriteMEM {
             instance,
             lk_multiplicity,
             step.memory_op().unwrap().previous_cycle,
-            step.cycle() + Tracer::SUBCYCLE_MEM,
+            step.memory_op().unwrap().cycle,
         )?;
 
         Ok(())
@@ -523,16 +521,16 @@ mod test {
 
         // Check the range lookups.
         let lkm = lkm.into_finalize_result();
-        lkm[ROMType::U14 as usize].iter().for_each(|(k, v)| {
+        lkm[ROMType::U14.id()].iter().for_each(|(k, v)| {
             assert_eq!(*k, 0xbeef >> 2);
             assert_eq!(*v, num_rows);
         });
-        assert_eq!(lkm[ROMType::U14 as usize].len(), 1);
-        lkm[ROMType::U16 as usize].iter().for_each(|(k, v)| {
+        assert_eq!(lkm[ROMType::U14.id()].len(), 1);
+        lkm[ROMType::U16.id()].iter().for_each(|(k, v)| {
             assert_eq!(*k, 0xbead);
             assert_eq!(*v, num_rows);
         });
-        assert_eq!(lkm[ROMType::U16 as usize].len(), 1);
+        assert_eq!(lkm[ROMType::U16.id()].len(), 1);
 
         if is_ok {
             cb.require_equal(|| "", mem_addr.expr_unaligned(), addr.into())?;
@@ -85,6 +85,13 @@ fn test_opcode_jalr() {
 
 #[test]
 fn test_opcode_lui() {
+    // The edges of the 20-bit immediate.
+    for imm in [0, 1, 0x90005, 0x7ffff, 0x80000, 0xfffff] {
+        verify_lui(imm_u(imm));
+    }
+}
+
+fn verify_lui(imm_value: u32) {
     let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
     let mut cb = CircuitBuilder::new(&mut cs);
     let config = cb
@@ -98,7 +105,6 @@ fn test_opcode_lui() {
         .unwrap()
         .unwrap();
 
-    let imm_value = imm_u(0x90005);
     let insn_code = encode_rv32(InsnKind::LUI, 0, 0, 4, imm_value);
     let (raw_witin, lkm) = LuiInstruction::<GoldilocksExt2>::assign_instances(
         &config,
@@ -118,6 +124,13 @@ fn test_opcode_lui() {
 
 #[test]
 fn test_opcode_auipc() {
+    // The largest immediates wrap around past 2^32.
+    for imm in [0, 1, 0x90005, 0x7ffff, 0x80000, 0xfffff] {
+        verify_auipc(imm_u(imm));
+    }
+}
+
+fn verify_auipc(imm_value: u32) {
     let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
     let mut cb = CircuitBuilder::new(&mut cs);
     let config = cb
@@ -131,7 +144,6 @@ fn test_opcode_auipc() {
         .unwrap()
         .unwrap();
 
-    let imm_value = imm_u(0x90005);
     let insn_code = encode_rv32(InsnKind::AUIPC, 0, 0, 4, imm_value);
     let (raw_witin, lkm) = AuipcInstruction::<GoldilocksExt2>::assign_instances(
         &config,
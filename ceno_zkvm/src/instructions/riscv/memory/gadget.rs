@@ -4,13 +4,13 @@ use crate::{
     error::ZKVMError,
     expression::{Expression, ToExpr, WitIn},
     instructions::riscv::{constants::UInt, insn_base::MemAddr},
-    set_val,
+    set_val, set_vals,
     witness::LkMultiplicity,
 };
 use ceno_emul::StepRecord;
 use ff::Field;
 use ff_ext::ExtensionField;
-use itertools::izip;
+use itertools::{Itertools, chain};
 use std::mem::MaybeUninit;
 
 pub struct MemWordChange<const N_ZEROS: usize> {
@@ -27,18 +27,11 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
         prev_word: &UInt<E>,
         rs2_word: &UInt<E>,
     ) -> Result<Self, ZKVMError> {
-        let alloc_bytes = |cb: &mut CircuitBuilder<E>,
-                           anno: &str,
-                           num_bytes: usize|
-         -> Result<Vec<WitIn>, ZKVMError> {
+        // the bytes are range checked together below
+        let alloc_bytes = |cb: &mut CircuitBuilder<E>, anno: &str, num_bytes: usize| {
             (0..num_bytes)
-                .map(|i| {
-                    let byte = cb.create_witin(|| format!("{}.le_bytes[{}]", anno, i));
-                    cb.assert_ux::<_, _, 8>(|| "byte range check", byte.expr())?;
-
-                    Ok(byte)
-                })
-                .collect()
+                .map(|i| cb.create_witin(|| format!("{}.le_bytes[{}]", anno, i)))
+                .collect_vec()
         };
 
         let decompose_limb = |cb: &mut CircuitBuilder<E>,
@@ -46,7 +39,7 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
                               limb: &Expression<E>,
                               num_bytes: usize|
          -> Result<Vec<WitIn>, ZKVMError> {
-            let bytes = alloc_bytes(cb, limb_anno, num_bytes)?;
+            let bytes = alloc_bytes(cb, limb_anno, num_bytes);
 
             cb.require_equal(
                 || format!("decompose {} into {} bytes", limb_anno, num_bytes),
@@ -76,31 +69,41 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
                 let prev_limb_bytes = decompose_limb(cb, "prev_limb", &prev_target_limb, 2)?;
 
                 // extract the least significant byte from u16 limb
-                let rs2_limb_bytes = alloc_bytes(cb, "rs2_limb[0]", 1)?;
+                let rs2_limb_bytes = alloc_bytes(cb, "rs2_limb[0]", 1);
                 let u8_base_inv = E::BaseField::from(1 << 8).invert().unwrap();
-                cb.assert_ux::<_, _, 8>(
-                    || "rs2_limb[0].le_bytes[1]",
-                    u8_base_inv.expr() * (&rs2_limbs[0] - rs2_limb_bytes[0].expr()),
-                )?;
+                let rs2_limb_byte_1 =
+                    u8_base_inv.expr() * (&rs2_limbs[0] - rs2_limb_bytes[0].expr());
+                cb.assert_ux_packed::<_, _, 8>(|| "byte range check", vec![
+                    prev_limb_bytes[0].expr(),
+                    prev_limb_bytes[1].expr(),
+                    rs2_limb_bytes[0].expr(),
+                    rs2_limb_byte_1,
+                ])?;
 
                 // alloc a new witIn to cache degree 2 expression
                 let expected_limb_change = cb.create_witin(|| "expected_limb_change");
-                cb.condition_require_equal(
+                let limb_change = cb.select(
+                    &low_bits[0],
+                    &((rs2_limb_bytes[0].expr() - prev_limb_bytes[1].expr()) << 8),
+                    &(rs2_limb_bytes[0].expr() - prev_limb_bytes[0].expr()),
+                );
+                cb.require_equal(
                     || "expected_limb_change = select(low_bits[0], rs2 - prev)",
-                    low_bits[0].clone(),
                     expected_limb_change.expr(),
-                    (rs2_limb_bytes[0].expr() - prev_limb_bytes[1].expr()) << 8,
-                    rs2_limb_bytes[0].expr() - prev_limb_bytes[0].expr(),
+                    limb_change,
                 )?;
 
                 // alloc a new witIn to cache degree 2 expression
                 let expected_change = cb.create_witin(|| "expected_change");
-                cb.condition_require_equal(
+                let change = cb.select(
+                    &low_bits[1],
+                    &(expected_limb_change.expr() << 16),
+                    &expected_limb_change.expr(),
+                );
+                cb.require_equal(
                     || "expected_change = select(low_bits[1], limb_change*2^16, limb_change)",
-                    low_bits[1].clone(),
                     expected_change.expr(),
-                    expected_limb_change.expr() << 16,
-                    expected_limb_change.expr(),
+                    change,
                 )?;
 
                 Ok(MemWordChange {
@@ -117,16 +120,18 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
                 let prev_limbs = prev_word.expr();
                 let rs2_limbs = rs2_word.expr();
 
+                // alloc a new witIn to cache degree 2 expression
                 let expected_change = cb.create_witin(|| "expected_change");
 
-                // alloc a new witIn to cache degree 2 expression
-                cb.condition_require_equal(
+                let change = cb.select(
+                    &low_bits[1],
+                    &((&rs2_limbs[0] - &prev_limbs[1]) << 16),
+                    &(&rs2_limbs[0] - &prev_limbs[0]),
+                );
+                cb.require_equal(
                     || "expected_change = select(low_bits[1], 2^16*(limb_change))",
-                    // degree 2 expression
-                    low_bits[1].clone(),
                     expected_change.expr(),
-                    (&rs2_limbs[0] - &prev_limbs[1]) << 16,
-                    &rs2_limbs[0] - &prev_limbs[0],
+                    change,
                 )?;
 
                 Ok(MemWordChange {
@@ -162,10 +167,11 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
 
         match N_ZEROS {
             0 => {
-                for (&col, byte) in izip!(&self.prev_limb_bytes, prev_limb.to_le_bytes()) {
-                    set_val!(instance, col, E::BaseField::from(byte as u64));
-                    lk_multiplicity.assert_ux::<8>(byte as u64);
-                }
+                set_vals!(
+                    instance,
+                    &self.prev_limb_bytes,
+                    prev_limb.to_le_bytes().map(|byte| byte as u64)
+                );
 
                 set_val!(
                     instance,
@@ -173,9 +179,10 @@ impl<const N_ZEROS: usize> MemWordChange<N_ZEROS> {
                     E::BaseField::from(rs2_limb.to_le_bytes()[0] as u64)
                 );
 
-                rs2_limb.to_le_bytes().into_iter().for_each(|byte| {
-                    lk_multiplicity.assert_ux::<8>(byte as u64);
-                });
+                let bytes = chain!(prev_limb.to_le_bytes(), rs2_limb.to_le_bytes())
+                    .map(|byte| byte as u64)
+                    .collect_vec();
+                lk_multiplicity.assert_ux_packed::<8>(&bytes);
                 let change = if low_bits[0] == 0 {
                     E::BaseField::from(rs2_limb.to_le_bytes()[0] as u64)
                         - E::BaseField::from(prev_limb.to_le_bytes()[0] as u64)
@@ -11,14 +11,13 @@ use crate::{
             RIVInstruction, constants::UInt, im_insn::IMInstructionConfig, insn_base::MemAddr,
         },
     },
-    set_val,
+    set_val, set_vals,
     tables::InsnRecord,
     utils::i64_to_base,
     witness::LkMultiplicity,
 };
 use ceno_emul::{ByteAddr, InsnKind, StepRecord};
 use ff_ext::ExtensionField;
-use itertools::izip;
 use std::{marker::PhantomData, mem::MaybeUninit};
 
 pub struct LoadConfig<E: ExtensionField> {
@@ -107,12 +106,12 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
         let target_limb = match I::INST_KIND {
             InsnKind::LB | InsnKind::LBU | InsnKind::LH | InsnKind::LHU => {
                 let target_limb = circuit_builder.create_witin(|| "target_limb");
-                circuit_builder.condition_require_equal(
+                let limb =
+                    circuit_builder.select(&addr_low_bits[1], &memory_value[1], &memory_value[0]);
+                circuit_builder.require_equal(
                     || "target_limb = memory_value[low_bits[1]]",
-                    addr_low_bits[1].clone(),
                     target_limb.expr(),
-                    memory_value[1].clone(),
-                    memory_value[0].clone(),
+                    limb,
                 )?;
                 Some(target_limb)
             }
@@ -122,15 +121,22 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
         // get target byte from memory word for LB and LBU
         let (target_byte_expr, target_limb_bytes) = match I::INST_KIND {
             InsnKind::LB | InsnKind::LBU => {
-                let target_byte = circuit_builder.create_u8(|| "limb.le_bytes[low_bits[0]]")?;
-                let dummy_byte = circuit_builder.create_u8(|| "limb.le_bytes[1-low_bits[0]]")?;
+                let target_byte = circuit_builder.create_witin(|| "limb.le_bytes[low_bits[0]]");
+                let dummy_byte = circuit_builder.create_witin(|| "limb.le_bytes[1-low_bits[0]]");
+                circuit_builder.assert_ux_packed::<_, _, 8>(|| "limb.le_bytes", vec![
+                    target_byte.expr(),
+                    dummy_byte.expr(),
+                ])?;
 
-                circuit_builder.condition_require_equal(
+                let limb = circuit_builder.select(
+                    &addr_low_bits[0],
+                    &(target_byte.expr() * (1<<8) + dummy_byte.expr()), // target_byte = limb.le_bytes[1]
+                    &(dummy_byte.expr() * (1<<8) + target_byte.expr()), // target_byte = limb.le_bytes[0]
+                );
+                circuit_builder.require_equal(
                     || "target_byte = target_limb[low_bits[0]]",
-                    addr_low_bits[0].clone(),
                     target_limb.unwrap().expr(),
-                    target_byte.expr() * (1<<8) + dummy_byte.expr(), // target_byte = limb.le_bytes[1]
-                    dummy_byte.expr() * (1<<8) + target_byte.expr(), // target_byte = limb.le_bytes[0]
+                    limb,
                 )?;
 
                 (
@@ -233,13 +239,9 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
                 // target_limb_bytes[1] = target_limb.to_le_bytes[0]
                 target_limb_bytes.reverse();
             }
-            for (&col, byte) in izip!(
-                limb_bytes.iter(),
-                target_limb_bytes.into_iter().map(|byte| byte as u64)
-            ) {
-                lk_multiplicity.assert_ux::<8>(byte);
-                set_val!(instance, col, E::BaseField::from(byte));
-            }
+            let bytes = target_limb_bytes.map(|byte| byte as u64);
+            lk_multiplicity.assert_ux_packed::<8>(&bytes);
+            set_vals!(instance, limb_bytes, bytes);
         }
         let val = match I::INST_KIND {
             InsnKind::LB | InsnKind::LBU => target_limb_bytes[0] as u64,
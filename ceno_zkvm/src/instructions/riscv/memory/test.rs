@@ -16,7 +16,9 @@ use crate::{
     },
     scheme::mock_prover::{MOCK_PC_START, MockProver},
 };
-use ceno_emul::{ByteAddr, Change, InsnKind, ReadOp, StepRecord, Word, WriteOp, encode_rv32};
+use ceno_emul::{
+    ByteAddr, Change, InsnKind, ReadOp, StepRecord, Tracer, Word, WriteOp, encode_rv32,
+};
 use ff_ext::ExtensionField;
 use goldilocks::GoldilocksExt2;
 use std::hash::Hash;
@@ -113,6 +115,7 @@ fn impl_opcode_store<E: ExtensionField + Hash, I: RIVInstruction, Inst: Instruct
                     after: new_mem_value,
                 },
                 previous_cycle: 4,
+                cycle: 12 + Tracer::SUBCYCLE_MEM,
             },
             8,
         ),
@@ -157,6 +160,7 @@ fn impl_opcode_load<E: ExtensionField + Hash, I: RIVInstruction, Inst: Instructi
                 addr: unaligned_addr.waddr(),
                 value: mem_value,
                 previous_cycle: 4,
+                cycle: 12 + Tracer::SUBCYCLE_MEM,
             },
             8,
         ),
@@ -410,6 +410,14 @@ mod test {
         verify_mulhu(2, 11);
         verify_mulhu(u32::MAX, u32::MAX);
         verify_mulhu(u16::MAX as u32, u16::MAX as u32);
+        // The products near 0 and near the largest high limb, see the module documentation.
+        verify_mulhu(0, 0);
+        verify_mulhu(0, u32::MAX);
+        verify_mulhu(1, u32::MAX);
+        verify_mulhu(u32::MAX, u32::MAX - 1);
+        verify_mulhu(1 << 31, 1 << 31);
+        verify_mulhu(1 << 31, u32::MAX);
+        verify_mulhu(i32::MAX as u32, 2);
     }
 
     fn verify_mulhu(rs1: u32, rs2: u32) {
@@ -4,10 +4,15 @@ use crate::{
         Instruction,
         riscv::{
             arith_imm::AddiInstruction,
+            bitmanip::{
+                AndnInstruction, ClzInstruction, CpopInstruction, OrnInstruction, XnorInstruction,
+            },
             branch::{
                 BeqInstruction, BgeInstruction, BgeuInstruction, BltInstruction, BneInstruction,
             },
             divu::DivUInstruction,
+            fence::{FenceIInstruction, FenceInstruction},
+            fused::{AuipcJalrInstruction, LuiAddiInstruction},
             logic::{AndInstruction, OrInstruction, XorInstruction},
             logic_imm::{AndiInstruction, OriInstruction, XoriInstruction},
             mulh::MulhuInstruction,
@@ -20,11 +25,13 @@ use crate::{
     },
     structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
     tables::{
-        AndTableCircuit, LtuTableCircuit, OrTableCircuit, PowTableCircuit, TableCircuit,
-        U5TableCircuit, U8TableCircuit, U14TableCircuit, U16TableCircuit, XorTableCircuit,
+        AndTableCircuit, ClzTableCircuit, LtuTableCircuit, OrTableCircuit, PackedU8TableCircuit,
+        PopcountTableCircuit, PowTableCircuit, TableCircuit, U5TableCircuit, U8TableCircuit,
+        U14TableCircuit, U16TableCircuit, XorTableCircuit,
     },
 };
 use ceno_emul::{
+    FusedKind,
     InsnKind::{self, *},
     Platform, StepRecord,
 };
@@ -42,7 +49,7 @@ use strum::IntoEnumIterator;
 use super::{
     arith::AddInstruction,
     branch::BltuInstruction,
-    ecall::HaltInstruction,
+    ecall::{HaltInstruction, RandomInstruction},
     jump::{JalInstruction, LuiInstruction},
     memory::LwInstruction,
 };
@@ -67,6 +74,13 @@ pub struct Rv32imConfig<E: ExtensionField> {
     pub mulhu_config: <MulhuInstruction<E> as Instruction<E>>::InstructionConfig,
     pub divu_config: <DivUInstruction<E> as Instruction<E>>::InstructionConfig,
 
+    // Bit-manipulation Opcodes
+    pub andn_config: <AndnInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub orn_config: <OrnInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub xnor_config: <XnorInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub clz_config: <ClzInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub cpop_config: <CpopInstruction<E> as Instruction<E>>::InstructionConfig,
+
     // ALU with imm
     pub addi_config: <AddiInstruction<E> as Instruction<E>>::InstructionConfig,
     pub andi_config: <AndiInstruction<E> as Instruction<E>>::InstructionConfig,
@@ -92,6 +106,10 @@ pub struct Rv32imConfig<E: ExtensionField> {
     pub auipc_config: <AuipcInstruction<E> as Instruction<E>>::InstructionConfig,
     pub lui_config: <LuiInstruction<E> as Instruction<E>>::InstructionConfig,
 
+    // Fused Opcodes
+    pub lui_addi_config: <LuiAddiInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub auipc_jalr_config: <AuipcJalrInstruction<E> as Instruction<E>>::InstructionConfig,
+
     // Memory Opcodes
     pub lw_config: <LwInstruction<E> as Instruction<E>>::InstructionConfig,
     pub lhu_config: <LhuInstruction<E> as Instruction<E>>::InstructionConfig,
@@ -102,18 +120,26 @@ pub struct Rv32imConfig<E: ExtensionField> {
     pub sh_config: <ShInstruction<E> as Instruction<E>>::InstructionConfig,
     pub sb_config: <SbInstruction<E> as Instruction<E>>::InstructionConfig,
 
+    // Fence Opcodes
+    pub fence_config: <FenceInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub fence_i_config: <FenceIInstruction<E> as Instruction<E>>::InstructionConfig,
+
     // Ecall Opcodes
     pub halt_config: <HaltInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub random_config: <RandomInstruction<E> as Instruction<E>>::InstructionConfig,
     // Tables.
     pub u16_range_config: <U16TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub u14_range_config: <U14TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub u8_range_config: <U8TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub u5_range_config: <U5TableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub packed_u8_range_config: <PackedU8TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub and_table_config: <AndTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub or_table_config: <OrTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub xor_table_config: <XorTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub ltu_config: <LtuTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub pow_config: <PowTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub clz_table_config: <ClzTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub popcount_table_config: <PopcountTableCircuit<E> as TableCircuit<E>>::TableConfig,
 }
 
 impl<E: ExtensionField> Rv32imConfig<E> {
@@ -136,6 +162,13 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         let mulhu_config = cs.register_opcode_circuit::<MulhuInstruction<E>>();
         let divu_config = cs.register_opcode_circuit::<DivUInstruction<E>>();
 
+        // bit-manipulation opcodes
+        let andn_config = cs.register_opcode_circuit::<AndnInstruction<E>>();
+        let orn_config = cs.register_opcode_circuit::<OrnInstruction<E>>();
+        let xnor_config = cs.register_opcode_circuit::<XnorInstruction<E>>();
+        let clz_config = cs.register_opcode_circuit::<ClzInstruction<E>>();
+        let cpop_config = cs.register_opcode_circuit::<CpopInstruction<E>>();
+
         // alu with imm opcodes
         let addi_config = cs.register_opcode_circuit::<AddiInstruction<E>>();
         let andi_config = cs.register_opcode_circuit::<AndiInstruction<E>>();
@@ -161,6 +194,10 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         let jalr_config = cs.register_opcode_circuit::<JalrInstruction<E>>();
         let auipc_config = cs.register_opcode_circuit::<AuipcInstruction<E>>();
 
+        // fused opcodes
+        let lui_addi_config = cs.register_opcode_circuit::<LuiAddiInstruction<E>>();
+        let auipc_jalr_config = cs.register_opcode_circuit::<AuipcJalrInstruction<E>>();
+
         // memory opcodes
         let lw_config = cs.register_opcode_circuit::<LwInstruction<E>>();
         let lhu_config = cs.register_opcode_circuit::<LhuInstruction<E>>();
@@ -171,18 +208,26 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         let sh_config = cs.register_opcode_circuit::<ShInstruction<E>>();
         let sb_config = cs.register_opcode_circuit::<SbInstruction<E>>();
 
+        // fence opcodes
+        let fence_config = cs.register_opcode_circuit::<FenceInstruction<E>>();
+        let fence_i_config = cs.register_opcode_circuit::<FenceIInstruction<E>>();
+
         // ecall opcodes
         let halt_config = cs.register_opcode_circuit::<HaltInstruction<E>>();
+        let random_config = cs.register_opcode_circuit::<RandomInstruction<E>>();
         // tables
         let u16_range_config = cs.register_table_circuit::<U16TableCircuit<E>>();
         let u14_range_config = cs.register_table_circuit::<U14TableCircuit<E>>();
         let u8_range_config = cs.register_table_circuit::<U8TableCircuit<E>>();
         let u5_range_config = cs.register_table_circuit::<U5TableCircuit<E>>();
+        let packed_u8_range_config = cs.register_table_circuit::<PackedU8TableCircuit<E>>();
         let and_table_config = cs.register_table_circuit::<AndTableCircuit<E>>();
         let or_table_config = cs.register_table_circuit::<OrTableCircuit<E>>();
         let xor_table_config = cs.register_table_circuit::<XorTableCircuit<E>>();
         let ltu_config = cs.register_table_circuit::<LtuTableCircuit<E>>();
         let pow_config = cs.register_table_circuit::<PowTableCircuit<E>>();
+        let clz_table_config = cs.register_table_circuit::<ClzTableCircuit<E>>();
+        let popcount_table_config = cs.register_table_circuit::<PopcountTableCircuit<E>>();
 
         Self {
             // alu opcodes
@@ -201,6 +246,12 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             mulhsu_config,
             mulhu_config,
             divu_config,
+            // bit-manipulation opcodes
+            andn_config,
+            orn_config,
+            xnor_config,
+            clz_config,
+            cpop_config,
             // alu with imm
             addi_config,
             andi_config,
@@ -223,6 +274,9 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             jal_config,
             jalr_config,
             auipc_config,
+            // fused opcodes
+            lui_addi_config,
+            auipc_jalr_config,
             // memory opcodes
             sw_config,
             sh_config,
@@ -232,18 +286,25 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             lh_config,
             lbu_config,
             lb_config,
+            // fence opcodes
+            fence_config,
+            fence_i_config,
             // ecall opcodes
             halt_config,
+            random_config,
             // tables
             u16_range_config,
             u14_range_config,
             u8_range_config,
             u5_range_config,
+            packed_u8_range_config,
             and_table_config,
             or_table_config,
             xor_table_config,
             ltu_config,
             pow_config,
+            clz_table_config,
+            popcount_table_config,
         }
     }
 
@@ -268,6 +329,12 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         fixed.register_opcode_circuit::<MulhsuInstruction<E>>(cs);
         fixed.register_opcode_circuit::<MulhuInstruction<E>>(cs);
         fixed.register_opcode_circuit::<DivUInstruction<E>>(cs);
+        // bit-manipulation
+        fixed.register_opcode_circuit::<AndnInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<OrnInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<XnorInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<ClzInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<CpopInstruction<E>>(cs);
         // alu with imm
         fixed.register_opcode_circuit::<AddiInstruction<E>>(cs);
         fixed.register_opcode_circuit::<AndiInstruction<E>>(cs);
@@ -290,6 +357,9 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         fixed.register_opcode_circuit::<JalrInstruction<E>>(cs);
         fixed.register_opcode_circuit::<AuipcInstruction<E>>(cs);
         fixed.register_opcode_circuit::<LuiInstruction<E>>(cs);
+        // fused
+        fixed.register_opcode_circuit::<LuiAddiInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<AuipcJalrInstruction<E>>(cs);
         // memory
         fixed.register_opcode_circuit::<SwInstruction<E>>(cs);
         fixed.register_opcode_circuit::<ShInstruction<E>>(cs);
@@ -299,30 +369,78 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         fixed.register_opcode_circuit::<LhInstruction<E>>(cs);
         fixed.register_opcode_circuit::<LbuInstruction<E>>(cs);
         fixed.register_opcode_circuit::<LbInstruction<E>>(cs);
+        // fence
+        fixed.register_opcode_circuit::<FenceInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<FenceIInstruction<E>>(cs);
 
         fixed.register_opcode_circuit::<HaltInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<RandomInstruction<E>>(cs);
 
         fixed.register_table_circuit::<U16TableCircuit<E>>(cs, &self.u16_range_config, &());
         fixed.register_table_circuit::<U14TableCircuit<E>>(cs, &self.u14_range_config, &());
         fixed.register_table_circuit::<U8TableCircuit<E>>(cs, &self.u8_range_config, &());
         fixed.register_table_circuit::<U5TableCircuit<E>>(cs, &self.u5_range_config, &());
+        fixed.register_table_circuit::<PackedU8TableCircuit<E>>(
+            cs,
+            &self.packed_u8_range_config,
+            &(),
+        );
         fixed.register_table_circuit::<AndTableCircuit<E>>(cs, &self.and_table_config, &());
         fixed.register_table_circuit::<OrTableCircuit<E>>(cs, &self.or_table_config, &());
         fixed.register_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_table_config, &());
         fixed.register_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &());
         fixed.register_table_circuit::<PowTableCircuit<E>>(cs, &self.pow_config, &());
+        fixed.register_table_circuit::<ClzTableCircuit<E>>(cs, &self.clz_table_config, &());
+        fixed.register_table_circuit::<PopcountTableCircuit<E>>(
+            cs,
+            &self.popcount_table_config,
+            &(),
+        );
     }
 
+    /// Assign the steps of a VM run with the seed `random_seed`, see `VMState::set_random_seed`.
     pub fn assign_opcode_circuit(
         &self,
         cs: &ZKVMConstraintSystem<E>,
         witness: &mut ZKVMWitnesses<E>,
         steps: Vec<StepRecord>,
+        random_seed: u64,
     ) -> Result<GroupedSteps, ZKVMError> {
+        let steps = steps.into_iter().map(|step| (None, step)).collect();
+        self.assign_fused_opcode_circuit(cs, witness, steps, random_seed)
+    }
+
+    /// Assign the steps of a trace fused by `ceno_emul::fuse_steps`, as `assign_opcode_circuit`
+    /// does. A fused step goes to the circuit of its [`FusedKind`].
+    pub fn assign_fused_opcode_circuit(
+        &self,
+        cs: &ZKVMConstraintSystem<E>,
+        witness: &mut ZKVMWitnesses<E>,
+        steps: Vec<(Option<FusedKind>, StepRecord)>,
+        random_seed: u64,
+    ) -> Result<GroupedSteps, ZKVMError> {
+        let mut lui_addi_records = Vec::new();
+        let mut auipc_jalr_records = Vec::new();
+        let steps = steps
+            .into_iter()
+            .filter_map(|(kind, record)| match kind {
+                Some(FusedKind::LuiAddi) => {
+                    lui_addi_records.push(record);
+                    None
+                }
+                Some(FusedKind::AuipcJalr) => {
+                    auipc_jalr_records.push(record);
+                    None
+                }
+                None => Some(record),
+            })
+            .collect_vec();
+
         let mut all_records: BTreeMap<usize, Vec<StepRecord>> = InsnKind::iter()
             .map(|insn_kind| ((insn_kind as usize), Vec::new()))
             .collect();
         let mut halt_records = Vec::new();
+        let mut random_records = Vec::new();
         steps.into_iter().for_each(|record| {
             let insn_kind = record.insn().codes().kind;
             match insn_kind {
@@ -330,6 +448,10 @@ impl<E: ExtensionField> Rv32imConfig<E> {
                 EANY if record.rs1().unwrap().value == Platform::ecall_halt() => {
                     halt_records.push(record);
                 }
+                // ecall / random
+                EANY if record.rs1().unwrap().value == Platform::ecall_random() => {
+                    random_records.push(record);
+                }
                 // other type of ecalls are handled by dummy ecall instruction
                 _ => {
                     let insn_kind = insn_kind as usize;
@@ -374,6 +496,12 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         assign_opcode!(MULHSU, MulhsuInstruction<E>, mulhsu_config);
         assign_opcode!(MULHU, MulhuInstruction<E>, mulhu_config);
         assign_opcode!(DIVU, DivUInstruction<E>, divu_config);
+        // bit-manipulation
+        assign_opcode!(ANDN, AndnInstruction<E>, andn_config);
+        assign_opcode!(ORN, OrnInstruction<E>, orn_config);
+        assign_opcode!(XNOR, XnorInstruction<E>, xnor_config);
+        assign_opcode!(CLZ, ClzInstruction<E>, clz_config);
+        assign_opcode!(CPOP, CpopInstruction<E>, cpop_config);
         // alu with imm
         assign_opcode!(ADDI, AddiInstruction<E>, addi_config);
         assign_opcode!(ANDI, AndiInstruction<E>, andi_config);
@@ -396,6 +524,17 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         assign_opcode!(JALR, JalrInstruction<E>, jalr_config);
         assign_opcode!(AUIPC, AuipcInstruction<E>, auipc_config);
         assign_opcode!(LUI, LuiInstruction<E>, lui_config);
+        // fused
+        witness.assign_opcode_circuit::<LuiAddiInstruction<E>>(
+            cs,
+            &self.lui_addi_config,
+            lui_addi_records,
+        )?;
+        witness.assign_opcode_circuit::<AuipcJalrInstruction<E>>(
+            cs,
+            &self.auipc_jalr_config,
+            auipc_jalr_records,
+        )?;
         // memory
         assign_opcode!(LW, LwInstruction<E>, lw_config);
         assign_opcode!(LB, LbInstruction<E>, lb_config);
@@ -405,9 +544,17 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         assign_opcode!(SW, SwInstruction<E>, sw_config);
         assign_opcode!(SH, ShInstruction<E>, sh_config);
         assign_opcode!(SB, SbInstruction<E>, sb_config);
+        // fence
+        assign_opcode!(FENCE, FenceInstruction<E>, fence_config);
+        assign_opcode!(FENCE_I, FenceIInstruction<E>, fence_i_config);
 
-        // ecall / halt
+        // ecall / halt, random
         witness.assign_opcode_circuit::<HaltInstruction<E>>(cs, &self.halt_config, halt_records)?;
+        witness.assign_opcode_circuit::<RandomInstruction<E>>(
+            cs,
+            &self.random_config.with_seed(random_seed),
+            random_records,
+        )?;
 
         assert_eq!(
             all_records.keys().cloned().collect::<BTreeSet<_>>(),
@@ -429,11 +576,22 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         witness.assign_table_circuit::<U14TableCircuit<E>>(cs, &self.u14_range_config, &())?;
         witness.assign_table_circuit::<U8TableCircuit<E>>(cs, &self.u8_range_config, &())?;
         witness.assign_table_circuit::<U5TableCircuit<E>>(cs, &self.u5_range_config, &())?;
+        witness.assign_table_circuit::<PackedU8TableCircuit<E>>(
+            cs,
+            &self.packed_u8_range_config,
+            &(),
+        )?;
         witness.assign_table_circuit::<AndTableCircuit<E>>(cs, &self.and_table_config, &())?;
         witness.assign_table_circuit::<OrTableCircuit<E>>(cs, &self.or_table_config, &())?;
         witness.assign_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_table_config, &())?;
         witness.assign_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &())?;
         witness.assign_table_circuit::<PowTableCircuit<E>>(cs, &self.pow_config, &())?;
+        witness.assign_table_circuit::<ClzTableCircuit<E>>(cs, &self.clz_table_config, &())?;
+        witness.assign_table_circuit::<PopcountTableCircuit<E>>(
+            cs,
+            &self.popcount_table_config,
+            &(),
+        )?;
 
         Ok(())
     }
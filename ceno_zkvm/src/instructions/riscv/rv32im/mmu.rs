@@ -84,6 +84,8 @@ impl<E: ExtensionField> MmuConfig<E> {
         Ok(())
     }
 
+    /// The registers all start at zero, as fixed in the verifying key. The entry point of the
+    /// runtime sets up `sp` and `gp` itself.
     pub fn initial_registers(&self) -> Vec<MemInitRecord> {
         (0..<RegTable as NonVolatileTable>::len(&self.params))
             .map(|index| MemInitRecord {
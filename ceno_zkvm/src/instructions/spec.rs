@@ -0,0 +1,262 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::{Itertools, chain, izip};
+
+use crate::{
+    circuit_builder::ConstraintSystem,
+    error::ZKVMError,
+    expression::Expression,
+    structs::{RAMType, ROMType, WitnessId},
+};
+
+/// A witness column of a chip.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ColumnSpec {
+    pub id: WitnessId,
+    pub name: String,
+    /// The narrowest width that a range check or a boolean constraint bounds the column to.
+    pub bits: Option<usize>,
+}
+
+/// A lookup into a fixed table.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct LookupSpec {
+    pub name: String,
+    pub table: ROMType,
+}
+
+/// A read or a write of a state record.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct RecordSpec {
+    pub name: String,
+    pub ram_type: RAMType,
+}
+
+/// The structure of an instruction circuit, as built by its constraint system.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ChipSpec {
+    pub name: String,
+    pub columns: Vec<ColumnSpec>,
+    pub lookups: Vec<LookupSpec>,
+    pub reads: Vec<RecordSpec>,
+    pub writes: Vec<RecordSpec>,
+    /// The number of zero constraints, of any degree.
+    pub num_constraints: usize,
+}
+
+impl ChipSpec {
+    pub fn from_cs<E: ExtensionField>(name: String, cs: &ConstraintSystem<E>) -> Self {
+        let mut bits = vec![None; cs.num_witin as usize];
+        let mut bound = |expr: &Expression<E>, width: usize| {
+            if let Expression::WitIn(id) = expr {
+                let column = &mut bits[*id as usize];
+                *column = Some(column.map_or(width, |b: usize| b.min(width)));
+            }
+        };
+
+        for (rom_type, items) in &cs.lk_expressions_items_map {
+            match rom_type {
+                ROMType::U5 => bound(&items[0], 5),
+                ROMType::U8 => bound(&items[0], 8),
+                ROMType::U14 => bound(&items[0], 14),
+                ROMType::U16 => bound(&items[0], 16),
+                ROMType::PackedU5 => items.iter().for_each(|item| bound(item, 5)),
+                ROMType::PackedU8 => items.iter().for_each(|item| bound(item, 8)),
+                ROMType::And | ROMType::Or | ROMType::Xor => {
+                    items.iter().for_each(|item| bound(item, 8));
+                }
+                ROMType::Ltu => {
+                    bound(&items[0], 8);
+                    bound(&items[1], 8);
+                    bound(&items[2], 1);
+                }
+                ROMType::Clz => {
+                    bound(&items[0], 8);
+                    bound(&items[1], 4);
+                    bound(&items[2], 1);
+                }
+                ROMType::Popcount => {
+                    bound(&items[0], 8);
+                    bound(&items[1], 4);
+                }
+                ROMType::Pow | ROMType::Instruction | ROMType::Custom(_) => {}
+            }
+        }
+
+        // `assert_bit(x)` is the constraint `x * (1 - x) = 0`.
+        let constraints = chain!(
+            izip!(
+                &cs.assert_zero_expressions_namespace_map,
+                &cs.assert_zero_expressions
+            ),
+            izip!(
+                &cs.assert_zero_sumcheck_expressions_namespace_map,
+                &cs.assert_zero_sumcheck_expressions
+            ),
+        );
+        for (name, expr) in constraints {
+            let mut witins = BTreeSet::new();
+            collect_witins(expr, &mut witins);
+            if name.contains("assert_bit") && witins.len() == 1 {
+                let id = witins.pop_first().unwrap();
+                bound(&Expression::WitIn(id), 1);
+            }
+        }
+
+        let columns = izip!(0.., &cs.witin_namespace_map, bits)
+            .map(|(id, name, bits)| ColumnSpec {
+                id,
+                name: name.clone(),
+                bits,
+            })
+            .collect();
+        let lookups = izip!(
+            &cs.lk_expressions_namespace_map,
+            &cs.lk_expressions_items_map
+        )
+        .map(|(name, (table, _))| LookupSpec {
+            name: name.clone(),
+            table: *table,
+        })
+        .collect();
+        let records =
+            |names: &[String], ram_types: &[(RAMType, Vec<Expression<E>>)]| -> Vec<RecordSpec> {
+                izip!(names, ram_types)
+                    .map(|(name, (ram_type, _))| RecordSpec {
+                        name: name.clone(),
+                        ram_type: *ram_type,
+                    })
+                    .collect()
+            };
+
+        ChipSpec {
+            name,
+            columns,
+            lookups,
+            reads: records(&cs.r_expressions_namespace_map, &cs.r_ram_types),
+            writes: records(&cs.w_expressions_namespace_map, &cs.w_ram_types),
+            num_constraints: cs.assert_zero_expressions.len()
+                + cs.assert_zero_sumcheck_expressions.len(),
+        }
+    }
+
+    /// Check that the values of an assigned row fit the widths of their columns, to catch
+    /// witness assignment code that disagrees with the circuit.
+    pub fn check_row<F: SmallField>(&self, row: &[F]) -> Result<(), ZKVMError> {
+        for column in &self.columns {
+            let (Some(bits), Some(value)) = (column.bits, row.get(column.id as usize)) else {
+                continue;
+            };
+            let value = value.to_canonical_u64();
+            if value >> bits != 0 {
+                return Err(ZKVMError::InvalidWitness(format!(
+                    "{}: {} = {value:#x} does not fit in {bits} bits",
+                    self.name, column.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// A Markdown description of the chip.
+    pub fn to_markdown(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "## {}\n", self.name).unwrap();
+        writeln!(text, "{} constraints.\n", self.num_constraints).unwrap();
+        writeln!(text, "| column | name | bits |\n|---|---|---|").unwrap();
+        for column in &self.columns {
+            let bits = column.bits.map(|b| b.to_string()).unwrap_or_default();
+            writeln!(text, "| {} | `{}` | {bits} |", column.id, column.name).unwrap();
+        }
+        let lookups = self
+            .lookups
+            .iter()
+            .map(|lookup| format!("{:?}", lookup.table))
+            .counts();
+        writeln!(text, "\n| lookup table | count |\n|---|---|").unwrap();
+        for (table, count) in lookups.into_iter().sorted() {
+            writeln!(text, "| {table} | {count} |").unwrap();
+        }
+        writeln!(text, "\n| record | type |\n|---|---|").unwrap();
+        for (direction, record) in chain!(
+            self.reads.iter().map(|r| ("read", r)),
+            self.writes.iter().map(|w| ("write", w)),
+        ) {
+            writeln!(
+                text,
+                "| {direction} `{}` | {:?} |",
+                record.name, record.ram_type
+            )
+            .unwrap();
+        }
+        text
+    }
+}
+
+fn collect_witins<E: ExtensionField>(expr: &Expression<E>, witins: &mut BTreeSet<WitnessId>) {
+    match expr {
+        Expression::WitIn(id) => {
+            witins.insert(*id);
+        }
+        Expression::Sum(a, b) | Expression::Product(a, b) => {
+            collect_witins(a, witins);
+            collect_witins(b, witins);
+        }
+        Expression::ScaledSum(x, a, b) => {
+            collect_witins(x, witins);
+            collect_witins(a, witins);
+            collect_witins(b, witins);
+        }
+        Expression::Fixed(_)
+        | Expression::Instance(_)
+        | Expression::Constant(_)
+        | Expression::Challenge(..) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+
+    use super::RecordSpec;
+    use crate::{
+        instructions::{Instruction, riscv::arith_imm::AddiInstruction},
+        structs::{RAMType, ROMType},
+    };
+
+    #[test]
+    fn test_describe_addi() {
+        let spec = AddiInstruction::<GoldilocksExt2>::describe();
+        assert_eq!(spec.name, "ADDI");
+        assert!(spec.columns.iter().all(|c| c.name.starts_with("ADDI/")));
+
+        // The state, rs1 and rd are each read, then written.
+        let ram_types =
+            |records: &[RecordSpec]| records.iter().map(|r| r.ram_type).collect::<Vec<_>>();
+        let expected = vec![RAMType::GlobalState, RAMType::Register, RAMType::Register];
+        assert_eq!(ram_types(&spec.reads), expected);
+        assert_eq!(ram_types(&spec.writes), expected);
+        assert_eq!(
+            spec.lookups
+                .iter()
+                .filter(|l| l.table == ROMType::Instruction)
+                .count(),
+            1
+        );
+
+        // The immediate limbs are range checked.
+        let imm = spec
+            .columns
+            .iter()
+            .find(|c| c.name.contains("imm") && c.bits == Some(16))
+            .unwrap();
+        let mut row = vec![Goldilocks::from(0); spec.columns.len()];
+        assert!(spec.check_row(&row).is_ok());
+        row[imm.id as usize] = Goldilocks::from(1 << 16);
+        assert!(spec.check_row(&row).is_err());
+
+        assert!(spec.to_markdown().starts_with("## ADDI\n"));
+    }
+}
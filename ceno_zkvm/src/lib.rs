@@ -1,6 +1,5 @@
 #![feature(box_patterns)]
 #![feature(stmt_expr_attributes)]
-#![feature(variant_count)]
 #![feature(strict_overflow_ops)]
 
 pub mod error;
@@ -18,6 +17,7 @@ pub mod stats;
 pub mod structs;
 mod uint;
 mod utils;
+pub mod verifier;
 mod virtual_polys;
 mod witness;
 
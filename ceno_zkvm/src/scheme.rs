@@ -1,14 +1,25 @@
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 use itertools::Itertools;
 use mpcs::PolynomialCommitmentScheme;
-use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Debug};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{collections::BTreeMap, fmt::Debug, time::Duration};
 use sumcheck::structs::IOPProverMessage;
+use transcript::Transcript;
 
-use crate::structs::TowerProofs;
+use crate::{
+    instructions::riscv::constants::{EXIT_CODE_IDX, LIMB_BITS},
+    structs::TowerProofs,
+};
+use auxiliary::ZKVMAuxiliaryProof;
+use packing::PackedSlot;
 
+pub mod auxiliary;
+pub mod commit_stream;
 pub mod constants;
+pub mod packing;
 pub mod prover;
+pub mod record_witness;
 pub mod utils;
 pub mod verifier;
 
@@ -16,7 +27,7 @@ pub mod mock_prover;
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO support >1 opcodes
     pub num_instances: usize,
@@ -40,8 +51,9 @@ pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
     pub lk_records_in_evals: Vec<E>,
 
     pub wits_commit: PCS::Commitment,
-    pub wits_opening_proof: PCS::Proof,
     pub wits_in_evals: Vec<E>,
+    /// Where the witness is in `wits_commit` if it is shared with other circuits.
+    pub packed_slot: Option<PackedSlot>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -61,10 +73,8 @@ pub struct ZKVMTableProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>
     pub rw_hints_num_vars: Vec<usize>,
 
     pub fixed_in_evals: Vec<E>,
-    pub fixed_opening_proof: Option<PCS::Proof>,
     pub wits_commit: PCS::Commitment,
     pub wits_in_evals: Vec<E>,
-    pub wits_opening_proof: PCS::Proof,
 }
 
 /// each field will be interpret to (constant) polynomial
@@ -76,6 +86,10 @@ pub struct PublicValues<T: Default + Clone + Debug> {
     end_pc: T,
     end_cycle: T,
     public_io: Vec<T>,
+    /// The register file after execution, padded like the register table.
+    final_regs: Vec<T>,
+    /// The seed of the RANDOM ecall, as its low and high halves.
+    random_seed: [T; 2],
 }
 
 impl PublicValues<u32> {
@@ -86,6 +100,7 @@ impl PublicValues<u32> {
         end_pc: u32,
         end_cycle: u32,
         public_io: Vec<u32>,
+        final_regs: Vec<u32>,
     ) -> Self {
         Self {
             exit_code,
@@ -94,8 +109,26 @@ impl PublicValues<u32> {
             end_pc,
             end_cycle,
             public_io,
+            final_regs,
+            random_seed: [0, 0],
         }
     }
+
+    /// Commit to the seed of the RANDOM ecall, see `VMState::set_random_seed`.
+    pub fn with_random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = [seed as u32, (seed >> 32) as u32];
+        self
+    }
+
+    pub fn random_seed(&self) -> u64 {
+        self.random_seed[0] as u64 | ((self.random_seed[1] as u64) << 32)
+    }
+
+    /// The value of a register after execution, e.g. `Platform::reg_arg0()`.
+    pub fn final_reg(&self, index: usize) -> u32 {
+        self.final_regs.get(index).copied().unwrap_or(0)
+    }
+
     pub fn to_vec<E: ExtensionField>(&self) -> Vec<Vec<E::BaseField>> {
         vec![
             vec![E::BaseField::from((self.exit_code & 0xffff) as u64)],
@@ -108,14 +141,30 @@ impl PublicValues<u32> {
                 .iter()
                 .map(|e| E::BaseField::from(*e as u64))
                 .collect(),
+            self.final_regs
+                .iter()
+                .map(|e| E::BaseField::from((e & 0xffff) as u64))
+                .collect(),
+            self.final_regs
+                .iter()
+                .map(|e| E::BaseField::from(((e >> 16) & 0xffff) as u64))
+                .collect(),
+            vec![E::BaseField::from(self.random_seed[0] as u64)],
+            vec![E::BaseField::from(self.random_seed[1] as u64)],
         ]
     }
 }
 
 /// Map circuit names to
 /// - an opcode or table proof,
-/// - an index unique across both types.
-#[derive(Clone)]
+/// - an index unique across both types,
+///
+/// and the names of the auxiliary sub-protocols to their proofs, with indices following.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO preserve in serde only for auxiliary public input
     // other raw value can be construct by verifier directly.
@@ -124,6 +173,13 @@ pub struct ZKVMProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pi_evals: Vec<E>,
     opcode_proofs: BTreeMap<String, (usize, ZKVMOpcodeProof<E, PCS>)>,
     table_proofs: BTreeMap<String, (usize, ZKVMTableProof<E, PCS>)>,
+    // the sub-protocols proven alongside, with indices after the ones of the circuits
+    aux_proofs: BTreeMap<String, (usize, ZKVMAuxiliaryProof<E, PCS>)>,
+    // one opening of the witness and fixed commitments of all circuits
+    pcs_opening_proof: Option<PCS::Proof>,
+    // not part of the transcript, verification ignores it
+    #[serde(default)]
+    pub metadata: Option<ZKVMProofMetadata>,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
@@ -147,9 +203,22 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
             pi_evals,
             opcode_proofs: BTreeMap::new(),
             table_proofs: BTreeMap::new(),
+            aux_proofs: BTreeMap::new(),
+            pcs_opening_proof: None,
+            metadata: None,
         }
     }
 
+    /// The exit code of the HALT step, or zero if the trace does not halt.
+    pub fn exit_code(&self) -> u32 {
+        let limb = |idx: usize| {
+            self.raw_pi[idx]
+                .first()
+                .map_or(0, |v| v.to_canonical_u64() as u32)
+        };
+        limb(EXIT_CODE_IDX) | (limb(EXIT_CODE_IDX + 1) << LIMB_BITS)
+    }
+
     pub fn update_pi_eval(&mut self, idx: usize, v: E) {
         self.pi_evals[idx] = v;
     }
@@ -160,3 +229,45 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
         self.opcode_proofs.len() + self.table_proofs.len()
     }
 }
+
+/// How a proof was produced, for monitoring. It is not bound to the transcript, so it can be
+/// stripped or replaced without affecting verification. Its checksum is unkeyed: it catches
+/// accidental corruption, not tampering, since anyone can recompute it after an edit. See
+/// [`verifier::ZKVMVerifier::verify_metadata`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZKVMProofMetadata {
+    /// The version of the crate that produced the proof.
+    pub prover_version: String,
+    /// The digest of the verifying key, see `ZKVMVerifyingKey::digest`.
+    pub params_digest: Vec<u64>,
+    /// The duration of each phase of the prover, in order.
+    pub timings: Vec<(String, Duration)>,
+    /// The number of executed instructions.
+    pub num_steps: usize,
+    /// The checksum of all the other fields, set by [`Self::with_checksum`].
+    pub checksum: Vec<u64>,
+}
+
+impl ZKVMProofMetadata {
+    fn compute_checksum<E: ExtensionField>(&self) -> Vec<u64> {
+        let mut transcript = Transcript::<E>::new(b"zkvm_proof_metadata");
+        let fields = Self {
+            checksum: vec![],
+            ..self.clone()
+        };
+        transcript.append_message(&serde_json::to_vec(&fields).unwrap());
+        transcript.read_challenge().elements.to_canonical_u64_vec()
+    }
+
+    /// Set the checksum of the metadata.
+    pub fn with_checksum<E: ExtensionField>(mut self) -> Self {
+        self.checksum = self.compute_checksum::<E>();
+        self
+    }
+
+    /// Whether the metadata matches its checksum, i.e. it was not corrupted since the checksum
+    /// was set. This does not authenticate the metadata.
+    pub fn checksum_matches<E: ExtensionField>(&self) -> bool {
+        self.checksum == self.compute_checksum::<E>()
+    }
+}
@@ -0,0 +1,61 @@
+//! Hooks for the sub-protocols proven alongside the zkVM, e.g. a GKR-proven precompile.
+//!
+//! Such a protocol reduces its statement to evaluations of its witness polynomials at one
+//! point. Instead of opening them by itself, it hands these claims to the zkVM, which commits
+//! to its witness together with the traces of the circuits, and discharges the claims in its
+//! final PCS opening. The protocol runs with the challenges of the zkVM and on its own fork of
+//! the transcript, like a circuit, so that its challenges depend on all the commitments and
+//! the final opening depends on its messages.
+
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::mle::DenseMultilinearExtension;
+use serde::{Deserialize, Serialize};
+use transcript::Transcript;
+
+use crate::{error::ZKVMError, structs::Point};
+
+/// The prover of a sub-protocol, see the module documentation.
+pub trait AuxiliaryProver<E: ExtensionField> {
+    /// The name of the protocol, unique among the protocols of a proof.
+    fn name(&self) -> String;
+
+    /// The witness polynomials that the zkVM commits to, all with the same number of variables.
+    fn witness(&self) -> Vec<DenseMultilinearExtension<E>>;
+
+    /// Prove the protocol with the zkVM `challenges`, drawn after all the commitments, and
+    /// the `transcript` of the protocol. Return the proof, to be read by
+    /// [`AuxiliaryVerifier::verify`], and the point at which the witness is opened with the
+    /// evaluations of every witness polynomial.
+    fn prove(
+        &self,
+        challenges: &[E; 2],
+        transcript: &mut Transcript<E>,
+    ) -> Result<(Vec<u8>, Point<E>, Vec<E>), ZKVMError>;
+}
+
+/// The verifier of a sub-protocol, see the module documentation.
+pub trait AuxiliaryVerifier<E: ExtensionField> {
+    /// The name of the protocol, the same as its [`AuxiliaryProver::name`].
+    fn name(&self) -> String;
+
+    /// Verify `proof` with the zkVM `challenges` and the `transcript` of the protocol, given
+    /// the claimed evaluations of the witness polynomials. Return the point of the claims,
+    /// which the final opening of the zkVM checks.
+    fn verify(
+        &self,
+        proof: &[u8],
+        wits_in_evals: &[E],
+        challenges: &[E; 2],
+        transcript: &mut Transcript<E>,
+    ) -> Result<Point<E>, ZKVMError>;
+}
+
+/// The part of a zkVM proof of a sub-protocol.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ZKVMAuxiliaryProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub wits_commit: PCS::Commitment,
+    pub wits_in_evals: Vec<E>,
+    /// The proof of the protocol, in its own encoding.
+    pub proof: Vec<u8>,
+}
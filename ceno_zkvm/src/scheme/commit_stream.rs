@@ -0,0 +1,311 @@
+//! The commitments to the witnesses of the circuits, made either after all the circuits are
+//! assigned, or on another thread while the next circuits are still being assigned, see
+//! [`commit_while_assigning`].
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, mpsc},
+    thread,
+    time::Duration,
+};
+
+use ff_ext::ExtensionField;
+use itertools::{chain, izip};
+use mpcs::PolynomialCommitmentScheme;
+use multilinear_extensions::{mle::DenseMultilinearExtension, util::ceil_log2};
+use sumcheck::{entered_span, exit_span};
+
+use crate::{
+    error::ZKVMError,
+    scheme::packing::{PackedSlot, pack_circuits, pack_witnesses},
+    structs::ZKVMWitnesses,
+    utils::{Instant, next_pow2_instance_padding},
+    witness::RowMajorMatrix,
+};
+
+/// Whether a witness is of an opcode or a table circuit, which sets the order of its commitment
+/// in the transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CircuitKind {
+    Opcode,
+    Table,
+}
+
+/// The witness of a circuit as polynomials, and its commitment if it has any instances.
+pub struct CommittedWitness<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub(crate) witness: Vec<DenseMultilinearExtension<E>>,
+    pub(crate) num_instances: usize,
+    /// Shared with the other circuits of its group if the witness is packed.
+    pub(crate) commitment: Option<Arc<PCS::CommitmentWithData>>,
+    pub(crate) packed_slot: Option<PackedSlot>,
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> CommittedWitness<E, PCS> {
+    fn commit(
+        pp: &PCS::ProverParam,
+        circuit_name: &str,
+        witness: RowMajorMatrix<E::BaseField>,
+    ) -> Result<Self, ZKVMError> {
+        let num_instances = witness.num_instances();
+        if num_instances == 0 {
+            return Ok(CommittedWitness {
+                witness: vec![],
+                num_instances,
+                commitment: None,
+                packed_slot: None,
+            });
+        }
+        let span = entered_span!("commit to iteration", circuit_name = circuit_name);
+        let witness = witness.into_mles_in_place();
+        let commitment = PCS::batch_commit(pp, &witness).map_err(ZKVMError::PCSError)?;
+        exit_span!(span);
+        Ok(CommittedWitness {
+            witness,
+            num_instances,
+            commitment: Some(Arc::new(commitment)),
+            packed_slot: None,
+        })
+    }
+}
+
+/// The committed witnesses of all the circuits, ready to be proven by
+/// [`super::prover::ZKVMProver::create_proof_from_committed`].
+pub struct CommittedWitnesses<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    opcodes: BTreeMap<String, CommittedWitness<E, PCS>>,
+    tables: BTreeMap<String, CommittedWitness<E, PCS>>,
+    /// The time spent committing, excluding the wait for the witnesses.
+    pub(crate) commit_time: Duration,
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> CommittedWitnesses<E, PCS> {
+    fn new() -> Self {
+        CommittedWitnesses {
+            opcodes: BTreeMap::new(),
+            tables: BTreeMap::new(),
+            commit_time: Duration::ZERO,
+        }
+    }
+
+    /// Commit to all the witnesses, one circuit after the other.
+    pub fn commit_all(
+        pp: &PCS::ProverParam,
+        witnesses: ZKVMWitnesses<E>,
+    ) -> Result<Self, ZKVMError> {
+        Committer::new(pp, None).commit_all(witnesses)
+    }
+
+    /// Commit to all the witnesses like [`Self::commit_all`], but pack those of the opcode
+    /// circuits of fewer than `min_num_vars` variables into shared commitments, see
+    /// [`super::packing`].
+    pub fn commit_all_packed(
+        pp: &PCS::ProverParam,
+        witnesses: ZKVMWitnesses<E>,
+        min_num_vars: usize,
+    ) -> Result<Self, ZKVMError> {
+        Committer::new(pp, Some(min_num_vars)).commit_all(witnesses)
+    }
+
+    fn commit(
+        &mut self,
+        pp: &PCS::ProverParam,
+        kind: CircuitKind,
+        circuit_name: String,
+        witness: RowMajorMatrix<E::BaseField>,
+    ) -> Result<(), ZKVMError> {
+        let start = Instant::now();
+        let committed = CommittedWitness::commit(pp, &circuit_name, witness)?;
+        self.commit_time += start.elapsed();
+        self.insert(kind, circuit_name, committed)
+    }
+
+    fn insert(
+        &mut self,
+        kind: CircuitKind,
+        circuit_name: String,
+        committed: CommittedWitness<E, PCS>,
+    ) -> Result<(), ZKVMError> {
+        let circuits = match kind {
+            CircuitKind::Opcode => &mut self.opcodes,
+            CircuitKind::Table => &mut self.tables,
+        };
+        if circuits.insert(circuit_name.clone(), committed).is_some() {
+            return Err(ZKVMError::InvalidWitness(format!(
+                "the witness of {circuit_name} is committed twice"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Iterate opcode circuits, then table circuits, sorted by name.
+    pub(crate) fn into_iter_sorted(
+        self,
+    ) -> impl Iterator<Item = (String, CommittedWitness<E, PCS>)> {
+        chain(self.opcodes, self.tables)
+    }
+}
+
+/// Commits to the witnesses as they come, except those of the small opcode circuits when
+/// packing, which are held back to be packed together by [`Committer::finish`].
+struct Committer<'a, E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pp: &'a PCS::ProverParam,
+    /// Pack the opcode circuits of fewer variables, if any.
+    min_num_vars: Option<usize>,
+    committed: CommittedWitnesses<E, PCS>,
+    small: BTreeMap<String, RowMajorMatrix<E::BaseField>>,
+}
+
+impl<'a, E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> Committer<'a, E, PCS> {
+    fn new(pp: &'a PCS::ProverParam, min_num_vars: Option<usize>) -> Self {
+        Committer {
+            pp,
+            min_num_vars,
+            committed: CommittedWitnesses::new(),
+            small: BTreeMap::new(),
+        }
+    }
+
+    fn num_vars(witness: &RowMajorMatrix<E::BaseField>) -> usize {
+        ceil_log2(next_pow2_instance_padding(witness.num_instances()))
+    }
+
+    fn commit_all(
+        mut self,
+        mut witnesses: ZKVMWitnesses<E>,
+    ) -> Result<CommittedWitnesses<E, PCS>, ZKVMError> {
+        for (kind, circuit_name, witness) in witnesses.take_witnesses() {
+            self.commit(kind, circuit_name, witness)?;
+        }
+        self.finish()
+    }
+
+    fn commit(
+        &mut self,
+        kind: CircuitKind,
+        circuit_name: String,
+        witness: RowMajorMatrix<E::BaseField>,
+    ) -> Result<(), ZKVMError> {
+        match self.min_num_vars {
+            Some(min_num_vars)
+                if kind == CircuitKind::Opcode
+                    && witness.num_instances() > 0
+                    && Self::num_vars(&witness) < min_num_vars =>
+            {
+                if self.small.insert(circuit_name.clone(), witness).is_some() {
+                    return Err(ZKVMError::InvalidWitness(format!(
+                        "the witness of {circuit_name} is committed twice"
+                    )));
+                }
+                Ok(())
+            }
+            _ => self.committed.commit(self.pp, kind, circuit_name, witness),
+        }
+    }
+
+    /// Pack and commit to the witnesses held back.
+    fn finish(mut self) -> Result<CommittedWitnesses<E, PCS>, ZKVMError> {
+        let Some(min_num_vars) = self.min_num_vars else {
+            return Ok(self.committed);
+        };
+        let (pp, committed, mut small) = (self.pp, &mut self.committed, self.small);
+        for group in pack_circuits(
+            small
+                .iter()
+                .map(|(circuit_name, witness)| (circuit_name.clone(), Self::num_vars(witness))),
+            min_num_vars,
+        ) {
+            let start = Instant::now();
+            let span = entered_span!("commit to packed iterations", num_circuits = group.len());
+            let (num_instances, witnesses): (Vec<_>, Vec<_>) = group
+                .iter()
+                .map(|circuit_name| {
+                    let witness = small.remove(circuit_name).unwrap();
+                    (witness.num_instances(), witness.into_mles_in_place())
+                })
+                .unzip();
+            let (packed, slots) = pack_witnesses(&witnesses);
+            let commitment = Arc::new(PCS::batch_commit(pp, &packed).map_err(ZKVMError::PCSError)?);
+            exit_span!(span);
+            committed.commit_time += start.elapsed();
+            for (circuit_name, num_instances, witness, slot) in
+                izip!(group, num_instances, witnesses, slots)
+            {
+                committed.insert(CircuitKind::Opcode, circuit_name, CommittedWitness {
+                    witness,
+                    num_instances,
+                    commitment: Some(commitment.clone()),
+                    packed_slot: Some(slot),
+                })?;
+            }
+        }
+        // the circuits left alone in their group
+        for (circuit_name, witness) in small {
+            committed.commit(pp, CircuitKind::Opcode, circuit_name, witness)?;
+        }
+        Ok(self.committed)
+    }
+}
+
+type WitnessMessage<E> = (
+    CircuitKind,
+    String,
+    RowMajorMatrix<<E as ExtensionField>::BaseField>,
+);
+
+/// Where the assigned witnesses go to be committed, see [`commit_while_assigning`].
+pub struct WitnessSink<E: ExtensionField>(mpsc::SyncSender<WitnessMessage<E>>);
+
+impl<E: ExtensionField> WitnessSink<E> {
+    /// Send the witnesses assigned so far to be committed. They are taken out of `witnesses`,
+    /// which keeps the lookup multiplicities needed by the next circuits.
+    pub fn send(&self, witnesses: &mut ZKVMWitnesses<E>) -> Result<(), ZKVMError> {
+        for message in witnesses.take_witnesses() {
+            self.0.send(message).map_err(|_| {
+                ZKVMError::InvalidWitness("the commitment of the witnesses has stopped".into())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `assign`, and commit to the witnesses that it sends to the sink on another thread in the
+/// meantime. At most `capacity` witnesses wait for their commitment, after which sending blocks,
+/// to bound the memory of the witnesses in flight.
+///
+/// With `min_num_vars`, the witnesses of the smaller opcode circuits are held back and packed
+/// once `assign` is done. The commitments are the same as with
+/// [`CommittedWitnesses::commit_all`], or [`CommittedWitnesses::commit_all_packed`], and so is
+/// the proof.
+pub fn commit_while_assigning<E, PCS, R>(
+    pp: &PCS::ProverParam,
+    capacity: usize,
+    min_num_vars: Option<usize>,
+    assign: impl FnOnce(&WitnessSink<E>) -> Result<R, ZKVMError>,
+) -> Result<(R, CommittedWitnesses<E, PCS>), ZKVMError>
+where
+    E: ExtensionField,
+    PCS: PolynomialCommitmentScheme<E>,
+    PCS::ProverParam: Sync,
+    PCS::CommitmentWithData: Send + Sync,
+{
+    let (sender, receiver) = mpsc::sync_channel::<WitnessMessage<E>>(capacity);
+    thread::scope(|scope| {
+        let committer = scope.spawn(move || {
+            let mut committer = Committer::new(pp, min_num_vars);
+            for (kind, circuit_name, witness) in receiver {
+                committer.commit(kind, circuit_name, witness)?;
+            }
+            committer.finish()
+        });
+
+        let sink = WitnessSink(sender);
+        let assigned = assign(&sink);
+        // Closing the channel lets the committer finish.
+        drop(sink);
+        let committed = committer
+            .join()
+            .expect("the commitment of the witnesses panicked");
+        // A failed commitment makes sending fail, so report it first.
+        let committed = committed?;
+        Ok((assigned?, committed))
+    })
+}
@@ -1,8 +1,20 @@
 pub(crate) const MIN_PAR_SIZE: usize = 64;
 pub(crate) const MAINCONSTRAIN_SUMCHECK_BATCH_SIZE: usize = 3; // read/write/lookup
 pub(crate) const SEL_DEGREE: usize = 2;
+/// default max degree of a zero constraint, before it is multiplied by the selector
+pub const MAX_CONSTRAINT_DEGREE: usize = 2;
 
 pub const NUM_FANIN: usize = 2;
 pub const NUM_FANIN_LOGUP: usize = 2;
 
 pub const MAX_NUM_VARIABLES: usize = 24;
+
+/// The opcode circuits with witnesses of fewer variables are packed into shared commitments,
+/// see [`super::packing`].
+pub const PACKING_MIN_NUM_VARS: usize = 12;
+
+/// The label of the transcript of a zkVM proof, for the prover and the verifier.
+pub const TRANSCRIPT_LABEL: &[u8] = b"riscv";
+
+/// The name of the ecall/halt circuit, whose proof tells whether the trace halts.
+pub const HALT_CIRCUIT_NAME: &str = "ECALL_HALT";
@@ -4,40 +4,44 @@ use super::{
 };
 use crate::{
     ROMType,
-    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    circuit_builder::{CircuitBuilder, ConstraintSystem, in_scope},
     expression::{Expression, fmt},
-    scheme::utils::{eval_by_expr_with_fixed, eval_by_expr_with_instance},
+    instructions::riscv::{DummyExtraConfig, Rv32imConfig},
+    scheme::utils::eval_by_expr_with_instance,
     state::{GlobalState, StateCircuit},
-    structs::{ProgramParams, RAMType, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+    structs::{RAMType, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
     tables::{
-        AndTable, LtuTable, OpsTable, OrTable, PowTable, ProgramTableCircuit, RangeTable,
-        TableCircuit, U5Table, U8Table, U14Table, U16Table, XorTable,
+        AndTable, ClzTable, LtuTable, OpsTable, OrTable, PackedRangeTable, PackedU5Table,
+        PackedU8Table, PopcountTable, PowTable, XorTable, program_table_content, rom_table_content,
     },
     witness::{LkMultiplicity, RowMajorMatrix},
 };
 use ark_std::test_rng;
-use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD};
-use ceno_emul::{ByteAddr, CENO_PLATFORM, PC_WORD_SIZE, Program};
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM, Cycle, PC_WORD_SIZE, Program, StepRecord, Tracer, fmt_insn,
+};
 use ff::Field;
 use ff_ext::ExtensionField;
 use generic_static::StaticTypeMap;
 use goldilocks::SmallField;
-use itertools::{Itertools, enumerate, izip};
-use multilinear_extensions::{mle::IntoMLEs, virtual_poly_v2::ArcMultilinearExtension};
+use itertools::{Itertools, chain, enumerate, izip};
+use multilinear_extensions::{
+    mle::{FieldType, IntoMLEs},
+    virtual_poly_v2::ArcMultilinearExtension,
+};
 use rand::thread_rng;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
-    fs::File,
     hash::Hash,
-    io::{BufReader, ErrorKind},
     marker::PhantomData,
     ops::Neg,
     sync::OnceLock,
 };
-use strum::IntoEnumIterator;
 
 const MAX_CONSTRAINT_DEGREE: usize = 2;
 const MOCK_PROGRAM_SIZE: usize = 32;
+/// How many instructions are shown on each side of a failed fetch.
+const FETCH_CONTEXT: usize = 3;
 pub const MOCK_PC_START: ByteAddr = ByteAddr(CENO_PLATFORM.pc_base());
 
 #[allow(clippy::enum_variant_names)]
@@ -67,10 +71,16 @@ pub enum MockProverError<E: ExtensionField> {
         evaluated: E,
         name: String,
         inst_id: usize,
+        /// The looked-up values, if known, e.g. the fetched pc first.
+        values: Vec<u64>,
+    },
+    RAMError {
+        ram_type: RAMType,
+        values: Vec<u64>,
+        name: String,
+        missing_in: &'static str, // "reads" or "writes"
+        inst_id: usize,
     },
-    // TODO later
-    // r_expressions
-    // w_expressions
     LkMultiplicityError {
         rom_type: ROMType,
         key: u64,
@@ -143,13 +153,39 @@ impl<E: ExtensionField> PartialEq for MockProverError<E> {
                     && left_evaluated == right_evaluated
                     && left_name == right_name
             }
+            (
+                MockProverError::RAMError {
+                    ram_type: left_ram_type,
+                    values: left_values,
+                    name: left_name,
+                    missing_in: left_missing_in,
+                    ..
+                },
+                MockProverError::RAMError {
+                    ram_type: right_ram_type,
+                    values: right_values,
+                    name: right_name,
+                    missing_in: right_missing_in,
+                    ..
+                },
+            ) => {
+                left_ram_type == right_ram_type
+                    && left_values == right_values
+                    && left_name == right_name
+                    && left_missing_in == right_missing_in
+            }
             _ => false,
         }
     }
 }
 
 impl<E: ExtensionField> MockProverError<E> {
-    pub fn print(&self, wits_in: &[ArcMultilinearExtension<E>], wits_in_name: &[String]) {
+    pub fn print(
+        &self,
+        wits_in: &[ArcMultilinearExtension<E>],
+        wits_in_name: &[String],
+        programs: &[u32],
+    ) {
         let mut wtns = vec![];
 
         match self {
@@ -207,6 +243,7 @@ impl<E: ExtensionField> MockProverError<E> {
                 evaluated,
                 name,
                 inst_id,
+                values,
             } => {
                 let expression_fmt = fmt::expr(expression, &mut wtns, false);
                 let wtns_fmt = fmt::wtns(&wtns, wits_in, *inst_id, wits_in_name);
@@ -217,6 +254,30 @@ impl<E: ExtensionField> MockProverError<E> {
                     Evaluation: {eval_fmt}\n\
                     Inst[{inst_id}]:\n{wtns_fmt}\n",
                 );
+                if let Some(&pc) = values.first().filter(|_| name.ends_with("fetch")) {
+                    let idx = (pc as usize).wrapping_sub(MOCK_PC_START.0 as usize) / PC_WORD_SIZE;
+                    println!("Program around pc {pc:#x}:");
+                    for i in idx.saturating_sub(FETCH_CONTEXT)
+                        ..programs.len().min(idx.saturating_add(FETCH_CONTEXT + 1))
+                    {
+                        let marker = if i == idx { ">" } else { " " };
+                        println!("{marker} {}", fmt_program_insn(programs, i));
+                    }
+                    println!();
+                }
+            }
+            Self::RAMError {
+                ram_type,
+                values,
+                name,
+                missing_in,
+                inst_id,
+            } => {
+                println!(
+                    "\nRAMError {name:?}: {ram_type:?} record not found in {missing_in}\n\
+                    Record: {values:x?}\n\
+                    Inst[{inst_id}]\n",
+                );
             }
             Self::LkMultiplicityError {
                 rom_type,
@@ -258,7 +319,20 @@ impl<E: ExtensionField> MockProverError<E> {
                         let (a, b) = PowTable::unpack(*key);
                         format!("Element: {a} ** {b}")
                     }
-                    ROMType::Instruction => format!("PC: {key}"),
+                    ROMType::Clz => format!("Element: clz({key})"),
+                    ROMType::Popcount => format!("Element: popcount({key})"),
+                    ROMType::PackedU5 => format!("Elements: {:?}", PackedU5Table::unpack(*key)),
+                    ROMType::PackedU8 => format!("Elements: {:?}", PackedU8Table::unpack(*key)),
+                    ROMType::Instruction => {
+                        let idx =
+                            (*key as usize).wrapping_sub(MOCK_PC_START.0 as usize) / PC_WORD_SIZE;
+                        if idx < programs.len() {
+                            format!("Element: {}", fmt_program_insn(programs, idx))
+                        } else {
+                            format!("PC: {key:#x}")
+                        }
+                    }
+                    ROMType::Custom(_) => format!("Key: {key}"),
                 };
                 println!(
                     "\nLkMultiplicityError:\n\
@@ -269,69 +343,251 @@ impl<E: ExtensionField> MockProverError<E> {
         }
     }
 
-    #[cfg(test)]
-    fn inst_id(&self) -> usize {
+    /// The instance where the error happens, if it is about a single instance.
+    pub fn inst_id(&self) -> Option<usize> {
         match self {
             Self::AssertZeroError { inst_id, .. }
             | Self::AssertEqualError { inst_id, .. }
             | Self::LookupError { inst_id, .. }
-            | Self::LkMultiplicityError { inst_id, .. } => *inst_id,
-            Self::DegreeTooHigh { .. } => unreachable!(),
+            | Self::RAMError { inst_id, .. }
+            | Self::LkMultiplicityError { inst_id, .. } => Some(*inst_id),
+            Self::DegreeTooHigh { .. } => None,
         }
     }
 
     fn contains(&self, constraint_name: &str) -> bool {
         format!("{:?}", self).contains(constraint_name)
     }
+
+    /// The path of the constraint that failed, if the error is about a single constraint.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::AssertZeroError { name, .. }
+            | Self::AssertEqualError { name, .. }
+            | Self::DegreeTooHigh { name, .. }
+            | Self::LookupError { name, .. }
+            | Self::RAMError { name, .. } => Some(name.as_str()),
+            Self::LkMultiplicityError { .. } => None,
+        }
+    }
+
+    /// Whether the failed constraint was created in the subtree of `scope`, see [`in_scope`].
+    pub fn in_scope(&self, scope: &str) -> bool {
+        self.name().is_some_and(|name| in_scope(name, scope))
+    }
 }
 
-pub struct MockProver<E: ExtensionField> {
-    _phantom: PhantomData<E>,
+/// An error of [`MockProver::run_trace`], attributed to a chip and, if it is about a single
+/// instance, to the cycle of its step.
+#[derive(Debug, Clone)]
+pub struct TraceError<E: ExtensionField> {
+    pub chip: String,
+    pub cycle: Option<Cycle>,
+    pub error: MockProverError<E>,
 }
 
-fn load_tables<E: ExtensionField>(cb: &CircuitBuilder<E>, challenge: [E; 2]) -> HashSet<Vec<u64>> {
-    fn load_range_table<RANGE: RangeTable, E: ExtensionField>(
-        t_vec: &mut Vec<Vec<u64>>,
-        cb: &CircuitBuilder<E>,
-        challenge: [E; 2],
-    ) {
-        for i in RANGE::content() {
-            let rlc_record =
-                cb.rlc_chip_record(vec![(RANGE::ROM_TYPE as usize).into(), (i as usize).into()]);
-            let rlc_record = eval_by_expr(&[], &challenge, &rlc_record);
-            t_vec.push(rlc_record.to_canonical_u64_vec());
+/// Whether a constraint was exercised by the witnesses of [`MockProver::coverage`].
+#[derive(Debug, Clone)]
+pub struct ConstraintCoverage {
+    /// `"assert_zero"` or `"lookup"`.
+    pub kind: &'static str,
+    pub name: String,
+    /// The number of instances where the constraint is not trivially satisfied.
+    pub active_instances: usize,
+    pub num_instances: usize,
+}
+
+/// The coverage of all the zero constraints and lookups of a circuit.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    pub constraints: Vec<ConstraintCoverage>,
+}
+
+impl CoverageReport {
+    /// Add the coverage of other witnesses for the same circuit.
+    pub fn merge(&mut self, other: &CoverageReport) {
+        if self.constraints.is_empty() {
+            self.constraints = other.constraints.clone();
+            return;
+        }
+        for (this, other) in izip!(&mut self.constraints, &other.constraints) {
+            assert_eq!(this.name, other.name, "coverage of different circuits");
+            this.active_instances += other.active_instances;
+            this.num_instances += other.num_instances;
         }
     }
 
-    fn load_op_table<OP: OpsTable, E: ExtensionField>(
-        t_vec: &mut Vec<Vec<u64>>,
-        cb: &CircuitBuilder<E>,
-        challenge: [E; 2],
-    ) {
-        for [a, b, c] in OP::content() {
-            let rlc_record = cb.rlc_chip_record(vec![
-                (OP::ROM_TYPE as usize).into(),
-                (a as usize).into(),
-                (b as usize).into(),
-                (c as usize).into(),
-            ]);
-            let rlc_record = eval_by_expr(&[], &challenge, &rlc_record);
-            t_vec.push(rlc_record.to_canonical_u64_vec());
+    /// The coverage of the constraints created in the subtree of `scope`, see [`in_scope`].
+    pub fn in_scope(&self, scope: &str) -> CoverageReport {
+        CoverageReport {
+            constraints: self
+                .constraints
+                .iter()
+                .filter(|c| in_scope(&c.name, scope))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The constraints that no instance exercised.
+    pub fn never_exercised(&self) -> Vec<&ConstraintCoverage> {
+        self.constraints
+            .iter()
+            .filter(|c| c.active_instances == 0)
+            .collect()
+    }
+
+    pub fn print(&self) {
+        let never_exercised = self.never_exercised();
+        println!(
+            "{} of {} constraints never exercised",
+            never_exercised.len(),
+            self.constraints.len()
+        );
+        for c in never_exercised {
+            println!(
+                "  {} {:?} (0 of {} instances)",
+                c.kind, c.name, c.num_instances
+            );
+        }
+    }
+}
+
+/// Split an expression into the terms of its sums, ignoring the constant ones.
+fn non_constant_terms<E: ExtensionField>(expr: &Expression<E>) -> Vec<Expression<E>> {
+    match expr {
+        Expression::Sum(a, b) => chain!(non_constant_terms(a), non_constant_terms(b)).collect(),
+        Expression::ScaledSum(x, a, b) => chain!(
+            non_constant_terms(&Expression::Product(x.clone(), a.clone())),
+            non_constant_terms(b)
+        )
+        .collect(),
+        _ if expr.degree() == 0 => vec![],
+        _ => vec![expr.clone()],
+    }
+}
+
+/// Whether the evaluation at each instance is not zero.
+fn nonzero_instances<E: ExtensionField>(mle: &ArcMultilinearExtension<E>) -> Vec<bool> {
+    match mle.evaluations() {
+        FieldType::Base(vec) => vec.iter().map(|v| *v != E::BaseField::ZERO).collect(),
+        FieldType::Ext(vec) => vec.iter().map(|v| *v != E::ZERO).collect(),
+        FieldType::Unreachable => unreachable!(),
+    }
+}
+
+fn fmt_program_insn(programs: &[u32], idx: usize) -> String {
+    format!(
+        "{:#x}: {}",
+        MOCK_PC_START.0 as usize + idx * PC_WORD_SIZE,
+        fmt_insn(programs[idx])
+    )
+}
+
+/// The key of the lookup of `args` in the table `rom_type`, as counted by [`LkMultiplicity`].
+fn lookup_key<E: ExtensionField>(cs: &ConstraintSystem<E>, rom_type: ROMType, args: &[u64]) -> u64 {
+    match rom_type {
+        ROMType::U5 | ROMType::U8 | ROMType::U14 | ROMType::U16 => args[0],
+        ROMType::And => AndTable::pack(args[0], args[1]),
+        ROMType::Or => OrTable::pack(args[0], args[1]),
+        ROMType::Xor => XorTable::pack(args[0], args[1]),
+        ROMType::Ltu => LtuTable::pack(args[0], args[1]),
+        ROMType::Pow => {
+            assert_eq!(args[0], 2);
+            PowTable::pack(2, args[1])
+        }
+        ROMType::Clz => ClzTable::pack(args[0], 0),
+        ROMType::Popcount => PopcountTable::pack(args[0], 0),
+        ROMType::PackedU5 => PackedU5Table::pack(args),
+        ROMType::PackedU8 => PackedU8Table::pack(args),
+        ROMType::Instruction => args[0],
+        ROMType::Custom(id) => (cs.custom_tables[&id].pack)(args),
+    }
+}
+
+/// The differences between the lookup multiplicities inferred from a constraint system and those
+/// counted by its assignment, attributed to the first instance.
+fn lk_multiplicity_errors<E: ExtensionField>(
+    lkm_from_cs: &[HashMap<u64, usize>],
+    lkm_from_assignment: &[HashMap<u64, usize>],
+) -> Vec<MockProverError<E>> {
+    let mut errors = vec![];
+    for (rom_type, cs_map, ass_map) in izip!(ROMType::iter(), lkm_from_cs, lkm_from_assignment) {
+        if *cs_map != *ass_map {
+            let cs_keys: HashSet<_> = cs_map.keys().collect();
+            let ass_keys: HashSet<_> = ass_map.keys().collect();
+
+            // lookup missing in lkm Constraint System.
+            ass_keys.difference(&cs_keys).for_each(|k| {
+                let count_ass = ass_map.get(k).unwrap();
+                errors.push(MockProverError::LkMultiplicityError {
+                    rom_type,
+                    key: **k,
+                    count: *count_ass as isize,
+                    inst_id: 0,
+                })
+            });
+
+            // lookup missing in lkm Assignments.
+            cs_keys.difference(&ass_keys).for_each(|k| {
+                let count_cs = cs_map.get(k).unwrap();
+                errors.push(MockProverError::LkMultiplicityError {
+                    rom_type,
+                    key: **k,
+                    count: -(*count_cs as isize),
+                    inst_id: 0,
+                })
+            });
+
+            // count of specific lookup differ lkm assignments and lkm cs
+            cs_keys.intersection(&ass_keys).for_each(|k| {
+                let count_cs = cs_map.get(k).unwrap();
+                let count_ass = ass_map.get(k).unwrap();
+
+                if count_cs != count_ass {
+                    errors.push(MockProverError::LkMultiplicityError {
+                        rom_type,
+                        key: **k,
+                        count: (*count_ass as isize) - (*count_cs as isize),
+                        inst_id: 0,
+                    })
+                }
+            });
         }
     }
+    errors
+}
+
+pub struct MockProver<E: ExtensionField> {
+    _phantom: PhantomData<E>,
+}
+
+/// The evaluation of the record of `row` in the table `rom_type`.
+fn table_record<E: ExtensionField>(
+    cb: &CircuitBuilder<E>,
+    challenge: [E; 2],
+    rom_type: ROMType,
+    row: Vec<u64>,
+) -> Vec<u64> {
+    let rlc_record = cb.rlc_chip_record(
+        chain!(
+            [rom_type.id().into()],
+            row.into_iter().map(|v| (v as usize).into())
+        )
+        .collect(),
+    );
+    eval_by_expr(&[], &challenge, &rlc_record).to_canonical_u64_vec()
+}
 
-    let mut table_vec = vec![];
-    load_range_table::<U5Table, _>(&mut table_vec, cb, challenge);
-    load_range_table::<U8Table, _>(&mut table_vec, cb, challenge);
-    load_range_table::<U14Table, _>(&mut table_vec, cb, challenge);
-    load_range_table::<U16Table, _>(&mut table_vec, cb, challenge);
-    load_op_table::<AndTable, _>(&mut table_vec, cb, challenge);
-    load_op_table::<OrTable, _>(&mut table_vec, cb, challenge);
-    load_op_table::<XorTable, _>(&mut table_vec, cb, challenge);
-    load_op_table::<LtuTable, _>(&mut table_vec, cb, challenge);
-    load_op_table::<PowTable, _>(&mut table_vec, cb, challenge);
-
-    HashSet::from_iter(table_vec)
+fn load_tables<E: ExtensionField>(cb: &CircuitBuilder<E>, challenge: [E; 2]) -> HashSet<Vec<u64>> {
+    ROMType::iter()
+        .filter_map(|rom_type| Some((rom_type, rom_table_content(rom_type)?)))
+        .flat_map(|(rom_type, content)| {
+            content
+                .into_iter()
+                .map(move |row| table_record(cb, challenge, rom_type, row))
+        })
+        .collect()
 }
 
 // load once per generic type E instantiation
@@ -346,36 +602,10 @@ fn load_once_tables<E: ExtensionField + 'static + Sync + Send>(
     let (challenges_repr, table) = cache.call_once::<E, _>(|| {
         let mut rng = test_rng();
         let challenge = [E::random(&mut rng), E::random(&mut rng)];
-        let base64_encoded =
-            STANDARD_NO_PAD.encode(serde_json::to_string(&challenge).unwrap().as_bytes());
-        let file_path = format!("table_cache_dev_{:?}.json", base64_encoded);
-        let table = match File::open(file_path.clone()) {
-            Ok(file) => {
-                let reader = BufReader::new(file);
-                serde_json::from_reader(reader).unwrap()
-            }
-            Err(e) if e.kind() == ErrorKind::NotFound => {
-                // Cached file doesn't exist, let's make a new one.
-                // And carefully avoid exposing a half-written file to other threads,
-                // or other runs of this program (in case of a crash).
-
-                let mut file = tempfile::NamedTempFile::new_in(".").unwrap();
-
-                // load new table and seserialize to file for later use
-                let table = load_tables(cb, challenge);
-                serde_json::to_writer(&mut file, &table).unwrap();
-                // Persist the file to the target location
-                // This is an atomic operation on Posix-like systems, so we don't have to worry
-                // about half-written files.
-                // Note, that if another process wrote to our target file in the meantime,
-                // we silently overwrite it here.  But that's fine.
-                file.persist(file_path).unwrap();
-                table
-            }
-            Err(e) => panic!("{:?}", e),
-        };
-
-        (challenge.map(|c| c.to_canonical_u64_vec()), table)
+        (
+            challenge.map(|c| c.to_canonical_u64_vec()),
+            load_tables(cb, challenge),
+        )
     });
     // reinitialize per generic type E
     (
@@ -394,7 +624,7 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         challenge: [E; 2],
         lkm: Option<LkMultiplicity>,
     ) -> Result<(), Vec<MockProverError<E>>> {
-        Self::run_maybe_challenge(cb, wits_in, &[], &[], Some(challenge), lkm)
+        Self::run_maybe_challenge(cb, &[], wits_in, &[], &[], Some(challenge), lkm)
     }
 
     pub fn run(
@@ -403,11 +633,88 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         programs: &[u32],
         lkm: Option<LkMultiplicity>,
     ) -> Result<(), Vec<MockProverError<E>>> {
-        Self::run_maybe_challenge(cb, wits_in, programs, &[], None, lkm)
+        Self::run_maybe_challenge(cb, &[], wits_in, programs, &[], None, lkm)
+    }
+
+    /// Like [`Self::run`], for a circuit with fixed columns, e.g. a table circuit. The records
+    /// of its tables are checked against the reference tables, where they are used.
+    pub fn run_with_fixed(
+        cb: &CircuitBuilder<E>,
+        fixed: RowMajorMatrix<E::BaseField>,
+        wits_in: &[ArcMultilinearExtension<'a, E>],
+        programs: &[u32],
+        lkm: Option<LkMultiplicity>,
+    ) -> Result<(), Vec<MockProverError<E>>> {
+        let fixed = fixed
+            .into_mles()
+            .into_iter()
+            .map(|f| f.into())
+            .collect_vec();
+        Self::run_maybe_challenge(cb, &fixed, wits_in, programs, &[], None, lkm)
+    }
+
+    /// Report which constraints are exercised by `wits_in`. A zero constraint
+    /// is exercised at an instance if one of its terms is not zero there, e.g.
+    /// its selector is 1, and a lookup if one of its looked up values is not
+    /// zero. Merge the reports of several test vectors to find the constraints
+    /// that none of them exercises.
+    pub fn coverage(
+        cb: &CircuitBuilder<E>,
+        wits_in: &[ArcMultilinearExtension<'a, E>],
+    ) -> CoverageReport {
+        let mut rng = thread_rng();
+        let challenge = [0u8; 2].map(|_| E::random(&mut rng));
+        let num_instances = wits_in.first().map_or(0, |w| w.evaluations().len());
+        let coverage = |kind, name: &String, terms: Vec<Expression<E>>| {
+            let mut active = vec![false; num_instances];
+            for term in terms {
+                let evaluated = wit_infer_by_expr(&[], wits_in, &[], &challenge, &term);
+                let nonzero = nonzero_instances(&evaluated);
+                if nonzero.len() == 1 {
+                    // The term does not depend on the instance.
+                    active.iter_mut().for_each(|a| *a |= nonzero[0]);
+                } else {
+                    izip!(&mut active, nonzero).for_each(|(a, nonzero)| *a |= nonzero);
+                }
+            }
+            ConstraintCoverage {
+                kind,
+                name: name.clone(),
+                active_instances: active.into_iter().filter(|a| *a).count(),
+                num_instances,
+            }
+        };
+
+        let assert_zero = izip!(
+            chain!(
+                &cb.cs.assert_zero_expressions,
+                &cb.cs.assert_zero_sumcheck_expressions
+            ),
+            chain!(
+                &cb.cs.assert_zero_expressions_namespace_map,
+                &cb.cs.assert_zero_sumcheck_expressions_namespace_map
+            )
+        )
+        .map(|(expr, name)| coverage("assert_zero", name, non_constant_terms(expr)));
+        let lookups = izip!(
+            &cb.cs.lk_expressions_items_map,
+            &cb.cs.lk_expressions_namespace_map
+        )
+        .map(|((_, items), name)| {
+            coverage(
+                "lookup",
+                name,
+                items.iter().flat_map(non_constant_terms).collect(),
+            )
+        });
+        CoverageReport {
+            constraints: chain!(assert_zero, lookups).collect(),
+        }
     }
 
     fn run_maybe_challenge(
         cb: &CircuitBuilder<E>,
+        fixed: &[ArcMultilinearExtension<'a, E>],
         wits_in: &[ArcMultilinearExtension<'a, E>],
         input_programs: &[u32],
         pi: &[ArcMultilinearExtension<'a, E>],
@@ -444,10 +751,20 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         } else {
             load_once_tables(cb)
         };
-        let mut prog_table = vec![];
-        Self::load_program_table(&mut prog_table, &program, challenge);
-        for prog in prog_table {
-            table.insert(prog);
+        assert!(
+            fixed.is_empty() || fixed.len() == cb.cs.num_fixed,
+            "expected {} fixed columns, got {}",
+            cb.cs.num_fixed,
+            fixed.len()
+        );
+        for row in program_table_content::<E::BaseField>(&program) {
+            table.insert(table_record(cb, challenge, ROMType::Instruction, row));
+        }
+        // the custom tables are not part of the cached ones
+        for (id, spec) in &cb.cs.custom_tables {
+            for row in (spec.content)() {
+                table.insert(table_record(cb, challenge, ROMType::Custom(*id), row));
+            }
         }
 
         let mut errors = vec![];
@@ -478,10 +795,10 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
                 let (left, right) = expr.unpack_sum().unwrap();
                 let right = right.neg();
 
-                let left_evaluated = wit_infer_by_expr(&[], wits_in, pi, &challenge, &left);
+                let left_evaluated = wit_infer_by_expr(fixed, wits_in, pi, &challenge, &left);
                 let left_evaluated = left_evaluated.get_base_field_vec();
 
-                let right_evaluated = wit_infer_by_expr(&[], wits_in, pi, &challenge, &right);
+                let right_evaluated = wit_infer_by_expr(fixed, wits_in, pi, &challenge, &right);
                 let right_evaluated = right_evaluated.get_base_field_vec();
 
                 // left_evaluated.len() ?= right_evaluated.len() due to padding instance
@@ -501,7 +818,7 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
                 }
             } else {
                 // contains require_zero
-                let expr_evaluated = wit_infer_by_expr(&[], wits_in, pi, &challenge, expr);
+                let expr_evaluated = wit_infer_by_expr(fixed, wits_in, pi, &challenge, expr);
                 let expr_evaluated = expr_evaluated.get_base_field_vec();
 
                 for (inst_id, element) in enumerate(expr_evaluated) {
@@ -518,24 +835,80 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         }
 
         // Lookup expressions
-        for (expr, name) in cb
+        for (i, (expr, name)) in cb
             .cs
             .lk_expressions
             .iter()
             .zip_eq(cb.cs.lk_expressions_namespace_map.iter())
+            .enumerate()
         {
-            let expr_evaluated = wit_infer_by_expr(&[], wits_in, pi, &challenge, expr);
+            let expr_evaluated = wit_infer_by_expr(fixed, wits_in, pi, &challenge, expr);
             let expr_evaluated = expr_evaluated.get_ext_field_vec();
 
             // Check each lookup expr exists in t vec
-            for (inst_id, element) in enumerate(expr_evaluated) {
-                if !table.contains(&element.to_canonical_u64_vec()) {
-                    errors.push(MockProverError::LookupError {
-                        expression: expr.clone(),
-                        evaluated: *element,
-                        name: name.clone(),
-                        inst_id,
-                    });
+            let missing = enumerate(expr_evaluated)
+                .filter(|(_, element)| !table.contains(&element.to_canonical_u64_vec()))
+                .collect_vec();
+            if missing.is_empty() {
+                continue;
+            }
+            // The items of the lookups, which follow the order of the lookups unless the
+            // circuit also has table records.
+            let items = if cb.cs.lk_table_expressions.is_empty() {
+                cb.cs.lk_expressions_items_map[i]
+                    .1
+                    .iter()
+                    .map(|item| wit_infer_by_expr(fixed, wits_in, pi, &challenge, item))
+                    .collect_vec()
+            } else {
+                vec![]
+            };
+            for (inst_id, element) in missing {
+                let values = items
+                    .iter()
+                    .map(|item| {
+                        let item = item.get_base_field_vec();
+                        item[inst_id % item.len()].to_canonical_u64()
+                    })
+                    .collect();
+                errors.push(MockProverError::LookupError {
+                    expression: expr.clone(),
+                    evaluated: *element,
+                    name: name.clone(),
+                    inst_id,
+                    values,
+                });
+            }
+        }
+
+        // Table records, which must be in the reference tables where they are looked up
+        if !fixed.is_empty() {
+            for (table_expr, name) in cb
+                .cs
+                .lk_table_expressions
+                .iter()
+                .zip_eq(cb.cs.lk_table_expressions_namespace_map.iter())
+            {
+                let values = wit_infer_by_expr(fixed, wits_in, pi, &challenge, &table_expr.values);
+                let multiplicity =
+                    wit_infer_by_expr(fixed, wits_in, pi, &challenge, &table_expr.multiplicity);
+                for (inst_id, (element, multiplicity)) in izip!(
+                    values.get_ext_field_vec(),
+                    multiplicity.get_base_field_vec()
+                )
+                .enumerate()
+                {
+                    if *multiplicity != E::BaseField::ZERO
+                        && !table.contains(&element.to_canonical_u64_vec())
+                    {
+                        errors.push(MockProverError::LookupError {
+                            expression: table_expr.values.clone(),
+                            evaluated: *element,
+                            name: name.clone(),
+                            inst_id,
+                            values: vec![],
+                        });
+                    }
                 }
             }
         }
@@ -555,7 +928,7 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
                             .map(|expr| {
                                 // TODO generalized to all inst_id
                                 let inst_id = 0;
-                                wit_infer_by_expr(&[], wits_in, pi, &challenge, expr)
+                                wit_infer_by_expr(fixed, wits_in, pi, &challenge, expr)
                                     .get_base_field_vec()[inst_id]
                                     .to_canonical_u64()
                             })
@@ -563,75 +936,14 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
                     )
                 })
                 .fold(LkMultiplicity::default(), |mut lkm, (rom_type, args)| {
-                    match rom_type {
-                        ROMType::U5 => lkm.assert_ux::<5>(args[0]),
-                        ROMType::U8 => lkm.assert_ux::<8>(args[0]),
-                        ROMType::U14 => lkm.assert_ux::<14>(args[0]),
-                        ROMType::U16 => lkm.assert_ux::<16>(args[0]),
-                        ROMType::And => lkm.lookup_and_byte(args[0], args[1]),
-                        ROMType::Or => lkm.lookup_or_byte(args[0], args[1]),
-                        ROMType::Xor => lkm.lookup_xor_byte(args[0], args[1]),
-                        ROMType::Ltu => lkm.lookup_ltu_byte(args[0], args[1]),
-                        ROMType::Pow => {
-                            assert_eq!(args[0], 2);
-                            lkm.lookup_pow2(args[1])
-                        }
-                        ROMType::Instruction => lkm.fetch(args[0] as u32),
-                    };
-
+                    lkm.increment(*rom_type, lookup_key(&cb.cs, *rom_type, &args));
                     lkm
                 });
 
             let lkm_from_cs = lkm_from_cs.into_finalize_result();
             let lkm_from_assignment = lkm_from_assignment.into_finalize_result();
 
-            // Compare each LK Multiplicity.
-
-            for (rom_type, cs_map, ass_map) in
-                izip!(ROMType::iter(), &lkm_from_cs, &lkm_from_assignment)
-            {
-                if *cs_map != *ass_map {
-                    let cs_keys: HashSet<_> = cs_map.keys().collect();
-                    let ass_keys: HashSet<_> = ass_map.keys().collect();
-
-                    // lookup missing in lkm Constraint System.
-                    ass_keys.difference(&cs_keys).for_each(|k| {
-                        let count_ass = ass_map.get(k).unwrap();
-                        errors.push(MockProverError::LkMultiplicityError {
-                            rom_type,
-                            key: **k,
-                            count: *count_ass as isize,
-                            inst_id: 0,
-                        })
-                    });
-
-                    // lookup missing in lkm Assignments.
-                    cs_keys.difference(&ass_keys).for_each(|k| {
-                        let count_cs = cs_map.get(k).unwrap();
-                        errors.push(MockProverError::LkMultiplicityError {
-                            rom_type,
-                            key: **k,
-                            count: -(*count_cs as isize),
-                            inst_id: 0,
-                        })
-                    });
-
-                    // count of specific lookup differ lkm assignments and lkm cs
-                    cs_keys.intersection(&ass_keys).for_each(|k| {
-                        let count_cs = cs_map.get(k).unwrap();
-                        let count_ass = ass_map.get(k).unwrap();
-
-                        if count_cs != count_ass {
-                            errors.push(MockProverError::LkMultiplicityError {
-                                rom_type,
-                                key: **k,
-                                count: (*count_ass as isize) - (*count_cs as isize),
-                                inst_id: 0,
-                            })
-                        }
-                    });
-                }
-            }
+            errors.extend(lk_multiplicity_errors(&lkm_from_cs, &lkm_from_assignment));
         }
 
         if errors.is_empty() {
@@ -641,28 +953,6 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         }
     }
 
-    fn load_program_table(t_vec: &mut Vec<Vec<u64>>, program: &Program, challenge: [E; 2]) {
-        let mut cs = ConstraintSystem::<E>::new(|| "mock_program");
-        let mut cb = CircuitBuilder::new_with_params(&mut cs, ProgramParams {
-            platform: CENO_PLATFORM,
-            program_size: MOCK_PROGRAM_SIZE,
-            ..ProgramParams::default()
-        });
-        let config = ProgramTableCircuit::<_>::construct_circuit(&mut cb).unwrap();
-        let fixed = ProgramTableCircuit::<E>::generate_fixed_traces(&config, cs.num_fixed, program);
-        for table_expr in &cs.lk_table_expressions {
-            for row in fixed.iter_rows() {
-                // TODO: Find a better way to obtain the row content.
-                let row = row
-                    .iter()
-                    .map(|v| unsafe { (*v).assume_init() }.into())
-                    .collect::<Vec<_>>();
-                let rlc_record = eval_by_expr_with_fixed(&row, &[], &challenge, &table_expr.values);
-                t_vec.push(rlc_record.to_canonical_u64_vec());
-            }
-        }
-    }
-
     /// Run and check errors
     ///
     /// Panic, unless we see exactly the expected errors.
@@ -699,7 +989,7 @@ Hints:
             );
 
             for (count, error) in errors.iter().dedup_with_count() {
-                error.print(wits_in, &cb.cs.witin_namespace_map);
+                error.print(wits_in, &cb.cs.witin_namespace_map, programs);
                 if count > 1 {
                     println!("Error: {} duplicates hidden.", count - 1);
                 }
@@ -726,6 +1016,7 @@ Hints:
         challenge: Option<[E; 2]>,
         lkm: Option<LkMultiplicity>,
     ) {
+        raw_witin.assert_fully_assigned(&*cb.cs);
         let wits_in = raw_witin
             .de_interleaving()
             .into_mles()
@@ -882,6 +1173,7 @@ Hints:
             num_instances.insert(circuit_name.clone(), num_rows);
         }
 
+        let mut num_lk_errors = 0;
         for (rom_type, inputs) in rom_inputs {
             let table = rom_tables.get_mut(&rom_type).unwrap();
             for (lk_input_values, circuit_name, lk_input_annotation, input_value_exprs) in inputs {
@@ -904,6 +1196,7 @@ Hints:
                     } else {
                         0
                     };
+                    num_lk_errors += 1;
                     // log mismatch error
                     let witness = wit_mles
                         .get(&circuit_name)
@@ -939,6 +1232,7 @@ Hints:
             // each table entry's multiplicity should equal to 0
             for (k, multiplicity) in table {
                 if !multiplicity.is_zero_vartime() {
+                    num_lk_errors += 1;
                     tracing::error!(
                         "table {:?}: {:x?} multiplicity = {:x}",
                         rom_type,
@@ -1198,8 +1492,220 @@ Hints:
             gs
         );
 
-        if num_rw_mismatch_errors > 0 {
-            panic!("found {} r/w mismatch errors", num_rw_mismatch_errors);
+        if num_lk_errors > 0 || num_rw_mismatch_errors > 0 {
+            panic!(
+                "found {} lookup errors and {} r/w mismatch errors",
+                num_lk_errors, num_rw_mismatch_errors
+            );
+        }
+    }
+
+    /// Assign the steps of `trace` to the instruction circuits of `config` and `dummy_config`,
+    /// and check them all together: the constraints and the lookup multiplicities of each chip,
+    /// and that the reads and writes of all chips balance, so that the pc and the clock of each
+    /// step continue from the previous one. The values before the trace may be read, and the
+    /// values after it written, without the other half.
+    pub fn run_trace(
+        cs: &ZKVMConstraintSystem<E>,
+        config: &Rv32imConfig<E>,
+        dummy_config: &DummyExtraConfig<E>,
+        programs: &[u32],
+        pi: &PublicValues<u32>,
+        trace: &[StepRecord],
+    ) -> Result<(), Vec<TraceError<E>>> {
+        let (Some(first), Some(last)) = (trace.first(), trace.last()) else {
+            return Ok(());
+        };
+        let mut witnesses = ZKVMWitnesses::default();
+        let steps = config
+            .assign_opcode_circuit(cs, &mut witnesses, trace.to_vec(), pi.random_seed())
+            .expect("failed to assign the trace");
+        dummy_config
+            .assign_opcode_circuit(cs, &mut witnesses, steps)
+            .expect("failed to assign the trace");
+
+        let pi_mles = pi
+            .to_vec::<E>()
+            .into_mles()
+            .into_iter()
+            .map(|v| v.into())
+            .collect_vec();
+        let mut rng = thread_rng();
+        let challenge = [0u8; 2].map(|_| E::random(&mut rng));
+
+        let mut errors = vec![];
+        // (ram_type, values, chip, name, inst_id, cycle) of each read, and of each write by values
+        let mut reads = vec![];
+        let mut writes = HashMap::<Vec<u64>, Vec<_>>::new();
+        for (chip, chip_cs) in cs.get_css() {
+            let Some(witness) = witnesses.get_opcode_witness(chip) else {
+                continue;
+            };
+            let num_instances = witness.num_instances();
+            if num_instances == 0 {
+                continue;
+            }
+            let wits_in = witness
+                .into_mles()
+                .into_iter()
+                .map(|w| w.into())
+                .collect_vec();
+            // the values of `exprs` at each instance
+            let eval_instances = |exprs: &[Expression<E>]| -> Vec<Vec<u64>> {
+                let columns = exprs
+                    .iter()
+                    .map(|expr| {
+                        let evaluated =
+                            wit_infer_by_expr(&[], &wits_in, &pi_mles, &challenge, expr);
+                        let evaluated = evaluated.get_base_field_vec();
+                        (0..num_instances)
+                            .map(|i| evaluated[i % evaluated.len()].to_canonical_u64())
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                (0..num_instances)
+                    .map(|i| columns.iter().map(|column| column[i]).collect())
+                    .collect()
+            };
+            // each step reads the global state (pc, ts) where it starts
+            let cycles = chip_cs
+                .r_ram_types
+                .iter()
+                .find(|(ram_type, _)| *ram_type == RAMType::GlobalState)
+                .map(|(_, record)| {
+                    eval_instances(record)
+                        .into_iter()
+                        .map(|values| *values.last().unwrap())
+                        .collect_vec()
+                })
+                .unwrap_or_else(|| panic!("{chip} does not read the global state"));
+
+            // constraints and lookups, ignoring the padding instances
+            let mut cb_cs = chip_cs.clone();
+            let cb = CircuitBuilder::new(&mut cb_cs);
+            let chip_errors =
+                Self::run_maybe_challenge(&cb, &[], &wits_in, programs, &pi_mles, None, None)
+                    .err()
+                    .unwrap_or_default();
+            for error in chip_errors {
+                let inst_id = error.inst_id();
+                if inst_id.is_none_or(|inst_id| inst_id < num_instances) {
+                    errors.push(TraceError {
+                        chip: chip.clone(),
+                        cycle: inst_id.map(|inst_id| cycles[inst_id]),
+                        error,
+                    });
+                }
+            }
+
+            // lookup multiplicities of all the instances
+            let mut lkm_from_cs = LkMultiplicity::default();
+            let mut first_instance = HashMap::new();
+            for (rom_type, items) in &chip_cs.lk_expressions_items_map {
+                for (inst_id, args) in enumerate(eval_instances(items)) {
+                    let key = lookup_key(chip_cs, *rom_type, &args);
+                    lkm_from_cs.increment(*rom_type, key);
+                    first_instance.entry((*rom_type, key)).or_insert(inst_id);
+                }
+            }
+            let lkm_from_assignment = witnesses
+                .take_lk_mlt(chip)
+                .expect("opcode circuit without lookup multiplicities")
+                .into_finalize_result();
+            for error in
+                lk_multiplicity_errors(&lkm_from_cs.into_finalize_result(), &lkm_from_assignment)
+            {
+                let MockProverError::LkMultiplicityError {
+                    rom_type,
+                    key,
+                    count,
+                    ..
+                } = error
+                else {
+                    unreachable!()
+                };
+                let inst_id = first_instance.get(&(rom_type, key)).copied();
+                errors.push(TraceError {
+                    chip: chip.clone(),
+                    cycle: inst_id.map(|inst_id| cycles[inst_id]),
+                    error: MockProverError::LkMultiplicityError {
+                        rom_type,
+                        key,
+                        count,
+                        inst_id: inst_id.unwrap_or(0),
+                    },
+                });
+            }
+
+            // RAM records
+            for ((ram_type, record), name) in
+                izip!(&chip_cs.r_ram_types, &chip_cs.r_expressions_namespace_map)
+            {
+                for (inst_id, values) in enumerate(eval_instances(record)) {
+                    reads.push((*ram_type, values, chip, name, inst_id, cycles[inst_id]));
+                }
+            }
+            for ((ram_type, record), name) in
+                izip!(&chip_cs.w_ram_types, &chip_cs.w_expressions_namespace_map)
+            {
+                for (inst_id, values) in enumerate(eval_instances(record)) {
+                    writes.entry(values).or_default().push((
+                        *ram_type,
+                        chip,
+                        name,
+                        inst_id,
+                        cycles[inst_id],
+                    ));
+                }
+            }
+        }
+
+        // The last value of a record is its timestamp. The state where the trace starts, and
+        // the values last written before it, are read without a write in the trace; the state
+        // where it ends, and the values last written in it, are written without a read.
+        let start_cycle = first.cycle();
+        let end_cycle = last.cycle() + Tracer::SUBCYCLES_PER_INSN;
+        let mut unmatched = vec![];
+        for read in reads {
+            let (ram_type, values, ..) = &read;
+            let written = writes.get_mut(values).and_then(|w| w.pop()).is_some();
+            let ts = *values.last().unwrap();
+            let before_trace = match ram_type {
+                RAMType::GlobalState => ts == start_cycle,
+                RAMType::Register | RAMType::Memory => ts < start_cycle,
+            };
+            if !written && !before_trace {
+                unmatched.push(("writes", read));
+            }
+        }
+        for (values, unread) in writes {
+            let ts = *values.last().unwrap();
+            for (ram_type, chip, name, inst_id, cycle) in unread {
+                if ram_type == RAMType::GlobalState && ts != end_cycle {
+                    let write = (ram_type, values.clone(), chip, name, inst_id, cycle);
+                    unmatched.push(("reads", write));
+                }
+            }
+        }
+        errors.extend(unmatched.into_iter().map(
+            |(missing_in, (ram_type, values, chip, name, inst_id, cycle))| TraceError {
+                chip: chip.clone(),
+                cycle: Some(cycle),
+                error: MockProverError::RAMError {
+                    ram_type,
+                    values,
+                    name: name.clone(),
+                    missing_in,
+                    inst_id,
+                },
+            },
+        ));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            errors.sort_by_key(|error| error.cycle);
+            Err(errors)
         }
     }
 }
@@ -1215,8 +1721,11 @@ mod tests {
         expression::{ToExpr, WitIn},
         gadgets::{AssertLTConfig, IsLtConfig},
         set_val,
+        structs::CustomTableId,
+        tables::{CustomTable, TableCircuit, U5TableCircuit},
         witness::{LkMultiplicity, RowMajorMatrix},
     };
+    use ceno_emul::{InsnKind, Platform, VMState, encode_rv32};
     use ff::Field;
     use goldilocks::{Goldilocks, GoldilocksExt2};
     use multilinear_extensions::mle::IntoMLE;
@@ -1312,6 +1821,183 @@ mod tests {
         MockProver::assert_satisfied(&builder, &wits_in, &[], Some(challenge), None);
     }
 
+    #[test]
+    fn test_coverage() {
+        let mut cs = ConstraintSystem::new(|| "test_coverage");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+        let sel = builder.create_witin(|| "sel");
+        let a = builder.create_witin(|| "a");
+        builder
+            .require_zero(|| "sel * (a - 1)", sel.expr() * (a.expr() - 1))
+            .unwrap();
+        builder
+            .assert_ux::<_, _, 5>(|| "assert u5", a.expr())
+            .unwrap();
+
+        let wits =
+            |sel: [u64; 2], a: [u64; 2]| -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+                [sel, a]
+                    .into_iter()
+                    .map(|values| values.map(Goldilocks::from).to_vec().into_mle().into())
+                    .collect()
+            };
+
+        // The selector is never set.
+        let mut report = MockProver::coverage(&builder, &wits([0, 0], [0, 5]));
+        let never_exercised = report.never_exercised();
+        assert_eq!(never_exercised.len(), 1);
+        assert_eq!(never_exercised[0].kind, "assert_zero");
+        assert!(never_exercised[0].name.contains("sel * (a - 1)"));
+
+        report.merge(&MockProver::coverage(&builder, &wits([1, 0], [1, 0])));
+        assert!(report.never_exercised().is_empty());
+        assert_eq!(report.constraints[0].active_instances, 1);
+        assert_eq!(report.constraints[1].active_instances, 2);
+        assert_eq!(report.constraints[1].num_instances, 4);
+    }
+
+    #[test]
+    fn test_errors_in_scope() {
+        let mut cs = ConstraintSystem::new(|| "test_errors_in_scope");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+        let a = builder.create_witin(|| "a");
+        builder
+            .scope("beq", |cb| {
+                cb.scope("lt", |cb| cb.require_zero(|| "a - 1", a.expr() - 1))?;
+                cb.require_zero(|| "a - 2", a.expr() - 2)
+            })
+            .unwrap();
+        assert!(
+            builder
+                .cs
+                .scope_sites
+                .contains_key("test_errors_in_scope/beq/lt")
+        );
+
+        let wits_in: Vec<ArcMultilinearExtension<GoldilocksExt2>> =
+            vec![vec![Goldilocks::from(3)].into_mle().into()];
+        let errors = MockProver::run(&builder, &wits_in, &[], None).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.iter().filter(|e| e.in_scope("beq")).count(), 2);
+        assert_eq!(errors.iter().filter(|e| e.in_scope("beq/lt")).count(), 1);
+        assert_eq!(errors.iter().filter(|e| e.in_scope("l")).count(), 0);
+
+        let report = MockProver::coverage(&builder, &wits_in);
+        assert_eq!(report.in_scope("lt").constraints.len(), 1);
+    }
+
+    #[test]
+    fn test_run_trace() {
+        type E = GoldilocksExt2;
+        let programs = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 1, 5),
+            encode_rv32(InsnKind::ADD, 1, 1, 2, 0),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let program = Program::new(
+            MOCK_PC_START.0,
+            MOCK_PC_START.0,
+            programs.clone(),
+            enumerate(&programs)
+                .map(|(i, insn)| (MOCK_PC_START.0 + (i * PC_WORD_SIZE) as u32, *insn))
+                .collect(),
+        );
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let trace = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .unwrap();
+        let end_cycle = vm.tracer().cycle() as u32;
+
+        let mut cs = ZKVMConstraintSystem::default();
+        let config = Rv32imConfig::<E>::construct_circuits(&mut cs);
+        let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut cs);
+        let pi = PublicValues::new(
+            0,
+            MOCK_PC_START.0,
+            Tracer::SUBCYCLES_PER_INSN as u32,
+            0,
+            end_cycle,
+            vec![0],
+            vec![0],
+        );
+        let run = |trace: &[StepRecord]| {
+            MockProver::run_trace(&cs, &config, &dummy_config, &programs, &pi, trace)
+        };
+        assert!(run(&trace).is_ok());
+
+        // Without its middle step, the state written by the first step is never read, and the
+        // state read by the last step is never written.
+        let errors = run(&[trace[0].clone(), trace[2].clone()]).unwrap_err();
+        let state_errors = errors
+            .iter()
+            .filter(|e| {
+                matches!(e.error, MockProverError::RAMError {
+                    ram_type: RAMType::GlobalState,
+                    ..
+                })
+            })
+            .map(|e| e.cycle)
+            .collect_vec();
+        assert_eq!(state_errors, [
+            Some(trace[0].cycle()),
+            Some(trace[2].cycle())
+        ]);
+    }
+
+    #[test]
+    fn test_run_trace_random_ecall() {
+        type E = GoldilocksExt2;
+        let (reg_ecall, reg_arg0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+        let programs = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, reg_ecall, Platform::ecall_random()),
+            encode_rv32(InsnKind::ADDI, 0, 0, reg_arg0, 5),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+            encode_rv32(InsnKind::ADDI, reg_arg0, 0, 1, 0),
+            encode_rv32(InsnKind::ADDI, 0, 0, reg_ecall, Platform::ecall_halt()),
+            encode_rv32(InsnKind::ADDI, 0, 0, reg_arg0, 0),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let program = Program::new(
+            MOCK_PC_START.0,
+            MOCK_PC_START.0,
+            programs.clone(),
+            enumerate(&programs)
+                .map(|(i, insn)| (MOCK_PC_START.0 + (i * PC_WORD_SIZE) as u32, *insn))
+                .collect(),
+        );
+        let seed = 0x1234_5678_9abc_def0;
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        vm.set_random_seed(seed);
+        let trace = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .unwrap();
+        let end_cycle = vm.tracer().cycle() as u32;
+
+        let mut cs = ZKVMConstraintSystem::default();
+        let config = Rv32imConfig::<E>::construct_circuits(&mut cs);
+        let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut cs);
+        let pi = PublicValues::new(
+            0,
+            MOCK_PC_START.0,
+            Tracer::SUBCYCLES_PER_INSN as u32,
+            0,
+            end_cycle,
+            vec![0],
+            vec![0],
+        );
+        let run = |seed: u64| {
+            let pi = pi.clone().with_random_seed(seed);
+            MockProver::run_trace(&cs, &config, &dummy_config, &programs, &pi, &trace)
+        };
+        assert!(run(seed).is_ok());
+
+        // The word of another seed is not the one that the trace reads back.
+        let errors = run(seed + 1).unwrap_err();
+        assert!(errors.iter().any(|e| e.chip == "ECALL_RANDOM"));
+    }
+
     #[test]
     // TODO: add it back after the support of missing lookup
     fn test_lookup_error() {
@@ -1337,7 +2023,7 @@ mod tests {
                         GoldilocksExt2::ZERO,
                     )),
                     Box::new(Expression::Constant(
-                        <GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::from(U5 as u64)
+                        <GoldilocksExt2 as ff_ext::ExtensionField>::BaseField::from(U5.id() as u64)
                     )),
                 )),
                 Box::new(Expression::Challenge(
@@ -1350,9 +2036,97 @@ mod tests {
             evaluated: 123002.into(), // 123 * 1000 + 2
             name: "test_lookup_error/assert_u5/assert u5".to_string(),
             inst_id: 0,
+            values: vec![123],
         }]);
-        // because inst_id is not checked in our PartialEq impl
-        assert_eq!(err[0].inst_id(), 0);
+        // because inst_id and values are not checked in our PartialEq impl
+        assert_eq!(err[0].inst_id(), Some(0));
+        assert!(matches!(&err[0], MockProverError::LookupError { values, .. } if values == &[123]));
+    }
+
+    /// The 4-bit values and their bit reversals.
+    struct Rev4Table;
+    impl CustomTable for Rev4Table {
+        const ID: CustomTableId = 0;
+        const NAME: &'static str = "rev4";
+        const NUM_COLUMNS: usize = 2;
+
+        fn content() -> Vec<Vec<u64>> {
+            (0..16u64)
+                .map(|a| vec![a, (a as u8).reverse_bits() as u64 >> 4])
+                .collect()
+        }
+
+        fn pack(row: &[u64]) -> u64 {
+            row[0]
+        }
+    }
+
+    #[test]
+    fn test_custom_lookup() {
+        let mut cs = ConstraintSystem::new(|| "test_custom_lookup");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+        let a = builder.create_witin(|| "a");
+        let b = builder.create_witin(|| "b");
+        builder
+            .lk_custom_record::<Rev4Table, _, _>(|| "rev4", vec![a.expr(), b.expr()])
+            .unwrap();
+
+        let wits = |a: u64, b: u64| -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            [a, b]
+                .into_iter()
+                .map(|v| vec![Goldilocks::from(v)].into_mle().into())
+                .collect()
+        };
+        let mut lkm = LkMultiplicity::default();
+        lkm.lookup_custom::<Rev4Table>(&[0b0011, 0b1100]);
+        MockProver::assert_satisfied(&builder, &wits(0b0011, 0b1100), &[], None, Some(lkm));
+
+        let result = MockProver::run(&builder, &wits(0b0011, 0b0011), &[], None);
+        assert!(matches!(result.unwrap_err()[..], [
+            MockProverError::LookupError { .. }
+        ]));
+    }
+
+    #[test]
+    fn test_table_with_fixed() {
+        let mut cs = ConstraintSystem::new(|| "test_table_with_fixed");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+        let config = U5TableCircuit::construct_circuit(&mut builder).unwrap();
+
+        let mut lkm = LkMultiplicity::default();
+        lkm.assert_ux::<5>(3);
+        let multiplicity = lkm.into_finalize_result();
+        let wits_in = U5TableCircuit::<GoldilocksExt2>::assign_instances(
+            &config,
+            builder.cs.num_witin as usize,
+            &multiplicity,
+            &(),
+        )
+        .unwrap()
+        .into_mles()
+        .into_iter()
+        .map(|w| w.into())
+        .collect_vec();
+        let fixed = || {
+            U5TableCircuit::<GoldilocksExt2>::generate_fixed_traces(
+                &config,
+                builder.cs.num_fixed,
+                &(),
+            )
+        };
+        MockProver::run_with_fixed(&builder, fixed(), &wits_in, &[], None).unwrap();
+
+        // The looked up row holds a value out of range.
+        let mut bad_fixed = fixed();
+        bad_fixed.iter_mut().nth(3).unwrap()[0] = MaybeUninit::new(Goldilocks::from(40));
+        let result = MockProver::run_with_fixed(&builder, bad_fixed, &wits_in, &[], None);
+        assert!(matches!(result.unwrap_err()[..], [
+            MockProverError::LookupError { inst_id: 3, .. }
+        ]));
+        // The rows that are not looked up are not checked.
+        let mut unused_fixed = fixed();
+        unused_fixed.iter_mut().nth(4).unwrap()[0] = MaybeUninit::new(Goldilocks::from(40));
+        MockProver::run_with_fixed(&builder, unused_fixed, &wits_in, &[], None).unwrap();
     }
 
     #[derive(Debug)]
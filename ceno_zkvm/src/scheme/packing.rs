@@ -0,0 +1,200 @@
+//! Packing of the witnesses of small opcode circuits into shared commitments.
+//!
+//! Every commitment has a fixed cost, in the Merkle tree and its openings, that dominates for
+//! circuits with few instances. The witnesses of such circuits are packed into a single batch of
+//! polynomials, each circuit in a slot of the hypercube selected by the last variables: the
+//! packed polynomial `j` is column `j` of the circuit of slot `i` on `x = (y, bits(i))`, and zero
+//! past the width of that circuit. Opening it at `(point, 0, .., 0, bits(i))` gives the
+//! evaluation of the column of that circuit at `point`, so the circuits are proven as before.
+
+use ff::Field;
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use multilinear_extensions::{
+    mle::{DenseMultilinearExtension, FieldType},
+    util::ceil_log2,
+};
+use serde::{Deserialize, Serialize};
+
+/// Where the witness of a circuit sits in a packed commitment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedSlot {
+    /// The number of variables of each slot, at least that of the witness of the circuit.
+    pub slot_num_vars: usize,
+    /// The number of variables that select the slot.
+    pub num_selector_vars: usize,
+    /// The index of the slot of the circuit.
+    pub index: usize,
+    /// The number of packed polynomials, at least the number of witnesses of the circuit.
+    pub num_polys: usize,
+}
+
+impl PackedSlot {
+    pub fn num_vars(&self) -> usize {
+        self.slot_num_vars + self.num_selector_vars
+    }
+
+    /// The point at which to open the packed polynomials for the witnesses of the circuit to be
+    /// evaluated at `point`.
+    pub fn open_point<E: ExtensionField>(&self, point: &[E]) -> Vec<E> {
+        assert!(point.len() <= self.slot_num_vars);
+        let selector = (0..self.num_selector_vars).map(|i| {
+            if self.index >> i & 1 == 1 {
+                E::ONE
+            } else {
+                E::ZERO
+            }
+        });
+        point
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(E::ZERO).take(self.slot_num_vars - point.len()))
+            .chain(selector)
+            .collect()
+    }
+
+    /// The evaluations of the packed polynomials at [`Self::open_point`], given those of the
+    /// witnesses of the circuit: the other polynomials are zero on its slot.
+    pub fn open_evals<E: ExtensionField>(&self, evals: &[E]) -> Vec<E> {
+        assert!(evals.len() <= self.num_polys);
+        let mut evals = evals.to_vec();
+        evals.resize(self.num_polys, E::ZERO);
+        evals
+    }
+}
+
+/// Group the circuits whose witnesses have fewer than `min_num_vars` variables, given by name,
+/// so that each group fits in a commitment of `min_num_vars` variables. The circuits are taken
+/// from the largest, which sets the size of the slots of its group, so that little room is lost
+/// to the smaller ones. Larger circuits are left out, and so are the groups of one circuit.
+pub fn pack_circuits(
+    num_vars: impl IntoIterator<Item = (String, usize)>,
+    min_num_vars: usize,
+) -> Vec<Vec<String>> {
+    let mut groups: Vec<(usize, Vec<String>)> = vec![];
+    for (name, num_vars) in num_vars
+        .into_iter()
+        .filter(|(_, num_vars)| *num_vars < min_num_vars)
+        .sorted_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)))
+    {
+        match groups.last_mut() {
+            Some((slot_num_vars, group)) if group.len() < 1 << (min_num_vars - *slot_num_vars) => {
+                group.push(name)
+            }
+            _ => groups.push((num_vars, vec![name])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Pack the witnesses of the circuits of a group, in order, see the module documentation.
+pub fn pack_witnesses<E: ExtensionField>(
+    witnesses: &[Vec<DenseMultilinearExtension<E>>],
+) -> (Vec<DenseMultilinearExtension<E>>, Vec<PackedSlot>) {
+    let slot_num_vars = witnesses
+        .iter()
+        .flatten()
+        .map(|mle| mle.num_vars)
+        .max()
+        .unwrap_or_default();
+    let num_selector_vars = ceil_log2(witnesses.len());
+    let num_polys = witnesses.iter().map(Vec::len).max().unwrap_or_default();
+    let packed = (0..num_polys)
+        .map(|j| {
+            let mut evals = vec![E::BaseField::ZERO; 1 << (slot_num_vars + num_selector_vars)];
+            for (slot, witness) in evals.chunks_mut(1 << slot_num_vars).zip(witnesses) {
+                let Some(mle) = witness.get(j) else {
+                    continue;
+                };
+                let FieldType::Base(column) = &mle.evaluations else {
+                    unreachable!("witnesses are in the base field")
+                };
+                slot[..column.len()].copy_from_slice(column);
+            }
+            DenseMultilinearExtension::from_evaluations_vec(
+                slot_num_vars + num_selector_vars,
+                evals,
+            )
+        })
+        .collect();
+    let slots = (0..witnesses.len())
+        .map(|index| PackedSlot {
+            slot_num_vars,
+            num_selector_vars,
+            index,
+            num_polys,
+        })
+        .collect();
+    (packed, slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use multilinear_extensions::mle::{DenseMultilinearExtension, MultilinearExtension};
+    use rand::rngs::OsRng;
+
+    use super::{pack_circuits, pack_witnesses};
+
+    type E = GoldilocksExt2;
+
+    #[test]
+    fn test_pack_circuits() {
+        let num_vars = [("a", 3), ("b", 5), ("c", 4), ("d", 4), ("e", 10), ("f", 3)]
+            .map(|(name, num_vars)| (name.to_string(), num_vars));
+        // b and c fill the two slots of 2^5 of a commitment of 2^6, then d, a and f take three
+        // slots of 2^4 of another; e is too large.
+        assert_eq!(pack_circuits(num_vars.clone(), 6), vec![
+            vec!["b".to_string(), "c".to_string()],
+            vec!["d".to_string(), "a".to_string(), "f".to_string()],
+        ]);
+        assert_eq!(pack_circuits(num_vars, 4), vec![vec![
+            "a".to_string(),
+            "f".to_string()
+        ]]);
+        // A circuit alone is not packed.
+        assert!(pack_circuits([("a".to_string(), 3)], 6).is_empty());
+    }
+
+    #[test]
+    fn test_pack_witnesses() {
+        let random_witness = |num_polys, num_vars| {
+            (0..num_polys)
+                .map(|_| DenseMultilinearExtension::<E>::random(num_vars, &mut OsRng))
+                .collect::<Vec<_>>()
+        };
+        let witnesses = vec![
+            random_witness(3, 4),
+            random_witness(5, 2),
+            random_witness(1, 4),
+        ];
+        let (packed, slots) = pack_witnesses(&witnesses);
+        assert_eq!(packed.len(), 5);
+        assert!(packed.iter().all(|mle| mle.num_vars == 6));
+
+        for (witness, slot) in witnesses.iter().zip(&slots) {
+            let num_vars = witness[0].num_vars;
+            let point = (0..num_vars).map(|_| E::random(OsRng)).collect::<Vec<_>>();
+            let evals = witness
+                .iter()
+                .map(|mle| mle.evaluate(&point))
+                .collect::<Vec<_>>();
+            let open_point = slot.open_point(&point);
+            assert_eq!(
+                packed
+                    .iter()
+                    .map(|mle| mle.evaluate(&open_point))
+                    .collect::<Vec<_>>(),
+                slot.open_evals(&evals)
+            );
+        }
+        // The unused slot is zero.
+        let unused = packed[0].get_base_field_vec()[3 << 4..].to_vec();
+        assert_eq!(unused, vec![Goldilocks::ZERO; 1 << 4]);
+    }
+}
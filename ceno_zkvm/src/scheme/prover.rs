@@ -25,7 +25,10 @@ use crate::{
     error::ZKVMError,
     expression::Instance,
     scheme::{
+        auxiliary::{AuxiliaryProver, ZKVMAuxiliaryProof},
+        commit_stream::CommittedWitnesses,
         constants::{MAINCONSTRAIN_SUMCHECK_BATCH_SIZE, NUM_FANIN, NUM_FANIN_LOGUP},
+        record_witness::RecordWitness,
         utils::{
             infer_tower_logup_witness, infer_tower_product_witness, interleaving_mles_to_mles,
             wit_infer_by_expr,
@@ -34,32 +37,100 @@ use crate::{
     structs::{
         Point, ProvingKey, TowerProofs, TowerProver, TowerProverSpec, ZKVMProvingKey, ZKVMWitnesses,
     },
-    utils::{get_challenge_pows, next_pow2_instance_padding, optimal_sumcheck_threads},
+    utils::{Instant, get_challenge_pows, next_pow2_instance_padding, optimal_sumcheck_threads},
     virtual_polys::VirtualPolynomials,
 };
 
-use super::{PublicValues, ZKVMOpcodeProof, ZKVMProof, ZKVMTableProof};
+use super::{PublicValues, ZKVMOpcodeProof, ZKVMProof, ZKVMProofMetadata, ZKVMTableProof};
 
-type ResultCreateTableProof<E, PCS> = (ZKVMTableProof<E, PCS>, HashMap<usize, E>);
+type ResultCreateTableProof<E, PCS> = (ZKVMTableProof<E, PCS>, HashMap<usize, E>, Point<E>);
 
 pub struct ZKVMProver<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pk: ZKVMProvingKey<E, PCS>,
+    /// Pack the witnesses of the opcode circuits of fewer variables, if any, see
+    /// [`super::packing`].
+    pub packing_min_num_vars: Option<usize>,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
     pub fn new(pk: ZKVMProvingKey<E, PCS>) -> Self {
-        ZKVMProver { pk }
+        ZKVMProver {
+            pk,
+            packing_min_num_vars: None,
+        }
+    }
+
+    /// Pack the witnesses of the opcode circuits of fewer than `min_num_vars` variables into
+    /// shared commitments.
+    pub fn with_packing(mut self, min_num_vars: usize) -> Self {
+        self.packing_min_num_vars = Some(min_num_vars);
+        self
     }
 
     /// create proof for zkvm execution
-    #[tracing::instrument(skip_all, name = "ZKVM_create_proof")]
     pub fn create_proof(
         &self,
         witnesses: ZKVMWitnesses<E>,
         pi: PublicValues<u32>,
+        transcript: Transcript<E>,
+    ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+        self.create_proof_with_auxiliary(witnesses, &[], pi, transcript)
+    }
+
+    /// create proof for zkvm execution, with the evaluation claims of the sub-protocols
+    /// `auxiliaries` discharged by the opening of the zkvm
+    #[tracing::instrument(skip_all, name = "ZKVM_create_proof")]
+    pub fn create_proof_with_auxiliary(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        auxiliaries: &[&dyn AuxiliaryProver<E>],
+        pi: PublicValues<u32>,
+        transcript: Transcript<E>,
+    ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+        let committed = self.commit_witnesses(witnesses)?;
+        self.create_proof_from_committed(committed, auxiliaries, pi, transcript)
+    }
+
+    /// Commit to the witnesses, which do not depend on the challenges, so this can run before
+    /// the transcript of the proof exists. The records depending on the challenges are inferred
+    /// by [`Self::create_proof_from_committed`], see [`super::record_witness`].
+    pub fn commit_witnesses(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+    ) -> Result<CommittedWitnesses<E, PCS>, ZKVMError> {
+        match self.packing_min_num_vars {
+            Some(min_num_vars) => {
+                CommittedWitnesses::commit_all_packed(&self.pk.pp, witnesses, min_num_vars)
+            }
+            None => CommittedWitnesses::commit_all(&self.pk.pp, witnesses),
+        }
+    }
+
+    /// create proof for zkvm execution from the witnesses committed in advance, e.g. while they
+    /// were assigned with [`super::commit_stream::commit_while_assigning`]
+    #[tracing::instrument(skip_all, name = "ZKVM_create_proof_from_committed")]
+    pub fn create_proof_from_committed(
+        &self,
+        committed: CommittedWitnesses<E, PCS>,
+        auxiliaries: &[&dyn AuxiliaryProver<E>],
+        pi: PublicValues<u32>,
         mut transcript: Transcript<E>,
     ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
         let mut vm_proof = ZKVMProof::empty(pi);
+        let auxiliaries = auxiliaries
+            .iter()
+            .sorted_by_key(|aux| aux.name())
+            .collect_vec();
+        if let Some((aux, _)) = auxiliaries
+            .iter()
+            .tuple_windows()
+            .find(|(a, b)| a.name() == b.name())
+        {
+            return Err(ZKVMError::InvalidWitness(format!(
+                "auxiliary protocol {} is given twice",
+                aux.name()
+            )));
+        }
 
         // including raw public input to transcript
         for v in vm_proof.raw_pi.iter().flatten() {
@@ -88,26 +159,38 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let mut wits = BTreeMap::new();
 
         let commit_to_traces_span = entered_span!("commit_to_traces");
-        // commit to opcode circuits first and then commit to table circuits, sorted by name
-        for (circuit_name, witness) in witnesses.into_iter_sorted() {
-            let num_instances = witness.num_instances();
-            let span = entered_span!("commit to iteration", circuit_name = circuit_name);
-            let witness = match num_instances {
-                0 => vec![],
-                _ => {
-                    let witness = witness.into_mles();
-                    commitments.insert(
-                        circuit_name.clone(),
-                        PCS::batch_commit_and_write(&self.pk.pp, &witness, &mut transcript)
-                            .map_err(ZKVMError::PCSError)?,
-                    );
-                    witness
-                }
-            };
-            exit_span!(span);
-            wits.insert(circuit_name, (witness, num_instances));
+        let mut timings = vec![];
+        let phase_start = Instant::now();
+        let commit_time = committed.commit_time;
+        // write the commitments of opcode circuits first and then of table circuits, sorted by name
+        for (circuit_name, committed) in committed.into_iter_sorted() {
+            if let Some(commitment) = committed.commitment {
+                PCS::write_commitment(&PCS::get_pure_commitment(&commitment), &mut transcript)
+                    .map_err(ZKVMError::PCSError)?;
+                commitments.insert(circuit_name.clone(), commitment);
+            }
+            wits.insert(
+                circuit_name,
+                (
+                    committed.witness,
+                    committed.num_instances,
+                    committed.packed_slot,
+                ),
+            );
         }
+        // then to the witnesses of the auxiliary protocols, sorted by name
+        let aux_commitments = auxiliaries
+            .iter()
+            .map(|aux| {
+                PCS::batch_commit_and_write(&self.pk.pp, &aux.witness(), &mut transcript)
+                    .map_err(ZKVMError::PCSError)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         exit_span!(commit_to_traces_span);
+        timings.push((
+            "commit_to_traces".to_string(),
+            commit_time + phase_start.elapsed(),
+        ));
 
         // squeeze two challenges from transcript
         let challenges = [
@@ -117,20 +200,27 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         tracing::debug!("challenges in prover: {:?}", challenges);
 
         let main_proofs_span = entered_span!("main_proofs");
-        let mut transcripts = transcript.fork(self.pk.circuit_pks.len());
+        let phase_start = Instant::now();
+        let num_circuits = self.pk.circuit_pks.len();
+        let mut transcripts = transcript.clone().fork(num_circuits + auxiliaries.len());
+        let (circuit_transcripts, aux_transcripts) = transcripts.split_at_mut(num_circuits);
+        // evaluation claims of all the commitments, discharged by a single opening at the end
+        let mut pcs_comms = vec![];
+        let mut pcs_points = vec![];
+        let mut pcs_evals = vec![];
         for ((circuit_name, pk), (i, transcript)) in self
             .pk
             .circuit_pks
             .iter() // Sorted by key.
-            .zip_eq(transcripts.iter_mut().enumerate())
+            .zip_eq(circuit_transcripts.iter_mut().enumerate())
         {
-            let (witness, num_instances) = wits
+            let (witness, num_instances, packed_slot) = wits
                 .remove(circuit_name)
                 .ok_or(ZKVMError::WitnessNotFound(circuit_name.clone()))?;
             if witness.is_empty() {
                 continue;
             }
-            let wits_commit = commitments.remove(circuit_name).unwrap();
+            let wits_commit: &PCS::CommitmentWithData = commitments.get(circuit_name).unwrap();
             // TODO: add an enum for circuit type either in constraint_system or vk
             let cs = pk.get_cs();
             let is_opcode_circuit = cs.lk_table_expressions.is_empty()
@@ -149,9 +239,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 for lk_s in &cs.lk_expressions_namespace_map {
                     tracing::debug!("opcode circuit {}: {}", circuit_name, lk_s);
                 }
-                let opcode_proof = self.create_opcode_proof(
+                let (mut opcode_proof, input_open_point) = self.create_opcode_proof(
                     circuit_name,
-                    &self.pk.pp,
                     pk,
                     witness.into_iter().map(|w| w.into()).collect_vec(),
                     wits_commit,
@@ -160,6 +249,15 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                     transcript,
                     &challenges,
                 )?;
+                pcs_comms.push(wits_commit);
+                if let Some(slot) = packed_slot {
+                    pcs_points.push(slot.open_point(&input_open_point));
+                    pcs_evals.push(slot.open_evals(&opcode_proof.wits_in_evals));
+                } else {
+                    pcs_points.push(input_open_point);
+                    pcs_evals.push(opcode_proof.wits_in_evals.clone());
+                }
+                opcode_proof.packed_slot = packed_slot;
                 tracing::info!(
                     "generated proof for opcode {} with num_instances={}",
                     circuit_name,
@@ -169,9 +267,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                     .opcode_proofs
                     .insert(circuit_name.clone(), (i, opcode_proof));
             } else {
-                let (table_proof, pi_in_evals) = self.create_table_proof(
+                let (table_proof, pi_in_evals, input_open_point) = self.create_table_proof(
                     circuit_name,
-                    &self.pk.pp,
                     pk,
                     witness.into_iter().map(|v| v.into()).collect_vec(),
                     wits_commit,
@@ -179,6 +276,14 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                     transcript,
                     &challenges,
                 )?;
+                pcs_comms.push(wits_commit);
+                pcs_points.push(input_open_point.clone());
+                pcs_evals.push(table_proof.wits_in_evals.clone());
+                if let Some(fixed_commit_wd) = &pk.fixed_commit_wd {
+                    pcs_comms.push(fixed_commit_wd);
+                    pcs_points.push(input_open_point);
+                    pcs_evals.push(table_proof.fixed_in_evals.clone());
+                }
                 tracing::info!(
                     "generated proof for table {} with num_instances={}",
                     circuit_name,
@@ -194,26 +299,87 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         }
         exit_span!(main_proofs_span);
 
+        for (j, (aux, aux_commit, transcript)) in
+            izip!(auxiliaries, &aux_commitments, aux_transcripts.iter_mut()).enumerate()
+        {
+            let (proof, point, evals) = aux.prove(&challenges, transcript)?;
+            pcs_comms.push(aux_commit);
+            pcs_points.push(point);
+            pcs_evals.push(evals.clone());
+            vm_proof.aux_proofs.insert(
+                aux.name(),
+                (num_circuits + j, ZKVMAuxiliaryProof {
+                    wits_commit: PCS::get_pure_commitment(aux_commit),
+                    wits_in_evals: evals,
+                    proof,
+                }),
+            );
+        }
+
+        timings.push(("main_proofs".to_string(), phase_start.elapsed()));
+
+        // bind the opening to the transcripts of all the circuits and auxiliary protocols
+        for transcript_i in transcripts.iter_mut() {
+            transcript.append_field_element_ext(&transcript_i.read_challenge().elements);
+        }
+        let pcs_open_span = entered_span!("pcs_open");
+        let opening_dur = Instant::now();
+        tracing::debug!("build opening proof for {} commitments", pcs_comms.len());
+        vm_proof.pcs_opening_proof = Some(
+            PCS::multi_batch_open(
+                &self.pk.pp,
+                &pcs_comms,
+                &pcs_points,
+                &pcs_evals,
+                &mut transcript,
+            )
+            .map_err(ZKVMError::PCSError)?,
+        );
+        // the witness commitments are opened, so their buffers can serve the next proof
+        commitments
+            .into_values()
+            .chain(aux_commitments)
+            .for_each(|comm| PCS::recycle(&self.pk.pp, comm));
+        tracing::info!("build opening proof took {:?}", opening_dur.elapsed());
+        exit_span!(pcs_open_span);
+        timings.push(("pcs_open".to_string(), opening_dur.elapsed()));
+
+        vm_proof.metadata = Some(
+            ZKVMProofMetadata {
+                prover_version: env!("CARGO_PKG_VERSION").to_string(),
+                params_digest: self.pk.digest()?.to_canonical_u64_vec(),
+                timings,
+                num_steps: vm_proof
+                    .opcode_proofs
+                    .values()
+                    .map(|(_, proof)| proof.num_instances)
+                    .sum(),
+                checksum: vec![],
+            }
+            .with_checksum::<E>(),
+        );
+
         Ok(vm_proof)
     }
     /// create proof giving witness and num_instances
     /// major flow break down into
     /// 1: witness layer inferring from input -> output
     /// 2: proof (sumcheck reduce) from output to input
+    /// The witness evaluations at the returned input opening point are left to
+    /// be proven by the caller.
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip_all, name = "create_opcode_proof", fields(circuit_name=name))]
     pub fn create_opcode_proof(
         &self,
         name: &str,
-        pp: &PCS::ProverParam,
         circuit_pk: &ProvingKey<E, PCS>,
         witnesses: Vec<ArcMultilinearExtension<'_, E>>,
-        wits_commit: PCS::CommitmentWithData,
+        wits_commit: &PCS::CommitmentWithData,
         pi: &[ArcMultilinearExtension<'_, E>],
         num_instances: usize,
         transcript: &mut Transcript<E>,
         challenges: &[E; 2],
-    ) -> Result<ZKVMOpcodeProof<E, PCS>, ZKVMError> {
+    ) -> Result<(ZKVMOpcodeProof<E, PCS>, Point<E>), ZKVMError> {
         let cs = circuit_pk.get_cs();
         let next_pow2_instances = next_pow2_instance_padding(num_instances);
         let log2_num_instances = ceil_log2(next_pow2_instances);
@@ -230,18 +396,12 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let wit_inference_span = entered_span!("wit_inference");
         // main constraint: read/write record witness inference
         let record_span = entered_span!("record");
-        let records_wit: Vec<ArcMultilinearExtension<'_, E>> = cs
-            .r_expressions
-            .par_iter()
-            .chain(cs.w_expressions.par_iter())
-            .chain(cs.lk_expressions.par_iter())
-            .map(|expr| {
-                assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&[], &witnesses, pi, challenges, expr)
-            })
-            .collect();
-        let (r_records_wit, w_lk_records_wit) = records_wit.split_at(cs.r_expressions.len());
-        let (w_records_wit, lk_records_wit) = w_lk_records_wit.split_at(cs.w_expressions.len());
+        let RecordWitness {
+            r_records: r_records_wit,
+            w_records: w_records_wit,
+            lk_records: lk_records_wit,
+            ..
+        } = RecordWitness::infer_opcode(cs, &witnesses, pi, challenges);
         exit_span!(record_span);
 
         // product constraint: tower witness inference
@@ -260,7 +420,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let span = entered_span!("tower_witness_r_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
         let r_records_last_layer =
-            interleaving_mles_to_mles(r_records_wit, num_instances, NUM_FANIN, E::ONE);
+            interleaving_mles_to_mles(&r_records_wit, num_instances, NUM_FANIN, E::ONE);
         assert_eq!(r_records_last_layer.len(), NUM_FANIN);
         exit_span!(span);
 
@@ -276,7 +436,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let span = entered_span!("tower_witness_w_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
         let w_records_last_layer =
-            interleaving_mles_to_mles(w_records_wit, num_instances, NUM_FANIN, E::ONE);
+            interleaving_mles_to_mles(&w_records_wit, num_instances, NUM_FANIN, E::ONE);
         assert_eq!(w_records_last_layer.len(), NUM_FANIN);
         exit_span!(span);
 
@@ -291,7 +451,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let span = entered_span!("tower_witness_lk_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
         let lk_records_last_layer =
-            interleaving_mles_to_mles(lk_records_wit, num_instances, NUM_FANIN, chip_record_alpha);
+            interleaving_mles_to_mles(&lk_records_wit, num_instances, NUM_FANIN, chip_record_alpha);
         assert_eq!(lk_records_last_layer.len(), 2);
         exit_span!(span);
 
@@ -545,7 +705,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
 
         tracing::debug!("main sel sumcheck start");
         let (main_sel_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
-            num_threads,
+            virtual_polys.num_threads(),
             virtual_polys.get_batched_polys(),
             transcript,
         );
@@ -598,49 +758,34 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .map(|poly| poly.evaluate(&input_open_point))
             .collect();
         exit_span!(span);
-
-        let pcs_open_span = entered_span!("pcs_open");
-        let opening_dur = std::time::Instant::now();
         tracing::debug!(
-            "[opcode {}]: build opening proof for {} polys at {:?}",
+            "[opcode {}]: {} polys to open at {:?}",
             name,
             witnesses.len(),
             input_open_point
         );
-        let wits_opening_proof = PCS::simple_batch_open(
-            pp,
-            &witnesses,
-            &wits_commit,
-            &input_open_point,
-            wits_in_evals.as_slice(),
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-        tracing::info!(
-            "[opcode {}] build opening proof took {:?}",
-            name,
-            opening_dur.elapsed(),
-        );
-        exit_span!(pcs_open_span);
-        let wits_commit = PCS::get_pure_commitment(&wits_commit);
-
-        Ok(ZKVMOpcodeProof {
-            num_instances,
-            record_r_out_evals,
-            record_w_out_evals,
-            lk_p1_out_eval,
-            lk_p2_out_eval,
-            lk_q1_out_eval,
-            lk_q2_out_eval,
-            tower_proof,
-            main_sel_sumcheck_proofs: main_sel_sumcheck_proofs.proofs,
-            r_records_in_evals,
-            w_records_in_evals,
-            lk_records_in_evals,
-            wits_commit,
-            wits_opening_proof,
-            wits_in_evals,
-        })
+        let wits_commit = PCS::get_pure_commitment(wits_commit);
+
+        Ok((
+            ZKVMOpcodeProof {
+                num_instances,
+                record_r_out_evals,
+                record_w_out_evals,
+                lk_p1_out_eval,
+                lk_p2_out_eval,
+                lk_q1_out_eval,
+                lk_q2_out_eval,
+                tower_proof,
+                main_sel_sumcheck_proofs: main_sel_sumcheck_proofs.proofs,
+                r_records_in_evals,
+                w_records_in_evals,
+                lk_records_in_evals,
+                wits_commit,
+                wits_in_evals,
+                packed_slot: None,
+            },
+            input_open_point,
+        ))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -651,10 +796,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
     pub fn create_table_proof(
         &self,
         name: &str,
-        pp: &PCS::ProverParam,
         circuit_pk: &ProvingKey<E, PCS>,
         witnesses: Vec<ArcMultilinearExtension<'_, E>>,
-        wits_commit: PCS::CommitmentWithData,
+        wits_commit: &PCS::CommitmentWithData,
         pi: &[ArcMultilinearExtension<'_, E>],
         transcript: &mut Transcript<E>,
         challenges: &[E; 2],
@@ -694,29 +838,14 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let wit_inference_span = entered_span!("wit_inference");
         // main constraint: lookup denominator and numerator record witness inference
         let record_span = entered_span!("record");
-        let mut records_wit: Vec<ArcMultilinearExtension<'_, E>> = cs
-            .r_table_expressions
-            .par_iter()
-            .map(|r| &r.expr)
-            .chain(cs.w_table_expressions.par_iter().map(|w| &w.expr))
-            .chain(
-                cs.lk_table_expressions
-                    .par_iter()
-                    .map(|lk| &lk.multiplicity),
-            )
-            .chain(cs.lk_table_expressions.par_iter().map(|lk| &lk.values))
-            .map(|expr| {
-                assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&fixed, &witnesses, pi, challenges, expr)
-            })
-            .collect();
-        let max_log2_num_instance = records_wit.iter().map(|mle| mle.num_vars()).max().unwrap();
-        let min_log2_num_instance = records_wit.iter().map(|mle| mle.num_vars()).min().unwrap();
-        let (r_set_wit, remains) = records_wit.split_at_mut(cs.r_table_expressions.len());
-        let (w_set_wit, remains) = remains.split_at_mut(cs.w_table_expressions.len());
-        let (lk_n_wit, remains) = remains.split_at_mut(cs.lk_table_expressions.len());
-        let (lk_d_wit, _empty) = remains.split_at_mut(cs.lk_table_expressions.len());
-        assert!(_empty.is_empty());
+        let records = RecordWitness::infer_table(cs, &fixed, &witnesses, pi, challenges);
+        let (min_log2_num_instance, max_log2_num_instance) = records.num_vars_range();
+        let RecordWitness {
+            r_records: r_set_wit,
+            w_records: w_set_wit,
+            lk_multiplicities: lk_n_wit,
+            lk_records: lk_d_wit,
+        } = records;
 
         exit_span!(record_span);
 
@@ -754,7 +883,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             })
             .collect::<Vec<_>>();
         let lk_denominator_last_layer = lk_d_wit
-            .iter_mut()
+            .iter()
             .map(|wit| {
                 let (first, second) = wit
                     .get_ext_field_vec()
@@ -969,7 +1098,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 }
 
                 let (same_r_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
-                    num_threads,
+                    virtual_polys.num_threads(),
                     virtual_polys.get_batched_polys(),
                     transcript,
                 );
@@ -1039,49 +1168,21 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .collect_vec();
         // TODO implement mechanism to skip commitment
 
-        let pcs_opening = entered_span!("pcs_opening");
-        let (fixed_opening_proof, fixed_commit) = if !fixed.is_empty() {
-            (
-                Some(
-                    PCS::simple_batch_open(
-                        pp,
-                        &fixed,
-                        circuit_pk.fixed_commit_wd.as_ref().unwrap(),
-                        &input_open_point,
-                        fixed_in_evals.as_slice(),
-                        transcript,
-                    )
-                    .map_err(ZKVMError::PCSError)?,
-                ),
-                Some(PCS::get_pure_commitment(
-                    circuit_pk.fixed_commit_wd.as_ref().unwrap(),
-                )),
-            )
-        } else {
-            (None, None)
-        };
-
+        let fixed_commit = circuit_pk
+            .fixed_commit_wd
+            .as_ref()
+            .map(PCS::get_pure_commitment);
         tracing::debug!(
-            "[table {}] build opening proof for {} fixed polys at {:?}: values = {:?}, commit = {:?}",
+            "[table {}] {} fixed polys to open at {:?}: values = {:?}, commit = {:?}",
             name,
             fixed.len(),
             input_open_point,
             fixed_in_evals,
             fixed_commit,
         );
-        let wits_opening_proof = PCS::simple_batch_open(
-            pp,
-            &witnesses,
-            &wits_commit,
-            &input_open_point,
-            wits_in_evals.as_slice(),
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-        exit_span!(pcs_opening);
-        let wits_commit = PCS::get_pure_commitment(&wits_commit);
+        let wits_commit = PCS::get_pure_commitment(wits_commit);
         tracing::debug!(
-            "[table {}] build opening proof for {} polys at {:?}: values = {:?}, commit = {:?}",
+            "[table {}] {} polys to open at {:?}: values = {:?}, commit = {:?}",
             name,
             witnesses.len(),
             input_open_point,
@@ -1099,13 +1200,12 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 lk_in_evals,
                 tower_proof,
                 fixed_in_evals,
-                fixed_opening_proof,
                 rw_hints_num_vars,
                 wits_in_evals,
                 wits_commit,
-                wits_opening_proof,
             },
             pi_in_evals,
+            input_open_point,
         ))
     }
 }
@@ -1245,7 +1345,7 @@ impl TowerProver {
                 // shows it to be (inexplicably) much more time-consuming than the call to `prove_batch_polys`
                 // This is likely a bug in the tracing-flame crate.
                 let (sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
-                    num_threads,
+                    virtual_polys.num_threads(),
                     virtual_polys.get_batched_polys(),
                     transcript,
                 );
@@ -0,0 +1,110 @@
+//! The witness of a circuit is inferred in two phases.
+//!
+//! 1. The assignment of its instances does not depend on the challenges, so it is computed, and
+//!    committed, before the transcript produces them, see
+//!    [`super::prover::ZKVMProver::commit_witnesses`] and [`super::commit_stream`].
+//! 2. Its records, i.e. the reads, writes and lookups combined by the challenges, are inferred
+//!    from the assignment once the challenges are drawn, see [`RecordWitness`]. They are the
+//!    input layer of the towers.
+
+use ff_ext::ExtensionField;
+use itertools::{Itertools, chain};
+use multilinear_extensions::virtual_poly_v2::ArcMultilinearExtension;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    circuit_builder::ConstraintSystem, expression::Expression, scheme::utils::wit_infer_by_expr,
+};
+
+/// The witness of a circuit that depends on the challenges: one polynomial per record, over the
+/// instances of the circuit.
+pub struct RecordWitness<'a, E: ExtensionField> {
+    pub r_records: Vec<ArcMultilinearExtension<'a, E>>,
+    pub w_records: Vec<ArcMultilinearExtension<'a, E>>,
+    /// The looked up records of an opcode circuit, or the records of the tables of a table
+    /// circuit.
+    pub lk_records: Vec<ArcMultilinearExtension<'a, E>>,
+    /// The multiplicities of the records of the tables, empty for an opcode circuit.
+    pub lk_multiplicities: Vec<ArcMultilinearExtension<'a, E>>,
+}
+
+impl<'a, E: ExtensionField> RecordWitness<'a, E> {
+    /// Infer the records of an opcode circuit from its assignment `witnesses`.
+    pub fn infer_opcode(
+        cs: &ConstraintSystem<E>,
+        witnesses: &[ArcMultilinearExtension<'a, E>],
+        pi: &[ArcMultilinearExtension<'a, E>],
+        challenges: &[E; 2],
+    ) -> Self {
+        let exprs = chain!(&cs.r_expressions, &cs.w_expressions, &cs.lk_expressions).collect_vec();
+        let mut records = infer_records(exprs, &[], witnesses, pi, challenges);
+        let lk_records = records.split_off(cs.r_expressions.len() + cs.w_expressions.len());
+        let w_records = records.split_off(cs.r_expressions.len());
+        RecordWitness {
+            r_records: records,
+            w_records,
+            lk_records,
+            lk_multiplicities: vec![],
+        }
+    }
+
+    /// Infer the records of a table circuit from its assignment `witnesses` and its `fixed`
+    /// columns.
+    pub fn infer_table(
+        cs: &ConstraintSystem<E>,
+        fixed: &[ArcMultilinearExtension<'a, E>],
+        witnesses: &[ArcMultilinearExtension<'a, E>],
+        pi: &[ArcMultilinearExtension<'a, E>],
+        challenges: &[E; 2],
+    ) -> Self {
+        let exprs = cs
+            .r_table_expressions
+            .iter()
+            .map(|r| &r.expr)
+            .chain(cs.w_table_expressions.iter().map(|w| &w.expr))
+            .chain(cs.lk_table_expressions.iter().map(|lk| &lk.multiplicity))
+            .chain(cs.lk_table_expressions.iter().map(|lk| &lk.values))
+            .collect_vec();
+        let mut records = infer_records(exprs, fixed, witnesses, pi, challenges);
+        let num_lk = cs.lk_table_expressions.len();
+        let lk_records = records.split_off(records.len() - num_lk);
+        let lk_multiplicities = records.split_off(records.len() - num_lk);
+        let w_records = records.split_off(cs.r_table_expressions.len());
+        RecordWitness {
+            r_records: records,
+            w_records,
+            lk_records,
+            lk_multiplicities,
+        }
+    }
+
+    /// The numbers of variables of the smallest and of the largest records.
+    pub fn num_vars_range(&self) -> (usize, usize) {
+        chain!(
+            &self.r_records,
+            &self.w_records,
+            &self.lk_multiplicities,
+            &self.lk_records
+        )
+        .map(|mle| mle.num_vars())
+        .minmax()
+        .into_option()
+        .expect("a circuit without records")
+    }
+}
+
+fn infer_records<'a, E: ExtensionField>(
+    exprs: Vec<&Expression<E>>,
+    fixed: &[ArcMultilinearExtension<'a, E>],
+    witnesses: &[ArcMultilinearExtension<'a, E>],
+    pi: &[ArcMultilinearExtension<'a, E>],
+    challenges: &[E; 2],
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    exprs
+        .into_par_iter()
+        .map(|expr| {
+            assert_eq!(expr.degree(), 1);
+            wit_infer_by_expr(fixed, witnesses, pi, challenges, expr)
+        })
+        .collect()
+}
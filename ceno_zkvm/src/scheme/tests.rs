@@ -1,15 +1,16 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::{marker::PhantomData, mem::MaybeUninit, time::Duration};
 
 use ceno_emul::{
     CENO_PLATFORM,
     InsnKind::{ADD, EANY},
-    PC_WORD_SIZE, Platform, Program, StepRecord, VMState,
+    PC_WORD_SIZE, Platform, Program, StepRecord, Tracer, VMState,
 };
 use ff::Field;
 use ff_ext::ExtensionField;
 use goldilocks::GoldilocksExt2;
-use itertools::Itertools;
+use itertools::{Itertools, izip};
 use mpcs::{Basefold, BasefoldDefault, BasefoldRSParams, PolynomialCommitmentScheme};
+use multilinear_extensions::mle::{DenseMultilinearExtension, MultilinearExtension};
 use transcript::Transcript;
 
 use crate::{
@@ -19,21 +20,30 @@ use crate::{
     expression::{ToExpr, WitIn},
     instructions::{
         Instruction,
-        riscv::{arith::AddInstruction, ecall::HaltInstruction},
+        riscv::{
+            arith::AddInstruction,
+            constants::{END_CYCLE_IDX, EXIT_CODE_IDX, EXIT_PC, FINAL_REGS_IDX, RANDOM_SEED_IDX},
+            ecall::HaltInstruction,
+        },
     },
     set_val,
     structs::{
-        PointAndEval, RAMType::Register, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses,
+        Point, PointAndEval, RAMType::Register, ZKVMConstraintSystem, ZKVMFixedTraces,
+        ZKVMProvingKey, ZKVMWitnesses,
     },
-    tables::{ProgramTableCircuit, U16TableCircuit},
+    tables::{ProgramTableCircuit, TableCircuit, U16TableCircuit},
+    verifier::verify_proof_json,
     witness::LkMultiplicity,
 };
 
 use super::{
-    PublicValues,
-    constants::{MAX_NUM_VARIABLES, NUM_FANIN},
+    PublicValues, ZKVMProof, ZKVMProofMetadata,
+    auxiliary::{AuxiliaryProver, AuxiliaryVerifier},
+    commit_stream::{CommittedWitnesses, commit_while_assigning},
+    constants::{MAX_NUM_VARIABLES, NUM_FANIN, PACKING_MIN_NUM_VARS, TRANSCRIPT_LABEL},
     prover::ZKVMProver,
-    verifier::ZKVMVerifier,
+    record_witness::RecordWitness,
+    verifier::{ZKVMVerifier, verify_cycle_public_values, verify_halt_public_values},
 };
 
 struct TestConfig {
@@ -135,13 +145,12 @@ fn test_rw_lk_expression_combination() {
             transcript.read_challenge().elements,
         ];
 
-        let proof = prover
+        let (proof, _) = prover
             .create_opcode_proof(
                 name.as_str(),
-                &prover.pk.pp,
                 prover.pk.circuit_pks.get(&name).unwrap(),
                 wits_in,
-                commit,
+                &commit,
                 &[],
                 num_instances,
                 &mut transcript,
@@ -163,7 +172,6 @@ fn test_rw_lk_expression_combination() {
         let _rt_input = verifier
             .verify_opcode_proof(
                 name.as_str(),
-                &vk.vp,
                 verifier.vk.circuit_vks.get(&name).unwrap(),
                 &proof,
                 &[],
@@ -181,6 +189,93 @@ fn test_rw_lk_expression_combination() {
     test_rw_lk_expression_combination_inner::<17, 61>();
 }
 
+#[test]
+fn test_commit_while_assigning() {
+    type E = GoldilocksExt2;
+    type Pcs = BasefoldDefault<E>;
+    type Circuit = TestCircuit<E, 1, 1>;
+
+    let param = Pcs::setup(1 << 13).unwrap();
+    let (pp, _) = Pcs::trim(param, 1 << 13).unwrap();
+    let mut zkvm_cs = ZKVMConstraintSystem::default();
+    let config = zkvm_cs.register_opcode_circuit::<Circuit>();
+    let assign = |witnesses: &mut ZKVMWitnesses<E>| {
+        witnesses.assign_opcode_circuit::<Circuit>(&zkvm_cs, &config, vec![
+            StepRecord::default();
+            1 << 8
+        ])
+    };
+    let commitments = |committed: CommittedWitnesses<E, Pcs>| {
+        committed
+            .into_iter_sorted()
+            .map(|(name, committed)| {
+                let commitment = Pcs::get_pure_commitment(&committed.commitment.unwrap());
+                (name, serde_json::to_string(&commitment).unwrap())
+            })
+            .collect_vec()
+    };
+
+    let mut witnesses = ZKVMWitnesses::default();
+    assign(&mut witnesses).unwrap();
+    let expected = commitments(CommittedWitnesses::commit_all(&pp, witnesses).unwrap());
+
+    let ((), committed) = commit_while_assigning::<E, Pcs, _>(&pp, 1, None, |sink| {
+        let mut witnesses = ZKVMWitnesses::default();
+        assign(&mut witnesses)?;
+        sink.send(&mut witnesses)?;
+        assert!(witnesses.get_opcode_witness(&Circuit::name()).is_none());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(commitments(committed), expected);
+
+    // An error of the assignment is returned after the commitments stop.
+    let result = commit_while_assigning::<E, Pcs, ()>(&pp, 1, None, |_| {
+        Err(ZKVMError::InvalidWitness("test".into()))
+    });
+    assert!(matches!(result, Err(ZKVMError::InvalidWitness(_))));
+}
+
+#[test]
+fn test_record_witness() {
+    type E = GoldilocksExt2;
+    type Circuit = TestCircuit<E, 2, 3>;
+
+    let mut zkvm_cs = ZKVMConstraintSystem::default();
+    let config = zkvm_cs.register_opcode_circuit::<Circuit>();
+    let mut witnesses = ZKVMWitnesses::default();
+    witnesses
+        .assign_opcode_circuit::<Circuit>(&zkvm_cs, &config, vec![StepRecord::default(); 1 << 4])
+        .unwrap();
+    // The assignment is done before the challenges are drawn.
+    let wits_in = witnesses
+        .get_opcode_witness(&Circuit::name())
+        .unwrap()
+        .into_mles()
+        .into_iter()
+        .map(|v| v.into())
+        .collect_vec();
+
+    let cs = zkvm_cs.get_cs(&Circuit::name()).unwrap();
+    let infer = |challenges: &[E; 2]| RecordWitness::infer_opcode(cs, &wits_in, &[], challenges);
+    let records = infer(&[E::from(2), E::from(3)]);
+    assert_eq!(records.r_records.len(), 2);
+    assert_eq!(records.w_records.len(), 2);
+    assert_eq!(records.lk_records.len(), 3);
+    assert!(records.lk_multiplicities.is_empty());
+    assert_eq!(records.num_vars_range(), (4, 4));
+    // The circuit writes back what it reads.
+    for (r, w) in izip!(&records.r_records, &records.w_records) {
+        assert_eq!(r.get_ext_field_vec(), w.get_ext_field_vec());
+    }
+
+    let other = infer(&[E::from(5), E::from(3)]);
+    assert_ne!(
+        records.r_records[0].get_ext_field_vec(),
+        other.r_records[0].get_ext_field_vec()
+    );
+}
+
 const PROGRAM_SIZE: usize = 4;
 #[allow(clippy::unusual_byte_groupings)]
 const ECALL_HALT: u32 = 0b_000000000000_00000_000_00000_1110011;
@@ -202,112 +297,398 @@ const PROGRAM_CODE: [u32; PROGRAM_SIZE] = {
 #[ignore = "this case is already tested in riscv_example as ecall_halt has only one instance"]
 #[test]
 fn test_single_add_instance_e2e() {
-    type E = GoldilocksExt2;
-    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+    single_add_instance_e2e(None);
+}
 
-    // set up program
-    let program = Program::new(
-        CENO_PLATFORM.pc_base(),
-        CENO_PLATFORM.pc_base(),
-        PROGRAM_CODE.to_vec(),
-        PROGRAM_CODE
-            .iter()
-            .enumerate()
-            .map(|(insn_idx, &insn)| {
-                (
-                    (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
-                    insn,
-                )
-            })
-            .collect(),
-    );
+#[test]
+fn test_single_add_instance_packed_e2e() {
+    single_add_instance_e2e(Some(PACKING_MIN_NUM_VARS));
+}
 
-    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
-    let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
-    let mut zkvm_cs = ZKVMConstraintSystem::default();
-    // opcode circuits
-    let add_config = zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
-    let halt_config = zkvm_cs.register_opcode_circuit::<HaltInstruction<E>>();
-    let u16_range_config = zkvm_cs.register_table_circuit::<U16TableCircuit<E>>();
+/// The add and halt circuits of [`PROGRAM_CODE`] with the records of its run.
+struct AddHaltSetup {
+    zkvm_cs: ZKVMConstraintSystem<GoldilocksExt2>,
+    add_config: <AddInstruction<GoldilocksExt2> as Instruction<GoldilocksExt2>>::InstructionConfig,
+    halt_config:
+        <HaltInstruction<GoldilocksExt2> as Instruction<GoldilocksExt2>>::InstructionConfig,
+    u16_range_config:
+        <U16TableCircuit<GoldilocksExt2> as TableCircuit<GoldilocksExt2>>::TableConfig,
+    prog_config: <ProgramTableCircuit<GoldilocksExt2> as TableCircuit<GoldilocksExt2>>::TableConfig,
+    program: Program,
+    add_records: Vec<StepRecord>,
+    halt_records: Vec<StepRecord>,
+}
 
-    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>();
+impl AddHaltSetup {
+    /// The circuits, their proving key and the records.
+    fn new() -> (
+        Self,
+        ZKVMProvingKey<GoldilocksExt2, Basefold<GoldilocksExt2, BasefoldRSParams>>,
+    ) {
+        type E = GoldilocksExt2;
+        type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+        // set up program
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            PROGRAM_CODE.to_vec(),
+            PROGRAM_CODE
+                .iter()
+                .enumerate()
+                .map(|(insn_idx, &insn)| {
+                    (
+                        (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
+                        insn,
+                    )
+                })
+                .collect(),
+        );
+
+        let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
+        let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        // opcode circuits
+        let add_config = zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
+        let halt_config = zkvm_cs.register_opcode_circuit::<HaltInstruction<E>>();
+        let u16_range_config = zkvm_cs.register_table_circuit::<U16TableCircuit<E>>();
 
-    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
-    zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
-    zkvm_fixed_traces.register_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs);
+        let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>();
 
-    zkvm_fixed_traces.register_table_circuit::<U16TableCircuit<E>>(
-        &zkvm_cs,
-        &u16_range_config,
-        &(),
-    );
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
+        zkvm_fixed_traces.register_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs);
 
-    zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
-        &zkvm_cs,
-        &prog_config,
-        &program,
-    );
+        zkvm_fixed_traces.register_table_circuit::<U16TableCircuit<E>>(
+            &zkvm_cs,
+            &u16_range_config,
+            &(),
+        );
 
-    let pk = zkvm_cs
-        .clone()
-        .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
-        .expect("keygen failed");
-    let vk = pk.get_vk();
+        zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
+            &zkvm_cs,
+            &prog_config,
+            &program,
+        );
 
-    // single instance
-    let mut vm = VMState::new(CENO_PLATFORM, program.clone());
-    let all_records = vm
-        .iter_until_halt()
-        .collect::<Result<Vec<StepRecord>, _>>()
-        .expect("vm exec failed")
-        .into_iter()
-        .collect::<Vec<_>>();
-    let mut add_records = vec![];
-    let mut halt_records = vec![];
-    all_records.into_iter().for_each(|record| {
-        let kind = record.insn().codes().kind;
-        match kind {
-            ADD => add_records.push(record),
-            EANY => {
-                if record.rs1().unwrap().value == Platform::ecall_halt() {
-                    halt_records.push(record);
+        let pk = zkvm_cs
+            .clone()
+            .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
+            .expect("keygen failed");
+
+        // single instance
+        let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+        let all_records = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .expect("vm exec failed")
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut add_records = vec![];
+        let mut halt_records = vec![];
+        all_records.into_iter().for_each(|record| {
+            let kind = record.insn().codes().kind;
+            match kind {
+                ADD => add_records.push(record),
+                EANY => {
+                    if record.rs1().unwrap().value == Platform::ecall_halt() {
+                        halt_records.push(record);
+                    }
                 }
+                _ => {}
             }
-            _ => {}
-        }
-    });
-    assert_eq!(add_records.len(), 1);
-    assert_eq!(halt_records.len(), 1);
+        });
+        assert_eq!(add_records.len(), 1);
+        assert_eq!(halt_records.len(), 1);
+
+        let setup = AddHaltSetup {
+            zkvm_cs,
+            add_config,
+            halt_config,
+            u16_range_config,
+            prog_config,
+            program,
+            add_records,
+            halt_records,
+        };
+        (setup, pk)
+    }
+
+    fn assign(&self) -> ZKVMWitnesses<GoldilocksExt2> {
+        type E = GoldilocksExt2;
+
+        let mut zkvm_witness = ZKVMWitnesses::default();
+        // assign opcode circuits
+        zkvm_witness
+            .assign_opcode_circuit::<AddInstruction<E>>(
+                &self.zkvm_cs,
+                &self.add_config,
+                self.add_records.clone(),
+            )
+            .unwrap();
+        zkvm_witness
+            .assign_opcode_circuit::<HaltInstruction<E>>(
+                &self.zkvm_cs,
+                &self.halt_config,
+                self.halt_records.clone(),
+            )
+            .unwrap();
+        zkvm_witness.finalize_lk_multiplicities();
+        zkvm_witness
+            .assign_table_circuit::<U16TableCircuit<E>>(&self.zkvm_cs, &self.u16_range_config, &())
+            .unwrap();
+        zkvm_witness
+            .assign_table_circuit::<ProgramTableCircuit<E>>(
+                &self.zkvm_cs,
+                &self.prog_config,
+                &self.program,
+            )
+            .unwrap();
+        zkvm_witness
+    }
+}
+
+fn single_add_instance_e2e(packing_min_num_vars: Option<usize>) {
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+    let (setup, pk) = AddHaltSetup::new();
+    let vk = pk.get_vk();
+    let vk_json = serde_json::to_string(&vk).unwrap();
 
     // proving
-    let prover = ZKVMProver::new(pk);
+    let mut prover = ZKVMProver::new(pk);
+    prover.packing_min_num_vars = packing_min_num_vars;
     let verifier = ZKVMVerifier::new(vk);
-    let mut zkvm_witness = ZKVMWitnesses::default();
-    // assign opcode circuits
-    zkvm_witness
-        .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, add_records)
-        .unwrap();
-    zkvm_witness
-        .assign_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs, &halt_config, halt_records)
-        .unwrap();
-    zkvm_witness.finalize_lk_multiplicities();
-    zkvm_witness
-        .assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &u16_range_config, &())
-        .unwrap();
-    zkvm_witness
-        .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+    let assign = || setup.assign();
+
+    // The commitments made while assigning are the same.
+    let commitments = |committed: CommittedWitnesses<E, Pcs>| {
+        committed
+            .into_iter_sorted()
+            .map(|(name, committed)| {
+                let commitment = Pcs::get_pure_commitment(&committed.commitment.unwrap());
+                (
+                    name,
+                    serde_json::to_string(&commitment).unwrap(),
+                    committed.packed_slot,
+                )
+            })
+            .collect_vec()
+    };
+    let (_, committed) =
+        commit_while_assigning::<E, Pcs, _>(&prover.pk.pp, 1, packing_min_num_vars, |sink| {
+            sink.send(&mut assign())
+        })
         .unwrap();
+    assert_eq!(
+        commitments(committed),
+        commitments(prover.commit_witnesses(assign()).unwrap())
+    );
 
-    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0]);
-    let transcript = Transcript::new(b"riscv");
+    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0], vec![]);
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
     let zkvm_proof = prover
-        .create_proof(zkvm_witness, pi, transcript)
+        .create_proof(assign(), pi, transcript)
         .expect("create_proof failed");
+    if packing_min_num_vars.is_some() {
+        // The add and halt circuits share a commitment.
+        let (_, add_proof) = &zkvm_proof.opcode_proofs[&AddInstruction::<E>::name()];
+        let (_, halt_proof) = &zkvm_proof.opcode_proofs[&HaltInstruction::<E>::name()];
+        assert!(add_proof.packed_slot.is_some() && halt_proof.packed_slot.is_some());
+        assert_eq!(
+            serde_json::to_string(&add_proof.wits_commit).unwrap(),
+            serde_json::to_string(&halt_proof.wits_commit).unwrap()
+        );
+    }
 
-    let transcript = Transcript::new(b"riscv");
+    let proof_json = serde_json::to_string(&zkvm_proof).unwrap();
+    let transcript = Transcript::new(TRANSCRIPT_LABEL);
     assert!(
         verifier
             .verify_proof(zkvm_proof, transcript)
             .expect("verify proof return with error"),
     );
+
+    // The same verification, from the serialized verifying key and proof.
+    assert!(verify_proof_json::<E, Pcs>(&vk_json, &proof_json, true).unwrap());
+}
+
+/// A toy sub-protocol, which opens its witness at a point drawn from its transcript.
+struct ToyAuxiliary {
+    name: String,
+    witness: Vec<DenseMultilinearExtension<GoldilocksExt2>>,
+}
+
+impl ToyAuxiliary {
+    const NUM_VARS: usize = 4;
+
+    fn new(name: &str) -> Self {
+        ToyAuxiliary {
+            name: name.to_string(),
+            witness: (0..3)
+                .map(|_| DenseMultilinearExtension::random(Self::NUM_VARS, &mut rand::thread_rng()))
+                .collect(),
+        }
+    }
+
+    fn point(transcript: &mut Transcript<GoldilocksExt2>) -> Point<GoldilocksExt2> {
+        (0..Self::NUM_VARS)
+            .map(|_| transcript.get_and_append_challenge(b"toy point").elements)
+            .collect()
+    }
+}
+
+impl AuxiliaryProver<GoldilocksExt2> for ToyAuxiliary {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn witness(&self) -> Vec<DenseMultilinearExtension<GoldilocksExt2>> {
+        self.witness.clone()
+    }
+
+    fn prove(
+        &self,
+        _challenges: &[GoldilocksExt2; 2],
+        transcript: &mut Transcript<GoldilocksExt2>,
+    ) -> Result<(Vec<u8>, Point<GoldilocksExt2>, Vec<GoldilocksExt2>), ZKVMError> {
+        let point = Self::point(transcript);
+        let evals = self
+            .witness
+            .iter()
+            .map(|w| w.evaluate(&point))
+            .collect_vec();
+        transcript.append_field_element_exts(&evals);
+        Ok((vec![Self::NUM_VARS as u8], point, evals))
+    }
+}
+
+impl AuxiliaryVerifier<GoldilocksExt2> for ToyAuxiliary {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn verify(
+        &self,
+        proof: &[u8],
+        wits_in_evals: &[GoldilocksExt2],
+        _challenges: &[GoldilocksExt2; 2],
+        transcript: &mut Transcript<GoldilocksExt2>,
+    ) -> Result<Point<GoldilocksExt2>, ZKVMError> {
+        if proof != [Self::NUM_VARS as u8] || wits_in_evals.len() != self.witness.len() {
+            return Err(ZKVMError::VerifyError("invalid toy proof".into()));
+        }
+        let point = Self::point(transcript);
+        transcript.append_field_element_exts(wits_in_evals);
+        Ok(point)
+    }
+}
+
+#[test]
+fn test_auxiliary_e2e() {
+    let (setup, pk) = AddHaltSetup::new();
+    let verifier = ZKVMVerifier::new(pk.get_vk());
+    let prover = ZKVMProver::new(pk);
+    let toy = ToyAuxiliary::new("toy");
+    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0], vec![]);
+    let zkvm_proof = prover
+        .create_proof_with_auxiliary(
+            setup.assign(),
+            &[&toy],
+            pi,
+            Transcript::new(TRANSCRIPT_LABEL),
+        )
+        .expect("create_proof failed");
+
+    let verify = |proof: ZKVMProof<_, _>, auxiliaries: &[&dyn AuxiliaryVerifier<_>]| {
+        verifier.verify_proof_with_auxiliary(proof, auxiliaries, Transcript::new(TRANSCRIPT_LABEL))
+    };
+    assert!(verify(zkvm_proof.clone(), &[&toy]).unwrap());
+
+    // The opening does not hold for other evaluations.
+    let mut tampered = zkvm_proof.clone();
+    tampered.aux_proofs.get_mut("toy").unwrap().1.wits_in_evals[0] += GoldilocksExt2::ONE;
+    assert!(!matches!(verify(tampered, &[&toy]), Ok(true)));
+
+    // The proof must have exactly the expected protocols.
+    assert!(verify(zkvm_proof.clone(), &[]).is_err());
+    let other = ToyAuxiliary::new("other");
+    assert!(verify(zkvm_proof, &[&toy, &other]).is_err());
+}
+
+#[test]
+fn test_public_final_regs() {
+    type E = GoldilocksExt2;
+    let a0 = Platform::reg_arg0();
+    let mut final_regs = vec![0; 64];
+    final_regs[a0] = 0x1234_5678;
+    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![], final_regs);
+    assert_eq!(pi.final_reg(a0), 0x1234_5678);
+    assert_eq!(pi.final_reg(64), 0);
+
+    // One instance per limb, with one register per row.
+    let raw_pi = pi.to_vec::<E>();
+    assert_eq!(raw_pi.len(), RANDOM_SEED_IDX + 2);
+    let limb = |v: u64| <E as ExtensionField>::BaseField::from(v);
+    assert_eq!(raw_pi[FINAL_REGS_IDX][a0], limb(0x5678));
+    assert_eq!(raw_pi[FINAL_REGS_IDX + 1][a0], limb(0x1234));
+    assert_eq!(raw_pi[FINAL_REGS_IDX + 1][a0 + 1], limb(0));
+
+    // The seed of the RANDOM ecall, as two halves.
+    let pi = pi.with_random_seed(0x1234_5678_9abc_def0);
+    assert_eq!(pi.random_seed(), 0x1234_5678_9abc_def0);
+    let raw_pi = pi.to_vec::<E>();
+    assert_eq!(raw_pi[RANDOM_SEED_IDX], vec![limb(0x9abc_def0)]);
+    assert_eq!(raw_pi[RANDOM_SEED_IDX + 1], vec![limb(0x1234_5678)]);
+}
+
+#[test]
+fn test_halt_public_values() {
+    type E = GoldilocksExt2;
+    type Pcs = BasefoldDefault<E>;
+    let halted = PublicValues::new(0x1_0002, 0, 0, EXIT_PC as u32, 8, vec![], vec![]);
+    assert!(verify_halt_public_values(&halted.to_vec::<E>(), true).is_ok());
+    assert!(verify_halt_public_values(&halted.to_vec::<E>(), false).is_err());
+    assert_eq!(ZKVMProof::<E, Pcs>::empty(halted).exit_code(), 0x1_0002);
+
+    // A trace that stops before the halt.
+    let running = PublicValues::new(0, 0, 0, CENO_PLATFORM.pc_base(), 8, vec![], vec![]);
+    assert!(verify_halt_public_values(&running.to_vec::<E>(), false).is_ok());
+    assert!(verify_halt_public_values(&running.to_vec::<E>(), true).is_err());
+
+    // An exit code limb out of range.
+    let mut raw_pi = PublicValues::new(0, 0, 0, EXIT_PC as u32, 8, vec![], vec![]).to_vec::<E>();
+    raw_pi[EXIT_CODE_IDX] = vec![<E as ExtensionField>::BaseField::from(1 << 16)];
+    assert!(verify_halt_public_values(&raw_pi, true).is_err());
+}
+
+#[test]
+fn test_cycle_public_values() {
+    type E = GoldilocksExt2;
+    let pi = |end_cycle| PublicValues::new(0, 0, 4, 0, end_cycle, vec![], vec![]).to_vec::<E>();
+    assert!(verify_cycle_public_values(&pi(Tracer::MAX_CYCLE as u32)).is_ok());
+
+    let mut raw_pi = pi(8);
+    raw_pi[END_CYCLE_IDX] = vec![<E as ExtensionField>::BaseField::from(
+        Tracer::MAX_CYCLE + 1,
+    )];
+    assert!(verify_cycle_public_values(&raw_pi).is_err());
+}
+
+#[test]
+fn test_proof_metadata_checksum() {
+    let metadata = ZKVMProofMetadata {
+        prover_version: "0.1.0".to_string(),
+        params_digest: vec![1, 2],
+        timings: vec![("main_proofs".to_string(), Duration::from_millis(5))],
+        num_steps: 10,
+        checksum: vec![],
+    }
+    .with_checksum::<GoldilocksExt2>();
+    assert!(metadata.checksum_matches::<GoldilocksExt2>());
+
+    let mut corrupted = metadata.clone();
+    corrupted.num_steps = 11;
+    assert!(!corrupted.checksum_matches::<GoldilocksExt2>());
 }
@@ -18,6 +18,8 @@ use rayon::{
     prelude::ParallelSliceMut,
 };
 
+pub use crate::verifier::eval_by_expr_with_instance;
+pub(crate) use crate::verifier::{eval_by_expr, eval_by_expr_with_fixed};
 use crate::{
     expression::Expression, scheme::constants::MIN_PAR_SIZE, utils::next_pow2_instance_padding,
 };
@@ -235,6 +237,8 @@ pub(crate) fn infer_tower_product_witness<E: ExtensionField>(
     wit_layers
 }
 
+/// Evaluate `expr` on every instance. The evaluation is pointwise, and the parallel iterators
+/// collect the instances in order, so the result does not depend on the number of threads.
 pub(crate) fn wit_infer_by_expr<'a, E: ExtensionField, const N: usize>(
     fixed: &[ArcMultilinearExtension<'a, E>],
     witnesses: &[ArcMultilinearExtension<'a, E>],
@@ -349,58 +353,6 @@ pub(crate) fn wit_infer_by_expr<'a, E: ExtensionField, const N: usize>(
     )
 }
 
-pub(crate) fn eval_by_expr<E: ExtensionField>(
-    witnesses: &[E],
-    challenges: &[E],
-    expr: &Expression<E>,
-) -> E {
-    eval_by_expr_with_fixed(&[], witnesses, challenges, expr)
-}
-
-pub(crate) fn eval_by_expr_with_fixed<E: ExtensionField>(
-    fixed: &[E],
-    witnesses: &[E],
-    challenges: &[E],
-    expr: &Expression<E>,
-) -> E {
-    expr.evaluate::<E>(
-        &|f| fixed[f.0],
-        &|witness_id| witnesses[witness_id as usize],
-        &|scalar| scalar.into(),
-        &|challenge_id, pow, scalar, offset| {
-            // TODO cache challenge power to be acquired once for each power
-            let challenge = challenges[challenge_id as usize];
-            challenge.pow([pow as u64]) * scalar + offset
-        },
-        &|a, b| a + b,
-        &|a, b| a * b,
-        &|x, a, b| a * x + b,
-    )
-}
-
-pub fn eval_by_expr_with_instance<E: ExtensionField>(
-    fixed: &[E],
-    witnesses: &[E],
-    instance: &[E],
-    challenges: &[E],
-    expr: &Expression<E>,
-) -> E {
-    expr.evaluate_with_instance::<E>(
-        &|f| fixed[f.0],
-        &|witness_id| witnesses[witness_id as usize],
-        &|i| instance[i.0],
-        &|scalar| scalar.into(),
-        &|challenge_id, pow, scalar, offset| {
-            // TODO cache challenge power to be acquired once for each power
-            let challenge = challenges[challenge_id as usize];
-            challenge.pow([pow as u64]) * scalar + offset
-        },
-        &|a, b| a + b,
-        &|a, b| a * b,
-        &|x, a, b| a * x + b,
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use ff::Field;
@@ -717,4 +669,42 @@ mod tests {
         );
         res.get_ext_field_vec();
     }
+
+    #[test]
+    fn test_wit_infer_by_expr_thread_count() {
+        type E = goldilocks::GoldilocksExt2;
+        type B = goldilocks::Goldilocks;
+        let mut cs = ConstraintSystem::<E>::new(|| "test");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb.create_witin(|| "a");
+        let b = cb.create_witin(|| "b");
+
+        let expr: Expression<E> = a.expr() * b.expr()
+            + a.expr() * 5
+            + Expression::Challenge(0, 2, E::ONE, E::ONE) * b.expr();
+        // Enough instances to be split among threads.
+        let num_instances = 1 << 12;
+        let witnesses: Vec<ArcMultilinearExtension<E>> = [3, 7]
+            .map(|seed| {
+                (0..num_instances)
+                    .map(|i| B::from(seed * i as u64 + 1))
+                    .collect_vec()
+                    .into_mle()
+                    .into()
+            })
+            .to_vec();
+        let challenges = [E::from(11u64)];
+
+        let [one, many] = [1, 4].map(|num_threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap()
+                .install(|| wit_infer_by_expr(&[], &witnesses, &[], &challenges, &expr))
+                .get_ext_field_vec()
+                .to_vec()
+        });
+        assert_eq!(one, many);
+        assert_eq!(one.len(), num_instances);
+    }
 }
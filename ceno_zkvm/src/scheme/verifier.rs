@@ -1,8 +1,9 @@
 use std::marker::PhantomData;
 
 use ark_std::iterable::Iterable;
-use ceno_emul::WORD_SIZE;
+use ceno_emul::{Tracer, WORD_SIZE};
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 
 use itertools::{Itertools, interleave, izip};
 use mpcs::PolynomialCommitmentScheme;
@@ -18,22 +19,89 @@ use crate::{
     circuit_builder::SetTableAddrType,
     error::ZKVMError,
     expression::Instance,
-    instructions::{Instruction, riscv::ecall::HaltInstruction},
+    instructions::riscv::constants::{
+        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, EXIT_PC, INIT_CYCLE_IDX, LIMB_BITS, TS_BITS,
+    },
     scheme::{
-        constants::{NUM_FANIN, NUM_FANIN_LOGUP, SEL_DEGREE},
-        utils::eval_by_expr_with_instance,
+        auxiliary::AuxiliaryVerifier,
+        constants::{HALT_CIRCUIT_NAME, MAX_NUM_VARIABLES, NUM_FANIN, NUM_FANIN_LOGUP, SEL_DEGREE},
     },
     structs::{Point, PointAndEval, TowerProofs, VerifyingKey, ZKVMVerifyingKey},
     utils::{
         eq_eval_less_or_equal_than, eval_wellform_address_vec, get_challenge_pows,
         next_pow2_instance_padding,
     },
+    verifier::eval_by_expr_with_instance,
 };
 
 use super::{
-    ZKVMOpcodeProof, ZKVMProof, ZKVMTableProof, constants::MAINCONSTRAIN_SUMCHECK_BATCH_SIZE,
+    ZKVMOpcodeProof, ZKVMProof, ZKVMProofMetadata, ZKVMTableProof,
+    constants::MAINCONSTRAIN_SUMCHECK_BATCH_SIZE,
 };
 
+/// Check the public values against the halting of the trace.
+///
+/// The HALT circuit moves to `EXIT_PC`, which is outside of the program table, so that no
+/// instruction follows it and its state is the final one of the trace. With a halt, the final pc
+/// must then be `EXIT_PC`, and the exit code limbs read by the circuit must be canonical. Without
+/// one, nothing constrains the exit code, so it must be zero.
+pub(crate) fn verify_halt_public_values<F: SmallField>(
+    raw_pi: &[Vec<F>],
+    does_halt: bool,
+) -> Result<(), ZKVMError> {
+    let single = |idx: usize| match raw_pi.get(idx).map(Vec::as_slice) {
+        Some([v]) => Ok(v.to_canonical_u64()),
+        _ => Err(ZKVMError::VerifyError(format!(
+            "public value {idx} is not a single element"
+        ))),
+    };
+    let exit_code = [single(EXIT_CODE_IDX)?, single(EXIT_CODE_IDX + 1)?];
+    if does_halt {
+        let end_pc = single(END_PC_IDX)?;
+        if end_pc != EXIT_PC as u64 {
+            return Err(ZKVMError::VerifyError(format!(
+                "halting trace ends at pc {end_pc:#x}, expected {EXIT_PC:#x}"
+            )));
+        }
+        if exit_code.iter().any(|&limb| limb >= 1 << LIMB_BITS) {
+            return Err(ZKVMError::VerifyError(format!(
+                "exit code limbs {exit_code:?} are not {LIMB_BITS}-bit"
+            )));
+        }
+    } else if exit_code != [0, 0] {
+        return Err(ZKVMError::VerifyError(format!(
+            "exit code limbs {exit_code:?} without a halt"
+        )));
+    }
+    Ok(())
+}
+
+/// Check that the cycles of the public values are timestamps of `TS_BITS` bits, as the ones of
+/// the circuits, so that a trace cannot wrap around them.
+pub(crate) fn verify_cycle_public_values<F: SmallField>(
+    raw_pi: &[Vec<F>],
+) -> Result<(), ZKVMError> {
+    for idx in [INIT_CYCLE_IDX, END_CYCLE_IDX] {
+        match raw_pi.get(idx).map(Vec::as_slice) {
+            Some([v]) if v.to_canonical_u64() <= Tracer::MAX_CYCLE => {}
+            _ => {
+                return Err(ZKVMError::VerifyError(format!(
+                    "public value {idx} is not a cycle of {TS_BITS} bits"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `p / q` for a logup fraction of the tower of `name`, whose denominator a valid proof never
+/// makes zero.
+fn logup_fraction<E: ExtensionField>(name: &str, p: E, q: E) -> Result<E, ZKVMError> {
+    Option::from(q.invert())
+        .map(|q_inv: E| p * q_inv)
+        .ok_or_else(|| ZKVMError::VerifyError(format!("{name}: zero logup denominator")))
+}
+
 pub struct ZKVMVerifier<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub(crate) vk: ZKVMVerifyingKey<E, PCS>,
 }
@@ -43,6 +111,29 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         ZKVMVerifier { vk }
     }
 
+    /// Check the metadata of a proof, if any: that it matches its checksum and names the
+    /// verifying key. This is a consistency check, independent of the verification of the
+    /// proof itself, and does not authenticate the metadata.
+    pub fn verify_metadata<'p>(
+        &self,
+        vm_proof: &'p ZKVMProof<E, PCS>,
+    ) -> Result<Option<&'p ZKVMProofMetadata>, ZKVMError> {
+        let Some(metadata) = &vm_proof.metadata else {
+            return Ok(None);
+        };
+        if !metadata.checksum_matches::<E>() {
+            return Err(ZKVMError::VerifyError(
+                "proof metadata does not match its checksum".into(),
+            ));
+        }
+        if metadata.params_digest != self.vk.digest()?.to_canonical_u64_vec() {
+            return Err(ZKVMError::VerifyError(
+                "proof metadata is for another verifying key".into(),
+            ));
+        }
+        Ok(Some(metadata))
+    }
+
     /// Verify a trace from start to halt.
     #[tracing::instrument(skip_all, name = "verify_proof")]
     pub fn verify_proof(
@@ -53,17 +144,39 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         self.verify_proof_halt(vm_proof, transcript, true)
     }
 
+    /// Verify a trace from start to halt, with the sub-protocols `auxiliaries` proven
+    /// alongside. The proof must contain exactly the protocols of `auxiliaries`.
+    pub fn verify_proof_with_auxiliary(
+        &self,
+        vm_proof: ZKVMProof<E, PCS>,
+        auxiliaries: &[&dyn AuxiliaryVerifier<E>],
+        transcript: Transcript<E>,
+    ) -> Result<bool, ZKVMError> {
+        self.verify_proof_halt_with_auxiliary(vm_proof, auxiliaries, transcript, true)
+    }
+
     /// Verify a trace from start to optional halt.
     pub fn verify_proof_halt(
         &self,
         vm_proof: ZKVMProof<E, PCS>,
         transcript: Transcript<E>,
         does_halt: bool,
+    ) -> Result<bool, ZKVMError> {
+        self.verify_proof_halt_with_auxiliary(vm_proof, &[], transcript, does_halt)
+    }
+
+    /// Verify a trace from start to optional halt, with the sub-protocols `auxiliaries`.
+    pub fn verify_proof_halt_with_auxiliary(
+        &self,
+        vm_proof: ZKVMProof<E, PCS>,
+        auxiliaries: &[&dyn AuxiliaryVerifier<E>],
+        transcript: Transcript<E>,
+        does_halt: bool,
     ) -> Result<bool, ZKVMError> {
         // require ecall/halt proof to exist, depending whether we expect a halt.
         let num_instances = vm_proof
             .opcode_proofs
-            .get(&HaltInstruction::<E>::name())
+            .get(HALT_CIRCUIT_NAME)
             .map(|(_, p)| p.num_instances)
             .unwrap_or(0);
         if num_instances != (does_halt as usize) {
@@ -72,15 +185,35 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 num_instances, does_halt as usize
             )));
         }
+        verify_halt_public_values(&vm_proof.raw_pi, does_halt)?;
+        verify_cycle_public_values(&vm_proof.raw_pi)?;
 
-        self.verify_proof_validity(vm_proof, transcript)
+        self.verify_proof_validity(vm_proof, auxiliaries, transcript)
     }
 
     fn verify_proof_validity(
         &self,
         vm_proof: ZKVMProof<E, PCS>,
+        auxiliaries: &[&dyn AuxiliaryVerifier<E>],
         mut transcript: Transcript<E>,
     ) -> Result<bool, ZKVMError> {
+        // the auxiliary protocols are sorted by name, like their proofs
+        let auxiliaries = auxiliaries
+            .iter()
+            .sorted_by_key(|aux| aux.name())
+            .collect_vec();
+        if !auxiliaries
+            .iter()
+            .map(|aux| aux.name())
+            .eq(vm_proof.aux_proofs.keys().cloned())
+        {
+            return Err(ZKVMError::VerifyError(format!(
+                "auxiliary protocols {:?} do not match the expected ones {:?}",
+                vm_proof.aux_proofs.keys().collect_vec(),
+                auxiliaries.iter().map(|aux| aux.name()).collect_vec()
+            )));
+        }
+
         // main invariant between opcode circuits and table circuits
         let mut prod_r = E::ONE;
         let mut prod_w = E::ONE;
@@ -127,6 +260,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             PCS::write_commitment(&proof.wits_commit, &mut transcript)
                 .map_err(ZKVMError::PCSError)?;
         }
+        for (name, (_, proof)) in vm_proof.aux_proofs.iter() {
+            tracing::debug!("read auxiliary {}'s commit", name);
+            PCS::write_commitment(&proof.wits_commit, &mut transcript)
+                .map_err(ZKVMError::PCSError)?;
+        }
 
         // alpha, beta
         let challenges = [
@@ -138,21 +276,32 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         let dummy_table_item = challenges[0];
         let mut dummy_table_item_multiplicity = 0;
         let point_eval = PointAndEval::default();
-        let mut transcripts = transcript.fork(self.vk.circuit_vks.len());
-
-        for (name, (i, opcode_proof)) in vm_proof.opcode_proofs {
-            let transcript = &mut transcripts[i];
+        let num_circuits = self.vk.circuit_vks.len();
+        let mut transcripts = transcript.clone().fork(num_circuits + auxiliaries.len());
+        // evaluation claims of all the commitments in circuit order, checked by a single
+        // opening at the end
+        let mut pcs_claims = vec![];
+        // the bound of the width of packed witnesses
+        let max_num_witin = self
+            .vk
+            .circuit_vks
+            .values()
+            .map(|vk| vk.get_cs().num_witin as usize)
+            .max()
+            .unwrap_or_default();
+
+        for (name, (i, opcode_proof)) in &vm_proof.opcode_proofs {
+            let transcript = &mut transcripts[*i];
 
             let circuit_vk = self
                 .vk
                 .circuit_vks
-                .get(&name)
+                .get(name)
                 .ok_or(ZKVMError::VKNotFound(name.clone()))?;
-            let _rand_point = self.verify_opcode_proof(
-                &name,
-                &self.vk.vp,
+            let input_opening_point = self.verify_opcode_proof(
+                name,
                 circuit_vk,
-                &opcode_proof,
+                opcode_proof,
                 pi_evals,
                 transcript,
                 NUM_FANIN,
@@ -160,6 +309,34 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 &challenges,
             )?;
             tracing::info!("verified proof for opcode {}", name);
+            // A witness packed with others is opened in its slot. Another circuit opening the
+            // same slot would only prove the same witness twice, as with two commitments.
+            let (input_opening_point, wits_in_evals) = match &opcode_proof.packed_slot {
+                Some(slot) => {
+                    let is_valid = slot.slot_num_vars >= input_opening_point.len()
+                        && slot.slot_num_vars <= MAX_NUM_VARIABLES
+                        && slot.num_selector_vars <= MAX_NUM_VARIABLES - slot.slot_num_vars
+                        && slot.index < 1 << slot.num_selector_vars
+                        && (opcode_proof.wits_in_evals.len()..=max_num_witin)
+                            .contains(&slot.num_polys);
+                    if !is_valid {
+                        return Err(ZKVMError::VerifyError(format!(
+                            "invalid packed slot of opcode {name}"
+                        )));
+                    }
+                    (
+                        slot.open_point(&input_opening_point),
+                        slot.open_evals(&opcode_proof.wits_in_evals),
+                    )
+                }
+                None => (input_opening_point, opcode_proof.wits_in_evals.clone()),
+            };
+            pcs_claims.push((
+                *i,
+                &opcode_proof.wits_commit,
+                input_opening_point,
+                wits_in_evals,
+            ));
 
             // getting the number of dummy padding item that we used in this opcode circuit
             let num_lks = circuit_vk.get_cs().lk_expressions.len();
@@ -173,25 +350,30 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             prod_r *= opcode_proof.record_r_out_evals.iter().product::<E>();
             prod_w *= opcode_proof.record_w_out_evals.iter().product::<E>();
 
-            logup_sum +=
-                opcode_proof.lk_p1_out_eval * opcode_proof.lk_q1_out_eval.invert().unwrap();
-            logup_sum +=
-                opcode_proof.lk_p2_out_eval * opcode_proof.lk_q2_out_eval.invert().unwrap();
+            logup_sum += logup_fraction(
+                name,
+                opcode_proof.lk_p1_out_eval,
+                opcode_proof.lk_q1_out_eval,
+            )?;
+            logup_sum += logup_fraction(
+                name,
+                opcode_proof.lk_p2_out_eval,
+                opcode_proof.lk_q2_out_eval,
+            )?;
         }
 
-        for (name, (i, table_proof)) in vm_proof.table_proofs {
-            let transcript = &mut transcripts[i];
+        for (name, (i, table_proof)) in &vm_proof.table_proofs {
+            let transcript = &mut transcripts[*i];
 
             let circuit_vk = self
                 .vk
                 .circuit_vks
-                .get(&name)
+                .get(name)
                 .ok_or(ZKVMError::VKNotFound(name.clone()))?;
-            let _rand_point = self.verify_table_proof(
-                &name,
-                &self.vk.vp,
+            let input_opening_point = self.verify_table_proof(
+                name,
                 circuit_vk,
-                &table_proof,
+                table_proof,
                 &vm_proof.raw_pi,
                 &vm_proof.pi_evals,
                 transcript,
@@ -200,13 +382,26 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 &challenges,
             )?;
             tracing::info!("verified proof for table {}", name);
+            pcs_claims.push((
+                *i,
+                &table_proof.wits_commit,
+                input_opening_point.clone(),
+                table_proof.wits_in_evals.clone(),
+            ));
+            if let Some(fixed_commit) = &circuit_vk.fixed_commit {
+                pcs_claims.push((
+                    *i,
+                    fixed_commit,
+                    input_opening_point,
+                    table_proof.fixed_in_evals.clone(),
+                ));
+            }
 
-            logup_sum = table_proof
-                .lk_out_evals
-                .iter()
-                .fold(logup_sum, |acc, [p1, p2, q1, q2]| {
-                    acc - *p1 * q1.invert().unwrap() - *p2 * q2.invert().unwrap()
-                });
+            // the multiplicities are witnesses of the table, committed with it: the tower binds
+            // these fractions to them, and the logup sum balances them against the lookups
+            for [p1, p2, q1, q2] in &table_proof.lk_out_evals {
+                logup_sum -= logup_fraction(name, *p1, *q1)? + logup_fraction(name, *p2, *q2)?;
+            }
 
             prod_w *= table_proof.w_out_evals.iter().flatten().product::<E>();
             prod_r *= table_proof.r_out_evals.iter().flatten().product::<E>();
@@ -214,6 +409,30 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         logup_sum -=
             E::from(dummy_table_item_multiplicity as u64) * dummy_table_item.invert().unwrap();
 
+        for (j, (aux, (name, (i, aux_proof)))) in
+            auxiliaries.iter().zip_eq(&vm_proof.aux_proofs).enumerate()
+        {
+            if *i != num_circuits + j {
+                return Err(ZKVMError::VerifyError(format!(
+                    "auxiliary {name} has index {i}, expected {}",
+                    num_circuits + j
+                )));
+            }
+            let input_opening_point = aux.verify(
+                &aux_proof.proof,
+                &aux_proof.wits_in_evals,
+                &challenges,
+                &mut transcripts[*i],
+            )?;
+            tracing::info!("verified auxiliary proof {}", name);
+            pcs_claims.push((
+                *i,
+                &aux_proof.wits_commit,
+                input_opening_point,
+                aux_proof.wits_in_evals.clone(),
+            ));
+        }
+
         // check logup relation across all proofs
         if logup_sum != E::ZERO {
             return Err(ZKVMError::VerifyError(format!(
@@ -243,15 +462,41 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             return Err(ZKVMError::VerifyError("prod_r != prod_w".into()));
         }
 
+        // bind the opening to the transcripts of all the circuits and auxiliary protocols
+        for transcript_i in transcripts.iter_mut() {
+            transcript.append_field_element_ext(&transcript_i.read_challenge().elements);
+        }
+        let Some(pcs_opening_proof) = &vm_proof.pcs_opening_proof else {
+            return Err(ZKVMError::VerifyError(
+                "pcs opening proof shouldn't be none".into(),
+            ));
+        };
+        // the prover opens the witness commitment of each circuit followed by its fixed one,
+        // then the ones of the auxiliary protocols
+        pcs_claims.sort_by_key(|(i, ..)| *i);
+        let (pcs_comms, pcs_points, pcs_evals): (Vec<_>, Vec<_>, Vec<_>) = pcs_claims
+            .into_iter()
+            .map(|(_, comm, point, evals)| (comm, point, evals))
+            .multiunzip();
+        PCS::multi_batch_verify(
+            &self.vk.vp,
+            &pcs_comms,
+            &pcs_points,
+            &pcs_evals,
+            pcs_opening_proof,
+            &mut transcript,
+        )
+        .map_err(ZKVMError::PCSError)?;
+
         Ok(true)
     }
 
-    /// verify proof and return input opening point
+    /// verify proof and return input opening point, where the witness evaluations
+    /// are left to be checked by the caller
     #[allow(clippy::too_many_arguments)]
     pub fn verify_opcode_proof(
         &self,
         name: &str,
-        vp: &PCS::VerifierParam,
         circuit_vk: &VerifyingKey<E, PCS>,
         proof: &ZKVMOpcodeProof<E, PCS>,
         pi: &[E],
@@ -478,20 +723,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         }
 
         tracing::debug!(
-            "[opcode {}] verify opening proof for {} polys at {:?}",
+            "[opcode {}] {} polys to open at {:?}",
             name,
             proof.wits_in_evals.len(),
             input_opening_point
         );
-        PCS::simple_batch_verify(
-            vp,
-            &proof.wits_commit,
-            &input_opening_point,
-            &proof.wits_in_evals,
-            &proof.wits_opening_proof,
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
 
         Ok(input_opening_point)
     }
@@ -500,7 +736,6 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
     pub fn verify_table_proof(
         &self,
         name: &str,
-        vp: &PCS::VerifierParam,
         circuit_vk: &VerifyingKey<E, PCS>,
         proof: &ZKVMTableProof<E, PCS>,
         raw_pi: &[Vec<E::BaseField>],
@@ -751,26 +986,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             );
         }
 
-        // do optional check of fixed_commitment openings by vk
-        if circuit_vk.fixed_commit.is_some() {
-            let Some(fixed_opening_proof) = &proof.fixed_opening_proof else {
-                return Err(ZKVMError::VerifyError(
-                    "fixed openning proof shoudn't be none".into(),
-                ));
-            };
-            PCS::simple_batch_verify(
-                vp,
-                circuit_vk.fixed_commit.as_ref().unwrap(),
-                &input_opening_point,
-                &proof.fixed_in_evals,
-                fixed_opening_proof,
-                transcript,
-            )
-            .map_err(ZKVMError::PCSError)?;
-        }
-
         tracing::debug!(
-            "[table {}] verified opening proof for {} fixed polys at {:?}: values = {:?}, commit = {:?}",
+            "[table {}] {} fixed polys to open at {:?}: values = {:?}, commit = {:?}",
             name,
             proof.fixed_in_evals.len(),
             input_opening_point,
@@ -778,17 +995,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             circuit_vk.fixed_commit,
         );
 
-        PCS::simple_batch_verify(
-            vp,
-            &proof.wits_commit,
-            &input_opening_point,
-            &proof.wits_in_evals,
-            &proof.wits_opening_proof,
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
         tracing::debug!(
-            "[table {}] verified opening proof for {} polys at {:?}: values = {:?}, commit = {:?}",
+            "[table {}] {} polys to open at {:?}: values = {:?}, commit = {:?}",
             name,
             proof.wits_in_evals.len(),
             input_opening_point,
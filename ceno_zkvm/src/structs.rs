@@ -1,8 +1,10 @@
 use crate::{
+    chip_handler::range_inference::count_inferred_range_checks,
     circuit_builder::{CircuitBuilder, ConstraintSystem},
     error::ZKVMError,
     expression::Expression,
     instructions::Instruction,
+    scheme::commit_stream::CircuitKind,
     state::StateCircuit,
     tables::TableCircuit,
     witness::{LkMultiplicity, RowMajorMatrix},
@@ -14,10 +16,13 @@ use mpcs::PolynomialCommitmentScheme;
 use multilinear_extensions::{
     mle::DenseMultilinearExtension, virtual_poly_v2::ArcMultilinearExtension,
 };
-use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
-use strum_macros::EnumIter;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+};
 use sumcheck::structs::IOPProverMessage;
+use transcript::Transcript;
 
 pub struct TowerProver;
 
@@ -43,9 +48,12 @@ pub struct TowerProverSpec<'a, E: ExtensionField> {
 pub type WitnessId = u16;
 pub type ChallengeId = u16;
 
-#[derive(Copy, Clone, Debug, EnumIter, PartialEq, Eq, Hash)]
+/// The id of a lookup table defined outside of this crate, see [`ROMType::Custom`].
+pub type CustomTableId = u8;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ROMType {
-    U5 = 0,      // 2^5 = 32
+    U5,          // 2^5 = 32
     U8,          // 2^8 = 256
     U14,         // 2^14 = 16,384
     U16,         // 2^16 = 65,536
@@ -54,10 +62,73 @@ pub enum ROMType {
     Xor,         // a ^ b where a, b are bytes
     Ltu,         // a <(usign) b where a, b are bytes and the result is 0/1.
     Pow,         // a ** b where a is 2 and b is 5-bit value
+    Clz,         // the leading zeros of a byte a, and whether a is 0
+    Popcount,    // the number of ones of a byte a
+    PackedU5,    // 3 values of 5 bits, 2^15
+    PackedU8,    // 2 bytes, 2^16
     Instruction, // Decoded instruction from the fixed program.
+    /// A table registered by a downstream circuit, see `tables::CustomTable`.
+    Custom(CustomTableId),
+}
+
+impl ROMType {
+    /// The number of slots for custom tables; their ids are below it.
+    pub const MAX_CUSTOM_TABLES: usize = 8;
+    /// The number of ROM types, including all the custom slots.
+    pub const COUNT: usize = ROMType::Custom(0).id() + Self::MAX_CUSTOM_TABLES;
+
+    /// The index of the table, which tags its records in the lookup argument and indexes the
+    /// lookup multiplicities.
+    pub const fn id(self) -> usize {
+        match self {
+            ROMType::U5 => 0,
+            ROMType::U8 => 1,
+            ROMType::U14 => 2,
+            ROMType::U16 => 3,
+            ROMType::And => 4,
+            ROMType::Or => 5,
+            ROMType::Xor => 6,
+            ROMType::Ltu => 7,
+            ROMType::Pow => 8,
+            ROMType::Clz => 9,
+            ROMType::Popcount => 10,
+            ROMType::PackedU5 => 11,
+            ROMType::PackedU8 => 12,
+            ROMType::Instruction => 13,
+            ROMType::Custom(id) => {
+                assert!(
+                    (id as usize) < Self::MAX_CUSTOM_TABLES,
+                    "custom table id out of range"
+                );
+                ROMType::Instruction.id() + 1 + id as usize
+            }
+        }
+    }
+
+    /// All the ROM types, in the order of their ids.
+    pub fn iter() -> impl Iterator<Item = ROMType> {
+        [
+            ROMType::U5,
+            ROMType::U8,
+            ROMType::U14,
+            ROMType::U16,
+            ROMType::And,
+            ROMType::Or,
+            ROMType::Xor,
+            ROMType::Ltu,
+            ROMType::Pow,
+            ROMType::Clz,
+            ROMType::Popcount,
+            ROMType::PackedU5,
+            ROMType::PackedU8,
+            ROMType::Instruction,
+        ]
+        .into_iter()
+        .chain((0..Self::MAX_CUSTOM_TABLES as CustomTableId).map(ROMType::Custom))
+    }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RAMType {
     GlobalState,
     Register,
@@ -113,7 +184,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ProvingKey<E, PCS> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct VerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub(crate) cs: ConstraintSystem<E>,
     pub fixed_commit: Option<PCS::Commitment>,
@@ -175,6 +250,8 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
         let mut circuit_builder =
             CircuitBuilder::<E>::new_with_params(&mut cs, self.params.clone());
         let config = OC::construct_circuit(&mut circuit_builder).unwrap();
+        #[cfg(feature = "auto_range_checks")]
+        circuit_builder.insert_range_checks().unwrap();
         assert!(self.circuit_css.insert(OC::name(), cs).is_none());
 
         config
@@ -263,8 +340,9 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
         assert!(self.combined_lk_mlt.is_none());
 
         let cs = cs.get_cs(&OC::name()).unwrap();
-        let (witness, logup_multiplicity) =
+        let (witness, mut logup_multiplicity) =
             OC::assign_instances(config, cs.num_witin as usize, records)?;
+        count_inferred_range_checks(cs, &witness, &mut logup_multiplicity);
         assert!(self.witnesses_opcodes.insert(OC::name(), witness).is_none());
         assert!(!self.witnesses_tables.contains_key(&OC::name()));
         assert!(
@@ -276,6 +354,12 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
         Ok(())
     }
 
+    /// Take the lookup multiplicities counted when assigning the opcode circuit `name`, before
+    /// they are merged by [`Self::finalize_lk_multiplicities`].
+    pub(crate) fn take_lk_mlt(&mut self, name: &str) -> Option<LkMultiplicity> {
+        self.lk_mlts.remove(name)
+    }
+
     // merge the multiplicities in each opcode circuit into one
     pub fn finalize_lk_multiplicities(&mut self) {
         assert!(self.combined_lk_mlt.is_none());
@@ -327,6 +411,20 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
     pub fn into_iter_sorted(self) -> impl Iterator<Item = (String, RowMajorMatrix<E::BaseField>)> {
         chain(self.witnesses_opcodes, self.witnesses_tables)
     }
+
+    /// Take the witnesses assigned so far, to commit to them, and keep the lookup multiplicities.
+    pub(crate) fn take_witnesses(
+        &mut self,
+    ) -> impl Iterator<Item = (CircuitKind, String, RowMajorMatrix<E::BaseField>)> {
+        chain(
+            mem::take(&mut self.witnesses_opcodes)
+                .into_iter()
+                .map(|(name, witness)| (CircuitKind::Opcode, name, witness)),
+            mem::take(&mut self.witnesses_tables)
+                .into_iter()
+                .map(|(name, witness)| (CircuitKind::Table, name, witness)),
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -369,7 +467,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PC
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub vp: PCS::VerifierParam,
     // vk for opcode and table circuits
@@ -378,3 +480,32 @@ pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E
     pub initial_global_state_expr: Expression<E>,
     pub finalize_global_state_expr: Expression<E>,
 }
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifyingKey<E, PCS> {
+    /// A digest of the circuits, their constraint systems and fixed commitments, to tell
+    /// verifying keys apart.
+    pub fn digest(&self) -> Result<E, ZKVMError> {
+        vk_digest(&self.circuit_vks)
+    }
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
+    /// The digest of the verifying key, see [`ZKVMVerifyingKey::digest`].
+    pub fn digest(&self) -> Result<E, ZKVMError> {
+        vk_digest(self.circuit_pks.iter().map(|(name, pk)| (name, &pk.vk)))
+    }
+}
+
+fn vk_digest<'a, E: ExtensionField + 'a, PCS: PolynomialCommitmentScheme<E> + 'a>(
+    circuit_vks: impl IntoIterator<Item = (&'a String, &'a VerifyingKey<E, PCS>)>,
+) -> Result<E, ZKVMError> {
+    let mut transcript = Transcript::<E>::new(b"zkvm_verifying_key");
+    for (name, vk) in circuit_vks {
+        transcript.append_message(name.as_bytes());
+        transcript.append_message(&serde_json::to_vec(&vk.cs).unwrap());
+        if let Some(fixed_commit) = &vk.fixed_commit {
+            PCS::write_commitment(fixed_commit, &mut transcript).map_err(ZKVMError::PCSError)?;
+        }
+    }
+    Ok(transcript.read_challenge().elements)
+}
@@ -0,0 +1,170 @@
+//! Lookup tables defined outside of this crate, e.g. a bit-reversal table.
+//!
+//! A custom table takes one of the `ROMType::Custom` slots. Its records are tagged by the slot
+//! like the built-in tables, so they go through the same lookup argument, and circuits that
+//! look it up register it in their constraint system for the mock prover.
+
+use std::{collections::HashMap, marker::PhantomData, mem::MaybeUninit};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, Fixed, ToExpr, WitIn},
+    scheme::constants::MIN_PAR_SIZE,
+    set_fixed_val, set_val,
+    structs::{CustomTableId, ROMType},
+    tables::TableCircuit,
+    witness::RowMajorMatrix,
+};
+
+/// Use this trait as parameter to CustomTableCircuit.
+pub trait CustomTable {
+    /// The slot of the table, unique among the custom tables of a VM.
+    const ID: CustomTableId;
+    const NAME: &'static str;
+    /// The number of values in a row.
+    const NUM_COLUMNS: usize;
+
+    /// The content of the table, rows of `NUM_COLUMNS` values.
+    fn content() -> Vec<Vec<u64>>;
+
+    /// The key of a row in the lookup multiplicities, unique within the table.
+    fn pack(row: &[u64]) -> u64;
+
+    fn rom_type() -> ROMType {
+        ROMType::Custom(Self::ID)
+    }
+
+    fn spec() -> CustomTableSpec {
+        CustomTableSpec {
+            name: Self::NAME,
+            content: Self::content,
+            pack: Self::pack,
+        }
+    }
+}
+
+/// What a constraint system keeps of a custom table that it looks up.
+#[derive(Clone, Copy, Debug)]
+pub struct CustomTableSpec {
+    pub name: &'static str,
+    pub content: fn() -> Vec<Vec<u64>>,
+    pub pack: fn(&[u64]) -> u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomTableConfig {
+    columns: Vec<Fixed>,
+    mlt: WitIn,
+}
+
+impl CustomTableConfig {
+    fn construct_circuit<E: ExtensionField, T: CustomTable>(
+        cb: &mut CircuitBuilder<E>,
+    ) -> Result<Self, ZKVMError> {
+        let columns = (0..T::NUM_COLUMNS)
+            .map(|i| cb.create_fixed(|| format!("column_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mlt = cb.create_witin(|| "mlt");
+
+        let record_exprs = columns.iter().map(|f| Expression::Fixed(*f)).collect_vec();
+        cb.register_custom_table::<T>();
+        cb.lk_table_record(
+            || "record",
+            T::content().len(),
+            T::rom_type(),
+            record_exprs,
+            mlt.expr(),
+        )?;
+
+        Ok(Self { columns, mlt })
+    }
+
+    fn generate_fixed_traces<F: SmallField>(
+        &self,
+        num_fixed: usize,
+        content: Vec<Vec<u64>>,
+    ) -> RowMajorMatrix<F> {
+        let mut fixed = RowMajorMatrix::<F>::new(content.len(), num_fixed);
+
+        fixed
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(content.into_par_iter())
+            .for_each(|(row, values)| {
+                assert_eq!(values.len(), self.columns.len(), "wrong row width");
+                for (col, val) in self.columns.iter().zip(values.iter()) {
+                    set_fixed_val!(row, *col, F::from(*val));
+                }
+            });
+
+        fixed
+    }
+
+    fn assign_instances<F: SmallField>(
+        &self,
+        num_witin: usize,
+        multiplicity: &HashMap<u64, usize>,
+        keys: Vec<u64>,
+    ) -> Result<RowMajorMatrix<F>, ZKVMError> {
+        let mut witness = RowMajorMatrix::<F>::new(keys.len(), num_witin);
+
+        witness
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(keys.into_par_iter())
+            .for_each(|(row, key)| {
+                let mlt = multiplicity.get(&key).copied().unwrap_or(0);
+                set_val!(row, self.mlt, F::from(mlt as u64));
+            });
+
+        Ok(witness)
+    }
+}
+
+pub struct CustomTableCircuit<E, T>(PhantomData<(E, T)>);
+
+impl<E: ExtensionField, T: CustomTable> TableCircuit<E> for CustomTableCircuit<E, T> {
+    type TableConfig = CustomTableConfig;
+    type FixedInput = ();
+    type WitnessInput = ();
+
+    fn name() -> String {
+        format!("CUSTOM_{}", T::NAME)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<CustomTableConfig, ZKVMError> {
+        cb.namespace(
+            || Self::name(),
+            |cb| CustomTableConfig::construct_circuit::<E, T>(cb),
+        )
+    }
+
+    fn generate_fixed_traces(
+        config: &CustomTableConfig,
+        num_fixed: usize,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        let mut table = config.generate_fixed_traces(num_fixed, T::content());
+        Self::padding_zero(&mut table, num_fixed).expect("padding error");
+        table
+    }
+
+    fn assign_instances(
+        config: &Self::TableConfig,
+        num_witin: usize,
+        multiplicity: &[HashMap<u64, usize>],
+        _input: &(),
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let multiplicity = &multiplicity[T::rom_type().id()];
+        let keys = T::content().iter().map(|row| T::pack(row)).collect_vec();
+        let mut table = config.assign_instances(num_witin, multiplicity, keys)?;
+        Self::padding_zero(&mut table, num_witin)?;
+        Ok(table)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     circuit_builder::CircuitBuilder, error::ZKVMError, scheme::constants::MIN_PAR_SIZE,
-    witness::RowMajorMatrix,
+    structs::ROMType, witness::RowMajorMatrix,
 };
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
@@ -13,11 +13,44 @@ mod ops;
 pub use ops::*;
 
 mod program;
-pub use program::{InsnRecord, ProgramTableCircuit};
+pub use program::{InsnRecord, ProgramFetches, ProgramTableCircuit, program_table_content};
 
 mod ram;
 pub use ram::*;
 
+mod custom;
+pub use custom::{CustomTable, CustomTableCircuit, CustomTableConfig, CustomTableSpec};
+
+/// The rows of the built-in table `rom_type`, each the values of a record after its type. They
+/// are fixed by the table alone, so the provers and the fixed commitments of the keys agree on
+/// them. `None` for the tables whose content depends on the program, see
+/// [`program_table_content`], or on the circuit.
+pub fn rom_table_content(rom_type: ROMType) -> Option<Vec<Vec<u64>>> {
+    fn range<T: RangeTable>() -> Vec<Vec<u64>> {
+        T::content().into_iter().map(|v| vec![v]).collect()
+    }
+    fn ops<T: OpsTable>() -> Vec<Vec<u64>> {
+        T::content().into_iter().map(Vec::from).collect()
+    }
+
+    Some(match rom_type {
+        ROMType::U5 => range::<U5Table>(),
+        ROMType::U8 => range::<U8Table>(),
+        ROMType::U14 => range::<U14Table>(),
+        ROMType::U16 => range::<U16Table>(),
+        ROMType::PackedU5 => PackedU5Table::content(),
+        ROMType::PackedU8 => PackedU8Table::content(),
+        ROMType::And => ops::<AndTable>(),
+        ROMType::Or => ops::<OrTable>(),
+        ROMType::Xor => ops::<XorTable>(),
+        ROMType::Ltu => ops::<LtuTable>(),
+        ROMType::Pow => ops::<PowTable>(),
+        ROMType::Clz => ops::<ClzTable>(),
+        ROMType::Popcount => ops::<PopcountTable>(),
+        ROMType::Instruction | ROMType::Custom(_) => return None,
+    })
+}
+
 pub trait TableCircuit<E: ExtensionField> {
     type TableConfig: Send + Sync;
     type FixedInput: Send + Sync + ?Sized;
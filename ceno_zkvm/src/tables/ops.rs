@@ -91,3 +91,49 @@ impl OpsTable for PowTable {
     }
 }
 pub type PowTableCircuit<E> = OpsTableCircuit<E, PowTable>;
+
+pub struct ClzTable;
+impl OpsTable for ClzTable {
+    const ROM_TYPE: ROMType = ROMType::Clz;
+    fn len() -> usize {
+        1 << 8
+    }
+
+    fn content() -> Vec<[u64; 3]> {
+        (0..Self::len() as u64)
+            .map(|a| [a, (a as u8).leading_zeros() as u64, (a == 0) as u64])
+            .collect()
+    }
+
+    fn pack(a: u64, _b: u64) -> u64 {
+        a
+    }
+
+    fn unpack(i: u64) -> (u64, u64) {
+        (i, 0)
+    }
+}
+pub type ClzTableCircuit<E> = OpsTableCircuit<E, ClzTable>;
+
+pub struct PopcountTable;
+impl OpsTable for PopcountTable {
+    const ROM_TYPE: ROMType = ROMType::Popcount;
+    fn len() -> usize {
+        1 << 8
+    }
+
+    fn content() -> Vec<[u64; 3]> {
+        (0..Self::len() as u64)
+            .map(|a| [a, a.count_ones() as u64, 0])
+            .collect()
+    }
+
+    fn pack(a: u64, _b: u64) -> u64 {
+        a
+    }
+
+    fn unpack(i: u64) -> (u64, u64) {
+        (i, 0)
+    }
+}
+pub type PopcountTableCircuit<E> = OpsTableCircuit<E, PopcountTable>;
@@ -5,7 +5,10 @@ use super::ops_impl::OpTableConfig;
 use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
-    circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    structs::ROMType,
+    tables::{TableCircuit, rom_table_content},
     witness::RowMajorMatrix,
 };
 use ff_ext::ExtensionField;
@@ -51,7 +54,10 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
         num_fixed: usize,
         _input: &(),
     ) -> RowMajorMatrix<E::BaseField> {
-        let mut table = config.generate_fixed_traces(num_fixed, OP::content());
+        let mut table = config.generate_fixed_traces(
+            num_fixed,
+            rom_table_content(OP::ROM_TYPE).expect("ops tables have a fixed content"),
+        );
         Self::padding_zero(&mut table, num_fixed).expect("padding error");
         table
     }
@@ -62,7 +68,7 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
         multiplicity: &[HashMap<u64, usize>],
         _input: &(),
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
-        let multiplicity = &multiplicity[OP::ROM_TYPE as usize];
+        let multiplicity = &multiplicity[OP::ROM_TYPE.id()];
         let mut table = config.assign_instances(num_witin, multiplicity, OP::len())?;
         Self::padding_zero(&mut table, num_witin)?;
         Ok(table)
@@ -45,7 +45,7 @@ impl OpTableConfig {
     pub fn generate_fixed_traces<F: SmallField>(
         &self,
         num_fixed: usize,
-        content: Vec<[u64; 3]>,
+        content: Vec<Vec<u64>>,
     ) -> RowMajorMatrix<F> {
         let mut fixed = RowMajorMatrix::<F>::new(content.len(), num_fixed);
 
@@ -54,7 +54,7 @@ impl OpTableConfig {
             .with_min_len(MIN_PAR_SIZE)
             .zip(content.into_par_iter())
             .for_each(|(row, abc)| {
-                for (col, val) in self.abc.iter().zip(abc.iter()) {
+                for (col, val) in self.abc.iter().zip_eq(abc.iter()) {
                     set_fixed_val!(row, *col, F::from(*val));
                 }
             });
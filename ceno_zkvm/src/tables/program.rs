@@ -16,8 +16,10 @@ use ceno_emul::{
 };
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
-use itertools::Itertools;
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use itertools::{Itertools, enumerate};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 
 #[macro_export]
 macro_rules! declare_program {
@@ -95,6 +97,55 @@ impl InsnRecord<()> {
     }
 }
 
+/// How many times each instruction of a program is fetched, in the order of the rows of the
+/// program table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramFetches(Vec<usize>);
+
+impl ProgramFetches {
+    /// Collect the fetches counted by `LkMultiplicity::fetch`, keyed by pc. A pc outside of the
+    /// program is an error, as no row of the table could match its lookup.
+    pub fn new(program: &Program, fetches: &HashMap<u64, usize>) -> Result<Self, ZKVMError> {
+        let mut counts = vec![0; program.instructions.len()];
+        for (&pc, &count) in fetches {
+            let offset = pc.wrapping_sub(program.base_address as u64) as usize;
+            let count_ref = (offset % WORD_SIZE == 0)
+                .then(|| counts.get_mut(offset / WORD_SIZE))
+                .flatten()
+                .ok_or_else(|| {
+                    ZKVMError::InvalidWitness(format!("fetch outside of the program: {pc:#x}"))
+                })?;
+            *count_ref = count;
+        }
+        Ok(ProgramFetches(counts))
+    }
+
+    /// The fetches of the instruction at `pc`.
+    pub fn get(&self, program: &Program, pc: u32) -> usize {
+        let i = (pc.wrapping_sub(program.base_address) as usize) / WORD_SIZE;
+        self.0.get(i).copied().unwrap_or(0)
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+/// The rows of the program table, one record per instruction of `program`, in the canonical
+/// form of the field `F`. The fixed trace and the mock prover both read the table from here.
+pub fn program_table_content<F: SmallField>(program: &Program) -> Vec<Vec<u64>> {
+    enumerate(&program.instructions)
+        .map(|(i, &insn)| {
+            let pc = program.base_address + (i * PC_STEP_SIZE) as u32;
+            InsnRecord::<F>::from_decoded(pc, &DecodedInstruction::new(insn))
+                .as_slice()
+                .iter()
+                .map(|v| v.to_canonical_u64())
+                .collect()
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub struct ProgramTableConfig {
     /// The fixed table of instruction records.
@@ -154,8 +205,8 @@ impl<E: ExtensionField> TableCircuit<E> for ProgramTableCircuit<E> {
         num_fixed: usize,
         program: &Self::FixedInput,
     ) -> RowMajorMatrix<E::BaseField> {
-        let num_instructions = program.instructions.len();
-        let pc_base = program.base_address;
+        let content = program_table_content::<E::BaseField>(program);
+        let num_instructions = content.len();
         assert!(num_instructions <= config.program_size);
 
         let mut fixed = RowMajorMatrix::<E::BaseField>::new(config.program_size, num_fixed);
@@ -163,15 +214,11 @@ impl<E: ExtensionField> TableCircuit<E> for ProgramTableCircuit<E> {
         fixed
             .par_iter_mut()
             .with_min_len(MIN_PAR_SIZE)
-            .zip((0..num_instructions).into_par_iter())
-            .for_each(|(row, i)| {
-                let pc = pc_base + (i * PC_STEP_SIZE) as u32;
-                let insn = DecodedInstruction::new(program.instructions[i]);
-                let values = InsnRecord::from_decoded(pc, &insn);
-
+            .zip(content.into_par_iter())
+            .for_each(|(row, values)| {
                 // Copy all the fields.
-                for (col, val) in config.record.as_slice().iter().zip_eq(values.as_slice()) {
-                    set_fixed_val!(row, *col, *val);
+                for (col, val) in config.record.as_slice().iter().zip_eq(values) {
+                    set_fixed_val!(row, *col, E::BaseField::from(val));
                 }
             });
 
@@ -187,20 +234,14 @@ impl<E: ExtensionField> TableCircuit<E> for ProgramTableCircuit<E> {
         multiplicity: &[HashMap<u64, usize>],
         program: &Program,
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
-        let multiplicity = &multiplicity[ROMType::Instruction as usize];
-
-        let mut prog_mlt = vec![0_usize; program.instructions.len()];
-        for (pc, mlt) in multiplicity {
-            let i = (*pc as usize - program.base_address as usize) / WORD_SIZE;
-            prog_mlt[i] = *mlt;
-        }
+        let fetches = ProgramFetches::new(program, &multiplicity[ROMType::Instruction.id()])?;
 
         let mut witness = RowMajorMatrix::<E::BaseField>::new(config.program_size, num_witin);
         witness
             .par_iter_mut()
             .with_min_len(MIN_PAR_SIZE)
-            .zip(prog_mlt.into_par_iter())
-            .for_each(|(row, mlt)| {
+            .zip(fetches.as_slice().par_iter())
+            .for_each(|(row, &mlt)| {
                 set_val!(row, config.mlt, E::BaseField::from(mlt as u64));
             });
 
@@ -233,3 +274,174 @@ fn test_decode_imm() {
         assert_eq!(imm, expected);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_program_fetches() {
+    use crate::witness::LkMultiplicity;
+
+    let base = 0x2000;
+    let program = Program::new(base, base, vec![0x13; 4], Default::default());
+    let mut lkm = LkMultiplicity::default();
+    for pc in [base, base + 4, base + 4, base + 12] {
+        lkm.fetch(pc);
+    }
+    let multiplicity = lkm.into_finalize_result();
+    let fetches = ProgramFetches::new(&program, &multiplicity[ROMType::Instruction.id()]).unwrap();
+    assert_eq!(fetches.as_slice(), &[1, 2, 0, 1]);
+    assert_eq!(fetches.get(&program, base + 4), 2);
+    assert_eq!(fetches.get(&program, base + 16), 0);
+
+    // A fetch outside of the program, or between instructions, has no row.
+    for pc in [base - 4, base + 16, base + 2] {
+        let fetches = HashMap::from([(pc as u64, 1)]);
+        assert!(matches!(
+            ProgramFetches::new(&program, &fetches),
+            Err(ZKVMError::InvalidWitness(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::Instruction,
+        scheme::{PublicValues, mock_prover::MockProver},
+        structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+        witness::LkMultiplicity,
+    };
+    use ceno_emul::{ByteAddr, StepRecord, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+
+    /// A circuit that only fetches the instruction of each step.
+    struct FetchInstruction<E>(PhantomData<E>);
+
+    impl<E: ExtensionField> Instruction<E> for FetchInstruction<E> {
+        type InstructionConfig = InsnRecord<WitIn>;
+
+        fn name() -> String {
+            "FETCH".into()
+        }
+
+        fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<InsnRecord<WitIn>, ZKVMError> {
+            let record = InsnRecord([(); 6].map(|_| cb.create_witin(|| "record")));
+            cb.lk_fetch(&InsnRecord(record.0.map(|w| w.expr())))?;
+            Ok(record)
+        }
+
+        fn assign_instance(
+            config: &InsnRecord<WitIn>,
+            instance: &mut [MaybeUninit<E::BaseField>],
+            lk_multiplicity: &mut LkMultiplicity,
+            step: &StepRecord,
+        ) -> Result<(), ZKVMError> {
+            let pc = step.pc().before.0;
+            let values = InsnRecord::<E::BaseField>::from_decoded(
+                pc,
+                &DecodedInstruction::new(step.insn_code()),
+            );
+            for (witin, &value) in config.as_slice().iter().zip_eq(values.as_slice()) {
+                set_val!(instance, witin, value);
+            }
+            lk_multiplicity.fetch(pc);
+            Ok(())
+        }
+    }
+
+    /// The program table, with one more fetch of the first instruction than the steps made.
+    struct OverCountedProgramTable<E>(PhantomData<E>);
+
+    impl<E: ExtensionField> TableCircuit<E> for OverCountedProgramTable<E> {
+        type TableConfig = ProgramTableConfig;
+        type FixedInput = Program;
+        type WitnessInput = Program;
+
+        fn name() -> String {
+            ProgramTableCircuit::<E>::name()
+        }
+
+        fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<ProgramTableConfig, ZKVMError> {
+            ProgramTableCircuit::<E>::construct_circuit(cb)
+        }
+
+        fn generate_fixed_traces(
+            config: &ProgramTableConfig,
+            num_fixed: usize,
+            program: &Program,
+        ) -> RowMajorMatrix<E::BaseField> {
+            ProgramTableCircuit::<E>::generate_fixed_traces(config, num_fixed, program)
+        }
+
+        fn assign_instances(
+            config: &ProgramTableConfig,
+            num_witin: usize,
+            multiplicity: &[HashMap<u64, usize>],
+            program: &Program,
+        ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+            let mut multiplicity = multiplicity.to_vec();
+            *multiplicity[ROMType::Instruction.id()]
+                .entry(program.base_address as u64)
+                .or_default() += 1;
+            ProgramTableCircuit::<E>::assign_instances(config, num_witin, &multiplicity, program)
+        }
+    }
+
+    fn check_fetches<TC>(program: &Program)
+    where
+        TC: TableCircuit<GoldilocksExt2, TableConfig = ProgramTableConfig, WitnessInput = Program>,
+    {
+        type E = GoldilocksExt2;
+
+        let mut cs = ZKVMConstraintSystem::default();
+        let fetch_config = cs.register_opcode_circuit::<FetchInstruction<E>>();
+        let prog_config = cs.register_table_circuit::<ProgramTableCircuit<E>>();
+
+        let mut fixed = ZKVMFixedTraces::default();
+        fixed.register_opcode_circuit::<FetchInstruction<E>>(&cs);
+        fixed.register_table_circuit::<ProgramTableCircuit<E>>(&cs, &prog_config, program);
+
+        // Fetch the second instruction twice, and the third one not at all.
+        let steps = [0, 1, 1, 3]
+            .into_iter()
+            .enumerate()
+            .map(|(cycle, i)| {
+                let pc = ByteAddr(program.base_address + (i * PC_STEP_SIZE) as u32);
+                StepRecord::new_nop_instruction(cycle as u64, pc, program.instructions[i])
+            })
+            .collect();
+        let mut witnesses = ZKVMWitnesses::default();
+        witnesses
+            .assign_opcode_circuit::<FetchInstruction<E>>(&cs, &fetch_config, steps)
+            .unwrap();
+        witnesses.finalize_lk_multiplicities();
+        witnesses
+            .assign_table_circuit::<TC>(&cs, &prog_config, program)
+            .unwrap();
+
+        let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0], vec![]);
+        MockProver::assert_satisfied_full(cs, fixed, &witnesses, &pi);
+    }
+
+    fn fetch_program() -> Program {
+        let base = 0x2000;
+        let instructions = vec![
+            encode_rv32(ADDI, 1, 0, 2, 7),
+            encode_rv32(ADD, 1, 2, 3, 0),
+            encode_rv32(ADDI, 1, 0, 2, -5i32 as u32),
+            encode_rv32(SUB, 3, 1, 4, 0),
+        ];
+        Program::new(base, base, instructions, Default::default())
+    }
+
+    #[test]
+    fn test_program_table_fetches() {
+        check_fetches::<ProgramTableCircuit<GoldilocksExt2>>(&fetch_program());
+    }
+
+    #[test]
+    #[should_panic(expected = "found 1 lookup errors")]
+    fn test_program_table_wrong_fetch_count() {
+        check_fetches::<OverCountedProgramTable<GoldilocksExt2>>(&fetch_program());
+    }
+}
@@ -1,5 +1,7 @@
 use ceno_emul::{Addr, VMState};
-use ram_circuit::{DynVolatileRamCircuit, NonVolatileRamCircuit, PubIORamCircuit};
+use ram_circuit::{
+    DynVolatileRamCircuit, NonVolatileRamCircuit, PubFinalRamCircuit, PubIORamCircuit,
+};
 
 use crate::{
     instructions::riscv::constants::UINT_LIMBS,
@@ -54,7 +56,7 @@ impl DynVolatileRamTable for PrivateMemTable {
 }
 pub type PrivateMemCircuit<E> = DynVolatileRamCircuit<E, PrivateMemTable>;
 
-/// RegTable, fix size without offset
+/// RegTable, fix size without offset. The final registers are public.
 #[derive(Clone)]
 pub struct RegTable;
 
@@ -72,7 +74,7 @@ impl NonVolatileTable for RegTable {
     }
 }
 
-pub type RegTableCircuit<E> = NonVolatileRamCircuit<E, RegTable>;
+pub type RegTableCircuit<E> = PubFinalRamCircuit<E, RegTable>;
 
 #[derive(Clone)]
 pub struct StaticMemTable;
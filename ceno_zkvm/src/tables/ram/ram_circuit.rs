@@ -11,7 +11,9 @@ use crate::{
     witness::RowMajorMatrix,
 };
 
-use super::ram_impl::{DynVolatileRamTableConfig, NonVolatileTableConfig, PubIOTableConfig};
+use super::ram_impl::{
+    DynVolatileRamTableConfig, NonVolatileTableConfig, PubFinalTableConfig, PubIOTableConfig,
+};
 
 #[derive(Clone, Debug)]
 pub struct MemInitRecord {
@@ -100,6 +102,51 @@ impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCirc
     }
 }
 
+/// PubFinalRamCircuit initializes and finalizes registers
+/// - at fixed addresses,
+/// - with fixed initial content,
+/// - with final content from the public input of proofs, so that the verifier learns the
+///   state that the program left, such as its return values.
+pub struct PubFinalRamCircuit<E, R>(PhantomData<(E, R)>);
+
+impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCircuit<E>
+    for PubFinalRamCircuit<E, NVRAM>
+{
+    type TableConfig = PubFinalTableConfig<NVRAM>;
+    type FixedInput = [MemInitRecord];
+    type WitnessInput = [MemFinalRecord];
+
+    fn name() -> String {
+        format!("RAM_{:?}_{}", NVRAM::RAM_TYPE, NVRAM::name())
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::TableConfig, ZKVMError> {
+        cb.namespace(
+            || Self::name(),
+            |cb| Self::TableConfig::construct_circuit(cb),
+        )
+    }
+
+    fn generate_fixed_traces(
+        config: &Self::TableConfig,
+        num_fixed: usize,
+        init_v: &Self::FixedInput,
+    ) -> RowMajorMatrix<E::BaseField> {
+        // assume returned table is well-formed include padding
+        config.gen_init_state(num_fixed, init_v)
+    }
+
+    fn assign_instances(
+        config: &Self::TableConfig,
+        num_witin: usize,
+        _multiplicity: &[HashMap<u64, usize>],
+        final_v: &Self::WitnessInput,
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        // assume returned table is well-formed include padding
+        config.assign_instances(num_witin, final_v)
+    }
+}
+
 /// PubIORamCircuit initializes and finalizes memory
 /// - at fixed addresses,
 /// - with content from the public input of proofs.
@@ -10,7 +10,7 @@ use crate::{
     circuit_builder::{CircuitBuilder, DynamicAddr, SetTableAddrType, SetTableSpec},
     error::ZKVMError,
     expression::{Expression, Fixed, ToExpr, WitIn},
-    instructions::riscv::constants::{LIMB_BITS, LIMB_MASK},
+    instructions::riscv::constants::{LIMB_BITS, LIMB_MASK, UINT_LIMBS},
     scheme::constants::MIN_PAR_SIZE,
     set_fixed_val, set_val,
     structs::ProgramParams,
@@ -172,6 +172,127 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
     }
 }
 
+/// define a non-volatile memory with init value, and final value set by instance
+#[derive(Clone, Debug)]
+pub struct PubFinalTableConfig<NVRAM: NonVolatileTable + Send + Sync + Clone> {
+    init_v: Vec<Fixed>,
+    addr: Fixed,
+
+    final_cycle: WitIn,
+
+    phantom: PhantomData<NVRAM>,
+    params: ProgramParams,
+}
+
+impl<NVRAM: NonVolatileTable + Send + Sync + Clone> PubFinalTableConfig<NVRAM> {
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+    ) -> Result<Self, ZKVMError> {
+        assert!(NVRAM::WRITABLE);
+        assert_eq!(NVRAM::V_LIMBS, UINT_LIMBS);
+        let init_v = (0..NVRAM::V_LIMBS)
+            .map(|i| cb.create_fixed(|| format!("init_v_limb_{i}")))
+            .collect::<Result<Vec<Fixed>, ZKVMError>>()?;
+        let addr = cb.create_fixed(|| "addr")?;
+        let final_v = cb.query_final_regs()?;
+
+        let final_cycle = cb.create_witin(|| "final_cycle");
+
+        let init_table = [
+            vec![(NVRAM::RAM_TYPE as usize).into()],
+            vec![Expression::Fixed(addr)],
+            init_v.iter().map(|v| v.expr()).collect_vec(),
+            vec![Expression::ZERO], // Initial cycle.
+        ]
+        .concat();
+
+        let final_table = [
+            // a v t
+            vec![(NVRAM::RAM_TYPE as usize).into()],
+            vec![Expression::Fixed(addr)],
+            final_v.iter().map(|v| v.expr()).collect_vec(),
+            vec![final_cycle.expr()],
+        ]
+        .concat();
+
+        cb.w_table_record(
+            || "init_table",
+            NVRAM::RAM_TYPE,
+            SetTableSpec {
+                addr_type: SetTableAddrType::FixedAddr,
+                len: NVRAM::len(&cb.params),
+            },
+            init_table,
+        )?;
+        cb.r_table_record(
+            || "final_table",
+            NVRAM::RAM_TYPE,
+            SetTableSpec {
+                addr_type: SetTableAddrType::FixedAddr,
+                len: NVRAM::len(&cb.params),
+            },
+            final_table,
+        )?;
+
+        Ok(Self {
+            init_v,
+            addr,
+            final_cycle,
+            phantom: PhantomData,
+            params: cb.params.clone(),
+        })
+    }
+
+    pub fn gen_init_state<F: SmallField>(
+        &self,
+        num_fixed: usize,
+        init_mem: &[MemInitRecord],
+    ) -> RowMajorMatrix<F> {
+        assert!(
+            NVRAM::len(&self.params).is_power_of_two(),
+            "{} len {} must be a power of 2",
+            NVRAM::name(),
+            NVRAM::len(&self.params)
+        );
+
+        let mut init_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.params), num_fixed);
+        assert_eq!(init_table.num_padding_instances(), 0);
+
+        init_table
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip_eq(init_mem.into_par_iter())
+            .for_each(|(row, rec)| {
+                self.init_v.iter().enumerate().for_each(|(l, limb)| {
+                    let val = (rec.value >> (l * LIMB_BITS)) & LIMB_MASK;
+                    set_fixed_val!(row, limb, (val as u64).into());
+                });
+                set_fixed_val!(row, self.addr, (rec.addr as u64).into());
+            });
+
+        init_table
+    }
+
+    /// The final values are not assigned, they must be given in the public input.
+    pub fn assign_instances<F: SmallField>(
+        &self,
+        num_witness: usize,
+        final_mem: &[MemFinalRecord],
+    ) -> Result<RowMajorMatrix<F>, ZKVMError> {
+        let mut final_table = RowMajorMatrix::<F>::new(NVRAM::len(&self.params), num_witness);
+
+        final_table
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip_eq(final_mem.into_par_iter())
+            .for_each(|(row, rec)| {
+                set_val!(row, self.final_cycle, rec.cycle);
+            });
+
+        Ok(final_table)
+    }
+}
+
 /// define public io
 /// init value set by instance
 #[derive(Clone, Debug)]
@@ -3,7 +3,7 @@
 mod range_impl;
 
 mod range_circuit;
-pub use range_circuit::{RangeTable, RangeTableCircuit};
+pub use range_circuit::{PackedRangeTable, PackedRangeTableCircuit, RangeTable, RangeTableCircuit};
 
 use crate::structs::ROMType;
 
@@ -42,3 +42,19 @@ impl RangeTable for U16Table {
     }
 }
 pub type U16TableCircuit<E> = RangeTableCircuit<E, U16Table>;
+
+pub struct PackedU5Table;
+impl PackedRangeTable for PackedU5Table {
+    const ROM_TYPE: ROMType = ROMType::PackedU5;
+    const BITS: usize = 5;
+    const PACK: usize = 3;
+}
+pub type PackedU5TableCircuit<E> = PackedRangeTableCircuit<E, PackedU5Table>;
+
+pub struct PackedU8Table;
+impl PackedRangeTable for PackedU8Table {
+    const ROM_TYPE: ROMType = ROMType::PackedU8;
+    const BITS: usize = 8;
+    const PACK: usize = 2;
+}
+pub type PackedU8TableCircuit<E> = PackedRangeTableCircuit<E, PackedU8Table>;
@@ -1,11 +1,14 @@
 //! Range tables as circuits with trait TableCircuit.
 
-use super::range_impl::RangeTableConfig;
+use super::range_impl::{PackedRangeTableConfig, RangeTableConfig};
 
 use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
-    circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    structs::ROMType,
+    tables::{TableCircuit, rom_table_content},
     witness::RowMajorMatrix,
 };
 use ff_ext::ExtensionField;
@@ -44,7 +47,10 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
         num_fixed: usize,
         _input: &(),
     ) -> RowMajorMatrix<E::BaseField> {
-        let mut table = config.generate_fixed_traces(num_fixed, RANGE::content());
+        let mut table = config.generate_fixed_traces(
+            num_fixed,
+            rom_table_content(RANGE::ROM_TYPE).expect("range tables have a fixed content"),
+        );
         Self::padding_zero(&mut table, num_fixed).expect("padding error");
         table
     }
@@ -55,7 +61,92 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
         multiplicity: &[HashMap<u64, usize>],
         _input: &(),
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
-        let multiplicity = &multiplicity[RANGE::ROM_TYPE as usize];
+        let multiplicity = &multiplicity[RANGE::ROM_TYPE.id()];
+        let mut table = config.assign_instances(num_witin, multiplicity, RANGE::len())?;
+        Self::padding_zero(&mut table, num_witin).expect("padding error");
+        Ok(table)
+    }
+}
+
+/// Use this trait as parameter to PackedRangeTableCircuit. A record of the table is `PACK` values
+/// of `BITS` bits, so that one lookup range-checks them all, and the row of a record is the
+/// values packed in little-endian order.
+pub trait PackedRangeTable {
+    const ROM_TYPE: ROMType;
+    const BITS: usize;
+    const PACK: usize;
+
+    fn len() -> usize {
+        1 << (Self::BITS * Self::PACK)
+    }
+
+    /// The row of the record `values`, of at most `PACK` values; the missing ones are zero.
+    fn pack(values: &[u64]) -> u64 {
+        assert!(values.len() <= Self::PACK);
+        values
+            .iter()
+            .rev()
+            .fold(0, |packed, value| (packed << Self::BITS) | value)
+    }
+
+    fn unpack(row: u64) -> Vec<u64> {
+        (0..Self::PACK)
+            .map(|i| (row >> (i * Self::BITS)) & ((1 << Self::BITS) - 1))
+            .collect()
+    }
+
+    fn content() -> Vec<Vec<u64>> {
+        (0..Self::len() as u64).map(Self::unpack).collect()
+    }
+}
+
+pub struct PackedRangeTableCircuit<E, R>(PhantomData<(E, R)>);
+
+impl<E: ExtensionField, RANGE: PackedRangeTable> TableCircuit<E>
+    for PackedRangeTableCircuit<E, RANGE>
+{
+    type TableConfig = PackedRangeTableConfig;
+    type FixedInput = ();
+    type WitnessInput = ();
+
+    fn name() -> String {
+        format!("RANGE_{:?}", RANGE::ROM_TYPE)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<PackedRangeTableConfig, ZKVMError> {
+        cb.namespace(
+            || Self::name(),
+            |cb| {
+                PackedRangeTableConfig::construct_circuit(
+                    cb,
+                    RANGE::ROM_TYPE,
+                    RANGE::len(),
+                    RANGE::PACK,
+                )
+            },
+        )
+    }
+
+    fn generate_fixed_traces(
+        config: &PackedRangeTableConfig,
+        num_fixed: usize,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        let mut table = config.generate_fixed_traces(
+            num_fixed,
+            rom_table_content(RANGE::ROM_TYPE).expect("range tables have a fixed content"),
+        );
+        Self::padding_zero(&mut table, num_fixed).expect("padding error");
+        table
+    }
+
+    fn assign_instances(
+        config: &Self::TableConfig,
+        num_witin: usize,
+        multiplicity: &[HashMap<u64, usize>],
+        _input: &(),
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let multiplicity = &multiplicity[RANGE::ROM_TYPE.id()];
         let mut table = config.assign_instances(num_witin, multiplicity, RANGE::len())?;
         Self::padding_zero(&mut table, num_witin).expect("padding error");
         Ok(table)
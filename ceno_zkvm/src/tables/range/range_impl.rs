@@ -40,7 +40,7 @@ impl RangeTableConfig {
     pub fn generate_fixed_traces<F: SmallField>(
         &self,
         num_fixed: usize,
-        content: Vec<u64>,
+        content: Vec<Vec<u64>>,
     ) -> RowMajorMatrix<F> {
         let mut fixed = RowMajorMatrix::<F>::new(content.len(), num_fixed);
 
@@ -48,8 +48,8 @@ impl RangeTableConfig {
             .par_iter_mut()
             .with_min_len(MIN_PAR_SIZE)
             .zip(content.into_par_iter())
-            .for_each(|(row, i)| {
-                set_fixed_val!(row, self.fixed, F::from(i));
+            .for_each(|(row, values)| {
+                set_fixed_val!(row, self.fixed, F::from(values[0]));
             });
 
         fixed
@@ -61,21 +61,87 @@ impl RangeTableConfig {
         multiplicity: &HashMap<u64, usize>,
         length: usize,
     ) -> Result<RowMajorMatrix<F>, ZKVMError> {
-        let mut witness = RowMajorMatrix::<F>::new(length, num_witin);
+        assign_multiplicities(self.mlt, num_witin, multiplicity, length)
+    }
+}
+
+/// A range table whose records are several values, one per fixed column.
+#[derive(Clone, Debug)]
+pub struct PackedRangeTableConfig {
+    fixed: Vec<Fixed>,
+    mlt: WitIn,
+}
 
-        let mut mlts = vec![0; length];
-        for (idx, mlt) in multiplicity {
-            mlts[*idx as usize] = *mlt;
-        }
+impl PackedRangeTableConfig {
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        rom_type: ROMType,
+        table_len: usize,
+        num_values: usize,
+    ) -> Result<Self, ZKVMError> {
+        let fixed = (0..num_values)
+            .map(|i| cb.create_fixed(|| format!("fixed_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mlt = cb.create_witin(|| "mlt");
+
+        let record_exprs = fixed.iter().copied().map(Expression::Fixed).collect();
+
+        cb.lk_table_record(|| "record", table_len, rom_type, record_exprs, mlt.expr())?;
+
+        Ok(Self { fixed, mlt })
+    }
+
+    pub fn generate_fixed_traces<F: SmallField>(
+        &self,
+        num_fixed: usize,
+        content: Vec<Vec<u64>>,
+    ) -> RowMajorMatrix<F> {
+        let mut fixed = RowMajorMatrix::<F>::new(content.len(), num_fixed);
 
-        witness
+        fixed
             .par_iter_mut()
             .with_min_len(MIN_PAR_SIZE)
-            .zip(mlts.into_par_iter())
-            .for_each(|(row, mlt)| {
-                set_val!(row, self.mlt, F::from(mlt as u64));
+            .zip(content.into_par_iter())
+            .for_each(|(row, values)| {
+                for (&col, value) in self.fixed.iter().zip(values) {
+                    set_fixed_val!(row, col, F::from(value));
+                }
             });
 
-        Ok(witness)
+        fixed
     }
+
+    pub fn assign_instances<F: SmallField>(
+        &self,
+        num_witin: usize,
+        multiplicity: &HashMap<u64, usize>,
+        length: usize,
+    ) -> Result<RowMajorMatrix<F>, ZKVMError> {
+        assign_multiplicities(self.mlt, num_witin, multiplicity, length)
+    }
+}
+
+/// The multiplicity of each row of a range table, indexed by the value of the row.
+fn assign_multiplicities<F: SmallField>(
+    mlt: WitIn,
+    num_witin: usize,
+    multiplicity: &HashMap<u64, usize>,
+    length: usize,
+) -> Result<RowMajorMatrix<F>, ZKVMError> {
+    let mut witness = RowMajorMatrix::<F>::new(length, num_witin);
+
+    let mut mlts = vec![0; length];
+    for (idx, count) in multiplicity {
+        mlts[*idx as usize] = *count;
+    }
+
+    witness
+        .par_iter_mut()
+        .with_min_len(MIN_PAR_SIZE)
+        .zip(mlts.into_par_iter())
+        .for_each(|(row, count)| {
+            set_val!(row, mlt, F::from(count as u64));
+        });
+
+    Ok(witness)
 }
@@ -203,6 +203,19 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
         self.assign_carries_auxiliary(instance, lkm, &value.carries, value.max_carry_value)
     }
 
+    /// Assign `value`, split into limbs of `C` bits. In debug builds, it must fit in `M` bits.
+    pub fn assign_u64(&self, instance: &mut [MaybeUninit<E::BaseField>], value: u64) {
+        debug_assert!(
+            value.checked_shr(M as u32).unwrap_or(0) == 0,
+            "{value:#x} does not fit in {M} bits"
+        );
+        let limbs = (0..Self::NUM_LIMBS)
+            .map(|i| (value.checked_shr((i * C) as u32).unwrap_or(0) & ((1 << C) - 1)) as u16)
+            .collect_vec();
+        self.assign_limbs(instance, &limbs)
+    }
+
+    /// Assign the limbs, then zeros. In debug builds, each limb must fit in `C` bits.
     pub fn assign_limbs(&self, instance: &mut [MaybeUninit<E::BaseField>], limbs_values: &[u16]) {
         assert!(
             limbs_values.len() <= Self::NUM_LIMBS,
@@ -210,6 +223,10 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
             limbs_values.len(),
             Self::NUM_LIMBS
         );
+        debug_assert!(
+            limbs_values.iter().all(|&limb| (limb as u64) >> C == 0),
+            "limbs {limbs_values:?} do not fit in {C} bits"
+        );
         if let UintLimb::WitIn(wires) = &self.limbs {
             for (wire, limb) in wires.iter().zip(
                 limbs_values
@@ -779,6 +796,46 @@ impl<'a, T: Into<u64> + From<u32> + Copy + Default> Value<'a, T> {
 #[cfg(test)]
 mod tests {
 
+    mod assign {
+        use std::mem::MaybeUninit;
+
+        use goldilocks::{Goldilocks, GoldilocksExt2};
+        use itertools::Itertools;
+
+        use crate::{
+            circuit_builder::{CircuitBuilder, ConstraintSystem},
+            instructions::riscv::constants::UInt8,
+        };
+
+        #[test]
+        fn test_assign_u64() {
+            let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "assign");
+            let mut cb = CircuitBuilder::new(&mut cs);
+            let uint = UInt8::new_unchecked(|| "uint", &mut cb).unwrap();
+
+            let mut instance = vec![MaybeUninit::<Goldilocks>::uninit(); 4];
+            uint.assign_u64(&mut instance, 0x1234_5678);
+            let limbs = instance
+                .iter()
+                .map(|v| unsafe { v.assume_init() })
+                .collect_vec();
+            assert_eq!(limbs, [0x78, 0x56, 0x34, 0x12].map(Goldilocks::from));
+        }
+
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "do not fit in 8 bits")]
+        fn test_assign_limbs_out_of_range() {
+            let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "assign");
+            let mut cb = CircuitBuilder::new(&mut cs);
+            let uint = UInt8::new_unchecked(|| "uint", &mut cb).unwrap();
+
+            // 16-bit limbs into a uint of 8-bit limbs
+            let mut instance = vec![MaybeUninit::<Goldilocks>::uninit(); 4];
+            uint.assign_limbs(&mut instance, &[0x5678, 0x1234]);
+        }
+    }
+
     mod value {
         use crate::{Value, witness::LkMultiplicity};
         #[test]
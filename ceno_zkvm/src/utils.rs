@@ -7,6 +7,12 @@ use itertools::Itertools;
 use multilinear_extensions::util::max_usable_threads;
 use transcript::Transcript;
 
+/// The clock of the prover timings, which `std::time` does not provide in wasm.
+#[cfg(feature = "wasm")]
+pub use instant::Instant;
+#[cfg(not(feature = "wasm"))]
+pub use std::time::Instant;
+
 pub fn i64_to_base<F: SmallField>(x: i64) -> F {
     if x >= 0 {
         F::from(x as u64)
@@ -0,0 +1,107 @@
+//! The verifier side of the zkVM: what is needed to check a proof, apart from the prover.
+//!
+//! This module only gathers the verifier's entry points; the verifier itself is
+//! [`ZKVMVerifier`] from the `scheme` module, in the same crate as the prover. The whole crate
+//! builds for wasm32, where rayon runs its tasks on the calling thread, so the verifier can run
+//! in a browser. With the `wasm` feature, the `wasm` module exposes it to JavaScript.
+
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use transcript::Transcript;
+
+use crate::expression::Expression;
+pub use crate::{
+    error::ZKVMError,
+    scheme::{
+        PublicValues, ZKVMProof,
+        constants::{HALT_CIRCUIT_NAME, TRANSCRIPT_LABEL},
+        verifier::ZKVMVerifier,
+    },
+    structs::{VerifyingKey, ZKVMVerifyingKey},
+};
+
+/// Verify a proof of a trace, given the verifying key and the proof as JSON. Whether the trace
+/// must halt is `does_halt`, as in [`ZKVMVerifier::verify_proof_halt`].
+pub fn verify_proof_json<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
+    vk_json: &str,
+    proof_json: &str,
+    does_halt: bool,
+) -> Result<bool, ZKVMError> {
+    let parse_error = |e: serde_json::Error| ZKVMError::ParseError(e.to_string());
+    let vk: ZKVMVerifyingKey<E, PCS> = serde_json::from_str(vk_json).map_err(parse_error)?;
+    let proof: ZKVMProof<E, PCS> = serde_json::from_str(proof_json).map_err(parse_error)?;
+    ZKVMVerifier::new(vk).verify_proof_halt(proof, Transcript::new(TRANSCRIPT_LABEL), does_halt)
+}
+
+pub fn eval_by_expr<E: ExtensionField>(
+    witnesses: &[E],
+    challenges: &[E],
+    expr: &Expression<E>,
+) -> E {
+    eval_by_expr_with_fixed(&[], witnesses, challenges, expr)
+}
+
+pub fn eval_by_expr_with_fixed<E: ExtensionField>(
+    fixed: &[E],
+    witnesses: &[E],
+    challenges: &[E],
+    expr: &Expression<E>,
+) -> E {
+    expr.evaluate::<E>(
+        &|f| fixed[f.0],
+        &|witness_id| witnesses[witness_id as usize],
+        &|scalar| scalar.into(),
+        &|challenge_id, pow, scalar, offset| {
+            // TODO cache challenge power to be acquired once for each power
+            let challenge = challenges[challenge_id as usize];
+            challenge.pow([pow as u64]) * scalar + offset
+        },
+        &|a, b| a + b,
+        &|a, b| a * b,
+        &|x, a, b| a * x + b,
+    )
+}
+
+pub fn eval_by_expr_with_instance<E: ExtensionField>(
+    fixed: &[E],
+    witnesses: &[E],
+    instance: &[E],
+    challenges: &[E],
+    expr: &Expression<E>,
+) -> E {
+    expr.evaluate_with_instance::<E>(
+        &|f| fixed[f.0],
+        &|witness_id| witnesses[witness_id as usize],
+        &|i| instance[i.0],
+        &|scalar| scalar.into(),
+        &|challenge_id, pow, scalar, offset| {
+            // TODO cache challenge power to be acquired once for each power
+            let challenge = challenges[challenge_id as usize];
+            challenge.pow([pow as u64]) * scalar + offset
+        },
+        &|a, b| a + b,
+        &|a, b| a * b,
+        &|x, a, b| a * x + b,
+    )
+}
+
+/// The entry points for JavaScript, with the field and commitment scheme of the prover.
+#[cfg(feature = "wasm")]
+pub mod wasm {
+    use goldilocks::GoldilocksExt2;
+    use mpcs::{Basefold, BasefoldRSParams};
+    use wasm_bindgen::prelude::*;
+
+    /// Verify a proof made with Basefold and Reed-Solomon codes, see
+    /// [`super::verify_proof_json`]. An invalid proof is an error, rather than `false`.
+    #[wasm_bindgen(js_name = verifyProof)]
+    pub fn verify_proof(vk_json: &str, proof_json: &str, does_halt: bool) -> Result<(), JsError> {
+        match super::verify_proof_json::<GoldilocksExt2, Basefold<GoldilocksExt2, BasefoldRSParams>>(
+            vk_json, proof_json, does_halt,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(JsError::new("the proof is invalid")),
+            Err(e) => Err(JsError::new(&format!("{e:?}"))),
+        }
+    }
+}
@@ -22,7 +22,11 @@ pub struct VirtualPolynomials<'a, E: ExtensionField> {
 }
 
 impl<'a, E: ExtensionField> VirtualPolynomials<'a, E> {
+    /// `num_threads` is an upper bound: it is rounded down to a power of two and clamped so
+    /// that every thread keeps at least one variable. Polynomials too small for that, including
+    /// constant ones, are proved by a single thread. Use `num_threads()` for the actual count.
     pub fn new(num_threads: usize, max_num_variables: usize) -> Self {
+        let num_threads = Self::clamp_num_threads(num_threads, max_num_variables);
         VirtualPolynomials {
             num_threads,
             polys: (0..num_threads)
@@ -32,6 +36,18 @@ impl<'a, E: ExtensionField> VirtualPolynomials<'a, E> {
         }
     }
 
+    fn clamp_num_threads(num_threads: usize, max_num_variables: usize) -> usize {
+        if num_threads <= 1 || max_num_variables <= 1 {
+            return 1;
+        }
+        let log2_num_threads = num_threads.ilog2() as usize;
+        1 << log2_num_threads.min(max_num_variables - 1)
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
     fn get_range_polys_by_thread_id(
         &self,
         thread_id: usize,
@@ -40,6 +56,12 @@ impl<'a, E: ExtensionField> VirtualPolynomials<'a, E> {
         polys
             .into_iter()
             .map(|poly| {
+                assert!(
+                    1 << poly.num_vars() >= self.num_threads,
+                    "a polynomial of {} variables cannot be split across {} threads",
+                    poly.num_vars(),
+                    self.num_threads,
+                );
                 let range_poly: ArcMultilinearExtension<E> =
                     Arc::new(poly.get_ranged_mle(self.num_threads, thread_id));
                 range_poly
@@ -227,6 +249,22 @@ mod tests {
         assert!(virtual_polys.degree() == 3);
     }
 
+    #[test]
+    fn test_clamp_num_threads() {
+        for (num_threads, max_num_variables, expected) in [
+            (1, 0, 1),
+            (8, 0, 1),
+            (8, 1, 1),
+            (8, 2, 2),
+            (8, 10, 8),
+            (6, 10, 4),
+        ] {
+            let virtual_polys = VirtualPolynomials::<E>::new(num_threads, max_num_variables);
+            assert_eq!(virtual_polys.num_threads(), expected);
+            assert_eq!(virtual_polys.get_batched_polys().len(), expected);
+        }
+    }
+
     #[test]
     fn test_sumcheck_different_degree() {
         let max_num_vars = 3;
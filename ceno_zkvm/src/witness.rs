@@ -1,4 +1,6 @@
 use ff::Field;
+use ff_ext::ExtensionField;
+use itertools::Itertools;
 use std::{
     array,
     cell::RefCell,
@@ -14,14 +16,21 @@ use multilinear_extensions::{
     util::create_uninit_vec,
 };
 use rayon::{
-    iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
+    iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+        IntoParallelRefMutIterator, ParallelIterator,
+    },
     slice::ParallelSliceMut,
 };
 use thread_local::ThreadLocal;
 
 use crate::{
+    circuit_builder::ConstraintSystem,
     structs::ROMType,
-    tables::{AndTable, LtuTable, OpsTable, OrTable, PowTable, XorTable},
+    tables::{
+        AndTable, ClzTable, CustomTable, LtuTable, OpsTable, OrTable, PackedRangeTable,
+        PackedU5Table, PackedU8Table, PopcountTable, PowTable, XorTable,
+    },
     utils::next_pow2_instance_padding,
 };
 
@@ -32,6 +41,19 @@ macro_rules! set_val {
     };
 }
 
+/// Like [`set_val!`] for each witness of `$fields` and value of `$vals`, which must have the same
+/// length.
+#[macro_export]
+macro_rules! set_vals {
+    ($ins:ident, $fields:expr, $vals:expr) => {
+        for (field, val) in
+            ::itertools::Itertools::zip_eq(::std::iter::IntoIterator::into_iter($fields), $vals)
+        {
+            $crate::set_val!($ins, field, val);
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! set_fixed_val {
     ($ins:ident, $field:expr, $val:expr) => {
@@ -39,6 +61,21 @@ macro_rules! set_fixed_val {
     };
 }
 
+/// Written over every 8 bytes of fresh cells in debug builds, so that cells which are never
+/// assigned can be found before `MaybeUninit` is assumed initialized. It is above the
+/// Goldilocks modulus, so an assigned canonical element never matches it, unlike all ones,
+/// which is also `u64::MAX` and an unreduced `2^32 - 2`.
+const POISON: u64 = 0xffff_ffff_0bad_c0de;
+
+/// The bytes of a poisoned cell of type `T`.
+fn poison_bytes<T>() -> impl Iterator<Item = u8> {
+    POISON
+        .to_le_bytes()
+        .into_iter()
+        .cycle()
+        .take(mem::size_of::<T>())
+}
+
 #[derive(Clone)]
 pub struct RowMajorMatrix<T: Sized + Sync + Clone + Send + Copy> {
     // represent 2D in 1D linear memory and avoid double indirection by Vec<Vec<T>> to improve performance
@@ -51,13 +88,71 @@ impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
     pub fn new(num_rows: usize, num_col: usize) -> Self {
         let num_total_rows = next_pow2_instance_padding(num_rows);
         let num_padding_rows = num_total_rows - num_rows;
+        let mut values = create_uninit_vec(num_total_rows * num_col);
+        if cfg!(debug_assertions) {
+            let poison = poison_bytes::<T>().collect_vec();
+            values.par_iter_mut().for_each(|v| unsafe {
+                std::ptr::copy_nonoverlapping(
+                    poison.as_ptr(),
+                    v.as_mut_ptr() as *mut u8,
+                    poison.len(),
+                )
+            });
+        }
         RowMajorMatrix {
-            values: create_uninit_vec(num_total_rows * num_col),
+            values,
             num_padding_rows,
             num_col,
         }
     }
 
+    /// (instance, column) of every non-padding cell that was never written. Cells are only
+    /// poisoned in debug builds, so this is always empty in release builds.
+    pub fn unassigned_cells(&self) -> Vec<(usize, usize)> {
+        if !cfg!(debug_assertions) {
+            return vec![];
+        }
+        self.values[..self.num_instances() * self.num_col]
+            .par_iter()
+            .enumerate()
+            .filter(|(_, v)| {
+                // Safety: every byte was initialized by the poisoning in `new`
+                let bytes = unsafe {
+                    std::slice::from_raw_parts(v.as_ptr() as *const u8, mem::size_of::<T>())
+                };
+                bytes.iter().copied().eq(poison_bytes::<T>())
+            })
+            .map(|(i, _)| (i / self.num_col, i % self.num_col))
+            .collect()
+    }
+
+    /// Panic with the namespace, column and instance of the first unassigned witness cells.
+    /// Call before the matrix is converted, since reading an unassigned cell is UB.
+    pub fn assert_fully_assigned<E: ExtensionField>(&self, cs: &ConstraintSystem<E>) {
+        let holes = self.unassigned_cells();
+        if holes.is_empty() {
+            return;
+        }
+        let report = holes
+            .iter()
+            .take(10)
+            .map(|(instance, col)| {
+                format!(
+                    "  witness {} (column {col}) on instance {instance}",
+                    cs.witin_namespace_map
+                        .get(*col)
+                        .map(String::as_str)
+                        .unwrap_or("<unnamed>")
+                )
+            })
+            .join("\n");
+        panic!(
+            "{} unassigned witness cells in {}:\n{report}",
+            holes.len(),
+            cs.ns.get_namespaces().join("/")
+        );
+    }
+
     pub fn num_instances(&self) -> usize {
         self.values.len() / self.num_col - self.num_padding_rows
     }
@@ -96,6 +191,46 @@ impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
             .par_chunks_mut(batch_size * self.num_col)
     }
 
+    /// Zero-copy strided view of one column, padding rows included.
+    pub fn iter_col(&self, col: usize) -> impl Iterator<Item = &MaybeUninit<T>> {
+        assert!(col < self.num_col);
+        self.values.iter().skip(col).step_by(self.num_col)
+    }
+
+    pub fn par_iter_col(&self, col: usize) -> impl IndexedParallelIterator<Item = &MaybeUninit<T>> {
+        assert!(col < self.num_col);
+        self.values.par_iter().skip(col).step_by(self.num_col)
+    }
+
+    /// Same result as [`Self::de_interleaving`], but transposes the matrix in place and then
+    /// peels the columns off its tail, so peak memory stays close to one copy of the trace
+    /// instead of two. Slower, since the transpose is a sequential permutation. Like
+    /// [`Self::de_interleaving`], it assumes that every cell, padding included, is assigned.
+    pub fn into_columns_in_place(self) -> Vec<Vec<T>> {
+        debug_assert!(self.unassigned_cells().is_empty());
+        let num_rows = self.values.len() / self.num_col;
+        let mut values = self.values;
+        // Only moves the cells, so it does not read the unassigned ones.
+        transpose_in_place(&mut values, num_rows, self.num_col);
+
+        let mut columns = Vec::with_capacity(self.num_col);
+        for col in (0..self.num_col).rev() {
+            let column = values.split_off(col * num_rows);
+            values.shrink_to_fit();
+            let mut column = mem::ManuallyDrop::new(column);
+            // Safety: MaybeUninit<T> has the layout of T, and the cells are assigned as above
+            columns.push(unsafe {
+                Vec::from_raw_parts(
+                    column.as_mut_ptr() as *mut T,
+                    column.len(),
+                    column.capacity(),
+                )
+            });
+        }
+        columns.reverse();
+        columns
+    }
+
     pub fn de_interleaving(mut self) -> Vec<Vec<T>> {
         (0..self.num_col)
             .map(|i| {
@@ -116,6 +251,41 @@ impl<F: Field> RowMajorMatrix<F> {
     ) -> Vec<DenseMultilinearExtension<E>> {
         self.de_interleaving().into_mles()
     }
+
+    /// Low-memory variant of [`Self::into_mles`], see [`Self::into_columns_in_place`].
+    pub fn into_mles_in_place<E: ff_ext::ExtensionField<BaseField = F>>(
+        self,
+    ) -> Vec<DenseMultilinearExtension<E>> {
+        self.into_columns_in_place().into_mles()
+    }
+}
+
+/// Transpose a row-major `rows x cols` matrix into column-major order by following the cycles
+/// of the permutation `r * cols + c -> c * rows + r`, using one bit of bookkeeping per cell.
+fn transpose_in_place<T>(values: &mut [T], rows: usize, cols: usize) {
+    let n = values.len();
+    assert_eq!(n, rows * cols);
+    if rows <= 1 || cols <= 1 {
+        return;
+    }
+    let mut visited = vec![0u64; n.div_ceil(64)];
+    // the first and last cells are fixed points
+    for start in 1..n - 1 {
+        if visited[start / 64] >> (start % 64) & 1 == 1 {
+            continue;
+        }
+        let mut cur = start;
+        loop {
+            let dest = (cur % cols) * rows + cur / cols;
+            visited[dest / 64] |= 1 << (dest % 64);
+            if dest == start {
+                break;
+            }
+            // the cell that belongs at `dest` is carried at `start`
+            values.swap(start, dest);
+            cur = dest;
+        }
+    }
 }
 
 impl<F: Field> Index<usize> for RowMajorMatrix<F> {
@@ -132,7 +302,7 @@ impl<F: Field> Index<usize> for RowMajorMatrix<F> {
 #[derive(Clone, Default, Debug)]
 #[allow(clippy::type_complexity)]
 pub struct LkMultiplicity {
-    multiplicity: Arc<ThreadLocal<RefCell<[HashMap<u64, usize>; mem::variant_count::<ROMType>()]>>>,
+    multiplicity: Arc<ThreadLocal<RefCell<[HashMap<u64, usize>; ROMType::COUNT]>>>,
 }
 
 impl LkMultiplicity {
@@ -148,6 +318,21 @@ impl LkMultiplicity {
         }
     }
 
+    /// assert within range, packed as by `CircuitBuilder::assert_ux_packed`
+    pub fn assert_ux_packed<const C: usize>(&mut self, values: &[u64]) {
+        match C {
+            8 => self.lookup_packed::<PackedU8Table>(values),
+            5 => self.lookup_packed::<PackedU5Table>(values),
+            _ => panic!("Unsupported packed bit range"),
+        }
+    }
+
+    fn lookup_packed<T: PackedRangeTable>(&mut self, values: &[u64]) {
+        for chunk in values.chunks(T::PACK) {
+            self.increment(T::ROM_TYPE, T::pack(chunk));
+        }
+    }
+
     /// Track a lookup into a logic table (AndTable, etc).
     pub fn logic_u8<OP: OpsTable>(&mut self, a: u64, b: u64) {
         self.increment(OP::ROM_TYPE, OP::pack(a, b));
@@ -177,41 +362,114 @@ impl LkMultiplicity {
         self.logic_u8::<PowTable>(2, v)
     }
 
+    /// lookup the leading zeros of the byte a
+    pub fn lookup_clz_byte(&mut self, a: u64) {
+        self.logic_u8::<ClzTable>(a, 0)
+    }
+
+    /// lookup the number of ones of the byte a
+    pub fn lookup_popcount_byte(&mut self, a: u64) {
+        self.logic_u8::<PopcountTable>(a, 0)
+    }
+
     /// Fetch instruction at pc
     pub fn fetch(&mut self, pc: u32) {
         self.increment(ROMType::Instruction, pc as u64);
     }
 
+    /// lookup `row` in the custom table `T`
+    pub fn lookup_custom<T: CustomTable>(&mut self, row: &[u64]) {
+        self.increment(T::rom_type(), T::pack(row))
+    }
+
+    /// Fold the counts of another, independently created, multiplicity into the calling
+    /// thread's shard. Clones of `self` share shards already and are skipped.
+    pub fn merge(&mut self, other: LkMultiplicity) {
+        if Arc::ptr_eq(&self.multiplicity, &other.multiplicity) {
+            return;
+        }
+        let multiplicity = self
+            .multiplicity
+            .get_or(|| RefCell::new(array::from_fn(|_| HashMap::new())));
+        let mut multiplicity = multiplicity.borrow_mut();
+        for shard in other.shards() {
+            multiplicity
+                .iter_mut()
+                .zip(shard)
+                .for_each(|(m1, m2)| merge_counts(m1, m2));
+        }
+    }
+
     /// merge result from multiple thread local to single result
-    pub fn into_finalize_result(self) -> [HashMap<u64, usize>; mem::variant_count::<ROMType>()] {
-        Arc::try_unwrap(self.multiplicity)
+    pub fn into_finalize_result(self) -> [HashMap<u64, usize>; ROMType::COUNT] {
+        self.par_finalize()
+    }
+
+    /// Merge the per-thread shards into a single result, one ROM type per rayon task.
+    pub fn par_finalize(self) -> [HashMap<u64, usize>; ROMType::COUNT] {
+        let mut per_rom_type: [Vec<HashMap<u64, usize>>; ROMType::COUNT] =
+            array::from_fn(|_| vec![]);
+        for shard in self.shards() {
+            per_rom_type
+                .iter_mut()
+                .zip(shard)
+                .for_each(|(maps, map)| maps.push(map));
+        }
+        per_rom_type
+            .into_par_iter()
+            .map(|mut maps| {
+                // merge into the largest map to move as few entries as possible
+                maps.sort_by_key(|map| std::cmp::Reverse(map.len()));
+                let mut maps = maps.into_iter();
+                let mut result = maps.next().unwrap_or_default();
+                maps.for_each(|map| merge_counts(&mut result, map));
+                result
+            })
+            .collect::<Vec<_>>()
+            .try_into()
             .unwrap()
+    }
+
+    /// Take ownership of all thread-local shards. Every clone of `self` must have been dropped.
+    fn shards(self) -> impl Iterator<Item = [HashMap<u64, usize>; ROMType::COUNT]> {
+        Arc::try_unwrap(self.multiplicity)
+            .expect("LkMultiplicity is still shared by a clone")
             .into_iter()
-            .fold(array::from_fn(|_| HashMap::new()), |mut x, y| {
-                x.iter_mut().zip(y.borrow().iter()).for_each(|(m1, m2)| {
-                    for (key, value) in m2 {
-                        *m1.entry(*key).or_insert(0) += value;
-                    }
-                });
-                x
-            })
+            .map(RefCell::into_inner)
     }
 
-    fn increment(&mut self, rom_type: ROMType, key: u64) {
+    pub(crate) fn increment(&mut self, rom_type: ROMType, key: u64) {
         let multiplicity = self
             .multiplicity
             .get_or(|| RefCell::new(array::from_fn(|_| HashMap::new())));
-        (*multiplicity.borrow_mut()[rom_type as usize]
+        (*multiplicity.borrow_mut()[rom_type.id()]
             .entry(key)
             .or_default()) += 1;
     }
 }
 
+fn merge_counts(m1: &mut HashMap<u64, usize>, m2: HashMap<u64, usize>) {
+    for (key, value) in m2 {
+        *m1.entry(key).or_insert(0) += value;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::thread;
+    use std::{mem::MaybeUninit, thread};
 
-    use crate::{structs::ROMType, witness::LkMultiplicity};
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use itertools::Itertools;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        set_val, set_vals,
+        structs::ROMType,
+        tables::{PackedRangeTable, PackedU5Table},
+        witness::{LkMultiplicity, RowMajorMatrix},
+    };
 
     #[test]
     fn test_lk_multiplicity_threads() {
@@ -227,6 +485,133 @@ mod tests {
         }
         let res = lkm.into_finalize_result();
         // check multiplicity counts of assert_byte
-        assert_eq!(res[ROMType::U8 as usize][&8], thread_count);
+        assert_eq!(res[ROMType::U8.id()][&8], thread_count);
+    }
+
+    #[test]
+    fn test_iter_col() {
+        let (num_rows, num_col) = (5, 3);
+        let mut matrix = RowMajorMatrix::<u64>::new(num_rows, num_col);
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = MaybeUninit::new((i * num_col + j) as u64);
+            }
+        }
+        assert_eq!(
+            matrix
+                .iter_col(1)
+                .map(|v| unsafe { v.assume_init() })
+                .collect::<Vec<_>>(),
+            vec![1, 4, 7, 10, 13, 16, 19, 22]
+        );
+    }
+
+    #[test]
+    fn test_into_mles_in_place() {
+        let new_matrix = || {
+            let (num_rows, num_col) = (5, 3);
+            let mut matrix = RowMajorMatrix::<Goldilocks>::new(num_rows, num_col);
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    *cell = MaybeUninit::new(Goldilocks::from((i * num_col + j) as u64));
+                }
+            }
+            matrix
+        };
+        assert_eq!(
+            new_matrix().into_columns_in_place(),
+            new_matrix().de_interleaving()
+        );
+        assert_eq!(
+            new_matrix().into_mles_in_place::<GoldilocksExt2>(),
+            new_matrix().into_mles::<GoldilocksExt2>()
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "witness unassigned/y (column 1) on instance 2")]
+    fn test_assert_fully_assigned() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "unassigned");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let y = cb.create_witin(|| "y");
+
+        let mut matrix = RowMajorMatrix::<Goldilocks>::new(3, cb.cs.num_witin as usize);
+        for (i, instance) in matrix.iter_mut().take(3).enumerate() {
+            set_val!(instance, x, i as u64);
+            if i != 2 {
+                set_val!(instance, y, i as u64);
+            }
+        }
+        // padding rows are not reported
+        assert_eq!(matrix.unassigned_cells(), vec![(2, 1)]);
+        matrix.assert_fully_assigned(&cs);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_all_ones_is_assigned() {
+        let mut matrix = RowMajorMatrix::<u64>::new(4, 2);
+        for instance in matrix.iter_mut() {
+            instance.fill(MaybeUninit::new(u64::MAX));
+        }
+        assert!(matrix.unassigned_cells().is_empty());
+    }
+
+    #[test]
+    fn test_set_vals() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "set_vals");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let wits = [0, 1, 2].map(|i| cb.create_witin(|| format!("w_{i}")));
+
+        let mut instance = vec![MaybeUninit::<Goldilocks>::uninit(); 3];
+        set_vals!(instance, &wits, [5u64, 6, 7]);
+        let values = instance
+            .iter()
+            .map(|v| unsafe { v.assume_init() })
+            .collect_vec();
+        assert_eq!(values, [5, 6, 7].map(Goldilocks::from));
+    }
+
+    #[test]
+    fn test_lk_multiplicity_merge() {
+        let lkm = LkMultiplicity::default();
+        // independent multiplicities, e.g. one per rayon task
+        (0..64u64).into_par_iter().for_each(|i| {
+            let mut local = LkMultiplicity::default();
+            local.assert_ux::<16>(i % 4);
+            local.fetch(0x1000);
+            lkm.clone().merge(local);
+        });
+        // merging a clone of itself is a no-op
+        lkm.clone().merge(lkm.clone());
+
+        let res = lkm.par_finalize();
+        for i in 0..4 {
+            assert_eq!(res[ROMType::U16.id()][&i], 16);
+        }
+        assert_eq!(res[ROMType::Instruction.id()][&0x1000], 64);
+        assert!(res[ROMType::U8.id()].is_empty());
+    }
+
+    #[test]
+    fn test_assert_ux_packed() {
+        let mut lkm = LkMultiplicity::default();
+        // two records of three values, the last one padded with zeros
+        lkm.assert_ux_packed::<5>(&[1, 2, 3, 31]);
+        lkm.assert_ux_packed::<8>(&[0xab, 0xcd]);
+        let res = lkm.into_finalize_result();
+
+        let u5_rows = [PackedU5Table::pack(&[1, 2, 3]), PackedU5Table::pack(&[31])];
+        assert_eq!(u5_rows, [1 | (2 << 5) | (3 << 10), 31]);
+        assert_eq!(PackedU5Table::unpack(u5_rows[1]), vec![31, 0, 0]);
+        assert_eq!(res[ROMType::PackedU5.id()].len(), 2);
+        assert!(
+            u5_rows
+                .iter()
+                .all(|row| res[ROMType::PackedU5.id()][row] == 1)
+        );
+        assert_eq!(res[ROMType::PackedU8.id()][&0xcdab], 1);
     }
 }
@@ -0,0 +1,18 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use ceno_rt::stdin::{read, read_slice};
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+ceno_rt::entry!(main);
+fn main() {
+    assert_eq!(read_slice(), b"raw");
+    let (numbers, name): (Vec<u32>, String) = read();
+    assert_eq!(numbers, [1, 2, 3]);
+    assert_eq!(name, "ceno");
+    assert_eq!(read::<Option<u64>>(), Some(u64::MAX));
+    assert_eq!(read::<Option<i8>>(), None);
+}
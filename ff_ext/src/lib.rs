@@ -1,10 +1,32 @@
 pub use ff;
 use ff::FromUniformBytes;
-use goldilocks::SmallField;
-use poseidon::poseidon::Poseidon;
+use goldilocks::{Goldilocks, SmallField};
+use poseidon::poseidon::{AdaptedField, Poseidon};
 use serde::Serialize;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
+pub mod pow;
+pub use pow::PowConst;
+pub mod sampling;
+pub use sampling::{UniformBase, random_vec};
+pub mod test_vectors;
+
+/// Reduces `x` to a Goldilocks element with the special form of its modulus, instead of generic
+/// modular reduction.
+pub use poseidon::reduce128;
+
+/// The Goldilocks element `n_lo + 2^64 * n_hi`, reduced like [`reduce128`].
+#[inline]
+pub fn from_noncanonical_u96(n_lo: u64, n_hi: u32) -> Goldilocks {
+    Goldilocks::from_noncanonical_u96(n_lo, n_hi)
+}
+
+/// The Goldilocks element `n`, reduced with [`reduce128`].
+#[inline]
+pub fn from_noncanonical_u128(n: u128) -> Goldilocks {
+    Goldilocks::from_noncanonical_u128(n)
+}
+
 pub trait ExtensionField:
     Serialize
     + FromUniformBytes<64>
@@ -37,11 +59,54 @@ pub trait ExtensionField:
 
     /// Convert a field elements to a u64 vector
     fn to_canonical_u64_vec(&self) -> Vec<u64>;
+
+    /// Equivalent to `*self * rhs`, overridden where the base field has a faster reduction
+    #[inline]
+    fn mul_reduce(&self, rhs: &Self) -> Self {
+        *self * rhs
+    }
+
+    /// Equivalent to `*self * rhs`, overridden where the base field has a faster reduction
+    #[inline]
+    fn mul_base_reduce(&self, rhs: &Self::BaseField) -> Self {
+        *self * rhs
+    }
+
+    /// Sum of pairwise products, overridden where the base field can delay reduction to the end
+    #[inline]
+    fn sum_of_products(pairs: impl IntoIterator<Item = (Self, Self)>) -> Self {
+        let [sum] = Self::sums_of_products(pairs.into_iter().map(|pair| [pair]));
+        sum
+    }
+
+    /// The `N` sums of pairwise products of `N` interleaved sequences, in a single pass
+    #[inline]
+    fn sums_of_products<const N: usize>(
+        pairs: impl IntoIterator<Item = [(Self, Self); N]>,
+    ) -> [Self; N] {
+        pairs.into_iter().fold([Self::ZERO; N], |mut sums, pairs| {
+            for (sum, (a, b)) in sums.iter_mut().zip(pairs) {
+                *sum += a * b;
+            }
+            sums
+        })
+    }
+
+    /// Sum of `a[i] * b[i]`
+    #[inline]
+    fn dot_product(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len());
+        Self::sum_of_products(a.iter().copied().zip(b.iter().copied()))
+    }
 }
 
 mod impl_goldilocks {
-    use crate::ExtensionField;
+    use crate::{ExtensionField, from_noncanonical_u128};
     use goldilocks::{ExtensionField as GoldilocksEF, Goldilocks, GoldilocksExt2};
+    use poseidon::poseidon::{AdaptedField, ProductAccumulator};
+
+    /// Non-residue defining GoldilocksExt2 = Goldilocks[x] / (x^2 - W)
+    const W: u64 = 7;
 
     impl ExtensionField for GoldilocksExt2 {
         const DEGREE: usize = 2;
@@ -65,5 +130,90 @@ mod impl_goldilocks {
         fn to_canonical_u64_vec(&self) -> Vec<u64> {
             <GoldilocksExt2 as GoldilocksEF>::to_canonical_u64_vec(self)
         }
+
+        /// (a0 + a1 x)(b0 + b1 x) = (a0 b0 + W a1 b1) + (a0 b1 + a1 b0) x, with each
+        /// coefficient accumulated in u128 and reduced once
+        #[inline]
+        fn mul_reduce(&self, rhs: &Self) -> Self {
+            let [a0, a1] = self.0;
+            let [b0, b1] = rhs.0;
+            let w_a1_b1 = from_noncanonical_u128(a1.mul_reduce(b1).0 as u128 * W as u128);
+            Self([
+                w_a1_b1.multiply_accumulate(a0, b0),
+                a0.mul_reduce(b1).multiply_accumulate(a1, b0),
+            ])
+        }
+
+        #[inline]
+        fn mul_base_reduce(&self, rhs: &Goldilocks) -> Self {
+            Self([self.0[0].mul_reduce(*rhs), self.0[1].mul_reduce(*rhs)])
+        }
+
+        #[inline]
+        fn sums_of_products<const N: usize>(
+            pairs: impl IntoIterator<Item = [(Self, Self); N]>,
+        ) -> [Self; N] {
+            let mut a0_b0 = [ProductAccumulator::default(); N];
+            let mut a1_b1 = [ProductAccumulator::default(); N];
+            let mut cross = [ProductAccumulator::default(); N];
+            for pairs in pairs {
+                for (i, (Self([a0, a1]), Self([b0, b1]))) in pairs.into_iter().enumerate() {
+                    a0_b0[i].add_product(a0, b0);
+                    a1_b1[i].add_product(a1, b1);
+                    cross[i].add_product(a0, b1);
+                    cross[i].add_product(a1, b0);
+                }
+            }
+            std::array::from_fn(|i| {
+                let w_a1_b1 =
+                    from_noncanonical_u128(a1_b1[i].reduce::<Goldilocks>().0 as u128 * W as u128);
+                Self([a0_b0[i].reduce::<Goldilocks>() + w_a1_b1, cross[i].reduce()])
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2, SmallField};
+    use poseidon::poseidon::AdaptedField;
+    use rand::{Rng, rngs::OsRng};
+
+    use super::{ExtensionField, from_noncanonical_u96, from_noncanonical_u128, reduce128};
+
+    #[test]
+    fn test_reduce() {
+        let p = Goldilocks::MODULUS_U64 as u128;
+        let reduced = |x: u128| (x % p) as u64;
+        for x in [0, p - 1, p, u64::MAX as u128, u128::MAX]
+            .into_iter()
+            .chain((0..100).map(|_| OsRng.gen::<u128>()))
+        {
+            assert_eq!(reduce128(x).to_canonical_u64(), reduced(x));
+            assert_eq!(from_noncanonical_u128(x).to_canonical_u64(), reduced(x));
+            let (lo, hi) = (x as u64, (x >> 64) as u32);
+            assert_eq!(
+                from_noncanonical_u96(lo, hi).to_canonical_u64(),
+                reduced(lo as u128 + ((hi as u128) << 64))
+            );
+        }
+    }
+
+    #[test]
+    fn test_mul_reduce() {
+        for _ in 0..100 {
+            let (a, b) = (
+                GoldilocksExt2::random(&mut OsRng),
+                GoldilocksExt2::random(&mut OsRng),
+            );
+            let c = Goldilocks::random(&mut OsRng);
+            assert_eq!(a.mul_reduce(&b), a * b);
+            assert_eq!(a.mul_base_reduce(&c), a * c);
+            assert_eq!(c.mul_reduce(a.as_bases()[0]), c * a.as_bases()[0]);
+        }
+        let max = -Goldilocks::ONE;
+        let max_ext = GoldilocksExt2::from_bases(&[max, max]);
+        assert_eq!(max_ext.mul_reduce(&max_ext), max_ext * max_ext);
     }
 }
@@ -0,0 +1,83 @@
+use goldilocks::SmallField;
+
+/// Exponentiation by constants, and inversion by a fixed addition chain.
+pub trait PowConst: SmallField {
+    /// `self^N`, by square-and-multiply over the bits of `N`, which unrolls to a fixed chain of
+    /// squarings and multiplications.
+    #[inline(always)]
+    fn pow_const<const N: u64>(&self) -> Self {
+        let mut acc = Self::ONE;
+        for i in (0..u64::BITS - N.leading_zeros()).rev() {
+            acc = acc.square();
+            if (N >> i) & 1 == 1 {
+                acc *= self;
+            }
+        }
+        acc
+    }
+
+    /// The inverse, or `None` for zero. In the Goldilocks field it is `self^(p - 2)`, by the
+    /// addition chain of Plonky2 with 72 multiplications.
+    #[inline]
+    fn inverse(&self) -> Option<Self> {
+        if Self::MODULUS_U64 != goldilocks::MODULUS {
+            return self.invert().into();
+        }
+        if self.is_zero_vartime() {
+            return None;
+        }
+        // p - 2 = 0b111...1 0 111...1, with 31 ones, then a zero, then 32 ones
+        let t2 = self.square() * self;
+        let t3 = t2.square() * self;
+        let t6 = exp_acc::<_, 3>(t3, t3);
+        let t12 = exp_acc::<_, 6>(t6, t6);
+        let t24 = exp_acc::<_, 12>(t12, t12);
+        let t30 = exp_acc::<_, 6>(t24, t6);
+        let t31 = t30.square() * self;
+        let t63 = exp_acc::<_, 32>(t31, t31);
+        Some(t63.square() * self)
+    }
+}
+
+impl<F: SmallField> PowConst for F {}
+
+/// `base^(2^N) * tail`
+#[inline(always)]
+fn exp_acc<F: SmallField, const N: usize>(base: F, tail: F) -> F {
+    (0..N).fold(base, |acc, _| acc.square()) * tail
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::{Goldilocks, SmallField};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::PowConst;
+
+    #[test]
+    fn test_pow_const() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let x = Goldilocks::random(&mut rng);
+        assert_eq!(x.pow_const::<0>(), Goldilocks::ONE);
+        assert_eq!(x.pow_const::<7>(), x.pow_vartime([7]));
+        assert_eq!(
+            x.pow_const::<{ goldilocks::MODULUS - 1 }>(),
+            Goldilocks::ONE
+        );
+    }
+
+    #[test]
+    fn test_inverse() {
+        assert_eq!(Goldilocks::MODULUS_U64, goldilocks::MODULUS);
+        assert_eq!(Goldilocks::ZERO.inverse(), None);
+        let mut rng = StdRng::seed_from_u64(0);
+        for x in [
+            Goldilocks::ONE,
+            -Goldilocks::ONE,
+            Goldilocks::random(&mut rng),
+        ] {
+            assert_eq!(x.inverse(), Some(x.invert().unwrap()));
+        }
+    }
+}
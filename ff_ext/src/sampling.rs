@@ -0,0 +1,75 @@
+//! Uniform sampling of canonical base field elements.
+//!
+//! The base field types live outside of this workspace, so `rand::distributions::Standard`
+//! cannot be implemented for them here; [`UniformBase`] is the distribution to use instead.
+
+use goldilocks::SmallField;
+use rand::{Rng, distributions::Distribution};
+
+/// The uniform distribution over a base field. A sample is a uniform `u64` below the modulus,
+/// by rejection of the ones above it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformBase;
+
+impl<F: SmallField> Distribution<F> for UniformBase {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> F {
+        loop {
+            let value = rng.next_u64();
+            if value < F::MODULUS_U64 {
+                return F::from(value);
+            }
+        }
+    }
+}
+
+/// `len` uniform base field elements.
+pub fn random_vec<F: SmallField>(len: usize, rng: &mut impl Rng) -> Vec<F> {
+    rng.sample_iter(UniformBase).take(len).collect()
+}
+
+/// The base field element of up to 8 uniform bytes in little endian, or `None` if their value
+/// is not below the modulus, for rejection sampling on a stream of bytes.
+pub fn base_from_uniform_bytes<F: SmallField>(bytes: &[u8]) -> Option<F> {
+    assert!(bytes.len() <= 8, "at most 8 bytes per element");
+    let mut le = [0u8; 8];
+    le[..bytes.len()].copy_from_slice(bytes);
+    let value = u64::from_le_bytes(le);
+    (value < F::MODULUS_U64).then(|| F::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::{Goldilocks, SmallField};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{base_from_uniform_bytes, random_vec};
+
+    #[test]
+    fn test_random_vec() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = random_vec::<Goldilocks>(1 << 12, &mut rng);
+        assert_eq!(values.len(), 1 << 12);
+        // The top bit is set in about half of the elements.
+        let high = values
+            .iter()
+            .filter(|v| v.to_canonical_u64() >> 63 == 1)
+            .count();
+        assert!((1800..2300).contains(&high));
+    }
+
+    #[test]
+    fn test_base_from_uniform_bytes() {
+        assert_eq!(
+            base_from_uniform_bytes::<Goldilocks>(&[1, 2]),
+            Some(Goldilocks::from(0x0201))
+        );
+        assert_eq!(
+            base_from_uniform_bytes::<Goldilocks>(&(Goldilocks::MODULUS_U64 - 1).to_le_bytes()),
+            Some(-Goldilocks::from(1))
+        );
+        assert_eq!(
+            base_from_uniform_bytes::<Goldilocks>(&Goldilocks::MODULUS_U64.to_le_bytes()),
+            None
+        );
+    }
+}
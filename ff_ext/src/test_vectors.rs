@@ -0,0 +1,1188 @@
+//! Test vectors of the Goldilocks field and its quadratic extension, for alternative
+//! implementations of the arithmetic, e.g. SIMD or GPU backends, to check themselves against
+//! with [`verify_against_vectors`], at startup or in their tests.
+//!
+//! The vectors are computed with big integers, independently of any field implementation, and
+//! every inverse is cross-checked by Fermat's little theorem and the extended Euclidean
+//! algorithm. They cover the edge cases of the reduction modulo `p = 2^64 - 2^32 + 1`: the
+//! values around 0, `EPSILON = 2^32 - 1` and `p`, whose sums and products overflow 64 bits.
+
+use ff::Field;
+use goldilocks::SmallField;
+
+use crate::ExtensionField;
+
+/// Check the arithmetic of `E` and its base field against the vectors, and describe the first
+/// mismatch. The base field must be Goldilocks, and `E` its quadratic extension.
+pub fn verify_against_vectors<E: ExtensionField>() -> Result<(), String> {
+    if E::BaseField::MODULUS_U64 != goldilocks::MODULUS || E::DEGREE != 2 {
+        return Err("not the quadratic extension of Goldilocks".to_string());
+    }
+    let base = |value: u64| E::BaseField::from(value);
+    let ext = |[c0, c1]: [u64; 2]| E::from_bases(&[base(c0), base(c1)]);
+
+    for &[a, b, sum, difference, product] in BASE_VECTORS {
+        let (x, y) = (base(a), base(b));
+        for (name, actual, expected) in [
+            ("sum", x + y, sum),
+            ("difference", x - y, difference),
+            ("product", x * y, product),
+        ] {
+            let actual = actual.to_canonical_u64();
+            if actual != expected {
+                return Err(format!(
+                    "{name} of {a:#x} and {b:#x} is {actual:#x} instead of {expected:#x}"
+                ));
+            }
+        }
+    }
+    for &[a, expected] in INVERSE_VECTORS {
+        let actual = Option::<E::BaseField>::from(base(a).invert())
+            .map(|inverse| inverse.to_canonical_u64());
+        if actual != Some(expected) {
+            return Err(format!(
+                "inverse of {a:#x} is {actual:x?} instead of {expected:#x}"
+            ));
+        }
+    }
+    if bool::from(E::BaseField::ZERO.invert().is_some()) {
+        return Err("zero has an inverse".to_string());
+    }
+
+    for &[a, b, product, inverse] in EXT_VECTORS {
+        let (x, y) = (ext(a), ext(b));
+        for (name, actual, expected) in [
+            ("product", x * y, product),
+            ("reduced product", x.mul_reduce(&y), product),
+            (
+                "sum of products",
+                E::sum_of_products([(x, y), (y, x)]),
+                product.map(|c| (c as u128 * 2 % goldilocks::MODULUS as u128) as u64),
+            ),
+            (
+                "interleaved sums of products",
+                E::sums_of_products([[(x, y), (y, x)], [(y, x), (x, y)]])[1],
+                product.map(|c| (c as u128 * 2 % goldilocks::MODULUS as u128) as u64),
+            ),
+        ] {
+            let actual = actual.to_canonical_u64_vec();
+            if actual != expected {
+                return Err(format!(
+                    "extension {name} of {a:x?} and {b:x?} is {actual:x?} instead of {expected:x?}"
+                ));
+            }
+        }
+        let actual = Option::<E>::from(x.invert()).map(|inverse| inverse.to_canonical_u64_vec());
+        if actual.as_deref() != Some(&inverse[..]) {
+            return Err(format!(
+                "extension inverse of {a:x?} is {actual:x?} instead of {inverse:x?}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `[a, b, a + b, a - b, a * b]`, on the values around 0, `EPSILON` and `p`, and two random ones.
+pub const BASE_VECTORS: &[[u64; 5]] = &[
+    [
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x0000000000000001,
+        0x0000000000000001,
+        0xffffffff00000000,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x0000000000000002,
+        0x0000000000000002,
+        0xfffffffeffffffff,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x00000000ffffffff,
+        0x00000000ffffffff,
+        0xfffffffe00000002,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x0000000100000000,
+        0x0000000100000000,
+        0xfffffffe00000001,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x0000000100000001,
+        0x0000000100000001,
+        0xfffffffe00000000,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0x8000000000000000,
+        0x8000000000000000,
+        0x7fffffff00000001,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0xfffffffe00000001,
+        0xfffffffe00000001,
+        0x0000000100000000,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0xfffffffeffffffff,
+        0xfffffffeffffffff,
+        0x0000000000000002,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0xffffffff00000000,
+        0xffffffff00000000,
+        0x0000000000000001,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c723f,
+        0x3d50690976c38dc2,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000000,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f14,
+        0x1d7cd8037af2b0ed,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000001,
+        0x0000000000000000,
+        0x0000000000000001,
+        0x0000000000000001,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000001,
+        0x0000000000000001,
+        0x0000000000000002,
+        0x0000000000000000,
+        0x0000000000000001,
+    ],
+    [
+        0x0000000000000001,
+        0x0000000000000002,
+        0x0000000000000003,
+        0xffffffff00000000,
+        0x0000000000000002,
+    ],
+    [
+        0x0000000000000001,
+        0x00000000ffffffff,
+        0x0000000100000000,
+        0xfffffffe00000003,
+        0x00000000ffffffff,
+    ],
+    [
+        0x0000000000000001,
+        0x0000000100000000,
+        0x0000000100000001,
+        0xfffffffe00000002,
+        0x0000000100000000,
+    ],
+    [
+        0x0000000000000001,
+        0x0000000100000001,
+        0x0000000100000002,
+        0xfffffffe00000001,
+        0x0000000100000001,
+    ],
+    [
+        0x0000000000000001,
+        0x8000000000000000,
+        0x8000000000000001,
+        0x7fffffff00000002,
+        0x8000000000000000,
+    ],
+    [
+        0x0000000000000001,
+        0xfffffffe00000001,
+        0xfffffffe00000002,
+        0x0000000100000001,
+        0xfffffffe00000001,
+    ],
+    [
+        0x0000000000000001,
+        0xfffffffeffffffff,
+        0xffffffff00000000,
+        0x0000000000000003,
+        0xfffffffeffffffff,
+    ],
+    [
+        0x0000000000000001,
+        0xffffffff00000000,
+        0x0000000000000000,
+        0x0000000000000002,
+        0xffffffff00000000,
+    ],
+    [
+        0x0000000000000001,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c7240,
+        0x3d50690976c38dc3,
+        0xc2af96f5893c723f,
+    ],
+    [
+        0x0000000000000001,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f15,
+        0x1d7cd8037af2b0ee,
+        0xe28327fb850d4f14,
+    ],
+    [
+        0x0000000000000002,
+        0x0000000000000000,
+        0x0000000000000002,
+        0x0000000000000002,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000000000002,
+        0x0000000000000001,
+        0x0000000000000003,
+        0x0000000000000001,
+        0x0000000000000002,
+    ],
+    [
+        0x0000000000000002,
+        0x0000000000000002,
+        0x0000000000000004,
+        0x0000000000000000,
+        0x0000000000000004,
+    ],
+    [
+        0x0000000000000002,
+        0x00000000ffffffff,
+        0x0000000100000001,
+        0xfffffffe00000004,
+        0x00000001fffffffe,
+    ],
+    [
+        0x0000000000000002,
+        0x0000000100000000,
+        0x0000000100000002,
+        0xfffffffe00000003,
+        0x0000000200000000,
+    ],
+    [
+        0x0000000000000002,
+        0x0000000100000001,
+        0x0000000100000003,
+        0xfffffffe00000002,
+        0x0000000200000002,
+    ],
+    [
+        0x0000000000000002,
+        0x8000000000000000,
+        0x8000000000000002,
+        0x7fffffff00000003,
+        0x00000000ffffffff,
+    ],
+    [
+        0x0000000000000002,
+        0xfffffffe00000001,
+        0xfffffffe00000003,
+        0x0000000100000002,
+        0xfffffffd00000001,
+    ],
+    [
+        0x0000000000000002,
+        0xfffffffeffffffff,
+        0x0000000000000000,
+        0x0000000000000004,
+        0xfffffffefffffffd,
+    ],
+    [
+        0x0000000000000002,
+        0xffffffff00000000,
+        0x0000000000000001,
+        0x0000000000000003,
+        0xfffffffeffffffff,
+    ],
+    [
+        0x0000000000000002,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c7241,
+        0x3d50690976c38dc4,
+        0x855f2dec1278e47d,
+    ],
+    [
+        0x0000000000000002,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f16,
+        0x1d7cd8037af2b0ef,
+        0xc5064ff80a1a9e27,
+    ],
+    [
+        0x00000000ffffffff,
+        0x0000000000000000,
+        0x00000000ffffffff,
+        0x00000000ffffffff,
+        0x0000000000000000,
+    ],
+    [
+        0x00000000ffffffff,
+        0x0000000000000001,
+        0x0000000100000000,
+        0x00000000fffffffe,
+        0x00000000ffffffff,
+    ],
+    [
+        0x00000000ffffffff,
+        0x0000000000000002,
+        0x0000000100000001,
+        0x00000000fffffffd,
+        0x00000001fffffffe,
+    ],
+    [
+        0x00000000ffffffff,
+        0x00000000ffffffff,
+        0x00000001fffffffe,
+        0x0000000000000000,
+        0xfffffffe00000001,
+    ],
+    [
+        0x00000000ffffffff,
+        0x0000000100000000,
+        0x00000001ffffffff,
+        0xffffffff00000000,
+        0xffffffff00000000,
+    ],
+    [
+        0x00000000ffffffff,
+        0x0000000100000001,
+        0x0000000200000000,
+        0xfffffffeffffffff,
+        0x00000000fffffffe,
+    ],
+    [
+        0x00000000ffffffff,
+        0x8000000000000000,
+        0x80000000ffffffff,
+        0x8000000000000000,
+        0xfffffffe80000001,
+    ],
+    [
+        0x00000000ffffffff,
+        0xfffffffe00000001,
+        0xffffffff00000000,
+        0x00000001ffffffff,
+        0x0000000000000001,
+    ],
+    [
+        0x00000000ffffffff,
+        0xfffffffeffffffff,
+        0x00000000fffffffd,
+        0x0000000100000001,
+        0xfffffffd00000003,
+    ],
+    [
+        0x00000000ffffffff,
+        0xffffffff00000000,
+        0x00000000fffffffe,
+        0x0000000100000000,
+        0xfffffffe00000002,
+    ],
+    [
+        0x00000000ffffffff,
+        0xc2af96f5893c723f,
+        0xc2af96f6893c723e,
+        0x3d50690a76c38dc1,
+        0x893c723db413f6cc,
+    ],
+    [
+        0x00000000ffffffff,
+        0xe28327fb850d4f14,
+        0xe28327fc850d4f13,
+        0x1d7cd8047af2b0ec,
+        0x850d4f12986f88f1,
+    ],
+    [
+        0x0000000100000000,
+        0x0000000000000000,
+        0x0000000100000000,
+        0x0000000100000000,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000100000000,
+        0x0000000000000001,
+        0x0000000100000001,
+        0x00000000ffffffff,
+        0x0000000100000000,
+    ],
+    [
+        0x0000000100000000,
+        0x0000000000000002,
+        0x0000000100000002,
+        0x00000000fffffffe,
+        0x0000000200000000,
+    ],
+    [
+        0x0000000100000000,
+        0x00000000ffffffff,
+        0x00000001ffffffff,
+        0x0000000000000001,
+        0xffffffff00000000,
+    ],
+    [
+        0x0000000100000000,
+        0x0000000100000000,
+        0x0000000200000000,
+        0x0000000000000000,
+        0x00000000ffffffff,
+    ],
+    [
+        0x0000000100000000,
+        0x0000000100000001,
+        0x0000000200000001,
+        0xffffffff00000000,
+        0x00000001ffffffff,
+    ],
+    [
+        0x0000000100000000,
+        0x8000000000000000,
+        0x8000000100000000,
+        0x8000000000000001,
+        0x7fffffff80000000,
+    ],
+    [
+        0x0000000100000000,
+        0xfffffffe00000001,
+        0x0000000000000000,
+        0x0000000200000000,
+        0xfffffffe00000002,
+    ],
+    [
+        0x0000000100000000,
+        0xfffffffeffffffff,
+        0x00000000fffffffe,
+        0x0000000100000002,
+        0xfffffffd00000001,
+    ],
+    [
+        0x0000000100000000,
+        0xffffffff00000000,
+        0x00000000ffffffff,
+        0x0000000100000001,
+        0xfffffffe00000001,
+    ],
+    [
+        0x0000000100000000,
+        0xc2af96f5893c723f,
+        0xc2af96f6893c723f,
+        0x3d50690a76c38dc2,
+        0x4bec09343d50690a,
+    ],
+    [
+        0x0000000100000000,
+        0xe28327fb850d4f14,
+        0xe28327fc850d4f14,
+        0x1d7cd8047af2b0ed,
+        0x6790770f1d7cd804,
+    ],
+    [
+        0x0000000100000001,
+        0x0000000000000000,
+        0x0000000100000001,
+        0x0000000100000001,
+        0x0000000000000000,
+    ],
+    [
+        0x0000000100000001,
+        0x0000000000000001,
+        0x0000000100000002,
+        0x0000000100000000,
+        0x0000000100000001,
+    ],
+    [
+        0x0000000100000001,
+        0x0000000000000002,
+        0x0000000100000003,
+        0x00000000ffffffff,
+        0x0000000200000002,
+    ],
+    [
+        0x0000000100000001,
+        0x00000000ffffffff,
+        0x0000000200000000,
+        0x0000000000000002,
+        0x00000000fffffffe,
+    ],
+    [
+        0x0000000100000001,
+        0x0000000100000000,
+        0x0000000200000001,
+        0x0000000000000001,
+        0x00000001ffffffff,
+    ],
+    [
+        0x0000000100000001,
+        0x0000000100000001,
+        0x0000000200000002,
+        0x0000000000000000,
+        0x0000000300000000,
+    ],
+    [
+        0x0000000100000001,
+        0x8000000000000000,
+        0x8000000100000001,
+        0x8000000000000002,
+        0x000000007fffffff,
+    ],
+    [
+        0x0000000100000001,
+        0xfffffffe00000001,
+        0x0000000000000001,
+        0x0000000200000001,
+        0xfffffffd00000002,
+    ],
+    [
+        0x0000000100000001,
+        0xfffffffeffffffff,
+        0x00000000ffffffff,
+        0x0000000100000003,
+        0xfffffffcffffffff,
+    ],
+    [
+        0x0000000100000001,
+        0xffffffff00000000,
+        0x0000000100000000,
+        0x0000000100000002,
+        0xfffffffe00000000,
+    ],
+    [
+        0x0000000100000001,
+        0xc2af96f5893c723f,
+        0xc2af96f6893c7240,
+        0x3d50690a76c38dc3,
+        0x0e9ba02ac68cdb48,
+    ],
+    [
+        0x0000000100000001,
+        0xe28327fb850d4f14,
+        0xe28327fc850d4f15,
+        0x1d7cd8047af2b0ee,
+        0x4a139f0ba28a2717,
+    ],
+    [
+        0x8000000000000000,
+        0x0000000000000000,
+        0x8000000000000000,
+        0x8000000000000000,
+        0x0000000000000000,
+    ],
+    [
+        0x8000000000000000,
+        0x0000000000000001,
+        0x8000000000000001,
+        0x7fffffffffffffff,
+        0x8000000000000000,
+    ],
+    [
+        0x8000000000000000,
+        0x0000000000000002,
+        0x8000000000000002,
+        0x7ffffffffffffffe,
+        0x00000000ffffffff,
+    ],
+    [
+        0x8000000000000000,
+        0x00000000ffffffff,
+        0x80000000ffffffff,
+        0x7fffffff00000001,
+        0xfffffffe80000001,
+    ],
+    [
+        0x8000000000000000,
+        0x0000000100000000,
+        0x8000000100000000,
+        0x7fffffff00000000,
+        0x7fffffff80000000,
+    ],
+    [
+        0x8000000000000000,
+        0x0000000100000001,
+        0x8000000100000001,
+        0x7ffffffeffffffff,
+        0x000000007fffffff,
+    ],
+    [
+        0x8000000000000000,
+        0x8000000000000000,
+        0x00000000ffffffff,
+        0x0000000000000000,
+        0xfffffffec0000001,
+    ],
+    [
+        0x8000000000000000,
+        0xfffffffe00000001,
+        0x7fffffff00000000,
+        0x8000000100000000,
+        0x7fffffff80000001,
+    ],
+    [
+        0x8000000000000000,
+        0xfffffffeffffffff,
+        0x7ffffffffffffffe,
+        0x8000000000000002,
+        0xfffffffe00000002,
+    ],
+    [
+        0x8000000000000000,
+        0xffffffff00000000,
+        0x7fffffffffffffff,
+        0x8000000000000001,
+        0x7fffffff00000001,
+    ],
+    [
+        0x8000000000000000,
+        0xc2af96f5893c723f,
+        0x42af96f6893c723e,
+        0xbd50690976c38dc2,
+        0x449e391eda09fb66,
+    ],
+    [
+        0x8000000000000000,
+        0xe28327fb850d4f14,
+        0x628327fc850d4f13,
+        0x9d7cd8037af2b0ed,
+        0xc286a788cc37c479,
+    ],
+    [
+        0xfffffffe00000001,
+        0x0000000000000000,
+        0xfffffffe00000001,
+        0xfffffffe00000001,
+        0x0000000000000000,
+    ],
+    [
+        0xfffffffe00000001,
+        0x0000000000000001,
+        0xfffffffe00000002,
+        0xfffffffe00000000,
+        0xfffffffe00000001,
+    ],
+    [
+        0xfffffffe00000001,
+        0x0000000000000002,
+        0xfffffffe00000003,
+        0xfffffffdffffffff,
+        0xfffffffd00000001,
+    ],
+    [
+        0xfffffffe00000001,
+        0x00000000ffffffff,
+        0xffffffff00000000,
+        0xfffffffd00000002,
+        0x0000000000000001,
+    ],
+    [
+        0xfffffffe00000001,
+        0x0000000100000000,
+        0x0000000000000000,
+        0xfffffffd00000001,
+        0xfffffffe00000002,
+    ],
+    [
+        0xfffffffe00000001,
+        0x0000000100000001,
+        0x0000000000000001,
+        0xfffffffd00000000,
+        0xfffffffd00000002,
+    ],
+    [
+        0xfffffffe00000001,
+        0x8000000000000000,
+        0x7fffffff00000000,
+        0x7ffffffe00000001,
+        0x7fffffff80000001,
+    ],
+    [
+        0xfffffffe00000001,
+        0xfffffffe00000001,
+        0xfffffffd00000001,
+        0x0000000000000000,
+        0x00000000ffffffff,
+    ],
+    [
+        0xfffffffe00000001,
+        0xfffffffeffffffff,
+        0xfffffffdffffffff,
+        0xfffffffe00000003,
+        0x0000000200000000,
+    ],
+    [
+        0xfffffffe00000001,
+        0xffffffff00000000,
+        0xfffffffe00000000,
+        0xfffffffe00000002,
+        0x0000000100000000,
+    ],
+    [
+        0xfffffffe00000001,
+        0xc2af96f5893c723f,
+        0xc2af96f4893c723f,
+        0x3d50690876c38dc2,
+        0xb413f6cac2af96f7,
+    ],
+    [
+        0xfffffffe00000001,
+        0xe28327fb850d4f14,
+        0xe28327fa850d4f14,
+        0x1d7cd8027af2b0ed,
+        0x986f88efe28327fd,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x0000000000000000,
+        0xfffffffeffffffff,
+        0xfffffffeffffffff,
+        0x0000000000000000,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x0000000000000001,
+        0xffffffff00000000,
+        0xfffffffefffffffe,
+        0xfffffffeffffffff,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x0000000000000002,
+        0x0000000000000000,
+        0xfffffffefffffffd,
+        0xfffffffefffffffd,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x00000000ffffffff,
+        0x00000000fffffffd,
+        0xfffffffe00000000,
+        0xfffffffd00000003,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x0000000100000000,
+        0x00000000fffffffe,
+        0xfffffffdffffffff,
+        0xfffffffd00000001,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x0000000100000001,
+        0x00000000ffffffff,
+        0xfffffffdfffffffe,
+        0xfffffffcffffffff,
+    ],
+    [
+        0xfffffffeffffffff,
+        0x8000000000000000,
+        0x7ffffffffffffffe,
+        0x7ffffffeffffffff,
+        0xfffffffe00000002,
+    ],
+    [
+        0xfffffffeffffffff,
+        0xfffffffe00000001,
+        0xfffffffdffffffff,
+        0x00000000fffffffe,
+        0x0000000200000000,
+    ],
+    [
+        0xfffffffeffffffff,
+        0xfffffffeffffffff,
+        0xfffffffefffffffd,
+        0x0000000000000000,
+        0x0000000000000004,
+    ],
+    [
+        0xfffffffeffffffff,
+        0xffffffff00000000,
+        0xfffffffefffffffe,
+        0xffffffff00000000,
+        0x0000000000000002,
+    ],
+    [
+        0xfffffffeffffffff,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c723d,
+        0x3d50690976c38dc0,
+        0x7aa0d212ed871b84,
+    ],
+    [
+        0xfffffffeffffffff,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f12,
+        0x1d7cd8037af2b0eb,
+        0x3af9b006f5e561da,
+    ],
+    [
+        0xffffffff00000000,
+        0x0000000000000000,
+        0xffffffff00000000,
+        0xffffffff00000000,
+        0x0000000000000000,
+    ],
+    [
+        0xffffffff00000000,
+        0x0000000000000001,
+        0x0000000000000000,
+        0xfffffffeffffffff,
+        0xffffffff00000000,
+    ],
+    [
+        0xffffffff00000000,
+        0x0000000000000002,
+        0x0000000000000001,
+        0xfffffffefffffffe,
+        0xfffffffeffffffff,
+    ],
+    [
+        0xffffffff00000000,
+        0x00000000ffffffff,
+        0x00000000fffffffe,
+        0xfffffffe00000001,
+        0xfffffffe00000002,
+    ],
+    [
+        0xffffffff00000000,
+        0x0000000100000000,
+        0x00000000ffffffff,
+        0xfffffffe00000000,
+        0xfffffffe00000001,
+    ],
+    [
+        0xffffffff00000000,
+        0x0000000100000001,
+        0x0000000100000000,
+        0xfffffffdffffffff,
+        0xfffffffe00000000,
+    ],
+    [
+        0xffffffff00000000,
+        0x8000000000000000,
+        0x7fffffffffffffff,
+        0x7fffffff00000000,
+        0x7fffffff00000001,
+    ],
+    [
+        0xffffffff00000000,
+        0xfffffffe00000001,
+        0xfffffffe00000000,
+        0x00000000ffffffff,
+        0x0000000100000000,
+    ],
+    [
+        0xffffffff00000000,
+        0xfffffffeffffffff,
+        0xfffffffefffffffe,
+        0x0000000000000001,
+        0x0000000000000002,
+    ],
+    [
+        0xffffffff00000000,
+        0xffffffff00000000,
+        0xfffffffeffffffff,
+        0x0000000000000000,
+        0x0000000000000001,
+    ],
+    [
+        0xffffffff00000000,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c723e,
+        0x3d50690976c38dc1,
+        0x3d50690976c38dc2,
+    ],
+    [
+        0xffffffff00000000,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f13,
+        0x1d7cd8037af2b0ec,
+        0x1d7cd8037af2b0ed,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x0000000000000000,
+        0xc2af96f5893c723f,
+        0xc2af96f5893c723f,
+        0x0000000000000000,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x0000000000000001,
+        0xc2af96f5893c7240,
+        0xc2af96f5893c723e,
+        0xc2af96f5893c723f,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x0000000000000002,
+        0xc2af96f5893c7241,
+        0xc2af96f5893c723d,
+        0x855f2dec1278e47d,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x00000000ffffffff,
+        0xc2af96f6893c723e,
+        0xc2af96f4893c7240,
+        0x893c723db413f6cc,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x0000000100000000,
+        0xc2af96f6893c723f,
+        0xc2af96f4893c723f,
+        0x4bec09343d50690a,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x0000000100000001,
+        0xc2af96f6893c7240,
+        0xc2af96f4893c723e,
+        0x0e9ba02ac68cdb48,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0x8000000000000000,
+        0x42af96f6893c723e,
+        0x42af96f5893c723f,
+        0x449e391eda09fb66,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0xfffffffe00000001,
+        0xc2af96f4893c723f,
+        0xc2af96f6893c723f,
+        0xb413f6cac2af96f7,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0xfffffffeffffffff,
+        0xc2af96f5893c723d,
+        0xc2af96f5893c7241,
+        0x7aa0d212ed871b84,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0xffffffff00000000,
+        0xc2af96f5893c723e,
+        0xc2af96f5893c7240,
+        0x3d50690976c38dc2,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0xc2af96f5893c723f,
+        0x855f2dec1278e47d,
+        0x0000000000000000,
+        0x5bfe2740b9bfac27,
+    ],
+    [
+        0xc2af96f5893c723f,
+        0xe28327fb850d4f14,
+        0xa532bef20e49c152,
+        0xe02c6ef9042f232c,
+        0x821ee6a431c6427a,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x0000000000000000,
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f14,
+        0x0000000000000000,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x0000000000000001,
+        0xe28327fb850d4f15,
+        0xe28327fb850d4f13,
+        0xe28327fb850d4f14,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x0000000000000002,
+        0xe28327fb850d4f16,
+        0xe28327fb850d4f12,
+        0xc5064ff80a1a9e27,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x00000000ffffffff,
+        0xe28327fc850d4f13,
+        0xe28327fa850d4f15,
+        0x850d4f12986f88f1,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x0000000100000000,
+        0xe28327fc850d4f14,
+        0xe28327fa850d4f14,
+        0x6790770f1d7cd804,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x0000000100000001,
+        0xe28327fc850d4f15,
+        0xe28327fa850d4f13,
+        0x4a139f0ba28a2717,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0x8000000000000000,
+        0x628327fc850d4f13,
+        0x628327fb850d4f14,
+        0xc286a788cc37c479,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0xfffffffe00000001,
+        0xe28327fa850d4f14,
+        0xe28327fc850d4f14,
+        0x986f88efe28327fd,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0xfffffffeffffffff,
+        0xe28327fb850d4f12,
+        0xe28327fb850d4f16,
+        0x3af9b006f5e561da,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0xffffffff00000000,
+        0xe28327fb850d4f13,
+        0xe28327fb850d4f15,
+        0x1d7cd8037af2b0ed,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0xc2af96f5893c723f,
+        0xa532bef20e49c152,
+        0x1fd39105fbd0dcd5,
+        0x821ee6a431c6427a,
+    ],
+    [
+        0xe28327fb850d4f14,
+        0xe28327fb850d4f14,
+        0xc5064ff80a1a9e27,
+        0x0000000000000000,
+        0x826442f917753ee0,
+    ],
+];
+/// `[a, 1 / a]`.
+pub const INVERSE_VECTORS: &[[u64; 2]] = &[
+    [0x0000000000000001, 0x0000000000000001],
+    [0x0000000000000002, 0x7fffffff80000001],
+    [0x00000000ffffffff, 0xfffffffe00000001],
+    [0x0000000100000000, 0xfffffffe00000002],
+    [0x0000000100000001, 0xaaaaaaa9aaaaaaac],
+    [0x8000000000000000, 0xfffffffd00000001],
+    [0xfffffffe00000001, 0x00000000ffffffff],
+    [0xfffffffeffffffff, 0x7fffffff80000000],
+    [0xffffffff00000000, 0xffffffff00000000],
+    [0xc2af96f5893c723f, 0x914b604d7bac3e86],
+    [0xe28327fb850d4f14, 0xc842defcddc2c9f5],
+    [0xe01b2408f2afa7d1, 0x29d5f115eb96dcaa],
+    [0xb98a26934af7a42b, 0x4fff417a0f817bb3],
+    [0x0e47600266091aef, 0x30cb3b428958f787],
+    [0xb8fbe951c78f0646, 0x933dc82a61f95e55],
+];
+/// `[a, b, a * b, 1 / a]` in the quadratic extension `x^2 = 7`, as `[c0, c1]` for `c0 + c1 x`.
+pub const EXT_VECTORS: &[[[u64; 2]; 4]] = &[
+    [
+        [0x0000000000000000, 0x0000000000000001],
+        [0x00000000ffffffff, 0x0000000100000000],
+        [0x0000000700000000, 0x00000000ffffffff],
+        [0x0000000000000000, 0x249249246db6db6e],
+    ],
+    [
+        [0x0000000000000001, 0x0000000000000001],
+        [0xffffffff00000000, 0x0000000000000001],
+        [0x0000000000000006, 0x0000000000000000],
+        [0x2aaaaaaa80000000, 0xd555555480000001],
+    ],
+    [
+        [0xffffffff00000000, 0xffffffff00000000],
+        [0x8000000000000000, 0xfffffffeffffffff],
+        [0x7fffffff0000000f, 0x7fffffff00000003],
+        [0xd555555480000001, 0x2aaaaaaa80000000],
+    ],
+    [
+        [0x00000000ffffffff, 0x0000000100000000],
+        [0x0fc2989ce713a21f, 0xf6612ce58d91b715],
+        [0x82b7ddf84c818af7, 0x8467f1cf6c4a8369],
+        [0xd31674c4c59d3168, 0xc59d3166674c59d4],
+    ],
+    [
+        [0xffffffff00000000, 0x0000000000000001],
+        [0x1d53a6ad27c5c589, 0x9c478b9d6001cf11],
+        [0x28a12aa47846e3ea, 0x810c1b0ec7c3f679],
+        [0xd555555480000001, 0xd555555480000001],
+    ],
+    [
+        [0x8000000000000000, 0xfffffffeffffffff],
+        [0xcc419eb782baca64, 0xfe42f4f53c92bb18],
+        [0xd9b3ffb9887b9031, 0x05c6201add1f9333],
+        [0x60468c5380dc0ef8, 0x7b7592d1811a3150],
+    ],
+    [
+        [0x0fc2989ce713a21f, 0xf6612ce58d91b715],
+        [0x819cde42c4014b94, 0x5fea72aacbed56b2],
+        [0x0a5a0801d3fa9000, 0x372423954d5eed2e],
+        [0x787bcb1b89516040, 0x8bad1086ab3a4039],
+    ],
+    [
+        [0x1d53a6ad27c5c589, 0x9c478b9d6001cf11],
+        [0x0000000000000000, 0x0000000000000001],
+        [0x45f4d151a00ca973, 0x1d53a6ad27c5c589],
+        [0x8f22d3835b897ac0, 0xf6a083052d9ea133],
+    ],
+    [
+        [0xcc419eb782baca64, 0xfe42f4f53c92bb18],
+        [0x0000000000000001, 0x0000000000000001],
+        [0xc01651732abde805, 0xca8493adbf4d857b],
+        [0xf9b11dcc5722db75, 0x3f206a7b8ca7808f],
+    ],
+    [
+        [0x819cde42c4014b94, 0x5fea72aacbed56b2],
+        [0xffffffff00000000, 0xffffffff00000000],
+        [0xdef9ff0da8815592, 0x1e78af1170115dbb],
+        [0xf372ee1b8ed74bb4, 0xa493bde22baeb5a1],
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use super::verify_against_vectors;
+
+    #[test]
+    fn test_goldilocks_vectors() {
+        verify_against_vectors::<GoldilocksExt2>().unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+//! Run the PCS benchmark suite and compare it against a stored baseline.
+//!
+//! ```sh
+//! cargo bench -p mpcs --features benchmark --bench report
+//! ```
+//!
+//! Environment variables:
+//! - `MPCS_BENCH_NUM_VARS`: range of log2 sizes, e.g. `16..=24` (default) or `20`
+//! - `MPCS_BENCH_OUTPUT`: write the report as JSON to this path
+//! - `MPCS_BENCH_BASELINE`: fail if slower than this JSON report by `MPCS_BENCH_TOLERANCE`
+//!   (default `0.1`, i.e. 10%)
+
+use std::{env, fs, ops::RangeInclusive};
+
+use goldilocks::GoldilocksExt2;
+use mpcs::{
+    BasefoldDefault,
+    bench::{BenchConfig, Report, run},
+};
+
+type E = GoldilocksExt2;
+type Pcs = BasefoldDefault<E>;
+
+fn parse_num_vars(s: &str) -> RangeInclusive<usize> {
+    match s.split_once("..=") {
+        Some((start, end)) => start.parse().unwrap()..=end.parse().unwrap(),
+        None => {
+            let num_vars = s.parse().unwrap();
+            num_vars..=num_vars
+        }
+    }
+}
+
+fn main() {
+    let mut config = BenchConfig::default();
+    if let Ok(num_vars) = env::var("MPCS_BENCH_NUM_VARS") {
+        config.num_vars = parse_num_vars(&num_vars);
+    }
+
+    let report = run::<E, Pcs>(&config);
+    println!("{report}");
+
+    if let Ok(path) = env::var("MPCS_BENCH_OUTPUT") {
+        fs::write(&path, serde_json::to_string_pretty(&report).unwrap()).unwrap();
+    }
+    if let Ok(path) = env::var("MPCS_BENCH_BASELINE") {
+        let baseline: Report = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let tolerance = env::var("MPCS_BENCH_TOLERANCE")
+            .map(|t| t.parse().unwrap())
+            .unwrap_or(0.1);
+        report.assert_no_regression(&baseline, tolerance);
+    }
+}
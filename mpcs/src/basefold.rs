@@ -1,5 +1,5 @@
 use crate::{
-    Error, Evaluation, NoninteractivePCS, PolynomialCommitmentScheme,
+    Error, Evaluation, NoninteractivePCS, PolynomialCommitmentScheme, VerificationLevel,
     sum_check::{
         SumCheck as _, VirtualPolynomial,
         classic::{ClassicSumCheck, CoefficientsProver},
@@ -8,20 +8,21 @@ use crate::{
     util::{
         add_polynomial_with_coeff,
         arithmetic::{
-            inner_product, inner_product_three, interpolate_field_type_over_boolean_hypercube,
+            inner_product_ext, inner_product_three, interpolate_field_type_over_boolean_hypercube,
         },
         expression::{Expression, Query, Rotation},
-        ext_to_usize,
+        field_type_index_ext,
         hash::{Digest, write_digest_to_transcript},
         log2_strict,
         merkle_tree::MerkleTree,
         multiply_poly,
-        plonky2_util::reverse_index_bits_in_place_field_type,
+        plonky2_util::{reverse_index_bits_in_place, reverse_index_bits_in_place_field_type},
         poly_index_ext, poly_iter_ext,
+        profile::{profile_exit, profile_span},
+        sanity_check,
     },
     validate_input,
 };
-use ark_std::{end_timer, start_timer};
 pub use encoding::{
     Basecode, BasecodeDefaultSpec, EncodingProverParameters, EncodingScheme, RSCode,
     RSCodeDefaultSpec,
@@ -31,8 +32,8 @@ use multilinear_extensions::mle::MultilinearExtension;
 use query_phase::{
     BatchedQueriesResultWithMerklePath, QueriesResultWithMerklePath,
     SimpleBatchQueriesResultWithMerklePath, batch_prover_query_phase, batch_verifier_query_phase,
-    prover_query_phase, simple_batch_prover_query_phase, simple_batch_verifier_query_phase,
-    verifier_query_phase,
+    draw_query_indices, prover_query_phase, simple_batch_prover_query_phase,
+    simple_batch_verifier_query_phase, verifier_query_phase,
 };
 use std::{borrow::BorrowMut, ops::Deref};
 pub use structure::BasefoldSpec;
@@ -44,13 +45,11 @@ use serde::{Serialize, de::DeserializeOwned};
 
 use multilinear_extensions::{
     mle::{DenseMultilinearExtension, FieldType},
+    structured::StructuredMle,
     virtual_poly::build_eq_x_r_vec,
 };
 
-use rayon::{
-    iter::IntoParallelIterator,
-    prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator},
-};
+use crate::util::parallel::prelude::*;
 use std::borrow::Cow;
 pub use sumcheck::{one_level_eval_hc, one_level_interp_hc};
 
@@ -59,11 +58,49 @@ type SumCheck<F> = ClassicSumCheck<CoefficientsProver<F>>;
 mod structure;
 pub use structure::{
     Basefold, BasefoldBasecodeParams, BasefoldCommitment, BasefoldCommitmentWithData,
-    BasefoldDefault, BasefoldParams, BasefoldProverParams, BasefoldRSParams,
+    BasefoldCompactCommitmentWithData, BasefoldDefault, BasefoldOpeningClaim, BasefoldParams,
+    BasefoldProverParams, BasefoldRSHighArityParams, BasefoldRSParams, BasefoldRSWideLeavesParams,
     BasefoldVerifierParams,
 };
 mod commit_phase;
+mod folding;
 use commit_phase::{batch_commit_phase, commit_phase, simple_batch_commit_phase};
+use folding::coset_log;
+pub use folding::{BinaryFolding, EncodingFolding, FoldingSchedule, HighArityFolding};
+
+/// The milestones of the opening protocols where the prover records its transcript digest.
+const MILESTONE_BATCHING: &str = "batching sumcheck";
+const MILESTONE_COMMIT_PHASE: &str = "commit phase";
+const MILESTONE_QUERY_PHASE: &str = "query phase";
+
+fn record_milestone<E: ExtensionField>(
+    digests: &mut Vec<(String, Digest<E::BaseField>)>,
+    milestone: &str,
+    transcript: &Transcript<E>,
+) {
+    digests.push((milestone.to_string(), transcript.state_digest()));
+}
+
+/// Compare the transcript with the prover's at a milestone, if the proof recorded it.
+fn check_milestone<E: ExtensionField>(
+    proof: &BasefoldProof<E>,
+    milestone: &str,
+    transcript: &Transcript<E>,
+) -> Result<(), Error>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    match proof
+        .transcript_digests
+        .iter()
+        .find(|(name, _)| name == milestone)
+    {
+        Some((_, digest)) if *digest != transcript.state_digest() => Err(Error::Transcript(
+            format!("transcript diverges from the prover's by the end of the {milestone}"),
+        )),
+        _ => Ok(()),
+    }
+}
 mod encoding;
 pub use encoding::{coset_fft, fft, fft_root_table};
 use multilinear_extensions::virtual_poly_v2::ArcMultilinearExtension;
@@ -90,15 +127,16 @@ where
         pp: &BasefoldProverParams<E, Spec>,
         poly: &DenseMultilinearExtension<E>,
     ) -> PolyEvalsCodeword<E> {
-        // bh_evals is just a copy of poly.evals().
-        // Note that this function implicitly assumes that the size of poly.evals() is a
-        // power of two. Otherwise, the function crashes with index out of bound.
-        let mut bh_evals = poly.evaluations.clone();
         let num_vars = poly.num_vars;
         if num_vars > pp.encoding_params.get_max_message_size_log() {
             return PolyEvalsCodeword::TooBig(num_vars);
         }
 
+        // bh_evals is just a copy of poly.evals().
+        // Note that this function implicitly assumes that the size of poly.evals() is a
+        // power of two. Otherwise, the function crashes with index out of bound.
+        let mut bh_evals = pp.workspace.copy_field_type(&poly.evaluations, 0);
+
         // In this case, the polynomial is so small that the opening is trivial.
         // So we just build the Merkle tree over the polynomial evaluations.
         // No codeword is needed.
@@ -106,8 +144,32 @@ where
             return PolyEvalsCodeword::TooSmall(bh_evals);
         }
 
-        // Switch to coefficient form
-        let mut coeffs = bh_evals.clone();
+        let codeword_len =
+            bh_evals.len() << <Spec::EncodingScheme as EncodingScheme<E>>::get_rate_log();
+
+        // A constant polynomial, such as an unused or all-zero column, has a
+        // constant codeword if the encoding scheme allows it. The codeword is
+        // then filled in directly, and the bit-reversals are no-ops.
+        let is_constant =
+            <Spec::EncodingScheme as EncodingScheme<E>>::constant_message_has_constant_codeword()
+                && match &bh_evals {
+                    FieldType::Base(evals) => evals.iter().all_equal(),
+                    FieldType::Ext(evals) => evals.iter().all_equal(),
+                    FieldType::Unreachable => unreachable!(),
+                };
+        if is_constant {
+            let mut codeword = pp.workspace.copy_field_type(&bh_evals, codeword_len);
+            match &mut codeword {
+                FieldType::Base(values) => values.resize(codeword_len, values[0]),
+                FieldType::Ext(values) => values.resize(codeword_len, values[0]),
+                FieldType::Unreachable => unreachable!(),
+            }
+            return PolyEvalsCodeword::Normal((bh_evals, codeword));
+        }
+
+        // Switch to coefficient form. Reserve room for the codeword, so that
+        // the encoding happens in this buffer.
+        let mut coeffs = pp.workspace.copy_field_type(&bh_evals, codeword_len);
         // TODO: directly return bit-reversed version if needed.
         interpolate_field_type_over_boolean_hypercube(&mut coeffs);
 
@@ -130,7 +192,7 @@ where
         if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
             reverse_index_bits_in_place_field_type(&mut coeffs);
         }
-        let mut codeword = Spec::EncodingScheme::encode(&pp.encoding_params, &coeffs);
+        let mut codeword = Spec::EncodingScheme::encode_owned(&pp.encoding_params, coeffs);
 
         // The evaluations over the hypercube are used in sum-check.
         // They are bit-reversed because the hypercube is ordered in little
@@ -156,6 +218,90 @@ where
         PolyEvalsCodeword::Normal((bh_evals, codeword))
     }
 
+    /// Rebuild a commitment from its compact form and the committed polynomials, so that it
+    /// can be opened again. The codewords are encoded and their Merkle tree is built again, and
+    /// its root is checked against the compact one, so that polynomials other than the
+    /// committed ones are rejected here instead of producing openings that do not verify.
+    pub fn hydrate(
+        pp: &BasefoldProverParams<E, Spec>,
+        compact: BasefoldCompactCommitmentWithData<E>,
+        polys: &[DenseMultilinearExtension<E>],
+    ) -> Result<BasefoldCommitmentWithData<E>, Error> {
+        if polys.len() != compact.num_polys
+            || polys.iter().any(|poly| {
+                poly.num_vars != compact.num_vars
+                    || matches!(poly.evaluations, FieldType::Base(_)) != compact.is_base
+            })
+        {
+            return Err(Error::InvalidPcsParam(format!(
+                "the commitment is to {} {} polynomials with {} variables",
+                compact.num_polys,
+                if compact.is_base { "base" } else { "extension" },
+                compact.num_vars
+            )));
+        }
+
+        let (polynomials_bh_evals, codewords): (Vec<_>, Vec<_>) = polys
+            .par_iter()
+            .map(
+                |poly| match Self::get_poly_bh_evals_and_codeword(pp, poly) {
+                    PolyEvalsCodeword::Normal((bh_evals, codeword)) => Ok((bh_evals, codeword)),
+                    PolyEvalsCodeword::TooSmall(evals) => {
+                        let leaves = pp.workspace.copy_field_type(&evals, 0);
+                        Ok((evals, leaves))
+                    }
+                    PolyEvalsCodeword::TooBig(num_vars) => Err(Error::PolynomialTooLarge(num_vars)),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        let leaf_width_log =
+            if BasefoldCommitmentWithData::<E>::trivial_num_vars::<Spec>(compact.num_vars) {
+                1
+            } else {
+                Spec::get_leaf_width_log()
+            };
+        let codeword_tree =
+            MerkleTree::from_batch_leaves_with_width_in(codewords, leaf_width_log, &pp.workspace);
+        if codeword_tree.root() != compact.root {
+            return Err(Error::MerkleRootMismatch);
+        }
+
+        Ok(BasefoldCommitmentWithData {
+            codeword_tree,
+            polynomials_bh_evals,
+            num_vars: compact.num_vars,
+            is_base: compact.is_base,
+            num_polys: compact.num_polys,
+        })
+    }
+
+    /// Verify independent openings concurrently, and return the result of each. The
+    /// verifications share `vp`, with its encoding tables and its cache of eq vectors, so the
+    /// points that the openings have in common are expanded once.
+    pub fn verify_many(
+        vp: &BasefoldVerifierParams<E, Spec>,
+        claims: Vec<BasefoldOpeningClaim<'_, E>>,
+        proofs: &[BasefoldProof<E>],
+    ) -> Vec<Result<(), Error>> {
+        assert_eq!(claims.len(), proofs.len());
+        claims
+            .into_par_iter()
+            .zip(proofs)
+            .map(|(mut claim, proof)| {
+                <Self as PolynomialCommitmentScheme<E>>::verify(
+                    vp,
+                    claim.comm,
+                    claim.point,
+                    &claim.eval,
+                    proof,
+                    &mut claim.transcript,
+                )
+            })
+            .collect()
+    }
+
     /// Transpose a matrix of field elements, generic over the type of field element
     pub fn transpose_field_type<T: Send + Sync + Copy>(
         matrix: &[FieldType<E>],
@@ -295,9 +441,12 @@ where
                 (
                     BasefoldProverParams {
                         encoding_params: pp,
+                        verification_level: VerificationLevel::default(),
+                        workspace: Default::default(),
                     },
                     BasefoldVerifierParams {
                         encoding_params: vp,
+                        eq_cache: Default::default(),
                     },
                 )
             },
@@ -308,7 +457,7 @@ where
         pp: &Self::ProverParam,
         poly: &DenseMultilinearExtension<E>,
     ) -> Result<Self::CommitmentWithData, Error> {
-        let timer = start_timer!(|| "Basefold::commit");
+        let timer = profile_span!("Basefold::commit");
 
         let is_base = match poly.evaluations {
             FieldType::Ext(_) => false,
@@ -323,7 +472,11 @@ where
         //  (2) The encoding of the coefficient vector (need an interpolation)
         let ret = match Self::get_poly_bh_evals_and_codeword(pp, poly) {
             PolyEvalsCodeword::Normal((bh_evals, codeword)) => {
-                let codeword_tree = MerkleTree::<E>::from_leaves(codeword);
+                let codeword_tree = MerkleTree::<E>::from_leaves_with_width_in(
+                    codeword,
+                    Spec::get_leaf_width_log(),
+                    &pp.workspace,
+                );
 
                 // All these values are stored in the `CommitmentWithData` because
                 // they are useful in opening, and we don't want to recompute them.
@@ -336,7 +489,10 @@ where
                 })
             }
             PolyEvalsCodeword::TooSmall(evals) => {
-                let codeword_tree = MerkleTree::<E>::from_leaves(evals.clone());
+                let codeword_tree = MerkleTree::<E>::from_leaves_in(
+                    pp.workspace.copy_field_type(&evals, 0),
+                    &pp.workspace,
+                );
 
                 // All these values are stored in the `CommitmentWithData` because
                 // they are useful in opening, and we don't want to recompute them.
@@ -351,7 +507,7 @@ where
             PolyEvalsCodeword::TooBig(num_vars) => Err(Error::PolynomialTooLarge(num_vars)),
         };
 
-        end_timer!(timer);
+        profile_exit!(timer);
 
         ret
     }
@@ -387,15 +543,15 @@ where
                 ));
             }
         }
-        let timer = start_timer!(|| "Basefold::batch commit");
+        let timer = profile_span!("Basefold::batch commit");
 
-        let encode_timer = start_timer!(|| "Basefold::batch commit::encoding and interpolations");
+        let encode_timer = profile_span!("Basefold::batch commit::encoding and interpolations");
         // convert each polynomial to a code word
         let evals_codewords = polys
             .par_iter()
             .map(|poly| Self::get_poly_bh_evals_and_codeword(pp, poly))
             .collect::<Vec<PolyEvalsCodeword<E>>>();
-        end_timer!(encode_timer);
+        profile_exit!(encode_timer);
 
         // build merkle tree from leaves
         let ret = match evals_codewords[0] {
@@ -412,7 +568,11 @@ where
                         }
                     })
                     .collect::<(Vec<_>, Vec<_>)>();
-                let codeword_tree = MerkleTree::<E>::from_batch_leaves(codewords);
+                let codeword_tree = MerkleTree::<E>::from_batch_leaves_with_width_in(
+                    codewords,
+                    Spec::get_leaf_width_log(),
+                    &pp.workspace,
+                );
                 Self::CommitmentWithData {
                     codeword_tree,
                     polynomials_bh_evals: bh_evals,
@@ -432,7 +592,13 @@ where
                         }
                     })
                     .collect::<Vec<_>>();
-                let codeword_tree = MerkleTree::<E>::from_batch_leaves(bh_evals.clone());
+                let codeword_tree = MerkleTree::<E>::from_batch_leaves_in(
+                    bh_evals
+                        .iter()
+                        .map(|evals| pp.workspace.copy_field_type(evals, 0))
+                        .collect(),
+                    &pp.workspace,
+                );
                 Self::CommitmentWithData {
                     codeword_tree,
                     polynomials_bh_evals: bh_evals,
@@ -444,7 +610,7 @@ where
             PolyEvalsCodeword::TooBig(num_vars) => return Err(Error::PolynomialTooLarge(num_vars)),
         };
 
-        end_timer!(timer);
+        profile_exit!(timer);
 
         Ok(ret)
     }
@@ -453,7 +619,7 @@ where
         comm: &Self::Commitment,
         transcript: &mut Transcript<E>,
     ) -> Result<(), Error> {
-        write_digest_to_transcript(&comm.root(), transcript);
+        transcript.append_message(&comm.to_bytes()?);
         Ok(())
     }
 
@@ -461,6 +627,14 @@ where
         comm.to_commitment()
     }
 
+    /// Return the buffers of the commitment to the workspace of `pp`.
+    fn recycle(pp: &Self::ProverParam, comm: Self::CommitmentWithData) {
+        pp.workspace.recycle_tree(comm.codeword_tree);
+        comm.polynomials_bh_evals
+            .into_iter()
+            .for_each(|evals| pp.workspace.recycle_field_type(evals));
+    }
+
     /// Open a single polynomial commitment at one point. If the given
     /// commitment with data contains more than one polynomial, this function
     /// will panic.
@@ -472,7 +646,7 @@ where
         _eval: &E, // Opening does not need eval, except for sanity check
         transcript: &mut Transcript<E>,
     ) -> Result<Self::Proof, Error> {
-        let timer = start_timer!(|| "Basefold::open");
+        let timer = profile_span!("Basefold::open");
 
         // The encoded polynomial should at least have the number of
         // variables of the basecode, i.e., the size of the message
@@ -501,7 +675,11 @@ where
             transcript,
             poly.num_vars,
             poly.num_vars - Spec::get_basecode_msg_size_log(),
-        );
+            pp.verification_level,
+            &pp.workspace,
+        )?;
+        let mut transcript_digests = vec![];
+        record_milestone(&mut transcript_digests, MILESTONE_COMMIT_PHASE, transcript);
 
         // 2. Query phase. ---------------------------------------
         //    Compute the query indices by Fiat-Shamir.
@@ -510,20 +688,25 @@ where
         //    (F, F, i) indicating the position opened at each round and
         //    the two values at that round
 
-        // 2.1 Prepare the answers. These include two values in each oracle,
-        //     in positions (i, i XOR 1), (i >> 1, (i >> 1) XOR 1), ...
-        //     respectively.
-        let query_timer = start_timer!(|| "Basefold::open::query_phase");
+        // 2.1 Prepare the answers. These include the two values at positions
+        //     (i, i XOR 1) in the commitment, then the coset of i >> layer in
+        //     each oracle committed along the folding schedule.
+        let query_timer = profile_span!("Basefold::open::query_phase");
         let queries = prover_query_phase(transcript, comm, &trees, Spec::get_number_queries());
-        end_timer!(query_timer);
+        record_milestone(&mut transcript_digests, MILESTONE_QUERY_PHASE, transcript);
+        profile_exit!(query_timer);
 
         // 2.2 Prepare the merkle paths for these answers.
-        let query_timer = start_timer!(|| "Basefold::open::build_query_result");
+        let query_timer = profile_span!("Basefold::open::build_query_result");
         let queries_with_merkle_path =
             QueriesResultWithMerklePath::from_query_result(queries, &trees, comm);
-        end_timer!(query_timer);
+        profile_exit!(query_timer);
+        // The oracles of the commit phase are no longer needed.
+        trees
+            .into_iter()
+            .for_each(|tree| pp.workspace.recycle_tree(tree));
 
-        end_timer!(timer);
+        profile_exit!(timer);
 
         // End of query phase.----------------------------------
 
@@ -536,6 +719,7 @@ where
             ),
             sumcheck_proof: None,
             trivial_proof: vec![],
+            transcript_digests,
         })
     }
 
@@ -552,7 +736,7 @@ where
         evals: &[Evaluation<E>],
         transcript: &mut Transcript<E>,
     ) -> Result<Self::Proof, Error> {
-        let timer = start_timer!(|| "Basefold::batch_open");
+        let timer = profile_span!("Basefold::batch_open");
         let num_vars = polys.iter().map(|poly| poly.num_vars).max().unwrap();
         let min_num_vars = polys.iter().map(|p| p.num_vars).min().unwrap();
         assert!(min_num_vars >= Spec::get_basecode_msg_size_log());
@@ -562,18 +746,18 @@ where
             assert!(!comm.is_trivial::<Spec>());
         });
 
-        if cfg!(feature = "sanity-check") {
-            evals.iter().for_each(|eval| {
-                assert_eq!(
-                    &polys[eval.poly()].evaluate(&points[eval.point()]),
-                    eval.value(),
-                )
-            })
+        if pp.verification_level.paranoid() {
+            for eval in evals {
+                sanity_check(
+                    &polys[eval.poly()].evaluate(&points[eval.point()]) == eval.value(),
+                    "opened polynomial evaluates to the claimed value",
+                )?;
+            }
         }
 
         validate_input("batch open", pp.get_max_message_size_log(), polys, points)?;
 
-        let sumcheck_timer = start_timer!(|| "Basefold::batch_open::initial sumcheck");
+        let sumcheck_timer = profile_span!("Basefold::batch_open::initial sumcheck");
         // evals.len() is the batch size, i.e., how many polynomials are being opened together
         let batch_size_log = evals.len().next_power_of_two().ilog2() as usize;
         let t = (0..batch_size_log)
@@ -637,24 +821,30 @@ where
         );
 
         let points = points.to_vec();
-        if cfg!(feature = "sanity-check") {
+        if pp.verification_level.paranoid() {
             let expected_sum = merged_polys
                 .iter()
                 .zip(&points)
                 .map(|((scalar, poly), point)| {
-                    inner_product(
-                        &poly_iter_ext(poly).collect_vec(),
-                        build_eq_x_r_vec(point).iter(),
-                    ) * scalar
+                    let eq = StructuredMle::eq_tensor(point);
+                    E::sum_of_products(poly_iter_ext(poly).zip_eq(eq.iter()))
+                        * scalar
                         * E::from(1 << (num_vars - poly.num_vars))
                     // When this polynomial is smaller, it will be repeatedly summed over the cosets of the hypercube
                 })
                 .sum::<E>();
-            assert_eq!(expected_sum, target_sum);
-
-            merged_polys.iter().enumerate().for_each(|(i, (_, poly))| {
-                assert_eq!(points[i].len(), poly.num_vars);
-            });
+            sanity_check(
+                expected_sum == target_sum,
+                "batched claims sum to the sumcheck target",
+            )?;
+        }
+        if pp.verification_level.cheap() {
+            for (point, (_, poly)) in points.iter().zip(&merged_polys) {
+                sanity_check(
+                    point.len() == poly.num_vars,
+                    "point length is the num_vars of its polynomial",
+                )?;
+            }
         }
 
         let expression = merged_polys
@@ -673,10 +863,17 @@ where
         let virtual_poly =
             VirtualPolynomial::new(&expression, sumcheck_polys, &[], points.as_slice());
 
-        let (challenges, merged_poly_evals, sumcheck_proof) =
-            SumCheck::prove(&(), num_vars, virtual_poly, target_sum, transcript)?;
+        let (challenges, merged_poly_evals, sumcheck_proof) = SumCheck::prove(
+            &pp.verification_level,
+            num_vars,
+            virtual_poly,
+            target_sum,
+            transcript,
+        )?;
+        let mut transcript_digests = vec![];
+        record_milestone(&mut transcript_digests, MILESTONE_BATCHING, transcript);
 
-        end_timer!(sumcheck_timer);
+        profile_exit!(sumcheck_timer);
 
         // Now the verifier has obtained the new target sum, and is able to compute the random
         // linear coefficients, and is able to evaluate eq_xy(point) for each poly to open.
@@ -692,62 +889,73 @@ where
             coeffs[eval.poly()] += eq_xy_evals[eval.point()] * poly_index_ext(&eq_xt, i);
         });
 
-        if cfg!(feature = "sanity-check") {
+        if pp.verification_level.paranoid() {
             let poly_evals = polys
                 .iter()
                 .map(|poly| poly.evaluate(&challenges[..poly.num_vars]))
                 .collect_vec();
-            let new_target_sum = inner_product(&poly_evals, &coeffs);
-            let desired_sum = merged_polys
-                .iter()
-                .zip(points)
-                .zip(merged_poly_evals)
-                .map(|(((scalar, poly), point), evals_from_sum_check)| {
-                    assert_eq!(
-                        evals_from_sum_check,
-                        poly.evaluate(&challenges[..poly.num_vars])
-                    );
-                    *scalar
-                        * evals_from_sum_check
-                        * eq_xy_eval(point.as_slice(), &challenges[0..point.len()])
-                })
-                .sum::<E>();
-            assert_eq!(new_target_sum, desired_sum);
+            let new_target_sum = inner_product_ext(&poly_evals, &coeffs);
+            let mut desired_sum = E::ZERO;
+            for (((scalar, poly), point), evals_from_sum_check) in
+                merged_polys.iter().zip(points).zip(merged_poly_evals)
+            {
+                sanity_check(
+                    evals_from_sum_check == poly.evaluate(&challenges[..poly.num_vars]),
+                    "sumcheck evaluations match the merged polynomials",
+                )?;
+                desired_sum += *scalar
+                    * evals_from_sum_check
+                    * eq_xy_eval(point.as_slice(), &challenges[0..point.len()]);
+            }
+            sanity_check(
+                new_target_sum == desired_sum,
+                "opened evaluations sum to the sumcheck result",
+            )?;
         }
         // Note that the verifier can also compute these coeffs locally, so no need to pass
         // them to the transcript.
 
         let point = challenges;
+        let comms = comms.iter().collect_vec();
 
         let (trees, commit_phase_proof) = batch_commit_phase::<E, Spec>(
             &pp.encoding_params,
             &point,
-            comms,
+            &comms,
             transcript,
             num_vars,
             num_vars - Spec::get_basecode_msg_size_log(),
             coeffs.as_slice(),
-        );
+            &[E::ONE],
+            pp.verification_level,
+            &pp.workspace,
+        )?;
+        record_milestone(&mut transcript_digests, MILESTONE_COMMIT_PHASE, transcript);
 
-        let query_timer = start_timer!(|| "Basefold::batch_open query phase");
+        let query_timer = profile_span!("Basefold::batch_open query phase");
         let query_result = batch_prover_query_phase(
             transcript,
             1 << (num_vars + Spec::get_rate_log()),
-            comms,
+            &comms,
             &trees,
             Spec::get_number_queries(),
         );
-        end_timer!(query_timer);
+        record_milestone(&mut transcript_digests, MILESTONE_QUERY_PHASE, transcript);
+        profile_exit!(query_timer);
 
-        let query_timer = start_timer!(|| "Basefold::batch_open build query result");
+        let query_timer = profile_span!("Basefold::batch_open build query result");
         let query_result_with_merkle_path =
             BatchedQueriesResultWithMerklePath::from_batched_query_result(
                 query_result,
                 &trees,
-                comms,
+                &comms,
             );
-        end_timer!(query_timer);
-        end_timer!(timer);
+        profile_exit!(query_timer);
+        // The oracles of the commit phase are no longer needed.
+        trees
+            .into_iter()
+            .for_each(|tree| pp.workspace.recycle_tree(tree));
+        profile_exit!(timer);
 
         Ok(Self::Proof {
             sumcheck_messages: commit_phase_proof.sumcheck_messages,
@@ -758,6 +966,7 @@ where
             ),
             sumcheck_proof: Some(sumcheck_proof),
             trivial_proof: vec![],
+            transcript_digests,
         })
     }
 
@@ -774,7 +983,7 @@ where
         evals: &[E],
         transcript: &mut Transcript<E>,
     ) -> Result<Self::Proof, Error> {
-        let timer = start_timer!(|| "Basefold::batch_open");
+        let timer = profile_span!("Basefold::batch_open");
         let num_vars = polys[0].num_vars();
 
         if comm.is_trivial::<Spec>() {
@@ -788,11 +997,13 @@ where
         assert_eq!(comm.num_polys, polys.len());
         assert_eq!(comm.num_polys, evals.len());
 
-        if cfg!(feature = "sanity-check") {
-            evals
-                .iter()
-                .zip(polys)
-                .for_each(|(eval, poly)| assert_eq!(&poly.evaluate(point), eval))
+        if pp.verification_level.paranoid() {
+            for (eval, poly) in evals.iter().zip(polys) {
+                sanity_check(
+                    &poly.evaluate(point) == eval,
+                    "opened polynomial evaluates to the claimed value",
+                )?;
+            }
         }
         // evals.len() is the batch size, i.e., how many polynomials are being opened together
         let batch_size_log = evals.len().next_power_of_two().ilog2() as usize;
@@ -808,7 +1019,7 @@ where
         // Note that this is a small polynomial (only batch_size) compared to the polynomials
         // to open.
         let eq_xt = build_eq_x_r_vec(&t)[..evals.len()].to_vec();
-        let _target_sum = inner_product(evals, &eq_xt);
+        let _target_sum = inner_product_ext(evals, &eq_xt);
 
         // Now the verifier has obtained the new target sum, and is able to compute the random
         // linear coefficients.
@@ -823,22 +1034,31 @@ where
             transcript,
             num_vars,
             num_vars - Spec::get_basecode_msg_size_log(),
-        );
+            pp.verification_level,
+            &pp.workspace,
+        )?;
+        let mut transcript_digests = vec![];
+        record_milestone(&mut transcript_digests, MILESTONE_COMMIT_PHASE, transcript);
 
-        let query_timer = start_timer!(|| "Basefold::open::query_phase");
+        let query_timer = profile_span!("Basefold::open::query_phase");
         // Each entry in queried_els stores a list of triples (F, F, i) indicating the
         // position opened at each round and the two values at that round
         let queries =
             simple_batch_prover_query_phase(transcript, comm, &trees, Spec::get_number_queries());
-        end_timer!(query_timer);
+        record_milestone(&mut transcript_digests, MILESTONE_QUERY_PHASE, transcript);
+        profile_exit!(query_timer);
 
-        let query_timer = start_timer!(|| "Basefold::open::build_query_result");
+        let query_timer = profile_span!("Basefold::open::build_query_result");
 
         let queries_with_merkle_path =
             SimpleBatchQueriesResultWithMerklePath::from_query_result(queries, &trees, comm);
-        end_timer!(query_timer);
+        profile_exit!(query_timer);
+        // The oracles of the commit phase are no longer needed.
+        trees
+            .into_iter()
+            .for_each(|tree| pp.workspace.recycle_tree(tree));
 
-        end_timer!(timer);
+        profile_exit!(timer);
 
         Ok(Self::Proof {
             sumcheck_messages: commit_phase_proof.sumcheck_messages,
@@ -849,6 +1069,212 @@ where
             ),
             sumcheck_proof: None,
             trivial_proof: vec![],
+            transcript_digests,
+        })
+    }
+    /// Open several batch commitments, each at its own point, with a single proof.
+    /// 1. The polynomials inside each commitment are combined with a random linear
+    ///    combination, turning every commitment into a single polynomial to open.
+    /// 2. The combined polynomials are opened together as in `batch_open`.
+    ///
+    /// The commitments that are too small to apply FRI are opened by sending all
+    /// their evaluations in `trivial_proof`.
+    ///
+    /// A point longer than the committed polynomials is truncated to their number
+    /// of variables.
+    fn multi_batch_open(
+        pp: &Self::ProverParam,
+        comms: &[&Self::CommitmentWithData],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        transcript: &mut Transcript<E>,
+    ) -> Result<Self::Proof, Error> {
+        let timer = profile_span!("Basefold::multi_batch_open");
+        assert_eq!(comms.len(), points.len());
+        assert_eq!(comms.len(), evals.len());
+        comms
+            .iter()
+            .zip(points)
+            .zip(evals)
+            .for_each(|((comm, point), evals)| {
+                assert!(comm.num_vars <= point.len());
+                assert_eq!(comm.num_polys, evals.len());
+            });
+
+        let trivial_proof = comms
+            .iter()
+            .filter(|comm| comm.is_trivial::<Spec>())
+            .flat_map(|comm| comm.polynomials_bh_evals.iter().cloned())
+            .collect_vec();
+
+        let (comms, (points, evals)): (Vec<_>, (Vec<_>, Vec<_>)) = comms
+            .iter()
+            .copied()
+            .zip(points.iter().zip(evals))
+            .filter(|(comm, _)| !comm.is_trivial::<Spec>())
+            .unzip();
+        if comms.is_empty() {
+            return Ok(Self::Proof::trivial(trivial_proof));
+        }
+        let points = comms
+            .iter()
+            .zip(points)
+            .map(|(comm, point)| point[..comm.num_vars].to_vec())
+            .collect_vec();
+        let num_vars = comms.iter().map(|comm| comm.num_vars).max().unwrap();
+
+        if pp.verification_level.paranoid() {
+            for ((comm, point), evals) in comms.iter().zip(&points).zip(evals) {
+                let mut bh_evals = comm.polynomials_bh_evals.clone();
+                bh_evals
+                    .iter_mut()
+                    .for_each(reverse_index_bits_in_place_field_type);
+                for (bh_evals, eval) in bh_evals.into_iter().zip(evals) {
+                    let poly = DenseMultilinearExtension {
+                        evaluations: bh_evals,
+                        num_vars: comm.num_vars,
+                    };
+                    sanity_check(
+                        &poly.evaluate(point) == eval,
+                        "opened polynomial evaluates to the claimed value",
+                    )?;
+                }
+            }
+        }
+
+        let sumcheck_timer = profile_span!("Basefold::multi_batch_open::initial sumcheck");
+        // Combine the polynomials inside each commitment with eq(X,t) where t is random.
+        let max_num_polys = comms.iter().map(|comm| comm.num_polys).max().unwrap();
+        let t = (0..max_num_polys.next_power_of_two().ilog2())
+            .map(|_| {
+                transcript
+                    .get_and_append_challenge(b"batch coeffs")
+                    .elements
+            })
+            .collect::<Vec<_>>();
+        let batch_coeffs = build_eq_x_r_vec(&t);
+        let polys = comms
+            .par_iter()
+            .map(|comm| {
+                let mut evals = (0..comm.poly_size())
+                    .into_par_iter()
+                    .map(|pos| {
+                        comm.polynomials_bh_evals
+                            .iter()
+                            .zip(&batch_coeffs)
+                            .map(|(bh_evals, coeff)| field_type_index_ext(bh_evals, pos) * coeff)
+                            .sum::<E>()
+                    })
+                    .collect::<Vec<_>>();
+                // The bh_evals are stored in big endian.
+                reverse_index_bits_in_place(&mut evals);
+                DenseMultilinearExtension::from_evaluations_ext_vec(comm.num_vars, evals)
+            })
+            .collect::<Vec<_>>();
+        let batched_evals = evals
+            .iter()
+            .map(|evals| inner_product_ext(evals.iter(), &batch_coeffs[..evals.len()]))
+            .collect_vec();
+
+        // Then batch the evaluation queries of the combined polynomials with eq(X,t').
+        let t = (0..comms.len().next_power_of_two().ilog2())
+            .map(|_| {
+                transcript
+                    .get_and_append_challenge(b"batch coeffs")
+                    .elements
+            })
+            .collect::<Vec<_>>();
+        let eq_xt = build_eq_x_r_vec(&t)[..comms.len()].to_vec();
+        // When a polynomial is smaller, it will be repeatedly summed over the cosets of the hypercube
+        let target_sum = inner_product_three(
+            &batched_evals,
+            &points
+                .iter()
+                .map(|point| E::from(1 << (num_vars - point.len())))
+                .collect_vec(),
+            &eq_xt,
+        );
+
+        let expression = eq_xt
+            .iter()
+            .enumerate()
+            .map(|(idx, scalar)| {
+                Expression::<E>::eq_xy(idx)
+                    * Expression::Polynomial(Query::new(idx, Rotation::cur()))
+                    * scalar
+            })
+            .sum();
+        let virtual_poly =
+            VirtualPolynomial::new(&expression, polys.iter(), &[], points.as_slice());
+
+        let (challenges, _, sumcheck_proof) = SumCheck::prove(
+            &pp.verification_level,
+            num_vars,
+            virtual_poly,
+            target_sum,
+            transcript,
+        )?;
+        let mut transcript_digests = vec![];
+        record_milestone(&mut transcript_digests, MILESTONE_BATCHING, transcript);
+        profile_exit!(sumcheck_timer);
+
+        let coeffs = points
+            .iter()
+            .zip(&eq_xt)
+            .map(|(point, eq_xt_i)| eq_xy_eval(&challenges[..point.len()], point) * eq_xt_i)
+            .collect_vec();
+
+        let point = challenges;
+
+        let (trees, commit_phase_proof) = batch_commit_phase::<E, Spec>(
+            &pp.encoding_params,
+            &point,
+            &comms,
+            transcript,
+            num_vars,
+            num_vars - Spec::get_basecode_msg_size_log(),
+            coeffs.as_slice(),
+            &batch_coeffs,
+            pp.verification_level,
+            &pp.workspace,
+        )?;
+        record_milestone(&mut transcript_digests, MILESTONE_COMMIT_PHASE, transcript);
+
+        let query_timer = profile_span!("Basefold::multi_batch_open query phase");
+        let query_result = batch_prover_query_phase(
+            transcript,
+            1 << (num_vars + Spec::get_rate_log()),
+            &comms,
+            &trees,
+            Spec::get_number_queries(),
+        );
+        record_milestone(&mut transcript_digests, MILESTONE_QUERY_PHASE, transcript);
+        profile_exit!(query_timer);
+
+        let query_timer = profile_span!("Basefold::multi_batch_open build query result");
+        let query_result_with_merkle_path =
+            BatchedQueriesResultWithMerklePath::from_batched_query_result(
+                query_result,
+                &trees,
+                &comms,
+            );
+        profile_exit!(query_timer);
+        // The oracles of the commit phase are no longer needed.
+        trees
+            .into_iter()
+            .for_each(|tree| pp.workspace.recycle_tree(tree));
+        profile_exit!(timer);
+
+        Ok(Self::Proof {
+            sumcheck_messages: commit_phase_proof.sumcheck_messages,
+            roots: commit_phase_proof.roots,
+            final_message: commit_phase_proof.final_message,
+            query_result_with_merkle_path: ProofQueriesResultWithMerklePath::Batched(
+                query_result_with_merkle_path,
+            ),
+            sumcheck_proof: Some(sumcheck_proof),
+            trivial_proof,
+            transcript_digests,
         })
     }
 
@@ -860,7 +1286,7 @@ where
         proof: &Self::Proof,
         transcript: &mut Transcript<E>,
     ) -> Result<(), Error> {
-        let timer = start_timer!(|| "Basefold::verify");
+        let timer = profile_span!("Basefold::verify");
 
         if proof.is_trivial() {
             let trivial_proof = &proof.trivial_proof;
@@ -873,15 +1299,14 @@ where
         }
 
         let num_vars = point.len();
-        if let Some(comm_num_vars) = comm.num_vars() {
-            assert_eq!(num_vars, comm_num_vars);
-            assert!(num_vars >= Spec::get_basecode_msg_size_log());
-        }
+        assert_eq!(num_vars, comm.num_vars());
+        assert!(num_vars >= Spec::get_basecode_msg_size_log());
         let num_rounds = num_vars - Spec::get_basecode_msg_size_log();
 
         let mut fold_challenges: Vec<E> = Vec::with_capacity(num_vars);
         let roots = &proof.roots;
         let sumcheck_messages = &proof.sumcheck_messages;
+        let mut committed_roots = roots.iter();
         for i in 0..num_rounds {
             transcript.append_field_element_exts(sumcheck_messages[i].as_slice());
             fold_challenges.push(
@@ -889,23 +1314,26 @@ where
                     .get_and_append_challenge(b"commit round")
                     .elements,
             );
-            if i < num_rounds - 1 {
-                write_digest_to_transcript(&roots[i], transcript);
+            if coset_log::<Spec::FoldingSchedule>(i + 1, num_rounds).is_some() {
+                let root = committed_roots.next().ok_or_else(|| {
+                    Error::InvalidPcsOpen(
+                        "fewer oracle roots than the folding schedule commits".to_string(),
+                    )
+                })?;
+                write_digest_to_transcript(root, transcript);
             }
         }
 
         let final_message = &proof.final_message;
         transcript.append_field_element_exts(final_message.as_slice());
+        check_milestone(proof, MILESTONE_COMMIT_PHASE, transcript)?;
 
-        let queries: Vec<_> = (0..Spec::get_number_queries())
-            .map(|_| {
-                ext_to_usize(
-                    &transcript
-                        .get_and_append_challenge(b"query indices")
-                        .elements,
-                ) % (1 << (num_vars + Spec::get_rate_log()))
-            })
-            .collect();
+        let queries = draw_query_indices(
+            transcript,
+            Spec::get_number_queries(),
+            1 << (num_vars + Spec::get_rate_log()),
+        );
+        check_milestone(proof, MILESTONE_QUERY_PHASE, transcript)?;
         let query_result_with_merkle_path = proof.query_result_with_merkle_path.as_single();
 
         // coeff is the eq polynomial evaluated at the last challenge.len() variables
@@ -915,9 +1343,10 @@ where
             &point[point.len() - fold_challenges.len()..],
             &rev_challenges,
         );
-        // Compute eq as the partially evaluated eq polynomial
-        let mut eq = build_eq_x_r_vec(&point[..point.len() - fold_challenges.len()]);
-        eq.par_iter_mut().for_each(|e| *e *= coeff);
+        // The partially evaluated eq polynomial is coeff * eq
+        let eq = vp
+            .eq_cache
+            .eq_x_r_vec(&point[..point.len() - fold_challenges.len()]);
 
         verifier_query_phase::<E, Spec>(
             queries.as_slice(),
@@ -930,10 +1359,11 @@ where
             final_message,
             roots,
             comm,
-            eq.as_slice(),
+            &eq,
+            coeff,
             eval,
         );
-        end_timer!(timer);
+        profile_exit!(timer);
 
         Ok(())
     }
@@ -946,24 +1376,21 @@ where
         proof: &Self::Proof,
         transcript: &mut Transcript<E>,
     ) -> Result<(), Error> {
-        let timer = start_timer!(|| "Basefold::batch_verify");
+        let timer = profile_span!("Basefold::batch_verify");
         // 	let key = "RAYON_NUM_THREADS";
         // 	env::set_var(key, "32");
         let comms = comms.iter().collect_vec();
         let num_vars = points.iter().map(|point| point.len()).max().unwrap();
         let num_rounds = num_vars - Spec::get_basecode_msg_size_log();
         validate_input("batch verify", num_vars, &[], points)?;
-        let poly_num_vars = comms.iter().map(|c| c.num_vars().unwrap()).collect_vec();
+        let poly_num_vars = comms.iter().map(|c| c.num_vars()).collect_vec();
         evals.iter().for_each(|eval| {
-            assert_eq!(
-                points[eval.point()].len(),
-                comms[eval.poly()].num_vars().unwrap()
-            );
+            assert_eq!(points[eval.point()].len(), comms[eval.poly()].num_vars());
         });
         assert!(poly_num_vars.iter().min().unwrap() >= &Spec::get_basecode_msg_size_log());
         assert!(!proof.is_trivial());
 
-        let sumcheck_timer = start_timer!(|| "Basefold::batch_verify::initial sumcheck");
+        let sumcheck_timer = profile_span!("Basefold::batch_verify::initial sumcheck");
         let batch_size_log = evals.len().next_power_of_two().ilog2() as usize;
         let t = (0..batch_size_log)
             .map(|_| {
@@ -992,7 +1419,8 @@ where
             proof.sumcheck_proof.as_ref().unwrap(),
             transcript,
         )?;
-        end_timer!(sumcheck_timer);
+        check_milestone(proof, MILESTONE_BATCHING, transcript)?;
+        profile_exit!(sumcheck_timer);
 
         // Now the goal is to use the BaseFold to check the new target sum. Note that this time
         // we only have one eq polynomial in the sum-check.
@@ -1021,16 +1449,14 @@ where
         }
         let final_message = &proof.final_message;
         transcript.append_field_element_exts(final_message.as_slice());
+        check_milestone(proof, MILESTONE_COMMIT_PHASE, transcript)?;
 
-        let queries: Vec<_> = (0..Spec::get_number_queries())
-            .map(|_| {
-                ext_to_usize(
-                    &transcript
-                        .get_and_append_challenge(b"query indices")
-                        .elements,
-                ) % (1 << (num_vars + Spec::get_rate_log()))
-            })
-            .collect();
+        let queries = draw_query_indices(
+            transcript,
+            Spec::get_number_queries(),
+            1 << (num_vars + Spec::get_rate_log()),
+        );
+        check_milestone(proof, MILESTONE_QUERY_PHASE, transcript)?;
         let query_result_with_merkle_path = proof.query_result_with_merkle_path.as_batched();
 
         // coeff is the eq polynomial evaluated at the last challenge.len() variables
@@ -1040,11 +1466,10 @@ where
             &verify_point.as_slice()[verify_point.len() - fold_challenges.len()..],
             &rev_challenges,
         );
-        // Compute eq as the partially evaluated eq polynomial
-        let mut eq = build_eq_x_r_vec(
-            &verify_point.as_slice()[..verify_point.len() - fold_challenges.len()],
-        );
-        eq.par_iter_mut().for_each(|e| *e *= coeff);
+        // The partially evaluated eq polynomial is coeff * eq
+        let eq = vp
+            .eq_cache
+            .eq_x_r_vec(&verify_point[..verify_point.len() - fold_challenges.len()]);
 
         batch_verifier_query_phase::<E, Spec>(
             queries.as_slice(),
@@ -1058,10 +1483,12 @@ where
             roots,
             &comms,
             &coeffs,
-            eq.as_slice(),
+            &[E::ONE],
+            &eq,
+            coeff,
             &new_target_sum,
         );
-        end_timer!(timer);
+        profile_exit!(timer);
         Ok(())
     }
 
@@ -1073,11 +1500,9 @@ where
         proof: &Self::Proof,
         transcript: &mut Transcript<E>,
     ) -> Result<(), Error> {
-        let timer = start_timer!(|| "Basefold::simple batch verify");
+        let timer = profile_span!("Basefold::simple batch verify");
         let batch_size = evals.len();
-        if let Some(num_polys) = comm.num_polys {
-            assert_eq!(num_polys, batch_size);
-        }
+        assert_eq!(comm.num_polys, batch_size);
 
         if proof.is_trivial() {
             let trivial_proof = &proof.trivial_proof;
@@ -1090,10 +1515,8 @@ where
         }
 
         let num_vars = point.len();
-        if let Some(comm_num_vars) = comm.num_vars() {
-            assert_eq!(num_vars, comm_num_vars);
-            assert!(num_vars >= Spec::get_basecode_msg_size_log());
-        }
+        assert_eq!(num_vars, comm.num_vars());
+        assert!(num_vars >= Spec::get_basecode_msg_size_log());
         let num_rounds = num_vars - Spec::get_basecode_msg_size_log();
 
         // evals.len() is the batch size, i.e., how many polynomials are being opened together
@@ -1110,6 +1533,7 @@ where
         let mut fold_challenges: Vec<E> = Vec::with_capacity(num_vars);
         let roots = &proof.roots;
         let sumcheck_messages = &proof.sumcheck_messages;
+        let mut committed_roots = roots.iter();
         for i in 0..num_rounds {
             transcript.append_field_element_exts(sumcheck_messages[i].as_slice());
             fold_challenges.push(
@@ -1117,22 +1541,25 @@ where
                     .get_and_append_challenge(b"commit round")
                     .elements,
             );
-            if i < num_rounds - 1 {
-                write_digest_to_transcript(&roots[i], transcript);
+            if coset_log::<Spec::FoldingSchedule>(i + 1, num_rounds).is_some() {
+                let root = committed_roots.next().ok_or_else(|| {
+                    Error::InvalidPcsOpen(
+                        "fewer oracle roots than the folding schedule commits".to_string(),
+                    )
+                })?;
+                write_digest_to_transcript(root, transcript);
             }
         }
         let final_message = &proof.final_message;
         transcript.append_field_element_exts(final_message.as_slice());
+        check_milestone(proof, MILESTONE_COMMIT_PHASE, transcript)?;
 
-        let queries: Vec<_> = (0..Spec::get_number_queries())
-            .map(|_| {
-                ext_to_usize(
-                    &transcript
-                        .get_and_append_challenge(b"query indices")
-                        .elements,
-                ) % (1 << (num_vars + Spec::get_rate_log()))
-            })
-            .collect();
+        let queries = draw_query_indices(
+            transcript,
+            Spec::get_number_queries(),
+            1 << (num_vars + Spec::get_rate_log()),
+        );
+        check_milestone(proof, MILESTONE_QUERY_PHASE, transcript)?;
         let query_result_with_merkle_path = proof.query_result_with_merkle_path.as_simple_batched();
 
         // coeff is the eq polynomial evaluated at the last challenge.len() variables
@@ -1142,9 +1569,10 @@ where
             &point[point.len() - fold_challenges.len()..],
             &rev_challenges,
         );
-        // Compute eq as the partially evaluated eq polynomial
-        let mut eq = build_eq_x_r_vec(&point[..point.len() - fold_challenges.len()]);
-        eq.par_iter_mut().for_each(|e| *e *= coeff);
+        // The partially evaluated eq polynomial is coeff * eq
+        let eq = vp
+            .eq_cache
+            .eq_x_r_vec(&point[..point.len() - fold_challenges.len()]);
 
         simple_batch_verifier_query_phase::<E, Spec>(
             queries.as_slice(),
@@ -1158,11 +1586,186 @@ where
             final_message,
             roots,
             comm,
-            eq.as_slice(),
+            &eq,
+            coeff,
             evals,
         );
-        end_timer!(timer);
+        profile_exit!(timer);
+
+        Ok(())
+    }
+
+    fn multi_batch_verify(
+        vp: &Self::VerifierParam,
+        comms: &[&Self::Commitment],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        proof: &Self::Proof,
+        transcript: &mut Transcript<E>,
+    ) -> Result<(), Error> {
+        let timer = profile_span!("Basefold::multi_batch_verify");
+        assert_eq!(comms.len(), points.len());
+        assert_eq!(comms.len(), evals.len());
+
+        // The commitments too small to apply FRI are opened in the clear.
+        let mut trivial_proof = proof.trivial_proof.iter();
+        for ((comm, point), evals) in comms.iter().zip(points).zip(evals) {
+            let num_vars = comm.num_vars();
+            assert!(num_vars <= point.len());
+            if !BasefoldCommitmentWithData::<E>::trivial_num_vars::<Spec>(num_vars) {
+                continue;
+            }
+            let bh_evals = trivial_proof
+                .by_ref()
+                .take(evals.len())
+                .cloned()
+                .collect_vec();
+            if bh_evals.len() != evals.len() {
+                return Err(Error::InvalidPcsOpen(
+                    "missing evaluations of a trivial commitment".to_string(),
+                ));
+            }
+            if comm.root() != MerkleTree::from_batch_leaves(bh_evals.clone()).root() {
+                return Err(Error::MerkleRootMismatch);
+            }
+            for (bh_evals, eval) in bh_evals.into_iter().zip(evals) {
+                let poly = DenseMultilinearExtension {
+                    evaluations: bh_evals,
+                    num_vars,
+                };
+                if &poly.evaluate(&point[..num_vars]) != eval {
+                    return Err(Error::InvalidPcsOpen(
+                        "evaluation of a trivial commitment mismatch".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let (comms, (points, evals)): (Vec<_>, (Vec<_>, Vec<_>)) = comms
+            .iter()
+            .copied()
+            .zip(points.iter().zip(evals))
+            .filter(|(comm, _)| {
+                !BasefoldCommitmentWithData::<E>::trivial_num_vars::<Spec>(comm.num_vars())
+            })
+            .unzip();
+        if comms.is_empty() {
+            return Ok(());
+        }
+        let points = comms
+            .iter()
+            .zip(points)
+            .map(|(comm, point)| point[..comm.num_vars()].to_vec())
+            .collect_vec();
+        let num_vars = points.iter().map(|point| point.len()).max().unwrap();
+        let num_rounds = num_vars - Spec::get_basecode_msg_size_log();
+        validate_input("multi batch verify", num_vars, &[], &points)?;
+
+        let sumcheck_timer = profile_span!("Basefold::multi_batch_verify::initial sumcheck");
+        let max_num_polys = evals.iter().map(|evals| evals.len()).max().unwrap();
+        let t = (0..max_num_polys.next_power_of_two().ilog2())
+            .map(|_| {
+                transcript
+                    .get_and_append_challenge(b"batch coeffs")
+                    .elements
+            })
+            .collect::<Vec<_>>();
+        let batch_coeffs = build_eq_x_r_vec(&t);
+        let batched_evals = evals
+            .iter()
+            .map(|evals| inner_product_ext(evals.iter(), &batch_coeffs[..evals.len()]))
+            .collect_vec();
+
+        let t = (0..comms.len().next_power_of_two().ilog2())
+            .map(|_| {
+                transcript
+                    .get_and_append_challenge(b"batch coeffs")
+                    .elements
+            })
+            .collect::<Vec<_>>();
+        let eq_xt = build_eq_x_r_vec(&t)[..comms.len()].to_vec();
+        let target_sum = inner_product_three(
+            &batched_evals,
+            &points
+                .iter()
+                .map(|point| E::from(1 << (num_vars - point.len())))
+                .collect_vec(),
+            &eq_xt,
+        );
 
+        let (new_target_sum, verify_point) = SumCheck::verify(
+            &(),
+            num_vars,
+            2,
+            target_sum,
+            proof.sumcheck_proof.as_ref().unwrap(),
+            transcript,
+        )?;
+        check_milestone(proof, MILESTONE_BATCHING, transcript)?;
+        profile_exit!(sumcheck_timer);
+
+        let coeffs = points
+            .iter()
+            .zip(&eq_xt)
+            .map(|(point, eq_xt_i)| eq_xy_eval(&verify_point[..point.len()], point) * eq_xt_i)
+            .collect_vec();
+
+        let mut fold_challenges: Vec<E> = Vec::with_capacity(num_vars);
+        let roots = &proof.roots;
+        let sumcheck_messages = &proof.sumcheck_messages;
+        for i in 0..num_rounds {
+            transcript.append_field_element_exts(sumcheck_messages[i].as_slice());
+            fold_challenges.push(
+                transcript
+                    .get_and_append_challenge(b"commit round")
+                    .elements,
+            );
+            if i < num_rounds - 1 {
+                write_digest_to_transcript(&roots[i], transcript);
+            }
+        }
+        let final_message = &proof.final_message;
+        transcript.append_field_element_exts(final_message.as_slice());
+        check_milestone(proof, MILESTONE_COMMIT_PHASE, transcript)?;
+
+        let queries = draw_query_indices(
+            transcript,
+            Spec::get_number_queries(),
+            1 << (num_vars + Spec::get_rate_log()),
+        );
+        check_milestone(proof, MILESTONE_QUERY_PHASE, transcript)?;
+        let query_result_with_merkle_path = proof.query_result_with_merkle_path.as_batched();
+
+        // coeff is the eq polynomial evaluated at the last challenge.len() variables
+        // in reverse order.
+        let rev_challenges = fold_challenges.clone().into_iter().rev().collect_vec();
+        let coeff = eq_xy_eval(
+            &verify_point.as_slice()[verify_point.len() - fold_challenges.len()..],
+            &rev_challenges,
+        );
+        // The partially evaluated eq polynomial is coeff * eq
+        let eq = vp
+            .eq_cache
+            .eq_x_r_vec(&verify_point[..verify_point.len() - fold_challenges.len()]);
+
+        batch_verifier_query_phase::<E, Spec>(
+            queries.as_slice(),
+            &vp.encoding_params,
+            query_result_with_merkle_path,
+            sumcheck_messages,
+            &fold_challenges,
+            num_rounds,
+            num_vars,
+            final_message,
+            roots,
+            &comms,
+            &coeffs,
+            &batch_coeffs,
+            &eq,
+            coeff,
+            &new_target_sum,
+        );
+        profile_exit!(timer);
         Ok(())
     }
 }
@@ -1177,18 +1780,388 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
-        basefold::Basefold,
+        Error, PolynomialCommitmentScheme, VerificationLevel,
+        basefold::{
+            Basefold, BasefoldCommitment, BasefoldCompactCommitmentWithData, BasefoldOpeningClaim,
+            query_phase::draw_query_indices,
+        },
         test_util::{
             run_batch_commit_open_verify, run_commit_open_verify,
+            run_multi_batch_commit_open_verify, run_partial_commit_open_verify,
             run_simple_batch_commit_open_verify,
         },
+        util::ext_to_usize,
+    };
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use itertools::{Itertools, izip};
+    use multilinear_extensions::{
+        mle::{DenseMultilinearExtension, MultilinearExtension},
+        virtual_poly_v2::ArcMultilinearExtension,
+    };
+    use rand::{Rng, SeedableRng, rngs::OsRng};
+    use rand_chacha::ChaCha8Rng;
+    use transcript::Transcript;
+
+    use super::{
+        BasefoldRSParams,
+        structure::{
+            BasefoldBasecodeParams, BasefoldRSHighArityParams, BasefoldRSWideLeavesParams,
+        },
     };
-    use goldilocks::GoldilocksExt2;
-
-    use super::{BasefoldRSParams, structure::BasefoldBasecodeParams};
 
     type PcsGoldilocksRSCode = Basefold<GoldilocksExt2, BasefoldRSParams>;
     type PcsGoldilocksBaseCode = Basefold<GoldilocksExt2, BasefoldBasecodeParams>;
+    type PcsGoldilocksRSHighArity = Basefold<GoldilocksExt2, BasefoldRSHighArityParams<3>>;
+
+    #[test]
+    fn query_indices_are_distinct() {
+        // Many more queries than indices, as in the codeword of a small polynomial.
+        let mut transcript = Transcript::<GoldilocksExt2>::new(b"test");
+        let indices = draw_query_indices(&mut transcript, 100, 8);
+        assert_eq!(
+            indices.iter().sorted().copied().collect_vec(),
+            (0..8).collect_vec()
+        );
+
+        // Repeats are redrawn until there are as many indices as queries.
+        let mut transcript = Transcript::<GoldilocksExt2>::new(b"test");
+        let indices = draw_query_indices(&mut transcript, 20, 32);
+        assert_eq!(indices.len(), 20);
+        assert!(indices.iter().all_unique());
+
+        // The same indices as drawn one by one.
+        let mut transcript = Transcript::<GoldilocksExt2>::new(b"test");
+        let drawn = (0..)
+            .map(|_| {
+                ext_to_usize(
+                    &transcript
+                        .get_and_append_challenge(b"query indices")
+                        .elements,
+                ) % 32
+            })
+            .unique()
+            .take(20)
+            .collect_vec();
+        assert_eq!(indices, drawn);
+    }
+
+    #[test]
+    fn commitment_bytes_roundtrip() {
+        let (pp, _) = {
+            let param = PcsGoldilocksRSCode::setup(1 << 10).unwrap();
+            PcsGoldilocksRSCode::trim(param, 1 << 10).unwrap()
+        };
+        let polys = (0..3)
+            .map(|_| DenseMultilinearExtension::random(10, &mut OsRng))
+            .collect::<Vec<_>>();
+        let comm = PcsGoldilocksRSCode::get_pure_commitment(
+            &PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap(),
+        );
+
+        let bytes = comm.to_bytes().unwrap();
+        let decoded = BasefoldCommitment::<GoldilocksExt2>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.root(), comm.root());
+        assert_eq!(decoded.num_vars(), 10);
+        assert_eq!(decoded.num_polys, 3);
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+
+        let too_many_vars = BasefoldCommitment::<GoldilocksExt2>::new(comm.root(), 256, true, 3);
+        assert!(matches!(
+            too_many_vars.to_bytes(),
+            Err(Error::Serialization(_))
+        ));
+
+        let mut other_version = bytes.clone();
+        *other_version.iter_mut().rev().nth(4).unwrap() ^= 0x30;
+        assert!(BasefoldCommitment::<GoldilocksExt2>::from_bytes(&other_version).is_err());
+        assert!(BasefoldCommitment::<GoldilocksExt2>::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn compact_commitment_hydrates() {
+        let (pp, vp) = setup_rscode();
+        let (polys, point, evals) = random_simple_batch(3);
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+
+        let stored = serde_json::to_vec(&comm.to_compact()).unwrap();
+        let compact: BasefoldCompactCommitmentWithData<GoldilocksExt2> =
+            serde_json::from_slice(&stored).unwrap();
+        assert_eq!(compact.to_commitment().root(), comm.to_commitment().root());
+        assert!(PcsGoldilocksRSCode::hydrate(&pp, compact.clone(), &polys[1..]).is_err());
+        let (other_polys, _, _) = random_simple_batch(3);
+        assert!(matches!(
+            PcsGoldilocksRSCode::hydrate(&pp, compact.clone(), &other_polys),
+            Err(Error::MerkleRootMismatch)
+        ));
+
+        let hydrated = PcsGoldilocksRSCode::hydrate(&pp, compact, &polys).unwrap();
+        assert!(hydrated == comm);
+        let polys = polys
+            .into_iter()
+            .map(|poly| poly.into())
+            .collect::<Vec<ArcMultilinearExtension<_>>>();
+        let proof = PcsGoldilocksRSCode::simple_batch_open(
+            &pp,
+            &polys,
+            &hydrated,
+            &point,
+            &evals,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+        PcsGoldilocksRSCode::simple_batch_verify(
+            &vp,
+            &hydrated.to_commitment(),
+            &point,
+            &evals,
+            &proof,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+    }
+
+    /// Random polynomials with 10 variables, a random point, and their evaluations.
+    fn random_simple_batch(
+        num_polys: usize,
+    ) -> (
+        Vec<DenseMultilinearExtension<GoldilocksExt2>>,
+        Vec<GoldilocksExt2>,
+        Vec<GoldilocksExt2>,
+    ) {
+        let polys = (0..num_polys)
+            .map(|_| DenseMultilinearExtension::random(10, &mut OsRng))
+            .collect::<Vec<_>>();
+        let point = (0..10)
+            .map(|_| GoldilocksExt2::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|poly| poly.evaluate(&point))
+            .collect::<Vec<_>>();
+        (polys, point, evals)
+    }
+
+    fn setup_rscode() -> (
+        <PcsGoldilocksRSCode as PolynomialCommitmentScheme<GoldilocksExt2>>::ProverParam,
+        <PcsGoldilocksRSCode as PolynomialCommitmentScheme<GoldilocksExt2>>::VerifierParam,
+    ) {
+        let param = PcsGoldilocksRSCode::setup(1 << 10).unwrap();
+        PcsGoldilocksRSCode::trim(param, 1 << 10).unwrap()
+    }
+
+    #[test]
+    fn simple_batch_open_paranoid() {
+        let (pp, _) = setup_rscode();
+        let pp = pp.with_verification_level(VerificationLevel::Paranoid);
+        let (polys, point, mut evals) = random_simple_batch(3);
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        let polys = polys
+            .into_iter()
+            .map(|poly| poly.into())
+            .collect::<Vec<ArcMultilinearExtension<_>>>();
+
+        let open = |evals: &[GoldilocksExt2]| {
+            let mut transcript = Transcript::new(b"BaseFold");
+            PcsGoldilocksRSCode::simple_batch_open(
+                &pp,
+                &polys,
+                &comm,
+                &point,
+                evals,
+                &mut transcript,
+            )
+        };
+        assert!(open(&evals).is_ok());
+        evals[0] += GoldilocksExt2::ONE;
+        assert!(matches!(open(&evals), Err(Error::SanityCheck(_))));
+    }
+
+    #[test]
+    fn constant_polys_commit_open_verify() {
+        let (pp, vp) = setup_rscode();
+        let (mut polys, point, _) = random_simple_batch(1);
+        polys.push(DenseMultilinearExtension::from_evaluations_vec(10, vec![
+            Goldilocks::from(7);
+            1 << 10
+        ]));
+        polys.push(DenseMultilinearExtension::from_evaluations_vec(10, vec![
+            Goldilocks::ZERO;
+            1 << 10
+        ]));
+        let evals = polys
+            .iter()
+            .map(|poly| poly.evaluate(&point))
+            .collect::<Vec<_>>();
+        assert_eq!(evals[1], GoldilocksExt2::from(7));
+
+        // The codewords of the constant polynomials are filled in without
+        // encoding, and the opening still verifies.
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        let polys = polys
+            .into_iter()
+            .map(|poly| poly.into())
+            .collect::<Vec<ArcMultilinearExtension<_>>>();
+        let proof = PcsGoldilocksRSCode::simple_batch_open(
+            &pp,
+            &polys,
+            &comm,
+            &point,
+            &evals,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+        let comm = PcsGoldilocksRSCode::get_pure_commitment(&comm);
+        PcsGoldilocksRSCode::simple_batch_verify(
+            &vp,
+            &comm,
+            &point,
+            &evals,
+            &proof,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+    }
+
+    /// Open `poly` at `point` under `Pcs`, and return whether the verifier accepts `eval` and
+    /// whether it accepts a wrong evaluation.
+    fn open_and_verify<Pcs: PolynomialCommitmentScheme<GoldilocksExt2>>(
+        poly: &DenseMultilinearExtension<GoldilocksExt2>,
+        point: &[GoldilocksExt2],
+        eval: GoldilocksExt2,
+    ) -> (bool, bool) {
+        let param = Pcs::setup(1 << 10).unwrap();
+        let (pp, vp) = Pcs::trim(param, 1 << 10).unwrap();
+        let comm = Pcs::commit(&pp, poly).unwrap();
+        let proof = Pcs::open(
+            &pp,
+            poly,
+            &comm,
+            point,
+            &eval,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+        let comm = Pcs::get_pure_commitment(&comm);
+        let verify = |eval| {
+            Pcs::verify(
+                &vp,
+                &comm,
+                point,
+                &eval,
+                &proof,
+                &mut Transcript::new(b"BaseFold"),
+            )
+            .is_ok()
+        };
+        (verify(eval), verify(eval + GoldilocksExt2::ONE))
+    }
+
+    #[test]
+    fn encodings_agree_on_random_openings() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..4 {
+            // Including polynomials small enough to be opened in the clear.
+            let num_vars = rng.gen_range(4..=10);
+            let poly = DenseMultilinearExtension::random(num_vars, &mut rng);
+            let point = (0..num_vars)
+                .map(|_| GoldilocksExt2::random(&mut rng))
+                .collect::<Vec<_>>();
+            let eval = poly.evaluate(&point);
+            let results = [
+                open_and_verify::<PcsGoldilocksRSCode>(&poly, &point, eval),
+                open_and_verify::<PcsGoldilocksBaseCode>(&poly, &point, eval),
+                open_and_verify::<PcsGoldilocksRSHighArity>(&poly, &point, eval),
+            ];
+            assert_eq!(results, [(true, false); 3], "num_vars = {num_vars}");
+        }
+    }
+
+    #[test]
+    fn verify_many_reports_each_result() {
+        let (pp, vp) = setup_rscode();
+        let (polys, point, mut evals) = random_simple_batch(3);
+        let comms = polys
+            .iter()
+            .map(|poly| PcsGoldilocksRSCode::commit(&pp, poly).unwrap())
+            .collect::<Vec<_>>();
+        let proofs = izip!(&polys, &comms, &evals)
+            .map(|(poly, comm, eval)| {
+                let mut transcript = Transcript::new(b"BaseFold");
+                PcsGoldilocksRSCode::open(&pp, poly, comm, &point, eval, &mut transcript).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let comms = comms
+            .iter()
+            .map(PcsGoldilocksRSCode::get_pure_commitment)
+            .collect::<Vec<_>>();
+
+        evals[1] += GoldilocksExt2::ONE;
+        let claims = comms
+            .iter()
+            .zip(&evals)
+            .map(|(comm, eval)| BasefoldOpeningClaim {
+                comm,
+                point: &point,
+                eval: *eval,
+                transcript: Transcript::new(b"BaseFold"),
+            })
+            .collect();
+        let results = PcsGoldilocksRSCode::verify_many(&vp, claims, &proofs);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn transcript_divergence_is_located() {
+        let (pp, vp) = setup_rscode();
+        let (polys, point, evals) = random_simple_batch(3);
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        let polys = polys
+            .into_iter()
+            .map(|poly| poly.into())
+            .collect::<Vec<ArcMultilinearExtension<_>>>();
+        let proof = PcsGoldilocksRSCode::simple_batch_open(
+            &pp,
+            &polys,
+            &comm,
+            &point,
+            &evals,
+            &mut Transcript::new(b"BaseFold"),
+        )
+        .unwrap();
+        let comm = PcsGoldilocksRSCode::get_pure_commitment(&comm);
+
+        // The verifier absorbs a message that the prover did not.
+        let mut transcript = Transcript::new(b"BaseFold");
+        transcript.append_message(b"extra");
+        let result = PcsGoldilocksRSCode::simple_batch_verify(
+            &vp,
+            &comm,
+            &point,
+            &evals,
+            &proof,
+            &mut transcript,
+        );
+        assert!(matches!(result, Err(Error::Transcript(e)) if e.contains("commit phase")));
+    }
+
+    #[test]
+    fn recycled_commitment_buffers_are_reused() {
+        let (pp, _) = setup_rscode();
+        let (polys, _, _) = random_simple_batch(3);
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        let root = comm.to_commitment().root();
+        PcsGoldilocksRSCode::recycle(&pp, comm);
+        let pooled_bytes = pp.workspace().pooled_bytes();
+        assert!(pooled_bytes > 0);
+
+        // Committing again takes its buffers from the pool, and gives the same result.
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        assert_eq!(comm.to_commitment().root(), root);
+        assert!(pp.workspace().pooled_bytes() < pooled_bytes);
+    }
 
     #[test]
     fn commit_open_verify_goldilocks_basecode_base() {
@@ -1272,6 +2245,105 @@ mod test {
         run_simple_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(false, 4, 6, 4);
     }
 
+    #[test]
+    fn commit_open_verify_goldilocks_rscode_high_arity() {
+        run_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSHighArity>(true, 10, 11);
+        run_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSHighArity>(false, 10, 11);
+        run_simple_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSHighArity>(
+            true, 10, 11, 4,
+        );
+        run_simple_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSHighArity>(
+            false, 10, 11, 4,
+        );
+        // Polynomials of different sizes fall back to binary folding.
+        run_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSHighArity>(true, 10, 11);
+    }
+
+    type PcsGoldilocksRSWideLeaves = Basefold<GoldilocksExt2, BasefoldRSWideLeavesParams<3>>;
+
+    #[test]
+    fn commit_open_verify_goldilocks_rscode_wide_leaves() {
+        run_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSWideLeaves>(true, 10, 11);
+        run_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSWideLeaves>(false, 10, 11);
+        run_simple_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSWideLeaves>(
+            true, 10, 11, 4,
+        );
+        run_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSWideLeaves>(false, 10, 11);
+
+        let param = PcsGoldilocksRSWideLeaves::setup(1 << 10).unwrap();
+        let (pp, _) = PcsGoldilocksRSWideLeaves::trim(param, 1 << 10).unwrap();
+        let (polys, _, _) = random_simple_batch(3);
+        let comm = PcsGoldilocksRSWideLeaves::batch_commit(&pp, &polys).unwrap();
+        let pair_comm = PcsGoldilocksRSCode::batch_commit(&setup_rscode().0, &polys).unwrap();
+        assert_eq!(comm.codeword_tree.leaf_width_log(), 3);
+        assert_eq!(comm.codeword_size_log(), pair_comm.codeword_size_log());
+        assert_eq!(
+            comm.codeword_tree
+                .merkle_path_without_leaf_sibling_or_root(5)
+                .len()
+                + 2,
+            pair_comm
+                .codeword_tree
+                .merkle_path_without_leaf_sibling_or_root(5)
+                .len()
+        );
+
+        let compact = comm.to_compact();
+        assert!(PcsGoldilocksRSWideLeaves::hydrate(&pp, compact, &polys).unwrap() == comm);
+    }
+
+    #[test]
+    fn high_arity_proof_is_smaller() {
+        let (polys, point, evals) = random_simple_batch(3);
+        let polys_arc = polys
+            .iter()
+            .map(|poly| poly.clone().into())
+            .collect::<Vec<ArcMultilinearExtension<_>>>();
+
+        let (pp, _) = setup_rscode();
+        let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+        let mut transcript = Transcript::new(b"BaseFold");
+        let binary = PcsGoldilocksRSCode::simple_batch_open(
+            &pp,
+            &polys_arc,
+            &comm,
+            &point,
+            &evals,
+            &mut transcript,
+        )
+        .unwrap();
+
+        let param = PcsGoldilocksRSHighArity::setup(1 << 10).unwrap();
+        let (pp, vp) = PcsGoldilocksRSHighArity::trim(param, 1 << 10).unwrap();
+        let comm = PcsGoldilocksRSHighArity::batch_commit(&pp, &polys).unwrap();
+        let mut transcript = Transcript::new(b"BaseFold");
+        let high_arity = PcsGoldilocksRSHighArity::simple_batch_open(
+            &pp,
+            &polys_arc,
+            &comm,
+            &point,
+            &evals,
+            &mut transcript,
+        )
+        .unwrap();
+        let mut transcript = Transcript::new(b"BaseFold");
+        PcsGoldilocksRSHighArity::simple_batch_verify(
+            &vp,
+            &PcsGoldilocksRSHighArity::get_pure_commitment(&comm),
+            &point,
+            &evals,
+            &high_arity,
+            &mut transcript,
+        )
+        .unwrap();
+
+        assert!(high_arity.roots.len() < binary.roots.len());
+        assert!(
+            serde_json::to_vec(&high_arity).unwrap().len()
+                < serde_json::to_vec(&binary).unwrap().len()
+        );
+    }
+
     #[test]
     fn batch_commit_open_verify_goldilocks_basecode_base() {
         // Both challenge and poly are over base field
@@ -1295,4 +2367,26 @@ mod test {
         // Both challenge and poly are over extension field
         run_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(false, 10, 11);
     }
+
+    #[test]
+    fn multi_batch_commit_open_verify_goldilocks_basecode_base() {
+        // Both challenge and poly are over base field
+        run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksBaseCode>(
+            true, 10, 11, 3,
+        );
+    }
+
+    #[test]
+    fn multi_batch_commit_open_verify_goldilocks_rscode_2() {
+        // Both challenge and poly are over extension field
+        run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(false, 10, 11, 3);
+        // Test trivial proof with small num vars
+        run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(false, 4, 6, 3);
+    }
+
+    #[test]
+    fn partial_commit_open_verify_goldilocks_rscode() {
+        // Including partial polynomials too small to apply FRI, and the whole point.
+        run_partial_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(10, 3..11);
+    }
 }
@@ -1,32 +1,39 @@
 use super::{
     encoding::EncodingScheme,
+    folding::{OracleTrees, coset_log},
     structure::{BasefoldCommitPhaseProof, BasefoldSpec},
     sumcheck::{
         sum_check_challenge_round, sum_check_first_round, sum_check_first_round_field_type,
         sum_check_last_round,
     },
 };
-use crate::util::{
-    arithmetic::{interpolate_over_boolean_hypercube, interpolate2_weights},
-    field_type_index_ext, field_type_iter_ext,
-    hash::write_digest_to_transcript,
-    log2_strict,
-    merkle_tree::MerkleTree,
+use crate::{
+    Error, VerificationLevel,
+    util::{
+        arithmetic::{degree_2_compress, hypercube_interpolate_in_place, interpolate2_weights},
+        field_type_index_ext, field_type_iter_ext,
+        hash::write_digest_to_transcript,
+        log2_strict,
+        merkle_tree::MerkleTree,
+        profile::{profile_bytes, profile_exit, profile_span},
+        prover_workspace::ProverWorkspace,
+        sanity_check,
+    },
 };
-use ark_std::{end_timer, start_timer};
+
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use serde::{Serialize, de::DeserializeOwned};
 use transcript::Transcript;
 
-use multilinear_extensions::{mle::FieldType, virtual_poly::build_eq_x_r_vec};
-
-use crate::util::plonky2_util::reverse_index_bits_in_place;
-use rayon::prelude::{
-    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
-    ParallelSlice,
+use multilinear_extensions::{
+    endian::BigEndianMle,
+    mle::{DenseMultilinearExtension, FieldType},
+    virtual_poly::build_eq_x_r_vec,
 };
 
+use crate::util::{parallel::prelude::*, plonky2_util::reverse_index_bits_in_place};
+
 use super::structure::BasefoldCommitmentWithData;
 
 // outputs (trees, sumcheck_oracles, oracles, bh_evals, eq, eval)
@@ -37,37 +44,46 @@ pub fn commit_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     transcript: &mut Transcript<E>,
     num_vars: usize,
     num_rounds: usize,
-) -> (Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>)
+    level: VerificationLevel,
+    workspace: &ProverWorkspace<E>,
+) -> Result<(Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>), Error>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Commit phase");
-    #[cfg(feature = "sanity-check")]
-    assert_eq!(point.len(), num_vars);
-    let mut trees = Vec::with_capacity(num_vars);
+    let timer = profile_span!("Commit phase");
+    if level.cheap() {
+        sanity_check(point.len() == num_vars, "point length is num_vars")?;
+    }
+    let mut trees = OracleTrees::new(workspace);
     let mut running_oracle = field_type_iter_ext(&comm.get_codewords()[0]).collect_vec();
     let mut running_evals = comm.polynomials_bh_evals[0].clone();
 
-    #[cfg(feature = "sanity-check")]
-    assert_eq!(
-        running_oracle.len(),
-        running_evals.len() << Spec::get_rate_log()
-    );
-    #[cfg(feature = "sanity-check")]
-    assert_eq!(running_evals.len(), 1 << num_vars);
+    if level.cheap() {
+        sanity_check(
+            running_oracle.len() == running_evals.len() << Spec::get_rate_log(),
+            "codeword length is the message length times the rate",
+        )?;
+        sanity_check(
+            running_evals.len() == 1 << num_vars,
+            "message length is 2^num_vars",
+        )?;
+    }
 
     // eq is the evaluation representation of the eq(X,r) polynomial over the hypercube
-    let build_eq_timer = start_timer!(|| "Basefold::open");
-    let mut eq = build_eq_x_r_vec(point);
-    end_timer!(build_eq_timer);
-    reverse_index_bits_in_place(&mut eq);
+    let build_eq_timer = profile_span!("Basefold::open");
+    let mut eq = big_endian_eq(point);
+    profile_exit!(build_eq_timer);
 
-    let sumcheck_timer = start_timer!(|| "Basefold sumcheck first round");
+    let sumcheck_timer = profile_span!("Basefold sumcheck first round");
     let mut last_sumcheck_message = sum_check_first_round_field_type(&mut eq, &mut running_evals);
-    end_timer!(sumcheck_timer);
+    profile_exit!(sumcheck_timer);
 
-    #[cfg(feature = "sanity-check")]
-    assert_eq!(last_sumcheck_message.len(), 3);
+    if level.cheap() {
+        sanity_check(
+            last_sumcheck_message.len() == 3,
+            "first sumcheck message has 3 evaluations",
+        )?;
+    }
 
     let mut running_evals = match running_evals {
         FieldType::Ext(evals) => evals,
@@ -78,74 +94,57 @@ where
     let mut sumcheck_messages = Vec::with_capacity(num_rounds);
     let mut roots = Vec::with_capacity(num_rounds - 1);
     let mut final_message = Vec::new();
-    let mut running_tree_inner = Vec::new();
+    let mut pending_challenges = Vec::new();
     for i in 0..num_rounds {
-        let sumcheck_timer = start_timer!(|| format!("Basefold round {}", i));
+        let sumcheck_timer = profile_span!("Basefold round", round = i);
         // For the first round, no need to send the running root, because this root is
         // committing to a vector that can be recovered from linearly combining other
         // already-committed vectors.
-        transcript.append_field_element_exts(&last_sumcheck_message);
-        sumcheck_messages.push(last_sumcheck_message);
+        let compressed_message = degree_2_compress(&last_sumcheck_message);
+        transcript.append_field_element_exts(&compressed_message);
+        sumcheck_messages.push(compressed_message);
 
         let challenge = transcript.get_and_append_challenge(b"commit round");
-
-        // Fold the current oracle for FRI
-        let new_running_oracle = basefold_one_round_by_interpolation_weights::<E, Spec>(
-            pp,
-            log2_strict(running_oracle.len()) - 1,
-            &running_oracle,
-            challenge.elements,
-        );
-
-        if i > 0 {
-            let running_tree = MerkleTree::<E>::from_inner_leaves(
-                running_tree_inner,
-                FieldType::Ext(running_oracle),
-            );
-            trees.push(running_tree);
-        }
+        pending_challenges.push(challenge.elements);
 
         if i < num_rounds - 1 {
             last_sumcheck_message =
                 sum_check_challenge_round(&mut eq, &mut running_evals, challenge.elements);
 
-            // To avoid cloning the running oracle, explicitly separate the
-            // computation of Merkle tree inner nodes and the building of
-            // entire Merkle tree. First compute the inner nodes without
-            // consuming the leaves, so that we can get the challenge.
-            // Then the oracle will be used to fold to the next oracle in the next
-            // round. After that, this oracle is free to be moved to build the
-            // complete Merkle tree.
-            running_tree_inner = MerkleTree::<E>::compute_inner_ext(&new_running_oracle);
-            let running_root = MerkleTree::<E>::root_from_inner(&running_tree_inner);
-            write_digest_to_transcript(&running_root, transcript);
-            roots.push(running_root.clone());
-
-            running_oracle = new_running_oracle;
+            // Only the oracles of the folding schedule are committed, so the oracle is folded
+            // through the rounds in between at once. The verifier folds the others by itself
+            // from the cosets opened in the committed ones.
+            if let Some(coset_log) = coset_log::<Spec::FoldingSchedule>(i + 1, num_rounds) {
+                let new_running_oracle =
+                    fold_oracle::<E, Spec>(pp, &running_oracle, &mut pending_challenges);
+                trees.folded(running_oracle);
+                let running_root = trees.commit(&new_running_oracle, coset_log);
+                write_digest_to_transcript(&running_root, transcript);
+                roots.push(running_root);
+                running_oracle = new_running_oracle;
+            }
         } else {
-            // Clear this so the compiler knows the old value is safe to move.
-            last_sumcheck_message = Vec::new();
-            running_oracle = Vec::new();
-            running_tree_inner = Vec::new();
+            let new_running_oracle =
+                fold_oracle::<E, Spec>(pp, &running_oracle, &mut pending_challenges);
+            trees.folded(std::mem::take(&mut running_oracle));
             // The difference of the last round is that we don't need to compute the message,
             // and we don't interpolate the small polynomials. So after the last round,
             // running_evals is exactly the evaluation representation of the
             // folded polynomial so far.
             sum_check_last_round(&mut eq, &mut running_evals, challenge.elements);
             // For the FRI part, we send the current polynomial as the message.
-            // Transform it back into little endiean before sending it
-            reverse_index_bits_in_place(&mut running_evals);
-            transcript.append_field_element_exts(&running_evals);
-            final_message = running_evals;
+            // Transform it back into little endian before sending it
+            final_message = into_little_endian(running_evals);
+            transcript.append_field_element_exts(&final_message);
             // To prevent the compiler from complaining that the value is moved
             running_evals = Vec::new();
 
-            if cfg!(feature = "sanity-check") {
+            if level.paranoid() {
                 // If the prover is honest, in the last round, the running oracle
                 // on the prover side should be exactly the encoding of the folded polynomial.
 
                 let mut coeffs = final_message.clone();
-                interpolate_over_boolean_hypercube(&mut coeffs);
+                hypercube_interpolate_in_place(&mut coeffs);
                 if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
                     reverse_index_bits_in_place(&mut coeffs);
                 }
@@ -160,40 +159,48 @@ where
 
                 let mut new_running_oracle = new_running_oracle;
                 reverse_index_bits_in_place(&mut new_running_oracle);
-                assert_eq!(basecode, new_running_oracle);
+                sanity_check(
+                    basecode == new_running_oracle,
+                    "last oracle is the encoding of the final message",
+                )?;
             }
         }
-        end_timer!(sumcheck_timer);
+        profile_exit!(sumcheck_timer);
     }
-    end_timer!(timer);
+    profile_exit!(timer);
 
-    (trees, BasefoldCommitPhaseProof {
+    Ok((trees.into_trees(), BasefoldCommitPhaseProof {
         sumcheck_messages,
         roots,
         final_message,
-    })
+    }))
 }
 
 // outputs (trees, sumcheck_oracles, oracles, bh_evals, eq, eval)
+// The polynomials inside each commitment are first combined with `batch_coeffs`, then the
+// combined polynomials of all the commitments are combined with `coeffs`.
 #[allow(clippy::too_many_arguments)]
 pub fn batch_commit_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     pp: &<Spec::EncodingScheme as EncodingScheme<E>>::ProverParameters,
     point: &[E],
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     transcript: &mut Transcript<E>,
     num_vars: usize,
     num_rounds: usize,
     coeffs: &[E],
-) -> (Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>)
+    batch_coeffs: &[E],
+    level: VerificationLevel,
+    workspace: &ProverWorkspace<E>,
+) -> Result<(Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>), Error>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Batch Commit phase");
+    let timer = profile_span!("Batch Commit phase");
     assert_eq!(point.len(), num_vars);
     let mut trees = Vec::with_capacity(num_vars);
     let mut running_oracle = vec![E::ZERO; 1 << (num_vars + Spec::get_rate_log())];
 
-    let build_oracle_timer = start_timer!(|| "Basefold build initial oracle");
+    let build_oracle_timer = profile_span!("Basefold build initial oracle");
     // Before the interaction, collect all the polynomials whose num variables match the
     // max num variables
     let running_oracle_len = running_oracle.len();
@@ -204,12 +211,12 @@ where
         .for_each(|(index, comm)| {
             running_oracle
                 .iter_mut()
-                .zip_eq(field_type_iter_ext(&comm.get_codewords()[0]))
+                .zip_eq(comm.batch_codewords(batch_coeffs))
                 .for_each(|(r, a)| *r += a * coeffs[index]);
         });
-    end_timer!(build_oracle_timer);
+    profile_exit!(build_oracle_timer);
 
-    let build_oracle_timer = start_timer!(|| "Basefold build initial sumcheck evals");
+    let build_oracle_timer = profile_span!("Basefold build initial sumcheck evals");
     // Unlike the FRI part, the sum-check part still follows the original procedure,
     // and linearly combine all the polynomials once for all
     let mut sum_of_all_evals_for_sumcheck = vec![E::ZERO; 1 << num_vars];
@@ -224,34 +231,39 @@ where
                 // to align the polynomials to the variable with index 0 before adding them
                 // together. So each element is repeated by
                 // sum_of_all_evals_for_sumcheck.len() / bh_evals.len() times
-                *r += field_type_index_ext(
-                    &comm.polynomials_bh_evals[0],
-                    pos >> (num_vars - log2_strict(comm.polynomials_bh_evals[0].len())),
-                ) * coeffs[index]
+                let pos = pos >> (num_vars - log2_strict(comm.polynomials_bh_evals[0].len()));
+                *r += comm
+                    .polynomials_bh_evals
+                    .iter()
+                    .zip(batch_coeffs)
+                    .map(|(bh_evals, batch_coeff)| {
+                        field_type_index_ext(bh_evals, pos) * batch_coeff
+                    })
+                    .sum::<E>()
+                    * coeffs[index]
             });
     });
-    end_timer!(build_oracle_timer);
+    profile_exit!(build_oracle_timer);
 
     // eq is the evaluation representation of the eq(X,r) polynomial over the hypercube
-    let mut eq = build_eq_x_r_vec(point);
-    reverse_index_bits_in_place(&mut eq);
+    let mut eq = big_endian_eq(point);
 
-    let sumcheck_timer = start_timer!(|| "Basefold first round");
+    let sumcheck_timer = profile_span!("Basefold first round");
     let mut sumcheck_messages = Vec::with_capacity(num_rounds + 1);
     let mut last_sumcheck_message =
         sum_check_first_round(&mut eq, &mut sum_of_all_evals_for_sumcheck);
-    sumcheck_messages.push(last_sumcheck_message.clone());
-    end_timer!(sumcheck_timer);
+    sumcheck_messages.push(degree_2_compress(&last_sumcheck_message));
+    profile_exit!(sumcheck_timer);
 
     let mut roots = Vec::with_capacity(num_rounds - 1);
     let mut final_message = Vec::new();
     let mut running_tree_inner = Vec::new();
     for i in 0..num_rounds {
-        let sumcheck_timer = start_timer!(|| format!("Batch basefold round {}", i));
+        let sumcheck_timer = profile_span!("Batch basefold round", round = i);
         // For the first round, no need to send the running root, because this root is
         // committing to a vector that can be recovered from linearly combining other
         // already-committed vectors.
-        transcript.append_field_element_exts(&last_sumcheck_message);
+        transcript.append_field_element_exts(&degree_2_compress(&last_sumcheck_message));
 
         let challenge = transcript
             .get_and_append_challenge(b"commit round")
@@ -264,6 +276,10 @@ where
             &running_oracle,
             challenge,
         );
+        profile_bytes!(
+            "folded_oracle",
+            std::mem::size_of_val(new_running_oracle.as_slice())
+        );
 
         if i > 0 {
             let running_tree = MerkleTree::<E>::from_inner_leaves(
@@ -276,8 +292,9 @@ where
         if i < num_rounds - 1 {
             last_sumcheck_message =
                 sum_check_challenge_round(&mut eq, &mut sum_of_all_evals_for_sumcheck, challenge);
-            sumcheck_messages.push(last_sumcheck_message.clone());
-            running_tree_inner = MerkleTree::<E>::compute_inner_ext(&new_running_oracle);
+            sumcheck_messages.push(degree_2_compress(&last_sumcheck_message));
+            running_tree_inner =
+                MerkleTree::<E>::compute_inner_ext_in(&new_running_oracle, workspace);
             let running_root = MerkleTree::<E>::root_from_inner(&running_tree_inner);
             write_digest_to_transcript(&running_root, transcript);
             roots.push(running_root);
@@ -291,7 +308,7 @@ where
                 .for_each(|(index, comm)| {
                     new_running_oracle
                         .iter_mut()
-                        .zip_eq(field_type_iter_ext(&comm.get_codewords()[0]))
+                        .zip_eq(comm.batch_codewords(batch_coeffs))
                         .for_each(|(r, a)| *r += a * coeffs[index]);
                 });
             running_oracle = new_running_oracle;
@@ -305,14 +322,13 @@ where
             // folded polynomial so far.
             sum_check_last_round(&mut eq, &mut sum_of_all_evals_for_sumcheck, challenge);
             // For the FRI part, we send the current polynomial as the message.
-            // Transform it back into little endiean before sending it
-            reverse_index_bits_in_place(&mut sum_of_all_evals_for_sumcheck);
-            transcript.append_field_element_exts(&sum_of_all_evals_for_sumcheck);
-            final_message = sum_of_all_evals_for_sumcheck;
+            // Transform it back into little endian before sending it
+            final_message = into_little_endian(sum_of_all_evals_for_sumcheck);
+            transcript.append_field_element_exts(&final_message);
             // To prevent the compiler from complaining that the value is moved
             sum_of_all_evals_for_sumcheck = Vec::new();
 
-            if cfg!(feature = "sanity-check") {
+            if level.paranoid() {
                 // If the prover is honest, in the last round, the running oracle
                 // on the prover side should be exactly the encoding of the folded polynomial.
 
@@ -320,7 +336,7 @@ where
                 if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
                     reverse_index_bits_in_place(&mut coeffs);
                 }
-                interpolate_over_boolean_hypercube(&mut coeffs);
+                hypercube_interpolate_in_place(&mut coeffs);
                 let basecode = <Spec::EncodingScheme as EncodingScheme<E>>::encode(
                     pp,
                     &FieldType::Ext(coeffs),
@@ -331,17 +347,20 @@ where
                 };
 
                 reverse_index_bits_in_place(&mut new_running_oracle);
-                assert_eq!(basecode, new_running_oracle);
+                sanity_check(
+                    basecode == new_running_oracle,
+                    "last oracle is the encoding of the final message",
+                )?;
             }
         }
-        end_timer!(sumcheck_timer);
+        profile_exit!(sumcheck_timer);
     }
-    end_timer!(timer);
-    (trees, BasefoldCommitPhaseProof {
+    profile_exit!(timer);
+    Ok((trees, BasefoldCommitPhaseProof {
         sumcheck_messages,
         roots,
         final_message,
-    })
+    }))
 }
 
 // outputs (trees, sumcheck_oracles, oracles, bh_evals, eq, eval)
@@ -354,18 +373,20 @@ pub fn simple_batch_commit_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     transcript: &mut Transcript<E>,
     num_vars: usize,
     num_rounds: usize,
-) -> (Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>)
+    level: VerificationLevel,
+    workspace: &ProverWorkspace<E>,
+) -> Result<(Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>), Error>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Simple batch commit phase");
+    let timer = profile_span!("Simple batch commit phase");
     assert_eq!(point.len(), num_vars);
     assert_eq!(comm.num_polys, batch_coeffs.len());
-    let prepare_timer = start_timer!(|| "Prepare");
-    let mut trees = Vec::with_capacity(num_vars);
-    let batch_codewords_timer = start_timer!(|| "Batch codewords");
+    let prepare_timer = profile_span!("Prepare");
+    let mut trees = OracleTrees::new(workspace);
+    let batch_codewords_timer = profile_span!("Batch codewords");
     let mut running_oracle = comm.batch_codewords(batch_coeffs);
-    end_timer!(batch_codewords_timer);
+    profile_exit!(batch_codewords_timer);
     let mut running_evals = (0..(1 << num_vars))
         .into_par_iter()
         .map(|i| {
@@ -376,81 +397,64 @@ where
                 .sum()
         })
         .collect::<Vec<_>>();
-    end_timer!(prepare_timer);
+    profile_exit!(prepare_timer);
 
     // eq is the evaluation representation of the eq(X,r) polynomial over the hypercube
-    let build_eq_timer = start_timer!(|| "Basefold::build eq");
-    let mut eq = build_eq_x_r_vec(point);
-    end_timer!(build_eq_timer);
+    let build_eq_timer = profile_span!("Basefold::build eq");
+    let mut eq = big_endian_eq(point);
+    profile_exit!(build_eq_timer);
 
-    let reverse_bits_timer = start_timer!(|| "Basefold::reverse bits");
-    reverse_index_bits_in_place(&mut eq);
-    end_timer!(reverse_bits_timer);
-
-    let sumcheck_timer = start_timer!(|| "Basefold sumcheck first round");
+    let sumcheck_timer = profile_span!("Basefold sumcheck first round");
     let mut last_sumcheck_message = sum_check_first_round(&mut eq, &mut running_evals);
-    end_timer!(sumcheck_timer);
+    profile_exit!(sumcheck_timer);
 
     let mut sumcheck_messages = Vec::with_capacity(num_rounds);
     let mut roots = Vec::with_capacity(num_rounds - 1);
     let mut final_message = Vec::new();
-    let mut running_tree_inner = Vec::new();
+    let mut pending_challenges = Vec::new();
     for i in 0..num_rounds {
-        let sumcheck_timer = start_timer!(|| format!("Basefold round {}", i));
+        let sumcheck_timer = profile_span!("Basefold round", round = i);
         // For the first round, no need to send the running root, because this root is
         // committing to a vector that can be recovered from linearly combining other
         // already-committed vectors.
-        transcript.append_field_element_exts(&last_sumcheck_message);
-        sumcheck_messages.push(last_sumcheck_message);
+        let compressed_message = degree_2_compress(&last_sumcheck_message);
+        transcript.append_field_element_exts(&compressed_message);
+        sumcheck_messages.push(compressed_message);
 
         let challenge = transcript
             .get_and_append_challenge(b"commit round")
             .elements;
-
-        // Fold the current oracle for FRI
-        let new_running_oracle = basefold_one_round_by_interpolation_weights::<E, Spec>(
-            pp,
-            log2_strict(running_oracle.len()) - 1,
-            &running_oracle,
-            challenge,
-        );
-
-        if i > 0 {
-            let running_tree = MerkleTree::<E>::from_inner_leaves(
-                running_tree_inner,
-                FieldType::Ext(running_oracle),
-            );
-            trees.push(running_tree);
-        }
+        pending_challenges.push(challenge);
 
         if i < num_rounds - 1 {
             last_sumcheck_message =
                 sum_check_challenge_round(&mut eq, &mut running_evals, challenge);
-            running_tree_inner = MerkleTree::<E>::compute_inner_ext(&new_running_oracle);
-            let running_root = MerkleTree::<E>::root_from_inner(&running_tree_inner);
-            write_digest_to_transcript(&running_root, transcript);
-            roots.push(running_root);
-            running_oracle = new_running_oracle;
+            if let Some(coset_log) = coset_log::<Spec::FoldingSchedule>(i + 1, num_rounds) {
+                let new_running_oracle =
+                    fold_oracle::<E, Spec>(pp, &running_oracle, &mut pending_challenges);
+                trees.folded(running_oracle);
+                let running_root = trees.commit(&new_running_oracle, coset_log);
+                write_digest_to_transcript(&running_root, transcript);
+                roots.push(running_root);
+                running_oracle = new_running_oracle;
+            }
         } else {
-            // Assign a new value to the old running vars so that the compiler
-            // knows the old value is safe to move.
-            last_sumcheck_message = Vec::new();
-            running_oracle = Vec::new();
-            running_tree_inner = Vec::new();
+            let new_running_oracle =
+                fold_oracle::<E, Spec>(pp, &running_oracle, &mut pending_challenges);
+            trees.folded(std::mem::take(&mut running_oracle));
             // The difference of the last round is that we don't need to compute the message,
             // and we don't interpolate the small polynomials. So after the last round,
             // running_evals is exactly the evaluation representation of the
             // folded polynomial so far.
             sum_check_last_round(&mut eq, &mut running_evals, challenge);
             // For the FRI part, we send the current polynomial as the message.
-            // Transform it back into little endiean before sending it
-            reverse_index_bits_in_place(&mut running_evals);
-            transcript.append_field_element_exts(&running_evals);
-            final_message = running_evals;
+            // Transform it back into little endian before sending it
+            final_message = into_little_endian(running_evals);
+            transcript.append_field_element_exts(&final_message);
             // To avoid the compiler complaining that running_evals is moved.
             running_evals = Vec::new();
 
-            if cfg!(feature = "sanity-check") {
+            if level.paranoid() {
                 // If the prover is honest, in the last round, the running oracle
                 // on the prover side should be exactly the encoding of the folded polynomial.
 
@@ -458,7 +462,7 @@ where
                 if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
                     reverse_index_bits_in_place(&mut coeffs);
                 }
-                interpolate_over_boolean_hypercube(&mut coeffs);
+                hypercube_interpolate_in_place(&mut coeffs);
                 let basecode = <Spec::EncodingScheme as EncodingScheme<E>>::encode(
                     pp,
                     &FieldType::Ext(coeffs),
@@ -470,17 +474,58 @@ where
 
                 let mut new_running_oracle = new_running_oracle;
                 reverse_index_bits_in_place(&mut new_running_oracle);
-                assert_eq!(basecode, new_running_oracle);
+                sanity_check(
+                    basecode == new_running_oracle,
+                    "last oracle is the encoding of the final message",
+                )?;
             }
         }
-        end_timer!(sumcheck_timer);
+        profile_exit!(sumcheck_timer);
     }
-    end_timer!(timer);
-    (trees, BasefoldCommitPhaseProof {
+    profile_exit!(timer);
+    Ok((trees.into_trees(), BasefoldCommitPhaseProof {
         sumcheck_messages,
         roots,
         final_message,
-    })
+    }))
+}
+
+/// The evaluations of `eq(X, point)` over the hypercube, in big endian as the sumcheck of the
+/// commit phase folds the last variable first.
+fn big_endian_eq<E: ExtensionField>(point: &[E]) -> Vec<E> {
+    let eq =
+        DenseMultilinearExtension::from_evaluations_ext_vec(point.len(), build_eq_x_r_vec(point));
+    match eq.into_big_endian().evaluations_to_owned() {
+        FieldType::Ext(eq) => eq,
+        _ => unreachable!(),
+    }
+}
+
+/// Reorder the big endian evaluations of the folded polynomial into the little endian message.
+fn into_little_endian<E: ExtensionField>(evals: Vec<E>) -> Vec<E> {
+    let num_vars = log2_strict(evals.len());
+    let mle = DenseMultilinearExtension::from_evaluations_ext_vec(num_vars, evals);
+    match BigEndianMle::from_big_endian(mle)
+        .into_little_endian()
+        .evaluations
+    {
+        FieldType::Ext(evals) => evals,
+        _ => unreachable!(),
+    }
+}
+
+/// Fold `oracle` for FRI with the challenges of the rounds since its last fold, and clear them.
+fn fold_oracle<E: ExtensionField, Spec: BasefoldSpec<E>>(
+    pp: &<Spec::EncodingScheme as EncodingScheme<E>>::ProverParameters,
+    oracle: &[E],
+    challenges: &mut Vec<E>,
+) -> Vec<E> {
+    let folded = <Spec::EncodingScheme as EncodingScheme<E>>::fold_bitreversed_codeword_radix(
+        pp, oracle, challenges,
+    );
+    challenges.clear();
+    profile_bytes!("folded_oracle", std::mem::size_of_val(folded.as_slice()));
+    folded
 }
 
 fn basefold_one_round_by_interpolation_weights<E: ExtensionField, Spec: BasefoldSpec<E>>(
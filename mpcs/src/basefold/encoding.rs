@@ -7,11 +7,8 @@ mod basecode;
 pub use basecode::{Basecode, BasecodeDefaultSpec};
 
 mod rs;
+use crate::util::parallel::prelude::*;
 use plonky2::util::log2_strict;
-use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
-    slice::ParallelSlice,
-};
 pub use rs::{RSCode, RSCodeDefaultSpec, coset_fft, fft, fft_root_table};
 
 use serde::{Serialize, de::DeserializeOwned};
@@ -41,6 +38,12 @@ pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
 
     fn encode(pp: &Self::ProverParameters, coeffs: &FieldType<E>) -> FieldType<E>;
 
+    /// Same as [`Self::encode`], but the allocation of `coeffs` may be reused
+    /// for the codeword, so it pays to reserve enough capacity for it.
+    fn encode_owned(pp: &Self::ProverParameters, coeffs: FieldType<E>) -> FieldType<E> {
+        Self::encode(pp, &coeffs)
+    }
+
     /// Encodes a message of small length, such that the verifier is also able
     /// to execute the encoding.
     fn encode_small(vp: &Self::VerifierParameters, coeffs: &FieldType<E>) -> FieldType<E>;
@@ -51,6 +54,12 @@ pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
 
     fn get_basecode_msg_size_log() -> usize;
 
+    /// The number of binary folds that the verifier applies at once to a coset of
+    /// `2^arity_log` positions, e.g. 2 for 4-to-1 and 3 for 8-to-1 folds.
+    fn get_folding_arity_log() -> usize {
+        1
+    }
+
     /// Whether the message needs to be bit-reversed to allow even-odd
     /// folding. If the folding is already even-odd style (like RS code),
     /// then set this function to return false. If the folding is originally
@@ -61,6 +70,13 @@ pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
         !Self::message_is_left_and_right_folding()
     }
 
+    /// Whether the codeword of a constant message is constant, with the
+    /// same value. If so, the prover skips the encoding of constant
+    /// polynomials, which are common among the witness columns.
+    fn constant_message_has_constant_codeword() -> bool {
+        false
+    }
+
     /// Returns three values: x0, x1 and 1/(x1-x0). Note that although
     /// 1/(x1-x0) can be computed from the other two values, we return it
     /// separately because inversion is expensive.
@@ -114,6 +130,52 @@ pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
         }
     }
 
+    /// Fold the given codeword into a codeword `2^challenges.len()` times smaller, with one
+    /// binary fold per challenge. The result is the same as folding with
+    /// `fold_bitreversed_codeword` for every challenge, without the intermediate codewords.
+    fn fold_bitreversed_codeword_radix(
+        pp: &Self::ProverParameters,
+        codeword: &[E],
+        challenges: &[E],
+    ) -> Vec<E> {
+        let level = log2_strict(codeword.len()) - 1;
+        if let [challenge] = challenges {
+            return codeword
+                .par_chunks_exact(2)
+                .enumerate()
+                .map(|(i, ys)| {
+                    let (x0, x1, w) = Self::prover_folding_coeffs(pp, level, i);
+                    interpolate2_weights([(x0, ys[0]), (x1, ys[1])], w, *challenge)
+                })
+                .collect();
+        }
+        codeword
+            .par_chunks_exact(1 << challenges.len())
+            .enumerate()
+            .map(|(i, ys)| {
+                fold_coset(ys.to_vec(), level, i, challenges, |level, index| {
+                    Self::prover_folding_coeffs(pp, level, index)
+                })
+            })
+            .collect()
+    }
+
+    /// Fold the coset `coset_index` of `2^challenges.len()` consecutive positions of a
+    /// bit-reversed codeword into one position, as `fold_bitreversed_codeword_radix` does,
+    /// with the folding coefficients of the verifier. `level` is the
+    /// level of the first fold, i.e. the logarithmic size of the codeword minus one.
+    fn verifier_fold_coset(
+        vp: &Self::VerifierParameters,
+        level: usize,
+        coset_index: usize,
+        coset: Vec<E>,
+        challenges: &[E],
+    ) -> E {
+        fold_coset(coset, level, coset_index, challenges, |level, index| {
+            Self::verifier_folding_coeffs(vp, level, index)
+        })
+    }
+
     /// Fold the given message into a smaller message of half size using challenge
     /// as the random linear combination coefficient.
     /// Note that this is always even-odd fold, assuming the message has
@@ -134,6 +196,34 @@ pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
     }
 }
 
+/// Fold a coset of `2^challenges.len()` consecutive positions of a bit-reversed codeword of
+/// size `2^(level + 1)` with one binary fold per challenge. `coeffs(level, index)` gives the
+/// folding coefficients of the pair `index` at `level`.
+fn fold_coset<E: ExtensionField>(
+    coset: Vec<E>,
+    level: usize,
+    coset_index: usize,
+    challenges: &[E],
+    coeffs: impl Fn(usize, usize) -> (E, E, E),
+) -> E {
+    assert_eq!(coset.len(), 1 << challenges.len());
+    let folded = challenges
+        .iter()
+        .enumerate()
+        .fold(coset, |coset, (round, challenge)| {
+            let offset = coset_index << (challenges.len() - round - 1);
+            coset
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(i, ys)| {
+                    let (x0, x1, w) = coeffs(level - round, offset + i);
+                    interpolate2_weights([(x0, ys[0]), (x1, ys[1])], w, *challenge)
+                })
+                .collect()
+        });
+    folded[0]
+}
+
 fn concatenate_field_types<E: ExtensionField>(coeffs: &[FieldType<E>]) -> FieldType<E> {
     match coeffs[0] {
         FieldType::Ext(_) => {
@@ -166,7 +256,9 @@ pub(crate) mod test_util {
     use multilinear_extensions::mle::FieldType;
     use rand::rngs::OsRng;
 
-    use crate::util::plonky2_util::reverse_index_bits_in_place_field_type;
+    use crate::util::{
+        field_type_iter_ext, log2_strict, plonky2_util::reverse_index_bits_in_place_field_type,
+    };
 
     use super::EncodingScheme;
 
@@ -233,4 +325,45 @@ pub(crate) mod test_util {
             folded_codeword = FieldType::Ext(folded_codeword_vec);
         }
     }
+
+    pub fn test_codeword_folding_radix<E: ExtensionField, Code: EncodingScheme<E>>() {
+        let num_vars = 10;
+
+        let poly: Vec<E> = (0..(1 << num_vars)).map(|i| E::from(i)).collect();
+        let poly = FieldType::Ext(poly);
+
+        let pp: Code::PublicParameters = Code::setup(num_vars);
+        let (pp, vp) = Code::trim(pp, num_vars).unwrap();
+        let mut codeword = Code::encode(&pp, &poly);
+        reverse_index_bits_in_place_field_type(&mut codeword);
+        let level = log2_strict(codeword.len()) - 1;
+
+        for arity_log in 1..=3 {
+            let challenges = (0..arity_log)
+                .map(|_| E::random(&mut OsRng))
+                .collect::<Vec<_>>();
+
+            let folded_codeword =
+                challenges
+                    .iter()
+                    .fold(codeword.clone(), |codeword, challenge| {
+                        FieldType::Ext(Code::fold_bitreversed_codeword(&pp, &codeword, *challenge))
+                    });
+            let folded_codeword = field_type_iter_ext(&folded_codeword).collect::<Vec<_>>();
+            let codeword_ext = field_type_iter_ext(&codeword).collect::<Vec<_>>();
+            assert_eq!(
+                Code::fold_bitreversed_codeword_radix(&pp, &codeword_ext, &challenges),
+                folded_codeword,
+                "arity {arity_log}"
+            );
+
+            for (coset_index, coset) in codeword_ext.chunks_exact(1 << arity_log).enumerate() {
+                assert_eq!(
+                    Code::verifier_fold_coset(&vp, level, coset_index, coset.to_vec(), &challenges),
+                    folded_codeword[coset_index],
+                    "arity {arity_log}, coset {coset_index}"
+                );
+            }
+        }
+    }
 }
@@ -4,25 +4,25 @@ use super::{EncodingProverParameters, EncodingScheme, concatenate_field_types};
 use crate::{
     Error,
     util::{
-        arithmetic::base_from_raw_bytes, log2_strict, num_of_bytes, plonky2_util::reverse_bits,
+        log2_strict, num_of_bytes,
+        parallel::prelude::*,
+        plonky2_util::reverse_bits,
+        profile::{profile_bytes, profile_exit, profile_span},
     },
     vec_mut,
 };
 use aes::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
-use ark_std::{end_timer, start_timer};
-use ff::{BatchInvert, Field, PrimeField};
-use ff_ext::ExtensionField;
+use ff::{BatchInvert, Field};
+use ff_ext::{ExtensionField, sampling::base_from_uniform_bytes};
 use generic_array::GenericArray;
 use multilinear_extensions::mle::FieldType;
 use rand::SeedableRng;
-use rayon::prelude::{ParallelIterator, ParallelSlice, ParallelSliceMut};
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::util::plonky2_util::reverse_index_bits_in_place;
 use rand_chacha::{ChaCha8Rng, rand_core::RngCore};
-use rayon::prelude::IntoParallelRefIterator;
 
 use crate::util::arithmetic::{horner, steps};
 
@@ -32,6 +32,11 @@ pub trait BasecodeSpec: std::fmt::Debug + Clone {
     fn get_rate_log() -> usize;
 
     fn get_basecode_msg_size_log() -> usize;
+
+    /// See `EncodingScheme::get_folding_arity_log`.
+    fn get_folding_arity_log() -> usize {
+        1
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -60,8 +65,17 @@ pub struct BasecodeParameters<E: ExtensionField> {
     pub(crate) table: Vec<Vec<E::BaseField>>,
     pub(crate) table_w_weights: Vec<Vec<(E::BaseField, E::BaseField)>>,
     pub(crate) rng_seed: [u8; 32],
+    /// The derivation of the table from the seed, see [`TABLE_VERSION`]. Parameters from
+    /// before it was recorded have version 0.
+    #[serde(default)]
+    pub(crate) table_version: u8,
 }
 
+/// The version of the derivation of the tables from the seed:
+/// 0. the sum of the bytes of each element, far from uniform;
+/// 1. rejection sampling on little-endian u64 chunks, see [`table_element`].
+pub const TABLE_VERSION: u8 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound(
     serialize = "E::BaseField: Serialize",
@@ -113,6 +127,7 @@ where
             table,
             table_w_weights,
             rng_seed: [0u8; 32],
+            table_version: TABLE_VERSION,
         }
     }
 
@@ -120,6 +135,12 @@ where
         mut pp: Self::PublicParameters,
         max_msg_size_log: usize,
     ) -> Result<(Self::ProverParameters, Self::VerifierParameters), Error> {
+        if pp.table_version != TABLE_VERSION {
+            return Err(Error::InvalidPcsParam(format!(
+                "Public parameter has table version {}, expected {}",
+                pp.table_version, TABLE_VERSION
+            )));
+        }
         if pp.table.len() < Spec::get_rate_log() + max_msg_size_log {
             return Err(Error::InvalidPcsParam(format!(
                 "Public parameter is setup for a smaller message size (log={}) than the trimmed message size (log={})",
@@ -152,6 +173,7 @@ where
     }
 
     fn encode(pp: &Self::ProverParameters, coeffs: &FieldType<E>) -> FieldType<E> {
+        let span = profile_span!("Encode Basecode", num_coeffs = coeffs.len());
         // Split the input into chunks of message size, encode each message, and return the codewords
         let basecode = encode_field_type_rs_basecode(
             coeffs,
@@ -161,13 +183,19 @@ where
 
         // Apply the recursive definition of the BaseFold code to the list of base codewords,
         // and produce the final codeword
-        evaluate_over_foldable_domain_generic_basecode::<E>(
+        let codeword = evaluate_over_foldable_domain_generic_basecode::<E>(
             1 << Spec::get_basecode_msg_size_log(),
             coeffs.len(),
             Spec::get_rate_log(),
             basecode,
             &pp.table,
-        )
+        );
+        profile_bytes!(
+            "codeword",
+            crate::util::profile::field_type_bytes(&codeword)
+        );
+        profile_exit!(span);
+        codeword
     }
 
     fn encode_small(_vp: &Self::VerifierParameters, coeffs: &FieldType<E>) -> FieldType<E> {
@@ -189,6 +217,10 @@ where
         Spec::get_basecode_msg_size_log()
     }
 
+    fn get_folding_arity_log() -> usize {
+        Spec::get_folding_arity_log()
+    }
+
     fn message_is_left_and_right_folding() -> bool {
         true
     }
@@ -207,13 +239,8 @@ where
         level: usize,
         index: usize,
     ) -> (E, E, E) {
-        type Aes128Ctr64LE = ctr::Ctr32LE<aes::Aes128>;
-        let mut cipher = Aes128Ctr64LE::new(
-            GenericArray::from_slice(&vp.aes_key[..]),
-            GenericArray::from_slice(&vp.aes_iv[..]),
-        );
-
-        let x0: E::BaseField = query_root_table_from_rng_aes::<E>(level, index, &mut cipher);
+        let x0: E::BaseField =
+            query_root_table_from_rng_aes::<E>(level, index, &vp.aes_key, &vp.aes_iv);
         let x1 = -x0;
 
         let w = (x1 - x0).invert().unwrap();
@@ -244,7 +271,7 @@ fn encode_field_type_rs_basecode<E: ExtensionField>(
 // FIXME: It is expensive for now because it is using naive FFT (although it is
 // over a small domain)
 fn get_basecode<F: Field>(poly: &[F], rate: usize, message_size: usize) -> Vec<Vec<F>> {
-    let timer = start_timer!(|| "Encode basecode");
+    let timer = profile_span!("Encode basecode");
     // The domain is just counting 1, 2, 3, ... , domain_size
     let domain: Vec<F> = steps(F::ONE).take(message_size * rate).collect();
     let res = poly
@@ -259,7 +286,7 @@ fn get_basecode<F: Field>(poly: &[F], rate: usize, message_size: usize) -> Vec<V
             target
         })
         .collect::<Vec<Vec<F>>>();
-    end_timer!(timer);
+    profile_exit!(timer);
 
     res
 }
@@ -272,7 +299,7 @@ pub fn evaluate_over_foldable_domain_generic_basecode<E: ExtensionField>(
     base_codewords: Vec<FieldType<E>>,
     table: &[Vec<E::BaseField>],
 ) -> FieldType<E> {
-    let timer = start_timer!(|| "evaluate over foldable domain");
+    let timer = profile_span!("evaluate over foldable domain");
     let k = num_coeffs;
     let logk = log2_strict(k);
     let base_log_k = log2_strict(base_message_length);
@@ -306,7 +333,7 @@ pub fn evaluate_over_foldable_domain_generic_basecode<E: ExtensionField>(
             });
         });
     }
-    end_timer!(timer);
+    profile_exit!(timer);
     coeffs_with_bc
 }
 
@@ -327,12 +354,7 @@ pub fn get_table_aes<E: ExtensionField, Rng: RngCore + Clone>(
     rng.fill_bytes(&mut key);
     rng.fill_bytes(&mut iv);
 
-    type Aes128Ctr64LE = ctr::Ctr32LE<aes::Aes128>;
-
-    let mut cipher = Aes128Ctr64LE::new(
-        GenericArray::from_slice(&key[..]),
-        GenericArray::from_slice(&iv[..]),
-    );
+    let mut cipher = table_cipher(&key, &iv, 0);
 
     // Allocate the buffer for storing n field elements (the entire codeword)
     let bytes = num_of_bytes::<E::BaseField>(1 << lg_n);
@@ -341,10 +363,14 @@ pub fn get_table_aes<E: ExtensionField, Rng: RngCore + Clone>(
 
     // Now, dest is a vector filled with random data for a field vector of size n
 
-    // Collect the bytes into field elements
+    // Collect the bytes into field elements, deriving the rare rejected ones again
     let flat_table: Vec<E::BaseField> = dest
         .par_chunks_exact(num_of_bytes::<E::BaseField>(1))
-        .map(|chunk| base_from_raw_bytes::<E>(chunk))
+        .enumerate()
+        .map(|(pos, chunk)| {
+            base_from_uniform_bytes(chunk)
+                .unwrap_or_else(|| table_element::<E>(&key, &iv, pos as u128))
+        })
         .collect::<Vec<_>>();
 
     // Now, flat_table is a field vector of size n, filled with random field elements
@@ -390,7 +416,8 @@ pub fn get_table_aes<E: ExtensionField, Rng: RngCore + Clone>(
 pub fn query_root_table_from_rng_aes<E: ExtensionField>(
     level: usize,
     index: usize,
-    cipher: &mut ctr::Ctr32LE<aes::Aes128>,
+    aes_key: &[u8; 16],
+    aes_iv: &[u8; 16],
 ) -> E::BaseField {
     let mut level_offset: u128 = 1;
     for lg_m in 1..=level {
@@ -398,23 +425,47 @@ pub fn query_root_table_from_rng_aes<E: ExtensionField>(
         level_offset += half_m;
     }
 
-    let pos = ((level_offset + (reverse_bits(index, level) as u128))
-        * ((E::BaseField::NUM_BITS as usize).next_power_of_two() as u128))
-        .checked_div(8)
-        .unwrap();
+    table_element::<E>(
+        aes_key,
+        aes_iv,
+        level_offset + reverse_bits(index, level) as u128,
+    )
+}
 
-    cipher.seek(pos);
+type Aes128Ctr32LE = ctr::Ctr32LE<aes::Aes128>;
 
-    let bytes = (E::BaseField::NUM_BITS as usize).next_power_of_two() / 8;
-    let mut dest: Vec<u8> = vec![0u8; bytes];
-    cipher.apply_keystream(&mut dest);
+/// The keystream of the `attempt`-th candidates of the table elements. The attempt tweaks the
+/// last byte of the IV, which is not part of the counter.
+fn table_cipher(key: &[u8; 16], iv: &[u8; 16], attempt: u8) -> Aes128Ctr32LE {
+    let mut iv = *iv;
+    iv[15] ^= attempt;
+    Aes128Ctr32LE::new(
+        GenericArray::from_slice(&key[..]),
+        GenericArray::from_slice(&iv[..]),
+    )
+}
 
-    base_from_raw_bytes::<E>(&dest)
+/// The table element at position `pos` of the flat table: its first candidate below the
+/// modulus, the candidates being the chunks at `pos` of the keystreams of the attempts. Each
+/// one is rejected with probability below `2^-32`.
+fn table_element<E: ExtensionField>(key: &[u8; 16], iv: &[u8; 16], pos: u128) -> E::BaseField {
+    let bytes = num_of_bytes::<E::BaseField>(1);
+    (0..=u8::MAX)
+        .find_map(|attempt| {
+            let mut cipher = table_cipher(key, iv, attempt);
+            cipher.seek(pos * bytes as u128);
+            let mut dest: Vec<u8> = vec![0u8; bytes];
+            cipher.apply_keystream(&mut dest);
+            base_from_uniform_bytes(&dest)
+        })
+        .expect("all candidates of a table element are rejected")
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::basefold::encoding::test_util::test_codeword_folding;
+    use crate::basefold::encoding::test_util::{
+        test_codeword_folding, test_codeword_folding_radix,
+    };
 
     use super::*;
     use goldilocks::GoldilocksExt2;
@@ -447,8 +498,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn trim_rejects_other_table_versions() {
+        type Code = Basecode<BasecodeDefaultSpec>;
+        let mut pp: BasecodeParameters<GoldilocksExt2> = Code::setup(4);
+        pp.table_version = 0;
+        assert!(Code::trim(pp, 4).is_err());
+    }
+
     #[test]
     fn test_basecode_codeword_folding() {
         test_codeword_folding::<GoldilocksExt2, Basecode<BasecodeDefaultSpec>>();
     }
+
+    #[test]
+    fn test_basecode_codeword_folding_radix() {
+        test_codeword_folding_radix::<GoldilocksExt2, Basecode<BasecodeDefaultSpec>>();
+    }
 }
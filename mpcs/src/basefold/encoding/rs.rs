@@ -1,15 +1,22 @@
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
 
 use super::{EncodingProverParameters, EncodingScheme};
 use crate::{
     Error,
-    util::{field_type_index_mul_base, log2_strict, plonky2_util::reverse_bits},
+    util::{
+        field_type_index_mul_base, log2_strict,
+        plonky2_util::reverse_bits,
+        profile::{profile_bytes, profile_exit, profile_span},
+    },
     vec_mut,
 };
-use ark_std::{end_timer, start_timer};
 use ff::{Field, PrimeField};
 use ff_ext::ExtensionField;
 use multilinear_extensions::mle::FieldType;
+use poseidon::poseidon::AdaptedField;
 
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
@@ -23,6 +30,11 @@ pub trait RSCodeSpec: std::fmt::Debug + Clone {
     fn get_rate_log() -> usize;
 
     fn get_basecode_msg_size_log() -> usize;
+
+    /// See `EncodingScheme::get_folding_arity_log`.
+    fn get_folding_arity_log() -> usize {
+        1
+    }
 }
 
 /// The FFT codes in this file are borrowed and adapted from Plonky2.
@@ -99,7 +111,6 @@ fn fft_classic_inner<E: ExtensionField>(
     // We've already done the first lg_packed_width (if they were required) iterations.
 
     for (lg_half_m, cur_root_table) in root_table.iter().enumerate().take(lg_n).skip(r) {
-        let n = 1 << lg_n;
         let lg_m = lg_half_m + 1;
         let m = 1 << lg_m; // Subarray size (in field elements).
         let half_m = m / 2;
@@ -107,17 +118,35 @@ fn fft_classic_inner<E: ExtensionField>(
 
         // omega values for this iteration, as slice of vectors
         let omega_table = &cur_root_table[..];
-        vec_mut!(|values| {
-            for k in (0..n).step_by(m) {
-                for j in 0..half_m {
-                    let omega = omega_table[j];
-                    let t = values[k + half_m + j] * omega;
-                    let u = values[k + j];
-                    values[k + j] = u + t;
-                    values[k + half_m + j] = u - t;
-                }
+        match values {
+            FieldType::Base(values) => {
+                butterflies(values, m, omega_table, |x: &E::BaseField, omega| {
+                    x.mul_reduce(*omega)
+                })
             }
-        })
+            FieldType::Ext(values) => butterflies(values, m, omega_table, |x: &E, omega| {
+                x.mul_base_reduce(omega)
+            }),
+            _ => panic!("Unsupported field type"),
+        }
+    }
+}
+
+#[inline(always)]
+fn butterflies<T: Copy + Add<Output = T> + Sub<Output = T>, F>(
+    values: &mut [T],
+    m: usize,
+    omega_table: &[F],
+    mul: impl Fn(&T, &F) -> T,
+) {
+    let half_m = m / 2;
+    for k in (0..values.len()).step_by(m) {
+        for j in 0..half_m {
+            let t = mul(&values[k + half_m + j], &omega_table[j]);
+            let u = values[k + j];
+            values[k + j] = u + t;
+            values[k + half_m + j] = u - t;
+        }
     }
 }
 
@@ -347,14 +376,25 @@ where
     }
 
     fn encode(pp: &Self::ProverParameters, coeffs: &FieldType<E>) -> FieldType<E> {
+        Self::encode_owned(pp, coeffs.clone())
+    }
+
+    fn encode_owned(pp: &Self::ProverParameters, coeffs: FieldType<E>) -> FieldType<E> {
         assert!(log2_strict(coeffs.len()) >= Spec::get_basecode_msg_size_log());
+        let span = profile_span!("Encode RSCode", num_coeffs = coeffs.len());
         // Use the full message size to determine the shift factor.
-        Self::encode_internal(&pp.fft_root_table, coeffs, pp.full_message_size_log)
+        let codeword = Self::encode_internal(&pp.fft_root_table, coeffs, pp.full_message_size_log);
+        profile_bytes!(
+            "codeword",
+            crate::util::profile::field_type_bytes(&codeword)
+        );
+        profile_exit!(span);
+        codeword
     }
 
     fn encode_small(vp: &Self::VerifierParameters, coeffs: &FieldType<E>) -> FieldType<E> {
         // Use the full message size to determine the shift factor.
-        Self::encode_internal(&vp.fft_root_table, coeffs, vp.full_message_size_log)
+        Self::encode_internal(&vp.fft_root_table, coeffs.clone(), vp.full_message_size_log)
     }
 
     fn get_number_queries() -> usize {
@@ -369,10 +409,20 @@ where
         Spec::get_basecode_msg_size_log()
     }
 
+    fn get_folding_arity_log() -> usize {
+        Spec::get_folding_arity_log()
+    }
+
     fn message_is_left_and_right_folding() -> bool {
         false
     }
 
+    // The codeword is the evaluations of the coefficients over a coset, and
+    // the coefficients of a constant polynomial are all zero but the first.
+    fn constant_message_has_constant_codeword() -> bool {
+        true
+    }
+
     fn prover_folding_coeffs(pp: &Self::ProverParameters, level: usize, index: usize) -> (E, E, E) {
         // The coefficients are for the bit-reversed codeword, so reverse the
         // bits before providing the coefficients.
@@ -456,7 +506,7 @@ where
 impl<Spec: RSCodeSpec> RSCode<Spec> {
     fn encode_internal<E: ExtensionField>(
         fft_root_table: &FftRootTable<E::BaseField>,
-        coeffs: &FieldType<E>,
+        coeffs: FieldType<E>,
         full_message_size_log: usize,
     ) -> FieldType<E>
     where
@@ -470,16 +520,14 @@ impl<Spec: RSCodeSpec> RSCode<Spec> {
         );
         let rate = 1 << Spec::get_rate_log();
         let mut ret = match coeffs {
-            FieldType::Base(coeffs) => {
-                let mut coeffs = coeffs.clone();
+            FieldType::Base(mut coeffs) => {
                 coeffs.extend(itertools::repeat_n(
                     E::BaseField::ZERO,
                     coeffs.len() * (rate - 1),
                 ));
                 FieldType::Base(coeffs)
             }
-            FieldType::Ext(coeffs) => {
-                let mut coeffs = coeffs.clone();
+            FieldType::Ext(mut coeffs) => {
                 coeffs.extend(itertools::repeat_n(E::ZERO, coeffs.len() * (rate - 1)));
                 FieldType::Ext(coeffs)
             }
@@ -524,7 +572,7 @@ impl<Spec: RSCodeSpec> RSCode<Spec> {
 
 #[allow(unused)]
 fn naive_fft<E: ExtensionField>(poly: &[E], rate: usize, shift: E::BaseField) -> Vec<E> {
-    let timer = start_timer!(|| "Encode RSCode");
+    let timer = profile_span!("Encode RSCode");
     let message_size = poly.len();
     let domain_size_bit = log2_strict(message_size * rate);
     let root = E::BaseField::ROOT_OF_UNITY.pow([1 << (E::BaseField::S - domain_size_bit as u32)]);
@@ -539,7 +587,7 @@ fn naive_fft<E: ExtensionField>(poly: &[E], rate: usize, shift: E::BaseField) ->
     res.iter_mut()
         .enumerate()
         .for_each(|(i, target)| *target = horner(poly, &E::from(domain[i])));
-    end_timer!(timer);
+    profile_exit!(timer);
 
     res
 }
@@ -547,7 +595,7 @@ fn naive_fft<E: ExtensionField>(poly: &[E], rate: usize, shift: E::BaseField) ->
 #[cfg(test)]
 mod tests {
     use crate::{
-        basefold::encoding::test_util::test_codeword_folding,
+        basefold::encoding::test_util::{test_codeword_folding, test_codeword_folding_radix},
         util::{field_type_index_ext, plonky2_util::reverse_index_bits_in_place_field_type},
     };
 
@@ -678,10 +726,30 @@ mod tests {
         test_codeword_folding::<GoldilocksExt2, RSCode<RSCodeDefaultSpec>>();
     }
 
+    #[test]
+    fn test_rs_codeword_folding_radix() {
+        test_codeword_folding_radix::<GoldilocksExt2, RSCode<RSCodeDefaultSpec>>();
+    }
+
     type E = GoldilocksExt2;
     type F = Goldilocks;
     type Code = RSCode<RSCodeDefaultSpec>;
 
+    #[test]
+    fn test_constant_codeword() {
+        let num_vars = 10;
+        let pp = <Code as EncodingScheme<E>>::setup(num_vars);
+        let (pp, _) = Code::trim(pp, num_vars).unwrap();
+
+        // The coefficients of a constant polynomial.
+        let mut coeffs = vec![F::ZERO; 1 << num_vars];
+        coeffs[0] = F::from(7);
+        let codeword = Code::encode(&pp, &FieldType::Base(coeffs));
+        let codeword_len = 1 << (num_vars + <Code as EncodingScheme<E>>::get_rate_log());
+        assert!(<Code as EncodingScheme<E>>::constant_message_has_constant_codeword());
+        assert_eq!(codeword, FieldType::Base(vec![F::from(7); codeword_len]));
+    }
+
     #[test]
     pub fn test_colinearity() {
         let num_vars = 10;
@@ -0,0 +1,201 @@
+//! The folding schedule decides which of the oracles folded in the commit phase are committed.
+//!
+//! Basefold folds the oracle once per round, and commits to every intermediate oracle. With a
+//! higher arity, only every `arity_log`-th oracle is committed, and a query opens a whole coset
+//! of `2^arity_log` positions of it, which the verifier folds by itself through the rounds
+//! until the next committed oracle. This trades fewer Merkle roots and paths in the proof for
+//! larger leaves, while the number of queries stays the same.
+//!
+//! A committed oracle is stored as `2^(arity_log - 1)` columns, so that the two leaves of a
+//! Merkle pair hold a whole coset. With the binary schedule, it is the usual tree over pairs.
+
+use core::{fmt::Debug, marker::PhantomData};
+
+use ff_ext::ExtensionField;
+use multilinear_extensions::mle::FieldType;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    basefold::EncodingScheme,
+    util::{hash::Digest, merkle_tree::MerkleTree, prover_workspace::ProverWorkspace},
+};
+
+pub trait FoldingSchedule: Debug + Clone {
+    /// The number of rounds folded from a committed oracle to the next one. A query opens
+    /// cosets of `2^arity_log` positions in the committed oracles.
+    fn arity_log() -> usize;
+}
+
+/// The schedule of Basefold, which commits to the oracle of every round.
+#[derive(Debug, Clone)]
+pub struct BinaryFolding;
+
+impl FoldingSchedule for BinaryFolding {
+    fn arity_log() -> usize {
+        1
+    }
+}
+
+/// Commit to the oracle of every `ARITY_LOG`-th round only.
+#[derive(Debug, Clone)]
+pub struct HighArityFolding<const ARITY_LOG: usize>;
+
+impl<const ARITY_LOG: usize> FoldingSchedule for HighArityFolding<ARITY_LOG> {
+    fn arity_log() -> usize {
+        assert!(ARITY_LOG > 0, "the folding arity is at least 2");
+        ARITY_LOG
+    }
+}
+
+/// The schedule with the folding arity of the encoding scheme, see
+/// `EncodingScheme::get_folding_arity_log`.
+#[derive(Debug, Clone)]
+pub struct EncodingFolding<E, Code>(PhantomData<(E, Code)>);
+
+impl<E: ExtensionField, Code: EncodingScheme<E>> FoldingSchedule for EncodingFolding<E, Code> {
+    fn arity_log() -> usize {
+        let arity_log = Code::get_folding_arity_log();
+        assert!(arity_log > 0, "the folding arity is at least 2");
+        arity_log
+    }
+}
+
+/// The oracles committed during `num_rounds` rounds, as `(layer, coset_log)`: the oracle
+/// after `layer` foldings of the codeword is opened on cosets of `2^coset_log` positions.
+///
+/// The first oracle is the commitment itself, which is opened on pairs, and the last one is
+/// sent as the final message, so neither of them is part of the schedule.
+pub(crate) fn committed_layers<S: FoldingSchedule>(
+    num_rounds: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let arity_log = S::arity_log();
+    (1..num_rounds)
+        .step_by(arity_log)
+        .map(move |layer| (layer, arity_log.min(num_rounds - layer)))
+}
+
+/// The coset size of the oracle of `layer`, if it is committed.
+pub(crate) fn coset_log<S: FoldingSchedule>(layer: usize, num_rounds: usize) -> Option<usize> {
+    let arity_log = S::arity_log();
+    (layer > 0 && layer < num_rounds && (layer - 1) % arity_log == 0)
+        .then(|| arity_log.min(num_rounds - layer))
+}
+
+/// Lay out `oracle` in `2^(coset_log - 1)` columns, such that the leaves `2q` and `2q + 1`
+/// of all the columns are the coset `q` of `2^coset_log` consecutive positions.
+fn coset_columns<E: ExtensionField>(oracle: &[E], coset_log: usize) -> Vec<FieldType<E>> {
+    let num_columns = 1 << (coset_log - 1);
+    (0..num_columns)
+        .map(|column| {
+            FieldType::Ext(
+                oracle
+                    .chunks_exact(1 << coset_log)
+                    .flat_map(|coset| [coset[2 * column], coset[2 * column + 1]])
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// The Merkle trees of the oracles committed along the schedule, in the order of the rounds.
+pub(crate) struct OracleTrees<'a, E: ExtensionField>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    workspace: &'a ProverWorkspace<E>,
+    trees: Vec<MerkleTree<E>>,
+    /// The inner nodes of the last oracle committed on pairs. To avoid cloning it, its tree
+    /// takes the oracle once it has been folded to the next one.
+    pending_inner: Option<Vec<Vec<Digest<E::BaseField>>>>,
+}
+
+impl<'a, E: ExtensionField> OracleTrees<'a, E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    pub(crate) fn new(workspace: &'a ProverWorkspace<E>) -> Self {
+        Self {
+            workspace,
+            trees: vec![],
+            pending_inner: None,
+        }
+    }
+
+    /// Commit to `oracle` on cosets of `2^coset_log` positions, and return the root.
+    pub(crate) fn commit(&mut self, oracle: &[E], coset_log: usize) -> Digest<E::BaseField> {
+        if coset_log == 1 {
+            let inner = MerkleTree::<E>::compute_inner_ext_in(oracle, self.workspace);
+            let root = MerkleTree::<E>::root_from_inner(&inner);
+            self.pending_inner = Some(inner);
+            root
+        } else {
+            let tree = MerkleTree::<E>::from_batch_leaves_in(
+                coset_columns(oracle, coset_log),
+                self.workspace,
+            );
+            let root = tree.root();
+            self.trees.push(tree);
+            root
+        }
+    }
+
+    /// Take back the oracle of the previous round once it has been folded. It becomes the
+    /// leaves of its tree if it was committed on pairs.
+    pub(crate) fn folded(&mut self, oracle: Vec<E>) {
+        if let Some(inner) = self.pending_inner.take() {
+            self.trees
+                .push(MerkleTree::from_inner_leaves(inner, FieldType::Ext(oracle)));
+        }
+    }
+
+    pub(crate) fn into_trees(self) -> Vec<MerkleTree<E>> {
+        self.trees
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+    use itertools::Itertools;
+
+    use super::{BinaryFolding, HighArityFolding, committed_layers, coset_columns, coset_log};
+    use crate::util::field_type_index_ext;
+
+    #[test]
+    fn test_committed_layers() {
+        assert_eq!(committed_layers::<BinaryFolding>(4).collect_vec(), vec![
+            (1, 1),
+            (2, 1),
+            (3, 1)
+        ]);
+        assert_eq!(
+            committed_layers::<HighArityFolding<3>>(8).collect_vec(),
+            vec![(1, 3), (4, 3), (7, 1)]
+        );
+        assert_eq!(committed_layers::<HighArityFolding<3>>(1).count(), 0);
+        for layer in 0..10 {
+            assert_eq!(
+                coset_log::<HighArityFolding<3>>(layer, 8),
+                committed_layers::<HighArityFolding<3>>(8)
+                    .find(|(l, _)| *l == layer)
+                    .map(|(_, coset_log)| coset_log)
+            );
+        }
+    }
+
+    #[test]
+    fn test_coset_columns() {
+        let oracle = (0..16u64).map(GoldilocksExt2::from).collect_vec();
+        let columns = coset_columns(&oracle, 2);
+        assert_eq!(columns.len(), 2);
+        // The leaves 2 and 3 of both columns hold the coset 4..8.
+        assert_eq!(
+            columns
+                .iter()
+                .map(|c| field_type_index_ext(c, 2))
+                .interleave(columns.iter().map(|c| field_type_index_ext(c, 3)))
+                .collect_vec(),
+            oracle[4..8]
+        );
+    }
+}
@@ -1,33 +1,56 @@
 use crate::util::{
     arithmetic::{
-        degree_2_eval, degree_2_zero_plus_one, inner_product, interpolate_over_boolean_hypercube,
+        degree_2_decompress, degree_2_eval, hypercube_interpolate_in_place, inner_product_ext,
         interpolate2_weights,
     },
     ext_to_usize, field_type_index_base, field_type_index_ext,
     hash::Digest,
     log2_strict,
     merkle_tree::{MerklePathWithoutLeafOrRoot, MerkleTree},
+    profile::{profile_exit, profile_span},
 };
-use ark_std::{end_timer, start_timer};
 use core::fmt::Debug;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::HashSet;
 use transcript::Transcript;
 
 use multilinear_extensions::mle::FieldType;
 
-use crate::util::plonky2_util::reverse_index_bits_in_place;
-use rayon::{
-    iter::IndexedParallelIterator,
-    prelude::{IntoParallelRefIterator, ParallelIterator},
+use crate::util::{
+    parallel::{parallelize_iter, prelude::*},
+    plonky2_util::reverse_index_bits_in_place,
 };
 
 use super::{
     encoding::EncodingScheme,
+    folding::committed_layers,
     structure::{BasefoldCommitment, BasefoldCommitmentWithData, BasefoldSpec},
 };
 
+/// Draw the indices of `num_queries` distinct queries into a codeword of `codeword_size`, or of
+/// all its indices if it is smaller. An index drawn again is redrawn, as its openings would be
+/// the same, so the proof has one opening per query and none of the soundness is lost to
+/// repeats. They are in the order of their first draw.
+pub fn draw_query_indices<E: ExtensionField>(
+    transcript: &mut Transcript<E>,
+    num_queries: usize,
+    codeword_size: usize,
+) -> Vec<usize> {
+    let num_indices = num_queries.min(codeword_size);
+    let mut indices = Vec::with_capacity(num_indices);
+    let mut drawn = HashSet::with_capacity(num_indices);
+    while indices.len() < num_indices {
+        let challenge = transcript.get_and_append_challenge(b"query indices");
+        let index = ext_to_usize(&challenge.elements) % codeword_size;
+        if drawn.insert(index) {
+            indices.push(index);
+        }
+    }
+    indices
+}
+
 pub fn prover_query_phase<E: ExtensionField>(
     transcript: &mut Transcript<E>,
     comm: &BasefoldCommitmentWithData<E>,
@@ -37,19 +60,7 @@ pub fn prover_query_phase<E: ExtensionField>(
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let queries: Vec<_> = (0..num_verifier_queries)
-        .map(|_| {
-            transcript
-                .get_and_append_challenge(b"query indices")
-                .elements
-        })
-        .collect();
-
-    // Transform the challenge queries from field elements into integers
-    let queries_usize: Vec<usize> = queries
-        .iter()
-        .map(|x_index| ext_to_usize(x_index) % comm.codeword_size())
-        .collect_vec();
+    let queries_usize = draw_query_indices(transcript, num_verifier_queries, comm.codeword_size());
 
     QueriesResult {
         inner: queries_usize
@@ -67,26 +78,14 @@ where
 pub fn batch_prover_query_phase<E: ExtensionField>(
     transcript: &mut Transcript<E>,
     codeword_size: usize,
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     trees: &[MerkleTree<E>],
     num_verifier_queries: usize,
 ) -> BatchedQueriesResult<E>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let queries: Vec<_> = (0..num_verifier_queries)
-        .map(|_| {
-            transcript
-                .get_and_append_challenge(b"query indices")
-                .elements
-        })
-        .collect();
-
-    // Transform the challenge queries from field elements into integers
-    let queries_usize: Vec<usize> = queries
-        .iter()
-        .map(|x_index| ext_to_usize(x_index) % codeword_size)
-        .collect_vec();
+    let queries_usize = draw_query_indices(transcript, num_verifier_queries, codeword_size);
 
     BatchedQueriesResult {
         inner: queries_usize
@@ -110,19 +109,7 @@ pub fn simple_batch_prover_query_phase<E: ExtensionField>(
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let queries: Vec<_> = (0..num_verifier_queries)
-        .map(|_| {
-            transcript
-                .get_and_append_challenge(b"query indices")
-                .elements
-        })
-        .collect();
-
-    // Transform the challenge queries from field elements into integers
-    let queries_usize: Vec<usize> = queries
-        .iter()
-        .map(|x_index| ext_to_usize(x_index) % comm.codeword_size())
-        .collect_vec();
+    let queries_usize = draw_query_indices(transcript, num_verifier_queries, comm.codeword_size());
 
     SimpleBatchQueriesResult {
         inner: queries_usize
@@ -150,15 +137,16 @@ pub fn verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     roots: &[Digest<E::BaseField>],
     comm: &BasefoldCommitment<E>,
     partial_eq: &[E],
+    partial_eq_coeff: E,
     eval: &E,
 ) where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Verifier query phase");
+    let timer = profile_span!("Verifier query phase");
 
-    let encode_timer = start_timer!(|| "Encode final codeword");
+    let encode_timer = profile_span!("Encode final codeword");
     let mut message = final_message.to_vec();
-    interpolate_over_boolean_hypercube(&mut message);
+    hypercube_interpolate_in_place(&mut message);
     if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
         reverse_index_bits_in_place(&mut message);
     }
@@ -169,9 +157,9 @@ pub fn verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
         _ => panic!("Final codeword must be extension field"),
     };
     reverse_index_bits_in_place(&mut final_codeword);
-    end_timer!(encode_timer);
+    profile_exit!(encode_timer);
 
-    let queries_timer = start_timer!(|| format!("Check {} queries", indices.len()));
+    let queries_timer = profile_span!("Check queries", num_queries = indices.len());
     queries.check::<Spec>(
         indices,
         vp,
@@ -182,31 +170,18 @@ pub fn verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
         roots,
         comm,
     );
-    end_timer!(queries_timer);
-
-    let final_timer = start_timer!(|| "Final checks");
-    assert_eq!(eval, &degree_2_zero_plus_one(&sum_check_messages[0]));
-
-    // The sum-check part of the protocol
-    for i in 0..fold_challenges.len() - 1 {
-        assert_eq!(
-            degree_2_eval(&sum_check_messages[i], fold_challenges[i]),
-            degree_2_zero_plus_one(&sum_check_messages[i + 1])
-        );
-    }
+    profile_exit!(queries_timer);
 
-    // Finally, the last sumcheck poly evaluation should be the same as the sum of the polynomial
-    // sent from the prover
+    let final_timer = profile_span!("Final checks");
+    // The sum-check part of the protocol: the claim left after the last round should be the
+    // same as the sum of the polynomial sent from the prover
     assert_eq!(
-        degree_2_eval(
-            &sum_check_messages[fold_challenges.len() - 1],
-            fold_challenges[fold_challenges.len() - 1]
-        ),
-        inner_product(final_message, partial_eq)
+        sumcheck_final_claim(*eval, sum_check_messages, fold_challenges),
+        inner_product_ext(final_message, partial_eq) * partial_eq_coeff
     );
-    end_timer!(final_timer);
+    profile_exit!(final_timer);
 
-    end_timer!(timer);
+    profile_exit!(timer);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -222,18 +197,20 @@ pub fn batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     roots: &[Digest<E::BaseField>],
     comms: &[&BasefoldCommitment<E>],
     coeffs: &[E],
+    batch_coeffs: &[E],
     partial_eq: &[E],
+    partial_eq_coeff: E,
     eval: &E,
 ) where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Verifier batch query phase");
-    let encode_timer = start_timer!(|| "Encode final codeword");
+    let timer = profile_span!("Verifier batch query phase");
+    let encode_timer = profile_span!("Encode final codeword");
     let mut message = final_message.to_vec();
     if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
         reverse_index_bits_in_place(&mut message);
     }
-    interpolate_over_boolean_hypercube(&mut message);
+    hypercube_interpolate_in_place(&mut message);
     let final_codeword =
         <Spec::EncodingScheme as EncodingScheme<E>>::encode_small(vp, &FieldType::Ext(message));
     let mut final_codeword = match final_codeword {
@@ -241,12 +218,12 @@ pub fn batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
         _ => panic!("Final codeword must be extension field"),
     };
     reverse_index_bits_in_place(&mut final_codeword);
-    end_timer!(encode_timer);
+    profile_exit!(encode_timer);
 
     // For computing the weights on the fly, because the verifier is incapable of storing
     // the weights.
 
-    let queries_timer = start_timer!(|| format!("Check {} queries", indices.len()));
+    let queries_timer = profile_span!("Check queries", num_queries = indices.len());
     queries.check::<Spec>(
         indices,
         vp,
@@ -257,32 +234,20 @@ pub fn batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
         roots,
         comms,
         coeffs,
+        batch_coeffs,
     );
-    end_timer!(queries_timer);
+    profile_exit!(queries_timer);
 
     #[allow(unused)]
-    let final_timer = start_timer!(|| "Final checks");
-    assert_eq!(eval, &degree_2_zero_plus_one(&sum_check_messages[0]));
-
-    // The sum-check part of the protocol
-    for i in 0..fold_challenges.len() - 1 {
-        assert_eq!(
-            degree_2_eval(&sum_check_messages[i], fold_challenges[i]),
-            degree_2_zero_plus_one(&sum_check_messages[i + 1])
-        );
-    }
-
-    // Finally, the last sumcheck poly evaluation should be the same as the sum of the polynomial
-    // sent from the prover
+    let final_timer = profile_span!("Final checks");
+    // The sum-check part of the protocol: the claim left after the last round should be the
+    // same as the sum of the polynomial sent from the prover
     assert_eq!(
-        degree_2_eval(
-            &sum_check_messages[fold_challenges.len() - 1],
-            fold_challenges[fold_challenges.len() - 1]
-        ),
-        inner_product(final_message, partial_eq)
+        sumcheck_final_claim(*eval, sum_check_messages, fold_challenges),
+        inner_product_ext(final_message, partial_eq) * partial_eq_coeff
     );
-    end_timer!(final_timer);
-    end_timer!(timer);
+    profile_exit!(final_timer);
+    profile_exit!(timer);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -299,18 +264,19 @@ pub fn simple_batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E
     roots: &[Digest<E::BaseField>],
     comm: &BasefoldCommitment<E>,
     partial_eq: &[E],
+    partial_eq_coeff: E,
     evals: &[E],
 ) where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let timer = start_timer!(|| "Verifier query phase");
+    let timer = profile_span!("Verifier query phase");
 
-    let encode_timer = start_timer!(|| "Encode final codeword");
+    let encode_timer = profile_span!("Encode final codeword");
     let mut message = final_message.to_vec();
     if <Spec::EncodingScheme as EncodingScheme<E>>::message_is_even_and_odd_folding() {
         reverse_index_bits_in_place(&mut message);
     }
-    interpolate_over_boolean_hypercube(&mut message);
+    hypercube_interpolate_in_place(&mut message);
     let final_codeword =
         <Spec::EncodingScheme as EncodingScheme<E>>::encode_small(vp, &FieldType::Ext(message));
     let mut final_codeword = match final_codeword {
@@ -318,11 +284,11 @@ pub fn simple_batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E
         _ => panic!("Final codeword must be extension field"),
     };
     reverse_index_bits_in_place(&mut final_codeword);
-    end_timer!(encode_timer);
+    profile_exit!(encode_timer);
 
     // For computing the weights on the fly, because the verifier is incapable of storing
     // the weights.
-    let queries_timer = start_timer!(|| format!("Check {} queries", indices.len()));
+    let queries_timer = profile_span!("Check queries", num_queries = indices.len());
     queries.check::<Spec>(
         indices,
         vp,
@@ -334,34 +300,40 @@ pub fn simple_batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E
         roots,
         comm,
     );
-    end_timer!(queries_timer);
-
-    let final_timer = start_timer!(|| "Final checks");
-    assert_eq!(
-        &inner_product(batch_coeffs, evals),
-        &degree_2_zero_plus_one(&sum_check_messages[0])
-    );
-
-    // The sum-check part of the protocol
-    for i in 0..fold_challenges.len() - 1 {
-        assert_eq!(
-            degree_2_eval(&sum_check_messages[i], fold_challenges[i]),
-            degree_2_zero_plus_one(&sum_check_messages[i + 1])
-        );
-    }
+    profile_exit!(queries_timer);
 
-    // Finally, the last sumcheck poly evaluation should be the same as the sum of the polynomial
-    // sent from the prover
+    let final_timer = profile_span!("Final checks");
+    // The sum-check part of the protocol: the claim left after the last round should be the
+    // same as the sum of the polynomial sent from the prover
     assert_eq!(
-        degree_2_eval(
-            &sum_check_messages[fold_challenges.len() - 1],
-            fold_challenges[fold_challenges.len() - 1]
+        sumcheck_final_claim(
+            inner_product_ext(batch_coeffs, evals),
+            sum_check_messages,
+            fold_challenges
         ),
-        inner_product(final_message, partial_eq)
+        inner_product_ext(final_message, partial_eq) * partial_eq_coeff
     );
-    end_timer!(final_timer);
+    profile_exit!(final_timer);
 
-    end_timer!(timer);
+    profile_exit!(timer);
+}
+
+/// Runs the sum-check rounds embedded in the commit phase from `claim`, and returns the
+/// claim left after the last round. Each round message only carries the evaluations of
+/// the round polynomial at 0 and 2; the one at 1 follows from the current claim.
+fn sumcheck_final_claim<E: ExtensionField>(
+    claim: E,
+    sum_check_messages: &[Vec<E>],
+    fold_challenges: &[E],
+) -> E {
+    assert_eq!(sum_check_messages.len(), fold_challenges.len());
+    sum_check_messages
+        .iter()
+        .zip(fold_challenges)
+        .fold(claim, |claim, (message, challenge)| {
+            assert_eq!(message.len(), 2);
+            degree_2_eval(&degree_2_decompress(message, claim), *challenge)
+        })
 }
 
 fn basefold_get_query<E: ExtensionField>(
@@ -372,8 +344,7 @@ fn basefold_get_query<E: ExtensionField>(
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let mut index = x_index;
-    let p1 = index | 1;
+    let p1 = x_index | 1;
     let p0 = p1 - 1;
 
     let commitment_query = match poly_codeword {
@@ -385,24 +356,9 @@ where
         }
         _ => unreachable!(),
     };
-    index >>= 1;
 
-    let mut oracle_queries = Vec::with_capacity(trees.len() + 1);
-    for tree in trees {
-        let p1 = index | 1;
-        let p0 = p1 - 1;
-
-        oracle_queries.push(CodewordSingleQueryResult::new_ext(
-            tree.get_leaf_as_extension(p0)[0],
-            tree.get_leaf_as_extension(p1)[0],
-            p0,
-        ));
-        index >>= 1;
-    }
-
-    let oracle_query = OracleListQueryResult {
-        inner: oracle_queries,
-    };
+    let oracle_query =
+        OracleCosetsQueryResult::new(trees, log2_strict(poly_codeword.len()), x_index);
 
     SingleQueryResult {
         oracle_query,
@@ -411,7 +367,7 @@ where
 }
 
 fn batch_basefold_get_query<E: ExtensionField>(
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     trees: &[MerkleTree<E>],
     codeword_size: usize,
     x_index: usize,
@@ -443,15 +399,7 @@ where
             let x_index = x_index >> (log2_strict(codeword_size) - comm.codeword_size_log());
             let p1 = x_index | 1;
             let p0 = p1 - 1;
-            match &comm.get_codewords()[0] {
-                FieldType::Ext(poly_codeword) => {
-                    CodewordSingleQueryResult::new_ext(poly_codeword[p0], poly_codeword[p1], p0)
-                }
-                FieldType::Base(poly_codeword) => {
-                    CodewordSingleQueryResult::new_base(poly_codeword[p0], poly_codeword[p1], p0)
-                }
-                _ => unreachable!(),
-            }
+            batch_leaves_get_query(comm.get_codewords(), p0, p1)
         })
         .collect_vec();
 
@@ -473,11 +421,29 @@ fn simple_batch_basefold_get_query<E: ExtensionField>(
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    let mut index = x_index;
-    let p1 = index | 1;
+    let p1 = x_index | 1;
     let p0 = p1 - 1;
 
-    let commitment_query = match poly_codewords[0] {
+    let commitment_query = batch_leaves_get_query(poly_codewords, p0, p1);
+    let oracle_query =
+        OracleCosetsQueryResult::new(trees, log2_strict(poly_codewords[0].len()), x_index);
+
+    SimpleBatchSingleQueryResult {
+        oracle_query,
+        commitment_query,
+    }
+}
+
+/// Open the leaves at positions `p0` and `p1` of all the codewords committed in one Merkle tree.
+fn batch_leaves_get_query<E: ExtensionField>(
+    poly_codewords: &[FieldType<E>],
+    p0: usize,
+    p1: usize,
+) -> SimpleBatchCommitmentSingleQueryResult<E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    match poly_codewords[0] {
         FieldType::Ext(_) => SimpleBatchCommitmentSingleQueryResult::new_ext(
             poly_codewords
                 .iter()
@@ -501,29 +467,6 @@ where
             p0,
         ),
         _ => unreachable!(),
-    };
-    index >>= 1;
-
-    let mut oracle_queries = Vec::with_capacity(trees.len() + 1);
-    for tree in trees {
-        let p1 = index | 1;
-        let p0 = p1 - 1;
-
-        oracle_queries.push(CodewordSingleQueryResult::new_ext(
-            tree.get_leaf_as_extension(p0)[0],
-            tree.get_leaf_as_extension(p1)[0],
-            p0,
-        ));
-        index >>= 1;
-    }
-
-    let oracle_query = OracleListQueryResult {
-        inner: oracle_queries,
-    };
-
-    SimpleBatchSingleQueryResult {
-        oracle_query,
-        commitment_query,
     }
 }
 
@@ -643,7 +586,7 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     pub fn check_merkle_path(&self, root: &Digest<E::BaseField>) {
-        // let timer = start_timer!(|| "CodewordSingleQuery::Check Merkle Path");
+        // let timer = profile_span!("CodewordSingleQuery::Check Merkle Path");
         match self.query.codepoints {
             CodewordPointPair::Ext(left, right) => {
                 self.merkle_path
@@ -654,7 +597,7 @@ where
                     .authenticate_leaves_root_base(left, right, self.query.index, root);
             }
         }
-        // end_timer!(timer);
+        // profile_exit!(timer);
     }
 }
 
@@ -671,7 +614,7 @@ struct CommitmentsQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    inner: Vec<CodewordSingleQueryResult<E>>,
+    inner: Vec<SimpleBatchCommitmentSingleQueryResult<E>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -687,49 +630,154 @@ struct CommitmentsQueryResultWithMerklePath<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    inner: Vec<CodewordSingleQueryResultWithMerklePath<E>>,
+    inner: Vec<SimpleBatchCommitmentSingleQueryResultWithMerklePath<E>>,
 }
 
-impl<E: ExtensionField> ListQueryResult<E> for OracleListQueryResult<E>
+/// The cosets opened in the oracles committed along the folding schedule, one per oracle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OracleCosetsQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    fn get_inner(&self) -> &Vec<CodewordSingleQueryResult<E>> {
-        &self.inner
-    }
+    inner: Vec<SimpleBatchCommitmentSingleQueryResult<E>>,
+}
 
-    fn get_inner_into(self) -> Vec<CodewordSingleQueryResult<E>> {
-        self.inner
+impl<E: ExtensionField> OracleCosetsQueryResult<E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    /// Open in every tree the coset that the position `x_index` of the codeword folds into.
+    fn new(trees: &[MerkleTree<E>], codeword_size_log: usize, x_index: usize) -> Self {
+        Self {
+            inner: trees
+                .iter()
+                .map(|tree| {
+                    // Each column holds two positions of every coset.
+                    let (num_columns, num_rows) = tree.size();
+                    let layer = codeword_size_log - log2_strict(num_columns * num_rows);
+                    let coset_log = log2_strict(num_columns) + 1;
+                    let p0 = (x_index >> (layer + coset_log)) << 1;
+                    batch_leaves_get_query(tree.leaves(), p0, p0 + 1)
+                })
+                .collect(),
+        }
     }
 }
 
-impl<E: ExtensionField> ListQueryResult<E> for CommitmentsQueryResult<E>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OracleCosetsQueryResultWithMerklePath<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    fn get_inner(&self) -> &Vec<CodewordSingleQueryResult<E>> {
-        &self.inner
+    inner: Vec<SimpleBatchCommitmentSingleQueryResultWithMerklePath<E>>,
+}
+
+impl<E: ExtensionField> OracleCosetsQueryResultWithMerklePath<E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    fn from_query_and_trees(
+        query_result: OracleCosetsQueryResult<E>,
+        trees: &[MerkleTree<E>],
+    ) -> Self {
+        Self {
+            inner: query_result
+                .inner
+                .into_iter()
+                .zip_eq(trees)
+                .map(|(query, tree)| {
+                    let merkle_path = tree.merkle_path_without_leaf_sibling_or_root(query.index);
+                    SimpleBatchCommitmentSingleQueryResultWithMerklePath { query, merkle_path }
+                })
+                .collect(),
+        }
     }
 
-    fn get_inner_into(self) -> Vec<CodewordSingleQueryResult<E>> {
+    fn check_merkle_paths(&self, roots: &[Digest<E::BaseField>]) {
+        assert_eq!(self.inner.len(), roots.len());
         self.inner
+            .iter()
+            .zip(roots)
+            .for_each(|(q, root)| q.check_merkle_path(root));
+    }
+
+    /// Fold `pair`, the pair opened in the commitment at `index`, through all the rounds. The
+    /// folded values must match the cosets opened in the committed oracles, which the
+    /// verifier folds by itself until the next committed oracle, and the final codeword.
+    #[allow(clippy::too_many_arguments)]
+    fn check_folds<Spec: BasefoldSpec<E>>(
+        &self,
+        vp: &<Spec::EncodingScheme as EncodingScheme<E>>::VerifierParameters,
+        fold_challenges: &[E],
+        num_rounds: usize,
+        num_vars: usize,
+        final_codeword: &[E],
+        (left, right): (E, E),
+        index: usize,
+    ) {
+        let fold = |round: usize, pair_index: usize, left: E, right: E| {
+            let (x0, x1, w) = <Spec::EncodingScheme as EncodingScheme<E>>::verifier_folding_coeffs(
+                vp,
+                num_vars + Spec::get_rate_log() - round - 1,
+                pair_index,
+            );
+            interpolate2_weights([(x0, left), (x1, right)], w, fold_challenges[round])
+        };
+
+        let mut value = fold(0, index >> 1, left, right);
+        for ((layer, coset_log), query) in
+            committed_layers::<Spec::FoldingSchedule>(num_rounds).zip_eq(&self.inner)
+        {
+            let position = index >> layer;
+            let coset_index = position >> coset_log;
+            assert_eq!(
+                query.query.index,
+                coset_index << 1,
+                "Wrong coset at round {layer}"
+            );
+            let coset = query.query.interleaved_ext();
+            assert_eq!(coset.len(), 1 << coset_log);
+            assert_eq!(
+                coset[position & ((1 << coset_log) - 1)],
+                value,
+                "Failed at round {}",
+                layer - 1
+            );
+
+            value = <Spec::EncodingScheme as EncodingScheme<E>>::verifier_fold_coset(
+                vp,
+                num_vars + Spec::get_rate_log() - layer - 1,
+                coset_index,
+                coset,
+                &fold_challenges[layer..layer + coset_log],
+            );
+        }
+
+        // Note that final_codeword has been bit-reversed, so no need to bit-reverse
+        // the index here.
+        assert_eq!(
+            value,
+            final_codeword[index >> num_rounds],
+            "Failed at round {}",
+            num_rounds - 1
+        );
     }
 }
 
-impl<E: ExtensionField> ListQueryResultWithMerklePath<E> for OracleListQueryResultWithMerklePath<E>
+impl<E: ExtensionField> ListQueryResult<E> for OracleListQueryResult<E>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    fn get_inner(&self) -> &Vec<CodewordSingleQueryResultWithMerklePath<E>> {
+    fn get_inner(&self) -> &Vec<CodewordSingleQueryResult<E>> {
         &self.inner
     }
 
-    fn new(inner: Vec<CodewordSingleQueryResultWithMerklePath<E>>) -> Self {
-        Self { inner }
+    fn get_inner_into(self) -> Vec<CodewordSingleQueryResult<E>> {
+        self.inner
     }
 }
 
-impl<E: ExtensionField> ListQueryResultWithMerklePath<E> for CommitmentsQueryResultWithMerklePath<E>
+impl<E: ExtensionField> ListQueryResultWithMerklePath<E> for OracleListQueryResultWithMerklePath<E>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
@@ -792,14 +840,14 @@ where
     }
 
     fn check_merkle_paths(&self, roots: &[Digest<E::BaseField>]) {
-        // let timer = start_timer!(|| "ListQuery::Check Merkle Path");
+        // let timer = profile_span!("ListQuery::Check Merkle Path");
         self.get_inner()
             .iter()
             .zip(roots.iter())
             .for_each(|(q, root)| {
                 q.check_merkle_path(root);
             });
-        // end_timer!(timer);
+        // profile_exit!(timer);
     }
 }
 
@@ -808,7 +856,7 @@ struct SingleQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    oracle_query: OracleListQueryResult<E>,
+    oracle_query: OracleCosetsQueryResult<E>,
     commitment_query: CodewordSingleQueryResult<E>,
 }
 
@@ -817,7 +865,7 @@ struct SingleQueryResultWithMerklePath<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    oracle_query: OracleListQueryResultWithMerklePath<E>,
+    oracle_query: OracleCosetsQueryResultWithMerklePath<E>,
     commitment_query: CodewordSingleQueryResultWithMerklePath<E>,
 }
 
@@ -832,9 +880,9 @@ where
     ) -> Self {
         assert!(commitment.codeword_tree.height() > 0);
         Self {
-            oracle_query: OracleListQueryResultWithMerklePath::from_query_and_trees(
+            oracle_query: OracleCosetsQueryResultWithMerklePath::from_query_and_trees(
                 single_query_result.oracle_query,
-                |i, j| oracle_trees[i].merkle_path_without_leaf_sibling_or_root(j),
+                oracle_trees,
             ),
             commitment_query: CodewordSingleQueryResultWithMerklePath {
                 query: single_query_result.commitment_query,
@@ -859,45 +907,26 @@ where
         comm: &BasefoldCommitment<E>,
         index: usize,
     ) {
-        // let timer = start_timer!(|| "Checking codeword single query");
+        // let timer = profile_span!("Checking codeword single query");
         self.oracle_query.check_merkle_paths(roots);
+        assert_eq!(
+            self.commitment_query.merkle_path.leaf_width_log(),
+            Spec::get_leaf_width_log(),
+            "Wrong leaf width of the commitment"
+        );
         self.commitment_query
             .check_merkle_path(&Digest(comm.root().0));
 
-        let (mut curr_left, mut curr_right) = self.commitment_query.query.codepoints.as_ext();
-
-        let mut right_index = index | 1;
-        let mut left_index = right_index - 1;
-
-        for (i, fold_challenge) in fold_challenges.iter().enumerate().take(num_rounds) {
-            let (x0, x1, w) = <Spec::EncodingScheme as EncodingScheme<E>>::verifier_folding_coeffs(
-                vp,
-                num_vars + Spec::get_rate_log() - i - 1,
-                left_index >> 1,
-            );
-
-            let res = interpolate2_weights([(x0, curr_left), (x1, curr_right)], w, *fold_challenge);
-
-            let next_index = right_index >> 1;
-            let next_oracle_value = if i < num_rounds - 1 {
-                right_index = next_index | 1;
-                left_index = right_index - 1;
-                let next_oracle_query = self.oracle_query.get_inner()[i].clone();
-                (curr_left, curr_right) = next_oracle_query.query.codepoints.as_ext();
-                if next_index & 1 == 0 {
-                    curr_left
-                } else {
-                    curr_right
-                }
-            } else {
-                // Note that final_codeword has been bit-reversed, so no need to bit-reverse
-                // next_index here.
-                final_codeword[next_index]
-            };
-            assert_eq!(res, next_oracle_value, "Failed at round {}", i);
-            // end_timer!(round_timer);
-        }
-        // end_timer!(timer);
+        self.oracle_query.check_folds::<Spec>(
+            vp,
+            fold_challenges,
+            num_rounds,
+            num_vars,
+            final_codeword,
+            self.commitment_query.query.codepoints.as_ext(),
+            index,
+        );
+        // profile_exit!(timer);
     }
 }
 
@@ -959,7 +988,11 @@ where
         roots: &[Digest<E::BaseField>],
         comm: &BasefoldCommitment<E>,
     ) {
-        self.inner.par_iter().zip(indices.par_iter()).for_each(
+        // One opening per distinct index, none missing.
+        assert_eq!(self.inner.len(), indices.len());
+        // Without the `parallel` feature, e.g. in wasm, the queries are checked in sequence.
+        parallelize_iter(
+            self.inner.iter().zip(indices.iter()),
             |((index, query), index_in_proof)| {
                 assert_eq!(index_in_proof, index);
                 query.check::<Spec>(
@@ -1002,21 +1035,27 @@ where
     pub fn from_batched_single_query_result(
         batched_single_query_result: BatchedSingleQueryResult<E>,
         oracle_trees: &[MerkleTree<E>],
-        commitments: &[BasefoldCommitmentWithData<E>],
+        commitments: &[&BasefoldCommitmentWithData<E>],
     ) -> Self {
         Self {
             oracle_query: OracleListQueryResultWithMerklePath::from_query_and_trees(
                 batched_single_query_result.oracle_query,
                 |i, j| oracle_trees[i].merkle_path_without_leaf_sibling_or_root(j),
             ),
-            commitments_query: CommitmentsQueryResultWithMerklePath::from_query_and_trees(
-                batched_single_query_result.commitments_query,
-                |i, j| {
-                    commitments[i]
-                        .codeword_tree
-                        .merkle_path_without_leaf_sibling_or_root(j)
-                },
-            ),
+            commitments_query: CommitmentsQueryResultWithMerklePath {
+                inner: batched_single_query_result
+                    .commitments_query
+                    .inner
+                    .into_iter()
+                    .zip_eq(commitments)
+                    .map(|(query, comm)| {
+                        let merkle_path = comm
+                            .codeword_tree
+                            .merkle_path_without_leaf_sibling_or_root(query.index);
+                        SimpleBatchCommitmentSingleQueryResultWithMerklePath { query, merkle_path }
+                    })
+                    .collect(),
+            },
         }
     }
 
@@ -1031,17 +1070,23 @@ where
         roots: &[Digest<E::BaseField>],
         comms: &[&BasefoldCommitment<E>],
         coeffs: &[E],
+        batch_coeffs: &[E],
         index: usize,
     ) {
         self.oracle_query.check_merkle_paths(roots);
-        self.commitments_query.check_merkle_paths(
-            comms
-                .iter()
-                .map(|comm| comm.root())
-                .collect_vec()
-                .as_slice(),
-        );
-        // end_timer!(commit_timer);
+        self.commitments_query
+            .inner
+            .iter()
+            .zip_eq(comms)
+            .for_each(|(q, comm)| {
+                assert_eq!(
+                    q.merkle_path.leaf_width_log(),
+                    Spec::get_leaf_width_log(),
+                    "Wrong leaf width of the commitment"
+                );
+                q.check_merkle_path(&comm.root())
+            });
+        // profile_exit!(commit_timer);
 
         let mut curr_left = E::ZERO;
         let mut curr_right = E::ZERO;
@@ -1054,15 +1099,16 @@ where
             let matching_comms = comms
                 .iter()
                 .enumerate()
-                .filter(|(_, comm)| comm.num_vars().unwrap() == num_vars - i)
+                .filter(|(_, comm)| comm.num_vars() == num_vars - i)
                 .map(|(index, _)| index)
                 .collect_vec();
 
             matching_comms.iter().for_each(|index| {
-                let query = self.commitments_query.get_inner()[*index].query;
+                let query = &self.commitments_query.inner[*index].query;
                 assert_eq!(query.index >> 1, left_index >> 1);
-                curr_left += query.left_ext() * coeffs[*index];
-                curr_right += query.right_ext() * coeffs[*index];
+                let (left, right) = query.leaves.batch(batch_coeffs);
+                curr_left += left * coeffs[*index];
+                curr_right += right * coeffs[*index];
             });
 
             let (x0, x1, w) = <Spec::EncodingScheme as EncodingScheme<E>>::verifier_folding_coeffs(
@@ -1099,18 +1145,18 @@ where
                 let matching_comms = comms
                     .iter()
                     .enumerate()
-                    .filter(|(_, comm)| comm.num_vars().unwrap() == num_vars - i - 1)
+                    .filter(|(_, comm)| comm.num_vars() == num_vars - i - 1)
                     .map(|(index, _)| index)
                     .collect_vec();
 
                 matching_comms.iter().for_each(|index| {
-                    let query: CodewordSingleQueryResult<E> =
-                        self.commitments_query.get_inner()[*index].query;
+                    let query = &self.commitments_query.inner[*index].query;
                     assert_eq!(query.index >> 1, next_index >> 1);
+                    let (left, right) = query.leaves.batch(batch_coeffs);
                     if next_index & 1 == 0 {
-                        res += query.left_ext() * coeffs[*index];
+                        res += left * coeffs[*index];
                     } else {
-                        res += query.right_ext() * coeffs[*index];
+                        res += right * coeffs[*index];
                     }
                 });
 
@@ -1119,9 +1165,9 @@ where
                 final_codeword[next_index]
             };
             assert_eq!(res, next_oracle_value, "Failed at round {}", i);
-            // end_timer!(round_timer);
+            // profile_exit!(round_timer);
         }
-        // end_timer!(timer);
+        // profile_exit!(timer);
     }
 }
 
@@ -1147,7 +1193,7 @@ where
     pub fn from_batched_query_result(
         batched_query_result: BatchedQueriesResult<E>,
         oracle_trees: &[MerkleTree<E>],
-        commitments: &[BasefoldCommitmentWithData<E>],
+        commitments: &[&BasefoldCommitmentWithData<E>],
     ) -> Self {
         Self {
             inner: batched_query_result
@@ -1179,9 +1225,13 @@ where
         roots: &[Digest<E::BaseField>],
         comms: &[&BasefoldCommitment<E>],
         coeffs: &[E],
+        batch_coeffs: &[E],
     ) {
-        let timer = start_timer!(|| "BatchedQueriesResult::check");
-        self.inner.par_iter().zip(indices.par_iter()).for_each(
+        let timer = profile_span!("BatchedQueriesResult::check");
+        // One opening per distinct index, none missing.
+        assert_eq!(self.inner.len(), indices.len());
+        parallelize_iter(
+            self.inner.iter().zip(indices.iter()),
             |((index, query), index_in_proof)| {
                 assert_eq!(index, index_in_proof);
                 query.check::<Spec>(
@@ -1193,11 +1243,12 @@ where
                     roots,
                     comms,
                     coeffs,
+                    batch_coeffs,
                     *index,
                 );
             },
         );
-        end_timer!(timer);
+        profile_exit!(timer);
     }
 }
 
@@ -1243,6 +1294,17 @@ where
             SimpleBatchLeavesPair::Base(x) => x.iter().map(|(_, x)| E::from(*x)).collect(),
         }
     }
+
+    /// The values of the two leaves of all the columns, as `left, right` per column.
+    fn interleaved_ext(&self) -> Vec<E> {
+        match &self.leaves {
+            SimpleBatchLeavesPair::Ext(x) => x.iter().flat_map(|(l, r)| [*l, *r]).collect(),
+            SimpleBatchLeavesPair::Base(x) => x
+                .iter()
+                .flat_map(|(l, r)| [E::from(*l), E::from(*r)])
+                .collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1259,7 +1321,7 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     pub fn check_merkle_path(&self, root: &Digest<E::BaseField>) {
-        // let timer = start_timer!(|| "CodewordSingleQuery::Check Merkle Path");
+        // let timer = profile_span!("CodewordSingleQuery::Check Merkle Path");
         match &self.query.leaves {
             SimpleBatchLeavesPair::Ext(inner) => {
                 self.merkle_path.authenticate_batch_leaves_root_ext(
@@ -1278,7 +1340,7 @@ where
                 );
             }
         }
-        // end_timer!(timer);
+        // profile_exit!(timer);
     }
 }
 
@@ -1287,7 +1349,7 @@ struct SimpleBatchSingleQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    oracle_query: OracleListQueryResult<E>,
+    oracle_query: OracleCosetsQueryResult<E>,
     commitment_query: SimpleBatchCommitmentSingleQueryResult<E>,
 }
 
@@ -1296,7 +1358,7 @@ struct SimpleBatchSingleQueryResultWithMerklePath<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    oracle_query: OracleListQueryResultWithMerklePath<E>,
+    oracle_query: OracleCosetsQueryResultWithMerklePath<E>,
     commitment_query: SimpleBatchCommitmentSingleQueryResultWithMerklePath<E>,
 }
 
@@ -1310,9 +1372,9 @@ where
         commitment: &BasefoldCommitmentWithData<E>,
     ) -> Self {
         Self {
-            oracle_query: OracleListQueryResultWithMerklePath::from_query_and_trees(
+            oracle_query: OracleCosetsQueryResultWithMerklePath::from_query_and_trees(
                 single_query_result.oracle_query,
-                |i, j| oracle_trees[i].merkle_path_without_leaf_sibling_or_root(j),
+                oracle_trees,
             ),
             commitment_query: SimpleBatchCommitmentSingleQueryResultWithMerklePath {
                 query: single_query_result.commitment_query.clone(),
@@ -1339,45 +1401,23 @@ where
         index: usize,
     ) {
         self.oracle_query.check_merkle_paths(roots);
+        assert_eq!(
+            self.commitment_query.merkle_path.leaf_width_log(),
+            Spec::get_leaf_width_log(),
+            "Wrong leaf width of the commitment"
+        );
         self.commitment_query
             .check_merkle_path(&Digest(comm.root().0));
 
-        let (mut curr_left, mut curr_right) =
-            self.commitment_query.query.leaves.batch(batch_coeffs);
-
-        let mut right_index = index | 1;
-        let mut left_index = right_index - 1;
-
-        for (i, fold_challenge) in fold_challenges.iter().enumerate().take(num_rounds) {
-            // let round_timer = start_timer!(|| format!("SingleQueryResult::round {}", i));
-
-            let (x0, x1, w) = <Spec::EncodingScheme as EncodingScheme<E>>::verifier_folding_coeffs(
-                vp,
-                num_vars + Spec::get_rate_log() - i - 1,
-                left_index >> 1,
-            );
-
-            let res = interpolate2_weights([(x0, curr_left), (x1, curr_right)], w, *fold_challenge);
-
-            let next_index = right_index >> 1;
-            let next_oracle_value = if i < num_rounds - 1 {
-                right_index = next_index | 1;
-                left_index = right_index - 1;
-                let next_oracle_query = self.oracle_query.get_inner()[i].clone();
-                (curr_left, curr_right) = next_oracle_query.query.codepoints.as_ext();
-                if next_index & 1 == 0 {
-                    curr_left
-                } else {
-                    curr_right
-                }
-            } else {
-                // Note that final_codeword has been bit-reversed, so no need to bit-reverse
-                // next_index here.
-                final_codeword[next_index]
-            };
-            assert_eq!(res, next_oracle_value, "Failed at round {}", i);
-            // end_timer!(round_timer);
-        }
+        self.oracle_query.check_folds::<Spec>(
+            vp,
+            fold_challenges,
+            num_rounds,
+            num_vars,
+            final_codeword,
+            self.commitment_query.query.leaves.batch(batch_coeffs),
+            index,
+        );
     }
 }
 
@@ -1436,7 +1476,10 @@ where
         roots: &[Digest<E::BaseField>],
         comm: &BasefoldCommitment<E>,
     ) {
-        self.inner.par_iter().zip(indices.par_iter()).for_each(
+        // One opening per distinct index, none missing.
+        assert_eq!(self.inner.len(), indices.len());
+        parallelize_iter(
+            self.inner.iter().zip(indices.iter()),
             |((index, query), index_in_proof)| {
                 assert_eq!(index, index_in_proof);
                 query.check::<Spec>(
@@ -1,19 +1,26 @@
 use crate::{
+    Error, VerificationLevel,
     sum_check::classic::{Coefficients, SumcheckProof},
-    util::{hash::Digest, merkle_tree::MerkleTree},
+    util::{
+        hash::Digest, log2_strict, merkle_tree::MerkleTree, prover_workspace::ProverWorkspace,
+        verifier_cache::VerifierCache,
+    },
 };
 use core::fmt::Debug;
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use multilinear_extensions::mle::FieldType;
 
-use std::{marker::PhantomData, slice};
+use std::{marker::PhantomData, slice, sync::Arc};
+use transcript::Transcript;
 
 pub use super::encoding::{EncodingProverParameters, EncodingScheme, RSCode, RSCodeDefaultSpec};
 use super::{
     Basecode, BasecodeDefaultSpec,
+    folding::{EncodingFolding, FoldingSchedule, HighArityFolding},
     query_phase::{
         BatchedQueriesResultWithMerklePath, QueriesResultWithMerklePath,
         SimpleBatchQueriesResultWithMerklePath,
@@ -39,12 +46,33 @@ where
 ))]
 pub struct BasefoldProverParams<E: ExtensionField, Spec: BasefoldSpec<E>> {
     pub encoding_params: <Spec::EncodingScheme as EncodingScheme<E>>::ProverParameters,
+    #[serde(default)]
+    pub verification_level: VerificationLevel,
+    /// Shared by the clones of these parameters.
+    #[serde(skip)]
+    pub(super) workspace: Arc<ProverWorkspace<E>>,
 }
 
 impl<E: ExtensionField, Spec: BasefoldSpec<E>> BasefoldProverParams<E, Spec> {
     pub fn get_max_message_size_log(&self) -> usize {
         self.encoding_params.get_max_message_size_log()
     }
+
+    pub fn with_verification_level(mut self, verification_level: VerificationLevel) -> Self {
+        self.verification_level = verification_level;
+        self
+    }
+
+    /// Take the prover buffers from `workspace`, e.g. to share one pool among
+    /// the parameters trimmed for several sizes.
+    pub fn with_workspace(mut self, workspace: Arc<ProverWorkspace<E>>) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    pub fn workspace(&self) -> &Arc<ProverWorkspace<E>> {
+        &self.workspace
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -54,6 +82,18 @@ impl<E: ExtensionField, Spec: BasefoldSpec<E>> BasefoldProverParams<E, Spec> {
 ))]
 pub struct BasefoldVerifierParams<E: ExtensionField, Spec: BasefoldSpec<E>> {
     pub(super) encoding_params: <Spec::EncodingScheme as EncodingScheme<E>>::VerifierParameters,
+    /// Shared by the clones of these parameters.
+    #[serde(skip)]
+    pub(super) eq_cache: Arc<VerifierCache<E>>,
+}
+
+impl<E: ExtensionField, Spec: BasefoldSpec<E>> BasefoldVerifierParams<E, Spec> {
+    /// Keep up to `capacity` eq vectors across verifications, instead of
+    /// [`crate::util::verifier_cache::DEFAULT_VERIFIER_CACHE_CAPACITY`].
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.eq_cache = Arc::new(VerifierCache::new(capacity));
+        self
+    }
 }
 
 /// A polynomial commitment together with all the data (e.g., the codeword, and Merkle tree)
@@ -105,7 +145,7 @@ where
     }
 
     pub fn codeword_size_log(&self) -> usize {
-        self.codeword_tree.height()
+        log2_strict(self.codeword_size())
     }
 
     pub fn poly_size(&self) -> usize {
@@ -133,6 +173,53 @@ where
     }
 }
 
+impl<E: ExtensionField> BasefoldCommitmentWithData<E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    /// The part of the commitment worth storing: the root of the Merkle tree, without the
+    /// codewords, the evaluations and the inner layers, which `Basefold::hydrate` recomputes
+    /// from the polynomials.
+    pub fn to_compact(&self) -> BasefoldCompactCommitmentWithData<E> {
+        BasefoldCompactCommitmentWithData {
+            root: self.codeword_tree.root(),
+            num_vars: self.num_vars,
+            is_base: self.is_base,
+            num_polys: self.num_polys,
+        }
+    }
+}
+
+/// A [`BasefoldCommitmentWithData`] reduced to its root and shape, to be stored or sent over
+/// the wire instead of the full commitment. `Basefold::hydrate` turns it back into a
+/// commitment that can be opened, rebuilding the Merkle tree from the polynomials and checking
+/// that it has the same root.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = ""))]
+pub struct BasefoldCompactCommitmentWithData<E: ExtensionField>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    pub(crate) root: Digest<E::BaseField>,
+    pub(crate) num_vars: usize,
+    pub(crate) is_base: bool,
+    pub(crate) num_polys: usize,
+}
+
+impl<E: ExtensionField> BasefoldCompactCommitmentWithData<E>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    pub fn to_commitment(&self) -> BasefoldCommitment<E> {
+        BasefoldCommitment::new(
+            self.root.clone(),
+            self.num_vars,
+            self.is_base,
+            self.num_polys,
+        )
+    }
+}
+
 impl<E: ExtensionField> From<BasefoldCommitmentWithData<E>> for Digest<E::BaseField>
 where
     E::BaseField: Serialize + DeserializeOwned,
@@ -151,6 +238,10 @@ where
     }
 }
 
+/// Version of the layout written by [`BasefoldCommitment::to_bytes`].
+const COMMITMENT_ENCODING_VERSION: u8 = 1;
+const COMMITMENT_FLAG_IS_BASE: u8 = 1;
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(bound(serialize = "", deserialize = ""))]
 pub struct BasefoldCommitment<E: ExtensionField>
@@ -158,9 +249,9 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     pub(super) root: Digest<E::BaseField>,
-    pub(super) num_vars: Option<usize>,
+    pub(super) num_vars: usize,
     pub(super) is_base: bool,
-    pub(super) num_polys: Option<usize>,
+    pub(super) num_polys: usize,
 }
 
 impl<E: ExtensionField> BasefoldCommitment<E>
@@ -175,9 +266,9 @@ where
     ) -> Self {
         Self {
             root,
-            num_vars: Some(num_vars),
+            num_vars,
             is_base,
-            num_polys: Some(num_polys),
+            num_polys,
         }
     }
 
@@ -185,13 +276,92 @@ where
         self.root.clone()
     }
 
-    pub fn num_vars(&self) -> Option<usize> {
+    pub fn num_vars(&self) -> usize {
         self.num_vars
     }
 
     pub fn is_base(&self) -> bool {
         self.is_base
     }
+
+    /// Encode as `root || num_vars: u8 || flags: u8 || num_polys: u32`, with the root elements
+    /// and `num_polys` in little-endian. The flags hold the layout version in the high nibble
+    /// and `is_base` in the lowest bit. Fails if `num_vars` or `num_polys` do not fit.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let num_vars = u8::try_from(self.num_vars).map_err(|_| {
+            Error::Serialization(format!("num_vars {} does not fit in a byte", self.num_vars))
+        })?;
+        let num_polys = u32::try_from(self.num_polys).map_err(|_| {
+            Error::Serialization(format!(
+                "num_polys {} does not fit in a u32",
+                self.num_polys
+            ))
+        })?;
+        let mut flags = COMMITMENT_ENCODING_VERSION << 4;
+        if self.is_base {
+            flags |= COMMITMENT_FLAG_IS_BASE;
+        }
+
+        let mut bytes = Vec::with_capacity(Self::encoded_len());
+        for x in self.root.0.iter() {
+            bytes.extend_from_slice(&x.to_canonical_u64().to_le_bytes());
+        }
+        bytes.push(num_vars);
+        bytes.push(flags);
+        bytes.extend_from_slice(&num_polys.to_le_bytes());
+        Ok(bytes)
+    }
+
+    /// Decode the layout of [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::encoded_len() {
+            return Err(Error::Serialization(format!(
+                "commitment of {} bytes, expected {}",
+                bytes.len(),
+                Self::encoded_len()
+            )));
+        }
+        let (root_bytes, rest) = bytes.split_at(bytes.len() - 6);
+
+        let root = root_bytes
+            .chunks_exact(8)
+            .map(|chunk| {
+                let x = u64::from_le_bytes(chunk.try_into().unwrap());
+                if x < E::BaseField::MODULUS_U64 {
+                    Ok(E::BaseField::from(x))
+                } else {
+                    Err(Error::Serialization(format!(
+                        "non-canonical field element {x:#x} in commitment root"
+                    )))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (num_vars, flags) = (rest[0], rest[1]);
+        if flags >> 4 != COMMITMENT_ENCODING_VERSION {
+            return Err(Error::Serialization(format!(
+                "unsupported commitment encoding version {}",
+                flags >> 4
+            )));
+        }
+        if flags & 0xf & !COMMITMENT_FLAG_IS_BASE != 0 {
+            return Err(Error::Serialization(format!(
+                "unknown commitment flags {flags:#x}"
+            )));
+        }
+        let num_polys = u32::from_le_bytes(rest[2..6].try_into().unwrap());
+
+        Ok(Self {
+            root: Digest::try_from(root).map_err(Error::Serialization)?,
+            num_vars: num_vars as usize,
+            is_base: flags & COMMITMENT_FLAG_IS_BASE != 0,
+            num_polys: num_polys as usize,
+        })
+    }
+
+    fn encoded_len() -> usize {
+        Digest::<E::BaseField>::default().0.len() * 8 + 6
+    }
 }
 
 impl<E: ExtensionField> PartialEq for BasefoldCommitmentWithData<E>
@@ -211,6 +381,10 @@ impl<E: ExtensionField> Eq for BasefoldCommitmentWithData<E> where
 
 pub trait BasefoldSpec<E: ExtensionField>: Debug + Clone {
     type EncodingScheme: EncodingScheme<E>;
+    /// Which oracles of the commit phase are committed by `open` and `simple_batch_open`.
+    /// The openings of polynomials of different sizes merge them into the oracle between
+    /// rounds, so they always commit to every oracle.
+    type FoldingSchedule: FoldingSchedule;
 
     fn get_number_queries() -> usize {
         Self::EncodingScheme::get_number_queries()
@@ -223,6 +397,14 @@ pub trait BasefoldSpec<E: ExtensionField>: Debug + Clone {
     fn get_basecode_msg_size_log() -> usize {
         Self::EncodingScheme::get_basecode_msg_size_log()
     }
+
+    /// Each leaf of the Merkle trees of the commitments holds `2^leaf_width_log` rows of the
+    /// codewords. Wider leaves make shorter Merkle paths, for the price of opening the whole
+    /// leaf at every query. The commitments to polynomials too small to be encoded, and the
+    /// oracles of the commit phase, always have leaves of a pair of rows.
+    fn get_leaf_width_log() -> usize {
+        1
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -233,6 +415,7 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     type EncodingScheme = Basecode<BasecodeDefaultSpec>;
+    type FoldingSchedule = EncodingFolding<E, Self::EncodingScheme>;
 }
 
 #[derive(Debug, Clone)]
@@ -243,6 +426,39 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     type EncodingScheme = RSCode<RSCodeDefaultSpec>;
+    type FoldingSchedule = EncodingFolding<E, Self::EncodingScheme>;
+}
+
+/// Basefold over Reed-Solomon codes that commits to the oracle of every `ARITY_LOG`-th round,
+/// for smaller proofs with the same number of queries.
+#[derive(Debug, Clone)]
+pub struct BasefoldRSHighArityParams<const ARITY_LOG: usize>;
+
+impl<E: ExtensionField, const ARITY_LOG: usize> BasefoldSpec<E>
+    for BasefoldRSHighArityParams<ARITY_LOG>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    type EncodingScheme = RSCode<RSCodeDefaultSpec>;
+    type FoldingSchedule = HighArityFolding<ARITY_LOG>;
+}
+
+/// Basefold over Reed-Solomon codes whose commitments have leaves of `2^LEAF_WIDTH_LOG` rows,
+/// for shorter Merkle paths in the queries.
+#[derive(Debug, Clone)]
+pub struct BasefoldRSWideLeavesParams<const LEAF_WIDTH_LOG: usize>;
+
+impl<E: ExtensionField, const LEAF_WIDTH_LOG: usize> BasefoldSpec<E>
+    for BasefoldRSWideLeavesParams<LEAF_WIDTH_LOG>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    type EncodingScheme = RSCode<RSCodeDefaultSpec>;
+    type FoldingSchedule = EncodingFolding<E, Self::EncodingScheme>;
+
+    fn get_leaf_width_log() -> usize {
+        LEAF_WIDTH_LOG
+    }
 }
 
 #[derive(Debug)]
@@ -312,17 +528,35 @@ where
     }
 }
 
+/// An opening to check with `Basefold::verify_many`: what [`crate::PolynomialCommitmentScheme::verify`]
+/// takes besides the parameters and the proof, with the transcript of the proof in the state
+/// in which the opening starts.
+pub struct BasefoldOpeningClaim<'a, E: ExtensionField>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    pub comm: &'a BasefoldCommitment<E>,
+    pub point: &'a [E],
+    pub eval: E,
+    pub transcript: Transcript<E>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasefoldProof<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
+    /// The evaluations at 0 and 2 of each sum-check round polynomial.
     pub(crate) sumcheck_messages: Vec<Vec<E>>,
     pub(crate) roots: Vec<Digest<E::BaseField>>,
     pub(crate) final_message: Vec<E>,
     pub(crate) query_result_with_merkle_path: ProofQueriesResultWithMerklePath<E>,
     pub(crate) sumcheck_proof: Option<SumcheckProof<E, Coefficients<E>>>,
     pub(crate) trivial_proof: Vec<FieldType<E>>,
+    /// Debug section: the transcript digests of the prover at the milestones of the protocol.
+    /// They are not part of the argument, but locate where the verifier's transcript diverges.
+    #[serde(default)]
+    pub(crate) transcript_digests: Vec<(String, Digest<E::BaseField>)>,
 }
 
 impl<E: ExtensionField> BasefoldProof<E>
@@ -339,6 +573,7 @@ where
             ),
             sumcheck_proof: None,
             trivial_proof: evals,
+            transcript_digests: vec![],
         }
     }
 
@@ -352,6 +587,7 @@ pub struct BasefoldCommitPhaseProof<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
+    /// The evaluations at 0 and 2 of each sum-check round polynomial.
     pub(crate) sumcheck_messages: Vec<Vec<E>>,
     pub(crate) roots: Vec<Digest<E::BaseField>>,
     pub(crate) final_message: Vec<E>,
@@ -1,10 +1,7 @@
+use crate::util::parallel::prelude::*;
 use ff::Field;
 use ff_ext::ExtensionField;
 use multilinear_extensions::mle::FieldType;
-use rayon::prelude::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-    ParallelSliceMut,
-};
 
 pub fn sum_check_first_round_field_type<E: ExtensionField>(
     eq: &mut [E],
@@ -0,0 +1,333 @@
+//! Programmatic benchmark harness for polynomial commitment schemes.
+//!
+//! [`run`] measures commit, open, batch_open, verify and batch_verify over a range of
+//! polynomial sizes and returns a serializable [`Report`]. Reports from two revisions can be
+//! compared with [`Report::regressions`], so performance work can be checked in CI rather
+//! than by eyeballing criterion output.
+
+use std::{
+    fmt::Display,
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use multilinear_extensions::mle::{DenseMultilinearExtension, MultilinearExtension};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use transcript::Transcript;
+
+use crate::{Evaluation, PolynomialCommitmentScheme};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Op {
+    Commit,
+    Open,
+    BatchOpen,
+    Verify,
+    BatchVerify,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchConfig {
+    /// log2 of the polynomial sizes to measure
+    pub num_vars: RangeInclusive<usize>,
+    /// number of polynomials opened by batch_open / batch_verify
+    pub batch_size: usize,
+    /// whether polynomials are over the base field or the extension field
+    pub is_base: bool,
+    /// number of timed runs per operation and size
+    pub num_samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            num_vars: 16..=24,
+            batch_size: 4,
+            is_base: true,
+            num_samples: 10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Measurement {
+    pub op: Op,
+    pub num_vars: usize,
+    pub samples: Vec<Duration>,
+}
+
+impl Measurement {
+    pub fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub op: Op,
+    pub num_vars: usize,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} at 2^{}: {:?} -> {:?} ({:+.1}%)",
+            self.op,
+            self.num_vars,
+            self.baseline,
+            self.current,
+            (self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0) * 100.0
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Report {
+    pub fn get(&self, op: Op, num_vars: usize) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .find(|m| m.op == op && m.num_vars == num_vars)
+    }
+
+    /// Operations whose fastest sample is slower than the baseline's fastest sample by more
+    /// than `tolerance`, e.g. `0.1` for 10%. Operations missing from the baseline are skipped.
+    pub fn regressions(&self, baseline: &Report, tolerance: f64) -> Vec<Regression> {
+        self.measurements
+            .iter()
+            .filter_map(|m| {
+                let base = baseline.get(m.op, m.num_vars)?;
+                let (baseline, current) = (base.min(), m.min());
+                (current.as_secs_f64() > baseline.as_secs_f64() * (1.0 + tolerance)).then_some(
+                    Regression {
+                        op: m.op,
+                        num_vars: m.num_vars,
+                        baseline,
+                        current,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn assert_no_regression(&self, baseline: &Report, tolerance: f64) {
+        let regressions = self.regressions(baseline, tolerance);
+        assert!(
+            regressions.is_empty(),
+            "performance regressions beyond {:.1}%:\n{}",
+            tolerance * 100.0,
+            regressions.iter().join("\n")
+        );
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for m in &self.measurements {
+            writeln!(
+                f,
+                "{:?} at 2^{}: mean {:?}, min {:?}",
+                m.op,
+                m.num_vars,
+                m.mean(),
+                m.min()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn sample<T>(num_samples: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    (0..num_samples)
+        .map(|_| {
+            let timer = Instant::now();
+            let _ = f();
+            timer.elapsed()
+        })
+        .collect()
+}
+
+fn random_poly<E: ExtensionField>(
+    num_vars: usize,
+    is_base: bool,
+    rng: &mut ChaCha8Rng,
+) -> DenseMultilinearExtension<E> {
+    if is_base {
+        DenseMultilinearExtension::random(num_vars, rng)
+    } else {
+        DenseMultilinearExtension::from_evaluations_ext_vec(
+            num_vars,
+            (0..1 << num_vars).map(|_| E::random(&mut *rng)).collect(),
+        )
+    }
+}
+
+pub fn run<E: ExtensionField, Pcs: PolynomialCommitmentScheme<E>>(config: &BenchConfig) -> Report {
+    assert!(config.num_samples > 0);
+    let mut rng = ChaCha8Rng::from_seed([0u8; 32]);
+    let mut report = Report::default();
+
+    for num_vars in config.num_vars.clone() {
+        let (pp, vp) = {
+            let poly_size = 1 << num_vars;
+            let param = Pcs::setup(poly_size).unwrap();
+            Pcs::trim(param, poly_size).unwrap()
+        };
+        let mut push = |op, samples| {
+            report.measurements.push(Measurement {
+                op,
+                num_vars,
+                samples,
+            })
+        };
+
+        // Single polynomial
+        let poly = random_poly::<E>(num_vars, config.is_base, &mut rng);
+        push(
+            Op::Commit,
+            sample(config.num_samples, || Pcs::commit(&pp, &poly).unwrap()),
+        );
+
+        let mut transcript = Transcript::new(b"BaseFold");
+        let comm = Pcs::commit_and_write(&pp, &poly, &mut transcript).unwrap();
+        let point = (0..num_vars)
+            .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+            .collect_vec();
+        let eval = poly.evaluate(&point);
+        transcript.append_field_element_ext(&eval);
+        push(
+            Op::Open,
+            sample(config.num_samples, || {
+                Pcs::open(&pp, &poly, &comm, &point, &eval, &mut transcript.clone()).unwrap()
+            }),
+        );
+
+        let proof = Pcs::open(&pp, &poly, &comm, &point, &eval, &mut transcript.clone()).unwrap();
+        let comm = Pcs::get_pure_commitment(&comm);
+        let mut transcript = Transcript::new(b"BaseFold");
+        Pcs::write_commitment(&comm, &mut transcript).unwrap();
+        let point = (0..num_vars)
+            .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+            .collect_vec();
+        transcript.append_field_element_ext(&eval);
+        push(
+            Op::Verify,
+            sample(config.num_samples, || {
+                Pcs::verify(&vp, &comm, &point, &eval, &proof, &mut transcript.clone()).unwrap()
+            }),
+        );
+
+        // Batch of polynomials opened at a shared point
+        let polys = (0..config.batch_size)
+            .map(|_| random_poly::<E>(num_vars, config.is_base, &mut rng))
+            .collect_vec();
+        let mut transcript = Transcript::new(b"BaseFold");
+        let comms = polys
+            .iter()
+            .map(|poly| Pcs::commit_and_write(&pp, poly, &mut transcript).unwrap())
+            .collect_vec();
+        let points = vec![
+            (0..num_vars)
+                .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+                .collect_vec(),
+        ];
+        let evals = polys
+            .iter()
+            .enumerate()
+            .map(|(i, poly)| Evaluation::new(i, 0, poly.evaluate(&points[0])))
+            .collect_vec();
+        transcript.append_field_element_exts(&evals.iter().map(|e| *e.value()).collect_vec());
+        push(
+            Op::BatchOpen,
+            sample(config.num_samples, || {
+                Pcs::batch_open(
+                    &pp,
+                    &polys,
+                    &comms,
+                    &points,
+                    &evals,
+                    &mut transcript.clone(),
+                )
+                .unwrap()
+            }),
+        );
+
+        let proof = Pcs::batch_open(
+            &pp,
+            &polys,
+            &comms,
+            &points,
+            &evals,
+            &mut transcript.clone(),
+        )
+        .unwrap();
+        let mut transcript = Transcript::new(b"BaseFold");
+        let comms = comms
+            .iter()
+            .map(|comm| {
+                let comm = Pcs::get_pure_commitment(comm);
+                Pcs::write_commitment(&comm, &mut transcript).unwrap();
+                comm
+            })
+            .collect_vec();
+        let points = vec![
+            (0..num_vars)
+                .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+                .collect_vec(),
+        ];
+        transcript.append_field_element_exts(&evals.iter().map(|e| *e.value()).collect_vec());
+        push(
+            Op::BatchVerify,
+            sample(config.num_samples, || {
+                Pcs::batch_verify(
+                    &vp,
+                    &comms,
+                    &points,
+                    &evals,
+                    &proof,
+                    &mut transcript.clone(),
+                )
+                .unwrap()
+            }),
+        );
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use super::{BenchConfig, Op, run};
+    use crate::BasefoldDefault;
+
+    #[test]
+    fn test_bench_report() {
+        let config = BenchConfig {
+            num_vars: 10..=11,
+            batch_size: 2,
+            is_base: true,
+            num_samples: 1,
+        };
+        let report = run::<GoldilocksExt2, BasefoldDefault<GoldilocksExt2>>(&config);
+        assert_eq!(report.measurements.len(), 10);
+        assert!(report.get(Op::BatchVerify, 11).is_some());
+        // a report never regresses against itself
+        assert!(report.regressions(&report, 0.0).is_empty());
+    }
+}
@@ -1,11 +1,13 @@
 use ff_ext::ExtensionField;
 use itertools::Itertools;
-use multilinear_extensions::mle::DenseMultilinearExtension;
-use serde::{Serialize, de::DeserializeOwned};
+use multilinear_extensions::mle::{DenseMultilinearExtension, MultilinearExtension};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::fmt::Debug;
 use transcript::Transcript;
 use util::hash::Digest;
 
+#[cfg(feature = "benchmark")]
+pub mod bench;
 pub mod sum_check;
 pub mod util;
 
@@ -160,6 +162,10 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
         Ok(comm)
     }
 
+    /// Give back a commitment that is no longer needed, once it has been opened, so that the
+    /// next commitments can reuse its buffers. By default it is dropped.
+    fn recycle(_pp: &Self::ProverParam, _comm: Self::CommitmentWithData) {}
+
     fn open(
         pp: &Self::ProverParam,
         poly: &DenseMultilinearExtension<E>,
@@ -191,6 +197,55 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
         transcript: &mut Transcript<E>,
     ) -> Result<Self::Proof, Error>;
 
+    /// Open several batch commitments with one proof, where the polynomials of
+    /// `comms[i]` are opened at `points[i]` and `evals[i]` lists their evaluations
+    /// in the order they were committed.
+    fn multi_batch_open(
+        pp: &Self::ProverParam,
+        comms: &[&Self::CommitmentWithData],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        transcript: &mut Transcript<E>,
+    ) -> Result<Self::Proof, Error>;
+
+    /// Open `poly` at a partial point, i.e. show that `partial_poly`, committed separately in
+    /// `partial_comm`, is `poly` with its first `partial_point.len()` variables fixed as in
+    /// [`MultilinearExtension::fix_variables`].
+    ///
+    /// The transcript must already hold both commitments. The number of variables and the
+    /// partial point are appended to it, the remaining variables are drawn from it, and both
+    /// polynomials are opened there with one [`Self::multi_batch_open`]
+    /// proof. Returns the proof with their common evaluation.
+    fn open_partial(
+        pp: &Self::ProverParam,
+        poly: &DenseMultilinearExtension<E>,
+        comm: &Self::CommitmentWithData,
+        partial_point: &[E],
+        partial_poly: &DenseMultilinearExtension<E>,
+        partial_comm: &Self::CommitmentWithData,
+        transcript: &mut Transcript<E>,
+    ) -> Result<(Self::Proof, E), Error> {
+        let point = partial_open_point(poly.num_vars, partial_point, transcript)?;
+        let rest = point[partial_point.len()..].to_vec();
+        if partial_poly.num_vars != rest.len() {
+            return Err(Error::InvalidPcsOpen(format!(
+                "partial polynomial has {} variables, expected {}",
+                partial_poly.num_vars,
+                rest.len()
+            )));
+        }
+        let eval = partial_poly.evaluate(&rest);
+        transcript.append_field_element_ext(&eval);
+        let proof = Self::multi_batch_open(
+            pp,
+            &[comm, partial_comm],
+            &[point, rest],
+            &[vec![eval], vec![eval]],
+            transcript,
+        )?;
+        Ok((proof, eval))
+    }
+
     fn verify(
         vp: &Self::VerifierParam,
         comm: &Self::Commitment,
@@ -217,6 +272,69 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
         proof: &Self::Proof,
         transcript: &mut Transcript<E>,
     ) -> Result<(), Error>;
+
+    fn multi_batch_verify(
+        vp: &Self::VerifierParam,
+        comms: &[&Self::Commitment],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        proof: &Self::Proof,
+        transcript: &mut Transcript<E>,
+    ) -> Result<(), Error>;
+
+    /// Verify an opening of [`Self::open_partial`], where the committed polynomial has
+    /// `num_vars` variables. As for the opening, the transcript must already hold both
+    /// commitments.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_partial(
+        vp: &Self::VerifierParam,
+        comm: &Self::Commitment,
+        num_vars: usize,
+        partial_point: &[E],
+        partial_comm: &Self::Commitment,
+        eval: &E,
+        proof: &Self::Proof,
+        transcript: &mut Transcript<E>,
+    ) -> Result<(), Error> {
+        let point = partial_open_point(num_vars, partial_point, transcript)?;
+        let rest = point[partial_point.len()..].to_vec();
+        transcript.append_field_element_ext(eval);
+        Self::multi_batch_verify(
+            vp,
+            &[comm, partial_comm],
+            &[point, rest],
+            &[vec![*eval], vec![*eval]],
+            proof,
+            transcript,
+        )
+    }
+}
+
+/// `partial_point` followed by the remaining `num_vars - partial_point.len()` coordinates,
+/// drawn from the transcript after `num_vars` and `partial_point`, so that the coordinates
+/// depend on both.
+fn partial_open_point<E: ExtensionField>(
+    num_vars: usize,
+    partial_point: &[E],
+    transcript: &mut Transcript<E>,
+) -> Result<Vec<E>, Error> {
+    if partial_point.len() > num_vars {
+        return Err(Error::InvalidPcsOpen(format!(
+            "partial point has {} variables, more than the {num_vars} of the polynomial",
+            partial_point.len()
+        )));
+    }
+    transcript.append_message(&(num_vars as u64).to_le_bytes());
+    transcript.append_field_element_exts(partial_point);
+    Ok(partial_point
+        .iter()
+        .copied()
+        .chain((partial_point.len()..num_vars).map(|_| {
+            transcript
+                .get_and_append_challenge(b"partial open")
+                .elements
+        }))
+        .collect())
 }
 
 pub trait NoninteractivePCS<E: ExtensionField>:
@@ -309,14 +427,41 @@ pub enum Error {
     PolynomialTooLarge(usize),
     PolynomialSizesNotEqual,
     MerkleRootMismatch,
+    /// A self-check of the prover failed, see [`VerificationLevel`].
+    SanityCheck(String),
+}
+
+/// How much the prover checks its own intermediate results. This helps to debug an opening
+/// that does not verify, at the cost of proving time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum VerificationLevel {
+    #[default]
+    None,
+    /// Check the sizes of the intermediate values.
+    Cheap,
+    /// Also recompute the claims: evaluate the opened polynomials, re-encode the final
+    /// message, and check every sumcheck round.
+    Paranoid,
+}
+
+impl VerificationLevel {
+    pub fn cheap(self) -> bool {
+        self >= Self::Cheap
+    }
+
+    pub fn paranoid(self) -> bool {
+        self >= Self::Paranoid
+    }
 }
 
 mod basefold;
 pub use basefold::{
     Basecode, BasecodeDefaultSpec, Basefold, BasefoldBasecodeParams, BasefoldCommitment,
-    BasefoldCommitmentWithData, BasefoldDefault, BasefoldParams, BasefoldRSParams, BasefoldSpec,
-    EncodingScheme, RSCode, RSCodeDefaultSpec, coset_fft, fft, fft_root_table, one_level_eval_hc,
-    one_level_interp_hc,
+    BasefoldCommitmentWithData, BasefoldCompactCommitmentWithData, BasefoldDefault,
+    BasefoldOpeningClaim, BasefoldParams, BasefoldRSHighArityParams, BasefoldRSParams,
+    BasefoldRSWideLeavesParams, BasefoldSpec, BinaryFolding, EncodingFolding, EncodingScheme,
+    FoldingSchedule, HighArityFolding, RSCode, RSCodeDefaultSpec, coset_fft, fft, fft_root_table,
+    one_level_eval_hc, one_level_interp_hc,
 };
 use multilinear_extensions::virtual_poly_v2::ArcMultilinearExtension;
 
@@ -626,4 +771,184 @@ pub mod test_util {
             result.unwrap();
         }
     }
+
+    pub(super) fn run_partial_commit_open_verify<E, Pcs>(
+        num_vars: usize,
+        partial_num_vars_range: std::ops::Range<usize>,
+    ) where
+        E: ExtensionField,
+        Pcs: PolynomialCommitmentScheme<E>,
+    {
+        let (pp, vp) = {
+            let poly_size = 1 << num_vars;
+            let param = Pcs::setup(poly_size).unwrap();
+            Pcs::trim(param, poly_size).unwrap()
+        };
+        for partial_num_vars in partial_num_vars_range {
+            let poly = DenseMultilinearExtension::random(num_vars, &mut OsRng);
+            let partial_point = (0..num_vars - partial_num_vars)
+                .map(|_| E::random(&mut OsRng))
+                .collect_vec();
+            let partial_poly = poly.fix_first_variables(&partial_point);
+
+            let (comm, partial_comm, eval, proof, challenge) = {
+                let mut transcript = Transcript::new(b"BaseFold");
+                let comm = Pcs::commit_and_write(&pp, &poly, &mut transcript).unwrap();
+                let partial_comm =
+                    Pcs::commit_and_write(&pp, &partial_poly, &mut transcript).unwrap();
+                let (proof, eval) = Pcs::open_partial(
+                    &pp,
+                    &poly,
+                    &comm,
+                    &partial_point,
+                    &partial_poly,
+                    &partial_comm,
+                    &mut transcript,
+                )
+                .unwrap();
+                (
+                    Pcs::get_pure_commitment(&comm),
+                    Pcs::get_pure_commitment(&partial_comm),
+                    eval,
+                    proof,
+                    transcript.read_challenge(),
+                )
+            };
+
+            let verify = |partial_point: &[E], eval: &E| {
+                let mut transcript = Transcript::new(b"BaseFold");
+                Pcs::write_commitment(&comm, &mut transcript).unwrap();
+                Pcs::write_commitment(&partial_comm, &mut transcript).unwrap();
+                let result = Pcs::verify_partial(
+                    &vp,
+                    &comm,
+                    num_vars,
+                    partial_point,
+                    &partial_comm,
+                    eval,
+                    &proof,
+                    &mut transcript,
+                );
+                (result, transcript.read_challenge())
+            };
+            let (result, v_challenge) = verify(&partial_point, &eval);
+            result.unwrap();
+            assert_eq!(challenge, v_challenge);
+            assert!(verify(&partial_point, &(eval + E::ONE)).0.is_err());
+            if let Some((first, rest)) = partial_point.split_first() {
+                let other_point = [vec![*first + E::ONE], rest.to_vec()].concat();
+                assert!(verify(&other_point, &eval).0.is_err());
+            }
+        }
+    }
+
+    pub(super) fn run_multi_batch_commit_open_verify<E, Pcs>(
+        base: bool,
+        num_vars_start: usize,
+        num_vars_end: usize,
+        batch_size: usize,
+    ) where
+        E: ExtensionField,
+        Pcs: PolynomialCommitmentScheme<E>,
+    {
+        for num_vars in num_vars_start..num_vars_end {
+            let rng = ChaCha8Rng::from_seed([0u8; 32]);
+            // Setup
+            let (pp, vp) = {
+                let poly_size = 1 << num_vars;
+                let param = Pcs::setup(poly_size).unwrap();
+                Pcs::trim(param, poly_size).unwrap()
+            };
+            // One commitment for each number of variables up to num_vars, including
+            // the ones too small to apply FRI, with a different number of polynomials.
+            let comm_num_vars = (1..=num_vars).rev().collect_vec();
+
+            let (comms, evals, proof, challenge) = {
+                let mut transcript = Transcript::new(b"BaseFold");
+                let comms_polys = comm_num_vars
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &num_vars)| {
+                        let polys = (0..batch_size - i % batch_size)
+                            .map(|_| {
+                                if base {
+                                    DenseMultilinearExtension::random(num_vars, &mut rng.clone())
+                                } else {
+                                    DenseMultilinearExtension::from_evaluations_ext_vec(
+                                        num_vars,
+                                        (0..1 << num_vars).map(|_| E::random(&mut OsRng)).collect(),
+                                    )
+                                }
+                            })
+                            .collect_vec();
+                        let comm =
+                            Pcs::batch_commit_and_write(&pp, &polys, &mut transcript).unwrap();
+                        (comm, polys)
+                    })
+                    .collect_vec();
+
+                let points = comm_num_vars
+                    .iter()
+                    .map(|&num_vars| {
+                        (0..num_vars)
+                            .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect_vec();
+
+                let evals = comms_polys
+                    .iter()
+                    .zip(&points)
+                    .map(|((_, polys), point)| {
+                        polys.iter().map(|poly| poly.evaluate(point)).collect_vec()
+                    })
+                    .collect_vec();
+                evals
+                    .iter()
+                    .for_each(|evals| transcript.append_field_element_exts(evals));
+
+                let comms = comms_polys.iter().map(|(comm, _)| comm).collect_vec();
+                let proof =
+                    Pcs::multi_batch_open(&pp, &comms, &points, &evals, &mut transcript).unwrap();
+                (
+                    comms
+                        .into_iter()
+                        .map(|comm| Pcs::get_pure_commitment(comm))
+                        .collect_vec(),
+                    evals,
+                    proof,
+                    transcript.read_challenge(),
+                )
+            };
+            // Batch verify
+            let result = {
+                let mut transcript = Transcript::new(b"BaseFold");
+                comms
+                    .iter()
+                    .for_each(|comm| Pcs::write_commitment(comm, &mut transcript).unwrap());
+
+                let points = comm_num_vars
+                    .iter()
+                    .map(|&num_vars| {
+                        (0..num_vars)
+                            .map(|_| transcript.get_and_append_challenge(b"Point").elements)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect_vec();
+                evals
+                    .iter()
+                    .for_each(|evals| transcript.append_field_element_exts(evals));
+
+                let comms = comms.iter().collect_vec();
+                let result =
+                    Pcs::multi_batch_verify(&vp, &comms, &points, &evals, &proof, &mut transcript);
+
+                let v_challenge = transcript.read_challenge();
+                assert_eq!(challenge, v_challenge);
+                result
+            };
+
+            result.unwrap();
+        }
+    }
 }
@@ -1,14 +1,15 @@
 use crate::{
-    Error,
+    Error, VerificationLevel,
     sum_check::{SumCheck, VirtualPolynomial},
     util::{
         arithmetic::BooleanHypercube,
         expression::{Expression, Rotation},
         parallel::par_map_collect,
         poly_index_ext,
+        profile::{profile_exit, profile_span},
+        sanity_check,
     },
 };
-use ark_std::{end_timer, start_timer};
 use ff::Field;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
@@ -19,7 +20,7 @@ use transcript::Transcript;
 mod coeff;
 use multilinear_extensions::{
     mle::{DenseMultilinearExtension, MultilinearExtension},
-    virtual_poly::build_eq_x_r_vec,
+    structured::StructuredMle,
 };
 
 pub(crate) use coeff::Coefficients;
@@ -33,15 +34,21 @@ pub struct ProverState<'a, E: ExtensionField> {
     sum: E,
     lagranges: HashMap<i32, (usize, E)>,
     identity: E,
-    eq_xys: Vec<DenseMultilinearExtension<E>>,
+    eq_xys: Vec<StructuredMle<E>>,
     polys: Vec<Vec<Cow<'a, DenseMultilinearExtension<E>>>>,
     challenges: &'a [E],
     round: usize,
     bh: BooleanHypercube,
+    verification_level: VerificationLevel,
 }
 
 impl<'a, E: ExtensionField> ProverState<'a, E> {
-    fn new(num_vars: usize, sum: E, virtual_poly: VirtualPolynomial<'a, E>) -> Self {
+    fn new(
+        num_vars: usize,
+        sum: E,
+        virtual_poly: VirtualPolynomial<'a, E>,
+        verification_level: VerificationLevel,
+    ) -> Self {
         assert!(num_vars > 0 && virtual_poly.expression.max_used_rotation_distance() <= num_vars);
         let bh = BooleanHypercube::new(num_vars);
         let lagranges = {
@@ -56,12 +63,12 @@ impl<'a, E: ExtensionField> ProverState<'a, E> {
                 })
                 .collect()
         };
+        // The eq polynomials are kept as tensor products instead of being materialized over
+        // the whole hypercube.
         let eq_xys = virtual_poly
             .ys
             .iter()
-            .map(|y| {
-                DenseMultilinearExtension::from_evaluations_ext_vec(y.len(), build_eq_x_r_vec(y))
-            })
+            .map(|y| StructuredMle::eq_tensor(y))
             .collect_vec();
         let polys = virtual_poly
             .polys
@@ -90,6 +97,7 @@ impl<'a, E: ExtensionField> ProverState<'a, E> {
             challenges: virtual_poly.challenges,
             round: 0,
             bh,
+            verification_level,
         }
     }
 
@@ -109,7 +117,7 @@ impl<'a, E: ExtensionField> ProverState<'a, E> {
             *b >>= 1;
         });
         self.eq_xys.iter_mut().for_each(|eq_xy| {
-            if eq_xy.num_vars > 0 {
+            if eq_xy.num_vars() > 0 {
                 eq_xy.fix_variables_in_place(&[*challenge])
             }
         });
@@ -188,34 +196,19 @@ pub trait ClassicSumCheckRoundMessage<E: ExtensionField>: Sized + Debug {
 
     fn write(&self, transcript: &mut Transcript<E>) -> Result<(), Error>;
 
-    fn sum(&self) -> E;
-
     fn auxiliary(_degree: usize) -> Self::Auxiliary {
         Default::default()
     }
 
     fn evaluate(&self, aux: &Self::Auxiliary, challenge: &E) -> E;
 
-    fn verify_consistency(
-        degree: usize,
-        mut sum: E,
-        msgs: &[Self],
-        challenges: &[E],
-    ) -> Result<E, Error> {
-        let aux = Self::auxiliary(degree);
-        for (round, (msg, challenge)) in msgs.iter().zip(challenges.iter()).enumerate() {
-            if sum != msg.sum() {
-                let msg = if round == 0 {
-                    format!("Expect sum {sum:?} but get {:?}", msg.sum())
-                } else {
-                    format!("Consistency failure at round {round}")
-                };
-                return Err(Error::InvalidSumcheck(msg));
-            }
-            sum = msg.evaluate(&aux, challenge);
-        }
-        Ok(sum)
-    }
+    /// The message sent for the round, without what the verifier recovers from the claimed
+    /// sum of the round. Messages that cannot be compressed are sent as they are.
+    fn compress(&self) -> Self;
+
+    /// Inverse of [`Self::compress`], given the degree of the round polynomial and the
+    /// claimed sum `p(0) + p(1)` of the round.
+    fn decompress(&self, degree: usize, claim: E) -> Result<Self, Error>;
 }
 
 #[derive(Clone, Debug)]
@@ -225,28 +218,29 @@ impl<E: ExtensionField, P: ClassicSumCheckProver<E>> SumCheck<E> for ClassicSumC
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    type ProverParam = ();
+    type ProverParam = VerificationLevel;
     type VerifierParam = ();
     type RoundMessage = P::RoundMessage;
 
     fn prove(
-        _: &Self::ProverParam,
+        verification_level: &Self::ProverParam,
         num_vars: usize,
         virtual_poly: VirtualPolynomial<E>,
         sum: E,
         transcript: &mut Transcript<E>,
     ) -> Result<(Vec<E>, Vec<E>, SumcheckProof<E, Self::RoundMessage>), Error> {
-        let _timer = start_timer!(|| {
-            let degree = virtual_poly.expression.degree();
-            format!("sum_check_prove-{num_vars}-{degree}")
-        });
+        let _span = profile_span!(
+            "sum_check_prove",
+            num_vars = num_vars,
+            degree = virtual_poly.expression.degree()
+        );
 
-        let mut state = ProverState::new(num_vars, sum, virtual_poly);
+        let mut state = ProverState::new(num_vars, sum, virtual_poly, *verification_level);
         let mut challenges = Vec::with_capacity(num_vars);
         let prover = P::new(&state);
 
-        if cfg!(feature = "sanity-check") {
-            assert_eq!(prover.sum(&state), state.sum);
+        if verification_level.paranoid() {
+            sanity_check(prover.sum(&state) == state.sum, "sumcheck claim")?;
         }
 
         let aux = P::RoundMessage::auxiliary(state.degree);
@@ -254,16 +248,17 @@ where
         let mut prover_messages = Vec::with_capacity(num_vars);
 
         for _round in 0..num_vars {
-            let timer = start_timer!(|| format!("sum_check_prove_round-{_round}"));
+            let timer = profile_span!("sum_check_prove_round", round = _round);
             let msg = prover.prove_round(&state);
-            end_timer!(timer);
-            msg.write(transcript)?;
-
-            if cfg!(feature = "sanity-check") {
-                assert_eq!(
-                    msg.evaluate(&aux, &E::ZERO) + msg.evaluate(&aux, &E::ONE),
-                    state.sum
-                );
+            profile_exit!(timer);
+            let compressed = msg.compress();
+            compressed.write(transcript)?;
+
+            if verification_level.paranoid() {
+                sanity_check(
+                    msg.evaluate(&aux, &E::ZERO) + msg.evaluate(&aux, &E::ONE) == state.sum,
+                    "sumcheck round message",
+                )?;
             }
 
             let challenge = transcript
@@ -271,10 +266,10 @@ where
                 .elements;
             challenges.push(challenge);
 
-            let timer = start_timer!(|| format!("sum_check_next_round-{_round}"));
+            let timer = profile_span!("sum_check_next_round", round = _round);
             state.next_round(msg.evaluate(&aux, &challenge), &challenge);
-            end_timer!(timer);
-            prover_messages.push(msg);
+            profile_exit!(timer);
+            prover_messages.push(compressed);
         }
 
         let proof = SumcheckProof {
@@ -292,25 +287,25 @@ where
         proof: &SumcheckProof<E, P::RoundMessage>,
         transcript: &mut Transcript<E>,
     ) -> Result<(E, Vec<E>), Error> {
-        let (msgs, challenges) = {
-            let mut msgs = Vec::with_capacity(num_vars);
-            let mut challenges = Vec::with_capacity(num_vars);
-            for i in 0..num_vars {
-                proof.rounds[i].write(transcript)?;
-                msgs.push(proof.rounds[i].clone());
-                challenges.push(
-                    transcript
-                        .get_and_append_challenge(b"sumcheck round")
-                        .elements,
-                );
-            }
-            (msgs, challenges)
-        };
-
-        Ok((
-            P::RoundMessage::verify_consistency(degree, sum, msgs.as_slice(), &challenges)?,
-            challenges,
-        ))
+        if proof.rounds.len() != num_vars {
+            return Err(Error::InvalidSumcheck(format!(
+                "Expect {num_vars} rounds but get {}",
+                proof.rounds.len()
+            )));
+        }
+        let aux = P::RoundMessage::auxiliary(degree);
+        let mut sum = sum;
+        let mut challenges = Vec::with_capacity(num_vars);
+        for round in &proof.rounds {
+            round.write(transcript)?;
+            let challenge = transcript
+                .get_and_append_challenge(b"sumcheck round")
+                .elements;
+            // A wrong claim gives a wrong message, caught by the final evaluation.
+            sum = round.decompress(degree, sum)?.evaluate(&aux, &challenge);
+            challenges.push(challenge);
+        }
+        Ok((sum, challenges))
     }
 }
 
@@ -321,6 +316,7 @@ mod tests {
         sum_check::eq_xy_eval,
         util::{arithmetic::inner_product, expression::Query, poly_iter_ext},
     };
+    use multilinear_extensions::virtual_poly::build_eq_x_r_vec;
     use transcript::Transcript;
 
     use super::*;
@@ -400,9 +396,11 @@ mod tests {
                 + evals[2] * eq_xy_eval(&points[1], &challenges[..1]) * Fr::from(4)
         );
 
+        // The round messages are recovered from the claim, so a wrong claim gives a wrong final
+        // evaluation.
         let mut transcript = Transcript::<E>::new(b"sumcheck");
 
-        <ClassicSumCheck<CoefficientsProver<E>> as SumCheck<E>>::verify(
+        let (wrong_sum, _) = <ClassicSumCheck<CoefficientsProver<E>> as SumCheck<E>>::verify(
             &(),
             2,
             2,
@@ -410,6 +408,7 @@ mod tests {
             &proof,
             &mut transcript,
         )
-        .expect_err("Should panic");
+        .unwrap();
+        assert_ne!(wrong_sum, new_sum);
     }
 }
@@ -2,7 +2,7 @@ use crate::{
     Error,
     sum_check::classic::{ClassicSumCheckProver, ClassicSumCheckRoundMessage, ProverState},
     util::{
-        arithmetic::{div_ceil, horner_field_type},
+        arithmetic::{degree_2_compress, degree_2_decompress, div_ceil, horner_field_type},
         expression::{CommonPolynomial, Expression, Rotation},
         impl_index,
         parallel::{num_threads, parallelize_iter},
@@ -48,15 +48,31 @@ impl<E: ExtensionField> ClassicSumCheckRoundMessage<E> for Coefficients<E> {
         Ok(())
     }
 
-    fn sum(&self) -> E {
-        self[1..]
-            .iter()
-            .fold(self[0].double(), |acc, coeff| acc + coeff)
-    }
-
     fn evaluate(&self, _: &Self::Auxiliary, challenge: &E) -> E {
         horner_field_type(&self.0, challenge)
     }
+
+    fn compress(&self) -> Self {
+        match &self.0 {
+            FieldType::Ext(coeffs) if coeffs.len() == 3 => {
+                Coefficients(FieldType::Ext(degree_2_compress(coeffs)))
+            }
+            // only degree 2 round messages are compressed
+            _ => self.clone(),
+        }
+    }
+
+    fn decompress(&self, degree: usize, claim: E) -> Result<Self, Error> {
+        match &self.0 {
+            FieldType::Ext(evals) if degree == 2 && evals.len() == 2 => Ok(Coefficients(
+                FieldType::Ext(degree_2_decompress(evals, claim)),
+            )),
+            _ if degree == 2 => Err(Error::InvalidSumcheck(
+                "Expect the evaluations at 0 and 2 of a degree 2 round message".to_string(),
+            )),
+            _ => Ok(self.clone()),
+        }
+    }
 }
 
 impl<'rhs, E: ExtensionField> AddAssign<&'rhs E> for Coefficients<E> {
@@ -114,9 +130,9 @@ impl<E: ExtensionField> CoefficientsProver<E> {
                     ) if query.rotation() == Rotation::cur() => {
                         let lhs = &state.eq_xys[*idx];
                         let rhs = &state.polys[query.poly()][state.num_vars];
-                        assert_eq!(lhs.num_vars, rhs.num_vars);
+                        assert_eq!(lhs.num_vars(), rhs.num_vars);
                         result.iter_mut().enumerate().for_each(|(i, v)| {
-                            *v += poly_index_ext(lhs, i % lhs.evaluations.len())
+                            *v += lhs.get(i % (1 << lhs.num_vars()))
                                 * poly_index_ext(rhs, i % rhs.evaluations.len())
                                 * scalar;
                         })
@@ -202,16 +218,15 @@ impl<E: ExtensionField> ClassicSumCheckProver<E> for CoefficientsProver<E> {
         if self.1.iter().all(|(_, products)| products.len() == 2) {
             for (scalar, products) in self.1.iter() {
                 let [lhs, rhs] = [0, 1].map(|idx| &products[idx]);
-                if cfg!(feature = "sanity-check") {
+                if state.verification_level.paranoid() {
                     // When LAZY = false, coeffs[1] will also be computed during the process
                     coeffs += (scalar, &self.karatsuba::<false>(state, lhs, rhs));
                 } else {
                     coeffs += (scalar, &self.karatsuba::<true>(state, lhs, rhs));
                 }
             }
-            if cfg!(feature = "sanity-check") {
-                assert_eq!(coeffs[0].double() + coeffs[1] + coeffs[2], state.sum);
-            } else {
+            // The round message is checked against the sum by the caller when paranoid.
+            if !state.verification_level.paranoid() {
                 coeffs[1] = state.sum - coeffs[0].double() - coeffs[2];
             }
         } else {
@@ -254,26 +269,34 @@ impl<E: ExtensionField> CoefficientsProver<E> {
                 // that the evaluation representations are of the full sizes, by repeating the
                 // existing evaluations.
 
+                let pairs = |start: usize, n: usize| {
+                    zip_self!(iter::repeat(lhs).flat_map(|x| x.iter()), 2, start * 2)
+                        .zip(zip_self!(
+                            iter::repeat(rhs).flat_map(|x| poly_iter_ext(x)),
+                            2,
+                            start * 2
+                        ))
+                        .take(n)
+                };
                 let evaluate_serial = |coeffs: &mut [E; 3], start: usize, n: usize| {
-                    zip_self!(
-                        iter::repeat(lhs).flat_map(|x| poly_iter_ext(x)),
-                        2,
-                        start * 2
-                    )
-                    .zip(zip_self!(
-                        iter::repeat(rhs).flat_map(|x| poly_iter_ext(x)),
-                        2,
-                        start * 2
-                    ))
-                    .take(n)
-                    .for_each(|((lhs_0, lhs_1), (rhs_0, rhs_1))| {
+                    if LAZY {
+                        // Only coeffs[0] and coeffs[2] are needed, and both are plain sums of
+                        // products, so reduction can be delayed to the end of the chunk
+                        let [coeff_0, coeff_2] = E::sums_of_products(pairs(start, n).map(
+                            |((lhs_0, lhs_1), (rhs_0, rhs_1))| {
+                                [(lhs_0, rhs_0), (lhs_1 - lhs_0, rhs_1 - rhs_0)]
+                            },
+                        ));
+                        coeffs[0] += coeff_0;
+                        coeffs[2] += coeff_2;
+                        return;
+                    }
+                    pairs(start, n).for_each(|((lhs_0, lhs_1), (rhs_0, rhs_1))| {
                         let coeff_0 = lhs_0 * rhs_0;
                         let coeff_2 = (lhs_1 - lhs_0) * (rhs_1 - rhs_0);
                         coeffs[0] += &coeff_0;
+                        coeffs[1] += &(lhs_1 * rhs_1 - coeff_0 - coeff_2);
                         coeffs[2] += &coeff_2;
-                        if !LAZY {
-                            coeffs[1] += &(lhs_1 * rhs_1 - coeff_0 - coeff_2);
-                        }
                     });
                 };
 
@@ -308,3 +331,45 @@ impl<E: ExtensionField> CoefficientsProver<E> {
         Coefficients(FieldType::Ext(coeffs.to_vec()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Coefficients;
+    use crate::sum_check::classic::ClassicSumCheckRoundMessage;
+    use goldilocks::GoldilocksExt2 as E;
+    use multilinear_extensions::mle::FieldType;
+
+    #[test]
+    fn test_compress_round_message() {
+        let claim = |coeffs: &[E]| coeffs.iter().sum::<E>() + coeffs[0];
+        let challenge = E::from(5);
+
+        // degree 2 messages are sent without their linear term
+        let msg = Coefficients(FieldType::Ext(vec![E::from(1), E::from(2), E::from(3)]));
+        let compressed = msg.compress();
+        assert_eq!(compressed.0.len(), 2);
+        let decompressed = compressed
+            .decompress(2, claim(&[1, 2, 3].map(E::from)))
+            .unwrap();
+        assert_eq!(
+            decompressed.evaluate(&(), &challenge),
+            msg.evaluate(&(), &challenge)
+        );
+
+        // other degrees are sent as they are
+        let msg = Coefficients(FieldType::Ext(vec![
+            E::from(1),
+            E::from(2),
+            E::from(3),
+            E::from(4),
+        ]));
+        let compressed = msg.compress();
+        assert_eq!(compressed.0.len(), 4);
+        let decompressed = compressed.decompress(3, E::from(0)).unwrap();
+        assert_eq!(
+            decompressed.evaluate(&(), &challenge),
+            msg.evaluate(&(), &challenge)
+        );
+        assert!(compressed.decompress(2, E::from(0)).is_err());
+    }
+}
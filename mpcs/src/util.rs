@@ -3,6 +3,9 @@ pub mod expression;
 pub mod hash;
 pub mod parallel;
 pub mod plonky2_util;
+pub mod profile;
+pub mod prover_workspace;
+pub mod verifier_cache;
 use ff::{Field, PrimeField};
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
@@ -22,6 +25,15 @@ pub fn base_to_usize<E: ExtensionField>(x: &E::BaseField) -> usize {
     x.to_canonical_u64() as usize
 }
 
+/// Return a [`Error::SanityCheck`] about `what` unless `holds`.
+pub(crate) fn sanity_check(holds: bool, what: &str) -> Result<(), Error> {
+    if holds {
+        Ok(())
+    } else {
+        Err(Error::SanityCheck(what.to_string()))
+    }
+}
+
 pub fn u32_to_field<E: ExtensionField>(x: u32) -> E::BaseField {
     E::BaseField::from(x as u64)
 }
@@ -10,7 +10,7 @@ mod hypercube;
 pub use bh::BooleanHypercube;
 pub use bitvec::field::BitField;
 pub use hypercube::{
-    interpolate_field_type_over_boolean_hypercube, interpolate_over_boolean_hypercube,
+    hypercube_interpolate_in_place, interpolate_field_type_over_boolean_hypercube,
 };
 use num_bigint::BigUint;
 
@@ -86,6 +86,14 @@ pub fn inner_product<'a, 'b, F: Field>(
         .unwrap_or_default()
 }
 
+/// Same as [`inner_product`], but lets the extension field delay modular reduction to the end
+pub fn inner_product_ext<'a, 'b, E: ExtensionField>(
+    lhs: impl IntoIterator<Item = &'a E>,
+    rhs: impl IntoIterator<Item = &'b E>,
+) -> E {
+    E::sum_of_products(lhs.into_iter().zip_eq(rhs).map(|(lhs, rhs)| (*lhs, *rhs)))
+}
+
 pub fn inner_product_three<'a, 'b, 'c, F: Field>(
     a: impl IntoIterator<Item = &'a F>,
     b: impl IntoIterator<Item = &'b F>,
@@ -200,10 +208,8 @@ pub fn interpolate2_weights<F: Field>(points: [(F, F); 2], weight: F, x: F) -> F
     // x  -> a1 + (x-a0)*(b1-a1)/(b0-a0)
     let (a0, a1) = points[0];
     let (b0, b1) = points[1];
-    if cfg!(feature = "sanity-check") {
-        assert_ne!(a0, b0);
-        assert_eq!(weight * (b0 - a0), F::ONE);
-    }
+    debug_assert_ne!(a0, b0);
+    debug_assert_eq!(weight * (b0 - a0), F::ONE);
     // Here weight = 1/(b0-a0). The reason for precomputing it is that inversion is expensive
     a1 + (x - a0) * (b1 - a1) * weight
 }
@@ -226,10 +232,67 @@ pub fn degree_2_eval<F: Field>(poly: &[F], point: F) -> F {
     poly[0] + point * poly[1] + point * point * poly[2]
 }
 
-pub fn base_from_raw_bytes<E: ExtensionField>(bytes: &[u8]) -> E::BaseField {
-    let mut res = E::BaseField::ZERO;
-    bytes.iter().for_each(|b| {
-        res += E::BaseField::from(u64::from(*b));
-    });
-    res
+/// Compresses the coefficients of a degree-2 sumcheck round polynomial into its
+/// evaluations at 0 and 2. The evaluation at 1 is left out since the verifier
+/// recovers it from the claimed sum of the round.
+pub fn degree_2_compress<F: Field>(poly: &[F]) -> Vec<F> {
+    vec![poly[0], degree_2_eval(poly, F::ONE.double())]
+}
+
+/// Inverse of `degree_2_compress`: given the evaluations at 0 and 2 and the claim
+/// `p(0) + p(1)`, returns the coefficients of the round polynomial.
+pub fn degree_2_decompress<F: Field>(evals: &[F], claim: F) -> Vec<F> {
+    let (at_0, at_2) = (evals[0], evals[1]);
+    let at_1 = claim - at_0;
+    let c2 = (at_2 - at_1.double() + at_0) * F::ONE.double().invert().unwrap();
+    vec![at_0, at_1 - at_0 - c2, c2]
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use poseidon::poseidon::AdaptedField;
+    use rand::rngs::OsRng;
+
+    use super::{
+        degree_2_compress, degree_2_decompress, degree_2_zero_plus_one, inner_product,
+        inner_product_ext,
+    };
+    use ff_ext::ExtensionField;
+
+    #[test]
+    fn test_inner_product_ext() {
+        let a = (0..1000)
+            .map(|_| GoldilocksExt2::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let b = (0..1000)
+            .map(|_| GoldilocksExt2::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        assert_eq!(inner_product_ext(&a, &b), inner_product(&a, &b));
+
+        let base_a = a.iter().map(|x| x.as_bases()[0]).collect::<Vec<_>>();
+        let base_b = b.iter().map(|x| x.as_bases()[1]).collect::<Vec<_>>();
+        assert_eq!(
+            Goldilocks::dot_product(&base_a, &base_b),
+            inner_product(&base_a, &base_b)
+        );
+
+        // all products at their maximum to exercise the carries
+        let max = vec![GoldilocksExt2::from_bases(&[-Goldilocks::ONE, -Goldilocks::ONE]); 1000];
+        assert_eq!(inner_product_ext(&max, &max), inner_product(&max, &max));
+    }
+
+    #[test]
+    fn test_degree_2_compress() {
+        let poly = (0..3)
+            .map(|_| GoldilocksExt2::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let compressed = degree_2_compress(&poly);
+        assert_eq!(compressed.len(), 2);
+        assert_eq!(
+            degree_2_decompress(&compressed, degree_2_zero_plus_one(&poly)),
+            poly
+        );
+    }
 }
@@ -1,37 +1,80 @@
+use crate::util::parallel::prelude::*;
 use ff::Field;
 use ff_ext::ExtensionField;
 use multilinear_extensions::mle::FieldType;
-use rayon::prelude::{ParallelIterator, ParallelSliceMut};
 
 use crate::util::log2_strict;
 
 pub fn interpolate_field_type_over_boolean_hypercube<E: ExtensionField>(evals: &mut FieldType<E>) {
     match evals {
-        FieldType::Ext(evals) => interpolate_over_boolean_hypercube(evals),
-        FieldType::Base(evals) => interpolate_over_boolean_hypercube(evals),
+        FieldType::Ext(evals) => hypercube_interpolate_in_place(evals),
+        FieldType::Base(evals) => hypercube_interpolate_in_place(evals),
         _ => unreachable!(),
     };
 }
 
-pub fn interpolate_over_boolean_hypercube<F: Field>(evals: &mut [F]) {
-    // let timer = start_timer!(|| "interpolate_over_hypercube");
-    // iterate over array, replacing even indices with (evals[i] - evals[(i+1)])
-    let n = log2_strict(evals.len());
+/// Below this many variables, the levels of the interpolation are computed one after the other,
+/// as the evaluations fit in the cache.
+const BLOCK_NUM_VARS: usize = 10;
 
-    evals.par_chunks_mut(2).for_each(|chunk| {
-        chunk[1] -= chunk[0];
-    });
-
-    // This code implicitly assumes that coeffs has size at least 1 << n,
-    // that means the size of evals should be a power of two
-    for i in 2..n + 1 {
-        let chunk_size = 1 << i;
-        evals.par_chunks_mut(chunk_size).for_each(|chunk| {
-            let half_chunk = chunk_size >> 1;
-            for j in half_chunk..chunk_size {
-                chunk[j] -= chunk[j - half_chunk];
+/// Convert the evaluations of a multilinear polynomial over the boolean hypercube into its
+/// coefficients, in place. Interpolating over the top variable subtracts the left half from the
+/// right half, which commutes with interpolating both halves over the other variables, so the
+/// halves are interpolated recursively, and in parallel, until they fit in the cache.
+pub fn hypercube_interpolate_in_place<F: Field>(evals: &mut [F]) {
+    let n = log2_strict(evals.len());
+    if n <= BLOCK_NUM_VARS {
+        for i in 0..n {
+            let half_chunk = 1 << i;
+            for chunk in evals.chunks_mut(half_chunk << 1) {
+                let (left, right) = chunk.split_at_mut(half_chunk);
+                right
+                    .iter_mut()
+                    .zip(left.iter())
+                    .for_each(|(r, l)| *r -= *l);
             }
-        });
+        }
+        return;
+    }
+
+    let (left, right) = evals.split_at_mut(evals.len() >> 1);
+    crate::util::parallel::join(
+        || hypercube_interpolate_in_place(left),
+        || hypercube_interpolate_in_place(right),
+    );
+    right
+        .par_iter_mut()
+        .zip(left.par_iter())
+        .for_each(|(r, l)| *r -= *l);
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use goldilocks::Goldilocks;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{BLOCK_NUM_VARS, hypercube_interpolate_in_place};
+
+    #[test]
+    fn test_hypercube_interpolate_in_place() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for num_vars in [0, 3, BLOCK_NUM_VARS + 2] {
+            let coeffs = (0..1 << num_vars)
+                .map(|_| Goldilocks::random(&mut rng))
+                .collect::<Vec<_>>();
+            // The evaluation at `x` sums the coefficients of the monomials whose variables are
+            // the subsets of the bits of `x`.
+            let mut evals = (0..coeffs.len())
+                .map(|x| {
+                    (0..coeffs.len())
+                        .filter(|s| s & x == *s)
+                        .map(|s| coeffs[s])
+                        .sum()
+                })
+                .collect::<Vec<_>>();
+            hypercube_interpolate_in_place(&mut evals);
+            assert_eq!(evals, coeffs);
+        }
     }
-    // end_timer!(timer);
 }
@@ -44,6 +44,55 @@ pub fn hash_two_leaves_batch_base<E: ExtensionField>(
     hash_two_digests(&a_m_to_1_hash, &b_m_to_1_hash)
 }
 
+/// Hash the consecutive `values` of a leaf holding more than two rows of a single column.
+/// For two values, this is [`hash_two_leaves_ext`].
+pub fn hash_leaves_ext<E: ExtensionField>(values: &[E]) -> Digest<E::BaseField> {
+    let input = values
+        .iter()
+        .flat_map(|v| v.as_bases())
+        .copied()
+        .collect::<Vec<_>>();
+    PoseidonHash::hash_or_noop(&input)
+}
+
+/// Same as [`hash_leaves_ext`] over the base field. For two values, this is
+/// [`hash_two_leaves_base`].
+pub fn hash_leaves_base<E: ExtensionField>(values: &[E::BaseField]) -> Digest<E::BaseField> {
+    PoseidonHash::hash_or_noop(values)
+}
+
+/// Hash a leaf holding `rows.len()` consecutive rows of several columns: every row is hashed
+/// to a digest, and the digests are hashed two by two up to one. For two rows, this is
+/// [`hash_two_leaves_batch_ext`].
+pub fn hash_leaves_batch_ext<E: ExtensionField>(rows: &[Vec<E>]) -> Digest<E::BaseField> {
+    hash_digests_to_one(
+        rows.iter()
+            .map(|row| PoseidonHash::hash_or_noop_iter(row.iter().flat_map(|v| v.as_bases())))
+            .collect(),
+    )
+}
+
+/// Same as [`hash_leaves_batch_ext`] over the base field.
+pub fn hash_leaves_batch_base<E: ExtensionField>(
+    rows: &[Vec<E::BaseField>],
+) -> Digest<E::BaseField> {
+    hash_digests_to_one(
+        rows.iter()
+            .map(|row| PoseidonHash::hash_or_noop_iter(row.iter()))
+            .collect(),
+    )
+}
+
+fn hash_digests_to_one<F: SmallField + Poseidon>(mut digests: Vec<Digest<F>>) -> Digest<F> {
+    while digests.len() > 1 {
+        digests = digests
+            .chunks_exact(2)
+            .map(|pair| hash_two_digests(&pair[0], &pair[1]))
+            .collect();
+    }
+    digests.pop().unwrap()
+}
+
 pub fn hash_two_digests<F: SmallField + Poseidon>(a: &Digest<F>, b: &Digest<F>) -> Digest<F> {
     PoseidonHash::two_to_one(a, b)
 }
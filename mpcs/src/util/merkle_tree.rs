@@ -1,25 +1,21 @@
+use crate::util::parallel::prelude::*;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use multilinear_extensions::mle::FieldType;
-use rayon::{
-    iter::{
-        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
-    },
-    slice::ParallelSlice,
-};
 
 use crate::util::{
     Deserialize, DeserializeOwned, Serialize, field_type_index_base, field_type_index_ext,
     hash::{
-        Digest, hash_two_digests, hash_two_leaves_base, hash_two_leaves_batch_base,
+        Digest, hash_leaves_base, hash_leaves_batch_base, hash_leaves_batch_ext, hash_leaves_ext,
+        hash_two_digests, hash_two_leaves_base, hash_two_leaves_batch_base,
         hash_two_leaves_batch_ext, hash_two_leaves_ext,
     },
     log2_strict,
+    profile::{profile_bytes, profile_exit, profile_span},
+    prover_workspace::ProverWorkspace,
 };
 use transcript::Transcript;
 
-use ark_std::{end_timer, start_timer};
-
 use super::hash::write_digest_to_transcript;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -37,15 +33,23 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     pub fn compute_inner(leaves: &FieldType<E>) -> Vec<Vec<Digest<E::BaseField>>> {
-        merkelize::<E>(&[leaves])
+        merkelize::<E>(&[leaves], 1, &ProverWorkspace::new())
     }
 
     pub fn compute_inner_base(leaves: &[E::BaseField]) -> Vec<Vec<Digest<E::BaseField>>> {
-        merkelize_base::<E>(&[leaves])
+        merkelize_base::<E>(&[leaves], &ProverWorkspace::new())
     }
 
     pub fn compute_inner_ext(leaves: &[E]) -> Vec<Vec<Digest<E::BaseField>>> {
-        merkelize_ext::<E>(&[leaves])
+        Self::compute_inner_ext_in(leaves, &ProverWorkspace::new())
+    }
+
+    /// Same as [`Self::compute_inner_ext`], with the layers taken from `workspace`.
+    pub fn compute_inner_ext_in(
+        leaves: &[E],
+        workspace: &ProverWorkspace<E>,
+    ) -> Vec<Vec<Digest<E::BaseField>>> {
+        merkelize_ext::<E>(&[leaves], workspace)
     }
 
     pub fn root_from_inner(inner: &[Vec<Digest<E::BaseField>>]) -> Digest<E::BaseField> {
@@ -60,19 +64,58 @@ where
     }
 
     pub fn from_leaves(leaves: FieldType<E>) -> Self {
+        Self::from_leaves_in(leaves, &ProverWorkspace::new())
+    }
+
+    /// Same as [`Self::from_leaves`], with the layers taken from `workspace`.
+    pub fn from_leaves_in(leaves: FieldType<E>, workspace: &ProverWorkspace<E>) -> Self {
+        Self::from_leaves_with_width_in(leaves, 1, workspace)
+    }
+
+    /// Build a tree whose leaves hold `2^leaf_width_log` consecutive rows each, instead of
+    /// a pair. The tree is `leaf_width_log - 1` levels shorter, and its paths have as many
+    /// fewer digests, but they carry the other rows of the leaf.
+    pub fn from_leaves_with_width_in(
+        leaves: FieldType<E>,
+        leaf_width_log: usize,
+        workspace: &ProverWorkspace<E>,
+    ) -> Self {
         Self {
-            inner: Self::compute_inner(&leaves),
+            inner: merkelize::<E>(&[&leaves], leaf_width_log, workspace),
             leaves: vec![leaves],
         }
     }
 
     pub fn from_batch_leaves(leaves: Vec<FieldType<E>>) -> Self {
+        Self::from_batch_leaves_in(leaves, &ProverWorkspace::new())
+    }
+
+    /// Same as [`Self::from_batch_leaves`], with the layers taken from `workspace`.
+    pub fn from_batch_leaves_in(leaves: Vec<FieldType<E>>, workspace: &ProverWorkspace<E>) -> Self {
+        Self::from_batch_leaves_with_width_in(leaves, 1, workspace)
+    }
+
+    /// Same as [`Self::from_leaves_with_width_in`], for several columns of leaves.
+    pub fn from_batch_leaves_with_width_in(
+        leaves: Vec<FieldType<E>>,
+        leaf_width_log: usize,
+        workspace: &ProverWorkspace<E>,
+    ) -> Self {
         Self {
-            inner: merkelize::<E>(&leaves.iter().collect_vec()),
+            inner: merkelize::<E>(&leaves.iter().collect_vec(), leaf_width_log, workspace),
             leaves,
         }
     }
 
+    /// Take apart the tree into its layers of digests and its leaves.
+    pub fn into_parts(self) -> (Vec<Vec<Digest<E::BaseField>>>, Vec<FieldType<E>>) {
+        (self.inner, self.leaves)
+    }
+
+    pub fn inner(&self) -> &[Vec<Digest<E::BaseField>>] {
+        &self.inner
+    }
+
     pub fn root(&self) -> Digest<E::BaseField> {
         Self::root_from_inner(&self.inner)
     }
@@ -85,6 +128,11 @@ where
         self.inner.len()
     }
 
+    /// Each leaf of the tree holds `2^leaf_width_log` consecutive rows.
+    pub fn leaf_width_log(&self) -> usize {
+        log2_strict(self.size().1) + 1 - self.height()
+    }
+
     pub fn leaves(&self) -> &Vec<FieldType<E>> {
         &self.leaves
     }
@@ -136,30 +184,65 @@ where
         }
     }
 
+    /// The path of the leaf of row `leaf_index`, without the row paired with it. If the
+    /// leaves hold more than a pair of rows, the path carries the other rows of the leaf.
     pub fn merkle_path_without_leaf_sibling_or_root(
         &self,
         leaf_index: usize,
     ) -> MerklePathWithoutLeafOrRoot<E> {
         assert!(leaf_index < self.size().1);
-        MerklePathWithoutLeafOrRoot::<E>::new(
+        let leaf_width_log = self.leaf_width_log();
+        let mut path = MerklePathWithoutLeafOrRoot::<E>::new(
             self.inner
                 .iter()
                 .take(self.height() - 1)
                 .enumerate()
                 .map(|(index, layer)| {
-                    Digest::<E::BaseField>(layer[(leaf_index >> (index + 1)) ^ 1].clone().0)
+                    Digest::<E::BaseField>(
+                        layer[(leaf_index >> (index + leaf_width_log)) ^ 1]
+                            .clone()
+                            .0,
+                    )
                 })
                 .collect(),
-        )
+        );
+        if leaf_width_log > 1 {
+            let first_row = (leaf_index >> leaf_width_log) << leaf_width_log;
+            let rows = (first_row..first_row + (1 << leaf_width_log))
+                .filter(|row| row >> 1 != leaf_index >> 1);
+            path.leaf_siblings = Some(match &self.leaves[0] {
+                FieldType::Base(_) => {
+                    LeafSiblings::Base(rows.map(|row| self.get_leaf_as_base(row)).collect())
+                }
+                FieldType::Ext(_) => {
+                    LeafSiblings::Ext(rows.map(|row| self.get_leaf_as_extension(row)).collect())
+                }
+                FieldType::Unreachable => unreachable!(),
+            });
+        }
+        path
     }
 }
 
+/// The rows of a leaf that are not part of the opened pair, for the trees whose leaves hold
+/// more than a pair of rows. Each row has one element per column.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum LeafSiblings<E: ExtensionField>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    Base(Vec<Vec<E::BaseField>>),
+    Ext(Vec<Vec<E>>),
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MerklePathWithoutLeafOrRoot<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
     inner: Vec<Digest<E::BaseField>>,
+    #[serde(default)]
+    leaf_siblings: Option<LeafSiblings<E>>,
 }
 
 impl<E: ExtensionField> MerklePathWithoutLeafOrRoot<E>
@@ -167,7 +250,19 @@ where
     E::BaseField: Serialize + DeserializeOwned,
 {
     pub fn new(inner: Vec<Digest<E::BaseField>>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            leaf_siblings: None,
+        }
+    }
+
+    /// The leaf width of the tree of the path, see [`MerkleTree::leaf_width_log`].
+    pub fn leaf_width_log(&self) -> usize {
+        match &self.leaf_siblings {
+            None => 1,
+            Some(LeafSiblings::Base(rows)) => log2_strict(rows.len() + 2),
+            Some(LeafSiblings::Ext(rows)) => log2_strict(rows.len() + 2),
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -195,6 +290,9 @@ where
         index: usize,
         root: &Digest<E::BaseField>,
     ) {
+        if self.leaf_siblings.is_some() {
+            return self.authenticate_batch_leaves_root_ext(vec![left], vec![right], index, root);
+        }
         authenticate_merkle_path_root::<E>(
             &self.inner,
             FieldType::Ext(vec![left, right]),
@@ -210,6 +308,9 @@ where
         index: usize,
         root: &Digest<E::BaseField>,
     ) {
+        if self.leaf_siblings.is_some() {
+            return self.authenticate_batch_leaves_root_base(vec![left], vec![right], index, root);
+        }
         authenticate_merkle_path_root::<E>(
             &self.inner,
             FieldType::Base(vec![left, right]),
@@ -225,13 +326,30 @@ where
         index: usize,
         root: &Digest<E::BaseField>,
     ) {
-        authenticate_merkle_path_root_batch::<E>(
-            &self.inner,
-            FieldType::Ext(left),
-            FieldType::Ext(right),
-            index,
-            root,
-        )
+        match &self.leaf_siblings {
+            None => authenticate_merkle_path_root_batch::<E>(
+                &self.inner,
+                FieldType::Ext(left),
+                FieldType::Ext(right),
+                index,
+                root,
+            ),
+            Some(LeafSiblings::Ext(rows)) => {
+                let rows = leaf_rows(rows, left, right, index);
+                let hash = if rows[0].len() > 1 {
+                    hash_leaves_batch_ext::<E>(&rows)
+                } else {
+                    hash_leaves_ext::<E>(&rows.iter().map(|row| row[0]).collect_vec())
+                };
+                authenticate_merkle_path_root_from_leaf::<E>(
+                    &self.inner,
+                    hash,
+                    index >> log2_strict(rows.len()),
+                    root,
+                )
+            }
+            Some(LeafSiblings::Base(_)) => panic!("Mismatching field type of the leaf siblings"),
+        }
     }
 
     pub fn authenticate_batch_leaves_root_base(
@@ -241,29 +359,94 @@ where
         index: usize,
         root: &Digest<E::BaseField>,
     ) {
-        authenticate_merkle_path_root_batch::<E>(
-            &self.inner,
-            FieldType::Base(left),
-            FieldType::Base(right),
-            index,
-            root,
-        )
+        match &self.leaf_siblings {
+            None => authenticate_merkle_path_root_batch::<E>(
+                &self.inner,
+                FieldType::Base(left),
+                FieldType::Base(right),
+                index,
+                root,
+            ),
+            Some(LeafSiblings::Base(rows)) => {
+                let rows = leaf_rows(rows, left, right, index);
+                let hash = if rows[0].len() > 1 {
+                    hash_leaves_batch_base::<E>(&rows)
+                } else {
+                    hash_leaves_base::<E>(&rows.iter().map(|row| row[0]).collect_vec())
+                };
+                authenticate_merkle_path_root_from_leaf::<E>(
+                    &self.inner,
+                    hash,
+                    index >> log2_strict(rows.len()),
+                    root,
+                )
+            }
+            Some(LeafSiblings::Ext(_)) => panic!("Mismatching field type of the leaf siblings"),
+        }
+    }
+}
+
+/// Hash the layers above the first one, until the root.
+fn merkelize_upper_layers<E: ExtensionField>(
+    tree: &mut Vec<Vec<Digest<E::BaseField>>>,
+    log_v: usize,
+    workspace: &ProverWorkspace<E>,
+) {
+    for i in 1..(log_v) {
+        let mut oracle = workspace.take_digests(tree[i - 1].len() >> 1);
+        oracle
+            .par_iter_mut()
+            .zip(tree[i - 1].par_chunks_exact(2))
+            .for_each(|(hash, ys)| *hash = hash_two_digests(&ys[0], &ys[1]));
+
+        tree.push(oracle);
     }
 }
 
 /// Merkle tree construction
 /// TODO: Support merkelizing mixed-type values
-fn merkelize<E: ExtensionField>(values: &[&FieldType<E>]) -> Vec<Vec<Digest<E::BaseField>>> {
-    #[cfg(feature = "sanity-check")]
-    for i in 0..(values.len() - 1) {
-        assert_eq!(values[i].len(), values[i + 1].len());
-    }
-    let timer = start_timer!(|| format!("merkelize {} values", values[0].len() * values.len()));
+fn merkelize<E: ExtensionField>(
+    values: &[&FieldType<E>],
+    leaf_width_log: usize,
+    workspace: &ProverWorkspace<E>,
+) -> Vec<Vec<Digest<E::BaseField>>> {
+    debug_assert!(values.iter().map(|values| values.len()).all_equal());
+    let timer = profile_span!("merkelize", num_values = values[0].len() * values.len());
     let log_v = log2_strict(values[0].len());
-    let mut tree = Vec::with_capacity(log_v);
-    // The first layer of hashes, half the number of leaves
-    let mut hashes = vec![Digest::default(); values[0].len() >> 1];
-    if values.len() == 1 {
+    assert!((1..=log_v).contains(&leaf_width_log));
+    let mut tree = Vec::with_capacity(log_v + 1 - leaf_width_log);
+    // The first layer of hashes, one per leaf of `2^leaf_width_log` rows
+    let mut hashes = workspace.take_digests(values[0].len() >> leaf_width_log);
+    if leaf_width_log > 1 {
+        hashes.par_iter_mut().enumerate().for_each(|(i, hash)| {
+            let rows = (i << leaf_width_log)..((i + 1) << leaf_width_log);
+            *hash = match (&values[0], values.len()) {
+                (FieldType::Base(values), 1) => hash_leaves_base::<E>(&values[rows]),
+                (FieldType::Ext(values), 1) => hash_leaves_ext::<E>(&values[rows]),
+                (FieldType::Base(_), _) => hash_leaves_batch_base::<E>(
+                    &rows
+                        .map(|row| {
+                            values
+                                .iter()
+                                .map(|values| field_type_index_base(values, row))
+                                .collect_vec()
+                        })
+                        .collect_vec(),
+                ),
+                (FieldType::Ext(_), _) => hash_leaves_batch_ext::<E>(
+                    &rows
+                        .map(|row| {
+                            values
+                                .iter()
+                                .map(|values| field_type_index_ext(values, row))
+                                .collect_vec()
+                        })
+                        .collect_vec(),
+                ),
+                (FieldType::Unreachable, _) => unreachable!(),
+            };
+        });
+    } else if values.len() == 1 {
         hashes.par_iter_mut().enumerate().for_each(|(i, hash)| {
             *hash = match &values[0] {
                 FieldType::Base(values) => {
@@ -309,28 +492,25 @@ fn merkelize<E: ExtensionField>(values: &[&FieldType<E>]) -> Vec<Vec<Digest<E::B
 
     tree.push(hashes);
 
-    for i in 1..(log_v) {
-        let oracle = tree[i - 1]
-            .par_chunks_exact(2)
-            .map(|ys| hash_two_digests(&ys[0], &ys[1]))
-            .collect::<Vec<_>>();
-
-        tree.push(oracle);
-    }
-    end_timer!(timer);
+    merkelize_upper_layers(&mut tree, log_v + 1 - leaf_width_log, workspace);
+    profile_bytes!(
+        "merkle_tree",
+        tree.iter().map(Vec::len).sum::<usize>() * std::mem::size_of::<Digest<E::BaseField>>()
+    );
+    profile_exit!(timer);
     tree
 }
 
-fn merkelize_base<E: ExtensionField>(values: &[&[E::BaseField]]) -> Vec<Vec<Digest<E::BaseField>>> {
-    #[cfg(feature = "sanity-check")]
-    for i in 0..(values.len() - 1) {
-        assert_eq!(values[i].len(), values[i + 1].len());
-    }
-    let timer = start_timer!(|| format!("merkelize {} values", values[0].len() * values.len()));
+fn merkelize_base<E: ExtensionField>(
+    values: &[&[E::BaseField]],
+    workspace: &ProverWorkspace<E>,
+) -> Vec<Vec<Digest<E::BaseField>>> {
+    debug_assert!(values.iter().map(|values| values.len()).all_equal());
+    let timer = profile_span!("merkelize", num_values = values[0].len() * values.len());
     let log_v = log2_strict(values[0].len());
     let mut tree = Vec::with_capacity(log_v);
     // The first layer of hashes, half the number of leaves
-    let mut hashes = vec![Digest::default(); values[0].len() >> 1];
+    let mut hashes = workspace.take_digests(values[0].len() >> 1);
     if values.len() == 1 {
         hashes.par_iter_mut().enumerate().for_each(|(i, hash)| {
             *hash = hash_two_leaves_base::<E>(&values[0][i << 1], &values[0][(i << 1) + 1]);
@@ -354,28 +534,25 @@ fn merkelize_base<E: ExtensionField>(values: &[&[E::BaseField]]) -> Vec<Vec<Dige
 
     tree.push(hashes);
 
-    for i in 1..(log_v) {
-        let oracle = tree[i - 1]
-            .par_chunks_exact(2)
-            .map(|ys| hash_two_digests(&ys[0], &ys[1]))
-            .collect::<Vec<_>>();
-
-        tree.push(oracle);
-    }
-    end_timer!(timer);
+    merkelize_upper_layers(&mut tree, log_v, workspace);
+    profile_bytes!(
+        "merkle_tree",
+        tree.iter().map(Vec::len).sum::<usize>() * std::mem::size_of::<Digest<E::BaseField>>()
+    );
+    profile_exit!(timer);
     tree
 }
 
-fn merkelize_ext<E: ExtensionField>(values: &[&[E]]) -> Vec<Vec<Digest<E::BaseField>>> {
-    #[cfg(feature = "sanity-check")]
-    for i in 0..(values.len() - 1) {
-        assert_eq!(values[i].len(), values[i + 1].len());
-    }
-    let timer = start_timer!(|| format!("merkelize {} values", values[0].len() * values.len()));
+fn merkelize_ext<E: ExtensionField>(
+    values: &[&[E]],
+    workspace: &ProverWorkspace<E>,
+) -> Vec<Vec<Digest<E::BaseField>>> {
+    debug_assert!(values.iter().map(|values| values.len()).all_equal());
+    let timer = profile_span!("merkelize", num_values = values[0].len() * values.len());
     let log_v = log2_strict(values[0].len());
     let mut tree = Vec::with_capacity(log_v);
     // The first layer of hashes, half the number of leaves
-    let mut hashes = vec![Digest::default(); values[0].len() >> 1];
+    let mut hashes = workspace.take_digests(values[0].len() >> 1);
     if values.len() == 1 {
         hashes.par_iter_mut().enumerate().for_each(|(i, hash)| {
             *hash = hash_two_leaves_ext::<E>(&values[0][i << 1], &values[0][(i << 1) + 1]);
@@ -399,15 +576,12 @@ fn merkelize_ext<E: ExtensionField>(values: &[&[E]]) -> Vec<Vec<Digest<E::BaseFi
 
     tree.push(hashes);
 
-    for i in 1..(log_v) {
-        let oracle = tree[i - 1]
-            .par_chunks_exact(2)
-            .map(|ys| hash_two_digests(&ys[0], &ys[1]))
-            .collect::<Vec<_>>();
-
-        tree.push(oracle);
-    }
-    end_timer!(timer);
+    merkelize_upper_layers(&mut tree, log_v, workspace);
+    profile_bytes!(
+        "merkle_tree",
+        tree.iter().map(Vec::len).sum::<usize>() * std::mem::size_of::<Digest<E::BaseField>>()
+    );
+    profile_exit!(timer);
     tree
 }
 
@@ -480,3 +654,35 @@ fn authenticate_merkle_path_root_batch<E: ExtensionField>(
     }
     assert_eq!(&hash, root);
 }
+
+/// The rows of a leaf, from the rows of the opened pair of row `index` and the others.
+fn leaf_rows<T: Clone>(
+    siblings: &[Vec<T>],
+    left: Vec<T>,
+    right: Vec<T>,
+    index: usize,
+) -> Vec<Vec<T>> {
+    let pair = (index >> 1) % ((siblings.len() + 2) >> 1);
+    let mut rows = siblings.to_vec();
+    rows.splice(2 * pair..2 * pair, [left, right]);
+    rows
+}
+
+fn authenticate_merkle_path_root_from_leaf<E: ExtensionField>(
+    path: &[Digest<E::BaseField>],
+    leaf_hash: Digest<E::BaseField>,
+    leaf_index: usize,
+    root: &Digest<E::BaseField>,
+) {
+    let mut x_index = leaf_index;
+    let mut hash = leaf_hash;
+    for path_i in path.iter() {
+        hash = if x_index & 1 == 0 {
+            hash_two_digests(&hash, path_i)
+        } else {
+            hash_two_digests(path_i, &hash)
+        };
+        x_index >>= 1;
+    }
+    assert_eq!(&hash, root);
+}
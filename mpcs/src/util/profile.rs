@@ -0,0 +1,65 @@
+//! Profiling instrumentation for the Basefold prover.
+//!
+//! With the `profile` feature, [`profile_span!`] opens a `tracing` span under the
+//! `mpcs::profile` target and [`profile_bytes!`] emits a byte counter event inside the
+//! current span, so a host can time and size each phase with any `tracing` subscriber.
+//! Without the feature both macros compile to nothing.
+
+/// Stand-in for `tracing::span::EnteredSpan` when the `profile` feature is disabled.
+#[derive(Debug)]
+pub struct NoopSpan;
+
+impl NoopSpan {
+    #[inline(always)]
+    pub fn exit(self) {}
+}
+
+/// Enter a profiling span, e.g. `profile_span!("Basefold round", round = i)`.
+/// The span is closed by `profile_exit!` or when dropped.
+#[cfg(feature = "profile")]
+macro_rules! profile_span {
+    ($name:expr $(, $($fields:tt)*)?) => {
+        tracing::info_span!(target: "mpcs::profile", $name $(, $($fields)*)?).entered()
+    };
+}
+
+#[cfg(not(feature = "profile"))]
+macro_rules! profile_span {
+    ($($tt:tt)*) => {
+        $crate::util::profile::NoopSpan
+    };
+}
+
+macro_rules! profile_exit {
+    ($span:expr) => {
+        $span.exit();
+    };
+}
+
+/// Record `$bytes` bytes produced under the counter `$name` in the current span.
+/// `$bytes` is not evaluated unless the `profile` feature is enabled.
+macro_rules! profile_bytes {
+    ($name:expr, $bytes:expr) => {
+        #[cfg(feature = "profile")]
+        {
+            tracing::info!(target: "mpcs::profile", counter = $name, bytes = $bytes as u64);
+        }
+    };
+}
+
+pub(crate) use profile_bytes;
+pub(crate) use profile_exit;
+pub(crate) use profile_span;
+
+/// Size in bytes of the evaluations held by `values`, for use with [`profile_bytes!`].
+#[cfg(feature = "profile")]
+pub(crate) fn field_type_bytes<E: ff_ext::ExtensionField>(
+    values: &multilinear_extensions::mle::FieldType<E>,
+) -> usize {
+    use multilinear_extensions::mle::FieldType;
+    match values {
+        FieldType::Base(values) => std::mem::size_of_val(values.as_slice()),
+        FieldType::Ext(values) => std::mem::size_of_val(values.as_slice()),
+        FieldType::Unreachable => 0,
+    }
+}
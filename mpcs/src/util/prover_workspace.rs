@@ -0,0 +1,223 @@
+use std::{fmt, mem, sync::Mutex};
+
+use ff_ext::ExtensionField;
+use multilinear_extensions::mle::FieldType;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::util::{hash::Digest, merkle_tree::MerkleTree};
+
+/// A pool of the buffers that the prover allocates for codewords, hypercube
+/// evaluations and Merkle layers, so that committing many polynomials one
+/// after the other does not go back to the allocator for every one of them.
+///
+/// Buffers return to the pool when they are recycled, e.g. by
+/// [`crate::PolynomialCommitmentScheme::recycle`] once a commitment is no
+/// longer needed. A request is served by the smallest pooled buffer that is
+/// large enough, so the pool settles on buffers sized for the largest
+/// polynomials committed. Each pool keeps at most [`MAX_POOLED_BUFFERS`]
+/// buffers, dropping the smallest ones beyond that.
+pub struct ProverWorkspace<E: ExtensionField> {
+    pools: Mutex<Pools<E>>,
+}
+
+struct Pools<E: ExtensionField> {
+    base: Vec<Vec<E::BaseField>>,
+    ext: Vec<Vec<E>>,
+    digests: Vec<Vec<Digest<E::BaseField>>>,
+}
+
+/// The number of buffers kept by each pool of a [`ProverWorkspace`].
+pub const MAX_POOLED_BUFFERS: usize = 64;
+
+/// Add `buffer` to `pool`, dropping the smallest buffer if the pool is full.
+fn put_into<T>(pool: &mut Vec<Vec<T>>, buffer: Vec<T>) {
+    pool.push(buffer);
+    if pool.len() > MAX_POOLED_BUFFERS {
+        let smallest = pool
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, buffer)| buffer.capacity())
+            .map(|(i, _)| i)
+            .unwrap();
+        pool.swap_remove(smallest);
+    }
+}
+
+/// Remove from `pool` the smallest buffer with at least `capacity`, or
+/// allocate a new one.
+fn take_from<T>(pool: &mut Vec<Vec<T>>, capacity: usize) -> Vec<T> {
+    let best = pool
+        .iter()
+        .enumerate()
+        .filter(|(_, buffer)| buffer.capacity() >= capacity)
+        .min_by_key(|(_, buffer)| buffer.capacity())
+        .map(|(i, _)| i);
+    match best {
+        Some(i) => {
+            let mut buffer = pool.swap_remove(i);
+            buffer.clear();
+            buffer
+        }
+        None => Vec::with_capacity(capacity),
+    }
+}
+
+impl<E: ExtensionField> ProverWorkspace<E> {
+    pub fn new() -> Self {
+        Self {
+            pools: Mutex::new(Pools {
+                base: vec![],
+                ext: vec![],
+                digests: vec![],
+            }),
+        }
+    }
+
+    /// Copy `values` into a pooled buffer that can grow to `capacity` elements
+    /// without reallocating.
+    pub fn copy_field_type(&self, values: &FieldType<E>, capacity: usize) -> FieldType<E> {
+        let capacity = capacity.max(values.len());
+        let mut pools = self.pools.lock().unwrap();
+        match values {
+            FieldType::Base(values) => {
+                let mut buffer = take_from(&mut pools.base, capacity);
+                drop(pools);
+                buffer.extend_from_slice(values);
+                FieldType::Base(buffer)
+            }
+            FieldType::Ext(values) => {
+                let mut buffer = take_from(&mut pools.ext, capacity);
+                drop(pools);
+                buffer.extend_from_slice(values);
+                FieldType::Ext(buffer)
+            }
+            FieldType::Unreachable => unreachable!(),
+        }
+    }
+
+    /// A pooled buffer of `len` digests, to be overwritten.
+    pub fn take_digests(&self, len: usize) -> Vec<Digest<E::BaseField>> {
+        let mut buffer = take_from(&mut self.pools.lock().unwrap().digests, len);
+        buffer.resize(len, Digest::default());
+        buffer
+    }
+
+    pub fn recycle_field_type(&self, values: FieldType<E>) {
+        let mut pools = self.pools.lock().unwrap();
+        match values {
+            FieldType::Base(values) => put_into(&mut pools.base, values),
+            FieldType::Ext(values) => put_into(&mut pools.ext, values),
+            FieldType::Unreachable => {}
+        }
+    }
+
+    pub fn recycle_digests(&self, digests: Vec<Digest<E::BaseField>>) {
+        put_into(&mut self.pools.lock().unwrap().digests, digests);
+    }
+
+    /// Return the leaves and all the layers of `tree` to the pool.
+    pub fn recycle_tree(&self, tree: MerkleTree<E>)
+    where
+        E::BaseField: Serialize + DeserializeOwned,
+    {
+        let (inner, leaves) = tree.into_parts();
+        inner
+            .into_iter()
+            .for_each(|layer| self.recycle_digests(layer));
+        leaves
+            .into_iter()
+            .for_each(|leaves| self.recycle_field_type(leaves));
+    }
+
+    /// The number of bytes held by the pooled buffers.
+    pub fn pooled_bytes(&self) -> usize {
+        let pools = self.pools.lock().unwrap();
+        pools
+            .base
+            .iter()
+            .map(|buffer| buffer.capacity() * mem::size_of::<E::BaseField>())
+            .chain(
+                pools
+                    .ext
+                    .iter()
+                    .map(|buffer| buffer.capacity() * mem::size_of::<E>()),
+            )
+            .chain(
+                pools
+                    .digests
+                    .iter()
+                    .map(|buffer| buffer.capacity() * mem::size_of::<Digest<E::BaseField>>()),
+            )
+            .sum()
+    }
+
+    /// Release all the pooled buffers to the allocator.
+    pub fn clear(&self) {
+        let mut pools = self.pools.lock().unwrap();
+        pools.base = vec![];
+        pools.ext = vec![];
+        pools.digests = vec![];
+    }
+}
+
+impl<E: ExtensionField> Default for ProverWorkspace<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: ExtensionField> fmt::Debug for ProverWorkspace<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverWorkspace")
+            .field("pooled_bytes", &self.pooled_bytes())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+    use multilinear_extensions::mle::FieldType;
+
+    use super::{MAX_POOLED_BUFFERS, ProverWorkspace};
+    use crate::util::hash::Digest;
+
+    #[test]
+    fn test_reuse_smallest_fitting_buffer() {
+        let workspace = ProverWorkspace::<GoldilocksExt2>::new();
+        workspace.recycle_field_type(FieldType::Base(Vec::with_capacity(16)));
+        let large = Vec::with_capacity(64);
+        let large_ptr = large.as_ptr();
+        workspace.recycle_field_type(FieldType::Base(large));
+
+        let values = FieldType::<GoldilocksExt2>::Base(vec![Default::default(); 8]);
+        let copy = workspace.copy_field_type(&values, 32);
+        assert_eq!(copy, values);
+        match copy {
+            // the very buffer that was recycled
+            FieldType::Base(copy) => assert_eq!(copy.as_ptr(), large_ptr),
+            _ => unreachable!(),
+        }
+        // Only the small buffer is left.
+        assert_eq!(workspace.pooled_bytes(), 16 * 8);
+
+        workspace.clear();
+        assert_eq!(workspace.pooled_bytes(), 0);
+    }
+
+    #[test]
+    fn test_pool_is_capped() {
+        let workspace = ProverWorkspace::<GoldilocksExt2>::new();
+        for capacity in 1..=MAX_POOLED_BUFFERS + 10 {
+            workspace.recycle_digests(Vec::with_capacity(capacity));
+        }
+        // Only the largest buffers are kept.
+        let digest_bytes = mem::size_of::<Digest<Goldilocks>>();
+        assert_eq!(
+            workspace.pooled_bytes(),
+            (11..=MAX_POOLED_BUFFERS + 10).sum::<usize>() * digest_bytes
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use multilinear_extensions::virtual_poly::build_eq_x_r_vec;
+
+/// The number of eq vectors that a cache keeps by default.
+pub const DEFAULT_VERIFIER_CACHE_CAPACITY: usize = 64;
+
+/// Memoize the vectors `eq(x, r)` over the hypercube, keyed by the point `r`, across
+/// verifications. Once `capacity` vectors are cached, the least recently used one is evicted.
+///
+/// A single verification expands its point once, so the cache only hits when several
+/// verifications share the verifier parameters and points, as in `Basefold::verify_many`.
+pub struct VerifierCache<E: ExtensionField> {
+    capacity: usize,
+    state: Mutex<CacheState<E>>,
+}
+
+struct CacheState<E> {
+    tick: u64,
+    /// Map a point to its eq vector and the tick of its last use.
+    eq_vecs: HashMap<Vec<u64>, (u64, Arc<[E]>)>,
+}
+
+impl<E: ExtensionField> VerifierCache<E> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState {
+                tick: 0,
+                eq_vecs: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Return `build_eq_x_r_vec(point)`, computing it only if it is not cached.
+    pub fn eq_x_r_vec(&self, point: &[E]) -> Arc<[E]> {
+        let key = point
+            .iter()
+            .flat_map(|x| x.as_bases().iter().map(|b| b.to_canonical_u64()))
+            .collect::<Vec<_>>();
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.tick += 1;
+            let tick = state.tick;
+            if let Some((last_use, eq)) = state.eq_vecs.get_mut(&key) {
+                *last_use = tick;
+                return eq.clone();
+            }
+        }
+
+        // Compute without holding the lock, so that concurrent verifications do not wait.
+        let eq: Arc<[E]> = build_eq_x_r_vec(point).into();
+
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+        state.eq_vecs.insert(key, (tick, eq.clone()));
+        if state.eq_vecs.len() > self.capacity {
+            // The capacity is small, so a linear scan is cheaper than maintaining an order.
+            let lru = state
+                .eq_vecs
+                .iter()
+                .min_by_key(|(_, (last_use, _))| *last_use)
+                .map(|(key, _)| key.clone())
+                .unwrap();
+            state.eq_vecs.remove(&lru);
+        }
+        eq
+    }
+}
+
+impl<E: ExtensionField> Default for VerifierCache<E> {
+    fn default() -> Self {
+        Self::new(DEFAULT_VERIFIER_CACHE_CAPACITY)
+    }
+}
+
+impl<E: ExtensionField> fmt::Debug for VerifierCache<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.state.lock().unwrap().eq_vecs.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ff::Field;
+    use goldilocks::GoldilocksExt2;
+    use multilinear_extensions::virtual_poly::build_eq_x_r_vec;
+    use rand::rngs::OsRng;
+
+    use super::VerifierCache;
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = VerifierCache::<GoldilocksExt2>::new(2);
+        let [p1, p2, p3] = [(); 3].map(|_| {
+            (0..4)
+                .map(|_| GoldilocksExt2::random(&mut OsRng))
+                .collect::<Vec<_>>()
+        });
+
+        let eq1 = cache.eq_x_r_vec(&p1);
+        assert_eq!(*eq1, build_eq_x_r_vec(&p1)[..]);
+        let eq2 = cache.eq_x_r_vec(&p2);
+        // Use p1, so that p2 is the least recently used.
+        assert!(Arc::ptr_eq(&eq1, &cache.eq_x_r_vec(&p1)));
+
+        cache.eq_x_r_vec(&p3);
+        assert!(Arc::ptr_eq(&eq1, &cache.eq_x_r_vec(&p1)));
+        assert!(!Arc::ptr_eq(&eq2, &cache.eq_x_r_vec(&p2)));
+    }
+}
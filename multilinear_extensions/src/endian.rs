@@ -0,0 +1,90 @@
+//! The order of the variables of a multilinear polynomial in its evaluations.
+//!
+//! [`DenseMultilinearExtension`] is little endian: its first variable is the lowest bit of the
+//! index of an evaluation. Big endian evaluations are its bit-reversal, so that fixing the last
+//! variable folds adjacent evaluations. [`BigEndianMle`] tags them, so that the bit-reversal and
+//! the order of the points are handled here rather than at each use.
+
+use ff_ext::ExtensionField;
+use plonky2::util::reverse_index_bits_in_place;
+
+use crate::mle::{DenseMultilinearExtension, FieldType, MultilinearExtension};
+
+impl<E: ExtensionField> DenseMultilinearExtension<E> {
+    /// Fix the first `partial_point.len()` variables, i.e. the lowest bits of the index, to
+    /// `partial_point`.
+    pub fn fix_first_variables(&self, partial_point: &[E]) -> Self {
+        self.fix_variables(partial_point)
+    }
+
+    /// Fix the last `partial_point.len()` variables, i.e. the highest bits of the index, to
+    /// `partial_point`, whose first element goes to the first of them.
+    pub fn fix_last_variables(&self, partial_point: &[E]) -> Self {
+        self.fix_high_variables(partial_point)
+    }
+
+    /// Reorder the evaluations in big endian.
+    pub fn into_big_endian(mut self) -> BigEndianMle<E> {
+        reverse_index_bits_in_place_field_type(&mut self.evaluations);
+        BigEndianMle(self)
+    }
+}
+
+/// A multilinear polynomial with its evaluations in big endian: its first variable is the
+/// highest bit of the index of an evaluation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BigEndianMle<E: ExtensionField>(DenseMultilinearExtension<E>);
+
+impl<E: ExtensionField> BigEndianMle<E> {
+    /// Tag `mle`, whose evaluations are in big endian already, e.g. a bit-reversed copy.
+    pub fn from_big_endian(mle: DenseMultilinearExtension<E>) -> Self {
+        BigEndianMle(mle)
+    }
+
+    /// Reorder the evaluations in little endian.
+    pub fn into_little_endian(mut self) -> DenseMultilinearExtension<E> {
+        reverse_index_bits_in_place_field_type(&mut self.0.evaluations);
+        self.0
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.0.num_vars
+    }
+
+    /// The evaluations, in big endian.
+    pub fn evaluations(&self) -> &FieldType<E> {
+        &self.0.evaluations
+    }
+
+    /// Take the evaluations, in big endian.
+    pub fn evaluations_to_owned(self) -> FieldType<E> {
+        self.0.evaluations
+    }
+
+    /// Fix the first `partial_point.len()` variables, i.e. the highest bits of the index, to
+    /// `partial_point`.
+    pub fn fix_first_variables(&self, partial_point: &[E]) -> Self {
+        let reversed = partial_point.iter().rev().copied().collect::<Vec<_>>();
+        BigEndianMle(self.0.fix_high_variables(&reversed))
+    }
+
+    /// Fix the last `partial_point.len()` variables, i.e. the lowest bits of the index, to
+    /// `partial_point`, whose first element goes to the first of them.
+    pub fn fix_last_variables(&self, partial_point: &[E]) -> Self {
+        let reversed = partial_point.iter().rev().copied().collect::<Vec<_>>();
+        BigEndianMle(self.0.fix_variables(&reversed))
+    }
+
+    pub fn evaluate(&self, point: &[E]) -> E {
+        let reversed = point.iter().rev().copied().collect::<Vec<_>>();
+        self.0.evaluate(&reversed)
+    }
+}
+
+fn reverse_index_bits_in_place_field_type<E: ExtensionField>(evaluations: &mut FieldType<E>) {
+    match evaluations {
+        FieldType::Base(values) => reverse_index_bits_in_place(values),
+        FieldType::Ext(values) => reverse_index_bits_in_place(values),
+        FieldType::Unreachable => unreachable!(),
+    }
+}
@@ -1,4 +1,6 @@
+pub mod endian;
 pub mod mle;
+pub mod structured;
 pub mod util;
 pub mod virtual_poly;
 pub mod virtual_poly_v2;
@@ -0,0 +1,198 @@
+//! Multilinear polynomials with a known structure, such as eq(X, r) or a selector of the first
+//! instances. They are evaluated on demand instead of being materialized over the whole
+//! hypercube.
+
+use ff_ext::ExtensionField;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{mle::DenseMultilinearExtension, virtual_poly::build_eq_x_r_vec};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructuredMle<E: ExtensionField> {
+    /// The evaluations over the hypercube, for the small factors of a tensor product.
+    Dense(Vec<E>),
+    /// `scalar * eq(X, point)`.
+    Eq { scalar: E, point: Vec<E> },
+    /// 1 on the first `num_instances` points of the hypercube and 0 elsewhere.
+    Selector {
+        num_vars: usize,
+        num_instances: usize,
+    },
+    /// `low(X_0, .., X_{k-1}) * high(X_k, .., X_{n-1})`, where k is the number of variables
+    /// of `low`.
+    Tensor(Box<StructuredMle<E>>, Box<StructuredMle<E>>),
+}
+
+impl<E: ExtensionField> StructuredMle<E> {
+    pub fn eq(point: &[E]) -> Self {
+        Self::Eq {
+            scalar: E::ONE,
+            point: point.to_vec(),
+        }
+    }
+
+    /// eq(X, point) as the tensor product of the eq polynomials of both halves of the point, so
+    /// that reading an evaluation costs one multiplication and only about 2^(n/2) values are
+    /// stored on each side.
+    pub fn eq_tensor(point: &[E]) -> Self {
+        let (low, high) = point.split_at(point.len() / 2);
+        Self::tensor(
+            Self::Dense(build_eq_x_r_vec(low)),
+            Self::Dense(build_eq_x_r_vec(high)),
+        )
+    }
+
+    pub fn selector(num_vars: usize, num_instances: usize) -> Self {
+        assert!(num_instances <= 1 << num_vars);
+        Self::Selector {
+            num_vars,
+            num_instances,
+        }
+    }
+
+    pub fn tensor(low: Self, high: Self) -> Self {
+        Self::Tensor(Box::new(low), Box::new(high))
+    }
+
+    pub fn num_vars(&self) -> usize {
+        match self {
+            Self::Dense(evals) => evals.len().ilog2() as usize,
+            Self::Eq { point, .. } => point.len(),
+            Self::Selector { num_vars, .. } => *num_vars,
+            Self::Tensor(low, high) => low.num_vars() + high.num_vars(),
+        }
+    }
+
+    /// The evaluation at the `index`-th point of the hypercube, where bit i of `index` is X_i.
+    pub fn get(&self, index: usize) -> E {
+        match self {
+            Self::Dense(evals) => evals[index],
+            Self::Eq { scalar, point } => {
+                point
+                    .iter()
+                    .enumerate()
+                    .fold(*scalar, |acc, (i, r)| match (index >> i) & 1 {
+                        1 => acc * r,
+                        _ => acc * (E::ONE - r),
+                    })
+            }
+            Self::Selector { num_instances, .. } => {
+                if index < *num_instances {
+                    E::ONE
+                } else {
+                    E::ZERO
+                }
+            }
+            Self::Tensor(low, high) => {
+                let low_num_vars = low.num_vars();
+                low.get(index & ((1 << low_num_vars) - 1)) * high.get(index >> low_num_vars)
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = E> + '_ {
+        (0..1 << self.num_vars()).map(|index| self.get(index))
+    }
+
+    /// Evaluates the polynomial at `point` without going over the hypercube.
+    pub fn evaluate(&self, point: &[E]) -> E {
+        assert_eq!(point.len(), self.num_vars());
+        match self {
+            Self::Dense(evals) => {
+                let mut evals = evals.clone();
+                point.iter().for_each(|x| fold_low_variable(&mut evals, *x));
+                evals[0]
+            }
+            Self::Eq { scalar, point: r } => r
+                .iter()
+                .zip(point)
+                .fold(*scalar, |acc, (r, x)| acc * eq_1(*r, *x)),
+            Self::Selector { num_instances, .. } => selector_eval(*num_instances, point),
+            Self::Tensor(low, high) => {
+                let (low_point, high_point) = point.split_at(low.num_vars());
+                low.evaluate(low_point) * high.evaluate(high_point)
+            }
+        }
+    }
+
+    /// Fixes the low variables to `partial_point`. Only a selector is materialized by this, as
+    /// it loses its structure once a variable is fixed.
+    pub fn fix_variables_in_place(&mut self, partial_point: &[E]) {
+        assert!(partial_point.len() <= self.num_vars());
+        partial_point.iter().for_each(|x| self.fix_low_variable(*x));
+    }
+
+    fn fix_low_variable(&mut self, x: E) {
+        match self {
+            Self::Dense(evals) => fold_low_variable(evals, x),
+            Self::Eq { scalar, point } => {
+                *scalar *= eq_1(point.remove(0), x);
+            }
+            Self::Selector { .. } => {
+                let mut evals = self.to_evaluations();
+                fold_low_variable(&mut evals, x);
+                *self = Self::Dense(evals);
+            }
+            Self::Tensor(low, high) => {
+                if low.num_vars() > 0 {
+                    low.fix_low_variable(x)
+                } else {
+                    high.fix_low_variable(x)
+                }
+            }
+        }
+    }
+
+    pub fn to_evaluations(&self) -> Vec<E> {
+        match self {
+            Self::Dense(evals) => evals.clone(),
+            Self::Eq { scalar, point } => {
+                let mut evals = build_eq_x_r_vec(point);
+                if *scalar != E::ONE {
+                    evals.iter_mut().for_each(|e| *e *= scalar);
+                }
+                evals
+            }
+            _ => (0..1 << self.num_vars())
+                .into_par_iter()
+                .map(|index| self.get(index))
+                .collect(),
+        }
+    }
+
+    pub fn to_dense_mle(&self) -> DenseMultilinearExtension<E> {
+        DenseMultilinearExtension::from_evaluations_ext_vec(self.num_vars(), self.to_evaluations())
+    }
+}
+
+/// eq(r, x) for a single variable.
+fn eq_1<E: ExtensionField>(r: E, x: E) -> E {
+    let rx = r * x;
+    rx + rx - r - x + E::ONE
+}
+
+fn fold_low_variable<E: ExtensionField>(evals: &mut Vec<E>, x: E) {
+    let half = evals.len() / 2;
+    for i in 0..half {
+        evals[i] = evals[2 * i] + x * (evals[2 * i + 1] - evals[2 * i]);
+    }
+    evals.truncate(half);
+}
+
+/// The multilinear extension of the indicator of the first `num_instances` points, evaluated at
+/// `point`. The top variable splits the hypercube into two halves, of which at most one is
+/// partially selected.
+fn selector_eval<E: ExtensionField>(num_instances: usize, point: &[E]) -> E {
+    match point.split_last() {
+        None if num_instances > 0 => E::ONE,
+        None => E::ZERO,
+        Some((top, low)) => {
+            let half = 1 << low.len();
+            if num_instances <= half {
+                (E::ONE - top) * selector_eval(num_instances, low)
+            } else {
+                (E::ONE - top) + *top * selector_eval(num_instances - half, low)
+            }
+        }
+    }
+}
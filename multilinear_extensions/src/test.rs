@@ -6,7 +6,10 @@ use goldilocks::GoldilocksExt2;
 type E = GoldilocksExt2;
 
 use crate::{
-    mle::{ArcDenseMultilinearExtension, DenseMultilinearExtension, MultilinearExtension},
+    mle::{
+        ArcDenseMultilinearExtension, DenseMultilinearExtension, FieldType, MultilinearExtension,
+    },
+    structured::StructuredMle,
     util::bit_decompose,
     virtual_poly::{VirtualPolynomial, build_eq_x_r},
 };
@@ -98,6 +101,68 @@ fn test_fix_high_variables() {
     assert_eq!(result2, expected2);
 }
 
+#[test]
+fn test_fix_first_and_last_variables() {
+    let mut rng = test_rng();
+    let nv = 4;
+    let poly = DenseMultilinearExtension::<E>::random(nv, &mut rng);
+    let point: Vec<_> = (0..nv).map(|_| E::random(&mut rng)).collect();
+    let eval = poly.evaluate(&point);
+
+    assert_eq!(
+        poly.fix_first_variables(&point[..1]).evaluate(&point[1..]),
+        eval
+    );
+    assert_eq!(
+        poly.fix_last_variables(&point[1..]).evaluate(&point[..1]),
+        eval
+    );
+
+    let big_endian = poly.clone().into_big_endian();
+    let FieldType::Base(evaluations) = big_endian.evaluations() else {
+        unreachable!()
+    };
+    assert_eq!(evaluations[1], poly.get_base_field_vec()[1 << (nv - 1)]);
+    assert_eq!(big_endian.evaluate(&point), eval);
+    let fixed = big_endian.fix_first_variables(&point[..1]);
+    assert_eq!(fixed.num_vars(), nv - 1);
+    assert_eq!(fixed.evaluate(&point[1..]), eval);
+    assert_eq!(
+        big_endian
+            .fix_last_variables(&point[1..])
+            .evaluate(&point[..1]),
+        eval
+    );
+    assert_eq!(big_endian.into_little_endian(), poly);
+}
+
+#[test]
+fn test_structured_mle() {
+    let mut rng = test_rng();
+    let nv = 5;
+    let r: Vec<_> = (0..nv).map(|_| E::random(&mut rng)).collect();
+    let point: Vec<_> = (0..nv).map(|_| E::random(&mut rng)).collect();
+    let rhs: Vec<_> = (0..2).map(|_| E::random(&mut rng)).collect();
+
+    for mle in [
+        StructuredMle::eq(&r),
+        StructuredMle::eq_tensor(&r),
+        StructuredMle::selector(nv, 0),
+        StructuredMle::selector(nv, 11),
+        StructuredMle::selector(nv, 1 << nv),
+        StructuredMle::tensor(StructuredMle::selector(nv - 2, 5), StructuredMle::eq(&rhs)),
+    ] {
+        let dense = mle.to_dense_mle();
+        assert_eq!(mle.num_vars(), nv);
+        assert_eq!(dense.get_ext_field_vec(), mle.iter().collect::<Vec<_>>());
+        assert_eq!(mle.evaluate(&point), dense.evaluate(&point));
+
+        let mut fixed = mle.clone();
+        fixed.fix_variables_in_place(&point[..2]);
+        assert_eq!(fixed.to_dense_mle(), dense.fix_variables(&point[..2]));
+    }
+}
+
 /// Naive method to build eq(x, r).
 /// Only used for testing purpose.
 // Evaluate
@@ -6,3 +6,9 @@ pub mod poseidon;
 mod poseidon_goldilocks;
 pub mod poseidon_hash;
 pub mod poseidon_permutation;
+
+pub use poseidon_goldilocks::reduce128;
+
+pub use constants::{
+    ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_ROUNDS, SPONGE_RATE, SPONGE_WIDTH,
+};
@@ -238,6 +238,28 @@ fn reduce_u160<F: AdaptedField>((n_lo, n_hi): (u128, u32)) -> F {
     F::from_noncanonical_u128(reduced128)
 }
 
+/// Sum of products of field elements with a single reduction at the end. Each product of two
+/// 64-bit representatives fits in a u128, and the 160-bit accumulator absorbs the carries.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProductAccumulator((u128, u32));
+
+impl ProductAccumulator {
+    #[inline(always)]
+    pub fn add_product<F: AdaptedField>(&mut self, x: F, y: F) {
+        let product = (x.to_noncanonical_u64() as u128) * (y.to_noncanonical_u64() as u128);
+        if self.0.1 == u32::MAX {
+            // fold the high limb back before it can overflow
+            self.0 = (self.reduce::<F>().to_noncanonical_u64() as u128, 0);
+        }
+        self.0 = add_u160_u128(self.0, product);
+    }
+
+    #[inline(always)]
+    pub fn reduce<F: AdaptedField>(self) -> F {
+        reduce_u160::<F>(self.0)
+    }
+}
+
 pub trait AdaptedField: SmallField {
     const ORDER: u64;
 
@@ -247,6 +269,22 @@ pub trait AdaptedField: SmallField {
 
     fn multiply_accumulate(&self, x: Self, y: Self) -> Self;
 
+    /// Equivalent to `*self * rhs`, but fields with a special-form modulus can reduce the
+    /// full 128-bit product directly instead of going through generic modular reduction.
+    #[inline]
+    fn mul_reduce(&self, rhs: Self) -> Self {
+        *self * rhs
+    }
+
+    /// Sum of `a[i] * b[i]`, reduced once instead of after every product
+    #[inline]
+    fn dot_product(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len());
+        let mut acc = ProductAccumulator::default();
+        a.iter().zip(b).for_each(|(x, y)| acc.add_product(*x, *y));
+        acc.reduce()
+    }
+
     /// Returns `n`. Assumes that `n` is already in canonical form, i.e. `n < Self::order()`.
     // TODO: Should probably be unsafe.
     fn from_canonical_u64(n: u64) -> Self {
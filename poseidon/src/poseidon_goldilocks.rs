@@ -228,6 +228,11 @@ impl AdaptedField for Goldilocks {
         // u64 + u64 * u64 cannot overflow.
         reduce128((self.0 as u128) + (x.0 as u128) * (y.0 as u128))
     }
+
+    #[inline]
+    fn mul_reduce(&self, rhs: Self) -> Self {
+        reduce128((self.0 as u128) * (rhs.0 as u128))
+    }
 }
 
 /// Fast addition modulo ORDER for x86-64.
@@ -282,7 +287,7 @@ fn reduce96((x_lo, x_hi): (u64, u32)) -> Goldilocks {
 /// Reduces to a 64-bit value. The result might not be in canonical form; it could be in between the
 /// field order and `2^64`.
 #[inline]
-fn reduce128(x: u128) -> Goldilocks {
+pub fn reduce128(x: u128) -> Goldilocks {
     let (x_lo, x_hi) = split(x); // This is a no-op
     let x_hi_hi = x_hi >> 32;
     let x_hi_lo = x_hi & EPSILON;
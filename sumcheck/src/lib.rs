@@ -1,11 +1,13 @@
 #[cfg(feature = "non_pow2_rayon_thread")]
 pub mod local_thread_pool;
 mod macros;
+pub mod permcheck;
 mod prover;
 mod prover_v2;
 pub mod structs;
 pub mod util;
 mod verifier;
+pub mod zerocheck;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,167 @@
+//! The permutation check proves that the values of two MLEs `f` and `g` on the boolean hypercube
+//! are the same multiset.
+//!
+//! With a challenge `beta` sampled after `f` and `g` are committed, it is so if
+//! `sum_x 1 / (beta + f(x)) = sum_x 1 / (beta + g(x))`. The prover commits to the inverses
+//! `h_f = 1 / (beta + f)` and `h_g = 1 / (beta + g)`, and a single sumcheck proves both that
+//! they are the inverses, with a zero-check batched by `alpha`, and that their sums are equal:
+//!
+//! `sum_x eq(r, x) (alpha (h_f (beta + f) - 1) + h_g (beta + g) - 1) + h_f(x) - h_g(x) = 0`
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ff::Field;
+use ff_ext::ExtensionField;
+use multilinear_extensions::{
+    mle::{DenseMultilinearExtension, MultilinearExtension},
+    op_mle,
+    virtual_poly::{VPAuxInfo, build_eq_x_r, eq_eval},
+    virtual_poly_v2::{ArcMultilinearExtension, VirtualPolynomialV2},
+};
+use transcript::Transcript;
+
+use crate::{
+    structs::{IOPProof, IOPProverStateV2, IOPVerifierState},
+    util::batch_inversion,
+    zerocheck::sample_eq_point,
+};
+
+/// The evaluations of the MLEs of a permutation check at the point where its sumcheck ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PermcheckEvals<E> {
+    pub f: E,
+    pub g: E,
+    pub h_f: E,
+    pub h_g: E,
+}
+
+/// What the verifier of a permutation check is left to check: that the evaluations at `point`,
+/// opened by the caller, are consistent with `expected_evaluation`, see
+/// [`PermcheckSubClaim::check`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PermcheckSubClaim<E: ExtensionField> {
+    pub beta: E,
+    pub alpha: E,
+    /// The point sampled for the eq polynomial.
+    pub eq_point: Vec<E>,
+    /// The point where the sumcheck ends.
+    pub point: Vec<E>,
+    pub expected_evaluation: E,
+}
+
+impl<E: ExtensionField> PermcheckSubClaim<E> {
+    /// Whether the evaluations opened at `point` are consistent with the sumcheck.
+    pub fn check(&self, evals: &PermcheckEvals<E>) -> bool {
+        let inverse_f = evals.h_f * (self.beta + evals.f) - E::ONE;
+        let inverse_g = evals.h_g * (self.beta + evals.g) - E::ONE;
+        eq_eval(&self.eq_point, &self.point) * (self.alpha * inverse_f + inverse_g) + evals.h_f
+            - evals.h_g
+            == self.expected_evaluation
+    }
+}
+
+/// Sample `beta`, after `f` and `g` are committed. It is sampled in the same way for the prover
+/// and the verifier.
+pub fn sample_beta<E: ExtensionField>(transcript: &mut Transcript<E>) -> E {
+    transcript
+        .get_and_append_challenge(b"permcheck beta")
+        .elements
+}
+
+/// Sample `alpha` and the point of the eq polynomial, after the inverses are committed.
+fn sample_alpha_and_eq_point<E: ExtensionField>(
+    num_vars: usize,
+    transcript: &mut Transcript<E>,
+) -> (E, Vec<E>) {
+    let alpha = transcript
+        .get_and_append_challenge(b"permcheck alpha")
+        .elements;
+    (alpha, sample_eq_point(num_vars, transcript))
+}
+
+/// The inverse `1 / (beta + f)` that the prover commits to.
+pub fn inverse<E: ExtensionField>(
+    beta: E,
+    f: &DenseMultilinearExtension<E>,
+) -> DenseMultilinearExtension<E> {
+    let mut values = op_mle!(f, |evals| evals
+        .iter()
+        .map(|v| beta + v)
+        .collect::<Vec<E>>());
+    batch_inversion(&mut values);
+    DenseMultilinearExtension::from_evaluations_ext_vec(f.num_vars(), values)
+}
+
+/// Prove that `f` and `g` are permutations of each other, given `beta` and the inverses
+/// `h_f` and `h_g` computed with [`inverse`]. The inverses must be committed, and the transcript
+/// updated with their commitments, before calling this.
+///
+/// Returns the evaluations to open at the point of the proof.
+pub fn prove<'a, E: ExtensionField>(
+    beta: E,
+    [f, g]: [ArcMultilinearExtension<'a, E>; 2],
+    [h_f, h_g]: [ArcMultilinearExtension<'a, E>; 2],
+    transcript: &mut Transcript<E>,
+) -> (IOPProof<E>, PermcheckEvals<E>) {
+    let num_vars = f.num_vars();
+    assert!(
+        [&g, &h_f, &h_g]
+            .iter()
+            .all(|mle| mle.num_vars() == num_vars)
+    );
+    let (alpha, eq_point) = sample_alpha_and_eq_point(num_vars, transcript);
+    let eq: ArcMultilinearExtension<'a, E> = build_eq_x_r(&eq_point);
+
+    let mut poly = VirtualPolynomialV2::new(num_vars);
+    poly.add_mle_list(vec![h_f.clone(), f.clone(), eq.clone()], alpha);
+    poly.add_mle_list(vec![h_f.clone(), eq.clone()], alpha * beta);
+    poly.add_mle_list(vec![h_g.clone(), g.clone(), eq.clone()], E::ONE);
+    poly.add_mle_list(vec![h_g.clone(), eq.clone()], beta);
+    poly.add_mle_list(vec![eq], -(alpha + E::ONE));
+    poly.add_mle_list(vec![h_f.clone()], E::ONE);
+    poly.add_mle_list(vec![h_g.clone()], -E::ONE);
+    let index = |mle: &ArcMultilinearExtension<'a, E>| {
+        poly.flattened_ml_extensions
+            .iter()
+            .position(|other| Arc::ptr_eq(other, mle))
+            .unwrap()
+    };
+    let [f, g, h_f, h_g] = [&f, &g, &h_f, &h_g].map(index);
+
+    let (proof, state) = IOPProverStateV2::prove_batch_polys(1, vec![poly], transcript);
+    let evals = state.get_mle_final_evaluations();
+    (proof, PermcheckEvals {
+        f: evals[f],
+        g: evals[g],
+        h_f: evals[h_f],
+        h_g: evals[h_g],
+    })
+}
+
+/// Verify the permutation check of MLEs of `num_vars` variables, given `beta` and the transcript
+/// updated with the commitments to the inverses.
+pub fn verify<E: ExtensionField>(
+    beta: E,
+    num_vars: usize,
+    proof: &IOPProof<E>,
+    transcript: &mut Transcript<E>,
+) -> PermcheckSubClaim<E> {
+    let (alpha, eq_point) = sample_alpha_and_eq_point(num_vars, transcript);
+    let subclaim = IOPVerifierState::verify(
+        E::ZERO,
+        proof,
+        &VPAuxInfo {
+            max_degree: 3,
+            num_variables: num_vars,
+            phantom: PhantomData,
+        },
+        transcript,
+    );
+    PermcheckSubClaim {
+        beta,
+        alpha,
+        eq_point,
+        point: subclaim.point.iter().map(|c| c.elements).collect(),
+        expected_evaluation: subclaim.expected_evaluation,
+    }
+}
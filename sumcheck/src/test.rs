@@ -3,14 +3,20 @@ use std::sync::Arc;
 use ark_std::{rand::RngCore, test_rng};
 use ff::Field;
 use ff_ext::ExtensionField;
-use goldilocks::GoldilocksExt2;
-use multilinear_extensions::{mle::MultilinearExtension, virtual_poly::VirtualPolynomial};
+use goldilocks::{Goldilocks, GoldilocksExt2};
+use multilinear_extensions::{
+    mle::{DenseMultilinearExtension, MultilinearExtension},
+    virtual_poly::VirtualPolynomial,
+    virtual_poly_v2::{ArcMultilinearExtension, VirtualPolynomialV2},
+};
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
 use transcript::Transcript;
 
 use crate::{
+    permcheck,
     structs::{IOPProverState, IOPVerifierState},
-    util::interpolate_uni_poly,
+    util::{ceil_log2, interpolate_uni_poly},
+    zerocheck,
 };
 
 // TODO add more tests related to various num_vars combination after PR #162
@@ -205,3 +211,107 @@ fn test_interpolation() {
 
     assert_eq!(poly.evaluate(&query), interpolate_uni_poly(&evals, query));
 }
+
+fn zerocheck_helper(f: Vec<Goldilocks>, g: Vec<Goldilocks>) {
+    let nv = ceil_log2(f.len());
+    let [f, g]: [ArcMultilinearExtension<GoldilocksExt2>; 2] = [f, g]
+        .map(|evals| Arc::new(DenseMultilinearExtension::from_evaluations_vec(nv, evals)) as _);
+    let mut poly = VirtualPolynomialV2::new(nv);
+    poly.add_mle_list(vec![f, g], GoldilocksExt2::from(3));
+
+    let mut transcript = Transcript::new(b"test");
+    let (proof, state) = zerocheck::prove(poly.clone(), &mut transcript);
+    // f, g, and the eq polynomial.
+    assert_eq!(state.get_mle_final_evaluations().len(), 3);
+
+    let mut transcript = Transcript::new(b"test");
+    let subclaim = zerocheck::verify(nv, 2, &proof, &mut transcript);
+    assert_eq!(subclaim.point, proof.point);
+    assert!(subclaim.check(poly.evaluate(&subclaim.point)));
+}
+
+#[test]
+fn test_zerocheck() {
+    let mut rng = test_rng();
+    // Neither is zero, but their product is.
+    let f = (0..32)
+        .map(|i| {
+            if i % 2 == 0 {
+                Goldilocks::ZERO
+            } else {
+                Goldilocks::random(&mut rng)
+            }
+        })
+        .collect();
+    let g = (0..32)
+        .map(|i| {
+            if i % 2 == 1 {
+                Goldilocks::ZERO
+            } else {
+                Goldilocks::random(&mut rng)
+            }
+        })
+        .collect();
+    zerocheck_helper(f, g);
+}
+
+#[test]
+#[should_panic]
+fn test_zerocheck_nonzero() {
+    let mut rng = test_rng();
+    let f = (0..32).map(|_| Goldilocks::random(&mut rng)).collect();
+    let g = (0..32).map(|_| Goldilocks::random(&mut rng)).collect();
+    zerocheck_helper(f, g);
+}
+
+fn permcheck_helper(f: Vec<Goldilocks>, g: Vec<Goldilocks>) {
+    let nv = ceil_log2(f.len());
+    let [f, g] = [f, g].map(|evals| DenseMultilinearExtension::from_evaluations_vec(nv, evals));
+
+    let arc = |mle| -> ArcMultilinearExtension<GoldilocksExt2> { Arc::new(mle) };
+    let mut transcript = Transcript::<GoldilocksExt2>::new(b"test");
+    let beta = permcheck::sample_beta(&mut transcript);
+    let [h_f, h_g] = [&f, &g].map(|mle| permcheck::inverse(beta, mle));
+    let (proof, evals) = permcheck::prove(
+        beta,
+        [f.clone(), g.clone()].map(arc),
+        [h_f.clone(), h_g.clone()].map(arc),
+        &mut transcript,
+    );
+
+    let mut transcript = Transcript::new(b"test");
+    let beta = permcheck::sample_beta(&mut transcript);
+    let subclaim = permcheck::verify(beta, nv, &proof, &mut transcript);
+    let point = &subclaim.point;
+    assert_eq!(evals, permcheck::PermcheckEvals {
+        f: f.evaluate(point),
+        g: g.evaluate(point),
+        h_f: h_f.evaluate(point),
+        h_g: h_g.evaluate(point),
+    });
+    assert!(subclaim.check(&evals));
+}
+
+#[test]
+fn test_permcheck() {
+    let mut rng = test_rng();
+    let f = (0..32)
+        .map(|_| Goldilocks::random(&mut rng))
+        .collect::<Vec<_>>();
+    let mut g = f.clone();
+    g.reverse();
+    g.swap(3, 17);
+    permcheck_helper(f, g);
+}
+
+#[test]
+#[should_panic]
+fn test_permcheck_not_permutation() {
+    let mut rng = test_rng();
+    let f = (0..32)
+        .map(|_| Goldilocks::random(&mut rng))
+        .collect::<Vec<_>>();
+    let mut g = f.clone();
+    g[5] = g[6];
+    permcheck_helper(f, g);
+}
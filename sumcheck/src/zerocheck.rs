@@ -0,0 +1,85 @@
+//! The zero-check proves that a virtual polynomial `f` vanishes on the boolean hypercube.
+//!
+//! The verifier samples a point `r`, and the sumcheck proves that `eq(r, x) f(x)` sums to zero.
+//! The sum is the evaluation at `r` of the multilinear extension of the values of `f`, so it is
+//! zero for a random `r` only if all the values are zero.
+
+use ff::Field;
+use ff_ext::ExtensionField;
+use multilinear_extensions::{
+    virtual_poly::{VPAuxInfo, build_eq_x_r, eq_eval},
+    virtual_poly_v2::VirtualPolynomialV2,
+};
+use std::marker::PhantomData;
+use transcript::Transcript;
+
+use crate::structs::{IOPProof, IOPProverStateV2, IOPVerifierState};
+
+/// What the verifier of a zero-check is left to check: that `f(point)`, opened by the caller,
+/// is consistent with `expected_evaluation`, see [`ZerocheckSubClaim::check`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ZerocheckSubClaim<E: ExtensionField> {
+    /// The point sampled for the eq polynomial.
+    pub eq_point: Vec<E>,
+    /// The point where the sumcheck ends.
+    pub point: Vec<E>,
+    /// The expected evaluation of `eq(eq_point, x) f(x)` at `point`.
+    pub expected_evaluation: E,
+}
+
+impl<E: ExtensionField> ZerocheckSubClaim<E> {
+    /// Whether `f(point)` is `evaluation`.
+    pub fn check(&self, evaluation: E) -> bool {
+        eq_eval(&self.eq_point, &self.point) * evaluation == self.expected_evaluation
+    }
+}
+
+/// Sample the point of the eq polynomial, in the same way for the prover and the verifier.
+pub(crate) fn sample_eq_point<E: ExtensionField>(
+    num_vars: usize,
+    transcript: &mut Transcript<E>,
+) -> Vec<E> {
+    (0..num_vars)
+        .map(|_| transcript.get_and_append_challenge(b"zerocheck").elements)
+        .collect()
+}
+
+/// Prove that `poly` is zero on the hypercube. All its products must have the full number of
+/// variables, and a degree of at most 2, since the eq polynomial adds one.
+///
+/// The final evaluations of the prover state are those of the MLEs of `poly`, followed by that of
+/// the eq polynomial.
+pub fn prove<'a, E: ExtensionField>(
+    mut poly: VirtualPolynomialV2<'a, E>,
+    transcript: &mut Transcript<E>,
+) -> (IOPProof<E>, IOPProverStateV2<'a, E>) {
+    let eq_point = sample_eq_point(poly.aux_info.max_num_variables, transcript);
+    poly.mul_by_mle(build_eq_x_r(&eq_point), E::BaseField::ONE);
+    IOPProverStateV2::prove_batch_polys(1, vec![poly], transcript)
+}
+
+/// Verify the zero-check of a polynomial of `num_vars` variables and degree `degree`, not counting
+/// the eq polynomial.
+pub fn verify<E: ExtensionField>(
+    num_vars: usize,
+    degree: usize,
+    proof: &IOPProof<E>,
+    transcript: &mut Transcript<E>,
+) -> ZerocheckSubClaim<E> {
+    let eq_point = sample_eq_point(num_vars, transcript);
+    let subclaim = IOPVerifierState::verify(
+        E::ZERO,
+        proof,
+        &VPAuxInfo {
+            max_degree: degree + 1,
+            num_variables: num_vars,
+            phantom: PhantomData,
+        },
+        transcript,
+    );
+    ZerocheckSubClaim {
+        eq_point,
+        point: subclaim.point.iter().map(|c| c.elements).collect(),
+        expected_evaluation: subclaim.expected_evaluation,
+    }
+}
@@ -1,23 +1,55 @@
 use ff::Field;
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
-use poseidon::poseidon_permutation::PoseidonPermutation;
+use poseidon::{digest::Digest, poseidon_permutation::PoseidonPermutation};
 
-use crate::Challenge;
+use crate::{
+    Challenge,
+    log::{TranscriptEvent, TranscriptLog, encode_base, encode_bytes, encode_ext},
+};
 
 #[derive(Clone)]
 pub struct Transcript<E: ExtensionField> {
     permutation: PoseidonPermutation<E::BaseField>,
+    log: Option<TranscriptLog>,
 }
 
 impl<E: ExtensionField> Transcript<E> {
     /// Create a new IOP transcript.
-    pub fn new(label: &'static [u8]) -> Self {
+    pub fn new(label: &[u8]) -> Self {
         let mut perm = PoseidonPermutation::new(core::iter::repeat(E::BaseField::ZERO));
         let label_f = E::BaseField::bytes_to_field_elements(label);
         perm.set_from_slice(label_f.as_slice(), 0);
         perm.permute();
-        Self { permutation: perm }
+        Self {
+            permutation: perm,
+            log: None,
+        }
+    }
+
+    /// Create a new IOP transcript that records its events, see [`Self::log`].
+    pub fn new_recording(label: &[u8]) -> Self {
+        let mut transcript = Self::new(label);
+        transcript.log = Some(TranscriptLog::default());
+        transcript.record(|| TranscriptEvent::New {
+            label: encode_bytes(label),
+        });
+        transcript
+    }
+
+    /// The events so far, if the transcript records them. A fork carries the events before it.
+    pub fn log(&self) -> Option<&TranscriptLog> {
+        self.log.as_ref()
+    }
+
+    pub fn take_log(&mut self) -> Option<TranscriptLog> {
+        self.log.take()
+    }
+
+    fn record(&mut self, event: impl FnOnce() -> TranscriptEvent) {
+        if let Some(log) = &mut self.log {
+            log.events.push(event());
+        }
     }
 }
 
@@ -35,6 +67,13 @@ impl<E: ExtensionField> Transcript<E> {
 
     // Append the message to the transcript.
     pub fn append_message(&mut self, msg: &[u8]) {
+        self.record(|| TranscriptEvent::Message {
+            bytes: encode_bytes(msg),
+        });
+        self.absorb_message(msg);
+    }
+
+    fn absorb_message(&mut self, msg: &[u8]) {
         let msg_f = E::BaseField::bytes_to_field_elements(msg);
         self.permutation.set_from_slice(&msg_f, 0);
         self.permutation.permute();
@@ -42,6 +81,9 @@ impl<E: ExtensionField> Transcript<E> {
 
     // Append the field extension element to the transcript.
     pub fn append_field_element_ext(&mut self, element: &E) {
+        self.record(|| TranscriptEvent::Ext {
+            value: encode_ext(element),
+        });
         self.permutation.set_from_slice(element.as_bases(), 0);
         self.permutation.permute();
     }
@@ -54,12 +96,18 @@ impl<E: ExtensionField> Transcript<E> {
 
     // Append the field elemetn to the transcript.
     pub fn append_field_element(&mut self, element: &E::BaseField) {
+        self.record(|| TranscriptEvent::Base {
+            value: encode_base(element),
+        });
         self.permutation.set_from_slice(&[*element], 0);
         self.permutation.permute();
     }
 
     // Append the challenge to the transcript.
     pub fn append_challenge(&mut self, challenge: Challenge<E>) {
+        self.record(|| TranscriptEvent::AppendChallenge {
+            value: encode_ext(&challenge.elements),
+        });
         self.permutation
             .set_from_slice(challenge.elements.as_bases(), 0);
         self.permutation.permute();
@@ -79,12 +127,16 @@ impl<E: ExtensionField> Transcript<E> {
     //
     // The output field element is statistical uniform as long
     // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<E> {
-        self.append_message(label);
+    pub fn get_and_append_challenge(&mut self, label: &[u8]) -> Challenge<E> {
+        self.absorb_message(label);
 
         let challenge = Challenge {
             elements: E::from_limbs(self.permutation.squeeze()),
         };
+        self.record(|| TranscriptEvent::Challenge {
+            label: encode_bytes(label),
+            value: encode_ext(&challenge.elements),
+        });
         challenge
     }
 
@@ -106,6 +158,9 @@ impl<E: ExtensionField> Transcript<E> {
 
     pub fn read_challenge(&mut self) -> Challenge<E> {
         let r = E::from_bases(&self.permutation.squeeze()[..2]);
+        self.record(|| TranscriptEvent::ReadChallenge {
+            value: encode_ext(&r),
+        });
 
         Challenge { elements: r }
     }
@@ -113,4 +168,15 @@ impl<E: ExtensionField> Transcript<E> {
     pub fn send_challenge(&self, _challenge: E) {
         unimplemented!()
     }
+
+    /// A digest of everything absorbed so far, without changing the transcript. Comparing the
+    /// digests of two parties locates where they stopped absorbing the same messages.
+    pub fn state_digest(&self) -> Digest<E::BaseField> {
+        let mut digest = Digest::default();
+        let width = digest.0.len();
+        digest
+            .0
+            .copy_from_slice(&self.permutation.squeeze()[..width]);
+        digest
+    }
 }
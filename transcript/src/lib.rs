@@ -3,8 +3,10 @@
 #![feature(generic_arg_infer)]
 
 pub mod basic;
+pub mod log;
 pub mod syncronized;
 pub use basic::Transcript;
+pub use log::{TranscriptEvent, TranscriptLog};
 pub use syncronized::TranscriptSyncronized;
 
 mod hasher;
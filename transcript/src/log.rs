@@ -0,0 +1,193 @@
+//! A typed JSON record of the proof stream of a [`Transcript`], and its replay, to check an
+//! independent verifier against the one of this repo.
+//!
+//! Field elements are written as `0x`-prefixed canonical 64-bit hex values, extension field
+//! elements as the list of their limbs, and byte strings as `0x`-prefixed hex.
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use serde::{Deserialize, Serialize};
+
+use crate::{Challenge, Transcript};
+
+/// An operation on a transcript, with what it absorbed or drew.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptEvent {
+    /// The creation of the transcript with `label`.
+    New {
+        label: String,
+    },
+    Message {
+        bytes: String,
+    },
+    Base {
+        value: String,
+    },
+    Ext {
+        value: Vec<String>,
+    },
+    AppendChallenge {
+        value: Vec<String>,
+    },
+    /// A challenge drawn with `label`, and appended to the transcript.
+    Challenge {
+        label: String,
+        value: Vec<String>,
+    },
+    /// A challenge drawn without changing the transcript.
+    ReadChallenge {
+        value: Vec<String>,
+    },
+}
+
+/// The events of a transcript created by [`Transcript::new_recording`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptLog {
+    pub events: Vec<TranscriptEvent>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The log does not start with a `new` event, or has another one later.
+    MisplacedNew(usize),
+    /// The event at this index has a malformed hex value.
+    Malformed(usize),
+    /// The challenge drawn at this event differs from the recorded one.
+    ChallengeMismatch(usize),
+}
+
+impl TranscriptLog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a log serializes")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Absorb the events into a new transcript, checking that it draws the recorded challenges.
+    /// The transcript records its own log, equal to this one.
+    pub fn replay<E: ExtensionField>(&self) -> Result<Transcript<E>, ReplayError> {
+        let label = match self.events.first() {
+            Some(TranscriptEvent::New { label }) => {
+                decode_bytes(label).ok_or(ReplayError::Malformed(0))?
+            }
+            _ => return Err(ReplayError::MisplacedNew(0)),
+        };
+        let mut transcript = Transcript::<E>::new_recording(&label);
+        for (i, event) in self.events.iter().enumerate().skip(1) {
+            let malformed = || ReplayError::Malformed(i);
+            match event {
+                TranscriptEvent::New { .. } => return Err(ReplayError::MisplacedNew(i)),
+                TranscriptEvent::Message { bytes } => {
+                    transcript.append_message(&decode_bytes(bytes).ok_or_else(malformed)?)
+                }
+                TranscriptEvent::Base { value } => {
+                    transcript.append_field_element(&decode_base(value).ok_or_else(malformed)?)
+                }
+                TranscriptEvent::Ext { value } => {
+                    transcript.append_field_element_ext(&decode_ext(value).ok_or_else(malformed)?)
+                }
+                TranscriptEvent::AppendChallenge { value } => {
+                    transcript.append_challenge(Challenge {
+                        elements: decode_ext(value).ok_or_else(malformed)?,
+                    })
+                }
+                TranscriptEvent::Challenge { label, value } => {
+                    let label = decode_bytes(label).ok_or_else(malformed)?;
+                    let expected = decode_ext::<E>(value).ok_or_else(malformed)?;
+                    if transcript.get_and_append_challenge(&label).elements != expected {
+                        return Err(ReplayError::ChallengeMismatch(i));
+                    }
+                }
+                TranscriptEvent::ReadChallenge { value } => {
+                    let expected = decode_ext::<E>(value).ok_or_else(malformed)?;
+                    if transcript.read_challenge().elements != expected {
+                        return Err(ReplayError::ChallengeMismatch(i));
+                    }
+                }
+            }
+        }
+        Ok(transcript)
+    }
+}
+
+pub(crate) fn encode_bytes(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("0x{hex}")
+}
+
+pub(crate) fn encode_base<F: SmallField>(element: &F) -> String {
+    format!("0x{:016x}", element.to_canonical_u64())
+}
+
+pub(crate) fn encode_ext<E: ExtensionField>(element: &E) -> Vec<String> {
+    element.as_bases().iter().map(encode_base).collect()
+}
+
+fn decode_bytes(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x")?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn decode_base<F: SmallField>(hex: &str) -> Option<F> {
+    let value = u64::from_str_radix(hex.strip_prefix("0x")?, 16).ok()?;
+    (value < F::MODULUS_U64).then(|| F::from(value))
+}
+
+fn decode_ext<E: ExtensionField>(limbs: &[String]) -> Option<E> {
+    if limbs.len() != E::DEGREE {
+        return None;
+    }
+    let bases = limbs
+        .iter()
+        .map(|limb| decode_base(limb))
+        .collect::<Option<Vec<_>>>()?;
+    Some(E::from_bases(&bases))
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::{Goldilocks, GoldilocksExt2};
+
+    use super::{ReplayError, TranscriptEvent, TranscriptLog};
+    use crate::Transcript;
+
+    #[test]
+    fn test_replay() {
+        let mut transcript = Transcript::<GoldilocksExt2>::new_recording(b"test");
+        transcript.append_message(b"message");
+        transcript.append_field_element(&Goldilocks::from(7));
+        transcript.append_field_element_ext(&GoldilocksExt2::from(11));
+        let challenge = transcript.get_and_append_challenge(b"alpha");
+        transcript.append_challenge(challenge);
+        let read = transcript.read_challenge();
+
+        let log = transcript.log().unwrap().clone();
+        assert_eq!(log.events.len(), 7);
+        assert_eq!(log.events[2], TranscriptEvent::Base {
+            value: "0x0000000000000007".to_string()
+        });
+
+        let log = TranscriptLog::from_json(&log.to_json()).unwrap();
+        let mut replayed = log.replay::<GoldilocksExt2>().unwrap();
+        assert_eq!(replayed.log(), Some(&log));
+        assert_eq!(replayed.read_challenge(), read);
+
+        let mut tampered = log.clone();
+        tampered.events[1] = TranscriptEvent::Message {
+            bytes: "0x00".to_string(),
+        };
+        assert_eq!(
+            tampered.replay::<GoldilocksExt2>().err(),
+            Some(ReplayError::ChallengeMismatch(4))
+        );
+    }
+}